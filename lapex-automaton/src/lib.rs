@@ -4,6 +4,7 @@ use std::{
 };
 
 use petgraph::{
+    dot::Dot,
     graph::EdgeIndex,
     graph::NodeIndex,
     prelude::DiGraph,
@@ -87,6 +88,58 @@ impl<StateType: Debug, TransitionType: Debug> Nfa<StateType, TransitionType> {
         self.graph
             .add_edge(start, end, NfaEdge::Transition(transition))
     }
+
+    /// Writes the automaton in the DOT language of Graphviz, with the states and transitions
+    /// labeled by the given functions. The transitions between the same two states are drawn as
+    /// one edge and labeled together. Accepting states are drawn with a double border.
+    pub fn to_dot(
+        &self,
+        state_label: impl Fn(StateId, &AutomatonState<StateType>) -> String,
+        transitions_label: impl Fn(&[&TransitionType]) -> String,
+    ) -> String {
+        to_dot(&self.graph, state_label, |edges| {
+            let transitions: Vec<&TransitionType> = edges
+                .iter()
+                .filter_map(|edge| match edge {
+                    NfaEdge::Epsilon => None,
+                    NfaEdge::Transition(transition) => Some(transition),
+                })
+                .collect();
+            let epsilon = edges.iter().any(|edge| matches!(edge, NfaEdge::Epsilon));
+            match (epsilon, transitions.is_empty()) {
+                (true, true) => String::from("ε"),
+                (true, false) => format!("ε, {}", transitions_label(&transitions)),
+                (false, _) => transitions_label(&transitions),
+            }
+        })
+    }
+}
+
+fn to_dot<StateType: Debug, EdgeType>(
+    graph: &Graph<AutomatonState<StateType>, EdgeType>,
+    state_label: impl Fn(StateId, &AutomatonState<StateType>) -> String,
+    edges_label: impl Fn(&[&EdgeType]) -> String,
+) -> String {
+    let mut labeled: Graph<String, String> = DiGraph::new();
+    for (index, state) in graph.node_references() {
+        labeled.add_node(state_label(index, state));
+    }
+    let mut edges: BTreeMap<(StateId, StateId), Vec<&EdgeType>> = BTreeMap::new();
+    for edge in graph.edge_references() {
+        edges
+            .entry((edge.source(), edge.target()))
+            .or_default()
+            .push(edge.weight());
+    }
+    for ((source, target), weights) in edges {
+        labeled.add_edge(source, target, edges_label(&weights));
+    }
+    // the labels are escaped by petgraph, only the accepting states need an attribute of their own
+    let node_attributes = |_, (index, _): (NodeIndex, &String)| match graph[index] {
+        AutomatonState::Accepting(_) => String::from("peripheries = 2"),
+        AutomatonState::Intermediate(_) => String::new(),
+    };
+    Dot::with_attr_getters(&labeled, &[], &|_, _| String::new(), &node_attributes).to_string()
 }
 
 pub struct Dfa<StateType: Debug, TransitionType: Debug> {
@@ -128,6 +181,10 @@ impl<StateType: Debug, TransitionType: Debug> Dfa<StateType, TransitionType> {
         self.graph.node_references()
     }
 
+    pub fn state(&self, node: StateId) -> Option<&AutomatonState<StateType>> {
+        self.graph.node_weight(node)
+    }
+
     pub fn transitions_from(
         &self,
         node: StateId,
@@ -136,6 +193,116 @@ impl<StateType: Debug, TransitionType: Debug> Dfa<StateType, TransitionType> {
             .edges_directed(node, Outgoing)
             .map(|eref| (eref.weight(), eref.target()))
     }
+
+    /// Writes the automaton in the DOT language of Graphviz, like `Nfa::to_dot`.
+    pub fn to_dot(
+        &self,
+        state_label: impl Fn(StateId, &AutomatonState<StateType>) -> String,
+        transitions_label: impl Fn(&[&TransitionType]) -> String,
+    ) -> String {
+        to_dot(&self.graph, state_label, transitions_label)
+    }
+}
+
+impl<StateType: Clone + Debug, TransitionType: Clone + Debug + Ord> Dfa<StateType, TransitionType> {
+    pub fn minimize(&self) -> Self
+    where
+        StateType: Ord,
+    {
+        self.minimize_by_key(|state| state.clone())
+    }
+
+    /// Merges the states which accept states of the same key and move to merged states on the
+    /// same transitions, and drops the states which cannot be reached from the start. The states
+    /// are refined by their transitions until no block splits anymore, like in Moore's algorithm,
+    /// so a missing transition stays different from one into a state which never accepts. The
+    /// start keeps the index 0.
+    pub fn minimize_by_key<K: Ord>(&self, key: impl Fn(&StateType) -> K) -> Self {
+        let start = StateId::new(0);
+        let mut reachable = vec![start];
+        let mut visited = BTreeSet::from([start]);
+        let mut next = 0;
+        while let Some(state) = reachable.get(next).copied() {
+            for (_, target) in self.transitions_from(state) {
+                if visited.insert(target) {
+                    reachable.push(target);
+                }
+            }
+            next += 1;
+        }
+        reachable.sort();
+        let index: BTreeMap<StateId, usize> = reachable
+            .iter()
+            .enumerate()
+            .map(|(i, state)| (*state, i))
+            .collect();
+
+        let accepts: Vec<Option<K>> = reachable
+            .iter()
+            .map(|state| match self.state(*state).unwrap() {
+                AutomatonState::Accepting(accept) => Some(key(accept)),
+                AutomatonState::Intermediate(_) => None,
+            })
+            .collect();
+        let mut block_of = number_in_order(&accepts);
+        loop {
+            let signatures: Vec<(usize, Vec<(&TransitionType, usize)>)> = reachable
+                .iter()
+                .map(|state| {
+                    let mut transitions: Vec<(&TransitionType, usize)> = self
+                        .transitions_from(*state)
+                        .map(|(transition, target)| (transition, block_of[index[&target]]))
+                        .collect();
+                    transitions.sort();
+                    (block_of[index[state]], transitions)
+                })
+                .collect();
+            let refined = number_in_order(&signatures);
+            // a block is only ever split, so the same number of blocks means nothing changed
+            let unchanged = refined.iter().max() == block_of.iter().max();
+            block_of = refined;
+            if unchanged {
+                break;
+            }
+        }
+
+        // the blocks are numbered in the order of their first state, so the start comes first
+        let mut representatives = Vec::new();
+        for (i, block) in block_of.iter().enumerate() {
+            if *block == representatives.len() {
+                representatives.push(reachable[i]);
+            }
+        }
+        let mut dfa = Dfa::new();
+        for state in &representatives {
+            match self.state(*state).unwrap() {
+                AutomatonState::Accepting(accept) => dfa.add_accepting_state(accept.clone()),
+                AutomatonState::Intermediate(_) => dfa.add_intermediate_state(),
+            };
+        }
+        for (block, state) in representatives.iter().enumerate() {
+            for (transition, target) in self.transitions_from(*state) {
+                dfa.add_transition(
+                    StateId::new(block),
+                    StateId::new(block_of[index[&target]]),
+                    transition.clone(),
+                );
+            }
+        }
+        dfa
+    }
+}
+
+// numbers the distinct values in the order they first appear
+fn number_in_order<T: Ord>(values: &[T]) -> Vec<usize> {
+    let mut numbers = BTreeMap::new();
+    values
+        .iter()
+        .map(|value| {
+            let next = numbers.len();
+            *numbers.entry(value).or_insert(next)
+        })
+        .collect()
 }
 
 impl<StateType: Clone + Debug, TransitionType: Debug + Clone + Eq + Ord>
@@ -162,7 +329,8 @@ impl<StateType: Clone + Debug, TransitionType: Debug + Clone + Eq + Ord>
         &self,
         dfa: &mut Graph<BTreeSet<StateId>, TransitionType>,
         nodes: Vec<StateId>,
-    ) -> StateId {
+        is_cancelled: &dyn Fn() -> bool,
+    ) -> Option<StateId> {
         let mut closure = BTreeSet::new(); // TODO: test perf of different data structures
         self.epsilon_closure(nodes, &mut closure);
 
@@ -173,7 +341,9 @@ impl<StateType: Clone + Debug, TransitionType: Debug + Clone + Eq + Ord>
             .map(|(i, _)| i);
         if let Some(node_dfa) = node_dfa_opt {
             // if the powerset exists, no need to recompute
-            node_dfa
+            Some(node_dfa)
+        } else if is_cancelled() {
+            None
         } else {
             // if the powerset is new, add it to the graph and recurse
             let node_dfa = dfa.add_node(closure.clone());
@@ -194,10 +364,10 @@ impl<StateType: Clone + Debug, TransitionType: Debug + Clone + Eq + Ord>
                 }
             }
             for (t, targets) in target_multi_map {
-                let target_dfa = self.add_powerset_to_dfa(dfa, targets);
+                let target_dfa = self.add_powerset_to_dfa(dfa, targets, is_cancelled)?;
                 dfa.add_edge(node_dfa, target_dfa, t);
             }
-            node_dfa
+            Some(node_dfa)
         }
     }
 
@@ -239,9 +409,21 @@ impl<StateType: Clone + Debug, TransitionType: Debug + Clone + Eq + Ord>
         &self,
         entrypoint: StateId,
     ) -> Dfa<Vec<StateType>, TransitionType> {
+        self.powerset_construction_cancellable(entrypoint, &|| false)
+            .unwrap()
+    }
+
+    /// Like `powerset_construction`, but checks `is_cancelled` for every new state and returns
+    /// `None` as soon as it returns true.
+    pub fn powerset_construction_cancellable(
+        &self,
+        entrypoint: StateId,
+        is_cancelled: &dyn Fn() -> bool,
+    ) -> Option<Dfa<Vec<StateType>, TransitionType>> {
         let mut powerset_dfa: Graph<BTreeSet<StateId>, TransitionType> = DiGraph::new();
 
-        let start_dfa = self.add_powerset_to_dfa(&mut powerset_dfa, vec![entrypoint]);
+        let start_dfa =
+            self.add_powerset_to_dfa(&mut powerset_dfa, vec![entrypoint], is_cancelled)?;
 
         let mut tmp_id = 0;
         let mut dfa = Dfa::new();
@@ -255,6 +437,6 @@ impl<StateType: Clone + Debug, TransitionType: Debug + Clone + Eq + Ord>
             start_dfa,
         );
 
-        dfa
+        Some(dfa)
     }
 }
@@ -1,14 +1,76 @@
-use std::path::Path;
+use std::{
+    io::{IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-use clap::{arg, command, Args, Parser, Subcommand};
-use lapex::{generate, Language, ParsingAlgorithm};
+use clap::{
+    arg,
+    builder::{PossibleValuesParser, TypedValueParser},
+    command, Args, CommandFactory, Parser, Subcommand, ValueEnum,
+};
+use lapex::{
+    default_table_cache, find_symbol_references, generate, lex_file, measure_coverage, read_input,
+    run_repl, run_resolve, scaffold_visitor, verify_output, ErrorCode, ErrorKind,
+    GenerationOptions, GenerationReport, InputEncoding, Language, LapexError, LexedToken,
+    ParsingAlgorithm, PrecedenceStrategy, ResolveOptions, DEFAULT_TAB_WIDTH, STDOUT_TARGET,
+};
+use lapex_grammars::Template;
 use tempdir::TempDir;
+use tracing_subscriber::filter::LevelFilter;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct CommandLine {
     #[command(subcommand)]
     command: Commands,
+    #[arg(
+        long,
+        global = true,
+        help = "When to color the diagnostics",
+        default_value = "auto"
+    )]
+    color: ColorChoice,
+    #[arg(
+        short,
+        long,
+        global = true,
+        conflicts_with = "verbose",
+        help = "Only print errors and the requested output"
+    )]
+    quiet: bool,
+    #[arg(short, long, global = true, help = "Print what is being done")]
+    verbose: bool,
+    #[arg(
+        long,
+        global = true,
+        value_name = "LEVEL",
+        value_parser = PossibleValuesParser::new(["off", "error", "warn", "info", "debug", "trace"])
+            .map(|level| level.parse::<LevelFilter>().unwrap()),
+        default_value = "off",
+        help = "Log the phases of the generation and the sizes of the automata to stderr"
+    )]
+    log_level: LevelFilter,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum ColorChoice {
+    /// Color the diagnostics if they are printed to a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(&self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
@@ -17,22 +79,197 @@ enum Commands {
     Generate(GenerateArgs),
     #[command(about = "Generate and test a parser on a source file")]
     Debug(DebugArgs),
+    #[command(about = "List all places where a token or production is used")]
+    WhereUsed(WhereUsedArgs),
+    #[command(about = "Create a new Rust project from a grammar template")]
+    New(NewArgs),
+    #[command(about = "Write an implementation of the parser's visitor with empty methods")]
+    ScaffoldVisitor(ScaffoldVisitorArgs),
+    #[command(about = "Report the rules and tokens which sample inputs never use")]
+    Coverage(CoverageArgs),
+    #[command(about = "Parse lines typed into the terminal and show the tokens and reduced rules")]
+    Repl(ReplArgs),
+    #[command(about = "Print the tokens the lexer of a grammar splits a file into")]
+    Lex(LexArgs),
+    #[command(about = "Go through the conflicts of a grammar and apply the suggested changes")]
+    Resolve(ResolveArgs),
+    #[command(
+        about = "Check the generated files in a directory against the manifest written with them"
+    )]
+    Verify(VerifyArgs),
+    #[command(about = "Describe the diagnostic with the given code, like E0001, with examples")]
+    Explain(ExplainArgs),
 }
 
 #[derive(Args, Debug)]
 struct GenerateArgs {
-    #[arg(required = true)]
-    grammar: String,
+    #[arg(
+        required = true,
+        help = "The grammar files or glob patterns, each of several grammars is generated into a directory named after it"
+    )]
+    grammars: Vec<String>,
     #[arg(long, help = "Do not generate a lexer")]
     no_lexer: bool,
+    #[arg(
+        long,
+        conflicts_with_all = ["table", "tables_only", "profile_corpus"],
+        help = "Do not generate a parser, which lets the grammar consist of only tokens"
+    )]
+    no_parser: bool,
     #[arg(long, help = "Output the parser table")]
     table: bool,
+    #[arg(
+        long,
+        help = "Also write the lexer NFA and DFA and the states of an LR parser as Graphviz .dot files"
+    )]
+    emit_graphs: bool,
     #[arg(short, long, help = "The parser algorithm to use", default_value_t = ParsingAlgorithm::LL1)]
     algorithm: ParsingAlgorithm,
+    #[arg(
+        long,
+        help = "Reject productions written in several rules instead of merging them into alternatives"
+    )]
+    strict_duplicates: bool,
+    #[arg(
+        long,
+        help = "Rewrite left-recursive productions into repetitions (LL algorithms only)"
+    )]
+    eliminate_left_recursion: bool,
+    #[arg(
+        long,
+        help = "Return errors instead of panicking on invalid parser states (Rust only)"
+    )]
+    panic_free: bool,
+    #[arg(
+        long = "pmr",
+        help = "Let the parser containers allocate from a std::pmr::memory_resource (C++ only)"
+    )]
+    polymorphic_allocator: bool,
+    #[arg(
+        long,
+        help = "Count what the parser does and report it at the end of every parse (LR and GLR only)"
+    )]
+    metrics: bool,
+    #[arg(
+        long,
+        help = "Let a hook be set on the parser which is called every few tokens (Rust LR and GLR only)"
+    )]
+    progress: bool,
+    #[arg(
+        long,
+        help = "Allow the lints a crate may enable beyond the defaults in the generated files (Rust only)"
+    )]
+    allow_lints: bool,
+    #[arg(
+        long,
+        help = "Also write a module building a concrete syntax tree while parsing (Rust LR and GLR only)"
+    )]
+    cst: bool,
+    #[arg(
+        long,
+        help = "Also write a module assembling a typed syntax tree while parsing (Rust LR and GLR only)"
+    )]
+    ast: bool,
+    #[arg(
+        long,
+        help = "Also write a function lexing and parsing an input in one call (Rust LR and GLR, C++ LR)"
+    )]
+    facade: bool,
+    #[arg(
+        long,
+        help = "Write the tables of the lexer and parser as JSON instead of their code (LR and GLR only)"
+    )]
+    tables_only: bool,
+    #[arg(
+        long,
+        help = "Fail instead of creating the target directory if it does not exist"
+    )]
+    no_create: bool,
+    #[arg(
+        long = "profile",
+        value_name = "FILE",
+        help = "Parse this sample input to place frequently used parser states first (LR only)"
+    )]
+    profile_corpus: Vec<String>,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(
+        long,
+        value_parser = parse_symbol_prefix,
+        help = "Prefix the generated Rust modules and C++ namespaces, overriding the prefix of the grammar"
+    )]
+    prefix: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAMESPACE",
+        value_parser = parse_cpp_namespace,
+        help = "Nest the generated C++ namespaces into this one, like mycompany::mylang (C++ only)"
+    )]
+    cpp_namespace: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_parser = parse_cpp_namespace,
+        help = "Take the token enum of a grammar with extern tokens from this Rust module path or C++ namespace"
+    )]
+    external_tokens: Option<String>,
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        value_parser = PossibleValuesParser::new(PrecedenceStrategy::ALL.map(|s| s.name()))
+            .map(|name| PrecedenceStrategy::from_name(&name).unwrap()),
+        help = "How to pick the token when several match the same input, overriding the precedence of the grammar"
+    )]
+    precedence: Option<PrecedenceStrategy>,
+    #[arg(
+        long,
+        help = "Require explicit precedences for regex tokens matching the same input"
+    )]
+    conservative_precedence: bool,
+    #[arg(
+        long,
+        help = "Warn about tokens which are only picked over another one because they are declared first"
+    )]
+    warn_token_order: bool,
+    #[arg(
+        long,
+        value_name = "EXPRESSION",
+        value_parser = parse_spdx_license,
+        help = "Write this SPDX license expression, like \"MIT OR Apache-2.0\", at the top of every generated file"
+    )]
+    spdx_license: Option<String>,
+    #[arg(
+        long,
+        value_name = "TEXT",
+        help = "Write this copyright line at the top of every generated file"
+    )]
+    copyright: Option<String>,
+    #[arg(
+        long,
+        help = "Build the LR tables instead of reusing the ones cached for grammars of the same structure"
+    )]
+    no_table_cache: bool,
     #[arg(short, long, help = "The language to generate code for")]
     language: Language,
-    #[arg(long,        help = "The target path to write the generated code to", default_value_t = String::from("./generated/"))]
+    #[arg(long,        help = "The target path to write the generated code to, or - to print all files to stdout", default_value_t = String::from("./generated/"))]
     target: String,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        help = "Print how long each phase of the generation took and how large the automata are"
+    )]
+    timings: Option<OutputFormat>,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Args, Debug)]
@@ -43,31 +280,610 @@ struct DebugArgs {
     source: String,
     #[arg(short, long, help = "The parser algorithm to use", default_value_t = ParsingAlgorithm::GLR)]
     algorithm: ParsingAlgorithm,
+    #[arg(
+        long,
+        help = "Reject productions written in several rules instead of merging them into alternatives"
+    )]
+    strict_duplicates: bool,
+    #[arg(
+        long,
+        help = "Build the LR tables instead of reusing the ones cached for grammars of the same structure"
+    )]
+    no_table_cache: bool,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct WhereUsedArgs {
+    #[arg(required = true)]
+    grammar: String,
+    #[arg(required = true)]
+    symbol: String,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct NewArgs {
+    #[arg(required = true)]
+    path: String,
+    #[arg(
+        long,
+        help = "The grammar to start from",
+        value_parser = PossibleValuesParser::new(lapex_grammars::TEMPLATES.iter().map(|t| t.name))
+    )]
+    template: String,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct ScaffoldVisitorArgs {
+    #[arg(required = true)]
+    grammar: String,
+    #[arg(short, long, help = "The language to write the visitor in")]
+    language: Language,
+    #[arg(long, help = "The name of the visitor type", default_value_t = String::from("MyVisitor"))]
+    name: String,
+    #[arg(
+        long,
+        value_parser = parse_symbol_prefix,
+        help = "The prefix the parser was generated with, overriding the prefix of the grammar"
+    )]
+    prefix: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAMESPACE",
+        value_parser = parse_cpp_namespace,
+        help = "The C++ namespace the parser was generated with"
+    )]
+    cpp_namespace: Option<String>,
+    #[arg(
+        short,
+        long,
+        help = "The file to write the visitor to, instead of printing it. Existing files are not overwritten"
+    )]
+    output: Option<String>,
+    #[arg(
+        long,
+        help = "Reject productions written in several rules instead of merging them into alternatives"
+    )]
+    strict_duplicates: bool,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct CoverageArgs {
+    #[arg(required = true)]
+    grammar: String,
+    #[arg(required = true, help = "The sample inputs to parse")]
+    inputs: Vec<String>,
+    #[arg(
+        long,
+        help = "Reject productions written in several rules instead of merging them into alternatives"
+    )]
+    strict_duplicates: bool,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+// scripts can tell the kinds of failures apart by the exit code
+// the prefix becomes part of module and namespace names, so it has to be an identifier
+fn parse_symbol_prefix(value: &str) -> Result<String, String> {
+    let mut chars = value.chars();
+    if chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        Ok(value.to_string())
+    } else {
+        Err(String::from(
+            "the prefix has to start with a letter and contain only letters, digits and underscores",
+        ))
+    }
+}
+
+// every name of a nested namespace has to be an identifier, like a prefix
+fn parse_cpp_namespace(value: &str) -> Result<String, String> {
+    if value
+        .split("::")
+        .all(|name| parse_symbol_prefix(name).is_ok())
+    {
+        Ok(value.to_string())
+    } else {
+        Err(String::from(
+            "the namespace has to consist of names separated by ::, which start with a letter and contain only letters, digits and underscores",
+        ))
+    }
+}
+
+// an SPDX expression consists of license identifiers, operators and parentheses
+fn parse_spdx_license(value: &str) -> Result<String, String> {
+    if !value.trim().is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || " .-+:()".contains(c))
+    {
+        Ok(value.to_string())
+    } else {
+        Err(String::from(
+            "the license has to be an SPDX expression of identifiers like MIT, AND, OR, WITH and parentheses",
+        ))
+    }
+}
+
+fn exit_code(kind: ErrorKind) -> ExitCode {
+    ExitCode::from(match kind {
+        ErrorKind::Grammar => 1,
+        ErrorKind::Conflict => 2,
+        ErrorKind::Io => 3,
+        ErrorKind::Internal => 4,
+    })
+}
+
+#[derive(Args, Debug)]
+struct ReplArgs {
+    #[arg(required = true)]
+    grammar: String,
+    #[arg(
+        long,
+        help = "Reject productions written in several rules instead of merging them into alternatives"
+    )]
+    strict_duplicates: bool,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct ResolveArgs {
+    #[arg(required = true)]
+    grammar: String,
+    #[arg(short, long, help = "The parser algorithm whose conflicts are resolved", default_value_t = ParsingAlgorithm::LR1)]
+    algorithm: ParsingAlgorithm,
+    #[arg(
+        long,
+        help = "Reject productions written in several rules instead of merging them into alternatives"
+    )]
+    strict_duplicates: bool,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    #[arg(
+        help = "The directory the files were generated into",
+        default_value = "."
+    )]
+    target: String,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
+}
+
+#[derive(Args, Debug)]
+struct ExplainArgs {
+    #[arg(help = "The code printed in the header of the diagnostic, like E0001")]
+    code: String,
+}
+
+#[derive(Args, Debug)]
+struct LexArgs {
+    #[arg(required = true)]
+    grammar: String,
+    #[arg(required = true)]
+    input: String,
+    #[arg(long, help = "How to print the tokens", default_value = "text")]
+    format: OutputFormat,
+    #[arg(long, help = "The text encoding of the input files", default_value_t = InputEncoding::Auto)]
+    encoding: InputEncoding,
+    #[arg(long, help = "The width of a tab when printing diagnostics", default_value_t = DEFAULT_TAB_WIDTH)]
+    tab_width: usize,
 }
 
+// the tables are cached in the cache directory of the user unless disabled
+fn table_cache(no_table_cache: bool) -> Option<PathBuf> {
+    if no_table_cache {
+        None
+    } else {
+        default_table_cache()
+    }
+}
+
+fn print_errors(mut errors: Vec<LapexError>, tab_width: usize, color: bool) -> ExitCode {
+    let error_count = errors.len();
+    for (i, error) in errors.iter_mut().enumerate() {
+        error.set_tab_width(tab_width);
+        error.set_color(color);
+        eprintln!("{}", error);
+        if i + 1 < error_count {
+            eprintln!();
+        }
+    }
+    eprintln!();
+    eprintln!("{}", LapexError::summary(&errors, color));
+    // the most severe error decides the exit code, warnings do not fail
+    errors
+        .iter()
+        .filter(|error| !error.is_warning())
+        .map(LapexError::kind)
+        .max()
+        .map_or(ExitCode::SUCCESS, exit_code)
+}
+
+// patterns are expanded here as well, for shells which do not expand them
+fn expand_grammar_paths(arguments: &[String]) -> Result<Vec<PathBuf>, Vec<LapexError>> {
+    let mut paths = Vec::new();
+    for argument in arguments {
+        if !argument.contains(['*', '?', '[']) {
+            paths.push(PathBuf::from(argument));
+            continue;
+        }
+        let no_match = || {
+            LapexError::io(
+                PathBuf::from(argument),
+                std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "no grammar matches the pattern",
+                ),
+            )
+        };
+        let mut matches: Vec<PathBuf> = glob::glob(argument)
+            .map_err(|_| no_match())?
+            .filter_map(Result::ok)
+            .collect();
+        if matches.is_empty() {
+            return Err(no_match());
+        }
+        matches.sort();
+        paths.extend(matches);
+    }
+    Ok(paths)
+}
+
+fn generate_usage_error(kind: clap::error::ErrorKind, message: impl std::fmt::Display) -> ! {
+    let mut command = CommandLine::command();
+    command.build();
+    command
+        .find_subcommand_mut("generate")
+        .expect("the generate command exists")
+        .error(kind, message)
+        .exit()
+}
+
+fn print_warnings(report: &mut GenerationReport, tab_width: usize, color: bool, quiet: bool) {
+    let warnings = report.take_warnings();
+    if !quiet && !warnings.is_empty() {
+        print_errors(warnings, tab_width, color);
+    }
+}
+
+fn write_template_project(template: &Template, project_path: &Path) -> std::io::Result<()> {
+    if project_path.exists() && project_path.read_dir()?.next().is_some() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            format!("{} is not empty", project_path.display()),
+        ));
+    }
+    std::fs::create_dir_all(project_path.join("src"))?;
+
+    let package_name: String = project_path
+        .canonicalize()?
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| String::from(template.name))
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect();
+    std::fs::write(
+        project_path.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+            package_name
+        ),
+    )?;
+    std::fs::write(
+        project_path.join(format!("{}.lapex", template.name)),
+        template.grammar,
+    )?;
+    std::fs::write(project_path.join("input.txt"), template.sample)?;
+
+    std::fs::write(
+        project_path.join("src").join("main.rs"),
+        r#"use lexer::Lexer;
+use parser::{DebugVisitor, Parser};
+
+mod lexer;
+mod parser;
+mod tokens;
+
 fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("input.txt"));
+    let source = std::fs::read_to_string(&path).unwrap();
+    let mut lexer = Lexer::new(&source);
+    let mut parser = Parser::new(|| lexer.next().map(|token| (token, ())), DebugVisitor {});
+    parser.parse().unwrap();
+}
+"#,
+    )
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if ch.is_control() => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn print_tokens(tokens: &[LexedToken], format: &OutputFormat) {
+    match format {
+        OutputFormat::Text => {
+            for token in tokens {
+                let span = format!(
+                    "{}:{}-{}:{}",
+                    token.span.start.line,
+                    token.span.start.col,
+                    token.span.end.line,
+                    token.span.end.col
+                );
+                println!("{:<12} {:<16} {:?}", span, token.name, token.text);
+            }
+        }
+        OutputFormat::Json => {
+            let tokens: Vec<String> = tokens
+                .iter()
+                .map(|token| {
+                    format!(
+                        "{{\"token\":{},\"start\":{{\"line\":{},\"col\":{}}},\"end\":{{\"line\":{},\"col\":{}}},\"text\":{}}}",
+                        json_string(&token.name),
+                        token.span.start.line,
+                        token.span.start.col,
+                        token.span.end.line,
+                        token.span.end.col,
+                        json_string(&token.text)
+                    )
+                })
+                .collect();
+            println!("[{}]", tokens.join(","));
+        }
+    }
+}
+
+fn print_report(
+    report: &GenerationReport,
+    format: &OutputFormat,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    match format {
+        OutputFormat::Text => {
+            for (phase, duration) in report.phases() {
+                writeln!(
+                    output,
+                    "{:<18} {:>10.3} ms",
+                    phase,
+                    duration.as_secs_f64() * 1000.0
+                )?;
+            }
+            writeln!(
+                output,
+                "{:<18} {:>10.3} ms",
+                "total",
+                report.total().as_secs_f64() * 1000.0
+            )?;
+            for (counter, value) in report.counters() {
+                writeln!(output, "{:<18} {:>10}", counter, value)?;
+            }
+        }
+        OutputFormat::Json => {
+            let phases: Vec<String> = report
+                .phases()
+                .iter()
+                .map(|(phase, duration)| format!("\"{}\":{}", phase, duration.as_secs_f64()))
+                .collect();
+            let counters: Vec<String> = report
+                .counters()
+                .iter()
+                .map(|(counter, value)| format!("\"{}\":{}", counter, value))
+                .collect();
+            writeln!(
+                output,
+                "{{\"phases\":{{{}}},\"total\":{},\"counters\":{{{}}}}}",
+                phases.join(","),
+                report.total().as_secs_f64(),
+                counters.join(",")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    // the panic message is printed before, but scripts should see an internal error
+    std::panic::catch_unwind(run).unwrap_or_else(|_| exit_code(ErrorKind::Internal))
+}
+
+fn run() -> ExitCode {
     let cli = CommandLine::parse();
+    let color = cli.color.enabled();
+    if cli.log_level != LevelFilter::OFF {
+        tracing_subscriber::fmt()
+            .with_max_level(cli.log_level)
+            .with_writer(std::io::stderr)
+            .with_ansi(color)
+            .init();
+    }
+    // progress messages go to stderr, to keep the output of the commands usable in pipes
+    let progress = |message: std::fmt::Arguments| {
+        if cli.verbose {
+            eprintln!("{}", message);
+        }
+    };
     match cli.command {
         Commands::Generate(cmd) => {
-            let result = generate(
-                !cmd.no_lexer,
-                cmd.algorithm,
-                cmd.table,
-                Path::new(&cmd.grammar),
-                Path::new(&cmd.target),
-                cmd.language,
-                lapex_input_gen::GeneratedLapexInputParser {},
-            );
-            match result {
-                Err(errors) => {
-                    for (i, error) in errors.iter().enumerate() {
-                        eprintln!("{}", error);
-                        if i + 1 < errors.len() {
-                            eprintln!();
+            let grammars = match expand_grammar_paths(&cmd.grammars) {
+                Ok(grammars) => grammars,
+                Err(errors) => return print_errors(errors, cmd.tab_width, color),
+            };
+            let target = Path::new(&cmd.target);
+            let targets: Vec<PathBuf> = if grammars.len() == 1 {
+                vec![target.to_path_buf()]
+            } else if cmd.target == STDOUT_TARGET {
+                generate_usage_error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "the generated code of several grammars cannot be printed to stdout",
+                )
+            } else {
+                grammars
+                    .iter()
+                    .map(|grammar| target.join(grammar.file_stem().unwrap_or_default()))
+                    .collect()
+            };
+            for (second, path) in targets.iter().enumerate() {
+                if let Some(first) = targets[..second].iter().position(|other| other == path) {
+                    generate_usage_error(
+                        clap::error::ErrorKind::ValueValidation,
+                        format!(
+                            "the grammars {} and {} would be generated into the same directory",
+                            grammars[first].display(),
+                            grammars[second].display()
+                        ),
+                    )
+                }
+            }
+
+            let options = GenerationOptions {
+                generate_lexer: !cmd.no_lexer,
+                generate_parser: !cmd.no_parser,
+                algorithm: cmd.algorithm.clone(),
+                generate_table: cmd.table,
+                emit_graphs: cmd.emit_graphs,
+                merge_duplicate_productions: !cmd.strict_duplicates,
+                eliminate_left_recursion: cmd.eliminate_left_recursion,
+                panic_free: cmd.panic_free,
+                polymorphic_allocator: cmd.polymorphic_allocator,
+                metrics: cmd.metrics,
+                progress: cmd.progress,
+                allow_lints: cmd.allow_lints,
+                cst: cmd.cst,
+                ast: cmd.ast,
+                facade: cmd.facade,
+                tables_only: cmd.tables_only,
+                create_target: !cmd.no_create,
+                profile_corpus: cmd.profile_corpus.iter().map(PathBuf::from).collect(),
+                encoding: cmd.encoding,
+                symbol_prefix: cmd.prefix.clone(),
+                cpp_namespace: cmd.cpp_namespace.clone(),
+                external_tokens: cmd.external_tokens.clone(),
+                precedence_strategy: cmd.precedence,
+                conservative_precedence: cmd.conservative_precedence,
+                warn_token_order: cmd.warn_token_order,
+                spdx_license: cmd.spdx_license.clone(),
+                copyright: cmd.copyright.clone(),
+                table_cache: table_cache(cmd.no_table_cache),
+                ..Default::default()
+            };
+            // the grammars are independent, so each one is generated on its own thread
+            let results: Vec<_> = std::thread::scope(|scope| {
+                let handles: Vec<_> = grammars
+                    .iter()
+                    .zip(&targets)
+                    .map(|(grammar, target)| {
+                        if cmd.no_parser {
+                            progress(format_args!(
+                                "Generating the lexer from {} into {}",
+                                grammar.display(),
+                                target.display()
+                            ));
+                        } else {
+                            progress(format_args!(
+                                "Generating the {} parser from {} into {}",
+                                cmd.algorithm,
+                                grammar.display(),
+                                target.display()
+                            ));
+                        }
+                        let (options, language) = (&options, cmd.language.clone());
+                        scope.spawn(move || {
+                            let source = read_input(grammar, options.encoding)?;
+                            generate(
+                                options,
+                                grammar,
+                                &source,
+                                target,
+                                language,
+                                lapex_input_gen::GeneratedLapexInputParser {},
+                            )
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| {
+                        handle
+                            .join()
+                            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                    })
+                    .collect()
+            });
+
+            // the diagnostics of all grammars are printed together, with one summary
+            let mut diagnostics = Vec::new();
+            for (grammar, result) in grammars.iter().zip(results) {
+                match result {
+                    Ok(mut report) => {
+                        let warnings = report.take_warnings();
+                        if !cli.quiet {
+                            diagnostics.extend(warnings);
+                        }
+                        progress(format_args!(
+                            "Finished {} in {:.3} ms",
+                            grammar.display(),
+                            report.total().as_secs_f64() * 1000.0
+                        ));
+                        if let Some(format) = &cmd.timings {
+                            if grammars.len() > 1 && matches!(format, OutputFormat::Text) {
+                                println!("{}:", grammar.display());
+                            }
+                            // the generated code takes stdout when it is streamed
+                            let printed = if cmd.target == STDOUT_TARGET {
+                                print_report(&report, format, &mut std::io::stderr())
+                            } else {
+                                print_report(&report, format, &mut std::io::stdout())
+                            };
+                            printed.expect("failed to print the timings");
                         }
                     }
+                    Err(errors) => diagnostics.extend(errors),
                 }
-                _ => {}
+            }
+            if diagnostics.is_empty() {
+                ExitCode::SUCCESS
+            } else {
+                print_errors(diagnostics, cmd.tab_width, color)
             }
         }
         Commands::Debug(cmd) => {
@@ -75,30 +891,60 @@ fn main() {
             let project_path = target_dir.path().join("generated");
             let target_path = project_path.join("src");
             std::fs::create_dir_all(&target_path).unwrap();
+            let grammar_path = Path::new(&cmd.grammar);
+            let grammar_source = match read_input(grammar_path, cmd.encoding) {
+                Ok(source) => source,
+                Err(errors) => return print_errors(errors, cmd.tab_width, color),
+            };
             let source_path = Path::new(&cmd.source);
-            let result = generate(
-                true,
+            // the generated program reads its input as UTF-8
+            let source = match read_input(source_path, cmd.encoding) {
+                Ok(source) => source,
+                Err(errors) => return print_errors(errors, cmd.tab_width, color),
+            };
+            progress(format_args!(
+                "Generating the {} parser from {} into {}",
                 cmd.algorithm,
-                true,
-                Path::new(&cmd.grammar),
+                cmd.grammar,
+                target_path.display()
+            ));
+            let result = generate(
+                &GenerationOptions {
+                    algorithm: cmd.algorithm,
+                    generate_table: true,
+                    merge_duplicate_productions: !cmd.strict_duplicates,
+                    facade: true,
+                    encoding: cmd.encoding,
+                    table_cache: table_cache(cmd.no_table_cache),
+                    ..Default::default()
+                },
+                grammar_path,
+                &grammar_source,
                 &target_path,
                 Language::Rust,
                 lapex_input_gen::GeneratedLapexInputParser {},
             );
             match result {
-                Err(errors) => {
-                    for (i, error) in errors.iter().enumerate() {
-                        eprintln!("{}", error);
-                        if i + 1 < errors.len() {
-                            eprintln!();
-                        }
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(mut report) => {
+                    print_warnings(&mut report, cmd.tab_width, color, cli.quiet);
+                    let mut cargo_flags = vec![if color {
+                        "--color=always"
+                    } else {
+                        "--color=never"
+                    }];
+                    if cli.quiet {
+                        cargo_flags.push("--quiet");
                     }
-                }
-                _ => {
+                    progress(format_args!(
+                        "Creating a cargo project in {}",
+                        project_path.display()
+                    ));
                     assert!(
                         std::process::Command::new("cargo")
                             .current_dir(&project_path)
                             .arg("init")
+                            .args(&cargo_flags)
                             .spawn()
                             .unwrap()
                             .wait()
@@ -106,57 +952,271 @@ fn main() {
                             .success(),
                         "Failed to initialize cargo project"
                     );
-                    std::fs::copy(source_path, project_path.join("input.txt")).unwrap();
+                    std::fs::write(project_path.join("input.txt"), source).unwrap();
                     std::fs::write(
                         target_path.join("main.rs"),
                         r#"
-                        use lexer::Lexer;
-                        use parser::{Parser, DebugVisitor};
-                        use tokens::TokenType;
-                        
                         mod lexer;
                         mod parser;
                         mod tokens;
-                        
-                        #[derive(Debug)]
-                        struct DebugError;
-                        impl std::error::Error for DebugError {}
-                        impl std::fmt::Display for DebugError {
-                            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                                write!(f, "DebugError")
-                            }
-                        }
 
                         fn main() {
-                            let viz = DebugVisitor {};
                             let src = std::fs::read_to_string("input.txt").unwrap();
-                            let mut lex = Lexer::new(src.as_str());
-                            let mut par = Parser::new(
-                                || {
-                                    let tk = lex.next().unwrap();
-                                    Ok::<(TokenType, ()), DebugError>((tk, ()))
-                                },
-                                viz,
-                            );
-                            par.parse().unwrap();
-                        }                        
+                            parser::parse_str(&src, parser::DebugVisitor {}).unwrap();
+                        }
                         "#,
                     )
                     .unwrap();
                     let mut run_process = std::process::Command::new("cargo")
                         .current_dir(&project_path)
                         .arg("run")
+                        .args(&cargo_flags)
                         .spawn()
                         .unwrap();
-                    let exit_code = run_process.wait().unwrap();
-                    if exit_code.success() {
-                        println!("Successfully parsed {}", source_path.display());
-                    } else {
+                    let parsed = run_process.wait().unwrap().success();
+                    target_dir.close().unwrap();
+                    if !parsed {
                         eprintln!("Failed to parse {}", source_path.display());
+                        return ExitCode::FAILURE;
                     }
-                    target_dir.close().unwrap();
+                    if !cli.quiet {
+                        println!("Successfully parsed {}", source_path.display());
+                    }
+                    ExitCode::SUCCESS
+                }
+            }
+        }
+        Commands::New(cmd) => {
+            let template = lapex_grammars::find_template(&cmd.template).unwrap();
+            let project_path = Path::new(&cmd.path);
+            if let Err(error) = write_template_project(template, project_path) {
+                eprintln!("failed to create {}: {}", project_path.display(), error);
+                return exit_code(ErrorKind::Io);
+            }
+            progress(format_args!(
+                "Generating the {} parser for the {} template",
+                ParsingAlgorithm::GLR,
+                template.name
+            ));
+            let result = generate(
+                &GenerationOptions {
+                    algorithm: ParsingAlgorithm::GLR,
+                    encoding: InputEncoding::Utf8,
+                    ..Default::default()
+                },
+                &project_path.join(format!("{}.lapex", template.name)),
+                template.grammar,
+                &project_path.join("src"),
+                Language::Rust,
+                lapex_input_gen::GeneratedLapexInputParser {},
+            );
+            match result {
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(_) => {
+                    if !cli.quiet {
+                        println!(
+                            "Created {} from the {} template, run it with `cargo run`",
+                            project_path.display(),
+                            template.name
+                        );
+                    }
+                    ExitCode::SUCCESS
+                }
+            }
+        }
+        Commands::ScaffoldVisitor(cmd) => {
+            let grammar_path = Path::new(&cmd.grammar);
+            let result = read_input(grammar_path, cmd.encoding).and_then(|source| {
+                scaffold_visitor(
+                    grammar_path,
+                    &source,
+                    cmd.language,
+                    &cmd.name,
+                    cmd.prefix.as_deref(),
+                    cmd.cpp_namespace.as_deref(),
+                    !cmd.strict_duplicates,
+                    lapex_input_gen::GeneratedLapexInputParser {},
+                )
+            });
+            match (result, &cmd.output) {
+                (Err(errors), _) => print_errors(errors, cmd.tab_width, color),
+                (Ok(visitor), None) => {
+                    print!("{}", visitor);
+                    ExitCode::SUCCESS
+                }
+                (Ok(visitor), Some(output)) => {
+                    let written = std::fs::OpenOptions::new()
+                        .write(true)
+                        .create_new(true)
+                        .open(output)
+                        .and_then(|mut file| file.write_all(visitor.as_bytes()));
+                    match written {
+                        Ok(()) => {
+                            progress(format_args!("Wrote the visitor to {}", output));
+                            ExitCode::SUCCESS
+                        }
+                        Err(error) => {
+                            eprintln!("failed to write {}: {}", output, error);
+                            exit_code(ErrorKind::Io)
+                        }
+                    }
+                }
+            }
+        }
+        Commands::Coverage(cmd) => {
+            let inputs: Vec<PathBuf> = cmd.inputs.iter().map(PathBuf::from).collect();
+            progress(format_args!(
+                "Parsing {} inputs with {}",
+                inputs.len(),
+                cmd.grammar
+            ));
+            let result = measure_coverage(
+                Path::new(&cmd.grammar),
+                &inputs,
+                !cmd.strict_duplicates,
+                cmd.encoding,
+                lapex_input_gen::GeneratedLapexInputParser {},
+            );
+            match result {
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(coverage) => {
+                    for path in &coverage.rejected {
+                        println!("{}: not accepted by the grammar", path.display());
+                    }
+                    for entry in coverage.unused_rules() {
+                        println!(
+                            "{}:{}:{}: rule never used: {}",
+                            cmd.grammar, entry.span.start.line, entry.span.start.col, entry.text
+                        );
+                    }
+                    for entry in coverage.unused_tokens() {
+                        println!(
+                            "{}:{}:{}: token never used: {}",
+                            cmd.grammar, entry.span.start.line, entry.span.start.col, entry.text
+                        );
+                    }
+                    println!(
+                        "rules: {}/{} ({:.1}%)",
+                        coverage.rules.len() - coverage.unused_rules().count(),
+                        coverage.rules.len(),
+                        coverage.rule_percentage()
+                    );
+                    println!(
+                        "tokens: {}/{} ({:.1}%)",
+                        coverage.tokens.len() - coverage.unused_tokens().count(),
+                        coverage.tokens.len(),
+                        coverage.token_percentage()
+                    );
+                    ExitCode::SUCCESS
+                }
+            }
+        }
+        Commands::Repl(cmd) => {
+            progress(format_args!(
+                "Building the LR(1) parser of {}, end the input to quit",
+                cmd.grammar
+            ));
+            let result = run_repl(
+                Path::new(&cmd.grammar),
+                !cmd.strict_duplicates,
+                cmd.encoding,
+                lapex_input_gen::GeneratedLapexInputParser {},
+                std::io::stdin().lock(),
+                std::io::stdout().lock(),
+            );
+            match result {
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(()) => ExitCode::SUCCESS,
+            }
+        }
+        Commands::Resolve(cmd) => {
+            let options = ResolveOptions {
+                algorithm: cmd.algorithm,
+                merge_duplicate_productions: !cmd.strict_duplicates,
+                encoding: cmd.encoding,
+                tab_width: cmd.tab_width,
+                color,
+            };
+            let result = run_resolve(
+                Path::new(&cmd.grammar),
+                &options,
+                lapex_input_gen::GeneratedLapexInputParser {},
+                std::io::stdin().lock(),
+                std::io::stdout().lock(),
+            );
+            match result {
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(0) => ExitCode::SUCCESS,
+                Ok(_) => exit_code(ErrorKind::Conflict),
+            }
+        }
+        Commands::Verify(cmd) => match verify_output(Path::new(&cmd.target)) {
+            Err(errors) => print_errors(errors, cmd.tab_width, color),
+            Ok(mismatches) => {
+                for mismatch in &mismatches {
+                    println!("{}", mismatch);
+                }
+                if mismatches.is_empty() {
+                    if !cli.quiet {
+                        println!("All generated files match the manifest");
+                    }
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::FAILURE
+                }
+            }
+        },
+        Commands::Explain(cmd) => match ErrorCode::from_name(&cmd.code) {
+            Some(code) => {
+                print!("{}", code.explanation());
+                ExitCode::SUCCESS
+            }
+            None => {
+                let last = ErrorCode::all().last().unwrap();
+                eprintln!(
+                    "there is no diagnostic {}, the codes go from E0001 to {}",
+                    cmd.code, last
+                );
+                ExitCode::FAILURE
+            }
+        },
+        Commands::Lex(cmd) => {
+            let result = lex_file(
+                Path::new(&cmd.grammar),
+                Path::new(&cmd.input),
+                cmd.encoding,
+                lapex_input_gen::GeneratedLapexInputParser {},
+            );
+            match result {
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(tokens) => {
+                    print_tokens(&tokens, &cmd.format);
+                    ExitCode::SUCCESS
+                }
+            }
+        }
+        Commands::WhereUsed(cmd) => {
+            let grammar_path = Path::new(&cmd.grammar);
+            let result = read_input(grammar_path, cmd.encoding).and_then(|source| {
+                find_symbol_references(
+                    grammar_path,
+                    &source,
+                    &cmd.symbol,
+                    lapex_input_gen::GeneratedLapexInputParser {},
+                )
+            });
+            match result {
+                Err(errors) => print_errors(errors, cmd.tab_width, color),
+                Ok(references) => {
+                    for span in references {
+                        println!("{}:{}:{}", cmd.grammar, span.start.line, span.start.col);
+                    }
+                    ExitCode::SUCCESS
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
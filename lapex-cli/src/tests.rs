@@ -0,0 +1,67 @@
+use std::{path::PathBuf, process::ExitCode};
+
+use lapex::{
+    generate_in_memory, read_input, ErrorKind, GenerationOptions, InputEncoding, Language,
+};
+use lapex_input_gen::GeneratedLapexInputParser;
+use tempdir::TempDir;
+
+use crate::{exit_code, expand_grammar_paths, print_errors};
+
+#[test]
+fn test_expand_grammar_paths() {
+    let dir = TempDir::new("lapex_glob").unwrap();
+    let pattern = dir.path().join("*.lapex").display().to_string();
+    let errors = expand_grammar_paths(std::slice::from_ref(&pattern)).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind(), ErrorKind::Io);
+
+    for name in ["b.lapex", "a.lapex", "c.txt"] {
+        std::fs::write(dir.path().join(name), "").unwrap();
+    }
+    // paths without a pattern are kept as they are, even if there is no such file
+    let paths = expand_grammar_paths(&[pattern, String::from("missing.lapex")]).unwrap();
+    assert_eq!(
+        paths,
+        vec![
+            dir.path().join("a.lapex"),
+            dir.path().join("b.lapex"),
+            PathBuf::from("missing.lapex"),
+        ]
+    );
+}
+
+#[test]
+fn test_exit_codes() {
+    let kinds = [
+        ErrorKind::Grammar,
+        ErrorKind::Conflict,
+        ErrorKind::Io,
+        ErrorKind::Internal,
+    ];
+    assert_eq!(kinds.map(exit_code), [1, 2, 3, 4].map(ExitCode::from));
+
+    let generate_grammar = |grammar| {
+        generate_in_memory(
+            &GenerationOptions {
+                create_target: false,
+                encoding: InputEncoding::Utf8,
+                ..Default::default()
+            },
+            &PathBuf::from("grammar.lapex"),
+            grammar,
+            Language::Rust,
+            GeneratedLapexInputParser {},
+        )
+        .unwrap_err()
+    };
+    let grammar_errors = generate_grammar("token NUM = /[0-9]+/;\nentry e;\nprod e = NUM");
+    let conflict_errors = generate_grammar(
+        "token NUM = /[0-9]+/;\ntoken PLUS = \"+\";\nentry e;\nprod e = s;\nprod s = s PLUS s | NUM;\n",
+    );
+    let io_errors = read_input(&PathBuf::from("missing.lapex"), InputEncoding::Auto).unwrap_err();
+    assert_eq!(print_errors(grammar_errors, 4, false), ExitCode::from(1));
+    assert_eq!(print_errors(conflict_errors, 4, false), ExitCode::from(2));
+    assert_eq!(print_errors(io_errors, 4, false), ExitCode::from(3));
+    assert_eq!(print_errors(Vec::new(), 4, false), ExitCode::SUCCESS);
+}
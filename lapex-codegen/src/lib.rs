@@ -2,14 +2,23 @@ use std::collections::BTreeMap;
 use std::io::Write;
 
 mod templating;
+mod user_code;
 
 pub use templating::Template;
 pub use templating::TemplateWriter;
+pub use user_code::restore_user_code;
+pub use user_code::strip_user_code;
+pub use user_code::write_user_code_section;
+pub use user_code::UserCodeError;
+
+// opens the writer of a file which has no target
+type DefaultWriterFun<'writer> =
+    Box<dyn (Fn(&str) -> std::io::Result<Box<dyn Write + 'writer>>) + 'writer>;
 
 pub struct GeneratedCodeWriter<'writer> {
-    targets: BTreeMap<&'static str, &'writer mut dyn Write>,
-    default_writer_fun:
-        Box<dyn (Fn(&'static str) -> std::io::Result<Box<dyn Write + 'writer>>) + 'writer>,
+    targets: BTreeMap<String, &'writer mut dyn Write>,
+    default_writer_fun: DefaultWriterFun<'writer>,
+    header: Vec<String>,
 }
 
 impl<'writer> GeneratedCodeWriter<'writer> {
@@ -20,7 +29,7 @@ impl<'writer> GeneratedCodeWriter<'writer> {
     pub fn with_default<F, W>(writer_fun: F) -> Self
     where
         W: Write + 'writer,
-        F: (Fn(&'static str) -> std::io::Result<W>) + 'writer,
+        F: (Fn(&str) -> std::io::Result<W>) + 'writer,
     {
         GeneratedCodeWriter {
             targets: BTreeMap::new(),
@@ -28,25 +37,63 @@ impl<'writer> GeneratedCodeWriter<'writer> {
                 let writer = writer_fun(name)?;
                 Ok(Box::new(writer))
             }),
+            header: Vec::new(),
         }
     }
 
-    pub fn add_target<W>(&mut self, key: &'static str, writer: &'writer mut W)
+    /// Writes these lines as comments at the top of every file, like a license notice.
+    pub fn set_header(&mut self, lines: Vec<String>) {
+        self.header = lines;
+    }
+
+    pub fn add_target<W>(&mut self, key: &str, writer: &'writer mut W)
     where
         W: Write,
     {
-        self.targets.insert(key, writer);
+        self.targets.insert(key.to_string(), writer);
     }
 
-    pub fn generate_code<G>(&mut self, key: &'static str, code_generator: G) -> std::io::Result<()>
+    pub fn generate_code<G>(&mut self, key: &str, code_generator: G) -> std::io::Result<()>
     where
         G: Fn(&mut dyn Write) -> Result<(), std::io::Error>,
     {
-        if let Some(writer) = self.targets.get_mut(&key) {
+        if let Some(writer) = self.targets.get_mut(key) {
+            write_header(&self.header, writer)?;
             code_generator(writer)
         } else {
             let mut sink = (self.default_writer_fun)(key)?;
+            write_header(&self.header, &mut sink)?;
             code_generator(&mut sink)
         }
     }
+
+    /// Writes a file which is not code, like the tables of a parser, so it gets no header.
+    pub fn generate_data<G>(&mut self, key: &str, data_generator: G) -> std::io::Result<()>
+    where
+        G: Fn(&mut dyn Write) -> Result<(), std::io::Error>,
+    {
+        if let Some(writer) = self.targets.get_mut(key) {
+            data_generator(writer)
+        } else {
+            data_generator(&mut (self.default_writer_fun)(key)?)
+        }
+    }
+}
+
+// both languages that code is generated for use the same line comments
+fn write_header(header: &[String], output: &mut dyn Write) -> std::io::Result<()> {
+    if header.is_empty() {
+        return Ok(());
+    }
+    for line in header {
+        if line.is_empty() {
+            writeln!(output, "//")?;
+        } else {
+            writeln!(output, "// {}", line)?;
+        }
+    }
+    writeln!(output)
 }
+
+#[cfg(test)]
+mod tests;
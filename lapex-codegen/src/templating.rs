@@ -1,32 +1,76 @@
 use std::{
     collections::HashMap,
     io::{Error, Write},
+    ops::Range,
 };
 
 use once_cell::unsync::Lazy;
-use regex::{Captures, Regex};
+use regex::Regex;
 
 pub struct Template<'src> {
     source: &'src str,
+    partials: HashMap<&'static str, &'src str>,
 }
 
 impl<'src> Template<'src> {
     pub fn new(source: &'src str) -> Self {
-        Template { source }
+        Template {
+            source,
+            partials: HashMap::new(),
+        }
+    }
+
+    /// Registers a template which is written in place of `/*{>name}*/`, with the substitutions
+    /// and conditions of the including template.
+    pub fn with_partial(mut self, name: &'static str, source: &'src str) -> Self {
+        self.partials.insert(name, source);
+        self
     }
 
     pub fn writer<'writer>(&'src self) -> TemplateWriter<'writer, 'src> {
         TemplateWriter {
-            template: self.source,
+            template: self,
             substitutions: HashMap::new(),
+            conditions: HashMap::new(),
         }
     }
 }
 
 pub struct TemplateWriter<'writer, 'template> {
-    template: &'template str,
+    template: &'template Template<'template>,
     substitutions:
         HashMap<&'static str, Box<dyn Fn(&mut dyn Write) -> Result<(), Error> + 'writer>>,
+    conditions: HashMap<&'static str, bool>,
+}
+
+enum Tag<'src> {
+    Substitution(&'src str),
+    Section { key: &'src str, inverted: bool },
+    SectionEnd(&'src str),
+    Partial(&'src str),
+}
+
+impl<'src> Tag<'src> {
+    fn parse(text: &'src str) -> Self {
+        let text = text.trim();
+        if let Some(key) = text.strip_prefix('#') {
+            Tag::Section {
+                key: key.trim(),
+                inverted: false,
+            }
+        } else if let Some(key) = text.strip_prefix('^') {
+            Tag::Section {
+                key: key.trim(),
+                inverted: true,
+            }
+        } else if let Some(key) = text.strip_prefix('/') {
+            Tag::SectionEnd(key.trim())
+        } else if let Some(key) = text.strip_prefix('>') {
+            Tag::Partial(key.trim())
+        } else {
+            Tag::Substitution(text)
+        }
+    }
 }
 
 impl<'writer, 'template> TemplateWriter<'writer, 'template> {
@@ -37,6 +81,12 @@ impl<'writer, 'template> TemplateWriter<'writer, 'template> {
         self.substitutions.insert(key, Box::new(writer));
     }
 
+    /// Decides whether the text between `/*{#key}*/` and `/*{/key}*/` is written. The text
+    /// between `/*{^key}*/` and `/*{/key}*/` is written if the condition is false instead.
+    pub fn condition(&mut self, key: &'static str, value: bool) {
+        self.conditions.insert(key, value);
+    }
+
     fn insert_substitution(&self, key: &str, writer: &mut dyn Write) -> Result<(), Error> {
         let subsitution_function = if let Some(fun) = self.substitutions.get(key) {
             fun
@@ -48,46 +98,92 @@ impl<'writer, 'template> TemplateWriter<'writer, 'template> {
         };
         subsitution_function(writer)
     }
-}
 
-fn replace_all_streaming<'h, R>(
-    regex: &Regex,
-    haystack: &'h str,
-    rep: R,
-    writer: &mut dyn Write,
-) -> std::io::Result<()>
-where
-    R: Fn(&Captures, &mut dyn Write) -> std::io::Result<()>,
-{
-    let mut it = regex.captures_iter(haystack).peekable();
-    if it.peek().is_none() {
-        write!(writer, "{}", haystack)?;
-        return Ok(());
+    fn condition_value(&self, key: &str) -> bool {
+        if let Some(value) = self.conditions.get(key) {
+            *value
+        } else {
+            panic!(
+                "Section '{}' in template failed, as no condition for it was supplied",
+                key
+            );
+        }
     }
-    let mut last_match = 0;
-    for cap in it {
-        // unwrap on 0 is OK because captures only reports matches
-        let m = cap.get(0).unwrap();
-        write!(writer, "{}", &haystack[last_match..m.start()])?;
-        rep(&cap, writer)?;
-        last_match = m.end();
+
+    fn partial_source(&self, key: &str) -> &'template str {
+        if let Some(source) = self.template.partials.get(key) {
+            source
+        } else {
+            panic!(
+                "Inclusion of '{}' in template failed, as no partial with this name was registered",
+                key
+            );
+        }
+    }
+
+    fn write_source(
+        &self,
+        regex: &Regex,
+        source: &str,
+        writer: &mut dyn Write,
+    ) -> Result<(), Error> {
+        // the keys of the open sections and whether their text is written
+        let mut sections: Vec<(&str, bool)> = Vec::new();
+        let mut last_match = 0;
+        for captures in regex.captures_iter(source) {
+            // unwrap on 0 is OK because captures only reports matches
+            let m = captures.get(0).unwrap();
+            let tag = Tag::parse(captures.get(1).unwrap().as_str());
+            let range = match tag {
+                Tag::Substitution(_) => m.range(),
+                _ => standalone_line(source, m.range()).unwrap_or(m.range()),
+            };
+            let visible = sections.iter().all(|(_, visible)| *visible);
+            if visible {
+                write!(writer, "{}", &source[last_match..range.start])?;
+            }
+            last_match = range.end;
+
+            match tag {
+                Tag::Substitution(key) if visible => self.insert_substitution(key, writer)?,
+                Tag::Substitution(_) => (),
+                Tag::Section { key, inverted } => {
+                    sections.push((key, self.condition_value(key) != inverted));
+                }
+                Tag::SectionEnd(key) => match sections.pop() {
+                    Some((open, _)) if open == key => (),
+                    Some((open, _)) => panic!(
+                        "Section '{}' in template is closed by the end of section '{}'",
+                        open, key
+                    ),
+                    None => panic!("End of section '{}' in template was never opened", key),
+                },
+                Tag::Partial(key) if visible => {
+                    self.write_source(regex, self.partial_source(key), writer)?
+                }
+                Tag::Partial(_) => (),
+            }
+        }
+        if let Some((open, _)) = sections.last() {
+            panic!("Section '{}' in template is never closed", open);
+        }
+        write!(writer, "{}", &source[last_match..])
     }
-    write!(writer, "{}", &haystack[last_match..])
-}
 
-impl<'writer, 'template> TemplateWriter<'writer, 'template> {
     pub fn write(&self, f: &mut dyn Write) -> std::io::Result<()> {
         let template_regex: Lazy<Regex> =
             Lazy::new(|| Regex::new("\\/\\*\\{(.*?)\\}\\*\\/").unwrap());
-        replace_all_streaming(
-            &template_regex,
-            self.template,
-            |captures, writer| {
-                let key = captures.get(1).unwrap().as_str().trim();
-                self.insert_substitution(key, writer)
-            },
-            f,
-        )?;
-        Ok(())
+        self.write_source(&template_regex, self.template.source, f)
     }
 }
+
+// a section or partial tag on a line of its own removes the whole line, to not leave empty lines
+fn standalone_line(source: &str, tag: Range<usize>) -> Option<Range<usize>> {
+    let line_start = source[..tag.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[tag.end..]
+        .find('\n')
+        .map_or(source.len(), |i| tag.end + i + 1);
+    let before = &source[line_start..tag.start];
+    let after = &source[tag.end..line_end];
+    (before.trim().is_empty() && after.trim().is_empty()).then_some(line_start..line_end)
+}
@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use crate::{
+    restore_user_code, strip_user_code, write_user_code_section, GeneratedCodeWriter, UserCodeError,
+};
+
+// a file like the generated tokens, with a section for imports and one for code
+fn generated_file() -> String {
+    let mut output = Vec::new();
+    write_user_code_section(&mut output, "imports").unwrap();
+    writeln!(output, "\npub enum TokenType {{}}\n").unwrap();
+    write_user_code_section(&mut output, "code").unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_user_code_sections() {
+    let generated = generated_file();
+    assert_eq!(
+        restore_user_code(&generated, &generated).unwrap(),
+        generated
+    );
+
+    let edited = generated
+        .replace(
+            "// <lapex:keep name=\"imports\">\n",
+            "// <lapex:keep name=\"imports\">\nuse std::fmt;\n",
+        )
+        .replace(
+            "// <lapex:keep name=\"code\">\n",
+            "// <lapex:keep name=\"code\">\n\n// helpers\n\n",
+        );
+    assert_ne!(edited, generated);
+    assert_eq!(restore_user_code(&edited, &generated).unwrap(), edited);
+    assert_eq!(strip_user_code(&edited).unwrap(), generated);
+
+    let orphaned = format!(
+        "{}// <lapex:keep name=\"other\">\n// </lapex:keep>\n",
+        generated
+    );
+    assert_eq!(
+        restore_user_code(&orphaned, &generated),
+        Err(UserCodeError::Orphaned("other".to_string()))
+    );
+    let unclosed = generated.replacen("// </lapex:keep>", "", 1);
+    assert_eq!(
+        restore_user_code(&unclosed, &generated),
+        Err(UserCodeError::Unclosed("imports".to_string()))
+    );
+    let duplicate = format!("{}{}", generated, generated);
+    assert_eq!(
+        strip_user_code(&duplicate),
+        Err(UserCodeError::Duplicate("imports".to_string()))
+    );
+    let unexpected = format!("// </lapex:keep>\n{}", generated);
+    assert_eq!(
+        strip_user_code(&unexpected),
+        Err(UserCodeError::UnexpectedEnd { line: 1 })
+    );
+}
+
+#[test]
+fn test_license_header() {
+    let mut code = Vec::new();
+    let mut data = Vec::new();
+    let mut gen = GeneratedCodeWriter::new();
+    gen.add_target("code", &mut code);
+    gen.add_target("data", &mut data);
+    gen.set_header(vec![
+        String::from("SPDX-License-Identifier: MIT OR Apache-2.0"),
+        String::new(),
+        String::from("Copyright 2024 Example"),
+    ]);
+    gen.generate_code("code", |output| write!(output, "{}", generated_file()))
+        .unwrap();
+    gen.generate_data("data", |output| write!(output, "{{}}"))
+        .unwrap();
+    drop(gen);
+
+    let code = String::from_utf8(code).unwrap();
+    let body = code
+        .strip_prefix(
+            "// SPDX-License-Identifier: MIT OR Apache-2.0\n//\n// Copyright 2024 Example\n\n",
+        )
+        .unwrap();
+    assert_eq!(body, generated_file());
+    // the header is no user code, so it is generated again like the rest of the file
+    assert_eq!(restore_user_code(&code, &code).unwrap(), code);
+    assert_eq!(strip_user_code(&code).unwrap(), code);
+    // data files like the tables can not have comments
+    assert_eq!(data, b"{}");
+}
@@ -0,0 +1,114 @@
+use std::{collections::BTreeMap, io::Write, ops::Range};
+
+const SECTION_START: &str = "// <lapex:keep name=\"";
+const SECTION_START_END: &str = "\">";
+const SECTION_END: &str = "// </lapex:keep>";
+
+#[derive(Debug, PartialEq)]
+pub enum UserCodeError {
+    Unclosed(String),
+    UnexpectedEnd {
+        line: usize,
+    },
+    Duplicate(String),
+    /// The previous file has a section the regenerated file does not contain.
+    Orphaned(String),
+}
+
+impl std::fmt::Display for UserCodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UserCodeError::Unclosed(name) => {
+                write!(f, "the user code section '{}' is never closed", name)
+            }
+            UserCodeError::UnexpectedEnd { line } => {
+                write!(
+                    f,
+                    "line {} closes a user code section which is not open",
+                    line
+                )
+            }
+            UserCodeError::Duplicate(name) => {
+                write!(f, "the user code section '{}' exists more than once", name)
+            }
+            UserCodeError::Orphaned(name) => write!(
+                f,
+                "the user code section '{}' has no place in the regenerated file",
+                name
+            ),
+        }
+    }
+}
+
+/// Writes an empty section, whose contents are kept when the file is generated again.
+pub fn write_user_code_section(output: &mut dyn Write, name: &str) -> std::io::Result<()> {
+    writeln!(output, "{}{}{}", SECTION_START, name, SECTION_START_END)?;
+    writeln!(output, "{}", SECTION_END)
+}
+
+// the byte ranges of the section contents, without the marker lines
+fn find_sections(code: &str) -> Result<Vec<(&str, Range<usize>)>, UserCodeError> {
+    let mut sections: Vec<(&str, Range<usize>)> = Vec::new();
+    let mut open: Option<(&str, usize)> = None;
+    let mut offset = 0;
+    for (index, line) in code.split_inclusive('\n').enumerate() {
+        let text = line.trim();
+        let start = text
+            .strip_prefix(SECTION_START)
+            .and_then(|rest| rest.strip_suffix(SECTION_START_END));
+        if let Some(name) = start {
+            if let Some((name, _)) = open {
+                return Err(UserCodeError::Unclosed(name.to_string()));
+            }
+            if sections.iter().any(|(other, _)| *other == name) {
+                return Err(UserCodeError::Duplicate(name.to_string()));
+            }
+            open = Some((name, offset + line.len()));
+        } else if text == SECTION_END {
+            match open.take() {
+                Some((name, start)) => sections.push((name, start..offset)),
+                None => return Err(UserCodeError::UnexpectedEnd { line: index + 1 }),
+            }
+        }
+        offset += line.len();
+    }
+    match open {
+        Some((name, _)) => Err(UserCodeError::Unclosed(name.to_string())),
+        None => Ok(sections),
+    }
+}
+
+/// Empties the user code sections, which gives back the file as it was generated.
+pub fn strip_user_code(code: &str) -> Result<String, UserCodeError> {
+    let mut stripped = String::with_capacity(code.len());
+    let mut copied = 0;
+    for (_, range) in find_sections(code)? {
+        stripped.push_str(&code[copied..range.start]);
+        copied = range.end;
+    }
+    stripped.push_str(&code[copied..]);
+    Ok(stripped)
+}
+
+/// Copies the contents of the user code sections of the previously generated file into the
+/// sections of the same name in the generated file.
+pub fn restore_user_code(previous: &str, generated: &str) -> Result<String, UserCodeError> {
+    let mut kept: BTreeMap<&str, &str> = find_sections(previous)?
+        .into_iter()
+        .map(|(name, range)| (name, &previous[range]))
+        .collect();
+    let mut restored = String::with_capacity(generated.len());
+    let mut copied = 0;
+    for (name, range) in find_sections(generated)? {
+        if let Some(contents) = kept.remove(name) {
+            restored.push_str(&generated[copied..range.start]);
+            restored.push_str(contents);
+            copied = range.end;
+        }
+    }
+    restored.push_str(&generated[copied..]);
+    match kept.into_keys().next() {
+        Some(name) => Err(UserCodeError::Orphaned(name.to_string())),
+        None => Ok(restored),
+    }
+}
@@ -0,0 +1,5 @@
+    template <class E>
+    using Vector = std::vector<E, std::allocator_traits<Allocator>::rebind_alloc<E>>;
+
+    template <class E>
+    using Deque = std::deque<E, std::allocator_traits<Allocator>::rebind_alloc<E>>;
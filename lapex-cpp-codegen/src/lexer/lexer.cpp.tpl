@@ -1,8 +1,15 @@
 #include "lexer.h"
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace lexer
 {
-    Lexer::Lexer(std::istream &in) : in_chars(in), ch(-1), err(0), start_pos(0), end_pos(0), position(0) {}
+    Lexer::Lexer(std::istream &in, Context *context) : in_chars(in), context(context), ch(-1), err(0), start_pos(0), end_pos(0), pos(0), location{1, 1}, start_location{1, 1}, end_location{1, 1}, after_cr(false) {}
 
     // Branchless UTF-8: https://github.com/skeeto/branchless-utf8
     void utf8_decode(std::istream &in, uint32_t *c, int *e)
@@ -49,11 +56,32 @@ namespace lexer
     {
         return this->end_pos;
     }
+    Location Lexer::position()
+    {
+        return this->start_location;
+    }
+    LocatedSpan Lexer::span_with_location()
+    {
+        return LocatedSpan{this->start_pos, this->end_pos, this->start_location, this->end_location};
+    }
 
     TokenType Lexer::next()
+    {
+        TokenType tk_type = this->scan();
+        if (this->context == nullptr || tk_type == TokenType::TK_EOF || tk_type == TokenType::TK_ERR)
+        {
+            return tk_type;
+        }
+        return this->context->classify(tk_type, this->start_pos, this->end_pos);
+    }
+
+    /*{alphabet_tables}*/
+
+    TokenType Lexer::scan()
     {
         uint32_t state = 0;
-        this->start_pos = position;
+        this->start_pos = this->pos;
+        this->start_location = this->location;
         while (1)
         {
             if (this->ch == -1)
@@ -65,10 +93,26 @@ namespace lexer
                 return TokenType::TK_ERR;
             }
 
-            /*{alphabet_switch}*/
+            /*{alphabet_lookup}*/
+            uint32_t consumed = this->ch;
             /*{automaton_switch}*/
-            this->position += 1;
+            this->pos += 1;
+            // \n, \r and \r\n end a line
+            if (consumed == '\r' || (consumed == '\n' && !this->after_cr))
+            {
+                this->location.line += 1;
+                this->location.column = 1;
+            }
+            else if (consumed != '\n')
+            {
+                this->location.column += 1;
+            }
+            this->after_cr = consumed == '\r';
         }
         return TokenType::TK_ERR;
     }
-}
\ No newline at end of file
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
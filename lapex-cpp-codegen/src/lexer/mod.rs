@@ -7,48 +7,140 @@ use lapex_codegen::{GeneratedCodeWriter, Template};
 use lapex_input::{Spanned, TokenRule};
 use lapex_lexer::LexerCodeGen;
 
-use crate::CppLexerCodeGen;
+use crate::{substitute_namespace, write_doc_comment, CppLexerCodeGen};
+
+// the characters below this are looked up in a table with one entry per character
+const ASCII_END: u32 = 128;
 
 struct LexerCodeWriter<'lexer> {
     lexer_header_template: Template<'static>,
     lexer_impl_template: Template<'static>,
     alphabet: &'lexer [RangeInclusive<u32>],
     dfa: &'lexer Dfa<&'lexer TokenRule<'lexer>, usize>,
+    namespace: Option<&'lexer str>,
 }
 
 impl<'lexer> LexerCodeWriter<'lexer> {
     pub fn new(
         alphabet: &'lexer [RangeInclusive<u32>],
         dfa: &'lexer Dfa<&'lexer TokenRule<'lexer>, usize>,
+        namespace: Option<&'lexer str>,
     ) -> Self {
         let lexer_header_template = Template::new(include_str!("lexer.h.tpl"));
         let lexer_impl_template = Template::new(include_str!("lexer.cpp.tpl"));
         LexerCodeWriter {
             alphabet,
             dfa,
+            namespace,
             lexer_header_template,
             lexer_impl_template,
         }
     }
 
-    fn write_alphabet_switch<W: Write + ?Sized>(
+    // the ranges above the ASCII characters with their index in the alphabet, sorted by start
+    fn non_ascii_ranges(&self) -> Vec<(usize, &RangeInclusive<u32>)> {
+        let mut ranges: Vec<(usize, &RangeInclusive<u32>)> = self
+            .alphabet
+            .iter()
+            .enumerate()
+            .filter(|(_, range)| *range.end() >= ASCII_END)
+            .collect();
+        ranges.sort_by_key(|(_, range)| *range.start());
+        ranges
+    }
+
+    fn write_alphabet_tables<W: Write + ?Sized>(
+        &self,
+        output: &mut W,
+    ) -> Result<(), std::io::Error> {
+        let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(", ");
+        let mut ascii_indices = (0..ASCII_END).map(|ch| {
+            self.alphabet
+                .iter()
+                .position(|range| range.contains(&ch))
+                .map_or(-1, |index| index as i64)
+                .to_string()
+        });
+        writeln!(
+            output,
+            "// the index in the alphabet of every ASCII character, or -1 if no token contains it"
+        )?;
+        write!(
+            output,
+            "static const int32_t ascii_indices[{}] = {{{}}};",
+            ASCII_END,
+            join(&mut ascii_indices)
+        )?;
+        let ranges = self.non_ascii_ranges();
+        if ranges.is_empty() {
+            return Ok(());
+        }
+        writeln!(output)?;
+        writeln!(
+            output,
+            "// the ranges of the other characters in the alphabet, sorted by their start"
+        )?;
+        writeln!(
+            output,
+            "static const uint32_t range_starts[] = {{{}}};",
+            join(&mut ranges.iter().map(|(_, range)| range.start().to_string()))
+        )?;
+        writeln!(
+            output,
+            "static const uint32_t range_ends[] = {{{}}};",
+            join(&mut ranges.iter().map(|(_, range)| range.end().to_string()))
+        )?;
+        write!(
+            output,
+            "static const uint32_t range_indices[] = {{{}}};",
+            join(&mut ranges.iter().map(|(index, _)| index.to_string()))
+        )
+    }
+
+    // ASCII characters are looked up directly, the others with a binary search over the ranges
+    fn write_alphabet_lookup<W: Write + ?Sized>(
         &self,
         output: &mut W,
     ) -> Result<(), std::io::Error> {
         writeln!(output, "uint32_t i;")?;
-        writeln!(output, "switch (ch)")?;
+        writeln!(output, "if (ch < {})", ASCII_END)?;
         writeln!(output, "{{")?;
-        for (i, range) in self.alphabet.iter().enumerate() {
-            if range.start() == range.end() {
-                writeln!(output, "case {}:", range.start())?;
-            } else {
-                writeln!(output, "case {} ... {}:", range.start(), range.end())?;
-            }
-            writeln!(output, "i = {};", i)?;
-            writeln!(output, "break;")?;
+        writeln!(output, "if (ascii_indices[ch] < 0)")?;
+        writeln!(output, "{{")?;
+        writeln!(output, "return TokenType::TK_ERR;")?;
+        writeln!(output, "}}")?;
+        writeln!(output, "i = ascii_indices[ch];")?;
+        writeln!(output, "}}")?;
+        writeln!(output, "else")?;
+        writeln!(output, "{{")?;
+        let range_count = self.non_ascii_ranges().len();
+        if range_count == 0 {
+            writeln!(output, "return TokenType::TK_ERR;")?;
+            return writeln!(output, "}}");
         }
-        writeln!(output, "default:")?;
+        writeln!(output, "size_t low = 0;")?;
+        writeln!(output, "size_t high = {};", range_count)?;
+        writeln!(output, "while (low < high)")?;
+        writeln!(output, "{{")?;
+        writeln!(output, "size_t middle = low + (high - low) / 2;")?;
+        writeln!(output, "if (range_ends[middle] < ch)")?;
+        writeln!(output, "{{")?;
+        writeln!(output, "low = middle + 1;")?;
+        writeln!(output, "}}")?;
+        writeln!(output, "else")?;
+        writeln!(output, "{{")?;
+        writeln!(output, "high = middle;")?;
+        writeln!(output, "}}")?;
+        writeln!(output, "}}")?;
+        writeln!(
+            output,
+            "if (low == {} || ch < range_starts[low])",
+            range_count
+        )?;
+        writeln!(output, "{{")?;
         writeln!(output, "return TokenType::TK_ERR;")?;
+        writeln!(output, "}}")?;
+        writeln!(output, "i = range_indices[low];")?;
         writeln!(output, "}}")
     }
 
@@ -72,12 +164,24 @@ impl<'lexer> LexerCodeWriter<'lexer> {
                 }
             }
             writeln!(output, "default:")?;
-            if let AutomatonState::Accepting(accept) = node {
-                writeln!(output, "// ACCEPT: {:?}", accept)?;
-                writeln!(output, "this->end_pos = this->position;")?;
-                writeln!(output, "return TokenType::TK_{};", accept.name)?;
-            } else {
-                writeln!(output, "return TokenType::TK_ERR;")?;
+            match node {
+                AutomatonState::Accepting(accept) if accept.skip => {
+                    // the next token is scanned from the character which ended this one
+                    writeln!(output, "// SKIP: {:?}", accept)?;
+                    writeln!(output, "state = 0;")?;
+                    writeln!(output, "this->start_pos = this->pos;")?;
+                    writeln!(output, "this->start_location = this->location;")?;
+                    writeln!(output, "continue;")?;
+                }
+                AutomatonState::Accepting(accept) => {
+                    writeln!(output, "// ACCEPT: {:?}", accept)?;
+                    writeln!(output, "this->end_pos = this->pos;")?;
+                    writeln!(output, "this->end_location = this->location;")?;
+                    writeln!(output, "return TokenType::TK_{};", accept.name)?;
+                }
+                AutomatonState::Intermediate(_) => {
+                    writeln!(output, "return TokenType::TK_ERR;")?;
+                }
             }
             writeln!(output, "}}")?;
             writeln!(output, "break;")?;
@@ -89,13 +193,17 @@ impl<'lexer> LexerCodeWriter<'lexer> {
     }
 
     fn write_header(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
-        self.lexer_header_template.writer().write(output)
+        let mut writer = self.lexer_header_template.writer();
+        substitute_namespace(&mut writer, self.namespace, "lexer.h");
+        writer.write(output)
     }
 
     fn write_impl(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
         let mut writer = self.lexer_impl_template.writer();
-        writer.substitute("alphabet_switch", |w| self.write_alphabet_switch(w));
+        writer.substitute("alphabet_tables", |w| self.write_alphabet_tables(w));
+        writer.substitute("alphabet_lookup", |w| self.write_alphabet_lookup(w));
         writer.substitute("automaton_switch", |w| self.write_state_machine_switch(w));
+        substitute_namespace(&mut writer, self.namespace, "lexer.cpp");
         writer.write(output)
     }
 }
@@ -104,14 +212,16 @@ struct TokensCodeWriter<'lexer> {
     tokens_header_template: Template<'static>,
     tokens_impl_template: Template<'static>,
     rules: &'lexer [Spanned<TokenRule<'lexer>>],
+    namespace: Option<&'lexer str>,
 }
 
 impl<'lexer> TokensCodeWriter<'lexer> {
-    fn new(rules: &'lexer [Spanned<TokenRule>]) -> Self {
+    fn new(rules: &'lexer [Spanned<TokenRule>], namespace: Option<&'lexer str>) -> Self {
         let tokens_header_template = Template::new(include_str!("tokens.h.tpl"));
         let tokens_impl_template = Template::new(include_str!("tokens.cpp.tpl"));
         TokensCodeWriter {
             rules,
+            namespace,
             tokens_header_template,
             tokens_impl_template,
         }
@@ -119,6 +229,7 @@ impl<'lexer> TokensCodeWriter<'lexer> {
 
     fn write_token_enum_variants(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
         for rule in self.rules {
+            write_doc_comment(output, rule.inner.doc.as_deref())?;
             writeln!(output, "TK_{},", rule.inner.name)?;
         }
         Ok(())
@@ -147,12 +258,14 @@ impl<'lexer> TokensCodeWriter<'lexer> {
         writer.substitute("get_token_name_function", |w| {
             self.write_get_token_name_function(w)
         });
+        substitute_namespace(&mut writer, self.namespace, "tokens.cpp");
         writer.write(output)
     }
 
     fn write_tokens_header(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
         let mut writer = self.tokens_header_template.writer();
         writer.substitute("token_enum_variants", |w| self.write_token_enum_variants(w));
+        substitute_namespace(&mut writer, self.namespace, "tokens.h");
         writer.write(output)
     }
 }
@@ -165,7 +278,8 @@ impl LexerCodeGen for CppLexerCodeGen {
         dfa: &Dfa<&TokenRule, usize>,
         gen: &mut GeneratedCodeWriter,
     ) {
-        let code_writer = LexerCodeWriter::new(alphabet, dfa);
+        let outer_namespace = self.outer_namespace();
+        let code_writer = LexerCodeWriter::new(alphabet, dfa, outer_namespace.as_deref());
         gen.generate_code("lexer.h", |output| code_writer.write_header(output))
             .unwrap();
         gen.generate_code("lexer.cpp", |output| code_writer.write_impl(output))
@@ -173,7 +287,8 @@ impl LexerCodeGen for CppLexerCodeGen {
     }
 
     fn generate_tokens(&self, rules: &[Spanned<TokenRule>], gen: &mut GeneratedCodeWriter) {
-        let code_writer = TokensCodeWriter::new(rules);
+        let outer_namespace = self.outer_namespace();
+        let code_writer = TokensCodeWriter::new(rules, outer_namespace.as_deref());
         gen.generate_code("tokens.h", |output| code_writer.write_tokens_header(output))
             .unwrap();
         gen.generate_code("tokens.cpp", |output| code_writer.write_tokens_impl(output))
@@ -1,8 +1,19 @@
 #include "tokens.h"
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace lexer
 {
     const char *get_token_name(TokenType tk_type) {
         /*{get_token_name_function}*/
     }
-}
\ No newline at end of file
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -1,7 +1,15 @@
-#pragma once
+#ifndef /*{include_guard}*/
+#define /*{include_guard}*/
 
 #include <cstdint>
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace lexer
 {
     enum class TokenType : uint32_t
@@ -12,4 +20,10 @@ namespace lexer
     };
     
     const char *get_token_name(TokenType tk_type);
-}
\ No newline at end of file
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
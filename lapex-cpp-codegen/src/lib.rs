@@ -1,8 +1,32 @@
-pub struct CppLexerCodeGen {}
+use lapex_codegen::TemplateWriter;
+use lapex_parser::grammar::Grammar;
+
+pub struct CppLexerCodeGen {
+    namespace: Option<String>,
+    symbol_prefix: Option<String>,
+}
 
 impl CppLexerCodeGen {
     pub fn new() -> Self {
-        CppLexerCodeGen {}
+        CppLexerCodeGen {
+            namespace: None,
+            symbol_prefix: None,
+        }
+    }
+
+    /// Nests the `lexer` namespace into a namespace of this name.
+    pub fn set_symbol_prefix(&mut self, symbol_prefix: Option<&str>) {
+        self.symbol_prefix = symbol_prefix.map(str::to_string);
+    }
+
+    /// Nests the generated namespaces into this one, written like `mycompany::mylang`, outside
+    /// of the one named after the prefix.
+    pub fn set_namespace(&mut self, namespace: Option<&str>) {
+        self.namespace = namespace.map(str::to_string);
+    }
+
+    fn outer_namespace(&self) -> Option<String> {
+        outer_namespace(self.namespace.as_deref(), self.symbol_prefix.as_deref())
     }
 }
 
@@ -12,11 +36,49 @@ impl Default for CppLexerCodeGen {
     }
 }
 
-pub struct CppLLParserCodeGen {}
+pub struct CppLLParserCodeGen {
+    polymorphic_allocator: bool,
+    namespace: Option<String>,
+    symbol_prefix: Option<String>,
+    external_tokens: Option<String>,
+}
 
 impl CppLLParserCodeGen {
     pub fn new() -> Self {
-        CppLLParserCodeGen {}
+        CppLLParserCodeGen {
+            polymorphic_allocator: false,
+            namespace: None,
+            symbol_prefix: None,
+            external_tokens: None,
+        }
+    }
+
+    /// Makes the containers of the parser use a `std::pmr::polymorphic_allocator`, so the memory
+    /// resource they allocate from can be passed to the parser.
+    pub fn set_polymorphic_allocator(&mut self, polymorphic_allocator: bool) {
+        self.polymorphic_allocator = polymorphic_allocator;
+    }
+
+    /// Nests the `parser` namespace into a namespace of this name, which has to match the one
+    /// of the lexer.
+    pub fn set_symbol_prefix(&mut self, symbol_prefix: Option<&str>) {
+        self.symbol_prefix = symbol_prefix.map(str::to_string);
+    }
+
+    /// Nests the generated namespaces into this one, written like `mycompany::mylang`, outside
+    /// of the one named after the prefix.
+    pub fn set_namespace(&mut self, namespace: Option<&str>) {
+        self.namespace = namespace.map(str::to_string);
+    }
+
+    /// Takes `TokenType` and `get_token_name` from this namespace instead of the generated
+    /// tokens, like `mylexer`. Its header is included in the imports section of `visitor.h`.
+    pub fn set_external_tokens(&mut self, external_tokens: Option<&str>) {
+        self.external_tokens = external_tokens.map(str::to_string);
+    }
+
+    fn outer_namespace(&self) -> Option<String> {
+        outer_namespace(self.namespace.as_deref(), self.symbol_prefix.as_deref())
     }
 }
 
@@ -26,11 +88,65 @@ impl Default for CppLLParserCodeGen {
     }
 }
 
-pub struct CppLRParserCodeGen {}
+pub struct CppLRParserCodeGen {
+    polymorphic_allocator: bool,
+    metrics: bool,
+    namespace: Option<String>,
+    symbol_prefix: Option<String>,
+    external_tokens: Option<String>,
+    facade: bool,
+}
 
 impl CppLRParserCodeGen {
     pub fn new() -> Self {
-        CppLRParserCodeGen {}
+        CppLRParserCodeGen {
+            polymorphic_allocator: false,
+            metrics: false,
+            namespace: None,
+            symbol_prefix: None,
+            external_tokens: None,
+            facade: false,
+        }
+    }
+
+    /// Makes the containers of the parser use a `std::pmr::polymorphic_allocator`, so the memory
+    /// resource they allocate from can be passed to the parser.
+    pub fn set_polymorphic_allocator(&mut self, polymorphic_allocator: bool) {
+        self.polymorphic_allocator = polymorphic_allocator;
+    }
+
+    /// Counts what the parser does during `parse()` and reports it through the `Metrics` class
+    /// once it ends.
+    pub fn set_metrics(&mut self, metrics: bool) {
+        self.metrics = metrics;
+    }
+
+    /// Nests the `parser` namespace into a namespace of this name, which has to match the one
+    /// of the lexer.
+    pub fn set_symbol_prefix(&mut self, symbol_prefix: Option<&str>) {
+        self.symbol_prefix = symbol_prefix.map(str::to_string);
+    }
+
+    /// Nests the generated namespaces into this one, written like `mycompany::mylang`, outside
+    /// of the one named after the prefix.
+    pub fn set_namespace(&mut self, namespace: Option<&str>) {
+        self.namespace = namespace.map(str::to_string);
+    }
+
+    /// Takes `TokenType` and `get_token_name` from this namespace instead of the generated
+    /// tokens, like `mylexer`. Its header is included in the imports section of `visitor.h`.
+    pub fn set_external_tokens(&mut self, external_tokens: Option<&str>) {
+        self.external_tokens = external_tokens.map(str::to_string);
+    }
+
+    /// Declares `parse_stream` in `parser.h`, which lexes and parses a stream in one call with
+    /// the generated lexer.
+    pub fn set_facade(&mut self, facade: bool) {
+        self.facade = facade;
+    }
+
+    fn outer_namespace(&self) -> Option<String> {
+        outer_namespace(self.namespace.as_deref(), self.symbol_prefix.as_deref())
     }
 }
 
@@ -54,7 +170,93 @@ impl Default for CppGLRParserCodeGen {
     }
 }
 
+// the namespace the `lexer` and `parser` namespaces are nested in, if any
+fn outer_namespace(namespace: Option<&str>, symbol_prefix: Option<&str>) -> Option<String> {
+    let names: Vec<&str> = namespace.into_iter().chain(symbol_prefix).collect();
+    (!names.is_empty()).then(|| names.join("::"))
+}
+
+// every template nests its namespace into the outer one, if there is one, and the headers are
+// guarded by a macro named after it and the file
+fn substitute_namespace<'writer>(
+    writer: &mut TemplateWriter<'writer, '_>,
+    namespace: Option<&'writer str>,
+    file_name: &str,
+) {
+    writer.condition("namespace", namespace.is_some());
+    writer.substitute("namespace", move |w| {
+        write!(w, "{}", namespace.unwrap_or_default())
+    });
+    let include_guard = format!(
+        "{}_{}",
+        namespace.unwrap_or("lapex").replace("::", "_"),
+        file_name.replace('.', "_")
+    )
+    .to_ascii_uppercase();
+    writer.substitute("include_guard", move |w| write!(w, "{}", include_guard));
+}
+
+// with tokens declared `extern`, the parsers keep naming the token enum `lexer::TokenType`, which
+// `visitor.h` makes an alias of the namespace they come from, and checks that the enum gives them
+// the ids declared in the grammar
+fn substitute_external_tokens<'writer>(
+    writer: &mut TemplateWriter<'writer, '_>,
+    grammar: &'writer Grammar,
+    external_tokens: Option<&'writer str>,
+) {
+    writer.condition("external_tokens", external_tokens.is_some());
+    // an alias can not have the name of the namespace it stands for
+    writer.condition(
+        "lexer_alias",
+        external_tokens.is_some_and(|namespace| namespace.trim_start_matches("::") != "lexer"),
+    );
+    writer.substitute("external_tokens", move |w| {
+        write!(w, "{}", external_tokens.unwrap_or_default())
+    });
+    writer.substitute("external_token_checks", move |w| {
+        for (terminal, name) in grammar.terminals_with_names() {
+            if let Some(id) = grammar.get_external_token_id(&terminal) {
+                writeln!(
+                    w,
+                    "static_assert(static_cast<uint32_t>(lexer::TokenType::TK_{}) == {}, \"the token {} has to have the id {}\");",
+                    name, id, name, id
+                )?;
+            }
+        }
+        Ok(())
+    });
+}
+
+// the `///` comment of a symbol, kept as a Doxygen comment in front of its declaration
+fn write_doc_comment(output: &mut dyn std::io::Write, doc: Option<&str>) -> std::io::Result<()> {
+    for line in doc.into_iter().flat_map(str::lines) {
+        if line.is_empty() {
+            writeln!(output, "///")?;
+        } else {
+            writeln!(output, "/// {}", line)?;
+        }
+    }
+    Ok(())
+}
+
+fn convert_snake_to_upper_camel(name: &str) -> String {
+    name.split('_')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let (head, tail) = s.split_at(1);
+            format!("{}{}", head.to_ascii_uppercase(), tail.to_ascii_lowercase())
+        })
+        .collect::<Vec<String>>()
+        .join("")
+}
+
 mod glr_parser;
 mod lexer;
 mod ll_parser;
 mod lr_parser;
+mod scaffold;
+
+pub use scaffold::write_visitor_scaffold;
+
+#[cfg(test)]
+mod tests;
@@ -1,10 +1,16 @@
+use std::collections::BTreeMap;
 use std::io::{Error, Write};
 
 use lapex_codegen::{GeneratedCodeWriter, Template};
 use lapex_parser::grammar::{Grammar, Symbol};
 use lapex_parser::ll_parser::{self, LLParserTable};
 
-use crate::CppLLParserCodeGen;
+use crate::{
+    substitute_external_tokens, substitute_namespace, write_doc_comment, CppLLParserCodeGen,
+};
+
+// a lookahead of the parser table with the production it selects
+type TableEntry<'table> = (&'table [Symbol], &'table Vec<Symbol>);
 
 struct CodeWriter<'parser> {
     grammar: &'parser Grammar<'parser>,
@@ -13,15 +19,22 @@ struct CodeWriter<'parser> {
     parser_impl_header_template: Template<'static>,
     parser_impl_template: Template<'static>,
     visitor_header_template: Template<'static>,
+    polymorphic_allocator: bool,
+    namespace: Option<&'parser str>,
+    external_tokens: Option<&'parser str>,
 }
 
 impl<'parser> CodeWriter<'parser> {
     pub fn new(
         grammar: &'parser Grammar,
         parser_table: &'parser LLParserTable,
+        polymorphic_allocator: bool,
+        namespace: Option<&'parser str>,
+        external_tokens: Option<&'parser str>,
     ) -> CodeWriter<'parser> {
-        let parser_header_template = Template::new(include_str!("parser.h.tpl"));
-        let parser_impl_header_template = Template::new(include_str!("parser_impl.h.tpl"));
+        let parser_header_template = Template::new(include_str!("../parser.h.tpl"));
+        let parser_impl_header_template = Template::new(include_str!("parser_impl.h.tpl"))
+            .with_partial("containers", include_str!("../containers.h.tpl"));
         let parser_impl_template = Template::new(include_str!("parser.cpp.tpl"));
         let visitor_header_template = Template::new(include_str!("visitor.h.tpl"));
         CodeWriter {
@@ -31,12 +44,22 @@ impl<'parser> CodeWriter<'parser> {
             parser_impl_header_template,
             parser_impl_template,
             visitor_header_template,
+            polymorphic_allocator,
+            namespace,
+            external_tokens,
         }
     }
 
     fn write_visitor_methods(&self, output: &mut dyn Write) -> Result<(), Error> {
         for non_terminal in self.grammar.non_terminals() {
             if let Some(name) = self.grammar.get_production_name(&non_terminal) {
+                let doc = self
+                    .grammar
+                    .rules()
+                    .iter()
+                    .filter(|rule| rule.lhs() == Some(non_terminal))
+                    .find_map(|rule| self.grammar.get_rule_doc(rule));
+                write_doc_comment(output, doc)?;
                 writeln!(output, "virtual void enter_{}() = 0;", name)?;
                 writeln!(output, "virtual void exit_{}() = 0;", name)?;
             }
@@ -63,6 +86,8 @@ impl<'parser> CodeWriter<'parser> {
                 writeln!(output, "break;")?;
             }
         }
+        writeln!(output, "default:")?;
+        writeln!(output, "break;")?;
         writeln!(output, "}}")
     }
 
@@ -71,26 +96,45 @@ impl<'parser> CodeWriter<'parser> {
         non_terminal: Symbol,
         output: &mut dyn Write,
     ) -> Result<(), Error> {
-        if let Some(name) = self.grammar.get_production_name(&non_terminal) {
-            write!(output, "NT_{}", name.to_uppercase())?;
-        } else {
-            if let Symbol::NonTerminal(non_terminal_index) = non_terminal {
-                write!(output, "NT_ANON{}", non_terminal_index)?;
-            } else {
-                unreachable!()
-            }
-        }
-        Ok(())
+        let name = self
+            .grammar
+            .get_non_terminal_name(&non_terminal)
+            .expect("every non-terminal has a name");
+        write!(output, "NT_{}", name.to_uppercase())
     }
 
     fn write_non_terminal_enum_variants(&self, output: &mut dyn Write) -> Result<(), Error> {
         for non_terminal in self.grammar.non_terminals() {
+            let non_terminal_index = if let Symbol::NonTerminal(i) = non_terminal {
+                i
+            } else {
+                unreachable!()
+            };
             self.write_non_terminal_enum_name(non_terminal, output)?;
-            writeln!(output, ",")?;
+            writeln!(output, " = {},", non_terminal_index)?;
         }
         Ok(())
     }
 
+    fn write_token_enum_name(&self, terminal: Symbol, output: &mut dyn Write) -> Result<(), Error> {
+        match terminal {
+            Symbol::Terminal(terminal_index) => write!(
+                output,
+                "lexer::TokenType::TK_{}",
+                self.grammar.get_token_name(terminal_index)
+            ),
+            Symbol::End => write!(output, "lexer::TokenType::TK_EOF"),
+            _ => unreachable!("lookaheads only consist of tokens"),
+        }
+    }
+
+    fn get_token_display_name(&self, terminal: Symbol) -> &str {
+        match terminal {
+            Symbol::Terminal(terminal_index) => self.grammar.get_token_name(terminal_index),
+            _ => "<EOF>",
+        }
+    }
+
     fn write_push_symbol_sequence(
         &self,
         symbols: &[Symbol],
@@ -150,6 +194,47 @@ impl<'parser> CodeWriter<'parser> {
         writeln!(output, "throw std::runtime_error(\"{}\");", message)
     }
 
+    // switches over the lookahead token at the given depth, so that nested switches select the
+    // production by as many tokens as needed
+    fn write_lookahead_switch(
+        &self,
+        non_terminal: Symbol,
+        entries: &[TableEntry],
+        depth: usize,
+        output: &mut dyn Write,
+    ) -> Result<(), Error> {
+        let mut entries_by_token: BTreeMap<Symbol, Vec<TableEntry>> = BTreeMap::new();
+        for entry in entries {
+            entries_by_token
+                .entry(entry.0[depth])
+                .or_default()
+                .push(*entry);
+        }
+        writeln!(output, "switch (lookahead[{}]) {{", depth)?;
+        for (terminal, entries) in &entries_by_token {
+            write!(output, "case ")?;
+            self.write_token_enum_name(*terminal, output)?;
+            writeln!(output, ": {{")?;
+            match entries.as_slice() {
+                [(lookahead, symbols)] if lookahead.len() == depth + 1 => {
+                    self.write_push_symbol_sequence(symbols, output)?
+                }
+                _ => self.write_lookahead_switch(non_terminal, entries, depth + 1, output)?,
+            }
+            writeln!(output, "break;")?;
+            writeln!(output, "}}")?;
+        }
+        writeln!(output, "default:")?;
+        self.write_parser_table_error(
+            self.grammar.get_production_name(&non_terminal),
+            entries_by_token
+                .keys()
+                .map(|terminal| self.get_token_display_name(*terminal)),
+            output,
+        )?;
+        writeln!(output, "}}")
+    }
+
     fn write_table_switch(&self, output: &mut dyn Write) -> Result<(), Error> {
         writeln!(output, "switch(non_terminal.identifier) {{")?;
         for non_terminal in self.grammar.non_terminals() {
@@ -159,30 +244,8 @@ impl<'parser> CodeWriter<'parser> {
                 unreachable!()
             };
             writeln!(output, "case {}: {{", non_terminal_index)?;
-            writeln!(output, "switch (lookahead) {{")?;
-            for (terminal, token_name) in self.grammar.terminals_with_names() {
-                let entry = self.parser_table.get_production(non_terminal, &terminal);
-                if let Some(symbols) = entry {
-                    writeln!(output, "case lexer::TokenType::TK_{}: {{", token_name)?;
-                    self.write_push_symbol_sequence(symbols, output)?;
-                    writeln!(output, "break;")?;
-                    writeln!(output, "}}")?;
-                }
-            }
-            writeln!(output, "default:")?;
-            self.write_parser_table_error(
-                self.grammar.get_production_name(&non_terminal),
-                self.grammar
-                    .terminals_with_names()
-                    .filter(|(symbol, _)| {
-                        self.parser_table
-                            .get_production(non_terminal, symbol)
-                            .is_some()
-                    })
-                    .map(|(_, name)| name),
-                output,
-            )?;
-            writeln!(output, "}}")?;
+            let entries: Vec<TableEntry> = self.parser_table.productions_of(non_terminal).collect();
+            self.write_lookahead_switch(non_terminal, &entries, 0, output)?;
             writeln!(output, "break;")?;
             writeln!(output, "}}")?;
         }
@@ -192,11 +255,20 @@ impl<'parser> CodeWriter<'parser> {
     fn write_visitor_header(&self, output: &mut dyn Write) -> Result<(), Error> {
         let mut writer = self.visitor_header_template.writer();
         writer.substitute("visitor_methods", |w| self.write_visitor_methods(w));
+        substitute_namespace(&mut writer, self.namespace, "visitor.h");
+        substitute_external_tokens(&mut writer, self.grammar, self.external_tokens);
         writer.write(output)
     }
 
     fn write_header(&self, output: &mut dyn Write) -> Result<(), Error> {
-        self.parser_header_template.writer().write(output)
+        let mut writer = self.parser_header_template.writer();
+        writer.condition("polymorphic_allocator", self.polymorphic_allocator);
+        // only the LR parser counts metrics and has a facade
+        writer.condition("metrics", false);
+        writer.condition("facade", false);
+        substitute_namespace(&mut writer, self.namespace, "parser.h");
+        writer.condition("external_tokens", self.external_tokens.is_some());
+        writer.write(output)
     }
 
     fn write_impl_header(&self, output: &mut dyn Write) -> Result<(), Error> {
@@ -221,6 +293,10 @@ impl<'parser> CodeWriter<'parser> {
         writer.substitute("non_terminal_enum_variants", |w| {
             self.write_non_terminal_enum_variants(w)
         });
+        writer.substitute("lookahead_length", |w| {
+            write!(w, "{}", self.parser_table.lookahead_length())
+        });
+        substitute_namespace(&mut writer, self.namespace, "parser_impl.h");
 
         writer.write(output)
     }
@@ -228,6 +304,7 @@ impl<'parser> CodeWriter<'parser> {
     fn write_impl(&self, output: &mut dyn Write) -> Result<(), Error> {
         let mut writer = self.parser_impl_template.writer();
         writer.substitute("parser_table_switch", |w| self.write_table_switch(w));
+        substitute_namespace(&mut writer, self.namespace, "parser.cpp");
         writer.write(output)
     }
 }
@@ -239,7 +316,14 @@ impl ll_parser::LLParserCodeGen for CppLLParserCodeGen {
         parser_table: &LLParserTable,
         gen: &mut GeneratedCodeWriter,
     ) {
-        let code_writer = CodeWriter::new(grammar, parser_table);
+        let outer_namespace = self.outer_namespace();
+        let code_writer = CodeWriter::new(
+            grammar,
+            parser_table,
+            self.polymorphic_allocator,
+            outer_namespace.as_deref(),
+            self.external_tokens.as_deref(),
+        );
         gen.generate_code("parser.h", |output| code_writer.write_header(output))
             .unwrap();
         gen.generate_code("parser.cpp", |output| code_writer.write_impl(output))
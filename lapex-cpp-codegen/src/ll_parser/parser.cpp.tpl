@@ -2,8 +2,15 @@
 
 #include <sstream>
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace parser {
-    void push_production_from_table(Symbol non_terminal, lexer::TokenType lookahead, std::stack<Symbol>& parse_stack) {
+    void push_production_from_table(Symbol non_terminal, const lexer::TokenType* lookahead, ParseStack& parse_stack) {
         /*{parser_table_switch}*/
     }
 
@@ -13,4 +20,8 @@ namespace parser {
         os << "Unexpected token '" << lexer::get_token_name(got) << "', expected token '" << lexer::get_token_name(expected) << "'";
         throw std::runtime_error(os.str());
     }
-}
\ No newline at end of file
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
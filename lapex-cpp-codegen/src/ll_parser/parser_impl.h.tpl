@@ -1,13 +1,24 @@
-#pragma once
+#ifndef /*{include_guard}*/
+#define /*{include_guard}*/
 
 #include "parser.h"
+#include <deque>
 #include <stack>
-#include <queue>
+#include <vector>
 
 #include <iostream>
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace parser
 {
+    /*{>containers}*/
+
     enum class SymbolKind : uint8_t
     {
         Terminal,
@@ -20,8 +31,13 @@ namespace parser
         SymbolKind kind;
         uint32_t identifier;
     };
+
+    using ParseStack = std::stack<Symbol, Deque<Symbol>>;
     
-    void push_production_from_table(Symbol non_terminal, lexer::TokenType lookahead, std::stack<Symbol> &parse_stack);
+    // the number of tokens the table looks at to choose a production
+    constexpr size_t LOOKAHEAD_LENGTH = /*{lookahead_length}*/;
+
+    void push_production_from_table(Symbol non_terminal, const lexer::TokenType *lookahead, ParseStack &parse_stack);
 
     void throw_unexpected_token_error(lexer::TokenType expected, lexer::TokenType got);
     
@@ -43,22 +59,34 @@ namespace parser
     }
 
     template <class T>
-    Parser<T>::Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor) : token_function(token_function), visitor(visitor) {}
+    Parser<T>::Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor, Allocator allocator) : token_function(token_function), visitor(visitor), allocator(allocator) {}
 
     template <class T>
     void Parser<T>::parse()
     {
-        std::queue<std::pair<lexer::TokenType, T>> lookahead;
-        lookahead.push(this->token_function());
+        Deque<Token<T>> lookahead(this->allocator);
+        lexer::TokenType lookahead_tokens[LOOKAHEAD_LENGTH];
 
-        std::stack<Symbol> parse_stack;
+        ParseStack parse_stack(this->allocator);
         Symbol end{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_EOF)};
         parse_stack.push(end);
-        Symbol entry{SymbolKind::NonTerminal, static_cast<uint32_t>({grammar_entry_non_terminal})};
+        Symbol entry{SymbolKind::NonTerminal, static_cast<uint32_t>(/*{grammar_entry_non_terminal}*/)};
         parse_stack.push(entry);
 
         while (parse_stack.size() > 0)
         {
+            // keep the buffer filled, repeating the end of input instead of reading past it
+            while (lookahead.size() < LOOKAHEAD_LENGTH)
+            {
+                if (!lookahead.empty() && lookahead.back().first == lexer::TokenType::TK_EOF)
+                {
+                    lookahead.push_back(lookahead.back());
+                }
+                else
+                {
+                    lookahead.push_back(this->token_function());
+                }
+            }
             Symbol current = parse_stack.top();
             parse_stack.pop();
             auto lookahead_token_and_data = lookahead.front();
@@ -71,7 +99,11 @@ namespace parser
             {
                 Symbol nt_exit_symbol{SymbolKind::ExitNonTerminal, current.identifier};
                 parse_stack.push(nt_exit_symbol);
-                push_production_from_table(current, lookahead_tk, parse_stack);
+                for (size_t i = 0; i < LOOKAHEAD_LENGTH; i++)
+                {
+                    lookahead_tokens[i] = lookahead[i].first;
+                }
+                push_production_from_table(current, lookahead_tokens, parse_stack);
                 enter_visitor(this->visitor, static_cast<NonTerminalType>(current.identifier));
             }
             else
@@ -81,9 +113,14 @@ namespace parser
                     throw_unexpected_token_error(static_cast<lexer::TokenType>(current.identifier), lookahead_tk);
                 }
                 this->visitor.token(lookahead_tk, lookahead_token_and_data.second);
-                lookahead.pop();
-                lookahead.push(this->token_function());
+                lookahead.pop_front();
             }
         }
     }
-}
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
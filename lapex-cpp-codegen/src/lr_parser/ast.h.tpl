@@ -0,0 +1,100 @@
+#ifndef /*{include_guard}*/
+#define /*{include_guard}*/
+
+#include "visitor.h"
+#include <memory>
+#include <utility>
+#include <variant>
+#include <vector>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
+namespace parser
+{
+    namespace ast
+    {
+        template <class T>
+        struct Terminal
+        {
+            lexer::TokenType type;
+            T data;
+        };
+
+        /*{node_declarations}*/
+
+        template <class T>
+        using Node = std::variant<Terminal<T>/*{node_variant_types}*/>;
+
+        /*{node_definitions}*/
+
+        // Builds a tree while parsing. Anonymous non-terminals like repetitions have no node type,
+        // their children are added to the node of the enclosing production instead.
+        template <class T>
+        class TreeBuilder : public Visitor<T>
+        {
+        private:
+            // the nodes produced by each symbol on the parse stack
+            std::vector<std::vector<Node<T>>> stack;
+
+            std::vector<Node<T>> pop_children(size_t symbols)
+            {
+                std::vector<Node<T>> children;
+                auto first = stack.end() - symbols;
+                for (auto it = first; it != stack.end(); ++it)
+                {
+                    for (auto &node : *it)
+                    {
+                        children.push_back(std::move(node));
+                    }
+                }
+                stack.erase(first, stack.end());
+                return children;
+            }
+
+            template <class N>
+            void reduce_named(size_t symbols, uint32_t alternative)
+            {
+                auto node = std::make_unique<N>();
+                node->alternative = alternative;
+                node->children = pop_children(symbols);
+                std::vector<Node<T>> nodes;
+                nodes.push_back(std::move(node));
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_anonymous(size_t symbols)
+            {
+                stack.push_back(pop_children(symbols));
+            }
+
+        public:
+            void shift(lexer::TokenType tk_type, T data) override
+            {
+                std::vector<Node<T>> nodes;
+                nodes.push_back(Terminal<T>{tk_type, std::move(data)});
+                stack.push_back(std::move(nodes));
+            }
+
+            /*{builder_methods}*/
+
+            // Returns the node of the entry production once the input has been parsed.
+            Node<T> take_root()
+            {
+                Node<T> root = std::move(stack.back().front());
+                stack.clear();
+                return root;
+            }
+        };
+    }
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,100 @@
+use std::io::{Error, Write};
+
+use lapex_parser::grammar::Symbol;
+
+use super::CodeWriter;
+use crate::{convert_snake_to_upper_camel, substitute_namespace};
+
+impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
+    fn named_non_terminals(&self) -> impl Iterator<Item = (Symbol, String)> + '_ {
+        self.rules_by_non_terminal
+            .keys()
+            .filter_map(|non_terminal| {
+                self.grammar.get_production_name(non_terminal).map(|name| {
+                    (
+                        *non_terminal,
+                        format!("{}Node", convert_snake_to_upper_camel(name)),
+                    )
+                })
+            })
+    }
+
+    fn write_node_declarations(&self, output: &mut dyn Write) -> Result<(), Error> {
+        for (_, node_name) in self.named_non_terminals() {
+            writeln!(output, "template <class T>")?;
+            writeln!(output, "struct {};", node_name)?;
+        }
+        Ok(())
+    }
+
+    fn write_node_variant_types(&self, output: &mut dyn Write) -> Result<(), Error> {
+        for (_, node_name) in self.named_non_terminals() {
+            write!(output, ", std::unique_ptr<{}<T>>", node_name)?;
+        }
+        Ok(())
+    }
+
+    fn write_node_definitions(&self, output: &mut dyn Write) -> Result<(), Error> {
+        for (non_terminal, node_name) in self.named_non_terminals() {
+            for rule in &self.rules_by_non_terminal[&non_terminal] {
+                writeln!(output, "// {}", rule.display(self.grammar))?;
+            }
+            writeln!(output, "template <class T>")?;
+            writeln!(output, "struct {}", node_name)?;
+            writeln!(output, "{{")?;
+            writeln!(
+                output,
+                "// the rule that was reduced, numbered like the reduce methods of the visitor"
+            )?;
+            writeln!(output, "uint32_t alternative;")?;
+            writeln!(output, "std::vector<Node<T>> children;")?;
+            writeln!(output, "}};")?;
+        }
+        Ok(())
+    }
+
+    fn write_builder_methods(&self, output: &mut dyn Write) -> Result<(), Error> {
+        for (non_terminal, rules) in &self.rules_by_non_terminal {
+            let node_name = self
+                .grammar
+                .get_production_name(non_terminal)
+                .map(|name| format!("{}Node", convert_snake_to_upper_camel(name)));
+            for (i, rule) in rules.iter().enumerate() {
+                let symbols = rule
+                    .rhs()
+                    .iter()
+                    .filter(|s| !matches!(s, Symbol::Epsilon))
+                    .count();
+                writeln!(
+                    output,
+                    "void {}() override",
+                    self.get_reduce_method_name(rule)
+                )?;
+                writeln!(output, "{{")?;
+                if let Some(node_name) = &node_name {
+                    writeln!(
+                        output,
+                        "reduce_named<{}<T>>({}, {});",
+                        node_name,
+                        symbols,
+                        i + 1
+                    )?;
+                } else {
+                    writeln!(output, "reduce_anonymous({});", symbols)?;
+                }
+                writeln!(output, "}}")?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_ast_header(&self, output: &mut dyn Write) -> Result<(), Error> {
+        let mut writer = self.ast_header_template.writer();
+        writer.substitute("node_declarations", |w| self.write_node_declarations(w));
+        writer.substitute("node_variant_types", |w| self.write_node_variant_types(w));
+        writer.substitute("node_definitions", |w| self.write_node_definitions(w));
+        writer.substitute("builder_methods", |w| self.write_builder_methods(w));
+        substitute_namespace(&mut writer, self.namespace, "ast.h");
+        writer.write(output)
+    }
+}
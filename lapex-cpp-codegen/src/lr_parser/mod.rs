@@ -10,8 +10,11 @@ use lapex_parser::{
 };
 
 mod action_goto;
+mod ast;
 
-use crate::CppLRParserCodeGen;
+use crate::{
+    substitute_external_tokens, substitute_namespace, write_doc_comment, CppLRParserCodeGen,
+};
 
 struct CodeWriter<'parser, 'rules> {
     grammar: &'parser Grammar<'parser>,
@@ -20,16 +23,32 @@ struct CodeWriter<'parser, 'rules> {
     parser_impl_header_template: Template<'static>,
     parser_impl_template: Template<'static>,
     visitor_header_template: Template<'static>,
+    polymorphic_allocator: bool,
+    metrics: bool,
+    facade: bool,
+    namespace: Option<&'parser str>,
+    external_tokens: Option<&'parser str>,
+    ast_header_template: Template<'static>,
     rule_index_map: BTreeMap<*const Rule<'rules>, usize>,
     rules_by_non_terminal: BTreeMap<Symbol, Vec<&'parser Rule<'rules>>>,
 }
 
 impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
-    fn new(grammar: &'grammar Grammar<'grammar>, parser_table: &'grammar ActionGotoTable) -> Self {
-        let parser_header_template = Template::new(include_str!("parser.h.tpl"));
-        let parser_impl_header_template = Template::new(include_str!("parser_impl.h.tpl"));
+    fn new(
+        grammar: &'grammar Grammar<'grammar>,
+        parser_table: &'grammar ActionGotoTable,
+        polymorphic_allocator: bool,
+        metrics: bool,
+        facade: bool,
+        namespace: Option<&'grammar str>,
+        external_tokens: Option<&'grammar str>,
+    ) -> Self {
+        let parser_header_template = Template::new(include_str!("../parser.h.tpl"));
+        let parser_impl_header_template = Template::new(include_str!("parser_impl.h.tpl"))
+            .with_partial("containers", include_str!("../containers.h.tpl"));
         let parser_impl_template = Template::new(include_str!("parser.cpp.tpl"));
         let visitor_header_template = Template::new(include_str!("visitor.h.tpl"));
+        let ast_header_template = Template::new(include_str!("ast.h.tpl"));
 
         let mut rules_by_non_terminal = BTreeMap::new();
         for rule in grammar.rules() {
@@ -55,6 +74,12 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
             parser_impl_header_template,
             parser_impl_template,
             visitor_header_template,
+            ast_header_template,
+            polymorphic_allocator,
+            metrics,
+            facade,
+            namespace,
+            external_tokens,
         }
     }
 
@@ -63,16 +88,11 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
         non_terminal: Symbol,
         output: &mut dyn Write,
     ) -> Result<(), Error> {
-        if let Some(name) = self.grammar.get_production_name(&non_terminal) {
-            write!(output, "NT_{}", name.to_uppercase())?;
-        } else {
-            if let Symbol::NonTerminal(non_terminal_index) = non_terminal {
-                write!(output, "NT_ANON{}", non_terminal_index)?;
-            } else {
-                unreachable!()
-            }
-        }
-        Ok(())
+        let name = self
+            .grammar
+            .get_non_terminal_name(&non_terminal)
+            .expect("every non-terminal has a name");
+        write!(output, "NT_{}", name.to_uppercase())
     }
 
     fn write_non_terminal_enum_variants(&self, output: &mut dyn Write) -> Result<(), Error> {
@@ -84,43 +104,47 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
     }
 
     fn write_header(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
-        self.parser_header_template.writer().write(output)
+        let mut writer = self.parser_header_template.writer();
+        writer.condition("polymorphic_allocator", self.polymorphic_allocator);
+        writer.condition("metrics", self.metrics);
+        writer.condition("facade", self.facade);
+        substitute_namespace(&mut writer, self.namespace, "parser.h");
+        writer.condition("external_tokens", self.external_tokens.is_some());
+        writer.write(output)
     }
 
     fn write_visitor_methods(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
-        for (non_terminal, rules) in &self.rules_by_non_terminal {
-            let non_terminal_name = self.get_non_terminal_name(non_terminal);
-            if rules.len() != 1 {
-                for (i, rule) in rules.iter().enumerate() {
-                    writeln!(output, "// {}", rule.display(self.grammar))?;
-                    writeln!(
-                        output,
-                        "virtual void reduce_{}_{}() = 0;",
-                        non_terminal_name,
-                        i + 1
-                    )?;
-                }
-            } else {
-                writeln!(output, "// {}", rules[0].display(self.grammar))?;
-                writeln!(output, "virtual void reduce_{}() = 0;", non_terminal_name)?;
+        for rules in self.rules_by_non_terminal.values() {
+            for rule in rules {
+                write_doc_comment(output, self.grammar.get_rule_doc(rule))?;
+                writeln!(output, "// {}", rule.display(self.grammar))?;
+                writeln!(
+                    output,
+                    "virtual void {}() = 0;",
+                    self.get_reduce_method_name(rule)
+                )?;
             }
         }
         Ok(())
     }
 
+    fn get_reduce_method_name(&self, rule: &Rule) -> String {
+        let non_terminal = rule.lhs().unwrap();
+        let non_terminal_name = self.get_non_terminal_name(&non_terminal);
+        let rules = &self.rules_by_non_terminal[&non_terminal];
+        if rules.len() == 1 {
+            format!("reduce_{}", non_terminal_name)
+        } else {
+            let index = rules.iter().position(|r| std::ptr::eq(*r, rule)).unwrap();
+            format!("reduce_{}_{}", non_terminal_name, index + 1)
+        }
+    }
+
     fn get_non_terminal_name(&self, non_terminal: &Symbol) -> String {
-        let non_terminal_name = self
-            .grammar
-            .get_production_name(non_terminal)
-            .map(|s| String::from(s))
-            .unwrap_or_else(|| {
-                if let Symbol::NonTerminal(index) = non_terminal {
-                    format!("anon{}", index)
-                } else {
-                    unreachable!()
-                }
-            });
-        non_terminal_name
+        self.grammar
+            .get_non_terminal_name(non_terminal)
+            .map(String::from)
+            .expect("every non-terminal has a name")
     }
 
     fn write_stack_reduce_table(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
@@ -163,7 +187,9 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
         let mut writer = self.parser_impl_template.writer();
         writer.substitute("action_table", |w| self.write_action_table(w));
         writer.substitute("goto_table", |w| self.write_goto_table(w));
+        writer.condition("facade", self.facade);
         writer.substitute("stack_reduce_table", |w| self.write_stack_reduce_table(w));
+        substitute_namespace(&mut writer, self.namespace, "parser.cpp");
         writer.write(output)
     }
 
@@ -172,23 +198,8 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
         for (rule, rule_index) in &self.rule_index_map {
             writeln!(output, "case {}: {{", rule_index)?;
             let rule = get_rule_from_pointer(rule);
-            if let Some(non_terminal) = rule.lhs() {
-                let rules_vec = self.rules_by_non_terminal.get(&non_terminal).unwrap();
-                let non_terminal_name = self.get_non_terminal_name(&non_terminal);
-                if rules_vec.len() == 1 {
-                    writeln!(output, "visitor.reduce_{}();", &non_terminal_name)?;
-                } else {
-                    let rule_index_in_vec = rules_vec
-                        .iter()
-                        .position(|r| std::ptr::eq(*r, rule))
-                        .unwrap();
-                    writeln!(
-                        output,
-                        "visitor.reduce_{}_{}();",
-                        &non_terminal_name,
-                        rule_index_in_vec + 1
-                    )?;
-                }
+            if rule.lhs().is_some() {
+                writeln!(output, "visitor.{}();", self.get_reduce_method_name(rule))?;
             }
             writeln!(output, "return;",)?;
             writeln!(output, "}}")?;
@@ -211,6 +222,8 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
         writer.substitute("entry_state", |w| {
             write!(w, "{}", self.parser_table.entry_state())
         });
+        writer.condition("metrics", self.metrics);
+        substitute_namespace(&mut writer, self.namespace, "parser_impl.h");
 
         writer.write(output)
     }
@@ -218,6 +231,8 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
     fn write_visitor_header(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
         let mut writer = self.visitor_header_template.writer();
         writer.substitute("visitor_methods", |w| self.write_visitor_methods(w));
+        substitute_namespace(&mut writer, self.namespace, "visitor.h");
+        substitute_external_tokens(&mut writer, self.grammar, self.external_tokens);
         writer.write(output)
     }
 }
@@ -236,7 +251,16 @@ impl LRParserCodeGen for CppLRParserCodeGen {
         parser_table: &lapex_parser::lr_parser::ActionGotoTable,
         gen: &mut GeneratedCodeWriter,
     ) {
-        let code_writer = CodeWriter::new(grammar, parser_table);
+        let outer_namespace = self.outer_namespace();
+        let code_writer = CodeWriter::new(
+            grammar,
+            parser_table,
+            self.polymorphic_allocator,
+            self.metrics,
+            self.facade,
+            outer_namespace.as_deref(),
+            self.external_tokens.as_deref(),
+        );
         gen.generate_code("parser.h", |output| code_writer.write_header(output))
             .unwrap();
         gen.generate_code("parser.cpp", |output| code_writer.write_impl(output))
@@ -249,5 +273,7 @@ impl LRParserCodeGen for CppLRParserCodeGen {
             code_writer.write_visitor_header(output)
         })
         .unwrap();
+        gen.generate_code("ast.h", |output| code_writer.write_ast_header(output))
+            .unwrap();
     }
 }
@@ -2,6 +2,13 @@
 
 #include <sstream>
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace parser
 {
     void throw_unexpected_token_error(const char* expected, lexer::TokenType got) {
@@ -15,7 +22,7 @@ namespace parser
         /*{action_table}*/
     }
 
-    void reduce_stack(uint32_t rule, std::vector<Symbol> &parse_stack, std::vector<Symbol> &rev_reduced_symbols)
+    void reduce_stack(uint32_t rule, Vector<Symbol> &parse_stack, Vector<Symbol> &rev_reduced_symbols)
     {
         /*{stack_reduce_table}*/
     }
@@ -24,4 +31,29 @@ namespace parser
     {
         /*{goto_table}*/
     }
-}
\ No newline at end of file
+/*{#facade}*/
+
+    void parse_stream(std::istream &input, Visitor<lexer::LocatedSpan> &visitor)
+    {
+        lexer::Lexer lexer(input);
+        auto token_function = [&lexer]()
+        {
+            lexer::TokenType token = lexer.next();
+            lexer::LocatedSpan span = lexer.span_with_location();
+            if (token == lexer::TokenType::TK_ERR)
+            {
+                std::ostringstream os;
+                os << "Invalid input at line " << span.start_location.line << ", column " << span.start_location.column;
+                throw std::runtime_error(os.str());
+            }
+            return Token<lexer::LocatedSpan>(token, span);
+        };
+        Parser<lexer::LocatedSpan> parser(token_function, visitor);
+        parser.parse();
+    }
+/*{/facade}*/
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -1,13 +1,27 @@
-#pragma once
+#ifndef /*{include_guard}*/
+#define /*{include_guard}*/
 
 #include "parser.h"
+/*{#metrics}*/
+#include <algorithm>
+/*{/metrics}*/
+#include <deque>
 #include <queue>
 #include <vector>
 
 #include <iostream>
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
 namespace parser
 {
+    /*{>containers}*/
+
     enum class SymbolKind : uint8_t
     {
         Terminal,
@@ -46,26 +60,57 @@ namespace parser
 
     Action determine_action(uint32_t state, const lexer::TokenType &lookahead_tpken);
 
-    void reduce_stack(uint32_t rule, std::vector<Symbol> &parse_stack, std::vector<Symbol> &rev_reduced_symbols);
+    void reduce_stack(uint32_t rule, Vector<Symbol> &parse_stack, Vector<Symbol> &rev_reduced_symbols);
 
     Transition retrieve_next_state(uint32_t state, const Symbol &current_symbol);
 
     template <class T>
-    void reduce_visitor(Visitor<T> &visitor, const std::vector<Symbol> &rev_reduced_symbols, uint32_t rule)
+    void reduce_visitor(Visitor<T> &visitor, const Vector<Symbol> &rev_reduced_symbols, uint32_t rule)
     {
         /*{visitor_reduce_switch}*/
     }
 
     template <class T>
-    Parser<T>::Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor) : token_function(token_function), visitor(visitor) {}
+    Parser<T>::Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor, Allocator allocator) : token_function(token_function), visitor(visitor), allocator(allocator) {}
+
+/*{#metrics}*/
+    template <class T>
+    void Parser<T>::set_metrics(Metrics *sink)
+    {
+        this->metrics_sink = sink;
+    }
+
+    template <class T>
+    const ParseMetrics &Parser<T>::metrics() const
+    {
+        return this->parse_metrics;
+    }
 
+/*{/metrics}*/
     template <class T>
     void Parser<T>::parse()
     {
-        std::queue<std::pair<lexer::TokenType, T>> lookahead;
+/*{#metrics}*/
+        this->parse_metrics = ParseMetrics();
+        // reports the metrics when parse() returns or throws
+        struct Report
+        {
+            Parser<T> &parser;
+
+            ~Report()
+            {
+                if (parser.metrics_sink != nullptr)
+                {
+                    parser.metrics_sink->report(parser.parse_metrics);
+                }
+            }
+        } report{*this};
+
+/*{/metrics}*/
+        std::queue<Token<T>, Deque<Token<T>>> lookahead(this->allocator);
         lookahead.push(this->token_function());
 
-        std::vector<Symbol> parse_stack;
+        Vector<Symbol> parse_stack(this->allocator);
         Symbol entry_symbol{SymbolKind::State, static_cast<uint32_t>(/*{entry_state}*/)};
         parse_stack.push_back(entry_symbol);
 
@@ -82,14 +127,20 @@ namespace parser
                 parse_stack.push_back(next_symbol);
 
                 this->visitor.shift(next_tk, next_token_and_data.second);
+/*{#metrics}*/
+                this->parse_metrics.tokens_consumed++;
+/*{/metrics}*/
                 lookahead.pop();
                 lookahead.push(this->token_function());
             }
             else if (action.action_type == ActionType::Reduce)
             {
-                std::vector<Symbol> rev_reduced_symbols;
+                Vector<Symbol> rev_reduced_symbols(this->allocator);
                 reduce_stack(action.reduced_rule, parse_stack, rev_reduced_symbols);
                 reduce_visitor(this->visitor, rev_reduced_symbols, action.reduced_rule);
+/*{#metrics}*/
+                this->parse_metrics.reductions++;
+/*{/metrics}*/
             }
             Symbol current_symbol = parse_stack.back();
             uint32_t stack_state = parse_stack.at(parse_stack.size() - 2).identifier;
@@ -103,7 +154,16 @@ namespace parser
             {
                 Symbol next_state_symbol{SymbolKind::State, transition.next_state};
                 parse_stack.push_back(next_state_symbol);
+/*{#metrics}*/
+                this->parse_metrics.max_stack_depth = std::max(this->parse_metrics.max_stack_depth, parse_stack.size() / 2 + 1);
+/*{/metrics}*/
             }
         }
     }
-}
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -1,7 +1,26 @@
-#pragma once
+#ifndef /*{include_guard}*/
+#define /*{include_guard}*/
 
+/*{^external_tokens}*/
 #include "tokens.h"
+/*{/external_tokens}*/
+/*{#external_tokens}*/
+#include <cstdint>
+/*{/external_tokens}*/
 
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
+/*{#external_tokens}*/
+/*{#lexer_alias}*/
+namespace lexer = /*{external_tokens}*/;
+/*{/lexer_alias}*/
+/*{external_token_checks}*/
+/*{/external_tokens}*/
 namespace parser
 {
     template <class T>
@@ -11,4 +30,10 @@ namespace parser
         virtual void shift(lexer::TokenType tk_type, T data) = 0;
         /*{visitor_methods}*/
     };
-}
\ No newline at end of file
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
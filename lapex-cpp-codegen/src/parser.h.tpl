@@ -0,0 +1,101 @@
+#ifndef /*{include_guard}*/
+#define /*{include_guard}*/
+
+/*{^external_tokens}*/
+#include "tokens.h"
+/*{/external_tokens}*/
+#include "visitor.h"
+/*{#facade}*/
+#include "lexer.h"
+#include <istream>
+/*{/facade}*/
+#include <cstddef>
+#include <functional>
+#include <memory>
+#include <utility>
+/*{#polymorphic_allocator}*/
+#include <memory_resource>
+/*{/polymorphic_allocator}*/
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+/*{#namespace}*/
+namespace /*{namespace}*/
+{
+/*{/namespace}*/
+namespace parser
+{
+
+    // the containers of the parser use this allocator, which is passed to its constructor
+/*{#polymorphic_allocator}*/
+    using Allocator = std::pmr::polymorphic_allocator<std::byte>;
+/*{/polymorphic_allocator}*/
+/*{^polymorphic_allocator}*/
+    using Allocator = std::allocator<std::byte>;
+/*{/polymorphic_allocator}*/
+
+    template <class T>
+    using Token = std::pair<lexer::TokenType, T>;
+/*{#metrics}*/
+
+    // what the parser did during the last call of parse()
+    struct ParseMetrics
+    {
+        // the tokens shifted, without the end of the file
+        size_t tokens_consumed = 0;
+        // the most states on the stack at once
+        size_t max_stack_depth = 0;
+        // the rules reduced
+        size_t reductions = 0;
+    };
+
+    // receives the metrics at the end of every call of parse(), also of one that throws
+    class Metrics
+    {
+    public:
+        virtual ~Metrics() = default;
+
+        virtual void report(const ParseMetrics &metrics) = 0;
+    };
+/*{/metrics}*/
+
+    template <class T>
+    class Parser
+    {
+    private:
+        std::function<Token<T>()> token_function;
+        Visitor<T> &visitor;
+        Allocator allocator;
+/*{#metrics}*/
+        ParseMetrics parse_metrics;
+        Metrics *metrics_sink = nullptr;
+/*{/metrics}*/
+
+    public:
+        Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor, Allocator allocator = Allocator());
+
+        void parse();
+/*{#metrics}*/
+
+        // reports the metrics of every call of parse() to the sink, which has to outlive the parser
+        void set_metrics(Metrics *sink);
+
+        // the metrics of the last call of parse(), or of the current one while parsing
+        const ParseMetrics &metrics() const;
+/*{/metrics}*/
+    };
+/*{#facade}*/
+
+    // lexes and parses the input in one call, passing the span of every token to the visitor.
+    // Throws std::runtime_error on input the lexer or the parser rejects
+    void parse_stream(std::istream &input, Visitor<lexer::LocatedSpan> &visitor);
+/*{/facade}*/
+
+}/*{#namespace}*/
+}/*{/namespace}*/
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
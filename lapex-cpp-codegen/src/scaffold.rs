@@ -0,0 +1,62 @@
+use std::{collections::BTreeMap, io::Write};
+
+use lapex_parser::grammar::{Grammar, Rule, Symbol};
+
+use crate::outer_namespace;
+
+/// Writes a class overriding all methods of the generated `parser::Visitor` with empty ones,
+/// meant to be copied into user code. The namespaces are qualified with the namespace and the
+/// prefix the parser was generated with, if any.
+pub fn write_visitor_scaffold(
+    grammar: &Grammar,
+    type_name: &str,
+    namespace: Option<&str>,
+    symbol_prefix: Option<&str>,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut rules_by_non_terminal: BTreeMap<Symbol, Vec<&Rule>> = BTreeMap::new();
+    for rule in grammar.rules() {
+        if let Some(non_terminal) = rule.lhs() {
+            rules_by_non_terminal
+                .entry(non_terminal)
+                .or_default()
+                .push(rule);
+        }
+    }
+
+    let namespace = outer_namespace(namespace, symbol_prefix)
+        .map_or(String::new(), |namespace| format!("{}::", namespace));
+    writeln!(output, "#pragma once")?;
+    writeln!(output)?;
+    writeln!(output, "#include \"visitor.h\"")?;
+    writeln!(output)?;
+    writeln!(output, "template <class T>")?;
+    writeln!(
+        output,
+        "class {} : public {}parser::Visitor<T>",
+        type_name, namespace
+    )?;
+    writeln!(output, "{{")?;
+    writeln!(output, "public:")?;
+    writeln!(
+        output,
+        "    void shift({}lexer::TokenType tk_type, T data) override {{}}",
+        namespace
+    )?;
+    for (non_terminal, rules) in &rules_by_non_terminal {
+        let non_terminal_name = grammar
+            .get_non_terminal_name(non_terminal)
+            .expect("every non-terminal has a name");
+        for (i, rule) in rules.iter().enumerate() {
+            let function = if rules.len() == 1 {
+                format!("reduce_{}", non_terminal_name)
+            } else {
+                format!("reduce_{}_{}", non_terminal_name, i + 1)
+            };
+            writeln!(output)?;
+            writeln!(output, "    // {}", rule.display(grammar))?;
+            writeln!(output, "    void {}() override {{}}", function)?;
+        }
+    }
+    writeln!(output, "}};")
+}
@@ -0,0 +1,154 @@
+use std::{cell::RefCell, collections::BTreeMap, io::Write, rc::Rc};
+
+use lapex_codegen::GeneratedCodeWriter;
+use lapex_input::{DefaultPrecedenceModel, LapexInputParser, PrecedenceStrategy, RuleSet};
+use lapex_lexer::{Alphabet, LexerCodeGen, TokenDfa};
+use lapex_parser::{
+    grammar::Grammar,
+    lr_parser::{generate_table, GenerationResult, LRParserCodeGen},
+};
+
+use crate::{write_visitor_scaffold, CppLRParserCodeGen, CppLexerCodeGen};
+
+// a file of the generated code, which stays readable after the code generator dropped its writer
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn capture_generated_files<F>(generate: F) -> BTreeMap<String, String>
+where
+    F: FnOnce(&mut GeneratedCodeWriter),
+{
+    let files: RefCell<BTreeMap<String, Rc<RefCell<Vec<u8>>>>> = RefCell::new(BTreeMap::new());
+    let mut gen = GeneratedCodeWriter::with_default(|name| {
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        files.borrow_mut().insert(name.to_string(), buffer.clone());
+        Ok(SharedBuffer(buffer))
+    });
+    generate(&mut gen);
+    drop(gen);
+    files
+        .into_inner()
+        .into_iter()
+        .map(|(name, buffer)| (name, String::from_utf8(buffer.take()).unwrap()))
+        .collect()
+}
+
+fn lexer_dfa<'rules>(rules: &'rules RuleSet<'rules>) -> (Alphabet, TokenDfa<'rules>) {
+    let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, _) = lapex_lexer::apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+    (alphabet, dfa)
+}
+
+#[test]
+fn test_cpp_namespace() {
+    let source = format!(
+        "prefix csv;\n{}",
+        lapex_grammars::find_template("csv").unwrap().grammar
+    );
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(&source)
+        .unwrap();
+    let grammar = Grammar::from_rule_set(&rules, false).unwrap();
+    let table = match generate_table::<1>(&grammar, false, false) {
+        GenerationResult::NoConflicts(table) => table,
+        _ => panic!("grammar csv has conflicts"),
+    };
+    let files = capture_generated_files(|gen| {
+        let mut lexer = CppLexerCodeGen::new();
+        lexer.set_symbol_prefix(rules.symbol_prefix);
+        lexer.set_namespace(Some("mycompany::mylang"));
+        lexer.generate_tokens(&rules.token_rules, gen);
+        let mut parser = CppLRParserCodeGen::new();
+        parser.set_symbol_prefix(rules.symbol_prefix);
+        parser.set_namespace(Some("mycompany::mylang"));
+        parser.generate_code(&grammar, &table, gen);
+    });
+    for (name, contents) in &files {
+        assert!(
+            contents.contains("namespace mycompany::mylang::csv\n{\n"),
+            "{} is not nested into the namespace",
+            name
+        );
+        if let Some(stem) = name.strip_suffix(".h") {
+            let guard = format!("MYCOMPANY_MYLANG_CSV_{}_H", stem.to_ascii_uppercase());
+            assert!(contents.starts_with(&format!("#ifndef {}\n#define {}\n", guard, guard)));
+            assert!(contents.ends_with("#endif\n"));
+        }
+    }
+
+    let mut scaffold = Vec::new();
+    write_visitor_scaffold(
+        &grammar,
+        "CsvVisitor",
+        Some("mycompany::mylang"),
+        rules.symbol_prefix,
+        &mut scaffold,
+    )
+    .unwrap();
+    let scaffold = String::from_utf8(scaffold).unwrap();
+    assert!(scaffold.contains("public mycompany::mylang::csv::parser::Visitor<T>"));
+}
+
+#[test]
+fn test_skipped_tokens() {
+    let source = r#"token NAME = /[a-z]+/;
+skip token WS = /[ \n]+/;
+skip token COMMENT = /#[^\n]*/;
+entry s;
+prod s = NAME*;
+"#;
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(source)
+        .unwrap();
+    let (alphabet, dfa) = lexer_dfa(&rules);
+    let files = capture_generated_files(|gen| {
+        CppLexerCodeGen::new().generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    assert!(files["lexer.cpp"].contains("continue;"));
+}
+
+#[test]
+fn test_lexer_lookup_table() {
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(lapex_grammars::find_template("json").unwrap().grammar)
+        .unwrap();
+    let (alphabet, dfa) = lexer_dfa(&rules);
+    let files = capture_generated_files(|gen| {
+        CppLexerCodeGen::new().generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    let lexer_code = &files["lexer.cpp"];
+    // the case ranges of GCC and Clang are not supported by MSVC
+    assert!(!lexer_code.contains(" ... "));
+
+    let ranges = alphabet.get_ranges();
+    let ascii_indices: Vec<String> = (0..128)
+        .map(|ch| {
+            let index = ranges.iter().position(|range| range.contains(&ch)).unwrap();
+            index.to_string()
+        })
+        .collect();
+    assert!(lexer_code.contains(&format!(
+        "static const int32_t ascii_indices[128] = {{{}}};",
+        ascii_indices.join(", ")
+    )));
+    let non_ascii = ranges.iter().filter(|range| *range.end() >= 128).count();
+    assert!(lexer_code.contains(&format!(
+        "if (low == {} || ch < range_starts[low])",
+        non_ascii
+    )));
+}
@@ -0,0 +1,38 @@
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    const char *get_token_name(TokenType tk_type) {
+        switch (tk_type) {
+case TokenType::TK_ERR:
+return "<ERR>";
+case TokenType::TK_EOF:
+return "<EOF>";
+case TokenType::TK_WHITESPACE:
+return "WHITESPACE";
+case TokenType::TK_RPAR:
+return "RPAR";
+case TokenType::TK_LPAR:
+return "LPAR";
+case TokenType::TK_SLASH:
+return "SLASH";
+case TokenType::TK_STAR:
+return "STAR";
+case TokenType::TK_MINUS:
+return "MINUS";
+case TokenType::TK_PLUS:
+return "PLUS";
+case TokenType::TK_NUMBER:
+return "NUMBER";
+default:
+return nullptr;
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,32 @@
+#ifndef LAPEX_TOKENS_H
+#define LAPEX_TOKENS_H
+
+#include <cstdint>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    enum class TokenType : uint32_t
+    {
+        TK_ERR = 0,
+        TK_EOF = 1,
+        TK_WHITESPACE,
+TK_RPAR,
+TK_LPAR,
+TK_SLASH,
+TK_STAR,
+TK_MINUS,
+TK_PLUS,
+TK_NUMBER,
+
+    };
+    
+    const char *get_token_name(TokenType tk_type);
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
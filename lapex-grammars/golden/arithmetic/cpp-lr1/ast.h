@@ -0,0 +1,205 @@
+#ifndef LAPEX_AST_H
+#define LAPEX_AST_H
+
+#include "visitor.h"
+#include <memory>
+#include <utility>
+#include <variant>
+#include <vector>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    namespace ast
+    {
+        template <class T>
+        struct Terminal
+        {
+            lexer::TokenType type;
+            T data;
+        };
+
+        template <class T>
+struct AtomNode;
+template <class T>
+struct UnaryNode;
+template <class T>
+struct ProductNode;
+template <class T>
+struct SumNode;
+template <class T>
+struct ExpressionNode;
+
+
+        template <class T>
+        using Node = std::variant<Terminal<T>, std::unique_ptr<AtomNode<T>>, std::unique_ptr<UnaryNode<T>>, std::unique_ptr<ProductNode<T>>, std::unique_ptr<SumNode<T>>, std::unique_ptr<ExpressionNode<T>>>;
+
+        // atom(0) -> <atom_alt>(8)
+template <class T>
+struct AtomNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// unary(1) -> <unary_alt>(9)
+template <class T>
+struct UnaryNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// product(2) -> <product_alt>(10)
+template <class T>
+struct ProductNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// sum(3) -> <sum_alt>(11)
+template <class T>
+struct SumNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// expression(4) -> sum(3)
+template <class T>
+struct ExpressionNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+
+
+        // Builds a tree while parsing. Anonymous non-terminals like repetitions have no node type,
+        // their children are added to the node of the enclosing production instead.
+        template <class T>
+        class TreeBuilder : public Visitor<T>
+        {
+        private:
+            // the nodes produced by each symbol on the parse stack
+            std::vector<std::vector<Node<T>>> stack;
+
+            std::vector<Node<T>> pop_children(size_t symbols)
+            {
+                std::vector<Node<T>> children;
+                auto first = stack.end() - symbols;
+                for (auto it = first; it != stack.end(); ++it)
+                {
+                    for (auto &node : *it)
+                    {
+                        children.push_back(std::move(node));
+                    }
+                }
+                stack.erase(first, stack.end());
+                return children;
+            }
+
+            template <class N>
+            void reduce_named(size_t symbols, uint32_t alternative)
+            {
+                auto node = std::make_unique<N>();
+                node->alternative = alternative;
+                node->children = pop_children(symbols);
+                std::vector<Node<T>> nodes;
+                nodes.push_back(std::move(node));
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_anonymous(size_t symbols)
+            {
+                stack.push_back(pop_children(symbols));
+            }
+
+        public:
+            void shift(lexer::TokenType tk_type, T data) override
+            {
+                std::vector<Node<T>> nodes;
+                nodes.push_back(Terminal<T>{tk_type, std::move(data)});
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_atom() override
+{
+reduce_named<AtomNode<T>>(1, 1);
+}
+void reduce_unary() override
+{
+reduce_named<UnaryNode<T>>(1, 1);
+}
+void reduce_product() override
+{
+reduce_named<ProductNode<T>>(1, 1);
+}
+void reduce_sum() override
+{
+reduce_named<SumNode<T>>(1, 1);
+}
+void reduce_expression() override
+{
+reduce_named<ExpressionNode<T>>(1, 1);
+}
+void reduce_atom_alt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_atom_alt_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_unary_alt_1() override
+{
+reduce_anonymous(2);
+}
+void reduce_unary_alt_2() override
+{
+reduce_anonymous(1);
+}
+void reduce_product_alt_1() override
+{
+reduce_anonymous(3);
+}
+void reduce_product_alt_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_product_alt_3() override
+{
+reduce_anonymous(1);
+}
+void reduce_sum_alt_1() override
+{
+reduce_anonymous(3);
+}
+void reduce_sum_alt_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_sum_alt_3() override
+{
+reduce_anonymous(1);
+}
+
+
+            // Returns the node of the entry production once the input has been parsed.
+            Node<T> take_root()
+            {
+                Node<T> root = std::move(stack.back().front());
+                stack.clear();
+                return root;
+            }
+        };
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,2519 @@
+#include "parser_impl.h"
+
+#include <sstream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    void throw_unexpected_token_error(const char* expected, lexer::TokenType got) {
+        std::ostringstream os;
+        os << "Unexpected token '" << lexer::get_token_name(got) << "', expected one of: " << expected;
+        throw std::runtime_error(os.str());
+    }
+
+    Action determine_action(uint32_t state, const lexer::TokenType &lookahead_token)
+    {
+        switch (state) {
+case 0: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 1: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 2: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 3: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 4: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 14};
+return act;
+}
+default:
+throw_unexpected_token_error("'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 5: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 6: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 7: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 8: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+default:
+throw_unexpected_token_error("'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 9: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 10: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 11: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 12: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 13: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 14: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 15: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 16: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 17: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 18: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 19: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 20: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 21: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 22: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 23: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 24: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 25: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 26: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 27: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 28: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 29: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 30: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 31: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 32: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 33: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 34: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+default:
+throw_unexpected_token_error("'SLASH', 'STAR', 'MINUS', 'PLUS', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 35: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 36: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_LPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'LPAR', 'MINUS', 'NUMBER'", lookahead_token);
+}
+}
+break;
+case 37: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 38: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 39: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 40: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 41: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 42: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+case 43: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RPAR:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_SLASH:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_STAR:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+default:
+throw_unexpected_token_error("'RPAR', 'SLASH', 'STAR', 'MINUS', 'PLUS'", lookahead_token);
+}
+}
+break;
+default:
+// Encountered a parser state that does not exist.
+std::terminate();
+}
+
+    }
+
+    void reduce_stack(uint32_t rule, Vector<Symbol> &parse_stack, Vector<Symbol> &rev_reduced_symbols)
+    {
+        switch(rule) {
+case 0: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ATOM_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 1: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ATOM_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 2: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ATOM)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 3: {
+for (size_t i = 0; i < 2; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_UNARY_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 4: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_UNARY_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 5: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_UNARY)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 6: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_PRODUCT_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 7: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_PRODUCT_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 8: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_PRODUCT_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 9: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_PRODUCT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 10: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_SUM_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 11: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_SUM_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 12: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_SUM_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 13: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_SUM)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 14: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_EXPRESSION)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+default:
+// Tried reducing non-existent rule.
+std::terminate();
+}
+
+    }
+
+    Transition retrieve_next_state(uint32_t state, const Symbol &current_symbol)
+    {
+        switch (state) {
+case 0: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{2, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{3, false};
+return transition;
+}
+case NonTerminalType::NT_SUM:
+{
+Transition transition{4, false};
+return transition;
+}
+case NonTerminalType::NT_EXPRESSION:
+{
+Transition transition{0, true};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{5, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{6, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{7, false};
+return transition;
+}
+case NonTerminalType::NT_SUM_ALT:
+{
+Transition transition{8, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 3: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_SLASH:
+{
+Transition transition{12, false};
+return transition;
+}
+case lexer::TokenType::TK_STAR:
+{
+Transition transition{13, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 4: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{14, false};
+return transition;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Transition transition{15, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 9: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{17, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{18, false};
+return transition;
+}
+case NonTerminalType::NT_SUM:
+{
+Transition transition{19, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{22, false};
+return transition;
+}
+case NonTerminalType::NT_SUM_ALT:
+{
+Transition transition{23, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 10: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{27, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{5, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{6, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 12: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{28, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{5, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{6, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 13: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{29, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{5, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{6, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 14: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{2, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{30, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{5, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{6, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{7, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 15: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{2, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{31, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{5, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{6, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{7, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 18: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_SLASH:
+{
+Transition transition{32, false};
+return transition;
+}
+case lexer::TokenType::TK_STAR:
+{
+Transition transition{33, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 19: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RPAR:
+{
+Transition transition{34, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{35, false};
+return transition;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Transition transition{36, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 24: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{17, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{18, false};
+return transition;
+}
+case NonTerminalType::NT_SUM:
+{
+Transition transition{37, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{22, false};
+return transition;
+}
+case NonTerminalType::NT_SUM_ALT:
+{
+Transition transition{23, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 25: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{38, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 30: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_SLASH:
+{
+Transition transition{12, false};
+return transition;
+}
+case lexer::TokenType::TK_STAR:
+{
+Transition transition{13, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 31: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_SLASH:
+{
+Transition transition{12, false};
+return transition;
+}
+case lexer::TokenType::TK_STAR:
+{
+Transition transition{13, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 32: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{39, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 33: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{40, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 35: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{17, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{41, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{22, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 36: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_LPAR:
+{
+Transition transition{24, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{25, false};
+return transition;
+}
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{26, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ATOM:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY:
+{
+Transition transition{17, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT:
+{
+Transition transition{42, false};
+return transition;
+}
+case NonTerminalType::NT_ATOM_ALT:
+{
+Transition transition{20, false};
+return transition;
+}
+case NonTerminalType::NT_UNARY_ALT:
+{
+Transition transition{21, false};
+return transition;
+}
+case NonTerminalType::NT_PRODUCT_ALT:
+{
+Transition transition{22, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 37: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RPAR:
+{
+Transition transition{43, false};
+return transition;
+}
+case lexer::TokenType::TK_MINUS:
+{
+Transition transition{35, false};
+return transition;
+}
+case lexer::TokenType::TK_PLUS:
+{
+Transition transition{36, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 41: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_SLASH:
+{
+Transition transition{32, false};
+return transition;
+}
+case lexer::TokenType::TK_STAR:
+{
+Transition transition{33, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 42: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_SLASH:
+{
+Transition transition{32, false};
+return transition;
+}
+case lexer::TokenType::TK_STAR:
+{
+Transition transition{33, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+default:
+// Encountered a parser state that does not exist.
+std::terminate();
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
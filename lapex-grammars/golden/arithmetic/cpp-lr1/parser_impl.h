@@ -0,0 +1,199 @@
+#ifndef LAPEX_PARSER_IMPL_H
+#define LAPEX_PARSER_IMPL_H
+
+#include "parser.h"
+#include <deque>
+#include <queue>
+#include <vector>
+
+#include <iostream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class E>
+    using Vector = std::vector<E, std::allocator_traits<Allocator>::rebind_alloc<E>>;
+
+    template <class E>
+    using Deque = std::deque<E, std::allocator_traits<Allocator>::rebind_alloc<E>>;
+
+    enum class SymbolKind : uint8_t
+    {
+        Terminal,
+        NonTerminal,
+        State
+    };
+
+    struct Symbol
+    {
+        SymbolKind kind;
+        uint32_t identifier;
+    };
+
+    enum class NonTerminalType : uint32_t
+    {
+        NT_ATOM,
+NT_UNARY,
+NT_PRODUCT,
+NT_SUM,
+NT_EXPRESSION,
+NT_ATOM_ALT,
+NT_UNARY_ALT,
+NT_PRODUCT_ALT,
+NT_SUM_ALT,
+
+    };
+
+    enum class ActionType : uint8_t
+    {
+        Shift,
+        Reduce,
+    };
+
+    struct Action
+    {
+        ActionType action_type;
+        uint16_t reduced_rule;
+    };
+
+    struct Transition
+    {
+        uint32_t next_state;
+        bool is_accepting;
+    };
+
+    Action determine_action(uint32_t state, const lexer::TokenType &lookahead_tpken);
+
+    void reduce_stack(uint32_t rule, Vector<Symbol> &parse_stack, Vector<Symbol> &rev_reduced_symbols);
+
+    Transition retrieve_next_state(uint32_t state, const Symbol &current_symbol);
+
+    template <class T>
+    void reduce_visitor(Visitor<T> &visitor, const Vector<Symbol> &rev_reduced_symbols, uint32_t rule)
+    {
+        switch(rule) {
+case 0: {
+visitor.reduce_atom_alt_1();
+return;
+}
+case 1: {
+visitor.reduce_atom_alt_2();
+return;
+}
+case 2: {
+visitor.reduce_atom();
+return;
+}
+case 3: {
+visitor.reduce_unary_alt_1();
+return;
+}
+case 4: {
+visitor.reduce_unary_alt_2();
+return;
+}
+case 5: {
+visitor.reduce_unary();
+return;
+}
+case 6: {
+visitor.reduce_product_alt_1();
+return;
+}
+case 7: {
+visitor.reduce_product_alt_2();
+return;
+}
+case 8: {
+visitor.reduce_product_alt_3();
+return;
+}
+case 9: {
+visitor.reduce_product();
+return;
+}
+case 10: {
+visitor.reduce_sum_alt_1();
+return;
+}
+case 11: {
+visitor.reduce_sum_alt_2();
+return;
+}
+case 12: {
+visitor.reduce_sum_alt_3();
+return;
+}
+case 13: {
+visitor.reduce_sum();
+return;
+}
+case 14: {
+visitor.reduce_expression();
+return;
+}
+default:
+// Tried reducing non-existent rule.
+std::terminate();
+}
+
+    }
+
+    template <class T>
+    Parser<T>::Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor, Allocator allocator) : token_function(token_function), visitor(visitor), allocator(allocator) {}
+
+    template <class T>
+    void Parser<T>::parse()
+    {
+        std::queue<Token<T>, Deque<Token<T>>> lookahead(this->allocator);
+        lookahead.push(this->token_function());
+
+        Vector<Symbol> parse_stack(this->allocator);
+        Symbol entry_symbol{SymbolKind::State, static_cast<uint32_t>(0)};
+        parse_stack.push_back(entry_symbol);
+
+        while (parse_stack.size() > 0)
+        {
+            auto next_token_and_data = lookahead.front();
+            lexer::TokenType next_tk = next_token_and_data.first;
+            Symbol next_symbol{SymbolKind::Terminal, static_cast<uint32_t>(next_tk)};
+
+            uint32_t state = parse_stack.back().identifier;
+            Action action = determine_action(state, next_tk);
+            if (action.action_type == ActionType::Shift)
+            {
+                parse_stack.push_back(next_symbol);
+
+                this->visitor.shift(next_tk, next_token_and_data.second);
+                lookahead.pop();
+                lookahead.push(this->token_function());
+            }
+            else if (action.action_type == ActionType::Reduce)
+            {
+                Vector<Symbol> rev_reduced_symbols(this->allocator);
+                reduce_stack(action.reduced_rule, parse_stack, rev_reduced_symbols);
+                reduce_visitor(this->visitor, rev_reduced_symbols, action.reduced_rule);
+            }
+            Symbol current_symbol = parse_stack.back();
+            uint32_t stack_state = parse_stack.at(parse_stack.size() - 2).identifier;
+            Transition transition = retrieve_next_state(stack_state, current_symbol);
+            if (transition.is_accepting)
+            {
+                parse_stack.pop_back();
+                parse_stack.pop_back();
+            }
+            else
+            {
+                Symbol next_state_symbol{SymbolKind::State, transition.next_state};
+                parse_stack.push_back(next_state_symbol);
+            }
+        }
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
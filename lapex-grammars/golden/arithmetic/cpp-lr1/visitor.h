@@ -0,0 +1,53 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void shift(lexer::TokenType tk_type, T data) = 0;
+        // atom(0) -> <atom_alt>(8)
+virtual void reduce_atom() = 0;
+// unary(1) -> <unary_alt>(9)
+virtual void reduce_unary() = 0;
+// product(2) -> <product_alt>(10)
+virtual void reduce_product() = 0;
+// sum(3) -> <sum_alt>(11)
+virtual void reduce_sum() = 0;
+// expression(4) -> sum(3)
+virtual void reduce_expression() = 0;
+// <atom_alt>(8) -> NUMBER(7)
+virtual void reduce_atom_alt_1() = 0;
+// <atom_alt>(8) -> LPAR(2) sum(3) RPAR(1)
+virtual void reduce_atom_alt_2() = 0;
+// <unary_alt>(9) -> MINUS(5) unary(1)
+virtual void reduce_unary_alt_1() = 0;
+// <unary_alt>(9) -> atom(0)
+virtual void reduce_unary_alt_2() = 0;
+// <product_alt>(10) -> product(2) STAR(4) unary(1)
+virtual void reduce_product_alt_1() = 0;
+// <product_alt>(10) -> product(2) SLASH(3) unary(1)
+virtual void reduce_product_alt_2() = 0;
+// <product_alt>(10) -> unary(1)
+virtual void reduce_product_alt_3() = 0;
+// <sum_alt>(11) -> sum(3) PLUS(6) product(2)
+virtual void reduce_sum_alt_1() = 0;
+// <sum_alt>(11) -> sum(3) MINUS(5) product(2)
+virtual void reduce_sum_alt_2() = 0;
+// <sum_alt>(11) -> product(2)
+virtual void reduce_sum_alt_3() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
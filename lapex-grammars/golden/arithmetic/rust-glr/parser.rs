@@ -0,0 +1,6 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ;pub trait Visitor < T > { fn shift (& mut self , token : TokenType , data : T) ; # [doc = "atom(0) -> <atom_alt>(8)"] fn reduce_atom (& mut self) ; # [doc = "unary(1) -> <unary_alt>(9)"] fn reduce_unary (& mut self) ; # [doc = "product(2) -> <product_alt>(10)"] fn reduce_product (& mut self) ; # [doc = "sum(3) -> <sum_alt>(11)"] fn reduce_sum (& mut self) ; # [doc = "expression(4) -> sum(3)"] fn reduce_expression (& mut self) ; # [doc = "<atom_alt>(8) -> NUMBER(7)"] fn reduce_atom_alt_1 (& mut self) ; # [doc = "<atom_alt>(8) -> LPAR(2) sum(3) RPAR(1)"] fn reduce_atom_alt_2 (& mut self) ; # [doc = "<unary_alt>(9) -> MINUS(5) unary(1)"] fn reduce_unary_alt_1 (& mut self) ; # [doc = "<unary_alt>(9) -> atom(0)"] fn reduce_unary_alt_2 (& mut self) ; # [doc = "<product_alt>(10) -> product(2) STAR(4) unary(1)"] fn reduce_product_alt_1 (& mut self) ; # [doc = "<product_alt>(10) -> product(2) SLASH(3) unary(1)"] fn reduce_product_alt_2 (& mut self) ; # [doc = "<product_alt>(10) -> unary(1)"] fn reduce_product_alt_3 (& mut self) ; # [doc = "<sum_alt>(11) -> sum(3) PLUS(6) product(2)"] fn reduce_sum_alt_1 (& mut self) ; # [doc = "<sum_alt>(11) -> sum(3) MINUS(5) product(2)"] fn reduce_sum_alt_2 (& mut self) ; # [doc = "<sum_alt>(11) -> product(2)"] fn reduce_sum_alt_3 (& mut self) ; }pub struct DebugVisitor { } impl < T > Visitor < T > for DebugVisitor { fn shift (& mut self , token : TokenType , _data : T) { println ! ("shift {:?}" , token) ; } fn reduce_atom (& mut self) { println ! ("atom(0) -> <atom_alt>(8)") ; } fn reduce_unary (& mut self) { println ! ("unary(1) -> <unary_alt>(9)") ; } fn reduce_product (& mut self) { println ! ("product(2) -> <product_alt>(10)") ; } fn reduce_sum (& mut self) { println ! ("sum(3) -> <sum_alt>(11)") ; } fn reduce_expression (& mut self) { println ! ("expression(4) -> sum(3)") ; } fn reduce_atom_alt_1 (& mut self) { println ! ("<atom_alt>(8) -> NUMBER(7)") ; } fn reduce_atom_alt_2 (& mut self) { println ! ("<atom_alt>(8) -> LPAR(2) sum(3) RPAR(1)") ; } fn reduce_unary_alt_1 (& mut self) { println ! ("<unary_alt>(9) -> MINUS(5) unary(1)") ; } fn reduce_unary_alt_2 (& mut self) { println ! ("<unary_alt>(9) -> atom(0)") ; } fn reduce_product_alt_1 (& mut self) { println ! ("<product_alt>(10) -> product(2) STAR(4) unary(1)") ; } fn reduce_product_alt_2 (& mut self) { println ! ("<product_alt>(10) -> product(2) SLASH(3) unary(1)") ; } fn reduce_product_alt_3 (& mut self) { println ! ("<product_alt>(10) -> unary(1)") ; } fn reduce_sum_alt_1 (& mut self) { println ! ("<sum_alt>(11) -> sum(3) PLUS(6) product(2)") ; } fn reduce_sum_alt_2 (& mut self) { println ! ("<sum_alt>(11) -> sum(3) MINUS(5) product(2)") ; } fn reduce_sum_alt_3 (& mut self) { println ! ("<sum_alt>(11) -> product(2)") ; } }pub struct Parser < T , E , F : FnMut () -> Result < (TokenType , T) , E > , V : Visitor < T >> { token_function : F , visitor : V , } # [derive (Debug , Clone , Copy)] # [allow (clippy :: enum_variant_names)] enum NonTerminalType { NtAtom , NtUnary , NtProduct , NtSum , NtExpression , NtAtomAlt , NtUnaryAlt , NtProductAlt , NtSumAlt } # [derive (Debug , Clone , Copy)] enum StackSymbol { Terminal { token : TokenType } , NonTerminal { non_terminal : NonTerminalType } , } # [derive (Clone , Copy , PartialEq , Eq , PartialOrd , Ord)] enum ReducedRule { Rule0 , Rule1 , Rule2 , Rule3 , Rule4 , Rule5 , Rule6 , Rule7 , Rule8 , Rule9 , Rule10 , Rule11 , Rule12 , Rule13 , Rule14 } enum Action { Shift , Reduce { rule : ReducedRule } } enum Goto { Accept , State { state_id : usize } } type StateId = usize ; type Stack < T > = GraphNode < StateId , StackSymbol , RecordedVisit < T >> ; # [derive (Debug)] pub enum ParserError < T , E : std :: error :: Error > { UnexpectedToken { got : TokenType , got_data : T , expected : Vec < TokenType > , } , LexerError { inner : E } , UnexpectedTokens { got : Vec < (TokenType , T) > , expected : Vec < Vec < TokenType >> , } , } impl < T : std :: fmt :: Debug , E : std :: error :: Error > std :: error :: Error for ParserError < T , E > { } impl < T , E : std :: error :: Error > std :: fmt :: Display for ParserError < T , E > { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { ParserError :: UnexpectedToken { got , got_data : _ , expected , } => write ! (f , "Unexpected token {:?}, expected one of: {:?}" , got , expected) , ParserError :: LexerError { inner } => write ! (f , "{}" , inner) , ParserError :: UnexpectedTokens { got , expected } => { let errors : Vec < String > = got . iter () . zip (expected . iter ()) . map (| ((got , _got_data) , expected) | { format ! ("Unexpected token {:?}, expected one of: {:?}" , got , expected) }) . collect () ; write ! (f , "Multiple diverging parse stacks reached unexpected ends:\n{}" , errors . join ("\n")) } } } } # [derive (Clone)] enum RecordedVisit < T > { Reduce { rule : ReducedRule } , Shift { token : TokenType , data : T } , } impl < T : Clone , E : std :: error :: Error , F : FnMut () -> Result < (TokenType , T) , E > , V : Visitor < T >> Parser < T , E , F , V > { # [doc = r" The token function is not called again once it returned `TokenType::EndOfFile`,"] # [doc = r" and `EndOfFile` is never passed to `Visitor::shift`."] pub fn new (token_function : F , visitor : V) -> Self { Parser { token_function , visitor , } } fn next_actions (& self , state : usize , next_token : TokenType , next_data : T) -> Result < & 'static [Action] , ParserError < T , E >> { match (state , next_token) { (0usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (0usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (0usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (0usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (1usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule4 } ,]) , (1usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule4 } ,]) , (1usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule4 } ,]) , (1usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule4 } ,]) , (1usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule4 } ,]) , (1usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule4 } ,]) , (1usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (2usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule8 } ,]) , (2usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule8 } ,]) , (2usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule8 } ,]) , (2usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule8 } ,]) , (2usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule8 } ,]) , (2usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule8 } ,]) , (2usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (3usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule12 } ,]) , (3usize , TokenType :: TkSlash) => Ok (& [Action :: Shift ,]) , (3usize , TokenType :: TkStar) => Ok (& [Action :: Shift ,]) , (3usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule12 } ,]) , (3usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule12 } ,]) , (3usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule12 } ,]) , (3usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (4usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (4usize , TokenType :: TkPlus) => Ok (& [Action :: Shift ,]) , (4usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule14 } ,]) , (4usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (5usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule2 } ,]) , (5usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule2 } ,]) , (5usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule2 } ,]) , (5usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule2 } ,]) , (5usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule2 } ,]) , (5usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule2 } ,]) , (5usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (6usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule5 } ,]) , (6usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule5 } ,]) , (6usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule5 } ,]) , (6usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule5 } ,]) , (6usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule5 } ,]) , (6usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule5 } ,]) , (6usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (7usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule9 } ,]) , (7usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule9 } ,]) , (7usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule9 } ,]) , (7usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule9 } ,]) , (7usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule9 } ,]) , (7usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule9 } ,]) , (7usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (8usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule13 } ,]) , (8usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule13 } ,]) , (8usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule13 } ,]) , (8usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule13 } ,]) , (8usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (9usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (9usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (9usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (9usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (10usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (10usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (10usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (10usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (11usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule0 } ,]) , (11usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule0 } ,]) , (11usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule0 } ,]) , (11usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule0 } ,]) , (11usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule0 } ,]) , (11usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule0 } ,]) , (11usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (12usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (12usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (12usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (12usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (13usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (13usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (13usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (13usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (14usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (14usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (14usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (14usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (15usize , TokenType :: TkLpar) => Ok (& [Action :: Shift ,]) , (15usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (15usize , TokenType :: TkNumber) => Ok (& [Action :: Shift ,]) , (15usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] , }) , (16usize , TokenType :: TkRpar) => Ok (& [Action :: Shift ,]) , (16usize , TokenType :: TkMinus) => Ok (& [Action :: Shift ,]) , (16usize , TokenType :: TkPlus) => Ok (& [Action :: Shift ,]) , (16usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: TkRpar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (17usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule3 } ,]) , (17usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule3 } ,]) , (17usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule3 } ,]) , (17usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule3 } ,]) , (17usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule3 } ,]) , (17usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule3 } ,]) , (17usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (18usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule7 } ,]) , (18usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule7 } ,]) , (18usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule7 } ,]) , (18usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule7 } ,]) , (18usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule7 } ,]) , (18usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule7 } ,]) , (18usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (19usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule6 } ,]) , (19usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule6 } ,]) , (19usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule6 } ,]) , (19usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule6 } ,]) , (19usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule6 } ,]) , (19usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule6 } ,]) , (19usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (20usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule11 } ,]) , (20usize , TokenType :: TkSlash) => Ok (& [Action :: Shift ,]) , (20usize , TokenType :: TkStar) => Ok (& [Action :: Shift ,]) , (20usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule11 } ,]) , (20usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule11 } ,]) , (20usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule11 } ,]) , (20usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (21usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule10 } ,]) , (21usize , TokenType :: TkSlash) => Ok (& [Action :: Shift ,]) , (21usize , TokenType :: TkStar) => Ok (& [Action :: Shift ,]) , (21usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule10 } ,]) , (21usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule10 } ,]) , (21usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule10 } ,]) , (21usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (22usize , TokenType :: TkRpar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule1 } ,]) , (22usize , TokenType :: TkSlash) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule1 } ,]) , (22usize , TokenType :: TkStar) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule1 } ,]) , (22usize , TokenType :: TkMinus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule1 } ,]) , (22usize , TokenType :: TkPlus) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule1 } ,]) , (22usize , TokenType :: EndOfFile) => Ok (& [Action :: Reduce { rule : ReducedRule :: Rule1 } ,]) , (22usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , got_data : next_data , expected : vec ! [TokenType :: EndOfFile , TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] , }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } fn next_goto (& self , state : & usize , symbol : & StackSymbol) -> Option < Goto > { match (state , symbol) { (0usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 2usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Some (Goto :: State { state_id : 3usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) => Some (Goto :: State { state_id : 4usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtExpression }) => Some (Goto :: Accept) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Some (Goto :: State { state_id : 7usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) => Some (Goto :: State { state_id : 8usize }) , (3usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Some (Goto :: State { state_id : 12usize }) , (3usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Some (Goto :: State { state_id : 13usize }) , (4usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 14usize }) , (4usize , StackSymbol :: Terminal { token : TokenType :: TkPlus }) => Some (Goto :: State { state_id : 15usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 2usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Some (Goto :: State { state_id : 3usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) => Some (Goto :: State { state_id : 16usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Some (Goto :: State { state_id : 7usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) => Some (Goto :: State { state_id : 8usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 17usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 18usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (13usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (13usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (13usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 19usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 2usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Some (Goto :: State { state_id : 20usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Some (Goto :: State { state_id : 7usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Some (Goto :: State { state_id : 9usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 10usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Some (Goto :: State { state_id : 11usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Some (Goto :: State { state_id : 1usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Some (Goto :: State { state_id : 2usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Some (Goto :: State { state_id : 21usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Some (Goto :: State { state_id : 5usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Some (Goto :: State { state_id : 6usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Some (Goto :: State { state_id : 7usize }) , (16usize , StackSymbol :: Terminal { token : TokenType :: TkRpar }) => Some (Goto :: State { state_id : 22usize }) , (16usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Some (Goto :: State { state_id : 14usize }) , (16usize , StackSymbol :: Terminal { token : TokenType :: TkPlus }) => Some (Goto :: State { state_id : 15usize }) , (20usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Some (Goto :: State { state_id : 12usize }) , (20usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Some (Goto :: State { state_id : 13usize }) , (21usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Some (Goto :: State { state_id : 12usize }) , (21usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Some (Goto :: State { state_id : 13usize }) , (_ , _) => None , } } fn get_rule_reduction (& self , rule : & ReducedRule) -> (usize , StackSymbol) { match rule { ReducedRule :: Rule0 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) , ReducedRule :: Rule1 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) , ReducedRule :: Rule2 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) , ReducedRule :: Rule3 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) , ReducedRule :: Rule4 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) , ReducedRule :: Rule5 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) , ReducedRule :: Rule6 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) , ReducedRule :: Rule7 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) , ReducedRule :: Rule8 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) , ReducedRule :: Rule9 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) , ReducedRule :: Rule10 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) , ReducedRule :: Rule11 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) , ReducedRule :: Rule12 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) , ReducedRule :: Rule13 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) , ReducedRule :: Rule14 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtExpression }) } } # [doc = r" Passes every token through `filter` before the parser sees it, lexer errors"] # [doc = r" bypass the filter."] # [allow (dead_code)] pub fn with_filter < P : FnMut (& (TokenType , T)) -> FilterAction < T >> (self , filter : P ,) -> Parser < T , E , impl FnMut () -> Result < (TokenType , T) , E > , V > { Parser { token_function : filter_tokens (self . token_function , filter) , visitor : self . visitor , } } fn do_visit (& mut self , rule : & ReducedRule) { match rule { ReducedRule :: Rule2 => self . visitor . reduce_atom () , ReducedRule :: Rule5 => self . visitor . reduce_unary () , ReducedRule :: Rule9 => self . visitor . reduce_product () , ReducedRule :: Rule13 => self . visitor . reduce_sum () , ReducedRule :: Rule14 => self . visitor . reduce_expression () , ReducedRule :: Rule0 => self . visitor . reduce_atom_alt_1 () , ReducedRule :: Rule1 => self . visitor . reduce_atom_alt_2 () , ReducedRule :: Rule3 => self . visitor . reduce_unary_alt_1 () , ReducedRule :: Rule4 => self . visitor . reduce_unary_alt_2 () , ReducedRule :: Rule6 => self . visitor . reduce_product_alt_1 () , ReducedRule :: Rule7 => self . visitor . reduce_product_alt_2 () , ReducedRule :: Rule8 => self . visitor . reduce_product_alt_3 () , ReducedRule :: Rule10 => self . visitor . reduce_sum_alt_1 () , ReducedRule :: Rule11 => self . visitor . reduce_sum_alt_2 () , ReducedRule :: Rule12 => self . visitor . reduce_sum_alt_3 () } } pub fn parse (& mut self) -> Result < () , ParserError < T , E >> { let mut lookahead = std :: collections :: VecDeque :: new () ; lookahead . push_back ((self . token_function) () . map_err (| e | ParserError :: LexerError { inner : e }) ?) ; let root = GraphNode :: root () ; let stack = root . push (Some (0usize) , None) ; let mut stacks = vec ! [stack] ; while ! (stacks . len () == 1 && stacks [0] . is_root ()) { let (next_token , next_data) = lookahead . front () . unwrap () ; let reduced = self . apply_reduces (stacks , next_token , next_data) . map_err (combine_errors) ? ; let (next_token , next_data) = lookahead . pop_front () . unwrap () ; let new_symbol = StackSymbol :: Terminal { token : next_token } ; if matches ! (next_token , TokenType :: EndOfFile) { lookahead . push_back ((next_token , next_data . clone ())) ; } else { lookahead . push_back ((self . token_function) () . map_err (| e | ParserError :: LexerError { inner : e }) ?) ; } let mut new_stacks = if reduced . iter () . any (| s | s . top () . is_none ()) { let preferred = reduced . into_iter () . filter (| s | s . top () . is_none ()) . min_by (| a , b | compare_parses (& a . recorded () , & b . recorded ())) . unwrap () ; vec ! [preferred] } else { let mut new_stacks = Vec :: new () ; for stack in reduced { let state = * stack . top () . unwrap () ; match self . next_goto (& state , & new_symbol) { Some (Goto :: State { state_id }) => { stack . record (RecordedVisit :: Shift { token : next_token , data : next_data . clone () , }) ; let new_node = stack . push (Some (state_id) , Some (new_symbol)) ; new_stacks . push (new_node) ; } Some (Goto :: Accept) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) , None => () , } } new_stacks } ; debug_assert ! (! new_stacks . is_empty () , "all parse stacks were dropped without an error") ; if new_stacks . len () == 1 { let stack = new_stacks . pop () . unwrap () ; let recorded = stack . pop_recorded () ; for record in recorded { match record { RecordedVisit :: Reduce { rule } => self . do_visit (& rule) , RecordedVisit :: Shift { token , data } => { debug_assert ! (! matches ! (token , TokenType :: EndOfFile) , "EndOfFile must not be shifted") ; self . visitor . shift (token , data) } } } stacks = vec ! [stack] ; } else { stacks = new_stacks ; } } Ok (()) } fn apply_reduces (& mut self , stacks : Vec < Stack < T >> , next_token : & TokenType , next_data : & T) -> Result < Vec < Stack < T >> , Vec < ParserError < T , E >> > { let mut to_reduce = stacks ; let mut reduced = Vec :: new () ; while ! to_reduce . is_empty () { let mut errors = Vec :: new () ; let all_error_count = to_reduce . len () ; let mut new_to_reduce = Vec :: new () ; for stack in to_reduce { let state = * stack . top () . unwrap () ; match self . next_actions (state , * next_token , next_data . clone ()) { Ok (actions) => { for action in actions { match action { Action :: Reduce { rule : reduced_rule } => { self . apply_reduce (reduced_rule , & stack , & mut reduced , & mut new_to_reduce ,) ; } Action :: Shift => { reduced . push (stack . clone_and_fork_record ()) ; } } ; } } Err (e) => { errors . push (e) ; } } } if reduced . is_empty () && errors . len () == all_error_count { return Err (errors) ; } to_reduce = new_to_reduce ; } Ok (reduced) } fn apply_reduce (& mut self , reduced_rule : & ReducedRule , stack : & Stack < T > , accepted : & mut Vec < Stack < T >> , new_to_reduce : & mut Vec < Stack < T >> ,) { let (to_pop , reduced_symbol) = self . get_rule_reduction (reduced_rule) ; let stacks_to_push = stack . unwind_stacks (to_pop) ; for mut stack in stacks_to_push { stack . record (RecordedVisit :: Reduce { rule : * reduced_rule , }) ; for _ in 0 .. to_pop { let (_edge , new_stack) = stack . pop () ; stack = new_stack ; } let state = * stack . top () . unwrap () ; match self . next_goto (& state , & reduced_symbol) { Some (Goto :: State { state_id }) => { let new_node = stack . push (Some (state_id) , Some (reduced_symbol)) ; new_to_reduce . push (new_node) ; } Some (Goto :: Accept) => { accepted . push (stack . accepted ()) ; } None => () , } } } } # [doc = r" What a token filter does with a token before the parser sees it."] # [allow (dead_code)] pub enum FilterAction < T > { # [doc = r" Passes the token on."] Keep , # [doc = r" Drops the token."] Drop , # [doc = r" Passes another token on in place of this one."] Replace (TokenType , T) , # [doc = r" Passes these tokens on before the token itself."] Inject (Vec < (TokenType , T) >) , } fn apply_filter < T , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (filter : & mut P , token : (TokenType , T) , pending : & mut std :: collections :: VecDeque < (TokenType , T) > ,) { let is_end = matches ! (token . 0 , TokenType :: EndOfFile) ; match filter (& token) { FilterAction :: Keep => pending . push_back (token) , FilterAction :: Drop => { if is_end { pending . push_back (token) ; } } FilterAction :: Replace (replacement , data) => { pending . push_back ((replacement , data)) ; if is_end { pending . push_back (token) ; } } FilterAction :: Inject (tokens) => { pending . extend (tokens) ; pending . push_back (token) ; } } } # [doc = r" Wraps a token function, so that every token is passed through `filter` first."] # [allow (dead_code)] pub fn filter_tokens < T , E , F : FnMut () -> Result < (TokenType , T) , E > , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (mut token_function : F , mut filter : P ,) -> impl FnMut () -> Result < (TokenType , T) , E > { let mut pending = std :: collections :: VecDeque :: new () ; move || loop { if let Some (token) = pending . pop_front () { return Ok (token) ; } apply_filter (& mut filter , token_function () ? , & mut pending) ; } } # [doc = r" Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`"] # [doc = r" once the iterator is exhausted."] # [allow (dead_code)] pub fn token_function_from_iter < T : Default , E , I : IntoIterator < Item = Result < (TokenType , T) , E >> > (tokens : I ,) -> impl FnMut () -> Result < (TokenType , T) , E > { let mut tokens = tokens . into_iter () ; move || { tokens . next () . unwrap_or_else (|| Ok ((TokenType :: EndOfFile , T :: default ()))) } } # [doc = r" Orders the parses of an ambiguous input by their recorded visits, independent of the"] # [doc = r" order the stacks were forked in. At the first visit two parses differ in, the one"] # [doc = r" which reduces is preferred over the one which shifts, and of two reductions the one"] # [doc = r" of the rule which comes first in the grammar."] fn compare_parses < T > (a : & [RecordedVisit < T >] , b : & [RecordedVisit < T >]) -> std :: cmp :: Ordering { let key = | visit : & RecordedVisit < T > | match visit { RecordedVisit :: Reduce { rule } => (0 , Some (* rule)) , RecordedVisit :: Shift { .. } => (1 , None) , } ; a . iter () . map (key) . cmp (b . iter () . map (key)) } fn combine_errors < T , E : std :: error :: Error > (mut errors : Vec < ParserError < T , E >>) -> ParserError < T , E > { let other_error = errors . iter () . position (| e | ! matches ! (e , ParserError :: UnexpectedToken { .. })) ; if let Some (index) = other_error { return errors . swap_remove (index) ; } match errors . len () { 1 => errors . pop () . unwrap () , 0 => unreachable ! () , _ => { let (got , expected) : (Vec < (TokenType , T) > , Vec < Vec < TokenType >>) = errors . into_iter () . map (| e | match e { ParserError :: UnexpectedToken { got , got_data , expected , } => ((got , got_data) , expected) , _ => unreachable ! () , }) . unzip () ; ParserError :: UnexpectedTokens { got , expected } } } } use gss :: GraphNode ; mod gss { use std :: { cell :: { Ref , RefCell } , rc :: Rc , } ; pub struct GraphNode < N , E , R > { inner : Rc < RefCell < GraphNodeInner < N , E , R >> > , recorded : Rc < RefCell < Vec < R >> > , } impl < N : Clone , E : Clone , R : Clone > GraphNode < N , E , R > { pub fn clone_and_fork_record (& self) -> Self { GraphNode { inner : self . inner . clone () , recorded : Rc :: new (RefCell :: new (self . recorded . borrow () . clone ())) , } } pub fn unwind_stacks (& self , depth : usize) -> Vec < Self > { if depth == 0 { return vec ! [self . clone_and_fork_record ()] ; } let mut resulting_parents = Vec :: new () ; let value = self . top () . map (| r | r . clone ()) ; for (edge , neighbor) in self . neighbors () . iter () { let new_parents = neighbor . unwind_stacks (depth - 1) ; for parent in new_parents { let mut new_node = parent . push (value . clone () , edge . clone ()) ; new_node . recorded = self . recorded . clone () ; resulting_parents . push (new_node . clone_and_fork_record ()) ; } } resulting_parents } } impl < N , E , R > GraphNode < N , E , R > { pub fn root () -> Self { GraphNode { inner : Rc :: new (RefCell :: new (GraphNodeInner { node_value : None , neighbors : vec ! [] , })) , recorded : Rc :: new (RefCell :: new (Vec :: new ())) , } } fn add_edge (& mut self , value : Option < E > , predecessor : GraphNode < N , E , R >) { self . inner . borrow_mut () . neighbors . push ((value , predecessor)) ; } pub fn top (& self) -> Option < Ref < '_ , N >> { Ref :: filter_map (self . inner . borrow () , | i | i . node_value . as_ref ()) . ok () } fn neighbors (& self) -> Ref < '_ , [Edge < N , E , R >] > { Ref :: map (self . inner . borrow () , | i | i . neighbors . as_slice ()) } pub fn pop (self) -> (Option < E > , Self) { let neighbors = & mut self . inner . borrow_mut () . neighbors ; assert_eq ! (neighbors . len () , 1 , "Tried to pop from stack branch with more/less than one predecessor") ; if let Some ((e , mut node)) = neighbors . pop () { node . recorded = self . recorded ; (e , node) } else { panic ! ("Tried to pop from stack branch with zero predecessors") ; } } pub fn recorded (& self) -> Ref < '_ , [R] > { Ref :: map (self . recorded . borrow () , | recorded | recorded . as_slice ()) } pub fn accepted (& self) -> Self { self . new_with_same_record (None) } pub fn pop_recorded (& self) -> Vec < R > { return self . recorded . borrow_mut () . split_off (0) ; } pub fn record (& self , record : R) { self . recorded . borrow_mut () . push (record) ; } pub fn is_root (& self) -> bool { self . inner . borrow () . node_value . is_none () } pub fn push (self , value : Option < N > , edge : Option < E >) -> GraphNode < N , E , R > { let mut new_node = self . new_with_same_record (value) ; new_node . add_edge (edge , self) ; new_node } fn new_with_same_record (& self , node_value : Option < N >) -> Self { GraphNode { inner : Rc :: new (RefCell :: new (GraphNodeInner { node_value , neighbors : vec ! [] , })) , recorded : self . recorded . clone () , } } } type Edge < N , E , R > = (Option < E > , GraphNode < N , E , R >) ; struct GraphNodeInner < N , E , R > { node_value : Option < N > , neighbors : Vec < Edge < N , E , R >> , } }
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,6 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ;pub trait Visitor < T > { fn shift (& mut self , token : TokenType , data : T) ; # [doc = "atom(0) -> <atom_alt>(8)"] fn reduce_atom (& mut self) ; # [doc = "unary(1) -> <unary_alt>(9)"] fn reduce_unary (& mut self) ; # [doc = "product(2) -> <product_alt>(10)"] fn reduce_product (& mut self) ; # [doc = "sum(3) -> <sum_alt>(11)"] fn reduce_sum (& mut self) ; # [doc = "expression(4) -> sum(3)"] fn reduce_expression (& mut self) ; # [doc = "<atom_alt>(8) -> NUMBER(7)"] fn reduce_atom_alt_1 (& mut self) ; # [doc = "<atom_alt>(8) -> LPAR(2) sum(3) RPAR(1)"] fn reduce_atom_alt_2 (& mut self) ; # [doc = "<unary_alt>(9) -> MINUS(5) unary(1)"] fn reduce_unary_alt_1 (& mut self) ; # [doc = "<unary_alt>(9) -> atom(0)"] fn reduce_unary_alt_2 (& mut self) ; # [doc = "<product_alt>(10) -> product(2) STAR(4) unary(1)"] fn reduce_product_alt_1 (& mut self) ; # [doc = "<product_alt>(10) -> product(2) SLASH(3) unary(1)"] fn reduce_product_alt_2 (& mut self) ; # [doc = "<product_alt>(10) -> unary(1)"] fn reduce_product_alt_3 (& mut self) ; # [doc = "<sum_alt>(11) -> sum(3) PLUS(6) product(2)"] fn reduce_sum_alt_1 (& mut self) ; # [doc = "<sum_alt>(11) -> sum(3) MINUS(5) product(2)"] fn reduce_sum_alt_2 (& mut self) ; # [doc = "<sum_alt>(11) -> product(2)"] fn reduce_sum_alt_3 (& mut self) ; }pub struct Parser < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> { token_function : F , visitor : V , } # [derive (Debug , Clone , Copy)] # [allow (clippy :: enum_variant_names)] enum NonTerminalType { NtAtom , NtUnary , NtProduct , NtSum , NtExpression , NtAtomAlt , NtUnaryAlt , NtProductAlt , NtSumAlt } # [derive (Debug , Clone , Copy)] enum StackSymbol { Terminal { token : TokenType } , NonTerminal { non_terminal : NonTerminalType } , State { state_id : usize } , } # [derive (Clone , Copy)] enum ReducedRule { Rule0 , Rule1 , Rule2 , Rule3 , Rule4 , Rule5 , Rule6 , Rule7 , Rule8 , Rule9 , Rule10 , Rule11 , Rule12 , Rule13 , Rule14 } enum Action { Shift , Reduce { rule : ReducedRule } } enum Goto { Accept , State { state_id : usize } } # [derive (Debug , Clone)] pub enum ParserError { UnexpectedToken { got : TokenType , expected : Vec < TokenType > } , } impl std :: error :: Error for ParserError { } impl std :: fmt :: Display for ParserError { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { ParserError :: UnexpectedToken { got , expected } => write ! (f , "Unexpected token {:?}, expected one of: {:?}" , got , expected) , } } } impl < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> Parser < T , F , V > { # [doc = r" The token function is not called again once it returned `TokenType::EndOfFile`,"] # [doc = r" and `EndOfFile` is never passed to `Visitor::shift`."] pub fn new (token_function : F , visitor : V) -> Self { Parser { token_function , visitor , } } fn next_action (& self , state : usize , next_token : TokenType) -> Result < Action , ParserError > { match (state , next_token) { (0usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (0usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (0usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (0usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (1usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (1usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (1usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (1usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (1usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (1usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (2usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (2usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (2usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (2usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (2usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (2usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (3usize , TokenType :: TkSlash) => Ok (Action :: Shift) , (3usize , TokenType :: TkStar) => Ok (Action :: Shift) , (3usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (3usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (3usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (3usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (4usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (4usize , TokenType :: TkPlus) => Ok (Action :: Shift) , (4usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule14 }) , (4usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (5usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (5usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (5usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (5usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (5usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (5usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (6usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (6usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (6usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (6usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (6usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (6usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (7usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (7usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (7usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (7usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (7usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (7usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (8usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (8usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (8usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (8usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (9usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (9usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (9usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (9usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (10usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (10usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (10usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (10usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (11usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (12usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (12usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (12usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (12usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (13usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (13usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (13usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (13usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (14usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (14usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (14usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (14usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (15usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (15usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (15usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (15usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (16usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (16usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (16usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (16usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (16usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (16usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (17usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (17usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (17usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (17usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (17usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (17usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (18usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (18usize , TokenType :: TkSlash) => Ok (Action :: Shift) , (18usize , TokenType :: TkStar) => Ok (Action :: Shift) , (18usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (18usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (18usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (19usize , TokenType :: TkRpar) => Ok (Action :: Shift) , (19usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (19usize , TokenType :: TkPlus) => Ok (Action :: Shift) , (19usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (20usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (20usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (20usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (20usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (20usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (20usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (21usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (21usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (21usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (21usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (21usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (21usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (22usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (22usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (22usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (22usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (22usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (22usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (23usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (23usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (23usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (23usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (24usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (24usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (24usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (24usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (25usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (25usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (25usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (25usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (26usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (26usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (26usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (26usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (26usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (26usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (27usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (27usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (27usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (27usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (27usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (27usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (28usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (28usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (28usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (28usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (28usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (28usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (29usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (29usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (29usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (29usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (29usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (29usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (30usize , TokenType :: TkSlash) => Ok (Action :: Shift) , (30usize , TokenType :: TkStar) => Ok (Action :: Shift) , (30usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (30usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (30usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (30usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (31usize , TokenType :: TkSlash) => Ok (Action :: Shift) , (31usize , TokenType :: TkStar) => Ok (Action :: Shift) , (31usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (31usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (31usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (31usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (32usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (32usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (32usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (32usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (33usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (33usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (33usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (33usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (34usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (34usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (34usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (34usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (34usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (34usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus , TokenType :: EndOfFile] }) , (35usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (35usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (35usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (35usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (36usize , TokenType :: TkLpar) => Ok (Action :: Shift) , (36usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (36usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (36usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkLpar , TokenType :: TkMinus , TokenType :: TkNumber] }) , (37usize , TokenType :: TkRpar) => Ok (Action :: Shift) , (37usize , TokenType :: TkMinus) => Ok (Action :: Shift) , (37usize , TokenType :: TkPlus) => Ok (Action :: Shift) , (37usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (38usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (38usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (38usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (38usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (38usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (38usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (39usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (39usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (39usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (39usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (39usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (39usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (40usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (40usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (40usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (40usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (40usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (40usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (41usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (41usize , TokenType :: TkSlash) => Ok (Action :: Shift) , (41usize , TokenType :: TkStar) => Ok (Action :: Shift) , (41usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (41usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (41usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (42usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (42usize , TokenType :: TkSlash) => Ok (Action :: Shift) , (42usize , TokenType :: TkStar) => Ok (Action :: Shift) , (42usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (42usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (42usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (43usize , TokenType :: TkRpar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (43usize , TokenType :: TkSlash) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (43usize , TokenType :: TkStar) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (43usize , TokenType :: TkMinus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (43usize , TokenType :: TkPlus) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (43usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRpar , TokenType :: TkSlash , TokenType :: TkStar , TokenType :: TkMinus , TokenType :: TkPlus] }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } fn next_goto (& self , state : usize , symbol : StackSymbol , next_token : TokenType) -> Result < Goto , ParserError > { match (state , symbol) { (0usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 9usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 10usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 11usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 1usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 2usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 3usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) => Ok (Goto :: State { state_id : 4usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtExpression }) => Ok (Goto :: Accept) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 5usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 6usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 7usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) => Ok (Goto :: State { state_id : 8usize }) , (3usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Ok (Goto :: State { state_id : 12usize }) , (3usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Ok (Goto :: State { state_id : 13usize }) , (4usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 14usize }) , (4usize , StackSymbol :: Terminal { token : TokenType :: TkPlus }) => Ok (Goto :: State { state_id : 15usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 17usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 18usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) => Ok (Goto :: State { state_id : 19usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 22usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) => Ok (Goto :: State { state_id : 23usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 9usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 10usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 11usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 1usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 27usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 5usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 6usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 9usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 10usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 11usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 1usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 28usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 5usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 6usize }) , (13usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 9usize }) , (13usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 10usize }) , (13usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 11usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 1usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 29usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 5usize }) , (13usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 6usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 9usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 10usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 11usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 1usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 2usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 30usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 5usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 6usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 7usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 9usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 10usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 11usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 1usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 2usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 31usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 5usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 6usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 7usize }) , (18usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Ok (Goto :: State { state_id : 32usize }) , (18usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Ok (Goto :: State { state_id : 33usize }) , (19usize , StackSymbol :: Terminal { token : TokenType :: TkRpar }) => Ok (Goto :: State { state_id : 34usize }) , (19usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 35usize }) , (19usize , StackSymbol :: Terminal { token : TokenType :: TkPlus }) => Ok (Goto :: State { state_id : 36usize }) , (24usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (24usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (24usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 17usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 18usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) => Ok (Goto :: State { state_id : 37usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 22usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) => Ok (Goto :: State { state_id : 23usize }) , (25usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (25usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (25usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (25usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (25usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 38usize }) , (25usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (25usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Ok (Goto :: State { state_id : 12usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Ok (Goto :: State { state_id : 13usize }) , (31usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Ok (Goto :: State { state_id : 12usize }) , (31usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Ok (Goto :: State { state_id : 13usize }) , (32usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (32usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (32usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (32usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (32usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 39usize }) , (32usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (32usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (33usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (33usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (33usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (33usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (33usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 40usize }) , (33usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (33usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (35usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (35usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (35usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 17usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 41usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 22usize }) , (36usize , StackSymbol :: Terminal { token : TokenType :: TkLpar }) => Ok (Goto :: State { state_id : 24usize }) , (36usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 25usize }) , (36usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 26usize }) , (36usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) => Ok (Goto :: State { state_id : 16usize }) , (36usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) => Ok (Goto :: State { state_id : 17usize }) , (36usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) => Ok (Goto :: State { state_id : 42usize }) , (36usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) => Ok (Goto :: State { state_id : 20usize }) , (36usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) => Ok (Goto :: State { state_id : 21usize }) , (36usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) => Ok (Goto :: State { state_id : 22usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkRpar }) => Ok (Goto :: State { state_id : 43usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkMinus }) => Ok (Goto :: State { state_id : 35usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkPlus }) => Ok (Goto :: State { state_id : 36usize }) , (41usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Ok (Goto :: State { state_id : 32usize }) , (41usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Ok (Goto :: State { state_id : 33usize }) , (42usize , StackSymbol :: Terminal { token : TokenType :: TkSlash }) => Ok (Goto :: State { state_id : 32usize }) , (42usize , StackSymbol :: Terminal { token : TokenType :: TkStar }) => Ok (Goto :: State { state_id : 33usize }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } # [doc = r" Passes every token through `filter` before the parser sees it."] # [allow (dead_code)] pub fn with_filter < P : FnMut (& (TokenType , T)) -> FilterAction < T >> (self , filter : P ,) -> Parser < T , impl FnMut () -> (TokenType , T) , V > { Parser { token_function : filter_tokens (self . token_function , filter) , visitor : self . visitor , } } fn reduce_stack_and_visit (& mut self , rule : ReducedRule , stack : & mut Vec < StackSymbol > , state : usize , next_token : TokenType) -> Result < () , ParserError > { let (to_pop , reduced) = match rule { ReducedRule :: Rule0 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) , ReducedRule :: Rule1 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtomAlt }) , ReducedRule :: Rule2 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtAtom }) , ReducedRule :: Rule3 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) , ReducedRule :: Rule4 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnaryAlt }) , ReducedRule :: Rule5 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtUnary }) , ReducedRule :: Rule6 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) , ReducedRule :: Rule7 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) , ReducedRule :: Rule8 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProductAlt }) , ReducedRule :: Rule9 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProduct }) , ReducedRule :: Rule10 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) , ReducedRule :: Rule11 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) , ReducedRule :: Rule12 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSumAlt }) , ReducedRule :: Rule13 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSum }) , ReducedRule :: Rule14 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtExpression }) } ; if stack . len () < to_pop * 2 { unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } stack . truncate (stack . len () - to_pop * 2) ; stack . push (reduced) ; match rule { ReducedRule :: Rule2 => self . visitor . reduce_atom () , ReducedRule :: Rule5 => self . visitor . reduce_unary () , ReducedRule :: Rule9 => self . visitor . reduce_product () , ReducedRule :: Rule13 => self . visitor . reduce_sum () , ReducedRule :: Rule14 => self . visitor . reduce_expression () , ReducedRule :: Rule0 => self . visitor . reduce_atom_alt_1 () , ReducedRule :: Rule1 => self . visitor . reduce_atom_alt_2 () , ReducedRule :: Rule3 => self . visitor . reduce_unary_alt_1 () , ReducedRule :: Rule4 => self . visitor . reduce_unary_alt_2 () , ReducedRule :: Rule6 => self . visitor . reduce_product_alt_1 () , ReducedRule :: Rule7 => self . visitor . reduce_product_alt_2 () , ReducedRule :: Rule8 => self . visitor . reduce_product_alt_3 () , ReducedRule :: Rule10 => self . visitor . reduce_sum_alt_1 () , ReducedRule :: Rule11 => self . visitor . reduce_sum_alt_2 () , ReducedRule :: Rule12 => self . visitor . reduce_sum_alt_3 () } Ok (()) } # [doc = r" Parses the whole input and returns its errors. The grammar has no error"] # [doc = r" productions, so parsing stops at the first error."] # [allow (dead_code)] pub fn parse_all (& mut self) -> (Vec < ParserError > , ()) { match self . parse () { Ok (()) => (Vec :: new () , ()) , Err (error) => (vec ! [error] , ()) , } } pub fn parse (& mut self) -> Result < () , ParserError > { let mut lookahead = std :: collections :: VecDeque :: new () ; lookahead . push_back ((self . token_function) ()) ; let mut stack = Vec :: new () ; stack . push (StackSymbol :: State { state_id : 0usize }) ; let mut state = 0usize ; while ! stack . is_empty () { let (next_token , _) = lookahead . front () . unwrap () ; let next_token = * next_token ; let action = self . next_action (state , next_token) ? ; match action { Action :: Shift => { let (next_token , next_data) = lookahead . pop_front () . unwrap () ; debug_assert ! (! matches ! (next_token , TokenType :: EndOfFile) , "EndOfFile must not be shifted") ; stack . push (StackSymbol :: Terminal { token : next_token }) ; self . visitor . shift (next_token , next_data) ; lookahead . push_back ((self . token_function) ()) ; } Action :: Reduce { rule : reduced_rule } => { self . reduce_stack_and_visit (reduced_rule , & mut stack , state , next_token) ? ; } } let (goto_state , current_symbol) = match stack . as_slice () { [.. , StackSymbol :: State { state_id } , current_symbol] => (* state_id , * current_symbol) , _ => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } ; let goto = self . next_goto (goto_state , current_symbol , next_token) ? ; match goto { Goto :: Accept => { stack . pop () ; stack . pop () ; } Goto :: State { state_id } => { stack . push (StackSymbol :: State { state_id }) ; state = state_id ; } } } Ok (()) } } # [doc = r" What a token filter does with a token before the parser sees it."] # [allow (dead_code)] pub enum FilterAction < T > { # [doc = r" Passes the token on."] Keep , # [doc = r" Drops the token."] Drop , # [doc = r" Passes another token on in place of this one."] Replace (TokenType , T) , # [doc = r" Passes these tokens on before the token itself."] Inject (Vec < (TokenType , T) >) , } fn apply_filter < T , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (filter : & mut P , token : (TokenType , T) , pending : & mut std :: collections :: VecDeque < (TokenType , T) > ,) { let is_end = matches ! (token . 0 , TokenType :: EndOfFile) ; match filter (& token) { FilterAction :: Keep => pending . push_back (token) , FilterAction :: Drop => { if is_end { pending . push_back (token) ; } } FilterAction :: Replace (replacement , data) => { pending . push_back ((replacement , data)) ; if is_end { pending . push_back (token) ; } } FilterAction :: Inject (tokens) => { pending . extend (tokens) ; pending . push_back (token) ; } } } # [doc = r" Wraps a token function, so that every token is passed through `filter` first."] # [allow (dead_code)] pub fn filter_tokens < T , F : FnMut () -> (TokenType , T) , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (mut token_function : F , mut filter : P ,) -> impl FnMut () -> (TokenType , T) { let mut pending = std :: collections :: VecDeque :: new () ; move || loop { if let Some (token) = pending . pop_front () { return token ; } apply_filter (& mut filter , token_function () , & mut pending) ; } } # [doc = r" Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`"] # [doc = r" once the iterator is exhausted."] # [allow (dead_code)] pub fn token_function_from_iter < T : Default , I : IntoIterator < Item = (TokenType , T) >> (tokens : I ,) -> impl FnMut () -> (TokenType , T) { let mut tokens = tokens . into_iter () ; move || { tokens . next () . unwrap_or_else (|| (TokenType :: EndOfFile , T :: default ())) } }
+// <lapex:keep name="code">
+// </lapex:keep>
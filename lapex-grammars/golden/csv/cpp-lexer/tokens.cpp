@@ -0,0 +1,32 @@
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    const char *get_token_name(TokenType tk_type) {
+        switch (tk_type) {
+case TokenType::TK_ERR:
+return "<ERR>";
+case TokenType::TK_EOF:
+return "<EOF>";
+case TokenType::TK_WHITESPACE:
+return "WHITESPACE";
+case TokenType::TK_FIELD:
+return "FIELD";
+case TokenType::TK_QUOTED:
+return "QUOTED";
+case TokenType::TK_NEWLINE:
+return "NEWLINE";
+case TokenType::TK_COMMA:
+return "COMMA";
+default:
+return nullptr;
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,29 @@
+#ifndef LAPEX_TOKENS_H
+#define LAPEX_TOKENS_H
+
+#include <cstdint>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    enum class TokenType : uint32_t
+    {
+        TK_ERR = 0,
+        TK_EOF = 1,
+        TK_WHITESPACE,
+TK_FIELD,
+TK_QUOTED,
+TK_NEWLINE,
+TK_COMMA,
+
+    };
+    
+    const char *get_token_name(TokenType tk_type);
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
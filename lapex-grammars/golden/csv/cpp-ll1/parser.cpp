@@ -0,0 +1,276 @@
+#include "parser_impl.h"
+
+#include <sstream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser {
+    void push_production_from_table(Symbol non_terminal, const lexer::TokenType* lookahead, ParseStack& parse_stack) {
+        switch(non_terminal.identifier) {
+case 0: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD_ALT)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'field'. Expected one of: FIELD, QUOTED");
+}
+break;
+}
+case 1: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_COMMA: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push(sym1);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'record'. Expected one of: <EOF>, FIELD, QUOTED, NEWLINE, COMMA");
+}
+break;
+}
+case 2: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_COMMA: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push(sym1);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'records'. Expected one of: <EOF>, FIELD, QUOTED, NEWLINE, COMMA");
+}
+break;
+}
+case 3: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_COMMA: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'file'. Expected one of: <EOF>, FIELD, QUOTED, NEWLINE, COMMA");
+}
+break;
+}
+case 5: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_FIELD)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_QUOTED)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: FIELD, QUOTED");
+}
+break;
+}
+case 6: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_COMMA: {
+// epsilon; push nothing to stack
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, FIELD, QUOTED, NEWLINE, COMMA");
+}
+break;
+}
+case 7: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_COMMA: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT_2)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_COMMA)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, NEWLINE, COMMA");
+}
+break;
+}
+case 8: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_FIELD: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_QUOTED: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_COMMA: {
+// epsilon; push nothing to stack
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, FIELD, QUOTED, NEWLINE, COMMA");
+}
+break;
+}
+case 9: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_NEWLINE)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, NEWLINE");
+}
+break;
+}
+}
+
+    }
+
+
+    void throw_unexpected_token_error(lexer::TokenType expected, lexer::TokenType got) {
+        std::ostringstream os;
+        os << "Unexpected token '" << lexer::get_token_name(got) << "', expected token '" << lexer::get_token_name(expected) << "'";
+        throw std::runtime_error(os.str());
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,166 @@
+#ifndef LAPEX_PARSER_IMPL_H
+#define LAPEX_PARSER_IMPL_H
+
+#include "parser.h"
+#include <deque>
+#include <stack>
+#include <vector>
+
+#include <iostream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class E>
+    using Vector = std::vector<E, std::allocator_traits<Allocator>::rebind_alloc<E>>;
+
+    template <class E>
+    using Deque = std::deque<E, std::allocator_traits<Allocator>::rebind_alloc<E>>;
+
+    enum class SymbolKind : uint8_t
+    {
+        Terminal,
+        NonTerminal,
+        ExitNonTerminal
+    };
+
+    struct Symbol
+    {
+        SymbolKind kind;
+        uint32_t identifier;
+    };
+
+    using ParseStack = std::stack<Symbol, Deque<Symbol>>;
+    
+    // the number of tokens the table looks at to choose a production
+    constexpr size_t LOOKAHEAD_LENGTH = 1;
+
+    void push_production_from_table(Symbol non_terminal, const lexer::TokenType *lookahead, ParseStack &parse_stack);
+
+    void throw_unexpected_token_error(lexer::TokenType expected, lexer::TokenType got);
+    
+    enum class NonTerminalType : uint32_t
+    {
+        NT_FIELD = 0,
+NT_RECORD = 1,
+NT_RECORDS = 2,
+NT_FILE = 3,
+NT_FIELD_ALT = 5,
+NT_RECORD_OPT = 6,
+NT_RECORD_STAR = 7,
+NT_RECORD_OPT_2 = 8,
+NT_RECORDS_STAR = 9,
+
+    };
+    
+    template <class T>
+    void exit_visitor(Visitor<T>& visitor, NonTerminalType non_terminal)
+    {
+        switch (non_terminal) {
+case NonTerminalType::NT_FIELD:
+visitor.exit_field();
+break;
+case NonTerminalType::NT_RECORD:
+visitor.exit_record();
+break;
+case NonTerminalType::NT_RECORDS:
+visitor.exit_records();
+break;
+case NonTerminalType::NT_FILE:
+visitor.exit_file();
+break;
+default:
+break;
+}
+
+    }
+
+    template <class T>
+    void enter_visitor(Visitor<T>& visitor, NonTerminalType non_terminal)
+    {
+        switch (non_terminal) {
+case NonTerminalType::NT_FIELD:
+visitor.enter_field();
+break;
+case NonTerminalType::NT_RECORD:
+visitor.enter_record();
+break;
+case NonTerminalType::NT_RECORDS:
+visitor.enter_records();
+break;
+case NonTerminalType::NT_FILE:
+visitor.enter_file();
+break;
+default:
+break;
+}
+
+    }
+
+    template <class T>
+    Parser<T>::Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor, Allocator allocator) : token_function(token_function), visitor(visitor), allocator(allocator) {}
+
+    template <class T>
+    void Parser<T>::parse()
+    {
+        Deque<Token<T>> lookahead(this->allocator);
+        lexer::TokenType lookahead_tokens[LOOKAHEAD_LENGTH];
+
+        ParseStack parse_stack(this->allocator);
+        Symbol end{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_EOF)};
+        parse_stack.push(end);
+        Symbol entry{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FILE)};
+        parse_stack.push(entry);
+
+        while (parse_stack.size() > 0)
+        {
+            // keep the buffer filled, repeating the end of input instead of reading past it
+            while (lookahead.size() < LOOKAHEAD_LENGTH)
+            {
+                if (!lookahead.empty() && lookahead.back().first == lexer::TokenType::TK_EOF)
+                {
+                    lookahead.push_back(lookahead.back());
+                }
+                else
+                {
+                    lookahead.push_back(this->token_function());
+                }
+            }
+            Symbol current = parse_stack.top();
+            parse_stack.pop();
+            auto lookahead_token_and_data = lookahead.front();
+            lexer::TokenType lookahead_tk = lookahead_token_and_data.first;
+            if (current.kind == SymbolKind::ExitNonTerminal)
+            {
+                exit_visitor(this->visitor, static_cast<NonTerminalType>(current.identifier));
+            }
+            else if (current.kind != SymbolKind::Terminal)
+            {
+                Symbol nt_exit_symbol{SymbolKind::ExitNonTerminal, current.identifier};
+                parse_stack.push(nt_exit_symbol);
+                for (size_t i = 0; i < LOOKAHEAD_LENGTH; i++)
+                {
+                    lookahead_tokens[i] = lookahead[i].first;
+                }
+                push_production_from_table(current, lookahead_tokens, parse_stack);
+                enter_visitor(this->visitor, static_cast<NonTerminalType>(current.identifier));
+            }
+            else
+            {
+                if (current.identifier != static_cast<uint32_t>(lookahead_tk))
+                {
+                    throw_unexpected_token_error(static_cast<lexer::TokenType>(current.identifier), lookahead_tk);
+                }
+                this->visitor.token(lookahead_tk, lookahead_token_and_data.second);
+                lookahead.pop_front();
+            }
+        }
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
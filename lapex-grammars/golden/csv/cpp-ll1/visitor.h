@@ -0,0 +1,31 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void token(lexer::TokenType tk_type, T data) = 0;
+        virtual void enter_field() = 0;
+virtual void exit_field() = 0;
+virtual void enter_record() = 0;
+virtual void exit_record() = 0;
+virtual void enter_records() = 0;
+virtual void exit_records() = 0;
+virtual void enter_file() = 0;
+virtual void exit_file() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
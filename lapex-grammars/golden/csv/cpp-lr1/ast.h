@@ -0,0 +1,191 @@
+#ifndef LAPEX_AST_H
+#define LAPEX_AST_H
+
+#include "visitor.h"
+#include <memory>
+#include <utility>
+#include <variant>
+#include <vector>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    namespace ast
+    {
+        template <class T>
+        struct Terminal
+        {
+            lexer::TokenType type;
+            T data;
+        };
+
+        template <class T>
+struct FieldNode;
+template <class T>
+struct RecordNode;
+template <class T>
+struct RecordsNode;
+template <class T>
+struct FileNode;
+
+
+        template <class T>
+        using Node = std::variant<Terminal<T>, std::unique_ptr<FieldNode<T>>, std::unique_ptr<RecordNode<T>>, std::unique_ptr<RecordsNode<T>>, std::unique_ptr<FileNode<T>>>;
+
+        // field(0) -> <field_alt>(5)
+template <class T>
+struct FieldNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// record(1) -> <record_opt>(6) <record_star>(7)
+template <class T>
+struct RecordNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// records(2) -> record(1) <records_star>(9)
+template <class T>
+struct RecordsNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// file(3) -> records(2)
+template <class T>
+struct FileNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+
+
+        // Builds a tree while parsing. Anonymous non-terminals like repetitions have no node type,
+        // their children are added to the node of the enclosing production instead.
+        template <class T>
+        class TreeBuilder : public Visitor<T>
+        {
+        private:
+            // the nodes produced by each symbol on the parse stack
+            std::vector<std::vector<Node<T>>> stack;
+
+            std::vector<Node<T>> pop_children(size_t symbols)
+            {
+                std::vector<Node<T>> children;
+                auto first = stack.end() - symbols;
+                for (auto it = first; it != stack.end(); ++it)
+                {
+                    for (auto &node : *it)
+                    {
+                        children.push_back(std::move(node));
+                    }
+                }
+                stack.erase(first, stack.end());
+                return children;
+            }
+
+            template <class N>
+            void reduce_named(size_t symbols, uint32_t alternative)
+            {
+                auto node = std::make_unique<N>();
+                node->alternative = alternative;
+                node->children = pop_children(symbols);
+                std::vector<Node<T>> nodes;
+                nodes.push_back(std::move(node));
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_anonymous(size_t symbols)
+            {
+                stack.push_back(pop_children(symbols));
+            }
+
+        public:
+            void shift(lexer::TokenType tk_type, T data) override
+            {
+                std::vector<Node<T>> nodes;
+                nodes.push_back(Terminal<T>{tk_type, std::move(data)});
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_field() override
+{
+reduce_named<FieldNode<T>>(1, 1);
+}
+void reduce_record() override
+{
+reduce_named<RecordNode<T>>(2, 1);
+}
+void reduce_records() override
+{
+reduce_named<RecordsNode<T>>(2, 1);
+}
+void reduce_file() override
+{
+reduce_named<FileNode<T>>(1, 1);
+}
+void reduce_field_alt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_field_alt_2() override
+{
+reduce_anonymous(1);
+}
+void reduce_record_opt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_record_opt_2() override
+{
+reduce_anonymous(0);
+}
+void reduce_record_star_1() override
+{
+reduce_anonymous(0);
+}
+void reduce_record_star_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_record_opt_2_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_record_opt_2_2() override
+{
+reduce_anonymous(0);
+}
+void reduce_records_star_1() override
+{
+reduce_anonymous(0);
+}
+void reduce_records_star_2() override
+{
+reduce_anonymous(3);
+}
+
+
+            // Returns the node of the entry production once the input has been parsed.
+            Node<T> take_root()
+            {
+                Node<T> root = std::move(stack.back().front());
+                stack.clear();
+                return root;
+            }
+        };
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
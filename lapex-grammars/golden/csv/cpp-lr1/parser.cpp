@@ -0,0 +1,800 @@
+#include "parser_impl.h"
+
+#include <sstream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    void throw_unexpected_token_error(const char* expected, lexer::TokenType got) {
+        std::ostringstream os;
+        os << "Unexpected token '" << lexer::get_token_name(got) << "', expected one of: " << expected;
+        throw std::runtime_error(os.str());
+    }
+
+    Action determine_action(uint32_t state, const lexer::TokenType &lookahead_token)
+    {
+        switch (state) {
+case 0: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_FIELD:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_QUOTED:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+default:
+throw_unexpected_token_error("'FIELD', 'QUOTED', 'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 1: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 2: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 3: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 4: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 5: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 6: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 7: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 8: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 9: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_FIELD:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_QUOTED:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+default:
+throw_unexpected_token_error("'FIELD', 'QUOTED', 'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 10: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 11: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_FIELD:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_QUOTED:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+default:
+throw_unexpected_token_error("'FIELD', 'QUOTED', 'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 12: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 13: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 14: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', 'COMMA', '<EOF>'", lookahead_token);
+}
+}
+break;
+case 15: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 16: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+default:
+throw_unexpected_token_error("'NEWLINE', '<EOF>'", lookahead_token);
+}
+}
+break;
+default:
+// Encountered a parser state that does not exist.
+std::terminate();
+}
+
+    }
+
+    void reduce_stack(uint32_t rule, Vector<Symbol> &parse_stack, Vector<Symbol> &rev_reduced_symbols)
+    {
+        switch(rule) {
+case 0: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 1: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 2: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FIELD)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 3: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 4: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 5: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT_2)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 6: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_OPT_2)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 7: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 8: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 9: {
+for (size_t i = 0; i < 2; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORD)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 10: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 11: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 12: {
+for (size_t i = 0; i < 2; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_RECORDS)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 13: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_FILE)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+default:
+// Tried reducing non-existent rule.
+std::terminate();
+}
+
+    }
+
+    Transition retrieve_next_state(uint32_t state, const Symbol &current_symbol)
+    {
+        switch (state) {
+case 0: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_FIELD:
+{
+Transition transition{6, false};
+return transition;
+}
+case lexer::TokenType::TK_QUOTED:
+{
+Transition transition{7, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_FIELD:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_RECORD:
+{
+Transition transition{2, false};
+return transition;
+}
+case NonTerminalType::NT_RECORDS:
+{
+Transition transition{3, false};
+return transition;
+}
+case NonTerminalType::NT_FILE:
+{
+Transition transition{0, true};
+return transition;
+}
+case NonTerminalType::NT_FIELD_ALT:
+{
+Transition transition{4, false};
+return transition;
+}
+case NonTerminalType::NT_RECORD_OPT:
+{
+Transition transition{5, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 2: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Transition transition{9, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_RECORDS_STAR:
+{
+Transition transition{8, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 5: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COMMA:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_RECORD_STAR:
+{
+Transition transition{10, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 9: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_FIELD:
+{
+Transition transition{6, false};
+return transition;
+}
+case lexer::TokenType::TK_QUOTED:
+{
+Transition transition{7, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_FIELD:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_RECORD:
+{
+Transition transition{12, false};
+return transition;
+}
+case NonTerminalType::NT_FIELD_ALT:
+{
+Transition transition{4, false};
+return transition;
+}
+case NonTerminalType::NT_RECORD_OPT:
+{
+Transition transition{5, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 11: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_FIELD:
+{
+Transition transition{6, false};
+return transition;
+}
+case lexer::TokenType::TK_QUOTED:
+{
+Transition transition{7, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_FIELD:
+{
+Transition transition{13, false};
+return transition;
+}
+case NonTerminalType::NT_FIELD_ALT:
+{
+Transition transition{4, false};
+return transition;
+}
+case NonTerminalType::NT_RECORD_OPT_2:
+{
+Transition transition{14, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 12: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NEWLINE:
+{
+Transition transition{9, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_RECORDS_STAR:
+{
+Transition transition{15, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 14: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COMMA:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_RECORD_STAR:
+{
+Transition transition{16, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+default:
+// Encountered a parser state that does not exist.
+std::terminate();
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
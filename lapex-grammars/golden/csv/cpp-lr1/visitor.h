@@ -0,0 +1,51 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void shift(lexer::TokenType tk_type, T data) = 0;
+        // field(0) -> <field_alt>(5)
+virtual void reduce_field() = 0;
+// record(1) -> <record_opt>(6) <record_star>(7)
+virtual void reduce_record() = 0;
+// records(2) -> record(1) <records_star>(9)
+virtual void reduce_records() = 0;
+// file(3) -> records(2)
+virtual void reduce_file() = 0;
+// <field_alt>(5) -> FIELD(1)
+virtual void reduce_field_alt_1() = 0;
+// <field_alt>(5) -> QUOTED(2)
+virtual void reduce_field_alt_2() = 0;
+// <record_opt>(6) -> field(0)
+virtual void reduce_record_opt_1() = 0;
+// <record_opt>(6) -> <eps>
+virtual void reduce_record_opt_2() = 0;
+// <record_star>(7) -> <eps>
+virtual void reduce_record_star_1() = 0;
+// <record_star>(7) -> COMMA(4) <record_opt_2>(8) <record_star>(7)
+virtual void reduce_record_star_2() = 0;
+// <record_opt_2>(8) -> field(0)
+virtual void reduce_record_opt_2_1() = 0;
+// <record_opt_2>(8) -> <eps>
+virtual void reduce_record_opt_2_2() = 0;
+// <records_star>(9) -> <eps>
+virtual void reduce_records_star_1() = 0;
+// <records_star>(9) -> NEWLINE(3) record(1) <records_star>(9)
+virtual void reduce_records_star_2() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,6 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ;pub trait Visitor < T > { fn shift (& mut self , token : TokenType , data : T) ; # [doc = "field(0) -> <field_alt>(5)"] fn reduce_field (& mut self) ; # [doc = "record(1) -> <record_opt>(6) <record_star>(7)"] fn reduce_record (& mut self) ; # [doc = "records(2) -> record(1) <records_star>(9)"] fn reduce_records (& mut self) ; # [doc = "file(3) -> records(2)"] fn reduce_file (& mut self) ; # [doc = "<field_alt>(5) -> FIELD(1)"] fn reduce_field_alt_1 (& mut self) ; # [doc = "<field_alt>(5) -> QUOTED(2)"] fn reduce_field_alt_2 (& mut self) ; # [doc = "<record_opt>(6) -> field(0)"] fn reduce_record_opt_some (& mut self) ; # [doc = "<record_opt>(6) -> <eps>"] fn reduce_record_opt_none (& mut self) ; # [doc = "<record_star>(7) -> <eps>"] fn reduce_record_star_empty (& mut self) ; # [doc = "<record_star>(7) -> COMMA(4) <record_opt_2>(8) <record_star>(7)"] fn reduce_record_star_tail (& mut self) ; # [doc = "<record_opt_2>(8) -> field(0)"] fn reduce_record_opt_2_some (& mut self) ; # [doc = "<record_opt_2>(8) -> <eps>"] fn reduce_record_opt_2_none (& mut self) ; # [doc = "<records_star>(9) -> <eps>"] fn reduce_records_star_empty (& mut self) ; # [doc = "<records_star>(9) -> NEWLINE(3) record(1) <records_star>(9)"] fn reduce_records_star_tail (& mut self) ; }pub struct Parser < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> { token_function : F , visitor : V , } # [derive (Debug , Clone , Copy)] # [allow (clippy :: enum_variant_names)] enum NonTerminalType { NtField , NtRecord , NtRecords , NtFile , NtFieldAlt , NtRecordOpt , NtRecordStar , NtRecordOpt2 , NtRecordsStar } # [derive (Debug , Clone , Copy)] enum StackSymbol { Terminal { token : TokenType } , NonTerminal { non_terminal : NonTerminalType } , State { state_id : usize } , } # [derive (Clone , Copy)] enum ReducedRule { Rule0 , Rule1 , Rule2 , Rule3 , Rule4 , Rule5 , Rule6 , Rule7 , Rule8 , Rule9 , Rule10 , Rule11 , Rule12 , Rule13 } enum Action { Shift , Reduce { rule : ReducedRule } } enum Goto { Accept , State { state_id : usize } } # [derive (Debug , Clone)] pub enum ParserError { UnexpectedToken { got : TokenType , expected : Vec < TokenType > } , } impl std :: error :: Error for ParserError { } impl std :: fmt :: Display for ParserError { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { ParserError :: UnexpectedToken { got , expected } => write ! (f , "Unexpected token {:?}, expected one of: {:?}" , got , expected) , } } } impl < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> Parser < T , F , V > { # [doc = r" The token function is not called again once it returned `TokenType::EndOfFile`,"] # [doc = r" and `EndOfFile` is never passed to `Visitor::shift`."] pub fn new (token_function : F , visitor : V) -> Self { Parser { token_function , visitor , } } fn next_action (& self , state : usize , next_token : TokenType) -> Result < Action , ParserError > { match (state , next_token) { (0usize , TokenType :: TkField) => Ok (Action :: Shift) , (0usize , TokenType :: TkQuoted) => Ok (Action :: Shift) , (0usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (0usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (0usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (0usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkField , TokenType :: TkQuoted , TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (1usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (1usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (1usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (1usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (2usize , TokenType :: TkNewline) => Ok (Action :: Shift) , (2usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (2usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (3usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (3usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (4usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (4usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (4usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (4usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (5usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (5usize , TokenType :: TkComma) => Ok (Action :: Shift) , (5usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (5usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (6usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (6usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (6usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (6usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (7usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (7usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (7usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (7usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (8usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (8usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (9usize , TokenType :: TkField) => Ok (Action :: Shift) , (9usize , TokenType :: TkQuoted) => Ok (Action :: Shift) , (9usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (9usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (9usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (9usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkField , TokenType :: TkQuoted , TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (10usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (10usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (10usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (11usize , TokenType :: TkField) => Ok (Action :: Shift) , (11usize , TokenType :: TkQuoted) => Ok (Action :: Shift) , (11usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (11usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (11usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (11usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkField , TokenType :: TkQuoted , TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (12usize , TokenType :: TkNewline) => Ok (Action :: Shift) , (12usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (12usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (13usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (13usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (13usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (13usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (14usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (14usize , TokenType :: TkComma) => Ok (Action :: Shift) , (14usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (14usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: TkComma , TokenType :: EndOfFile] }) , (15usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (15usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (16usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (16usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (16usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } fn next_goto (& self , state : usize , symbol : StackSymbol , next_token : TokenType) -> Result < Goto , ParserError > { match (state , symbol) { (0usize , StackSymbol :: Terminal { token : TokenType :: TkField }) => Ok (Goto :: State { state_id : 6usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkQuoted }) => Ok (Goto :: State { state_id : 7usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtField }) => Ok (Goto :: State { state_id : 1usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecord }) => Ok (Goto :: State { state_id : 2usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecords }) => Ok (Goto :: State { state_id : 3usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFile }) => Ok (Goto :: Accept) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFieldAlt }) => Ok (Goto :: State { state_id : 4usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt }) => Ok (Goto :: State { state_id : 5usize }) , (2usize , StackSymbol :: Terminal { token : TokenType :: TkNewline }) => Ok (Goto :: State { state_id : 9usize }) , (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordsStar }) => Ok (Goto :: State { state_id : 8usize }) , (5usize , StackSymbol :: Terminal { token : TokenType :: TkComma }) => Ok (Goto :: State { state_id : 11usize }) , (5usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordStar }) => Ok (Goto :: State { state_id : 10usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkField }) => Ok (Goto :: State { state_id : 6usize }) , (9usize , StackSymbol :: Terminal { token : TokenType :: TkQuoted }) => Ok (Goto :: State { state_id : 7usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtField }) => Ok (Goto :: State { state_id : 1usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecord }) => Ok (Goto :: State { state_id : 12usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFieldAlt }) => Ok (Goto :: State { state_id : 4usize }) , (9usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt }) => Ok (Goto :: State { state_id : 5usize }) , (11usize , StackSymbol :: Terminal { token : TokenType :: TkField }) => Ok (Goto :: State { state_id : 6usize }) , (11usize , StackSymbol :: Terminal { token : TokenType :: TkQuoted }) => Ok (Goto :: State { state_id : 7usize }) , (11usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtField }) => Ok (Goto :: State { state_id : 13usize }) , (11usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFieldAlt }) => Ok (Goto :: State { state_id : 4usize }) , (11usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt2 }) => Ok (Goto :: State { state_id : 14usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkNewline }) => Ok (Goto :: State { state_id : 9usize }) , (12usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordsStar }) => Ok (Goto :: State { state_id : 15usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkComma }) => Ok (Goto :: State { state_id : 11usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordStar }) => Ok (Goto :: State { state_id : 16usize }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } # [doc = r" Passes every token through `filter` before the parser sees it."] # [allow (dead_code)] pub fn with_filter < P : FnMut (& (TokenType , T)) -> FilterAction < T >> (self , filter : P ,) -> Parser < T , impl FnMut () -> (TokenType , T) , V > { Parser { token_function : filter_tokens (self . token_function , filter) , visitor : self . visitor , } } fn reduce_stack_and_visit (& mut self , rule : ReducedRule , stack : & mut Vec < StackSymbol > , state : usize , next_token : TokenType) -> Result < () , ParserError > { let (to_pop , reduced) = match rule { ReducedRule :: Rule0 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFieldAlt }) , ReducedRule :: Rule1 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFieldAlt }) , ReducedRule :: Rule2 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtField }) , ReducedRule :: Rule3 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt }) , ReducedRule :: Rule4 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt }) , ReducedRule :: Rule5 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt2 }) , ReducedRule :: Rule6 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordOpt2 }) , ReducedRule :: Rule7 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordStar }) , ReducedRule :: Rule8 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordStar }) , ReducedRule :: Rule9 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecord }) , ReducedRule :: Rule10 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordsStar }) , ReducedRule :: Rule11 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecordsStar }) , ReducedRule :: Rule12 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtRecords }) , ReducedRule :: Rule13 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFile }) } ; if stack . len () < to_pop * 2 { unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } stack . truncate (stack . len () - to_pop * 2) ; stack . push (reduced) ; match rule { ReducedRule :: Rule2 => self . visitor . reduce_field () , ReducedRule :: Rule9 => self . visitor . reduce_record () , ReducedRule :: Rule12 => self . visitor . reduce_records () , ReducedRule :: Rule13 => self . visitor . reduce_file () , ReducedRule :: Rule0 => self . visitor . reduce_field_alt_1 () , ReducedRule :: Rule1 => self . visitor . reduce_field_alt_2 () , ReducedRule :: Rule3 => self . visitor . reduce_record_opt_some () , ReducedRule :: Rule4 => self . visitor . reduce_record_opt_none () , ReducedRule :: Rule7 => self . visitor . reduce_record_star_empty () , ReducedRule :: Rule8 => self . visitor . reduce_record_star_tail () , ReducedRule :: Rule5 => self . visitor . reduce_record_opt_2_some () , ReducedRule :: Rule6 => self . visitor . reduce_record_opt_2_none () , ReducedRule :: Rule10 => self . visitor . reduce_records_star_empty () , ReducedRule :: Rule11 => self . visitor . reduce_records_star_tail () } Ok (()) } # [doc = r" Parses the whole input and returns its errors. The grammar has no error"] # [doc = r" productions, so parsing stops at the first error."] # [allow (dead_code)] pub fn parse_all (& mut self) -> (Vec < ParserError > , ()) { match self . parse () { Ok (()) => (Vec :: new () , ()) , Err (error) => (vec ! [error] , ()) , } } pub fn parse (& mut self) -> Result < () , ParserError > { let mut lookahead = std :: collections :: VecDeque :: new () ; lookahead . push_back ((self . token_function) ()) ; let mut stack = Vec :: new () ; stack . push (StackSymbol :: State { state_id : 0usize }) ; let mut state = 0usize ; while ! stack . is_empty () { let (next_token , _) = lookahead . front () . unwrap () ; let next_token = * next_token ; let action = self . next_action (state , next_token) ? ; match action { Action :: Shift => { let (next_token , next_data) = lookahead . pop_front () . unwrap () ; debug_assert ! (! matches ! (next_token , TokenType :: EndOfFile) , "EndOfFile must not be shifted") ; stack . push (StackSymbol :: Terminal { token : next_token }) ; self . visitor . shift (next_token , next_data) ; lookahead . push_back ((self . token_function) ()) ; } Action :: Reduce { rule : reduced_rule } => { self . reduce_stack_and_visit (reduced_rule , & mut stack , state , next_token) ? ; } } let (goto_state , current_symbol) = match stack . as_slice () { [.. , StackSymbol :: State { state_id } , current_symbol] => (* state_id , * current_symbol) , _ => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } ; let goto = self . next_goto (goto_state , current_symbol , next_token) ? ; match goto { Goto :: Accept => { stack . pop () ; stack . pop () ; } Goto :: State { state_id } => { stack . push (StackSymbol :: State { state_id }) ; state = state_id ; } } } Ok (()) } } # [doc = r" What a token filter does with a token before the parser sees it."] # [allow (dead_code)] pub enum FilterAction < T > { # [doc = r" Passes the token on."] Keep , # [doc = r" Drops the token."] Drop , # [doc = r" Passes another token on in place of this one."] Replace (TokenType , T) , # [doc = r" Passes these tokens on before the token itself."] Inject (Vec < (TokenType , T) >) , } fn apply_filter < T , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (filter : & mut P , token : (TokenType , T) , pending : & mut std :: collections :: VecDeque < (TokenType , T) > ,) { let is_end = matches ! (token . 0 , TokenType :: EndOfFile) ; match filter (& token) { FilterAction :: Keep => pending . push_back (token) , FilterAction :: Drop => { if is_end { pending . push_back (token) ; } } FilterAction :: Replace (replacement , data) => { pending . push_back ((replacement , data)) ; if is_end { pending . push_back (token) ; } } FilterAction :: Inject (tokens) => { pending . extend (tokens) ; pending . push_back (token) ; } } } # [doc = r" Wraps a token function, so that every token is passed through `filter` first."] # [allow (dead_code)] pub fn filter_tokens < T , F : FnMut () -> (TokenType , T) , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (mut token_function : F , mut filter : P ,) -> impl FnMut () -> (TokenType , T) { let mut pending = std :: collections :: VecDeque :: new () ; move || loop { if let Some (token) = pending . pop_front () { return token ; } apply_filter (& mut filter , token_function () , & mut pending) ; } } # [doc = r" Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`"] # [doc = r" once the iterator is exhausted."] # [allow (dead_code)] pub fn token_function_from_iter < T : Default , I : IntoIterator < Item = (TokenType , T) >> (tokens : I ,) -> impl FnMut () -> (TokenType , T) { let mut tokens = tokens . into_iter () ; move || { tokens . next () . unwrap_or_else (|| (TokenType :: EndOfFile , T :: default ())) } }
+// <lapex:keep name="code">
+// </lapex:keep>
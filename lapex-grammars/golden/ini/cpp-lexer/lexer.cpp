@@ -0,0 +1,646 @@
+#include "lexer.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    Lexer::Lexer(std::istream &in, Context *context) : in_chars(in), context(context), ch(-1), err(0), start_pos(0), end_pos(0), pos(0), location{1, 1}, start_location{1, 1}, end_location{1, 1}, after_cr(false) {}
+
+    // Branchless UTF-8: https://github.com/skeeto/branchless-utf8
+    void utf8_decode(std::istream &in, uint32_t *c, int *e)
+    {
+        static const char lengths[] = {
+            1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+            0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 2, 2, 3, 3, 4, 0};
+        static const int masks[] = {0x00, 0x7f, 0x1f, 0x0f, 0x07};
+        static const uint32_t mins[] = {4194304, 0, 128, 2048, 65536};
+        static const int shiftc[] = {0, 18, 12, 6, 0};
+        static const int shifte[] = {0, 6, 4, 2, 0};
+
+        char buf[4] = {0};
+        in.read(buf, 1);
+        uint8_t *s = (uint8_t *)buf;
+        int len = lengths[s[0] >> 3];
+        in.read(buf + 1, (len > 1) * (len - 1));
+
+        /* Assume a four-byte character and load four bytes. Unused bits are
+         * shifted out.
+         */
+        *c = (uint32_t)(s[0] & masks[len]) << 18;
+        *c |= (uint32_t)(s[1] & 0x3f) << 12;
+        *c |= (uint32_t)(s[2] & 0x3f) << 6;
+        *c |= (uint32_t)(s[3] & 0x3f) << 0;
+        *c >>= shiftc[len];
+
+        /* Accumulate the various error conditions. */
+        *e = (*c < mins[len]) << 6;      // non-canonical encoding
+        *e |= ((*c >> 11) == 0x1b) << 7; // surrogate half?
+        *e |= (*c > 0x10FFFF) << 8;      // out of range?
+        *e |= (s[1] & 0xc0) >> 2;
+        *e |= (s[2] & 0xc0) >> 4;
+        *e |= (s[3]) >> 6;
+        *e ^= 0x2a; // top two bits of each tail byte correct?
+        *e >>= shifte[len];
+    }
+
+    size_t Lexer::start()
+    {
+        return this->start_pos;
+    }
+    size_t Lexer::end()
+    {
+        return this->end_pos;
+    }
+    Location Lexer::position()
+    {
+        return this->start_location;
+    }
+    LocatedSpan Lexer::span_with_location()
+    {
+        return LocatedSpan{this->start_pos, this->end_pos, this->start_location, this->end_location};
+    }
+
+    TokenType Lexer::next()
+    {
+        TokenType tk_type = this->scan();
+        if (this->context == nullptr || tk_type == TokenType::TK_EOF || tk_type == TokenType::TK_ERR)
+        {
+            return tk_type;
+        }
+        return this->context->classify(tk_type, this->start_pos, this->end_pos);
+    }
+
+    // the index in the alphabet of every ASCII character, or -1 if no token contains it
+static const int32_t ascii_indices[128] = {0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 3, 4, 5, 6, 7, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 8, 9, 10, 10, 11, 12, 12, 12, 12, 12, 12, 12, 12, 12, 13, 14, 15, 16, 17, 17, 17, 17, 17, 17, 17, 17, 18, 19, 20, 21, 22, 23, 23, 23, 24, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 34, 35, 36, 36, 36, 36, 36};
+// the ranges of the other characters in the alphabet, sorted by their start
+static const uint32_t range_starts[] = {123, 1114111};
+static const uint32_t range_ends[] = {1114110, 1114111};
+static const uint32_t range_indices[] = {36, 37};
+
+    TokenType Lexer::scan()
+    {
+        uint32_t state = 0;
+        this->start_pos = this->pos;
+        this->start_location = this->location;
+        while (1)
+        {
+            if (this->ch == -1)
+            {
+                utf8_decode(this->in_chars, &this->ch, &this->err);
+            }
+            if (this->err)
+            {
+                return TokenType::TK_ERR;
+            }
+
+            uint32_t i;
+if (ch < 128)
+{
+if (ascii_indices[ch] < 0)
+{
+return TokenType::TK_ERR;
+}
+i = ascii_indices[ch];
+}
+else
+{
+size_t low = 0;
+size_t high = 2;
+while (low < high)
+{
+size_t middle = low + (high - low) / 2;
+if (range_ends[middle] < ch)
+{
+low = middle + 1;
+}
+else
+{
+high = middle;
+}
+}
+if (low == 2 || ch < range_starts[low])
+{
+return TokenType::TK_ERR;
+}
+i = range_indices[low];
+}
+
+            uint32_t consumed = this->ch;
+            switch (state)
+{
+case 0:
+switch (i)
+{
+case 0: 
+return TokenType::TK_EOF;
+case 2: 
+this->ch = -1;
+state = 6;
+break;
+case 3: 
+this->ch = -1;
+state = 8;
+break;
+case 6: 
+this->ch = -1;
+state = 7;
+break;
+case 9: 
+this->ch = -1;
+state = 6;
+break;
+case 11: 
+this->ch = -1;
+state = 5;
+break;
+case 20: 
+this->ch = -1;
+state = 5;
+break;
+case 22: 
+this->ch = -1;
+state = 4;
+break;
+case 24: 
+this->ch = -1;
+state = 1;
+break;
+case 25: 
+this->ch = -1;
+state = 1;
+break;
+case 26: 
+this->ch = -1;
+state = 1;
+break;
+case 27: 
+this->ch = -1;
+state = 3;
+break;
+case 29: 
+this->ch = -1;
+state = 2;
+break;
+case 31: 
+this->ch = -1;
+state = 1;
+break;
+case 33: 
+this->ch = -1;
+state = 1;
+break;
+case 34: 
+this->ch = -1;
+state = 1;
+break;
+case 35: 
+this->ch = -1;
+state = 1;
+break;
+default:
+return TokenType::TK_ERR;
+}
+break;
+case 1:
+switch (i)
+{
+case 13: 
+this->ch = -1;
+state = 1;
+break;
+case 14: 
+this->ch = -1;
+state = 1;
+break;
+case 16: 
+this->ch = -1;
+state = 1;
+break;
+case 17: 
+this->ch = -1;
+state = 1;
+break;
+case 18: 
+this->ch = -1;
+state = 1;
+break;
+case 24: 
+this->ch = -1;
+state = 1;
+break;
+case 25: 
+this->ch = -1;
+state = 1;
+break;
+case 26: 
+this->ch = -1;
+state = 1;
+break;
+case 31: 
+this->ch = -1;
+state = 1;
+break;
+case 33: 
+this->ch = -1;
+state = 1;
+break;
+case 34: 
+this->ch = -1;
+state = 1;
+break;
+case 35: 
+this->ch = -1;
+state = 1;
+break;
+default:
+// ACCEPT: TokenRule { name: "KEY", precedence: None, pattern: Pattern { pattern: Sequence { elements: [CharSet { chars: [Range('A', 'Z'), Range('_', '_'), Range('a', 'z')], negated: false }, Repetition { min: 0, max: None, inner: CharSet { chars: [Range('-', '.'), Range('0', '9'), Range('A', 'Z'), Range('_', '_'), Range('a', 'z')], negated: false }, greedy: true }] } }, skip: false, doc: None }
+this->end_pos = this->pos;
+this->end_location = this->location;
+return TokenType::TK_KEY;
+}
+break;
+case 2:
+switch (i)
+{
+default:
+// ACCEPT: TokenRule { name: "RBRACK", precedence: None, pattern: Literal { characters: [']'] }, skip: false, doc: None }
+this->end_pos = this->pos;
+this->end_location = this->location;
+return TokenType::TK_RBRACK;
+}
+break;
+case 3:
+switch (i)
+{
+default:
+// ACCEPT: TokenRule { name: "LBRACK", precedence: None, pattern: Literal { characters: ['['] }, skip: false, doc: None }
+this->end_pos = this->pos;
+this->end_location = this->location;
+return TokenType::TK_LBRACK;
+}
+break;
+case 4:
+switch (i)
+{
+case 1: 
+this->ch = -1;
+state = 4;
+break;
+case 2: 
+this->ch = -1;
+state = 4;
+break;
+case 4: 
+this->ch = -1;
+state = 4;
+break;
+case 5: 
+this->ch = -1;
+state = 4;
+break;
+case 7: 
+this->ch = -1;
+state = 4;
+break;
+case 8: 
+this->ch = -1;
+state = 4;
+break;
+case 9: 
+this->ch = -1;
+state = 4;
+break;
+case 10: 
+this->ch = -1;
+state = 4;
+break;
+case 11: 
+this->ch = -1;
+state = 4;
+break;
+case 12: 
+this->ch = -1;
+state = 4;
+break;
+case 13: 
+this->ch = -1;
+state = 4;
+break;
+case 14: 
+this->ch = -1;
+state = 4;
+break;
+case 15: 
+this->ch = -1;
+state = 4;
+break;
+case 16: 
+this->ch = -1;
+state = 4;
+break;
+case 17: 
+this->ch = -1;
+state = 4;
+break;
+case 18: 
+this->ch = -1;
+state = 4;
+break;
+case 19: 
+this->ch = -1;
+state = 4;
+break;
+case 20: 
+this->ch = -1;
+state = 4;
+break;
+case 21: 
+this->ch = -1;
+state = 4;
+break;
+case 22: 
+this->ch = -1;
+state = 4;
+break;
+case 23: 
+this->ch = -1;
+state = 4;
+break;
+case 24: 
+this->ch = -1;
+state = 4;
+break;
+case 25: 
+this->ch = -1;
+state = 4;
+break;
+case 26: 
+this->ch = -1;
+state = 4;
+break;
+case 27: 
+this->ch = -1;
+state = 4;
+break;
+case 28: 
+this->ch = -1;
+state = 4;
+break;
+case 29: 
+this->ch = -1;
+state = 4;
+break;
+case 30: 
+this->ch = -1;
+state = 4;
+break;
+case 31: 
+this->ch = -1;
+state = 4;
+break;
+case 32: 
+this->ch = -1;
+state = 4;
+break;
+case 33: 
+this->ch = -1;
+state = 4;
+break;
+case 34: 
+this->ch = -1;
+state = 4;
+break;
+case 35: 
+this->ch = -1;
+state = 4;
+break;
+case 36: 
+this->ch = -1;
+state = 4;
+break;
+case 37: 
+this->ch = -1;
+state = 4;
+break;
+default:
+// ACCEPT: TokenRule { name: "VALUE", precedence: None, pattern: Pattern { pattern: Sequence { elements: [Sequence { elements: [Char { chars: Single('=') }] }, Repetition { min: 0, max: None, inner: CharSet { chars: [Range('\0', '\t'), Range('\u{b}', '\u{c}'), Range('\u{e}', '\u{10ffff}')], negated: false }, greedy: true }] } }, skip: false, doc: None }
+this->end_pos = this->pos;
+this->end_location = this->location;
+return TokenType::TK_VALUE;
+}
+break;
+case 5:
+switch (i)
+{
+case 1: 
+this->ch = -1;
+state = 5;
+break;
+case 2: 
+this->ch = -1;
+state = 5;
+break;
+case 4: 
+this->ch = -1;
+state = 5;
+break;
+case 5: 
+this->ch = -1;
+state = 5;
+break;
+case 7: 
+this->ch = -1;
+state = 5;
+break;
+case 8: 
+this->ch = -1;
+state = 5;
+break;
+case 9: 
+this->ch = -1;
+state = 5;
+break;
+case 10: 
+this->ch = -1;
+state = 5;
+break;
+case 11: 
+this->ch = -1;
+state = 5;
+break;
+case 12: 
+this->ch = -1;
+state = 5;
+break;
+case 13: 
+this->ch = -1;
+state = 5;
+break;
+case 14: 
+this->ch = -1;
+state = 5;
+break;
+case 15: 
+this->ch = -1;
+state = 5;
+break;
+case 16: 
+this->ch = -1;
+state = 5;
+break;
+case 17: 
+this->ch = -1;
+state = 5;
+break;
+case 18: 
+this->ch = -1;
+state = 5;
+break;
+case 19: 
+this->ch = -1;
+state = 5;
+break;
+case 20: 
+this->ch = -1;
+state = 5;
+break;
+case 21: 
+this->ch = -1;
+state = 5;
+break;
+case 22: 
+this->ch = -1;
+state = 5;
+break;
+case 23: 
+this->ch = -1;
+state = 5;
+break;
+case 24: 
+this->ch = -1;
+state = 5;
+break;
+case 25: 
+this->ch = -1;
+state = 5;
+break;
+case 26: 
+this->ch = -1;
+state = 5;
+break;
+case 27: 
+this->ch = -1;
+state = 5;
+break;
+case 28: 
+this->ch = -1;
+state = 5;
+break;
+case 29: 
+this->ch = -1;
+state = 5;
+break;
+case 30: 
+this->ch = -1;
+state = 5;
+break;
+case 31: 
+this->ch = -1;
+state = 5;
+break;
+case 32: 
+this->ch = -1;
+state = 5;
+break;
+case 33: 
+this->ch = -1;
+state = 5;
+break;
+case 34: 
+this->ch = -1;
+state = 5;
+break;
+case 35: 
+this->ch = -1;
+state = 5;
+break;
+case 36: 
+this->ch = -1;
+state = 5;
+break;
+case 37: 
+this->ch = -1;
+state = 5;
+break;
+default:
+// SKIP: TokenRule { name: "COMMENT", precedence: None, pattern: Pattern { pattern: Sequence { elements: [CharSet { chars: [Range('#', '#'), Range(';', ';')], negated: false }, Repetition { min: 0, max: None, inner: CharSet { chars: [Range('\0', '\t'), Range('\u{b}', '\u{c}'), Range('\u{e}', '\u{10ffff}')], negated: false }, greedy: true }] } }, skip: true, doc: None }
+state = 0;
+this->start_pos = this->pos;
+this->start_location = this->location;
+continue;
+}
+break;
+case 6:
+switch (i)
+{
+case 2: 
+this->ch = -1;
+state = 6;
+break;
+case 9: 
+this->ch = -1;
+state = 6;
+break;
+default:
+// SKIP: TokenRule { name: "WHITESPACE", precedence: None, pattern: Pattern { pattern: Repetition { min: 1, max: None, inner: CharSet { chars: [Range('\t', '\t'), Range(' ', ' ')], negated: false }, greedy: true } }, skip: true, doc: None }
+state = 0;
+this->start_pos = this->pos;
+this->start_location = this->location;
+continue;
+}
+break;
+case 7:
+switch (i)
+{
+case 3: 
+this->ch = -1;
+state = 8;
+break;
+default:
+return TokenType::TK_ERR;
+}
+break;
+case 8:
+switch (i)
+{
+default:
+// ACCEPT: TokenRule { name: "NEWLINE", precedence: None, pattern: Pattern { pattern: Sequence { elements: [Repetition { min: 0, max: Some(1), inner: Sequence { elements: [Char { chars: Single('\r') }] }, greedy: true }, Sequence { elements: [Char { chars: Single('\n') }] }] } }, skip: false, doc: None }
+this->end_pos = this->pos;
+this->end_location = this->location;
+return TokenType::TK_NEWLINE;
+}
+break;
+default:
+return TokenType::TK_ERR;
+}
+
+            this->pos += 1;
+            // \n, \r and \r\n end a line
+            if (consumed == '\r' || (consumed == '\n' && !this->after_cr))
+            {
+                this->location.line += 1;
+                this->location.column = 1;
+            }
+            else if (consumed != '\n')
+            {
+                this->location.column += 1;
+            }
+            this->after_cr = consumed == '\r';
+        }
+        return TokenType::TK_ERR;
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,36 @@
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    const char *get_token_name(TokenType tk_type) {
+        switch (tk_type) {
+case TokenType::TK_ERR:
+return "<ERR>";
+case TokenType::TK_EOF:
+return "<EOF>";
+case TokenType::TK_WHITESPACE:
+return "WHITESPACE";
+case TokenType::TK_COMMENT:
+return "COMMENT";
+case TokenType::TK_VALUE:
+return "VALUE";
+case TokenType::TK_KEY:
+return "KEY";
+case TokenType::TK_NEWLINE:
+return "NEWLINE";
+case TokenType::TK_RBRACK:
+return "RBRACK";
+case TokenType::TK_LBRACK:
+return "LBRACK";
+default:
+return nullptr;
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
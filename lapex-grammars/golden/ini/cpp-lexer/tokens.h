@@ -0,0 +1,31 @@
+#ifndef LAPEX_TOKENS_H
+#define LAPEX_TOKENS_H
+
+#include <cstdint>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    enum class TokenType : uint32_t
+    {
+        TK_ERR = 0,
+        TK_EOF = 1,
+        TK_WHITESPACE,
+TK_COMMENT,
+TK_VALUE,
+TK_KEY,
+TK_NEWLINE,
+TK_RBRACK,
+TK_LBRACK,
+
+    };
+    
+    const char *get_token_name(TokenType tk_type);
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
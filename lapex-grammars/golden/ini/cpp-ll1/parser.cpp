@@ -0,0 +1,220 @@
+#include "parser_impl.h"
+
+#include <sstream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser {
+    void push_production_from_table(Symbol non_terminal, const lexer::TokenType* lookahead, ParseStack& parse_stack) {
+        switch(non_terminal.identifier) {
+case 0: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_VALUE)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_KEY)};
+parse_stack.push(sym1);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'property'. Expected one of: KEY");
+}
+break;
+}
+case 1: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_RBRACK)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_KEY)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_LBRACK)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'section'. Expected one of: LBRACK");
+}
+break;
+}
+case 2: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'line'. Expected one of: KEY, LBRACK");
+}
+break;
+}
+case 3: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_OPT)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_OPT)};
+parse_stack.push(sym1);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'lines'. Expected one of: <EOF>, KEY, NEWLINE, LBRACK");
+}
+break;
+}
+case 4: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'file'. Expected one of: <EOF>, KEY, NEWLINE, LBRACK");
+}
+break;
+}
+case 7: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_PROPERTY)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_SECTION)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: KEY, LBRACK");
+}
+break;
+}
+case 8: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINE)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, KEY, NEWLINE, LBRACK");
+}
+break;
+}
+case 9: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINES_OPT_2)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_NEWLINE)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, NEWLINE");
+}
+break;
+}
+case 10: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_EOF: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_KEY: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_NEWLINE: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_LINE)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: <EOF>, KEY, NEWLINE, LBRACK");
+}
+break;
+}
+}
+
+    }
+
+
+    void throw_unexpected_token_error(lexer::TokenType expected, lexer::TokenType got) {
+        std::ostringstream os;
+        os << "Unexpected token '" << lexer::get_token_name(got) << "', expected token '" << lexer::get_token_name(expected) << "'";
+        throw std::runtime_error(os.str());
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,33 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void token(lexer::TokenType tk_type, T data) = 0;
+        virtual void enter_property() = 0;
+virtual void exit_property() = 0;
+virtual void enter_section() = 0;
+virtual void exit_section() = 0;
+virtual void enter_line() = 0;
+virtual void exit_line() = 0;
+virtual void enter_lines() = 0;
+virtual void exit_lines() = 0;
+virtual void enter_file() = 0;
+virtual void exit_file() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,197 @@
+#ifndef LAPEX_AST_H
+#define LAPEX_AST_H
+
+#include "visitor.h"
+#include <memory>
+#include <utility>
+#include <variant>
+#include <vector>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    namespace ast
+    {
+        template <class T>
+        struct Terminal
+        {
+            lexer::TokenType type;
+            T data;
+        };
+
+        template <class T>
+struct PropertyNode;
+template <class T>
+struct SectionNode;
+template <class T>
+struct LineNode;
+template <class T>
+struct LinesNode;
+template <class T>
+struct FileNode;
+
+
+        template <class T>
+        using Node = std::variant<Terminal<T>, std::unique_ptr<PropertyNode<T>>, std::unique_ptr<SectionNode<T>>, std::unique_ptr<LineNode<T>>, std::unique_ptr<LinesNode<T>>, std::unique_ptr<FileNode<T>>>;
+
+        // property(0) -> KEY(3) VALUE(2)
+template <class T>
+struct PropertyNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// section(1) -> LBRACK(6) KEY(3) RBRACK(5)
+template <class T>
+struct SectionNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// line(2) -> <line_alt>(7)
+template <class T>
+struct LineNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// lines(3) -> <lines_opt>(8) <lines_star>(9)
+template <class T>
+struct LinesNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// file(4) -> lines(3)
+template <class T>
+struct FileNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+
+
+        // Builds a tree while parsing. Anonymous non-terminals like repetitions have no node type,
+        // their children are added to the node of the enclosing production instead.
+        template <class T>
+        class TreeBuilder : public Visitor<T>
+        {
+        private:
+            // the nodes produced by each symbol on the parse stack
+            std::vector<std::vector<Node<T>>> stack;
+
+            std::vector<Node<T>> pop_children(size_t symbols)
+            {
+                std::vector<Node<T>> children;
+                auto first = stack.end() - symbols;
+                for (auto it = first; it != stack.end(); ++it)
+                {
+                    for (auto &node : *it)
+                    {
+                        children.push_back(std::move(node));
+                    }
+                }
+                stack.erase(first, stack.end());
+                return children;
+            }
+
+            template <class N>
+            void reduce_named(size_t symbols, uint32_t alternative)
+            {
+                auto node = std::make_unique<N>();
+                node->alternative = alternative;
+                node->children = pop_children(symbols);
+                std::vector<Node<T>> nodes;
+                nodes.push_back(std::move(node));
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_anonymous(size_t symbols)
+            {
+                stack.push_back(pop_children(symbols));
+            }
+
+        public:
+            void shift(lexer::TokenType tk_type, T data) override
+            {
+                std::vector<Node<T>> nodes;
+                nodes.push_back(Terminal<T>{tk_type, std::move(data)});
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_property() override
+{
+reduce_named<PropertyNode<T>>(2, 1);
+}
+void reduce_section() override
+{
+reduce_named<SectionNode<T>>(3, 1);
+}
+void reduce_line() override
+{
+reduce_named<LineNode<T>>(1, 1);
+}
+void reduce_lines() override
+{
+reduce_named<LinesNode<T>>(2, 1);
+}
+void reduce_file() override
+{
+reduce_named<FileNode<T>>(1, 1);
+}
+void reduce_line_alt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_line_alt_2() override
+{
+reduce_anonymous(1);
+}
+void reduce_lines_opt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_lines_opt_2() override
+{
+reduce_anonymous(0);
+}
+void reduce_lines_star_1() override
+{
+reduce_anonymous(0);
+}
+void reduce_lines_star_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_lines_opt_2_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_lines_opt_2_2() override
+{
+reduce_anonymous(0);
+}
+
+
+            // Returns the node of the entry production once the input has been parsed.
+            Node<T> take_root()
+            {
+                Node<T> root = std::move(stack.back().front());
+                stack.clear();
+                return root;
+            }
+        };
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
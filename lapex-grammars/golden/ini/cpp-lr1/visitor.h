@@ -0,0 +1,49 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void shift(lexer::TokenType tk_type, T data) = 0;
+        // property(0) -> KEY(3) VALUE(2)
+virtual void reduce_property() = 0;
+// section(1) -> LBRACK(6) KEY(3) RBRACK(5)
+virtual void reduce_section() = 0;
+// line(2) -> <line_alt>(7)
+virtual void reduce_line() = 0;
+// lines(3) -> <lines_opt>(8) <lines_star>(9)
+virtual void reduce_lines() = 0;
+// file(4) -> lines(3)
+virtual void reduce_file() = 0;
+// <line_alt>(7) -> section(1)
+virtual void reduce_line_alt_1() = 0;
+// <line_alt>(7) -> property(0)
+virtual void reduce_line_alt_2() = 0;
+// <lines_opt>(8) -> line(2)
+virtual void reduce_lines_opt_1() = 0;
+// <lines_opt>(8) -> <eps>
+virtual void reduce_lines_opt_2() = 0;
+// <lines_star>(9) -> <eps>
+virtual void reduce_lines_star_1() = 0;
+// <lines_star>(9) -> NEWLINE(4) <lines_opt_2>(10) <lines_star>(9)
+virtual void reduce_lines_star_2() = 0;
+// <lines_opt_2>(10) -> line(2)
+virtual void reduce_lines_opt_2_1() = 0;
+// <lines_opt_2>(10) -> <eps>
+virtual void reduce_lines_opt_2_2() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,7 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ; # [derive (Debug)] pub enum LexerError { InvalidChar { bad_ch : u32 } , UnexpectedAlphabet { range : std :: ops :: RangeInclusive < u32 > } , # [doc = r" The input of a `StreamLexer` could not be read, or is no valid UTF-8."] Read (std :: io :: Error) } impl std :: error :: Error for LexerError { } impl std :: fmt :: Display for LexerError { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { LexerError :: InvalidChar { bad_ch } => write ! (f , "Lexer got strange codepoint {}, char value is '{:?}'" , bad_ch , std :: char :: from_u32 (* bad_ch)) , LexerError :: UnexpectedAlphabet { range } => write ! (f , "Lexer got char in unexpected range: {:?}" , range) , LexerError :: Read (error) => write ! (f , "Lexer could not read the input: {}" , error) } } } # [doc = r" The state every token starts to be scanned in."] pub const START_STATE : usize = 0 ; # [doc = r" The character class of `'\0'`, which the lexer treats as the end of input."] pub const END_OF_INPUT_CLASS : usize = 0 ; # [doc = r" Maps a character to its character class, the unit of transitions in the DFA."] pub fn classify (ch : char) -> Option < usize > { Lexer :: get_alphabet_index (ch as u32) } # [doc = r" Returns the state the DFA moves to from `state` on a character of `class`."] pub fn step (state : usize , class : usize) -> Option < usize > { match (state , class) { (0usize , 2usize) => Some (6usize) , (0usize , 3usize) => Some (8usize) , (0usize , 6usize) => Some (7usize) , (0usize , 9usize) => Some (6usize) , (0usize , 11usize) => Some (5usize) , (0usize , 20usize) => Some (5usize) , (0usize , 22usize) => Some (4usize) , (0usize , 24usize) => Some (1usize) , (0usize , 25usize) => Some (1usize) , (0usize , 26usize) => Some (1usize) , (0usize , 27usize) => Some (3usize) , (0usize , 29usize) => Some (2usize) , (0usize , 31usize) => Some (1usize) , (0usize , 33usize) => Some (1usize) , (0usize , 34usize) => Some (1usize) , (0usize , 35usize) => Some (1usize) , (1usize , 13usize) => Some (1usize) , (1usize , 14usize) => Some (1usize) , (1usize , 16usize) => Some (1usize) , (1usize , 17usize) => Some (1usize) , (1usize , 18usize) => Some (1usize) , (1usize , 24usize) => Some (1usize) , (1usize , 25usize) => Some (1usize) , (1usize , 26usize) => Some (1usize) , (1usize , 31usize) => Some (1usize) , (1usize , 33usize) => Some (1usize) , (1usize , 34usize) => Some (1usize) , (1usize , 35usize) => Some (1usize) , (4usize , 1usize) => Some (4usize) , (4usize , 2usize) => Some (4usize) , (4usize , 4usize) => Some (4usize) , (4usize , 5usize) => Some (4usize) , (4usize , 7usize) => Some (4usize) , (4usize , 8usize) => Some (4usize) , (4usize , 9usize) => Some (4usize) , (4usize , 10usize) => Some (4usize) , (4usize , 11usize) => Some (4usize) , (4usize , 12usize) => Some (4usize) , (4usize , 13usize) => Some (4usize) , (4usize , 14usize) => Some (4usize) , (4usize , 15usize) => Some (4usize) , (4usize , 16usize) => Some (4usize) , (4usize , 17usize) => Some (4usize) , (4usize , 18usize) => Some (4usize) , (4usize , 19usize) => Some (4usize) , (4usize , 20usize) => Some (4usize) , (4usize , 21usize) => Some (4usize) , (4usize , 22usize) => Some (4usize) , (4usize , 23usize) => Some (4usize) , (4usize , 24usize) => Some (4usize) , (4usize , 25usize) => Some (4usize) , (4usize , 26usize) => Some (4usize) , (4usize , 27usize) => Some (4usize) , (4usize , 28usize) => Some (4usize) , (4usize , 29usize) => Some (4usize) , (4usize , 30usize) => Some (4usize) , (4usize , 31usize) => Some (4usize) , (4usize , 32usize) => Some (4usize) , (4usize , 33usize) => Some (4usize) , (4usize , 34usize) => Some (4usize) , (4usize , 35usize) => Some (4usize) , (4usize , 36usize) => Some (4usize) , (4usize , 37usize) => Some (4usize) , (5usize , 1usize) => Some (5usize) , (5usize , 2usize) => Some (5usize) , (5usize , 4usize) => Some (5usize) , (5usize , 5usize) => Some (5usize) , (5usize , 7usize) => Some (5usize) , (5usize , 8usize) => Some (5usize) , (5usize , 9usize) => Some (5usize) , (5usize , 10usize) => Some (5usize) , (5usize , 11usize) => Some (5usize) , (5usize , 12usize) => Some (5usize) , (5usize , 13usize) => Some (5usize) , (5usize , 14usize) => Some (5usize) , (5usize , 15usize) => Some (5usize) , (5usize , 16usize) => Some (5usize) , (5usize , 17usize) => Some (5usize) , (5usize , 18usize) => Some (5usize) , (5usize , 19usize) => Some (5usize) , (5usize , 20usize) => Some (5usize) , (5usize , 21usize) => Some (5usize) , (5usize , 22usize) => Some (5usize) , (5usize , 23usize) => Some (5usize) , (5usize , 24usize) => Some (5usize) , (5usize , 25usize) => Some (5usize) , (5usize , 26usize) => Some (5usize) , (5usize , 27usize) => Some (5usize) , (5usize , 28usize) => Some (5usize) , (5usize , 29usize) => Some (5usize) , (5usize , 30usize) => Some (5usize) , (5usize , 31usize) => Some (5usize) , (5usize , 32usize) => Some (5usize) , (5usize , 33usize) => Some (5usize) , (5usize , 34usize) => Some (5usize) , (5usize , 35usize) => Some (5usize) , (5usize , 36usize) => Some (5usize) , (5usize , 37usize) => Some (5usize) , (6usize , 2usize) => Some (6usize) , (6usize , 9usize) => Some (6usize) , (7usize , 3usize) => Some (8usize) , (_ , _) => None } } # [doc = r" Returns the token recognized when scanning stops in `state`."] pub fn accepting (state : usize) -> Option < TokenType > { match state { 1usize => Some (TokenType :: TkKey) , 2usize => Some (TokenType :: TkRbrack) , 3usize => Some (TokenType :: TkLbrack) , 4usize => Some (TokenType :: TkValue) , 5usize => Some (TokenType :: TkComment) , 6usize => Some (TokenType :: TkWhitespace) , 8usize => Some (TokenType :: TkNewline) , _ => None } } # [doc = r" A line and a column in the input, both starting at 1. Columns count characters, not"] # [doc = r" bytes."] # [derive (Debug , Clone , Copy , PartialEq , Eq , PartialOrd , Ord , Hash)] pub struct Location { pub line : usize , pub column : usize } impl Location { fn advance (& mut self , text : & str , after_cr : bool) { let mut after_cr = after_cr ; for ch in text . chars () { match ch { '\n' if after_cr => { } '\n' | '\r' => { self . line += 1 ; self . column = 1 ; } _ => self . column += 1 } after_cr = ch == '\r' ; } } } # [doc = r" The bytes of a token in the input, with the location of its first character and of"] # [doc = r" the character after it."] # [derive (Debug , Clone , PartialEq , Eq)] pub struct LocatedSpan { pub span : std :: ops :: Range < usize > , pub start : Location , pub end : Location } pub struct Lexer < 'src > { src : & 'src str , start : usize , position : usize , start_location : Location } impl < 'src > Lexer < 'src > { pub fn new (src : & 'src str) -> Self { Lexer { src , start : 0 , position : 0 , start_location : Location { line : 1 , column : 1 } } } fn get_alphabet_index (c : u32) -> Option < usize > { match c { 0u32 => Some (0usize) , 1u32 ..= 8u32 => Some (1usize) , 9u32 => Some (2usize) , 10u32 => Some (3usize) , 11u32 => Some (4usize) , 12u32 => Some (5usize) , 13u32 => Some (6usize) , 14u32 => Some (7usize) , 15u32 ..= 31u32 => Some (8usize) , 32u32 => Some (9usize) , 33u32 ..= 34u32 => Some (10usize) , 35u32 => Some (11usize) , 36u32 ..= 44u32 => Some (12usize) , 45u32 => Some (13usize) , 46u32 => Some (14usize) , 47u32 => Some (15usize) , 48u32 => Some (16usize) , 49u32 ..= 56u32 => Some (17usize) , 57u32 => Some (18usize) , 58u32 => Some (19usize) , 59u32 => Some (20usize) , 60u32 => Some (21usize) , 61u32 => Some (22usize) , 62u32 ..= 64u32 => Some (23usize) , 65u32 => Some (24usize) , 66u32 ..= 89u32 => Some (25usize) , 90u32 => Some (26usize) , 91u32 => Some (27usize) , 92u32 => Some (28usize) , 93u32 => Some (29usize) , 94u32 => Some (30usize) , 95u32 => Some (31usize) , 96u32 => Some (32usize) , 97u32 => Some (33usize) , 98u32 ..= 121u32 => Some (34usize) , 122u32 => Some (35usize) , 123u32 ..= 1114110u32 => Some (36usize) , 1114111u32 => Some (37usize) , _ => None } } fn get_alphabet_range (c : usize) -> Option < std :: ops :: RangeInclusive < u32 >> { match c { 0usize => Some (0u32 ..= 0u32) , 1usize => Some (1u32 ..= 8u32) , 2usize => Some (9u32 ..= 9u32) , 3usize => Some (10u32 ..= 10u32) , 4usize => Some (11u32 ..= 11u32) , 5usize => Some (12u32 ..= 12u32) , 6usize => Some (13u32 ..= 13u32) , 7usize => Some (14u32 ..= 14u32) , 8usize => Some (15u32 ..= 31u32) , 9usize => Some (32u32 ..= 32u32) , 10usize => Some (33u32 ..= 34u32) , 11usize => Some (35u32 ..= 35u32) , 12usize => Some (36u32 ..= 44u32) , 13usize => Some (45u32 ..= 45u32) , 14usize => Some (46u32 ..= 46u32) , 15usize => Some (47u32 ..= 47u32) , 16usize => Some (48u32 ..= 48u32) , 17usize => Some (49u32 ..= 56u32) , 18usize => Some (57u32 ..= 57u32) , 19usize => Some (58u32 ..= 58u32) , 20usize => Some (59u32 ..= 59u32) , 21usize => Some (60u32 ..= 60u32) , 22usize => Some (61u32 ..= 61u32) , 23usize => Some (62u32 ..= 64u32) , 24usize => Some (65u32 ..= 65u32) , 25usize => Some (66u32 ..= 89u32) , 26usize => Some (90u32 ..= 90u32) , 27usize => Some (91u32 ..= 91u32) , 28usize => Some (92u32 ..= 92u32) , 29usize => Some (93u32 ..= 93u32) , 30usize => Some (94u32 ..= 94u32) , 31usize => Some (95u32 ..= 95u32) , 32usize => Some (96u32 ..= 96u32) , 33usize => Some (97u32 ..= 97u32) , 34usize => Some (98u32 ..= 121u32) , 35usize => Some (122u32 ..= 122u32) , 36usize => Some (123u32 ..= 1114110u32) , 37usize => Some (1114111u32 ..= 1114111u32) , _ => None } } # [allow (clippy :: should_implement_trait)] pub fn next (& mut self) -> Result < TokenType , LexerError > { let mut state = START_STATE ; self . begin_token () ; loop { let next_ch = self . src [self . position ..] . chars () . next () . unwrap_or ('\0') ; let class = if let Some (class) = classify (next_ch) { class } else { return Err (LexerError :: InvalidChar { bad_ch : next_ch as u32 }) ; } ; if state == START_STATE && class == END_OF_INPUT_CLASS { return Ok (TokenType :: EndOfFile) ; } else if let Some (target) = step (state , class) { self . position += next_ch . len_utf8 () ; state = target ; self . skip_ascii_run (state) ; } else if let Some (token) = accepting (state) { if matches ! (token , TokenType :: TkWhitespace | TokenType :: TkComment) { state = START_STATE ; self . begin_token () ; continue ; } return Ok (token) ; } else { return Err (LexerError :: UnexpectedAlphabet { range : Lexer :: get_alphabet_range (class) . unwrap () }) ; } } } fn skip_ascii_run (& mut self , state : usize) { match state { 1usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 45u8 ..= 46u8 | 48u8 ..= 57u8 | 65u8 ..= 90u8 | 95u8 ..= 95u8 | 97u8 ..= 122u8)) . unwrap_or (rest . len ()) ; } , 4usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 1u8 ..= 9u8 | 11u8 ..= 12u8 | 14u8 ..= 122u8)) . unwrap_or (rest . len ()) ; } , 5usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 1u8 ..= 9u8 | 11u8 ..= 12u8 | 14u8 ..= 122u8)) . unwrap_or (rest . len ()) ; } , 6usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 9u8 ..= 9u8 | 32u8 ..= 32u8)) . unwrap_or (rest . len ()) ; } , _ => { } } } fn begin_token (& mut self) { let after_cr = self . after_cr () ; self . start_location . advance (& self . src [self . start .. self . position] , after_cr) ; self . start = self . position ; } fn after_cr (& self) -> bool { self . start > 0 && self . src . as_bytes () [self . start - 1] == b'\r' } pub fn span (& self) -> std :: ops :: Range < usize > { self . start .. self . position } pub fn slice (& self) -> & 'src str { & self . src [self . span ()] } # [doc = r" The location the current token starts at."] # [allow (dead_code)] pub fn position (& self) -> Location { self . start_location } # [doc = r" The span of the current token, with the locations it starts and ends at."] # [allow (dead_code)] pub fn span_with_location (& self) -> LocatedSpan { let mut end = self . start_location ; end . advance (self . slice () , self . after_cr ()) ; LocatedSpan { span : self . span () , start : self . start_location , end } } # [doc = r" Lets the context decide the type of every token but `EndOfFile`."] # [allow (dead_code)] pub fn with_context < C : LexerContext > (self , context : SharedContext < C >) -> ContextLexer < 'src , C > { ContextLexer { lexer : self , context } } } # [doc = r" Feeds the state of the parser back into the lexer, for languages in which the type of"] # [doc = r" a token depends on what was parsed before, like the names declared by a `typedef` in C."] # [allow (dead_code)] pub trait LexerContext { fn classify (& mut self , token : TokenType , text : & str) -> TokenType ; } # [doc = r" The visitor of the parser keeps a clone to update the context the lexer reads."] # [allow (dead_code)] pub type SharedContext < C > = std :: rc :: Rc < std :: cell :: RefCell < C >> ; # [doc = r" A lexer which passes its tokens through a `LexerContext`. The LR parser asks for the next"] # [doc = r" token right after shifting the previous one, so the context contains the updates of"] # [doc = r" the visitor up to that shift."] # [allow (dead_code)] pub struct ContextLexer < 'src , C : LexerContext > { lexer : Lexer < 'src > , context : SharedContext < C > } # [allow (dead_code)] impl < 'src , C : LexerContext > ContextLexer < 'src , C > { # [allow (clippy :: should_implement_trait)] pub fn next (& mut self) -> Result < TokenType , LexerError > { match self . lexer . next () ? { TokenType :: EndOfFile => Ok (TokenType :: EndOfFile) , token => Ok (self . context . borrow_mut () . classify (token , self . lexer . slice ())) } } pub fn span (& self) -> std :: ops :: Range < usize > { self . lexer . span () } pub fn slice (& self) -> & 'src str { self . lexer . slice () } pub fn position (& self) -> Location { self . lexer . position () } pub fn span_with_location (& self) -> LocatedSpan { self . lexer . span_with_location () } pub fn context (& self) -> & SharedContext < C > { & self . context } } # [doc = r" A lexer which reads its input character by character instead of borrowing it whole,"] # [doc = r" for large files or network streams. Only the text of the current token is kept."] # [allow (dead_code)] pub struct StreamLexer < I : Iterator < Item = std :: io :: Result < char >> > { input : I , peeked : Option < char > , ended : bool , text : String , start : usize , start_location : Location , after_cr : bool } # [allow (dead_code)] impl < I : Iterator < Item = std :: io :: Result < char >> > StreamLexer < I > { pub fn new (input : I) -> Self { StreamLexer { input , peeked : None , ended : false , text : String :: new () , start : 0 , start_location : Location { line : 1 , column : 1 } , after_cr : false } } fn peek (& mut self) -> Result < char , LexerError > { if self . peeked . is_none () && ! self . ended { match self . input . next () { Some (Ok (ch)) => self . peeked = Some (ch) , Some (Err (error)) => return Err (LexerError :: Read (error)) , None => self . ended = true } } Ok (self . peeked . unwrap_or ('\0')) } # [allow (clippy :: should_implement_trait)] pub fn next (& mut self) -> Result < TokenType , LexerError > { let mut state = START_STATE ; self . begin_token () ; loop { let next_ch = self . peek () ? ; let class = if let Some (class) = classify (next_ch) { class } else { return Err (LexerError :: InvalidChar { bad_ch : next_ch as u32 }) ; } ; if state == START_STATE && class == END_OF_INPUT_CLASS { return Ok (TokenType :: EndOfFile) ; } else if let Some (target) = step (state , class) { self . text . push (self . peeked . take () . unwrap ()) ; state = target ; } else if let Some (token) = accepting (state) { if matches ! (token , TokenType :: TkWhitespace | TokenType :: TkComment) { state = START_STATE ; self . begin_token () ; continue ; } return Ok (token) ; } else { return Err (LexerError :: UnexpectedAlphabet { range : Lexer :: get_alphabet_range (class) . unwrap () }) ; } } } fn begin_token (& mut self) { self . start_location . advance (& self . text , self . after_cr) ; self . start += self . text . len () ; if let Some (last) = self . text . chars () . last () { self . after_cr = last == '\r' ; } self . text . clear () ; } # [doc = r" The bytes of the current token, counted in UTF-8 from the start of the input."] pub fn span (& self) -> std :: ops :: Range < usize > { self . start .. self . start + self . text . len () } # [doc = r" The text of the current token, which is only kept until the next one is read."] pub fn slice (& self) -> & str { & self . text } # [doc = r" The location the current token starts at."] pub fn position (& self) -> Location { self . start_location } # [doc = r" The span of the current token, with the locations it starts and ends at."] pub fn span_with_location (& self) -> LocatedSpan { let mut end = self . start_location ; end . advance (& self . text , self . after_cr) ; LocatedSpan { span : self . span () , start : self . start_location , end } } } # [allow (dead_code)] impl < C : Iterator < Item = char >> StreamLexer < std :: iter :: Map < C , fn (char) -> std :: io :: Result < char >> > { # [doc = r" Lexes characters which can not fail to be read."] pub fn from_chars < T : IntoIterator < IntoIter = C >> (chars : T) -> Self { StreamLexer :: new (chars . into_iter () . map (Ok as fn (char) -> std :: io :: Result < char >)) } } # [allow (dead_code)] impl < R : std :: io :: Read > StreamLexer < Utf8Chars < R >> { # [doc = r" Lexes the UTF-8 text of a reader, which is buffered internally."] pub fn from_reader (reader : R) -> Self { StreamLexer :: new (Utf8Chars { bytes : std :: io :: Read :: bytes (std :: io :: BufReader :: new (reader)) }) } } # [doc = r" Decodes the characters of a UTF-8 reader. Invalid UTF-8 is reported as an error of"] # [doc = r" kind `InvalidData`."] # [allow (dead_code)] pub struct Utf8Chars < R : std :: io :: Read > { bytes : std :: io :: Bytes < std :: io :: BufReader < R >> } impl < R : std :: io :: Read > Iterator for Utf8Chars < R > { type Item = std :: io :: Result < char > ; fn next (& mut self) -> Option < Self :: Item > { let first = match self . bytes . next () ? { Ok (first) => first , Err (error) => return Some (Err (error)) } ; let width = match first { 0x00 ..= 0x7f => 1 , 0xc0 ..= 0xdf => 2 , 0xe0 ..= 0xef => 3 , 0xf0 ..= 0xf7 => 4 , _ => 0 } ; let mut encoded = [first , 0 , 0 , 0] ; for byte in encoded . iter_mut () . take (width) . skip (1) { match self . bytes . next () { Some (Ok (next)) => * byte = next , Some (Err (error)) => return Some (Err (error)) , None => break } } let decoded = std :: str :: from_utf8 (& encoded [.. width]) . ok () . and_then (| text | text . chars () . next ()) ; Some (decoded . ok_or_else (|| { std :: io :: Error :: new (std :: io :: ErrorKind :: InvalidData , "stream did not contain valid UTF-8") })) } }
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,7 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+# [derive (Clone , Copy , Debug)] pub enum TokenType { EndOfFile , TkWhitespace , TkComment , TkValue , TkKey , TkNewline , TkRbrack , TkLbrack , }
+
+// <lapex:keep name="code">
+// </lapex:keep>
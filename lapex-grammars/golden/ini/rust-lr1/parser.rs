@@ -0,0 +1,6 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ;pub trait Visitor < T > { fn shift (& mut self , token : TokenType , data : T) ; # [doc = "property(0) -> KEY(3) VALUE(2)"] fn reduce_property (& mut self) ; # [doc = "section(1) -> LBRACK(6) KEY(3) RBRACK(5)"] fn reduce_section (& mut self) ; # [doc = "line(2) -> <line_alt>(7)"] fn reduce_line (& mut self) ; # [doc = "lines(3) -> <lines_opt>(8) <lines_star>(9)"] fn reduce_lines (& mut self) ; # [doc = "file(4) -> lines(3)"] fn reduce_file (& mut self) ; # [doc = "<line_alt>(7) -> section(1)"] fn reduce_line_alt_1 (& mut self) ; # [doc = "<line_alt>(7) -> property(0)"] fn reduce_line_alt_2 (& mut self) ; # [doc = "<lines_opt>(8) -> line(2)"] fn reduce_lines_opt_some (& mut self) ; # [doc = "<lines_opt>(8) -> <eps>"] fn reduce_lines_opt_none (& mut self) ; # [doc = "<lines_star>(9) -> <eps>"] fn reduce_lines_star_empty (& mut self) ; # [doc = "<lines_star>(9) -> NEWLINE(4) <lines_opt_2>(10) <lines_star>(9)"] fn reduce_lines_star_tail (& mut self) ; # [doc = "<lines_opt_2>(10) -> line(2)"] fn reduce_lines_opt_2_some (& mut self) ; # [doc = "<lines_opt_2>(10) -> <eps>"] fn reduce_lines_opt_2_none (& mut self) ; }pub struct Parser < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> { token_function : F , visitor : V , } # [derive (Debug , Clone , Copy)] # [allow (clippy :: enum_variant_names)] enum NonTerminalType { NtProperty , NtSection , NtLine , NtLines , NtFile , NtLineAlt , NtLinesOpt , NtLinesStar , NtLinesOpt2 } # [derive (Debug , Clone , Copy)] enum StackSymbol { Terminal { token : TokenType } , NonTerminal { non_terminal : NonTerminalType } , State { state_id : usize } , } # [derive (Clone , Copy)] enum ReducedRule { Rule0 , Rule1 , Rule2 , Rule3 , Rule4 , Rule5 , Rule6 , Rule7 , Rule8 , Rule9 , Rule10 , Rule11 , Rule12 } enum Action { Shift , Reduce { rule : ReducedRule } } enum Goto { Accept , State { state_id : usize } } # [derive (Debug , Clone)] pub enum ParserError { UnexpectedToken { got : TokenType , expected : Vec < TokenType > } , } impl std :: error :: Error for ParserError { } impl std :: fmt :: Display for ParserError { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { ParserError :: UnexpectedToken { got , expected } => write ! (f , "Unexpected token {:?}, expected one of: {:?}" , got , expected) , } } } impl < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> Parser < T , F , V > { # [doc = r" The token function is not called again once it returned `TokenType::EndOfFile`,"] # [doc = r" and `EndOfFile` is never passed to `Visitor::shift`."] pub fn new (token_function : F , visitor : V) -> Self { Parser { token_function , visitor , } } fn next_action (& self , state : usize , next_token : TokenType) -> Result < Action , ParserError > { match (state , next_token) { (0usize , TokenType :: TkKey) => Ok (Action :: Shift) , (0usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (0usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (0usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (0usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkKey , TokenType :: TkNewline , TokenType :: TkLbrack , TokenType :: EndOfFile] }) , (1usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (1usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (1usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (2usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (2usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (2usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (3usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (3usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (3usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (4usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (4usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (5usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (5usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (5usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (6usize , TokenType :: TkNewline) => Ok (Action :: Shift) , (6usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (6usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (7usize , TokenType :: TkValue) => Ok (Action :: Shift) , (7usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkValue] }) , (8usize , TokenType :: TkKey) => Ok (Action :: Shift) , (8usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkKey] }) , (9usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (9usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (10usize , TokenType :: TkKey) => Ok (Action :: Shift) , (10usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (10usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (10usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (10usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkKey , TokenType :: TkNewline , TokenType :: TkLbrack , TokenType :: EndOfFile] }) , (11usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (11usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (12usize , TokenType :: TkRbrack) => Ok (Action :: Shift) , (12usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (13usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (13usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (13usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (14usize , TokenType :: TkNewline) => Ok (Action :: Shift) , (14usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (14usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (15usize , TokenType :: TkNewline) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (15usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (15usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNewline , TokenType :: EndOfFile] }) , (16usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (16usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } fn next_goto (& self , state : usize , symbol : StackSymbol , next_token : TokenType) -> Result < Goto , ParserError > { match (state , symbol) { (0usize , StackSymbol :: Terminal { token : TokenType :: TkKey }) => Ok (Goto :: State { state_id : 7usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 8usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProperty }) => Ok (Goto :: State { state_id : 1usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSection }) => Ok (Goto :: State { state_id : 2usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLine }) => Ok (Goto :: State { state_id : 3usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLines }) => Ok (Goto :: State { state_id : 4usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFile }) => Ok (Goto :: Accept) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLineAlt }) => Ok (Goto :: State { state_id : 5usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesOpt }) => Ok (Goto :: State { state_id : 6usize }) , (6usize , StackSymbol :: Terminal { token : TokenType :: TkNewline }) => Ok (Goto :: State { state_id : 10usize }) , (6usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesStar }) => Ok (Goto :: State { state_id : 9usize }) , (7usize , StackSymbol :: Terminal { token : TokenType :: TkValue }) => Ok (Goto :: State { state_id : 11usize }) , (8usize , StackSymbol :: Terminal { token : TokenType :: TkKey }) => Ok (Goto :: State { state_id : 12usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkKey }) => Ok (Goto :: State { state_id : 7usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 8usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProperty }) => Ok (Goto :: State { state_id : 1usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSection }) => Ok (Goto :: State { state_id : 2usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLine }) => Ok (Goto :: State { state_id : 13usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLineAlt }) => Ok (Goto :: State { state_id : 5usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesOpt2 }) => Ok (Goto :: State { state_id : 14usize }) , (12usize , StackSymbol :: Terminal { token : TokenType :: TkRbrack }) => Ok (Goto :: State { state_id : 15usize }) , (14usize , StackSymbol :: Terminal { token : TokenType :: TkNewline }) => Ok (Goto :: State { state_id : 10usize }) , (14usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesStar }) => Ok (Goto :: State { state_id : 16usize }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } # [doc = r" Passes every token through `filter` before the parser sees it."] # [allow (dead_code)] pub fn with_filter < P : FnMut (& (TokenType , T)) -> FilterAction < T >> (self , filter : P ,) -> Parser < T , impl FnMut () -> (TokenType , T) , V > { Parser { token_function : filter_tokens (self . token_function , filter) , visitor : self . visitor , } } fn reduce_stack_and_visit (& mut self , rule : ReducedRule , stack : & mut Vec < StackSymbol > , state : usize , next_token : TokenType) -> Result < () , ParserError > { let (to_pop , reduced) = match rule { ReducedRule :: Rule0 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtProperty }) , ReducedRule :: Rule1 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtSection }) , ReducedRule :: Rule2 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLineAlt }) , ReducedRule :: Rule3 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLineAlt }) , ReducedRule :: Rule4 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLine }) , ReducedRule :: Rule5 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesOpt }) , ReducedRule :: Rule6 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesOpt }) , ReducedRule :: Rule7 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesOpt2 }) , ReducedRule :: Rule8 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesOpt2 }) , ReducedRule :: Rule9 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesStar }) , ReducedRule :: Rule10 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLinesStar }) , ReducedRule :: Rule11 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtLines }) , ReducedRule :: Rule12 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtFile }) } ; if stack . len () < to_pop * 2 { unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } stack . truncate (stack . len () - to_pop * 2) ; stack . push (reduced) ; match rule { ReducedRule :: Rule0 => self . visitor . reduce_property () , ReducedRule :: Rule1 => self . visitor . reduce_section () , ReducedRule :: Rule4 => self . visitor . reduce_line () , ReducedRule :: Rule11 => self . visitor . reduce_lines () , ReducedRule :: Rule12 => self . visitor . reduce_file () , ReducedRule :: Rule2 => self . visitor . reduce_line_alt_1 () , ReducedRule :: Rule3 => self . visitor . reduce_line_alt_2 () , ReducedRule :: Rule5 => self . visitor . reduce_lines_opt_some () , ReducedRule :: Rule6 => self . visitor . reduce_lines_opt_none () , ReducedRule :: Rule9 => self . visitor . reduce_lines_star_empty () , ReducedRule :: Rule10 => self . visitor . reduce_lines_star_tail () , ReducedRule :: Rule7 => self . visitor . reduce_lines_opt_2_some () , ReducedRule :: Rule8 => self . visitor . reduce_lines_opt_2_none () } Ok (()) } # [doc = r" Parses the whole input and returns its errors. The grammar has no error"] # [doc = r" productions, so parsing stops at the first error."] # [allow (dead_code)] pub fn parse_all (& mut self) -> (Vec < ParserError > , ()) { match self . parse () { Ok (()) => (Vec :: new () , ()) , Err (error) => (vec ! [error] , ()) , } } pub fn parse (& mut self) -> Result < () , ParserError > { let mut lookahead = std :: collections :: VecDeque :: new () ; lookahead . push_back ((self . token_function) ()) ; let mut stack = Vec :: new () ; stack . push (StackSymbol :: State { state_id : 0usize }) ; let mut state = 0usize ; while ! stack . is_empty () { let (next_token , _) = lookahead . front () . unwrap () ; let next_token = * next_token ; let action = self . next_action (state , next_token) ? ; match action { Action :: Shift => { let (next_token , next_data) = lookahead . pop_front () . unwrap () ; debug_assert ! (! matches ! (next_token , TokenType :: EndOfFile) , "EndOfFile must not be shifted") ; stack . push (StackSymbol :: Terminal { token : next_token }) ; self . visitor . shift (next_token , next_data) ; lookahead . push_back ((self . token_function) ()) ; } Action :: Reduce { rule : reduced_rule } => { self . reduce_stack_and_visit (reduced_rule , & mut stack , state , next_token) ? ; } } let (goto_state , current_symbol) = match stack . as_slice () { [.. , StackSymbol :: State { state_id } , current_symbol] => (* state_id , * current_symbol) , _ => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } ; let goto = self . next_goto (goto_state , current_symbol , next_token) ? ; match goto { Goto :: Accept => { stack . pop () ; stack . pop () ; } Goto :: State { state_id } => { stack . push (StackSymbol :: State { state_id }) ; state = state_id ; } } } Ok (()) } } # [doc = r" What a token filter does with a token before the parser sees it."] # [allow (dead_code)] pub enum FilterAction < T > { # [doc = r" Passes the token on."] Keep , # [doc = r" Drops the token."] Drop , # [doc = r" Passes another token on in place of this one."] Replace (TokenType , T) , # [doc = r" Passes these tokens on before the token itself."] Inject (Vec < (TokenType , T) >) , } fn apply_filter < T , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (filter : & mut P , token : (TokenType , T) , pending : & mut std :: collections :: VecDeque < (TokenType , T) > ,) { let is_end = matches ! (token . 0 , TokenType :: EndOfFile) ; match filter (& token) { FilterAction :: Keep => pending . push_back (token) , FilterAction :: Drop => { if is_end { pending . push_back (token) ; } } FilterAction :: Replace (replacement , data) => { pending . push_back ((replacement , data)) ; if is_end { pending . push_back (token) ; } } FilterAction :: Inject (tokens) => { pending . extend (tokens) ; pending . push_back (token) ; } } } # [doc = r" Wraps a token function, so that every token is passed through `filter` first."] # [allow (dead_code)] pub fn filter_tokens < T , F : FnMut () -> (TokenType , T) , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (mut token_function : F , mut filter : P ,) -> impl FnMut () -> (TokenType , T) { let mut pending = std :: collections :: VecDeque :: new () ; move || loop { if let Some (token) = pending . pop_front () { return token ; } apply_filter (& mut filter , token_function () , & mut pending) ; } } # [doc = r" Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`"] # [doc = r" once the iterator is exhausted."] # [allow (dead_code)] pub fn token_function_from_iter < T : Default , I : IntoIterator < Item = (TokenType , T) >> (tokens : I ,) -> impl FnMut () -> (TokenType , T) { let mut tokens = tokens . into_iter () ; move || { tokens . next () . unwrap_or_else (|| (TokenType :: EndOfFile , T :: default ())) } }
+// <lapex:keep name="code">
+// </lapex:keep>
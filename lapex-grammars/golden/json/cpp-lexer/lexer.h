@@ -0,0 +1,73 @@
+#ifndef LAPEX_LEXER_H
+#define LAPEX_LEXER_H
+
+#include "tokens.h"
+#include <istream>
+#include <cstdint>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    // a line and a column in the input, both starting at 1. Columns count code points, not bytes
+    struct Location
+    {
+        size_t line;
+        size_t column;
+    };
+
+    // the positions of a token like Lexer::start() and Lexer::end(), with the locations of its first
+    // code point and of the one after it
+    struct LocatedSpan
+    {
+        size_t start;
+        size_t end;
+        Location start_location;
+        Location end_location;
+    };
+
+    // feeds the state of the parser back into the lexer, for languages in which the type of a token
+    // depends on what was parsed before, like the names declared by a typedef in C. The visitor of
+    // the parser updates it, the LR parsers ask for the next token right after shifting the previous one
+    class Context
+    {
+    public:
+        virtual ~Context() = default;
+        // start and end are the positions of the token, like Lexer::start() and Lexer::end()
+        virtual TokenType classify(TokenType tk_type, size_t start, size_t end) = 0;
+    };
+
+    class Lexer
+    {
+        std::istream &in_chars;
+        Context *context;
+        uint32_t ch;
+        int err;
+        size_t pos;
+        size_t start_pos;
+        size_t end_pos;
+        Location location;
+        Location start_location;
+        Location end_location;
+        // whether the last character was a \r, whose line break a \n right after it belongs to
+        bool after_cr;
+
+        TokenType scan();
+
+    public:
+        // the context is optional and decides the type of every token but TK_EOF and TK_ERR
+        Lexer(std::istream &in_chars, Context *context = nullptr);
+        TokenType next();
+        size_t start();
+        size_t end();
+        // the location the current token starts at
+        Location position();
+        LocatedSpan span_with_location();
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,46 @@
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    const char *get_token_name(TokenType tk_type) {
+        switch (tk_type) {
+case TokenType::TK_ERR:
+return "<ERR>";
+case TokenType::TK_EOF:
+return "<EOF>";
+case TokenType::TK_WHITESPACE:
+return "WHITESPACE";
+case TokenType::TK_NUMBER:
+return "NUMBER";
+case TokenType::TK_STRING:
+return "STRING";
+case TokenType::TK_KW_NULL:
+return "KW_NULL";
+case TokenType::TK_KW_FALSE:
+return "KW_FALSE";
+case TokenType::TK_KW_TRUE:
+return "KW_TRUE";
+case TokenType::TK_COMMA:
+return "COMMA";
+case TokenType::TK_COLON:
+return "COLON";
+case TokenType::TK_RBRACK:
+return "RBRACK";
+case TokenType::TK_LBRACK:
+return "LBRACK";
+case TokenType::TK_RBRACE:
+return "RBRACE";
+case TokenType::TK_LBRACE:
+return "LBRACE";
+default:
+return nullptr;
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
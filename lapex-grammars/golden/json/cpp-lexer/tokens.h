@@ -0,0 +1,36 @@
+#ifndef LAPEX_TOKENS_H
+#define LAPEX_TOKENS_H
+
+#include <cstdint>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace lexer
+{
+    enum class TokenType : uint32_t
+    {
+        TK_ERR = 0,
+        TK_EOF = 1,
+        TK_WHITESPACE,
+TK_NUMBER,
+TK_STRING,
+TK_KW_NULL,
+TK_KW_FALSE,
+TK_KW_TRUE,
+TK_COMMA,
+TK_COLON,
+TK_RBRACK,
+TK_LBRACK,
+TK_RBRACE,
+TK_LBRACE,
+
+    };
+    
+    const char *get_token_name(TokenType tk_type);
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,370 @@
+#include "parser_impl.h"
+
+#include <sstream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser {
+    void push_production_from_table(Symbol non_terminal, const lexer::TokenType* lookahead, ParseStack& parse_stack) {
+        switch(non_terminal.identifier) {
+case 0: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_NUMBER: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_KW_NULL: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_KW_FALSE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_KW_TRUE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+case lexer::TokenType::TK_LBRACE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'elements'. Expected one of: NUMBER, STRING, KW_NULL, KW_FALSE, KW_TRUE, LBRACK, LBRACE");
+}
+break;
+}
+case 1: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_RBRACK)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ARRAY_OPT)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_LBRACK)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'array'. Expected one of: LBRACK");
+}
+break;
+}
+case 2: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_COLON)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_STRING)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'member'. Expected one of: STRING");
+}
+break;
+}
+case 3: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBERS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBER)};
+parse_stack.push(sym1);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'members'. Expected one of: STRING");
+}
+break;
+}
+case 4: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_LBRACE: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_RBRACE)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_OBJECT_OPT)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_LBRACE)};
+parse_stack.push(sym2);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'object'. Expected one of: LBRACE");
+}
+break;
+}
+case 5: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_NUMBER: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_NULL: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_FALSE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_TRUE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'value'. Expected one of: NUMBER, STRING, KW_NULL, KW_FALSE, KW_TRUE, LBRACK, LBRACE");
+}
+break;
+}
+case 6: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_NUMBER: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_NULL: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_FALSE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_TRUE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for production 'document'. Expected one of: NUMBER, STRING, KW_NULL, KW_FALSE, KW_TRUE, LBRACK, LBRACE");
+}
+break;
+}
+case 12: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_COMMA: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_COMMA)};
+parse_stack.push(sym2);
+break;
+}
+case lexer::TokenType::TK_RBRACK: {
+// epsilon; push nothing to stack
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: COMMA, RBRACK");
+}
+break;
+}
+case 13: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_NUMBER: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_NULL: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_FALSE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_TRUE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_RBRACK: {
+// epsilon; push nothing to stack
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: NUMBER, STRING, KW_NULL, KW_FALSE, KW_TRUE, RBRACK, LBRACK, LBRACE");
+}
+break;
+}
+case 14: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_COMMA: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBERS_STAR)};
+parse_stack.push(sym0);
+Symbol sym1{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBER)};
+parse_stack.push(sym1);
+Symbol sym2{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_COMMA)};
+parse_stack.push(sym2);
+break;
+}
+case lexer::TokenType::TK_RBRACE: {
+// epsilon; push nothing to stack
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: COMMA, RBRACE");
+}
+break;
+}
+case 15: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBERS)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_RBRACE: {
+// epsilon; push nothing to stack
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: STRING, RBRACE");
+}
+break;
+}
+case 16: {
+switch (lookahead[0]) {
+case lexer::TokenType::TK_NUMBER: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_NUMBER)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_STRING: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_STRING)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_NULL: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_KW_NULL)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_FALSE: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_KW_FALSE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_KW_TRUE: {
+Symbol sym0{SymbolKind::Terminal, static_cast<uint32_t>(lexer::TokenType::TK_KW_TRUE)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACK: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ARRAY)};
+parse_stack.push(sym0);
+break;
+}
+case lexer::TokenType::TK_LBRACE: {
+Symbol sym0{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_OBJECT)};
+parse_stack.push(sym0);
+break;
+}
+default:
+throw std::runtime_error("Encountered unknown lookahead for anonymous production. Expected one of: NUMBER, STRING, KW_NULL, KW_FALSE, KW_TRUE, LBRACK, LBRACE");
+}
+break;
+}
+}
+
+    }
+
+
+    void throw_unexpected_token_error(lexer::TokenType expected, lexer::TokenType got) {
+        std::ostringstream os;
+        os << "Unexpected token '" << lexer::get_token_name(got) << "', expected token '" << lexer::get_token_name(expected) << "'";
+        throw std::runtime_error(os.str());
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
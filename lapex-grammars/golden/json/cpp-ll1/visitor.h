@@ -0,0 +1,37 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void token(lexer::TokenType tk_type, T data) = 0;
+        virtual void enter_elements() = 0;
+virtual void exit_elements() = 0;
+virtual void enter_array() = 0;
+virtual void exit_array() = 0;
+virtual void enter_member() = 0;
+virtual void exit_member() = 0;
+virtual void enter_members() = 0;
+virtual void exit_members() = 0;
+virtual void enter_object() = 0;
+virtual void exit_object() = 0;
+virtual void enter_value() = 0;
+virtual void exit_value() = 0;
+virtual void enter_document() = 0;
+virtual void exit_document() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
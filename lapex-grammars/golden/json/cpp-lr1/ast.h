@@ -0,0 +1,253 @@
+#ifndef LAPEX_AST_H
+#define LAPEX_AST_H
+
+#include "visitor.h"
+#include <memory>
+#include <utility>
+#include <variant>
+#include <vector>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    namespace ast
+    {
+        template <class T>
+        struct Terminal
+        {
+            lexer::TokenType type;
+            T data;
+        };
+
+        template <class T>
+struct ElementsNode;
+template <class T>
+struct ArrayNode;
+template <class T>
+struct MemberNode;
+template <class T>
+struct MembersNode;
+template <class T>
+struct ObjectNode;
+template <class T>
+struct ValueNode;
+template <class T>
+struct DocumentNode;
+
+
+        template <class T>
+        using Node = std::variant<Terminal<T>, std::unique_ptr<ElementsNode<T>>, std::unique_ptr<ArrayNode<T>>, std::unique_ptr<MemberNode<T>>, std::unique_ptr<MembersNode<T>>, std::unique_ptr<ObjectNode<T>>, std::unique_ptr<ValueNode<T>>, std::unique_ptr<DocumentNode<T>>>;
+
+        // elements(0) -> value(5) <elements_star>(12)
+template <class T>
+struct ElementsNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// array(1) -> LBRACK(9) <array_opt>(13) RBRACK(8)
+template <class T>
+struct ArrayNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// member(2) -> STRING(2) COLON(7) value(5)
+template <class T>
+struct MemberNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// members(3) -> member(2) <members_star>(14)
+template <class T>
+struct MembersNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// object(4) -> LBRACE(11) <object_opt>(15) RBRACE(10)
+template <class T>
+struct ObjectNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// value(5) -> <value_alt>(16)
+template <class T>
+struct ValueNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+// document(6) -> value(5)
+template <class T>
+struct DocumentNode
+{
+// the rule that was reduced, numbered like the reduce methods of the visitor
+uint32_t alternative;
+std::vector<Node<T>> children;
+};
+
+
+        // Builds a tree while parsing. Anonymous non-terminals like repetitions have no node type,
+        // their children are added to the node of the enclosing production instead.
+        template <class T>
+        class TreeBuilder : public Visitor<T>
+        {
+        private:
+            // the nodes produced by each symbol on the parse stack
+            std::vector<std::vector<Node<T>>> stack;
+
+            std::vector<Node<T>> pop_children(size_t symbols)
+            {
+                std::vector<Node<T>> children;
+                auto first = stack.end() - symbols;
+                for (auto it = first; it != stack.end(); ++it)
+                {
+                    for (auto &node : *it)
+                    {
+                        children.push_back(std::move(node));
+                    }
+                }
+                stack.erase(first, stack.end());
+                return children;
+            }
+
+            template <class N>
+            void reduce_named(size_t symbols, uint32_t alternative)
+            {
+                auto node = std::make_unique<N>();
+                node->alternative = alternative;
+                node->children = pop_children(symbols);
+                std::vector<Node<T>> nodes;
+                nodes.push_back(std::move(node));
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_anonymous(size_t symbols)
+            {
+                stack.push_back(pop_children(symbols));
+            }
+
+        public:
+            void shift(lexer::TokenType tk_type, T data) override
+            {
+                std::vector<Node<T>> nodes;
+                nodes.push_back(Terminal<T>{tk_type, std::move(data)});
+                stack.push_back(std::move(nodes));
+            }
+
+            void reduce_elements() override
+{
+reduce_named<ElementsNode<T>>(2, 1);
+}
+void reduce_array() override
+{
+reduce_named<ArrayNode<T>>(3, 1);
+}
+void reduce_member() override
+{
+reduce_named<MemberNode<T>>(3, 1);
+}
+void reduce_members() override
+{
+reduce_named<MembersNode<T>>(2, 1);
+}
+void reduce_object() override
+{
+reduce_named<ObjectNode<T>>(3, 1);
+}
+void reduce_value() override
+{
+reduce_named<ValueNode<T>>(1, 1);
+}
+void reduce_document() override
+{
+reduce_named<DocumentNode<T>>(1, 1);
+}
+void reduce_elements_star_1() override
+{
+reduce_anonymous(0);
+}
+void reduce_elements_star_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_array_opt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_array_opt_2() override
+{
+reduce_anonymous(0);
+}
+void reduce_members_star_1() override
+{
+reduce_anonymous(0);
+}
+void reduce_members_star_2() override
+{
+reduce_anonymous(3);
+}
+void reduce_object_opt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_object_opt_2() override
+{
+reduce_anonymous(0);
+}
+void reduce_value_alt_1() override
+{
+reduce_anonymous(1);
+}
+void reduce_value_alt_2() override
+{
+reduce_anonymous(1);
+}
+void reduce_value_alt_3() override
+{
+reduce_anonymous(1);
+}
+void reduce_value_alt_4() override
+{
+reduce_anonymous(1);
+}
+void reduce_value_alt_5() override
+{
+reduce_anonymous(1);
+}
+void reduce_value_alt_6() override
+{
+reduce_anonymous(1);
+}
+void reduce_value_alt_7() override
+{
+reduce_anonymous(1);
+}
+
+
+            // Returns the node of the entry production once the input has been parsed.
+            Node<T> take_root()
+            {
+                Node<T> root = std::move(stack.back().front());
+                stack.clear();
+                return root;
+            }
+        };
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
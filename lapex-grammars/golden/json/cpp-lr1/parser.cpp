@@ -0,0 +1,2218 @@
+#include "parser_impl.h"
+
+#include <sstream>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    void throw_unexpected_token_error(const char* expected, lexer::TokenType got) {
+        std::ostringstream os;
+        os << "Unexpected token '" << lexer::get_token_name(got) << "', expected one of: " << expected;
+        throw std::runtime_error(os.str());
+    }
+
+    Action determine_action(uint32_t state, const lexer::TokenType &lookahead_token)
+    {
+        switch (state) {
+case 0: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NUMBER', 'STRING', 'KW_NULL', 'KW_FALSE', 'KW_TRUE', 'LBRACK', 'LBRACE'", lookahead_token);
+}
+}
+break;
+case 1: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 14};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 2: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 3: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 21};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 4: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 20};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 5: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 16};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 6: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 15};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 7: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 19};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 8: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 18};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 9: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 17};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 10: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NUMBER', 'STRING', 'KW_NULL', 'KW_FALSE', 'KW_TRUE', 'RBRACK', 'LBRACK', 'LBRACE'", lookahead_token);
+}
+}
+break;
+case 11: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+default:
+throw_unexpected_token_error("'STRING', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 12: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 3};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACK'", lookahead_token);
+}
+}
+break;
+case 13: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 14};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 14};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 14: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 15: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 16: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACK'", lookahead_token);
+}
+}
+break;
+case 17: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 20};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 20};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 18: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 16};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 16};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 19: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 15};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 15};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 20: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 19};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 19};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 21: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 18};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 18};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 22: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 17};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 17};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 23: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NUMBER', 'STRING', 'KW_NULL', 'KW_FALSE', 'KW_TRUE', 'RBRACK', 'LBRACK', 'LBRACE'", lookahead_token);
+}
+}
+break;
+case 24: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+default:
+throw_unexpected_token_error("'STRING', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 25: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 26: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 10};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACE'", lookahead_token);
+}
+}
+break;
+case 27: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACE'", lookahead_token);
+}
+}
+break;
+case 28: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COLON:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'COLON'", lookahead_token);
+}
+}
+break;
+case 29: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 2};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACK'", lookahead_token);
+}
+}
+break;
+case 30: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NUMBER', 'STRING', 'KW_NULL', 'KW_FALSE', 'KW_TRUE', 'LBRACK', 'LBRACE'", lookahead_token);
+}
+}
+break;
+case 31: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 32: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACK'", lookahead_token);
+}
+}
+break;
+case 33: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACE'", lookahead_token);
+}
+}
+break;
+case 34: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 9};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACE'", lookahead_token);
+}
+}
+break;
+case 35: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'STRING'", lookahead_token);
+}
+}
+break;
+case 36: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_EOF:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+default:
+throw_unexpected_token_error("'<EOF>'", lookahead_token);
+}
+}
+break;
+case 37: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NUMBER', 'STRING', 'KW_NULL', 'KW_FALSE', 'KW_TRUE', 'LBRACK', 'LBRACE'", lookahead_token);
+}
+}
+break;
+case 38: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 39: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 40: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACK'", lookahead_token);
+}
+}
+break;
+case 41: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 7};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 42: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 14};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 14};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 43: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 13};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 44: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 6};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 45: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 20};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 20};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 46: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 16};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 16};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 47: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 15};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 15};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 48: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 19};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 19};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 49: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 18};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 18};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 50: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 17};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 17};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 51: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_NUMBER:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 4};
+return act;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'NUMBER', 'STRING', 'KW_NULL', 'KW_FALSE', 'KW_TRUE', 'RBRACK', 'LBRACK', 'LBRACE'", lookahead_token);
+}
+}
+break;
+case 52: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_STRING:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 11};
+return act;
+}
+default:
+throw_unexpected_token_error("'STRING', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 53: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Reduce, 1};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACK'", lookahead_token);
+}
+}
+break;
+case 54: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 8};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACE'", lookahead_token);
+}
+}
+break;
+case 55: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACK:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACK'", lookahead_token);
+}
+}
+break;
+case 56: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Shift, 0};
+return act;
+}
+default:
+throw_unexpected_token_error("'RBRACE'", lookahead_token);
+}
+}
+break;
+case 57: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 5};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+case 58: {
+switch (lookahead_token) {
+case lexer::TokenType::TK_COMMA:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+case lexer::TokenType::TK_RBRACE:
+{
+Action act{ActionType::Reduce, 12};
+return act;
+}
+default:
+throw_unexpected_token_error("'COMMA', 'RBRACE'", lookahead_token);
+}
+}
+break;
+default:
+// Encountered a parser state that does not exist.
+std::terminate();
+}
+
+    }
+
+    void reduce_stack(uint32_t rule, Vector<Symbol> &parse_stack, Vector<Symbol> &rev_reduced_symbols)
+    {
+        switch(rule) {
+case 0: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 1: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 2: {
+for (size_t i = 0; i < 2; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ELEMENTS)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 3: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ARRAY_OPT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 4: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ARRAY_OPT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 5: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_ARRAY)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 6: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBER)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 7: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBERS_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 8: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBERS_STAR)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 9: {
+for (size_t i = 0; i < 2; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_MEMBERS)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 10: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_OBJECT_OPT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 11: {
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_OBJECT_OPT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 12: {
+for (size_t i = 0; i < 3; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_OBJECT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 13: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 14: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 15: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 16: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 17: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 18: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 19: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE_ALT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 20: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_VALUE)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+case 21: {
+for (size_t i = 0; i < 1; i++) {
+parse_stack.pop_back();
+Symbol reduced_symbol = parse_stack.back();
+rev_reduced_symbols.push_back(reduced_symbol);
+parse_stack.pop_back();
+}
+Symbol reduced_non_terminal{SymbolKind::NonTerminal, static_cast<uint32_t>(NonTerminalType::NT_DOCUMENT)};
+parse_stack.push_back(reduced_non_terminal);
+return;
+}
+default:
+// Tried reducing non-existent rule.
+std::terminate();
+}
+
+    }
+
+    Transition retrieve_next_state(uint32_t state, const Symbol &current_symbol)
+    {
+        switch (state) {
+case 0: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{5, false};
+return transition;
+}
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{6, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Transition transition{7, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Transition transition{8, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Transition transition{9, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Transition transition{10, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Transition transition{11, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ARRAY:
+{
+Transition transition{1, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT:
+{
+Transition transition{2, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE:
+{
+Transition transition{3, false};
+return transition;
+}
+case NonTerminalType::NT_DOCUMENT:
+{
+Transition transition{0, true};
+return transition;
+}
+case NonTerminalType::NT_VALUE_ALT:
+{
+Transition transition{4, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 10: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{18, false};
+return transition;
+}
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{19, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Transition transition{20, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Transition transition{21, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Transition transition{22, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Transition transition{23, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Transition transition{24, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ELEMENTS:
+{
+Transition transition{12, false};
+return transition;
+}
+case NonTerminalType::NT_ARRAY:
+{
+Transition transition{13, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT:
+{
+Transition transition{14, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE:
+{
+Transition transition{15, false};
+return transition;
+}
+case NonTerminalType::NT_ARRAY_OPT:
+{
+Transition transition{16, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE_ALT:
+{
+Transition transition{17, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 11: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{28, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_MEMBER:
+{
+Transition transition{25, false};
+return transition;
+}
+case NonTerminalType::NT_MEMBERS:
+{
+Transition transition{26, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT_OPT:
+{
+Transition transition{27, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 15: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COMMA:
+{
+Transition transition{30, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ELEMENTS_STAR:
+{
+Transition transition{29, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 16: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RBRACK:
+{
+Transition transition{31, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 23: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{18, false};
+return transition;
+}
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{19, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Transition transition{20, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Transition transition{21, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Transition transition{22, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Transition transition{23, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Transition transition{24, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ELEMENTS:
+{
+Transition transition{12, false};
+return transition;
+}
+case NonTerminalType::NT_ARRAY:
+{
+Transition transition{13, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT:
+{
+Transition transition{14, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE:
+{
+Transition transition{15, false};
+return transition;
+}
+case NonTerminalType::NT_ARRAY_OPT:
+{
+Transition transition{32, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE_ALT:
+{
+Transition transition{17, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 24: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{28, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_MEMBER:
+{
+Transition transition{25, false};
+return transition;
+}
+case NonTerminalType::NT_MEMBERS:
+{
+Transition transition{26, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT_OPT:
+{
+Transition transition{33, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 25: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COMMA:
+{
+Transition transition{35, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_MEMBERS_STAR:
+{
+Transition transition{34, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 27: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RBRACE:
+{
+Transition transition{36, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 28: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COLON:
+{
+Transition transition{37, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 30: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{18, false};
+return transition;
+}
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{19, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Transition transition{20, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Transition transition{21, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Transition transition{22, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Transition transition{23, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Transition transition{24, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ARRAY:
+{
+Transition transition{13, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT:
+{
+Transition transition{14, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE:
+{
+Transition transition{38, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE_ALT:
+{
+Transition transition{17, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 32: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RBRACK:
+{
+Transition transition{39, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 33: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RBRACE:
+{
+Transition transition{40, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 35: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{28, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_MEMBER:
+{
+Transition transition{41, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 37: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{46, false};
+return transition;
+}
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{47, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Transition transition{48, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Transition transition{49, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Transition transition{50, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Transition transition{51, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Transition transition{52, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ARRAY:
+{
+Transition transition{42, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT:
+{
+Transition transition{43, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE:
+{
+Transition transition{44, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE_ALT:
+{
+Transition transition{45, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 38: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COMMA:
+{
+Transition transition{30, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ELEMENTS_STAR:
+{
+Transition transition{53, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 41: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_COMMA:
+{
+Transition transition{35, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_MEMBERS_STAR:
+{
+Transition transition{54, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 51: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_NUMBER:
+{
+Transition transition{18, false};
+return transition;
+}
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{19, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_NULL:
+{
+Transition transition{20, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_FALSE:
+{
+Transition transition{21, false};
+return transition;
+}
+case lexer::TokenType::TK_KW_TRUE:
+{
+Transition transition{22, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACK:
+{
+Transition transition{23, false};
+return transition;
+}
+case lexer::TokenType::TK_LBRACE:
+{
+Transition transition{24, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_ELEMENTS:
+{
+Transition transition{12, false};
+return transition;
+}
+case NonTerminalType::NT_ARRAY:
+{
+Transition transition{13, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT:
+{
+Transition transition{14, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE:
+{
+Transition transition{15, false};
+return transition;
+}
+case NonTerminalType::NT_ARRAY_OPT:
+{
+Transition transition{55, false};
+return transition;
+}
+case NonTerminalType::NT_VALUE_ALT:
+{
+Transition transition{17, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 52: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_STRING:
+{
+Transition transition{28, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+case NonTerminalType::NT_MEMBER:
+{
+Transition transition{25, false};
+return transition;
+}
+case NonTerminalType::NT_MEMBERS:
+{
+Transition transition{26, false};
+return transition;
+}
+case NonTerminalType::NT_OBJECT_OPT:
+{
+Transition transition{56, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 55: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RBRACK:
+{
+Transition transition{57, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+case 56: {
+if (current_symbol.kind == SymbolKind::Terminal) {
+switch (static_cast<lexer::TokenType>(current_symbol.identifier)) {
+case lexer::TokenType::TK_RBRACE:
+{
+Transition transition{58, false};
+return transition;
+}
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else if (current_symbol.kind == SymbolKind::NonTerminal) {
+switch (static_cast<parser::NonTerminalType>(current_symbol.identifier)) {
+default:
+// Entered state where top of stack doesn't produce a valid transition.
+std::terminate();
+}
+} else {
+// There was a state atop the stack when there should have been a symbol.
+std::terminate();
+}
+}
+break;
+default:
+// Encountered a parser state that does not exist.
+std::terminate();
+}
+
+    }
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,42 @@
+#ifndef LAPEX_PARSER_H
+#define LAPEX_PARSER_H
+
+#include "tokens.h"
+#include "visitor.h"
+#include <cstddef>
+#include <functional>
+#include <memory>
+#include <utility>
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+
+    // the containers of the parser use this allocator, which is passed to its constructor
+    using Allocator = std::allocator<std::byte>;
+
+    template <class T>
+    using Token = std::pair<lexer::TokenType, T>;
+
+    template <class T>
+    class Parser
+    {
+    private:
+        std::function<Token<T>()> token_function;
+        Visitor<T> &visitor;
+        Allocator allocator;
+
+    public:
+        Parser(std::function<Token<T>()> token_function, Visitor<T> &visitor, Allocator allocator = Allocator());
+
+        void parse();
+    };
+
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
@@ -0,0 +1,67 @@
+#ifndef LAPEX_VISITOR_H
+#define LAPEX_VISITOR_H
+
+#include "tokens.h"
+
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+namespace parser
+{
+    template <class T>
+    class Visitor
+    {
+    public:
+        virtual void shift(lexer::TokenType tk_type, T data) = 0;
+        // elements(0) -> value(5) <elements_star>(12)
+virtual void reduce_elements() = 0;
+// array(1) -> LBRACK(9) <array_opt>(13) RBRACK(8)
+virtual void reduce_array() = 0;
+// member(2) -> STRING(2) COLON(7) value(5)
+virtual void reduce_member() = 0;
+// members(3) -> member(2) <members_star>(14)
+virtual void reduce_members() = 0;
+// object(4) -> LBRACE(11) <object_opt>(15) RBRACE(10)
+virtual void reduce_object() = 0;
+// value(5) -> <value_alt>(16)
+virtual void reduce_value() = 0;
+// document(6) -> value(5)
+virtual void reduce_document() = 0;
+// <elements_star>(12) -> <eps>
+virtual void reduce_elements_star_1() = 0;
+// <elements_star>(12) -> COMMA(6) value(5) <elements_star>(12)
+virtual void reduce_elements_star_2() = 0;
+// <array_opt>(13) -> elements(0)
+virtual void reduce_array_opt_1() = 0;
+// <array_opt>(13) -> <eps>
+virtual void reduce_array_opt_2() = 0;
+// <members_star>(14) -> <eps>
+virtual void reduce_members_star_1() = 0;
+// <members_star>(14) -> COMMA(6) member(2) <members_star>(14)
+virtual void reduce_members_star_2() = 0;
+// <object_opt>(15) -> members(3)
+virtual void reduce_object_opt_1() = 0;
+// <object_opt>(15) -> <eps>
+virtual void reduce_object_opt_2() = 0;
+// <value_alt>(16) -> object(4)
+virtual void reduce_value_alt_1() = 0;
+// <value_alt>(16) -> array(1)
+virtual void reduce_value_alt_2() = 0;
+// <value_alt>(16) -> STRING(2)
+virtual void reduce_value_alt_3() = 0;
+// <value_alt>(16) -> NUMBER(1)
+virtual void reduce_value_alt_4() = 0;
+// <value_alt>(16) -> KW_TRUE(5)
+virtual void reduce_value_alt_5() = 0;
+// <value_alt>(16) -> KW_FALSE(4)
+virtual void reduce_value_alt_6() = 0;
+// <value_alt>(16) -> KW_NULL(3)
+virtual void reduce_value_alt_7() = 0;
+
+    };
+}
+
+// <lapex:keep name="code">
+// </lapex:keep>
+
+#endif
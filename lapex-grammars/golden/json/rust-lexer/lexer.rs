@@ -0,0 +1,7 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ; # [derive (Debug)] pub enum LexerError { InvalidChar { bad_ch : u32 } , UnexpectedAlphabet { range : std :: ops :: RangeInclusive < u32 > } , # [doc = r" The input of a `StreamLexer` could not be read, or is no valid UTF-8."] Read (std :: io :: Error) } impl std :: error :: Error for LexerError { } impl std :: fmt :: Display for LexerError { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { LexerError :: InvalidChar { bad_ch } => write ! (f , "Lexer got strange codepoint {}, char value is '{:?}'" , bad_ch , std :: char :: from_u32 (* bad_ch)) , LexerError :: UnexpectedAlphabet { range } => write ! (f , "Lexer got char in unexpected range: {:?}" , range) , LexerError :: Read (error) => write ! (f , "Lexer could not read the input: {}" , error) } } } # [doc = r" The state every token starts to be scanned in."] pub const START_STATE : usize = 0 ; # [doc = r" The character class of `'\0'`, which the lexer treats as the end of input."] pub const END_OF_INPUT_CLASS : usize = 0 ; # [doc = r" Maps a character to its character class, the unit of transitions in the DFA."] pub fn classify (ch : char) -> Option < usize > { Lexer :: get_alphabet_index (ch as u32) } # [doc = r" Returns the state the DFA moves to from `state` on a character of `class`."] pub fn step (state : usize , class : usize) -> Option < usize > { match (state , class) { (0usize , 2usize) => Some (31usize) , (0usize , 3usize) => Some (31usize) , (0usize , 6usize) => Some (31usize) , (0usize , 8usize) => Some (31usize) , (0usize , 10usize) => Some (28usize) , (0usize , 14usize) => Some (27usize) , (0usize , 15usize) => Some (26usize) , (0usize , 18usize) => Some (25usize) , (0usize , 19usize) => Some (19usize) , (0usize , 20usize) => Some (19usize) , (0usize , 21usize) => Some (19usize) , (0usize , 22usize) => Some (18usize) , (0usize , 26usize) => Some (17usize) , (0usize , 28usize) => Some (16usize) , (0usize , 33usize) => Some (11usize) , (0usize , 37usize) => Some (7usize) , (0usize , 41usize) => Some (3usize) , (0usize , 44usize) => Some (2usize) , (0usize , 46usize) => Some (1usize) , (3usize , 39usize) => Some (4usize) , (4usize , 42usize) => Some (5usize) , (5usize , 32usize) => Some (6usize) , (7usize , 42usize) => Some (8usize) , (8usize , 35usize) => Some (9usize) , (9usize , 35usize) => Some (10usize) , (11usize , 30usize) => Some (12usize) , (12usize , 35usize) => Some (13usize) , (13usize , 40usize) => Some (14usize) , (14usize , 32usize) => Some (15usize) , (19usize , 16usize) => Some (23usize) , (19usize , 18usize) => Some (19usize) , (19usize , 19usize) => Some (19usize) , (19usize , 20usize) => Some (19usize) , (19usize , 21usize) => Some (19usize) , (19usize , 24usize) => Some (20usize) , (19usize , 32usize) => Some (20usize) , (20usize , 13usize) => Some (22usize) , (20usize , 15usize) => Some (22usize) , (20usize , 18usize) => Some (21usize) , (20usize , 19usize) => Some (21usize) , (20usize , 20usize) => Some (21usize) , (20usize , 21usize) => Some (21usize) , (21usize , 18usize) => Some (21usize) , (21usize , 19usize) => Some (21usize) , (21usize , 20usize) => Some (21usize) , (21usize , 21usize) => Some (21usize) , (22usize , 18usize) => Some (21usize) , (22usize , 19usize) => Some (21usize) , (22usize , 20usize) => Some (21usize) , (22usize , 21usize) => Some (21usize) , (23usize , 18usize) => Some (24usize) , (23usize , 19usize) => Some (24usize) , (23usize , 20usize) => Some (24usize) , (23usize , 21usize) => Some (24usize) , (24usize , 18usize) => Some (24usize) , (24usize , 19usize) => Some (24usize) , (24usize , 20usize) => Some (24usize) , (24usize , 21usize) => Some (24usize) , (24usize , 24usize) => Some (20usize) , (24usize , 32usize) => Some (20usize) , (25usize , 16usize) => Some (23usize) , (25usize , 24usize) => Some (20usize) , (25usize , 32usize) => Some (20usize) , (26usize , 18usize) => Some (25usize) , (26usize , 19usize) => Some (19usize) , (26usize , 20usize) => Some (19usize) , (26usize , 21usize) => Some (19usize) , (28usize , 1usize) => Some (28usize) , (28usize , 2usize) => Some (28usize) , (28usize , 4usize) => Some (28usize) , (28usize , 5usize) => Some (28usize) , (28usize , 6usize) => Some (28usize) , (28usize , 7usize) => Some (28usize) , (28usize , 8usize) => Some (28usize) , (28usize , 9usize) => Some (28usize) , (28usize , 10usize) => Some (30usize) , (28usize , 11usize) => Some (28usize) , (28usize , 12usize) => Some (28usize) , (28usize , 13usize) => Some (28usize) , (28usize , 14usize) => Some (28usize) , (28usize , 15usize) => Some (28usize) , (28usize , 16usize) => Some (28usize) , (28usize , 17usize) => Some (28usize) , (28usize , 18usize) => Some (28usize) , (28usize , 19usize) => Some (28usize) , (28usize , 20usize) => Some (28usize) , (28usize , 21usize) => Some (28usize) , (28usize , 22usize) => Some (28usize) , (28usize , 23usize) => Some (28usize) , (28usize , 24usize) => Some (28usize) , (28usize , 25usize) => Some (28usize) , (28usize , 26usize) => Some (28usize) , (28usize , 27usize) => Some (29usize) , (28usize , 28usize) => Some (28usize) , (28usize , 29usize) => Some (28usize) , (28usize , 30usize) => Some (28usize) , (28usize , 31usize) => Some (28usize) , (28usize , 32usize) => Some (28usize) , (28usize , 33usize) => Some (28usize) , (28usize , 34usize) => Some (28usize) , (28usize , 35usize) => Some (28usize) , (28usize , 36usize) => Some (28usize) , (28usize , 37usize) => Some (28usize) , (28usize , 38usize) => Some (28usize) , (28usize , 39usize) => Some (28usize) , (28usize , 40usize) => Some (28usize) , (28usize , 41usize) => Some (28usize) , (28usize , 42usize) => Some (28usize) , (28usize , 43usize) => Some (28usize) , (28usize , 44usize) => Some (28usize) , (28usize , 45usize) => Some (28usize) , (28usize , 46usize) => Some (28usize) , (28usize , 47usize) => Some (28usize) , (28usize , 48usize) => Some (28usize) , (29usize , 1usize) => Some (28usize) , (29usize , 2usize) => Some (28usize) , (29usize , 4usize) => Some (28usize) , (29usize , 5usize) => Some (28usize) , (29usize , 6usize) => Some (28usize) , (29usize , 7usize) => Some (28usize) , (29usize , 8usize) => Some (28usize) , (29usize , 9usize) => Some (28usize) , (29usize , 10usize) => Some (28usize) , (29usize , 11usize) => Some (28usize) , (29usize , 12usize) => Some (28usize) , (29usize , 13usize) => Some (28usize) , (29usize , 14usize) => Some (28usize) , (29usize , 15usize) => Some (28usize) , (29usize , 16usize) => Some (28usize) , (29usize , 17usize) => Some (28usize) , (29usize , 18usize) => Some (28usize) , (29usize , 19usize) => Some (28usize) , (29usize , 20usize) => Some (28usize) , (29usize , 21usize) => Some (28usize) , (29usize , 22usize) => Some (28usize) , (29usize , 23usize) => Some (28usize) , (29usize , 24usize) => Some (28usize) , (29usize , 25usize) => Some (28usize) , (29usize , 26usize) => Some (28usize) , (29usize , 27usize) => Some (28usize) , (29usize , 28usize) => Some (28usize) , (29usize , 29usize) => Some (28usize) , (29usize , 30usize) => Some (28usize) , (29usize , 31usize) => Some (28usize) , (29usize , 32usize) => Some (28usize) , (29usize , 33usize) => Some (28usize) , (29usize , 34usize) => Some (28usize) , (29usize , 35usize) => Some (28usize) , (29usize , 36usize) => Some (28usize) , (29usize , 37usize) => Some (28usize) , (29usize , 38usize) => Some (28usize) , (29usize , 39usize) => Some (28usize) , (29usize , 40usize) => Some (28usize) , (29usize , 41usize) => Some (28usize) , (29usize , 42usize) => Some (28usize) , (29usize , 43usize) => Some (28usize) , (29usize , 44usize) => Some (28usize) , (29usize , 45usize) => Some (28usize) , (29usize , 46usize) => Some (28usize) , (29usize , 47usize) => Some (28usize) , (29usize , 48usize) => Some (28usize) , (31usize , 2usize) => Some (31usize) , (31usize , 3usize) => Some (31usize) , (31usize , 6usize) => Some (31usize) , (31usize , 8usize) => Some (31usize) , (_ , _) => None } } # [doc = r" Returns the token recognized when scanning stops in `state`."] pub fn accepting (state : usize) -> Option < TokenType > { match state { 1usize => Some (TokenType :: TkRbrace) , 2usize => Some (TokenType :: TkLbrace) , 6usize => Some (TokenType :: TkKwTrue) , 10usize => Some (TokenType :: TkKwNull) , 15usize => Some (TokenType :: TkKwFalse) , 16usize => Some (TokenType :: TkRbrack) , 17usize => Some (TokenType :: TkLbrack) , 18usize => Some (TokenType :: TkColon) , 19usize => Some (TokenType :: TkNumber) , 21usize => Some (TokenType :: TkNumber) , 24usize => Some (TokenType :: TkNumber) , 25usize => Some (TokenType :: TkNumber) , 27usize => Some (TokenType :: TkComma) , 30usize => Some (TokenType :: TkString) , 31usize => Some (TokenType :: TkWhitespace) , _ => None } } # [doc = r" A line and a column in the input, both starting at 1. Columns count characters, not"] # [doc = r" bytes."] # [derive (Debug , Clone , Copy , PartialEq , Eq , PartialOrd , Ord , Hash)] pub struct Location { pub line : usize , pub column : usize } impl Location { fn advance (& mut self , text : & str , after_cr : bool) { let mut after_cr = after_cr ; for ch in text . chars () { match ch { '\n' if after_cr => { } '\n' | '\r' => { self . line += 1 ; self . column = 1 ; } _ => self . column += 1 } after_cr = ch == '\r' ; } } } # [doc = r" The bytes of a token in the input, with the location of its first character and of"] # [doc = r" the character after it."] # [derive (Debug , Clone , PartialEq , Eq)] pub struct LocatedSpan { pub span : std :: ops :: Range < usize > , pub start : Location , pub end : Location } pub struct Lexer < 'src > { src : & 'src str , start : usize , position : usize , start_location : Location } impl < 'src > Lexer < 'src > { pub fn new (src : & 'src str) -> Self { Lexer { src , start : 0 , position : 0 , start_location : Location { line : 1 , column : 1 } } } fn get_alphabet_index (c : u32) -> Option < usize > { match c { 0u32 => Some (0usize) , 1u32 ..= 8u32 => Some (1usize) , 9u32 => Some (2usize) , 10u32 => Some (3usize) , 11u32 => Some (4usize) , 12u32 => Some (5usize) , 13u32 => Some (6usize) , 14u32 ..= 31u32 => Some (7usize) , 32u32 => Some (8usize) , 33u32 => Some (9usize) , 34u32 => Some (10usize) , 35u32 => Some (11usize) , 36u32 ..= 42u32 => Some (12usize) , 43u32 => Some (13usize) , 44u32 => Some (14usize) , 45u32 => Some (15usize) , 46u32 => Some (16usize) , 47u32 => Some (17usize) , 48u32 => Some (18usize) , 49u32 => Some (19usize) , 50u32 ..= 56u32 => Some (20usize) , 57u32 => Some (21usize) , 58u32 => Some (22usize) , 59u32 ..= 68u32 => Some (23usize) , 69u32 => Some (24usize) , 70u32 ..= 90u32 => Some (25usize) , 91u32 => Some (26usize) , 92u32 => Some (27usize) , 93u32 => Some (28usize) , 94u32 ..= 96u32 => Some (29usize) , 97u32 => Some (30usize) , 98u32 ..= 100u32 => Some (31usize) , 101u32 => Some (32usize) , 102u32 => Some (33usize) , 103u32 ..= 107u32 => Some (34usize) , 108u32 => Some (35usize) , 109u32 => Some (36usize) , 110u32 => Some (37usize) , 111u32 ..= 113u32 => Some (38usize) , 114u32 => Some (39usize) , 115u32 => Some (40usize) , 116u32 => Some (41usize) , 117u32 => Some (42usize) , 118u32 ..= 122u32 => Some (43usize) , 123u32 => Some (44usize) , 124u32 => Some (45usize) , 125u32 => Some (46usize) , 126u32 ..= 1114110u32 => Some (47usize) , 1114111u32 => Some (48usize) , _ => None } } fn get_alphabet_range (c : usize) -> Option < std :: ops :: RangeInclusive < u32 >> { match c { 0usize => Some (0u32 ..= 0u32) , 1usize => Some (1u32 ..= 8u32) , 2usize => Some (9u32 ..= 9u32) , 3usize => Some (10u32 ..= 10u32) , 4usize => Some (11u32 ..= 11u32) , 5usize => Some (12u32 ..= 12u32) , 6usize => Some (13u32 ..= 13u32) , 7usize => Some (14u32 ..= 31u32) , 8usize => Some (32u32 ..= 32u32) , 9usize => Some (33u32 ..= 33u32) , 10usize => Some (34u32 ..= 34u32) , 11usize => Some (35u32 ..= 35u32) , 12usize => Some (36u32 ..= 42u32) , 13usize => Some (43u32 ..= 43u32) , 14usize => Some (44u32 ..= 44u32) , 15usize => Some (45u32 ..= 45u32) , 16usize => Some (46u32 ..= 46u32) , 17usize => Some (47u32 ..= 47u32) , 18usize => Some (48u32 ..= 48u32) , 19usize => Some (49u32 ..= 49u32) , 20usize => Some (50u32 ..= 56u32) , 21usize => Some (57u32 ..= 57u32) , 22usize => Some (58u32 ..= 58u32) , 23usize => Some (59u32 ..= 68u32) , 24usize => Some (69u32 ..= 69u32) , 25usize => Some (70u32 ..= 90u32) , 26usize => Some (91u32 ..= 91u32) , 27usize => Some (92u32 ..= 92u32) , 28usize => Some (93u32 ..= 93u32) , 29usize => Some (94u32 ..= 96u32) , 30usize => Some (97u32 ..= 97u32) , 31usize => Some (98u32 ..= 100u32) , 32usize => Some (101u32 ..= 101u32) , 33usize => Some (102u32 ..= 102u32) , 34usize => Some (103u32 ..= 107u32) , 35usize => Some (108u32 ..= 108u32) , 36usize => Some (109u32 ..= 109u32) , 37usize => Some (110u32 ..= 110u32) , 38usize => Some (111u32 ..= 113u32) , 39usize => Some (114u32 ..= 114u32) , 40usize => Some (115u32 ..= 115u32) , 41usize => Some (116u32 ..= 116u32) , 42usize => Some (117u32 ..= 117u32) , 43usize => Some (118u32 ..= 122u32) , 44usize => Some (123u32 ..= 123u32) , 45usize => Some (124u32 ..= 124u32) , 46usize => Some (125u32 ..= 125u32) , 47usize => Some (126u32 ..= 1114110u32) , 48usize => Some (1114111u32 ..= 1114111u32) , _ => None } } # [allow (clippy :: should_implement_trait)] pub fn next (& mut self) -> Result < TokenType , LexerError > { let mut state = START_STATE ; self . begin_token () ; loop { let next_ch = self . src [self . position ..] . chars () . next () . unwrap_or ('\0') ; let class = if let Some (class) = classify (next_ch) { class } else { return Err (LexerError :: InvalidChar { bad_ch : next_ch as u32 }) ; } ; if state == START_STATE && class == END_OF_INPUT_CLASS { return Ok (TokenType :: EndOfFile) ; } else if let Some (target) = step (state , class) { self . position += next_ch . len_utf8 () ; state = target ; self . skip_ascii_run (state) ; } else if let Some (token) = accepting (state) { if matches ! (token , TokenType :: TkWhitespace) { state = START_STATE ; self . begin_token () ; continue ; } return Ok (token) ; } else { return Err (LexerError :: UnexpectedAlphabet { range : Lexer :: get_alphabet_range (class) . unwrap () }) ; } } } fn skip_ascii_run (& mut self , state : usize) { match state { 19usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 48u8 ..= 57u8)) . unwrap_or (rest . len ()) ; } , 21usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 48u8 ..= 57u8)) . unwrap_or (rest . len ()) ; } , 24usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 48u8 ..= 57u8)) . unwrap_or (rest . len ()) ; } , 28usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 1u8 ..= 9u8 | 11u8 ..= 33u8 | 35u8 ..= 91u8 | 93u8 ..= 125u8)) . unwrap_or (rest . len ()) ; } , 31usize => { let rest = & self . src . as_bytes () [self . position ..] ; self . position += rest . iter () . position (| b | ! matches ! (b , 9u8 ..= 10u8 | 13u8 ..= 13u8 | 32u8 ..= 32u8)) . unwrap_or (rest . len ()) ; } , _ => { } } } fn begin_token (& mut self) { let after_cr = self . after_cr () ; self . start_location . advance (& self . src [self . start .. self . position] , after_cr) ; self . start = self . position ; } fn after_cr (& self) -> bool { self . start > 0 && self . src . as_bytes () [self . start - 1] == b'\r' } pub fn span (& self) -> std :: ops :: Range < usize > { self . start .. self . position } pub fn slice (& self) -> & 'src str { & self . src [self . span ()] } # [doc = r" The location the current token starts at."] # [allow (dead_code)] pub fn position (& self) -> Location { self . start_location } # [doc = r" The span of the current token, with the locations it starts and ends at."] # [allow (dead_code)] pub fn span_with_location (& self) -> LocatedSpan { let mut end = self . start_location ; end . advance (self . slice () , self . after_cr ()) ; LocatedSpan { span : self . span () , start : self . start_location , end } } # [doc = r" Lets the context decide the type of every token but `EndOfFile`."] # [allow (dead_code)] pub fn with_context < C : LexerContext > (self , context : SharedContext < C >) -> ContextLexer < 'src , C > { ContextLexer { lexer : self , context } } } # [doc = r" Feeds the state of the parser back into the lexer, for languages in which the type of"] # [doc = r" a token depends on what was parsed before, like the names declared by a `typedef` in C."] # [allow (dead_code)] pub trait LexerContext { fn classify (& mut self , token : TokenType , text : & str) -> TokenType ; } # [doc = r" The visitor of the parser keeps a clone to update the context the lexer reads."] # [allow (dead_code)] pub type SharedContext < C > = std :: rc :: Rc < std :: cell :: RefCell < C >> ; # [doc = r" A lexer which passes its tokens through a `LexerContext`. The LR parser asks for the next"] # [doc = r" token right after shifting the previous one, so the context contains the updates of"] # [doc = r" the visitor up to that shift."] # [allow (dead_code)] pub struct ContextLexer < 'src , C : LexerContext > { lexer : Lexer < 'src > , context : SharedContext < C > } # [allow (dead_code)] impl < 'src , C : LexerContext > ContextLexer < 'src , C > { # [allow (clippy :: should_implement_trait)] pub fn next (& mut self) -> Result < TokenType , LexerError > { match self . lexer . next () ? { TokenType :: EndOfFile => Ok (TokenType :: EndOfFile) , token => Ok (self . context . borrow_mut () . classify (token , self . lexer . slice ())) } } pub fn span (& self) -> std :: ops :: Range < usize > { self . lexer . span () } pub fn slice (& self) -> & 'src str { self . lexer . slice () } pub fn position (& self) -> Location { self . lexer . position () } pub fn span_with_location (& self) -> LocatedSpan { self . lexer . span_with_location () } pub fn context (& self) -> & SharedContext < C > { & self . context } } # [doc = r" A lexer which reads its input character by character instead of borrowing it whole,"] # [doc = r" for large files or network streams. Only the text of the current token is kept."] # [allow (dead_code)] pub struct StreamLexer < I : Iterator < Item = std :: io :: Result < char >> > { input : I , peeked : Option < char > , ended : bool , text : String , start : usize , start_location : Location , after_cr : bool } # [allow (dead_code)] impl < I : Iterator < Item = std :: io :: Result < char >> > StreamLexer < I > { pub fn new (input : I) -> Self { StreamLexer { input , peeked : None , ended : false , text : String :: new () , start : 0 , start_location : Location { line : 1 , column : 1 } , after_cr : false } } fn peek (& mut self) -> Result < char , LexerError > { if self . peeked . is_none () && ! self . ended { match self . input . next () { Some (Ok (ch)) => self . peeked = Some (ch) , Some (Err (error)) => return Err (LexerError :: Read (error)) , None => self . ended = true } } Ok (self . peeked . unwrap_or ('\0')) } # [allow (clippy :: should_implement_trait)] pub fn next (& mut self) -> Result < TokenType , LexerError > { let mut state = START_STATE ; self . begin_token () ; loop { let next_ch = self . peek () ? ; let class = if let Some (class) = classify (next_ch) { class } else { return Err (LexerError :: InvalidChar { bad_ch : next_ch as u32 }) ; } ; if state == START_STATE && class == END_OF_INPUT_CLASS { return Ok (TokenType :: EndOfFile) ; } else if let Some (target) = step (state , class) { self . text . push (self . peeked . take () . unwrap ()) ; state = target ; } else if let Some (token) = accepting (state) { if matches ! (token , TokenType :: TkWhitespace) { state = START_STATE ; self . begin_token () ; continue ; } return Ok (token) ; } else { return Err (LexerError :: UnexpectedAlphabet { range : Lexer :: get_alphabet_range (class) . unwrap () }) ; } } } fn begin_token (& mut self) { self . start_location . advance (& self . text , self . after_cr) ; self . start += self . text . len () ; if let Some (last) = self . text . chars () . last () { self . after_cr = last == '\r' ; } self . text . clear () ; } # [doc = r" The bytes of the current token, counted in UTF-8 from the start of the input."] pub fn span (& self) -> std :: ops :: Range < usize > { self . start .. self . start + self . text . len () } # [doc = r" The text of the current token, which is only kept until the next one is read."] pub fn slice (& self) -> & str { & self . text } # [doc = r" The location the current token starts at."] pub fn position (& self) -> Location { self . start_location } # [doc = r" The span of the current token, with the locations it starts and ends at."] pub fn span_with_location (& self) -> LocatedSpan { let mut end = self . start_location ; end . advance (& self . text , self . after_cr) ; LocatedSpan { span : self . span () , start : self . start_location , end } } } # [allow (dead_code)] impl < C : Iterator < Item = char >> StreamLexer < std :: iter :: Map < C , fn (char) -> std :: io :: Result < char >> > { # [doc = r" Lexes characters which can not fail to be read."] pub fn from_chars < T : IntoIterator < IntoIter = C >> (chars : T) -> Self { StreamLexer :: new (chars . into_iter () . map (Ok as fn (char) -> std :: io :: Result < char >)) } } # [allow (dead_code)] impl < R : std :: io :: Read > StreamLexer < Utf8Chars < R >> { # [doc = r" Lexes the UTF-8 text of a reader, which is buffered internally."] pub fn from_reader (reader : R) -> Self { StreamLexer :: new (Utf8Chars { bytes : std :: io :: Read :: bytes (std :: io :: BufReader :: new (reader)) }) } } # [doc = r" Decodes the characters of a UTF-8 reader. Invalid UTF-8 is reported as an error of"] # [doc = r" kind `InvalidData`."] # [allow (dead_code)] pub struct Utf8Chars < R : std :: io :: Read > { bytes : std :: io :: Bytes < std :: io :: BufReader < R >> } impl < R : std :: io :: Read > Iterator for Utf8Chars < R > { type Item = std :: io :: Result < char > ; fn next (& mut self) -> Option < Self :: Item > { let first = match self . bytes . next () ? { Ok (first) => first , Err (error) => return Some (Err (error)) } ; let width = match first { 0x00 ..= 0x7f => 1 , 0xc0 ..= 0xdf => 2 , 0xe0 ..= 0xef => 3 , 0xf0 ..= 0xf7 => 4 , _ => 0 } ; let mut encoded = [first , 0 , 0 , 0] ; for byte in encoded . iter_mut () . take (width) . skip (1) { match self . bytes . next () { Some (Ok (next)) => * byte = next , Some (Err (error)) => return Some (Err (error)) , None => break } } let decoded = std :: str :: from_utf8 (& encoded [.. width]) . ok () . and_then (| text | text . chars () . next ()) ; Some (decoded . ok_or_else (|| { std :: io :: Error :: new (std :: io :: ErrorKind :: InvalidData , "stream did not contain valid UTF-8") })) } }
+
+// <lapex:keep name="code">
+// </lapex:keep>
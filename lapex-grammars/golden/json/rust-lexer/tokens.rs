@@ -0,0 +1,7 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+# [derive (Clone , Copy , Debug)] pub enum TokenType { EndOfFile , TkWhitespace , TkNumber , TkString , TkKwNull , TkKwFalse , TkKwTrue , TkComma , TkColon , TkRbrack , TkLbrack , TkRbrace , TkLbrace , }
+
+// <lapex:keep name="code">
+// </lapex:keep>
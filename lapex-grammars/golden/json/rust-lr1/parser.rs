@@ -0,0 +1,6 @@
+// <lapex:keep name="imports">
+// </lapex:keep>
+
+use super :: tokens :: TokenType ;pub trait Visitor < T > { fn shift (& mut self , token : TokenType , data : T) ; # [doc = "elements(0) -> value(5) <elements_star>(12)"] fn reduce_elements (& mut self) ; # [doc = "array(1) -> LBRACK(9) <array_opt>(13) RBRACK(8)"] fn reduce_array (& mut self) ; # [doc = "member(2) -> STRING(2) COLON(7) value(5)"] fn reduce_member (& mut self) ; # [doc = "members(3) -> member(2) <members_star>(14)"] fn reduce_members (& mut self) ; # [doc = "object(4) -> LBRACE(11) <object_opt>(15) RBRACE(10)"] fn reduce_object (& mut self) ; # [doc = "value(5) -> <value_alt>(16)"] fn reduce_value (& mut self) ; # [doc = "document(6) -> value(5)"] fn reduce_document (& mut self) ; # [doc = "<elements_star>(12) -> <eps>"] fn reduce_elements_star_empty (& mut self) ; # [doc = "<elements_star>(12) -> COMMA(6) value(5) <elements_star>(12)"] fn reduce_elements_star_tail (& mut self) ; # [doc = "<array_opt>(13) -> elements(0)"] fn reduce_array_opt_some (& mut self) ; # [doc = "<array_opt>(13) -> <eps>"] fn reduce_array_opt_none (& mut self) ; # [doc = "<members_star>(14) -> <eps>"] fn reduce_members_star_empty (& mut self) ; # [doc = "<members_star>(14) -> COMMA(6) member(2) <members_star>(14)"] fn reduce_members_star_tail (& mut self) ; # [doc = "<object_opt>(15) -> members(3)"] fn reduce_object_opt_some (& mut self) ; # [doc = "<object_opt>(15) -> <eps>"] fn reduce_object_opt_none (& mut self) ; # [doc = "<value_alt>(16) -> object(4)"] fn reduce_value_alt_1 (& mut self) ; # [doc = "<value_alt>(16) -> array(1)"] fn reduce_value_alt_2 (& mut self) ; # [doc = "<value_alt>(16) -> STRING(2)"] fn reduce_value_alt_3 (& mut self) ; # [doc = "<value_alt>(16) -> NUMBER(1)"] fn reduce_value_alt_4 (& mut self) ; # [doc = "<value_alt>(16) -> KW_TRUE(5)"] fn reduce_value_alt_5 (& mut self) ; # [doc = "<value_alt>(16) -> KW_FALSE(4)"] fn reduce_value_alt_6 (& mut self) ; # [doc = "<value_alt>(16) -> KW_NULL(3)"] fn reduce_value_alt_7 (& mut self) ; }pub struct Parser < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> { token_function : F , visitor : V , } # [derive (Debug , Clone , Copy)] # [allow (clippy :: enum_variant_names)] enum NonTerminalType { NtElements , NtArray , NtMember , NtMembers , NtObject , NtValue , NtDocument , NtElementsStar , NtArrayOpt , NtMembersStar , NtObjectOpt , NtValueAlt } # [derive (Debug , Clone , Copy)] enum StackSymbol { Terminal { token : TokenType } , NonTerminal { non_terminal : NonTerminalType } , State { state_id : usize } , } # [derive (Clone , Copy)] enum ReducedRule { Rule0 , Rule1 , Rule2 , Rule3 , Rule4 , Rule5 , Rule6 , Rule7 , Rule8 , Rule9 , Rule10 , Rule11 , Rule12 , Rule13 , Rule14 , Rule15 , Rule16 , Rule17 , Rule18 , Rule19 , Rule20 , Rule21 } enum Action { Shift , Reduce { rule : ReducedRule } } enum Goto { Accept , State { state_id : usize } } # [derive (Debug , Clone)] pub enum ParserError { UnexpectedToken { got : TokenType , expected : Vec < TokenType > } , } impl std :: error :: Error for ParserError { } impl std :: fmt :: Display for ParserError { fn fmt (& self , f : & mut std :: fmt :: Formatter) -> std :: fmt :: Result { match self { ParserError :: UnexpectedToken { got , expected } => write ! (f , "Unexpected token {:?}, expected one of: {:?}" , got , expected) , } } } impl < T , F : FnMut () -> (TokenType , T) , V : Visitor < T >> Parser < T , F , V > { # [doc = r" The token function is not called again once it returned `TokenType::EndOfFile`,"] # [doc = r" and `EndOfFile` is never passed to `Visitor::shift`."] pub fn new (token_function : F , visitor : V) -> Self { Parser { token_function , visitor , } } fn next_action (& self , state : usize , next_token : TokenType) -> Result < Action , ParserError > { match (state , next_token) { (0usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (0usize , TokenType :: TkString) => Ok (Action :: Shift) , (0usize , TokenType :: TkKwNull) => Ok (Action :: Shift) , (0usize , TokenType :: TkKwFalse) => Ok (Action :: Shift) , (0usize , TokenType :: TkKwTrue) => Ok (Action :: Shift) , (0usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (0usize , TokenType :: TkLbrace) => Ok (Action :: Shift) , (0usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNumber , TokenType :: TkString , TokenType :: TkKwNull , TokenType :: TkKwFalse , TokenType :: TkKwTrue , TokenType :: TkLbrack , TokenType :: TkLbrace] }) , (1usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule14 }) , (1usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (2usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (2usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (3usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule21 }) , (3usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (4usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule20 }) , (4usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (5usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule16 }) , (5usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (6usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule15 }) , (6usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (7usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule19 }) , (7usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (8usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule18 }) , (8usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (9usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule17 }) , (9usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (10usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (10usize , TokenType :: TkString) => Ok (Action :: Shift) , (10usize , TokenType :: TkKwNull) => Ok (Action :: Shift) , (10usize , TokenType :: TkKwFalse) => Ok (Action :: Shift) , (10usize , TokenType :: TkKwTrue) => Ok (Action :: Shift) , (10usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (10usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (10usize , TokenType :: TkLbrace) => Ok (Action :: Shift) , (10usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNumber , TokenType :: TkString , TokenType :: TkKwNull , TokenType :: TkKwFalse , TokenType :: TkKwTrue , TokenType :: TkRbrack , TokenType :: TkLbrack , TokenType :: TkLbrace] }) , (11usize , TokenType :: TkString) => Ok (Action :: Shift) , (11usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (11usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkString , TokenType :: TkRbrace] }) , (12usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule3 }) , (12usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (13usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule14 }) , (13usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule14 }) , (13usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (14usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (14usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (14usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (15usize , TokenType :: TkComma) => Ok (Action :: Shift) , (15usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (15usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (16usize , TokenType :: TkRbrack) => Ok (Action :: Shift) , (16usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (17usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule20 }) , (17usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule20 }) , (17usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (18usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule16 }) , (18usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule16 }) , (18usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (19usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule15 }) , (19usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule15 }) , (19usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (20usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule19 }) , (20usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule19 }) , (20usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (21usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule18 }) , (21usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule18 }) , (21usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (22usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule17 }) , (22usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule17 }) , (22usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (23usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (23usize , TokenType :: TkString) => Ok (Action :: Shift) , (23usize , TokenType :: TkKwNull) => Ok (Action :: Shift) , (23usize , TokenType :: TkKwFalse) => Ok (Action :: Shift) , (23usize , TokenType :: TkKwTrue) => Ok (Action :: Shift) , (23usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (23usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (23usize , TokenType :: TkLbrace) => Ok (Action :: Shift) , (23usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNumber , TokenType :: TkString , TokenType :: TkKwNull , TokenType :: TkKwFalse , TokenType :: TkKwTrue , TokenType :: TkRbrack , TokenType :: TkLbrack , TokenType :: TkLbrace] }) , (24usize , TokenType :: TkString) => Ok (Action :: Shift) , (24usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (24usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkString , TokenType :: TkRbrace] }) , (25usize , TokenType :: TkComma) => Ok (Action :: Shift) , (25usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (25usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (26usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule10 }) , (26usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrace] }) , (27usize , TokenType :: TkRbrace) => Ok (Action :: Shift) , (27usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrace] }) , (28usize , TokenType :: TkColon) => Ok (Action :: Shift) , (28usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkColon] }) , (29usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule2 }) , (29usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (30usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (30usize , TokenType :: TkString) => Ok (Action :: Shift) , (30usize , TokenType :: TkKwNull) => Ok (Action :: Shift) , (30usize , TokenType :: TkKwFalse) => Ok (Action :: Shift) , (30usize , TokenType :: TkKwTrue) => Ok (Action :: Shift) , (30usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (30usize , TokenType :: TkLbrace) => Ok (Action :: Shift) , (30usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNumber , TokenType :: TkString , TokenType :: TkKwNull , TokenType :: TkKwFalse , TokenType :: TkKwTrue , TokenType :: TkLbrack , TokenType :: TkLbrace] }) , (31usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (31usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (32usize , TokenType :: TkRbrack) => Ok (Action :: Shift) , (32usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (33usize , TokenType :: TkRbrace) => Ok (Action :: Shift) , (33usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrace] }) , (34usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule9 }) , (34usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrace] }) , (35usize , TokenType :: TkString) => Ok (Action :: Shift) , (35usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkString] }) , (36usize , TokenType :: EndOfFile) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (36usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: EndOfFile] }) , (37usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (37usize , TokenType :: TkString) => Ok (Action :: Shift) , (37usize , TokenType :: TkKwNull) => Ok (Action :: Shift) , (37usize , TokenType :: TkKwFalse) => Ok (Action :: Shift) , (37usize , TokenType :: TkKwTrue) => Ok (Action :: Shift) , (37usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (37usize , TokenType :: TkLbrace) => Ok (Action :: Shift) , (37usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNumber , TokenType :: TkString , TokenType :: TkKwNull , TokenType :: TkKwFalse , TokenType :: TkKwTrue , TokenType :: TkLbrack , TokenType :: TkLbrace] }) , (38usize , TokenType :: TkComma) => Ok (Action :: Shift) , (38usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule0 }) , (38usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (39usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (39usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (39usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (40usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (40usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (40usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrack] }) , (41usize , TokenType :: TkComma) => Ok (Action :: Shift) , (41usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule7 }) , (41usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (42usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule14 }) , (42usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule14 }) , (42usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (43usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (43usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule13 }) , (43usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (44usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (44usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule6 }) , (44usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (45usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule20 }) , (45usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule20 }) , (45usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (46usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule16 }) , (46usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule16 }) , (46usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (47usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule15 }) , (47usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule15 }) , (47usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (48usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule19 }) , (48usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule19 }) , (48usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (49usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule18 }) , (49usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule18 }) , (49usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (50usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule17 }) , (50usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule17 }) , (50usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (51usize , TokenType :: TkNumber) => Ok (Action :: Shift) , (51usize , TokenType :: TkString) => Ok (Action :: Shift) , (51usize , TokenType :: TkKwNull) => Ok (Action :: Shift) , (51usize , TokenType :: TkKwFalse) => Ok (Action :: Shift) , (51usize , TokenType :: TkKwTrue) => Ok (Action :: Shift) , (51usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule4 }) , (51usize , TokenType :: TkLbrack) => Ok (Action :: Shift) , (51usize , TokenType :: TkLbrace) => Ok (Action :: Shift) , (51usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkNumber , TokenType :: TkString , TokenType :: TkKwNull , TokenType :: TkKwFalse , TokenType :: TkKwTrue , TokenType :: TkRbrack , TokenType :: TkLbrack , TokenType :: TkLbrace] }) , (52usize , TokenType :: TkString) => Ok (Action :: Shift) , (52usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule11 }) , (52usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkString , TokenType :: TkRbrace] }) , (53usize , TokenType :: TkRbrack) => Ok (Action :: Reduce { rule : ReducedRule :: Rule1 }) , (53usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (54usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule8 }) , (54usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrace] }) , (55usize , TokenType :: TkRbrack) => Ok (Action :: Shift) , (55usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrack] }) , (56usize , TokenType :: TkRbrace) => Ok (Action :: Shift) , (56usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkRbrace] }) , (57usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (57usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule5 }) , (57usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (58usize , TokenType :: TkComma) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (58usize , TokenType :: TkRbrace) => Ok (Action :: Reduce { rule : ReducedRule :: Rule12 }) , (58usize , _) => Err (ParserError :: UnexpectedToken { got : next_token , expected : vec ! [TokenType :: TkComma , TokenType :: TkRbrace] }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } fn next_goto (& self , state : usize , symbol : StackSymbol , next_token : TokenType) -> Result < Goto , ParserError > { match (state , symbol) { (0usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 5usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 6usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkKwNull }) => Ok (Goto :: State { state_id : 7usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkKwFalse }) => Ok (Goto :: State { state_id : 8usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkKwTrue }) => Ok (Goto :: State { state_id : 9usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 10usize }) , (0usize , StackSymbol :: Terminal { token : TokenType :: TkLbrace }) => Ok (Goto :: State { state_id : 11usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) => Ok (Goto :: State { state_id : 1usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) => Ok (Goto :: State { state_id : 2usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) => Ok (Goto :: State { state_id : 3usize }) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtDocument }) => Ok (Goto :: Accept) , (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) => Ok (Goto :: State { state_id : 4usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 18usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 19usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkKwNull }) => Ok (Goto :: State { state_id : 20usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkKwFalse }) => Ok (Goto :: State { state_id : 21usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkKwTrue }) => Ok (Goto :: State { state_id : 22usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 23usize }) , (10usize , StackSymbol :: Terminal { token : TokenType :: TkLbrace }) => Ok (Goto :: State { state_id : 24usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElements }) => Ok (Goto :: State { state_id : 12usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) => Ok (Goto :: State { state_id : 13usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) => Ok (Goto :: State { state_id : 14usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) => Ok (Goto :: State { state_id : 15usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArrayOpt }) => Ok (Goto :: State { state_id : 16usize }) , (10usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) => Ok (Goto :: State { state_id : 17usize }) , (11usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 28usize }) , (11usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMember }) => Ok (Goto :: State { state_id : 25usize }) , (11usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembers }) => Ok (Goto :: State { state_id : 26usize }) , (11usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObjectOpt }) => Ok (Goto :: State { state_id : 27usize }) , (15usize , StackSymbol :: Terminal { token : TokenType :: TkComma }) => Ok (Goto :: State { state_id : 30usize }) , (15usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElementsStar }) => Ok (Goto :: State { state_id : 29usize }) , (16usize , StackSymbol :: Terminal { token : TokenType :: TkRbrack }) => Ok (Goto :: State { state_id : 31usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 18usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 19usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkKwNull }) => Ok (Goto :: State { state_id : 20usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkKwFalse }) => Ok (Goto :: State { state_id : 21usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkKwTrue }) => Ok (Goto :: State { state_id : 22usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 23usize }) , (23usize , StackSymbol :: Terminal { token : TokenType :: TkLbrace }) => Ok (Goto :: State { state_id : 24usize }) , (23usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElements }) => Ok (Goto :: State { state_id : 12usize }) , (23usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) => Ok (Goto :: State { state_id : 13usize }) , (23usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) => Ok (Goto :: State { state_id : 14usize }) , (23usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) => Ok (Goto :: State { state_id : 15usize }) , (23usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArrayOpt }) => Ok (Goto :: State { state_id : 32usize }) , (23usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) => Ok (Goto :: State { state_id : 17usize }) , (24usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 28usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMember }) => Ok (Goto :: State { state_id : 25usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembers }) => Ok (Goto :: State { state_id : 26usize }) , (24usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObjectOpt }) => Ok (Goto :: State { state_id : 33usize }) , (25usize , StackSymbol :: Terminal { token : TokenType :: TkComma }) => Ok (Goto :: State { state_id : 35usize }) , (25usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembersStar }) => Ok (Goto :: State { state_id : 34usize }) , (27usize , StackSymbol :: Terminal { token : TokenType :: TkRbrace }) => Ok (Goto :: State { state_id : 36usize }) , (28usize , StackSymbol :: Terminal { token : TokenType :: TkColon }) => Ok (Goto :: State { state_id : 37usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 18usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 19usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkKwNull }) => Ok (Goto :: State { state_id : 20usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkKwFalse }) => Ok (Goto :: State { state_id : 21usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkKwTrue }) => Ok (Goto :: State { state_id : 22usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 23usize }) , (30usize , StackSymbol :: Terminal { token : TokenType :: TkLbrace }) => Ok (Goto :: State { state_id : 24usize }) , (30usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) => Ok (Goto :: State { state_id : 13usize }) , (30usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) => Ok (Goto :: State { state_id : 14usize }) , (30usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) => Ok (Goto :: State { state_id : 38usize }) , (30usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) => Ok (Goto :: State { state_id : 17usize }) , (32usize , StackSymbol :: Terminal { token : TokenType :: TkRbrack }) => Ok (Goto :: State { state_id : 39usize }) , (33usize , StackSymbol :: Terminal { token : TokenType :: TkRbrace }) => Ok (Goto :: State { state_id : 40usize }) , (35usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 28usize }) , (35usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMember }) => Ok (Goto :: State { state_id : 41usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 46usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 47usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkKwNull }) => Ok (Goto :: State { state_id : 48usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkKwFalse }) => Ok (Goto :: State { state_id : 49usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkKwTrue }) => Ok (Goto :: State { state_id : 50usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 51usize }) , (37usize , StackSymbol :: Terminal { token : TokenType :: TkLbrace }) => Ok (Goto :: State { state_id : 52usize }) , (37usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) => Ok (Goto :: State { state_id : 42usize }) , (37usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) => Ok (Goto :: State { state_id : 43usize }) , (37usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) => Ok (Goto :: State { state_id : 44usize }) , (37usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) => Ok (Goto :: State { state_id : 45usize }) , (38usize , StackSymbol :: Terminal { token : TokenType :: TkComma }) => Ok (Goto :: State { state_id : 30usize }) , (38usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElementsStar }) => Ok (Goto :: State { state_id : 53usize }) , (41usize , StackSymbol :: Terminal { token : TokenType :: TkComma }) => Ok (Goto :: State { state_id : 35usize }) , (41usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembersStar }) => Ok (Goto :: State { state_id : 54usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkNumber }) => Ok (Goto :: State { state_id : 18usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 19usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkKwNull }) => Ok (Goto :: State { state_id : 20usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkKwFalse }) => Ok (Goto :: State { state_id : 21usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkKwTrue }) => Ok (Goto :: State { state_id : 22usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkLbrack }) => Ok (Goto :: State { state_id : 23usize }) , (51usize , StackSymbol :: Terminal { token : TokenType :: TkLbrace }) => Ok (Goto :: State { state_id : 24usize }) , (51usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElements }) => Ok (Goto :: State { state_id : 12usize }) , (51usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) => Ok (Goto :: State { state_id : 13usize }) , (51usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) => Ok (Goto :: State { state_id : 14usize }) , (51usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) => Ok (Goto :: State { state_id : 15usize }) , (51usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArrayOpt }) => Ok (Goto :: State { state_id : 55usize }) , (51usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) => Ok (Goto :: State { state_id : 17usize }) , (52usize , StackSymbol :: Terminal { token : TokenType :: TkString }) => Ok (Goto :: State { state_id : 28usize }) , (52usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMember }) => Ok (Goto :: State { state_id : 25usize }) , (52usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembers }) => Ok (Goto :: State { state_id : 26usize }) , (52usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObjectOpt }) => Ok (Goto :: State { state_id : 56usize }) , (55usize , StackSymbol :: Terminal { token : TokenType :: TkRbrack }) => Ok (Goto :: State { state_id : 57usize }) , (56usize , StackSymbol :: Terminal { token : TokenType :: TkRbrace }) => Ok (Goto :: State { state_id : 58usize }) , (_ , _) => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } } # [doc = r" Passes every token through `filter` before the parser sees it."] # [allow (dead_code)] pub fn with_filter < P : FnMut (& (TokenType , T)) -> FilterAction < T >> (self , filter : P ,) -> Parser < T , impl FnMut () -> (TokenType , T) , V > { Parser { token_function : filter_tokens (self . token_function , filter) , visitor : self . visitor , } } fn reduce_stack_and_visit (& mut self , rule : ReducedRule , stack : & mut Vec < StackSymbol > , state : usize , next_token : TokenType) -> Result < () , ParserError > { let (to_pop , reduced) = match rule { ReducedRule :: Rule0 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElementsStar }) , ReducedRule :: Rule1 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElementsStar }) , ReducedRule :: Rule2 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtElements }) , ReducedRule :: Rule3 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArrayOpt }) , ReducedRule :: Rule4 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArrayOpt }) , ReducedRule :: Rule5 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtArray }) , ReducedRule :: Rule6 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMember }) , ReducedRule :: Rule7 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembersStar }) , ReducedRule :: Rule8 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembersStar }) , ReducedRule :: Rule9 => (2usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtMembers }) , ReducedRule :: Rule10 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObjectOpt }) , ReducedRule :: Rule11 => (0usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObjectOpt }) , ReducedRule :: Rule12 => (3usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtObject }) , ReducedRule :: Rule13 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule14 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule15 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule16 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule17 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule18 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule19 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValueAlt }) , ReducedRule :: Rule20 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtValue }) , ReducedRule :: Rule21 => (1usize , StackSymbol :: NonTerminal { non_terminal : NonTerminalType :: NtDocument }) } ; if stack . len () < to_pop * 2 { unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } stack . truncate (stack . len () - to_pop * 2) ; stack . push (reduced) ; match rule { ReducedRule :: Rule2 => self . visitor . reduce_elements () , ReducedRule :: Rule5 => self . visitor . reduce_array () , ReducedRule :: Rule6 => self . visitor . reduce_member () , ReducedRule :: Rule9 => self . visitor . reduce_members () , ReducedRule :: Rule12 => self . visitor . reduce_object () , ReducedRule :: Rule20 => self . visitor . reduce_value () , ReducedRule :: Rule21 => self . visitor . reduce_document () , ReducedRule :: Rule0 => self . visitor . reduce_elements_star_empty () , ReducedRule :: Rule1 => self . visitor . reduce_elements_star_tail () , ReducedRule :: Rule3 => self . visitor . reduce_array_opt_some () , ReducedRule :: Rule4 => self . visitor . reduce_array_opt_none () , ReducedRule :: Rule7 => self . visitor . reduce_members_star_empty () , ReducedRule :: Rule8 => self . visitor . reduce_members_star_tail () , ReducedRule :: Rule10 => self . visitor . reduce_object_opt_some () , ReducedRule :: Rule11 => self . visitor . reduce_object_opt_none () , ReducedRule :: Rule13 => self . visitor . reduce_value_alt_1 () , ReducedRule :: Rule14 => self . visitor . reduce_value_alt_2 () , ReducedRule :: Rule15 => self . visitor . reduce_value_alt_3 () , ReducedRule :: Rule16 => self . visitor . reduce_value_alt_4 () , ReducedRule :: Rule17 => self . visitor . reduce_value_alt_5 () , ReducedRule :: Rule18 => self . visitor . reduce_value_alt_6 () , ReducedRule :: Rule19 => self . visitor . reduce_value_alt_7 () } Ok (()) } # [doc = r" Parses the whole input and returns its errors. The grammar has no error"] # [doc = r" productions, so parsing stops at the first error."] # [allow (dead_code)] pub fn parse_all (& mut self) -> (Vec < ParserError > , ()) { match self . parse () { Ok (()) => (Vec :: new () , ()) , Err (error) => (vec ! [error] , ()) , } } pub fn parse (& mut self) -> Result < () , ParserError > { let mut lookahead = std :: collections :: VecDeque :: new () ; lookahead . push_back ((self . token_function) ()) ; let mut stack = Vec :: new () ; stack . push (StackSymbol :: State { state_id : 0usize }) ; let mut state = 0usize ; while ! stack . is_empty () { let (next_token , _) = lookahead . front () . unwrap () ; let next_token = * next_token ; let action = self . next_action (state , next_token) ? ; match action { Action :: Shift => { let (next_token , next_data) = lookahead . pop_front () . unwrap () ; debug_assert ! (! matches ! (next_token , TokenType :: EndOfFile) , "EndOfFile must not be shifted") ; stack . push (StackSymbol :: Terminal { token : next_token }) ; self . visitor . shift (next_token , next_data) ; lookahead . push_back ((self . token_function) ()) ; } Action :: Reduce { rule : reduced_rule } => { self . reduce_stack_and_visit (reduced_rule , & mut stack , state , next_token) ? ; } } let (goto_state , current_symbol) = match stack . as_slice () { [.. , StackSymbol :: State { state_id } , current_symbol] => (* state_id , * current_symbol) , _ => unreachable ! ("invalid parser state {} for token {:?}" , state , next_token) } ; let goto = self . next_goto (goto_state , current_symbol , next_token) ? ; match goto { Goto :: Accept => { stack . pop () ; stack . pop () ; } Goto :: State { state_id } => { stack . push (StackSymbol :: State { state_id }) ; state = state_id ; } } } Ok (()) } } # [doc = r" What a token filter does with a token before the parser sees it."] # [allow (dead_code)] pub enum FilterAction < T > { # [doc = r" Passes the token on."] Keep , # [doc = r" Drops the token."] Drop , # [doc = r" Passes another token on in place of this one."] Replace (TokenType , T) , # [doc = r" Passes these tokens on before the token itself."] Inject (Vec < (TokenType , T) >) , } fn apply_filter < T , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (filter : & mut P , token : (TokenType , T) , pending : & mut std :: collections :: VecDeque < (TokenType , T) > ,) { let is_end = matches ! (token . 0 , TokenType :: EndOfFile) ; match filter (& token) { FilterAction :: Keep => pending . push_back (token) , FilterAction :: Drop => { if is_end { pending . push_back (token) ; } } FilterAction :: Replace (replacement , data) => { pending . push_back ((replacement , data)) ; if is_end { pending . push_back (token) ; } } FilterAction :: Inject (tokens) => { pending . extend (tokens) ; pending . push_back (token) ; } } } # [doc = r" Wraps a token function, so that every token is passed through `filter` first."] # [allow (dead_code)] pub fn filter_tokens < T , F : FnMut () -> (TokenType , T) , P : FnMut (& (TokenType , T)) -> FilterAction < T >> (mut token_function : F , mut filter : P ,) -> impl FnMut () -> (TokenType , T) { let mut pending = std :: collections :: VecDeque :: new () ; move || loop { if let Some (token) = pending . pop_front () { return token ; } apply_filter (& mut filter , token_function () , & mut pending) ; } } # [doc = r" Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`"] # [doc = r" once the iterator is exhausted."] # [allow (dead_code)] pub fn token_function_from_iter < T : Default , I : IntoIterator < Item = (TokenType , T) >> (tokens : I ,) -> impl FnMut () -> (TokenType , T) { let mut tokens = tokens . into_iter () ; move || { tokens . next () . unwrap_or_else (|| (TokenType :: EndOfFile , T :: default ())) } }
+// <lapex:keep name="code">
+// </lapex:keep>
@@ -0,0 +1,37 @@
+/// A maintained grammar with an input it accepts, to start new projects from.
+#[derive(Debug)]
+pub struct Template {
+    pub name: &'static str,
+    pub grammar: &'static str,
+    pub sample: &'static str,
+}
+
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "arithmetic",
+        grammar: include_str!("../grammars/arithmetic.lapex"),
+        sample: include_str!("../grammars/arithmetic.txt"),
+    },
+    Template {
+        name: "csv",
+        grammar: include_str!("../grammars/csv.lapex"),
+        sample: include_str!("../grammars/csv.txt"),
+    },
+    Template {
+        name: "ini",
+        grammar: include_str!("../grammars/ini.lapex"),
+        sample: include_str!("../grammars/ini.txt"),
+    },
+    Template {
+        name: "json",
+        grammar: include_str!("../grammars/json.lapex"),
+        sample: include_str!("../grammars/json.txt"),
+    },
+];
+
+pub fn find_template(name: &str) -> Option<&'static Template> {
+    TEMPLATES.iter().find(|template| template.name == name)
+}
+
+#[cfg(test)]
+mod tests;
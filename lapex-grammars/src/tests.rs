@@ -0,0 +1,1444 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+    path::PathBuf,
+    process::Command,
+    rc::Rc,
+};
+
+use lapex_codegen::GeneratedCodeWriter;
+use lapex_cpp_codegen::{CppLLParserCodeGen, CppLRParserCodeGen, CppLexerCodeGen};
+use lapex_input::{
+    DefaultPrecedenceModel, LapexInputParser, LapexParsingError, PrecedenceStrategy, RuleSet,
+};
+use lapex_lexer::{Alphabet, LexerCodeGen, TokenDfa};
+use lapex_parser::{
+    grammar::{Grammar, GrammarError, Symbol},
+    ll_parser::LLParserCodeGen,
+    lr_parser::{
+        generate_table, output_table, ActionGotoTable, GenerationResult, LRParserCodeGen,
+        StateProfile,
+    },
+    validation::validate,
+};
+use lapex_rust_codegen::{RustGLRParserCodeGen, RustLRParserCodeGen, RustLexerCodeGen};
+
+use crate::{find_template, Template, TEMPLATES};
+
+/// Compares the LR(1) table with `golden/<name>.table`. Run with `LAPEX_BLESS=1` to update the
+/// golden files after an intended change.
+fn check_template(template: &Template) {
+    let (rules, grammar, table) = lr1_table(template.grammar);
+    // whitespace and comments are declared with `skip`, so every token is used
+    assert_eq!(
+        validate(grammar, rules),
+        Vec::new(),
+        "template {} has unused rules",
+        template.name
+    );
+    assert!(
+        grammar.duplicates().is_empty(),
+        "template {} has duplicate rules",
+        template.name
+    );
+
+    let mut output = Vec::new();
+    output_table(grammar, &table, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let golden_path = format!(
+        "{}/golden/{}.table",
+        env!("CARGO_MANIFEST_DIR"),
+        template.name
+    );
+    if std::env::var_os("LAPEX_BLESS").is_some() {
+        std::fs::write(&golden_path, &output).unwrap();
+    }
+    let golden = std::fs::read_to_string(&golden_path).unwrap();
+    assert_eq!(output, golden, "table of {} changed", template.name);
+
+    let (alphabet, dfa) = lexer_dfa(rules);
+    let used_symbols: BTreeSet<Symbol> = grammar
+        .rules()
+        .iter()
+        .flat_map(|rule| rule.rhs().iter().copied())
+        .collect();
+    let tokens: Vec<Symbol> = lapex_lexer::scan_tokens(&dfa, &alphabet, template.sample)
+        .unwrap()
+        .into_iter()
+        .filter_map(|rule| {
+            grammar
+                .terminals_with_names()
+                .find(|(_, name)| *name == rule.name)
+                .map(|(symbol, _)| symbol)
+                .filter(|symbol| used_symbols.contains(symbol))
+        })
+        .collect();
+    assert!(
+        StateProfile::new(&table).record(&table, &tokens),
+        "sample of {} is rejected",
+        template.name
+    );
+}
+
+// a file of the generated code, which stays readable after the code generator dropped its writer
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn capture_generated_files<F>(generate: F) -> BTreeMap<String, String>
+where
+    F: FnOnce(&mut GeneratedCodeWriter),
+{
+    let files: RefCell<BTreeMap<String, Rc<RefCell<Vec<u8>>>>> = RefCell::new(BTreeMap::new());
+    let mut gen = GeneratedCodeWriter::with_default(|name| {
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        files.borrow_mut().insert(name.to_string(), buffer.clone());
+        Ok(SharedBuffer(buffer))
+    });
+    generate(&mut gen);
+    drop(gen);
+    files
+        .into_inner()
+        .into_iter()
+        .map(|(name, buffer)| (name, String::from_utf8(buffer.take()).unwrap()))
+        .collect()
+}
+
+/// Parses the grammar with its duplicate productions merged. The rules and the grammar are leaked,
+/// so that the tables built from them can be kept for the rest of the test.
+fn parse_grammar(source: &str) -> (&'static RuleSet<'static>, &'static Grammar<'static>) {
+    let source: &'static str = Box::leak(source.to_owned().into_boxed_str());
+    let rules = Box::leak(Box::new(
+        lapex_input_gen::GeneratedLapexInputParser {}
+            .parse_lapex(source)
+            .unwrap(),
+    ));
+    let grammar = Box::leak(Box::new(Grammar::from_rule_set(rules, true).unwrap()));
+    (rules, grammar)
+}
+
+/// Parses the grammar and builds its LR(1) table, which must be free of conflicts.
+fn lr1_table(
+    source: &str,
+) -> (
+    &'static RuleSet<'static>,
+    &'static Grammar<'static>,
+    ActionGotoTable<'static, 'static>,
+) {
+    let (rules, grammar) = parse_grammar(source);
+    match generate_table::<1>(grammar, false, false) {
+        GenerationResult::NoConflicts(table) => (rules, grammar, table),
+        _ => panic!("grammar has conflicts:\n{}", source),
+    }
+}
+
+/// Builds the DFA of the lexer for the tokens, which must not have unused precedences.
+fn lexer_dfa<'rules>(rules: &'rules RuleSet<'rules>) -> (Alphabet, TokenDfa<'rules>) {
+    let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, unused_precedences) = lapex_lexer::apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+    assert!(unused_precedences.is_empty(), "unused token precedences");
+    (alphabet, dfa)
+}
+
+/// Compares the files with the ones in `golden/<template>/<backend>`. Like the tables, they are
+/// updated by running with `LAPEX_BLESS=1`.
+fn check_golden_files(template: &Template, backend: &str, files: &BTreeMap<String, String>) {
+    let golden_dir = PathBuf::from(format!(
+        "{}/golden/{}/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        template.name,
+        backend
+    ));
+    if std::env::var_os("LAPEX_BLESS").is_some() {
+        let _ = std::fs::remove_dir_all(&golden_dir);
+        std::fs::create_dir_all(&golden_dir).unwrap();
+        for (name, contents) in files {
+            std::fs::write(golden_dir.join(name), contents).unwrap();
+        }
+    }
+    let golden_names: BTreeSet<String> = std::fs::read_dir(&golden_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    assert_eq!(
+        golden_names,
+        files.keys().cloned().collect(),
+        "files generated by {} for {} changed",
+        backend,
+        template.name
+    );
+    for (name, contents) in files {
+        let golden = std::fs::read_to_string(golden_dir.join(name)).unwrap();
+        assert_eq!(
+            contents, &golden,
+            "{} generated by {} for {} changed",
+            name, backend, template.name
+        );
+    }
+}
+
+fn check_generated_code(template: &Template) {
+    let (rules, grammar, lr_table) = lr1_table(template.grammar);
+    let (alphabet, dfa) = lexer_dfa(rules);
+    let generate_lexer = |lexer: &dyn LexerCodeGen, gen: &mut GeneratedCodeWriter| {
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    };
+    let glr_table = match generate_table::<1>(grammar, true, true) {
+        GenerationResult::NoConflicts(table) | GenerationResult::AllowedConflicts { table, .. } => {
+            table
+        }
+        _ => unreachable!(),
+    };
+
+    let files = capture_generated_files(|gen| generate_lexer(&CppLexerCodeGen::new(), gen));
+    check_golden_files(template, "cpp-lexer", &files);
+
+    let files = capture_generated_files(|gen| {
+        CppLRParserCodeGen::new().generate_code(grammar, &lr_table, gen);
+        gen.generate_code("scaffold", |output| {
+            lapex_cpp_codegen::write_visitor_scaffold(
+                grammar,
+                "TemplateVisitor",
+                None,
+                None,
+                output,
+            )
+        })
+        .unwrap();
+    });
+    check_golden_files(template, "cpp-lr1", &files);
+
+    // not every template can be parsed with a single token of lookahead
+    if let Ok(ll_table) = lapex_parser::ll_parser::generate_table(grammar) {
+        let files = capture_generated_files(|gen| {
+            CppLLParserCodeGen::new().generate_code(grammar, &ll_table, gen);
+        });
+        check_golden_files(template, "cpp-ll1", &files);
+    }
+
+    let files = capture_generated_files(|gen| generate_lexer(&RustLexerCodeGen::new(), gen));
+    check_golden_files(template, "rust-lexer", &files);
+
+    let files = capture_generated_files(|gen| {
+        RustLRParserCodeGen::new().generate_code(grammar, &lr_table, gen);
+        gen.generate_code("scaffold", |output| {
+            lapex_rust_codegen::write_visitor_scaffold(grammar, "TemplateVisitor", None, output)
+        })
+        .unwrap();
+    });
+    check_golden_files(template, "rust-lr1", &files);
+
+    let files = capture_generated_files(|gen| {
+        RustGLRParserCodeGen::new().generate_code(grammar, &glr_table, gen);
+    });
+    check_golden_files(template, "rust-glr", &files);
+}
+
+#[test]
+fn test_templates() {
+    for template in TEMPLATES {
+        check_template(template);
+    }
+}
+
+#[test]
+fn test_generated_code() {
+    for template in TEMPLATES {
+        check_generated_code(template);
+    }
+}
+
+/// Compiles the files as the modules of a library with `-D warnings` and the given lints, through
+/// clippy if it is installed.
+fn check_lint_free(name: &str, files: &BTreeMap<String, String>, lints: &[&str]) {
+    let dir = std::env::temp_dir().join(format!("lapex-lints-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut lib = String::from("//! Generated modules.\n");
+    for (file, contents) in files {
+        std::fs::write(dir.join(file), contents).unwrap();
+        lib.push_str(&format!(
+            "/// Generated module.\npub mod {};\n",
+            file.trim_end_matches(".rs")
+        ));
+    }
+    std::fs::write(dir.join("lib.rs"), lib).unwrap();
+    let has_clippy = Command::new("clippy-driver")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    let output = Command::new(if has_clippy { "clippy-driver" } else { "rustc" })
+        .current_dir(&dir)
+        .args([
+            "--edition",
+            "2021",
+            "--crate-type",
+            "lib",
+            "--emit",
+            "metadata",
+        ])
+        .args(["-D", "warnings"])
+        .args(lints.iter().flat_map(|lint| ["-D", lint]))
+        .arg("lib.rs")
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        output.status.success(),
+        "code generated for {} has warnings:\n{}",
+        name,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_generated_rust_code_lints() {
+    for template in TEMPLATES {
+        let (rules, grammar, lr_table) = lr1_table(template.grammar);
+        let (alphabet, dfa) = lexer_dfa(rules);
+        // both parsers in one library, the GLR one with its own prefix
+        let generate = |panic_free: bool, metrics: bool, allow_lints: bool, cst: bool| {
+            capture_generated_files(|gen| {
+                for prefix in [None, Some("glr")] {
+                    let mut lexer = RustLexerCodeGen::new();
+                    lexer.set_symbol_prefix(prefix);
+                    lexer.set_allow_lints(allow_lints);
+                    lexer.generate_tokens(&rules.token_rules, gen);
+                    lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+                }
+                let mut lr_parser = RustLRParserCodeGen::new();
+                lr_parser.set_panic_free(panic_free);
+                lr_parser.set_metrics(metrics);
+                lr_parser.set_progress(metrics);
+                lr_parser.set_allow_lints(allow_lints);
+                lr_parser.set_cst(cst);
+                lr_parser.set_ast(cst);
+                lr_parser.set_facade(metrics);
+                lr_parser.generate_code(grammar, &lr_table, gen);
+                let mut glr_parser = RustGLRParserCodeGen::new();
+                glr_parser.set_panic_free(panic_free);
+                glr_parser.set_metrics(metrics);
+                glr_parser.set_progress(metrics);
+                glr_parser.set_allow_lints(allow_lints);
+                glr_parser.set_cst(cst);
+                glr_parser.set_ast(cst);
+                glr_parser.set_facade(metrics);
+                glr_parser.set_symbol_prefix(Some("glr"));
+                glr_parser.generate_code(grammar, &lr_table, gen);
+            })
+        };
+        check_lint_free(template.name, &generate(false, false, false, false), &[]);
+        check_lint_free(template.name, &generate(true, true, false, true), &[]);
+        check_lint_free(
+            template.name,
+            &generate(true, true, true, true),
+            &[
+                "missing_docs",
+                "unreachable_pub",
+                "clippy::pedantic",
+                "clippy::nursery",
+            ],
+        );
+    }
+}
+
+#[test]
+fn test_find_template() {
+    assert_eq!(find_template("json").map(|t| t.name), Some("json"));
+    assert!(find_template("xml").is_none());
+}
+
+#[test]
+fn test_symbol_prefix() {
+    let source = format!("prefix csv;\n{}", find_template("csv").unwrap().grammar);
+    let (rules, grammar, table) = lr1_table(&source);
+    assert_eq!(rules.symbol_prefix, Some("csv"));
+
+    let files = capture_generated_files(|gen| {
+        let mut lexer = RustLexerCodeGen::new();
+        lexer.set_symbol_prefix(rules.symbol_prefix);
+        lexer.generate_tokens(&rules.token_rules, gen);
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_symbol_prefix(rules.symbol_prefix);
+        parser.generate_code(grammar, &table, gen);
+    });
+    assert_eq!(
+        files.keys().collect::<Vec<_>>(),
+        ["csv_parser.rs", "csv_tokens.rs"]
+    );
+    let parser_code = files["csv_parser.rs"]
+        .strip_prefix("// <lapex:keep name=\"imports\">\n// </lapex:keep>\n\n")
+        .unwrap();
+    assert!(parser_code.starts_with("use super :: csv_tokens :: TokenType ;"));
+
+    let files = capture_generated_files(|gen| {
+        let mut lexer = CppLexerCodeGen::new();
+        lexer.set_symbol_prefix(rules.symbol_prefix);
+        lexer.generate_tokens(&rules.token_rules, gen);
+    });
+    let header = &files["tokens.h"];
+    assert!(header.contains("namespace csv\n{\nnamespace lexer\n{\n"));
+    assert!(header.contains("}\n}\n\n// <lapex:keep name=\"code\">"));
+}
+
+#[test]
+fn test_concrete_syntax_tree() {
+    let source = r#"token NUM = /[0-9]+/;
+token PLUS = "+";
+skip token WS = / +/;
+entry sum;
+prod sum = NUM (PLUS NUM)*;
+"#;
+    let (rules, grammar, table) = lr1_table(source);
+    let (alphabet, dfa) = lexer_dfa(rules);
+    // the LR parser and the GLR one with its own prefix
+    let mut files = capture_generated_files(|gen| {
+        for prefix in [None, Some("glr")] {
+            let mut lexer = RustLexerCodeGen::new();
+            lexer.set_symbol_prefix(prefix);
+            lexer.generate_tokens(&rules.token_rules, gen);
+            lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+        }
+        let mut lr_parser = RustLRParserCodeGen::new();
+        lr_parser.set_cst(true);
+        lr_parser.generate_code(grammar, &table, gen);
+        let mut glr_parser = RustGLRParserCodeGen::new();
+        glr_parser.set_cst(true);
+        glr_parser.set_symbol_prefix(Some("glr"));
+        glr_parser.generate_code(grammar, &table, gen);
+    });
+    assert!(files.contains_key("cst.rs") && files.contains_key("glr_cst.rs"));
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod cst;
+mod glr_cst;
+mod glr_lexer;
+mod glr_parser;
+mod glr_tokens;
+mod lexer;
+mod parser;
+mod tokens;
+
+// the repetition has no node, its tokens are children of the sum
+fn texts<T>(children: &[T], text: impl Fn(&T) -> Option<&str>) -> Vec<&str> {
+    children.iter().map(|child| text(child).expect("a token")).collect()
+}
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("1 + 22 + 3");
+    let tree = cst::parse_tree(|| {
+        let token = lexer.next().unwrap();
+        (token, lexer.slice().to_string())
+    })
+    .unwrap();
+    assert!(matches!(
+        tree,
+        cst::Node::NonTerminal { non_terminal: cst::NonTerminalType::NtSum, alternative: 1, .. }
+    ));
+    let children = texts(tree.children(), |child| match child {
+        cst::Node::Terminal { data, .. } => Some(data.as_str()),
+        _ => None,
+    });
+    assert_eq!(children, ["1", "+", "22", "+", "3"]);
+
+    let mut lexer = glr_lexer::Lexer::new("4 + 5");
+    let tree = glr_cst::parse_tree(|| {
+        lexer
+            .next()
+            .map(|token| (token, lexer.slice().to_string()))
+    })
+    .unwrap();
+    let children = texts(tree.children(), |child| match child {
+        glr_cst::Node::Terminal { data, .. } => Some(data.as_str()),
+        _ => None,
+    });
+    assert_eq!(children, ["4", "+", "5"]);
+}
+"#,
+        ),
+    );
+    let dir = std::env::temp_dir().join(format!("lapex-cst-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in &files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", "cst", "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join("cst")).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn test_typed_syntax_tree() {
+    let source = r#"token NUM = /[0-9]+/;
+token PLUS = "+";
+token MINUS = "-";
+token LPAR = "(";
+token RPAR = ")";
+token COMMA = ",";
+token IDENT = /[a-z]+/;
+skip token WS = / +/;
+entry sum;
+prod sum = term (op term)*;
+prod op[add] = PLUS;
+prod op[sub] = MINUS;
+prod term[num] = NUM;
+prod term[call] = IDENT LPAR args? RPAR;
+prod term[paren] = LPAR sum RPAR;
+prod args = sum (COMMA sum)*;
+"#;
+    let (rules, grammar, table) = lr1_table(source);
+    let (alphabet, dfa) = lexer_dfa(rules);
+    // the LR parser and the GLR one with its own prefix
+    let mut files = capture_generated_files(|gen| {
+        for prefix in [None, Some("glr")] {
+            let mut lexer = RustLexerCodeGen::new();
+            lexer.set_symbol_prefix(prefix);
+            lexer.generate_tokens(&rules.token_rules, gen);
+            lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+        }
+        let mut lr_parser = RustLRParserCodeGen::new();
+        lr_parser.set_ast(true);
+        lr_parser.generate_code(grammar, &table, gen);
+        let mut glr_parser = RustGLRParserCodeGen::new();
+        glr_parser.set_ast(true);
+        glr_parser.set_symbol_prefix(Some("glr"));
+        glr_parser.generate_code(grammar, &table, gen);
+    });
+    assert!(files.contains_key("ast.rs") && files.contains_key("glr_ast.rs"));
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod ast;
+mod glr_ast;
+mod glr_lexer;
+mod glr_parser;
+mod glr_tokens;
+mod lexer;
+mod parser;
+mod tokens;
+
+use ast::{Op, Sum, Term};
+
+fn eval(sum: &Sum<String>) -> i64 {
+    let mut value = eval_term(&sum.term);
+    for (op, term) in &sum.sum_star {
+        match **op {
+            Op::Add { .. } => value += eval_term(term),
+            Op::Sub { .. } => value -= eval_term(term),
+        }
+    }
+    value
+}
+
+fn eval_term(term: &Term<String>) -> i64 {
+    match term {
+        Term::Num { num } => num.parse().unwrap(),
+        Term::Call { ident, args, .. } => {
+            let args: Vec<i64> = args
+                .iter()
+                .flat_map(|args| {
+                    std::iter::once(eval(&args.sum))
+                        .chain(args.args_star.iter().map(|(_, sum)| eval(sum)))
+                })
+                .collect();
+            match ident.as_str() {
+                "max" => args.into_iter().max().unwrap_or(0),
+                "neg" => -args[0],
+                name => panic!("unknown function {}", name),
+            }
+        }
+        Term::Paren { sum, .. } => eval(sum),
+    }
+}
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("1 + max(2, 10 - 3, (4)) - neg(5) + max()");
+    let tree = ast::parse_ast(|| {
+        let token = lexer.next().unwrap();
+        (token, lexer.slice().to_string())
+    })
+    .unwrap();
+    assert!(matches!(*tree.term, Term::Num { ref num } if num == "1"));
+    assert_eq!(tree.sum_star.len(), 3);
+    assert_eq!(eval(&tree), 13);
+
+    let mut lexer = glr_lexer::Lexer::new("4 - 5");
+    let tree = glr_ast::parse_ast(|| {
+        lexer
+            .next()
+            .map(|token| (token, lexer.slice().to_string()))
+    })
+    .unwrap();
+    assert!(matches!(*tree.sum_star[0].0, glr_ast::Op::Sub { ref minus } if minus == "-"));
+}
+"#,
+        ),
+    );
+    let dir = std::env::temp_dir().join(format!("lapex-ast-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in &files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", "ast", "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join("ast")).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn test_external_tokens() {
+    fn parse(source: &str) -> Result<RuleSet<'_>, LapexParsingError> {
+        lapex_input_gen::GeneratedLapexInputParser {}.parse_lapex(source)
+    }
+    assert!(matches!(
+        parse("extern token A = 1;\ntoken B = \"b\";\nentry s;\nprod s = A B;"),
+        Err(LapexParsingError::MixedExternalTokens)
+    ));
+    assert!(matches!(
+        parse("extern token A = 1;\nextern token B = 1;\nentry s;\nprod s = A B;"),
+        Err(LapexParsingError::DuplicateTokenId(1))
+    ));
+    assert!(matches!(
+        parse("extern token A = 4294967296;\nentry s;\nprod s = A;"),
+        Err(LapexParsingError::InvalidTokenId { .. })
+    ));
+
+    let source =
+        "extern token NUM = 7;\nextern token PLUS = 3;\nentry sum;\nprod sum = NUM (PLUS NUM)*;\n";
+    let (rules, grammar, table) = lr1_table(source);
+    assert!(rules.has_external_tokens());
+    assert!(lapex_input::to_lapex_source(rules).contains("extern token NUM = 7;"));
+
+    let files = capture_generated_files(|gen| {
+        let mut parser = CppLRParserCodeGen::new();
+        parser.set_external_tokens(Some("mylexer"));
+        parser.generate_code(grammar, &table, gen);
+    });
+    let visitor = &files["visitor.h"];
+    assert!(!visitor.contains("#include \"tokens.h\""));
+    assert!(visitor.contains("namespace lexer = mylexer;"));
+    assert!(visitor.contains("static_cast<uint32_t>(lexer::TokenType::TK_NUM) == 7"));
+
+    // the parser is driven by a hand-written lexer, whose enum gives the tokens their ids
+    let mut files = capture_generated_files(|gen| {
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_external_tokens(Some("crate::lexer"));
+        parser.set_cst(true);
+        parser.generate_code(grammar, &table, gen);
+    });
+    assert!(!files.contains_key("tokens.rs"));
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod cst;
+mod parser;
+
+mod lexer {
+    #[derive(Clone, Copy, Debug)]
+    pub enum TokenType {
+        EndOfFile = 0,
+        TkPlus = 3,
+        TkNum = 7,
+    }
+
+    pub fn tokenize(input: &str) -> Vec<(TokenType, &str)> {
+        input
+            .split(' ')
+            .map(|word| match word {
+                "+" => (TokenType::TkPlus, word),
+                _ => (TokenType::TkNum, word),
+            })
+            .collect()
+    }
+}
+
+fn main() {
+    let mut tokens = lexer::tokenize("1 + 2 + 3").into_iter();
+    let tree = cst::parse_tree(|| tokens.next().unwrap_or((lexer::TokenType::EndOfFile, "")))
+        .unwrap();
+    assert_eq!(tree.children().len(), 5);
+}
+"#,
+        ),
+    );
+    let dir = std::env::temp_dir().join(format!("lapex-extern-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in &files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", "extern", "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join("extern")).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn test_parse_metrics() {
+    let (_, grammar, table) = lr1_table(find_template("csv").unwrap().grammar);
+
+    let files = capture_generated_files(|gen| {
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_metrics(true);
+        parser.generate_code(grammar, &table, gen);
+    });
+    let parser_code = &files["parser.rs"];
+    assert!(parser_code.contains("pub tokens_consumed : usize"));
+    assert!(parser_code.contains("pub fn with_metrics"));
+    assert!(parser_code.contains("fn parse_measured (& mut self)"));
+    assert!(parser_code.contains("self . metrics . reductions += 1 ;"));
+
+    let files = capture_generated_files(|gen| {
+        let mut parser = RustGLRParserCodeGen::new();
+        parser.set_metrics(true);
+        parser.generate_code(grammar, &table, gen);
+    });
+    let parser_code = &files["parser.rs"];
+    assert!(parser_code.contains("pub forks : usize"));
+    assert!(parser_code.contains("fn parse_measured (& mut self)"));
+
+    let files = capture_generated_files(|gen| {
+        let mut parser = CppLRParserCodeGen::new();
+        parser.set_metrics(true);
+        parser.generate_code(grammar, &table, gen);
+    });
+    assert!(files["parser.h"].contains("class Metrics\n"));
+    assert!(files["parser.h"].contains("void set_metrics(Metrics *sink);"));
+    assert!(files["parser_impl.h"].contains("this->parse_metrics.tokens_consumed++;"));
+}
+
+#[test]
+fn test_doc_comments() {
+    let source = "/// A whole number.\n///\n/// Without a sign.\ntoken NUM = /[0-9]+/;\n\
+        token PLUS = \"+\";\nentry sum;\n/// Numbers added up.\nprod sum = NUM (PLUS NUM)*;\n";
+    let (rules, grammar, table) = lr1_table(source);
+    let token_doc = |name: &str| {
+        let rule = rules
+            .token_rules
+            .iter()
+            .find(|rule| rule.inner.name == name);
+        rule.unwrap().inner.doc.as_deref()
+    };
+    assert_eq!(token_doc("NUM"), Some("A whole number.\n\nWithout a sign."));
+    assert_eq!(token_doc("PLUS"), None);
+    assert_eq!(
+        rules.production_rules[0].inner.doc.as_deref(),
+        Some("Numbers added up.")
+    );
+    assert!(lapex_input::to_lapex_source(rules)
+        .contains("/// A whole number.\n///\n/// Without a sign.\ntoken NUM"));
+
+    let files = capture_generated_files(|gen| {
+        RustLexerCodeGen::new().generate_tokens(&rules.token_rules, gen);
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_cst(true);
+        parser.generate_code(grammar, &table, gen);
+    });
+    // the code is not formatted, quote separates the tokens by spaces
+    assert!(files["tokens.rs"].contains(
+        "# [doc = \" A whole number.\"] # [doc = \"\"] # [doc = \" Without a sign.\"] TkNum"
+    ));
+    assert!(files["parser.rs"].contains("# [doc = \" Numbers added up.\"]"));
+    assert!(files["cst.rs"].contains("# [doc = \" Numbers added up.\"] NtSum"));
+
+    let files = capture_generated_files(|gen| {
+        CppLexerCodeGen::new().generate_tokens(&rules.token_rules, gen);
+        CppLRParserCodeGen::new().generate_code(grammar, &table, gen);
+    });
+    assert!(files["tokens.h"].contains("/// A whole number.\n///\n/// Without a sign.\nTK_NUM,"));
+    assert!(files["visitor.h"].contains("/// Numbers added up.\n// sum"));
+}
+
+#[test]
+fn test_sync_tokens() {
+    fn grammar_error(source: &str) -> GrammarError {
+        let rules = lapex_input_gen::GeneratedLapexInputParser {}
+            .parse_lapex(source)
+            .unwrap();
+        Grammar::from_rule_set(&rules, false).unwrap_err()
+    }
+    assert_eq!(
+        grammar_error("token A = \"a\";\nsync B;\nentry s;\nprod s = A;"),
+        GrammarError::MissingSymbol(String::from("B"))
+    );
+    assert_eq!(
+        grammar_error("token A = \"a\";\nsync s;\nentry s;\nprod s = A;"),
+        GrammarError::NotAToken(String::from("s"))
+    );
+    assert_eq!(
+        grammar_error("token A = \"a\";\nsync A;\nentry s;\nprod s = A | error;"),
+        GrammarError::MixedErrorRecovery
+    );
+
+    let source = r#"token ID = /[a-z]+/;
+token NUM = /[0-9]+/;
+token EQ = "=";
+token PLUS = "+";
+token SEMI = ";";
+skip token WS = / +/;
+sync SEMI;
+entry program;
+prod program = stmt*;
+prod stmt = ID EQ expr SEMI;
+prod expr = NUM (PLUS NUM)*;
+"#;
+    let (rules, grammar, table) = lr1_table(source);
+    assert!(lapex_input::to_lapex_source(rules).contains("sync SEMI;"));
+    let (alphabet, dfa) = lexer_dfa(rules);
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_cst(true);
+        parser.generate_code(grammar, &table, gen);
+    });
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod cst;
+mod lexer;
+mod parser;
+mod tokens;
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("a = 1 + ; b = 2 ; c = = 3 ; d = 4 ;");
+    let mut builder = cst::TreeBuilder::new();
+    let token_function = || {
+        let token = lexer.next().unwrap();
+        (token, lexer.slice().to_string())
+    };
+    let (errors, ()) = parser::Parser::new(token_function, &mut builder).parse_all();
+    assert_eq!(errors.len(), 2);
+
+    // the symbols popped when recovering are gone, the skipped tokens come before the token the
+    // parser continued with
+    let tree = builder.take_root().unwrap();
+    let statements: Vec<Vec<String>> = tree
+        .children()
+        .iter()
+        .map(|statement| {
+            statement
+                .children()
+                .iter()
+                .map(|child| match child {
+                    cst::Node::Terminal { data, .. } => data.clone(),
+                    cst::Node::NonTerminal { .. } => String::from("expr"),
+                    cst::Node::Error { skipped, .. } => {
+                        let skipped: Vec<&str> = skipped.iter().map(|(_, text)| text.as_str()).collect();
+                        format!("error {}", skipped.join(" "))
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        statements,
+        [
+            vec!["a", "=", "expr", "error ", ";"],
+            vec!["b", "=", "expr", ";"],
+            vec!["error = 3 ;", "d", "=", "expr", ";"],
+        ]
+    );
+}
+"#,
+        ),
+    );
+    let dir = std::env::temp_dir().join(format!("lapex-sync-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in &files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", "sync", "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join("sync")).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn test_progress_hook() {
+    let (_, grammar, table) = lr1_table(
+        "extern token NUM = 1;\nextern token PLUS = 2;\nentry sum;\nprod sum = NUM (PLUS NUM)*;\n",
+    );
+
+    let files = capture_generated_files(|gen| {
+        RustLRParserCodeGen::new().generate_code(grammar, &table, gen);
+    });
+    assert!(!files["parser.rs"].contains("ParseProgress"));
+
+    // both parsers report every second token of the same input
+    let mut files = capture_generated_files(|gen| {
+        let mut lr_parser = RustLRParserCodeGen::new();
+        lr_parser.set_external_tokens(Some("crate::lexer"));
+        lr_parser.set_progress(true);
+        lr_parser.set_cst(true);
+        lr_parser.generate_code(grammar, &table, gen);
+        let mut glr_parser = RustGLRParserCodeGen::new();
+        glr_parser.set_external_tokens(Some("crate::lexer"));
+        glr_parser.set_symbol_prefix(Some("glr"));
+        glr_parser.set_progress(true);
+        glr_parser.set_cst(true);
+        glr_parser.generate_code(grammar, &table, gen);
+    });
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod cst;
+mod glr_cst;
+mod glr_parser;
+mod parser;
+
+use std::{cell::RefCell, rc::Rc};
+
+mod lexer {
+    #[derive(Clone, Copy, Debug)]
+    pub enum TokenType {
+        EndOfFile = 0,
+        TkNum = 1,
+        TkPlus = 2,
+    }
+
+    pub fn tokenize(input: &str) -> Vec<(TokenType, &str)> {
+        input
+            .split(' ')
+            .map(|word| match word {
+                "+" => (TokenType::TkPlus, word),
+                _ => (TokenType::TkNum, word),
+            })
+            .collect()
+    }
+}
+
+const INPUT: &str = "1 + 2 + 3 + 4";
+
+fn main() {
+    let reported = Rc::new(RefCell::new(Vec::new()));
+    let hook_reported = reported.clone();
+    let mut tokens = lexer::tokenize(INPUT).into_iter();
+    let mut builder = cst::TreeBuilder::new();
+    parser::Parser::new(
+        || tokens.next().unwrap_or((lexer::TokenType::EndOfFile, "")),
+        &mut builder,
+    )
+    .with_progress(2, move |progress: &parser::ParseProgress| {
+        hook_reported.borrow_mut().push(progress.tokens_consumed)
+    })
+    .parse()
+    .unwrap();
+    assert_eq!(*reported.borrow(), vec![2, 4, 6]);
+
+    reported.borrow_mut().clear();
+    let hook_reported = reported.clone();
+    let mut tokens = lexer::tokenize(INPUT).into_iter();
+    let mut builder = glr_cst::TreeBuilder::new();
+    glr_parser::Parser::new(
+        || Ok::<_, std::fmt::Error>(tokens.next().unwrap_or((lexer::TokenType::EndOfFile, ""))),
+        &mut builder,
+    )
+    .with_progress(3, move |progress: &glr_parser::ParseProgress| {
+        hook_reported.borrow_mut().push(progress.tokens_consumed)
+    })
+    .parse()
+    .unwrap();
+    assert_eq!(*reported.borrow(), vec![3, 6]);
+}
+"#,
+        ),
+    );
+    let dir = std::env::temp_dir().join(format!("lapex-progress-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in &files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", "progress", "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join("progress")).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn test_lexer_locations() {
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(
+            "token NAME = /[a-zé]+/;\nskip token WS = /[ \\n]+/;\nskip token CR = /\\r/;\nskip token COMMENT = /#[^\\r\\n]*/;\nentry s;\nprod s = NAME*;\n",
+        )
+        .unwrap();
+    let (alphabet, dfa) = lexer_dfa(&rules);
+    let files = capture_generated_files(|gen| {
+        CppLexerCodeGen::new().generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    assert!(files["lexer.h"].contains("LocatedSpan span_with_location();"));
+    assert!(files["lexer.cpp"].contains("this->end_location = this->location;"));
+    assert!(files["lexer.cpp"].contains("this->after_cr = consumed == '\\r';"));
+
+    // the skipped tokens and the characters of more than one byte move the locations as well
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod lexer;
+mod tokens;
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("  one # comment\ntwo\n  thrée  ");
+    let mut locations = Vec::new();
+    while let tokens::TokenType::TkName = lexer.next().unwrap() {
+        let located = lexer.span_with_location();
+        assert_eq!(located.start, lexer.position());
+        assert_eq!(located.span, lexer.span());
+        locations.push((
+            located.start.line,
+            located.start.column,
+            located.end.line,
+            located.end.column,
+        ));
+    }
+    assert_eq!(locations, [(1, 3, 1, 6), (2, 1, 2, 4), (3, 3, 3, 8)]);
+
+    // `\r\n`, `\r` and `\n` each end a line, also when the `\r` and `\n` are different tokens
+    let mut lexer = lexer::Lexer::new("one\r\ntwo\rthree\n\r\nfour\r\n\r\nfive");
+    let mut locations = Vec::new();
+    while let tokens::TokenType::TkName = lexer.next().unwrap() {
+        let located = lexer.span_with_location();
+        locations.push((
+            located.start.line,
+            located.start.column,
+            located.end.line,
+            located.end.column,
+        ));
+    }
+    assert_eq!(
+        locations,
+        [(1, 1, 1, 4), (2, 1, 2, 4), (3, 1, 3, 6), (5, 1, 5, 5), (7, 1, 7, 5)]
+    );
+}
+"#,
+        ),
+    );
+    let dir = std::env::temp_dir().join(format!("lapex-locations-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in &files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", "locations", "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join("locations")).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+#[test]
+fn test_skipped_ascii_runs() {
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(
+            "token NAME = /[a-zéü]+/;\nskip token WS = /[ \\n]+/;\nskip token COMMENT = /#[^\\n]*/;\nentry s;\nprod s = NAME*;\n",
+        )
+        .unwrap();
+    let (alphabet, dfa) = lexer_dfa(&rules);
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    assert!(files["lexer.rs"].contains("fn skip_ascii_run"));
+    // the runs of ascii bytes in names and comments stop at the characters of more than one byte
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod lexer;
+mod tokens;
+
+fn main() {
+    let src = "abcé   # cömment über ascii\n\n  üabc  éé # ü";
+    let mut lexer = lexer::Lexer::new(src);
+    let mut tokens = Vec::new();
+    while let tokens::TokenType::TkName = lexer.next().unwrap() {
+        let located = lexer.span_with_location();
+        tokens.push((
+            lexer.slice(),
+            located.start.line,
+            located.start.column,
+            located.end.column,
+        ));
+    }
+    assert_eq!(
+        tokens,
+        [("abcé", 1, 1, 5), ("üabc", 3, 3, 7), ("éé", 3, 9, 11)]
+    );
+    assert_eq!(lexer.span(), src.len()..src.len());
+}
+"#,
+        ),
+    );
+    compile_and_run_rust("skipped-runs", &files);
+}
+
+#[test]
+fn test_parse_str_facade() {
+    let (rules, grammar, lr_table) = lr1_table(
+        "token NUM = /[0-9]+/;\ntoken PLUS = \"+\";\nskip token WS = /[ \\n]+/;\nentry sum;\nprod sum = NUM (PLUS NUM)*;\n",
+    );
+    let (alphabet, dfa) = lexer_dfa(rules);
+
+    let files = capture_generated_files(|gen| {
+        let mut parser = CppLRParserCodeGen::new();
+        parser.set_facade(true);
+        parser.generate_code(grammar, &lr_table, gen);
+    });
+    assert!(files["parser.h"].contains("#include \"lexer.h\""));
+    assert!(files["parser.h"]
+        .contains("void parse_stream(std::istream &input, Visitor<lexer::LocatedSpan> &visitor);"));
+    assert!(files["parser.cpp"].contains("void parse_stream(std::istream &input"));
+
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_facade(true);
+        parser.generate_code(grammar, &lr_table, gen);
+    });
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod lexer;
+mod parser;
+mod tokens;
+
+struct Texts<'a>(&'a mut Vec<String>);
+
+impl<'src> parser::Visitor<&'src str> for Texts<'_> {
+    fn shift(&mut self, _token: tokens::TokenType, data: &'src str) {
+        self.0.push(data.to_string());
+    }
+    fn reduce_sum(&mut self) {}
+    fn reduce_sum_star_empty(&mut self) {}
+    fn reduce_sum_star_tail(&mut self) {}
+}
+
+fn main() {
+    // the skipped whitespace never reaches the visitor
+    let mut texts = Vec::new();
+    parser::parse_str("1 + 22\n  +3", Texts(&mut texts)).unwrap();
+    assert_eq!(texts, ["1", "+", "22", "+", "3"]);
+
+    // the lexer error is reported instead of the parser error it causes
+    let error = parser::parse_str("1 +\n x", Texts(&mut Vec::new())).unwrap_err();
+    let parser::ParseError::Lexer { location, .. } = error else {
+        panic!("expected a lexer error, got {}", error);
+    };
+    assert_eq!((location.line, location.column), (2, 2));
+
+    let error = parser::parse_str("1 + + 2", Texts(&mut Vec::new())).unwrap_err();
+    assert!(matches!(error, parser::ParseError::Parser(_)), "{}", error);
+}
+"#,
+        ),
+    );
+    compile_and_run_rust("facade", &files);
+}
+
+#[test]
+fn test_stream_lexer() {
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(
+            "token NUM = /[0-9]+/;\ntoken WORD = /[a-zäö]+/;\nskip token WS = /[ \\r\\n]+/;\nentry words;\nprod words = (NUM | WORD)*;\n",
+        )
+        .unwrap();
+    let (alphabet, dfa) = lexer_dfa(&rules);
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod lexer;
+mod tokens;
+
+use lexer::{Lexer, LexerError, LocatedSpan, StreamLexer};
+
+fn tokens(mut next: impl FnMut() -> Result<Option<(String, LocatedSpan)>, LexerError>) -> Vec<(String, LocatedSpan)> {
+    let mut tokens = Vec::new();
+    while let Some(token) = next().unwrap() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+fn main() {
+    let src = "12 öl\r\nab\r\r\n 3";
+    let mut lexer = Lexer::new(src);
+    let expected = tokens(|| match lexer.next()? {
+        tokens::TokenType::EndOfFile => Ok(None),
+        _ => Ok(Some((lexer.slice().to_string(), lexer.span_with_location()))),
+    });
+    assert_eq!(expected.len(), 4);
+
+    // the same tokens, spans and locations whether read from chars or from a reader
+    let mut lexer = StreamLexer::from_chars(src.chars());
+    let from_chars = tokens(|| match lexer.next()? {
+        tokens::TokenType::EndOfFile => Ok(None),
+        _ => Ok(Some((lexer.slice().to_string(), lexer.span_with_location()))),
+    });
+    assert_eq!(from_chars, expected);
+    let mut lexer = StreamLexer::from_reader(src.as_bytes());
+    let from_reader = tokens(|| match lexer.next()? {
+        tokens::TokenType::EndOfFile => Ok(None),
+        _ => Ok(Some((lexer.slice().to_string(), lexer.span_with_location()))),
+    });
+    assert_eq!(from_reader, expected);
+
+    // invalid UTF-8 fails when it is reached, not before
+    let mut lexer = StreamLexer::from_reader(&b"ab \xff"[..]);
+    assert!(matches!(lexer.next(), Ok(tokens::TokenType::TkWord)));
+    assert!(matches!(lexer.next(), Err(LexerError::Read(_))));
+}
+"#,
+        ),
+    );
+    compile_and_run_rust("stream-lexer", &files);
+}
+
+#[test]
+fn test_glr_ambiguous_parse_order() {
+    let (rules, grammar) = parse_grammar(
+        "token N = /[0-9]+/;\ntoken PLUS = \"+\";\nskip token WS = / +/;\nentry s;\nprod s = e;\nprod e = e PLUS e | N;\n",
+    );
+    let (alphabet, dfa) = lexer_dfa(rules);
+    let table = match generate_table::<1>(grammar, true, false) {
+        GenerationResult::AllowedConflicts { table, .. } => table,
+        _ => panic!("grammar is not ambiguous"),
+    };
+    let generate = || {
+        capture_generated_files(|gen| {
+            let lexer = RustLexerCodeGen::new();
+            lexer.generate_tokens(&rules.token_rules, gen);
+            lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+            let mut parser = RustGLRParserCodeGen::new();
+            parser.set_facade(true);
+            parser.generate_code(grammar, &table, gen);
+        })
+    };
+    let mut files = generate();
+    assert_eq!(files, generate());
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod lexer;
+mod parser;
+mod tokens;
+
+struct Trace<'a>(&'a mut Vec<String>);
+
+impl<'src> parser::Visitor<&'src str> for Trace<'_> {
+    fn shift(&mut self, _token: tokens::TokenType, data: &'src str) {
+        self.0.push(data.to_string());
+    }
+    fn reduce_s(&mut self) {}
+    fn reduce_e(&mut self) {}
+    fn reduce_e_alt_1(&mut self) {
+        self.0.push(String::from("add"));
+    }
+    fn reduce_e_alt_2(&mut self) {}
+}
+
+fn main() {
+    // every input with two additions or more has several parses, of which the one reducing
+    // first, so the left associative one, is visited in every run
+    for _ in 0..10 {
+        let mut trace = Vec::new();
+        parser::parse_str("1 + 2 + 3 + 4", Trace(&mut trace)).unwrap();
+        assert_eq!(trace, ["1", "+", "2", "add", "+", "3", "add", "+", "4", "add"]);
+    }
+}
+"#,
+        ),
+    );
+    compile_and_run_rust("glr-ambiguous", &files);
+}
+
+#[test]
+fn test_graphviz_output() {
+    let (rules, grammar) = parse_grammar(
+        "token N = /[0-9]+/;\ntoken PLUS = \"+\";\nskip token WS = / +/;\nentry s;\nprod s = e;\nprod e = e PLUS e | N;\n",
+    );
+    let (alphabet, dfa) = lexer_dfa(rules);
+    let (_, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+
+    let nfa = lapex_lexer::nfa_to_dot(&nfa, &alphabet);
+    assert!(nfa.starts_with("digraph {"));
+    assert!(nfa.contains("label = \"ε\""));
+    let dfa = lapex_lexer::dfa_to_dot(&dfa, &alphabet);
+    // the digits are split into classes by no other token, so they form one range
+    assert!(dfa.contains("label = \"0-9\""));
+    assert!(dfa.contains("label = \"\\\\u{20}\""));
+    for name in ["N", "PLUS", "WS"] {
+        assert!(dfa.contains(&format!("\\l{}\" peripheries = 2", name)));
+    }
+
+    let table = match generate_table::<1>(grammar, true, false) {
+        GenerationResult::AllowedConflicts { table, .. } => table,
+        _ => panic!("grammar is not ambiguous"),
+    };
+    let mut parser = Vec::new();
+    lapex_parser::lr_parser::output_graph(grammar, &table, &mut parser).unwrap();
+    let parser = String::from_utf8(parser).unwrap();
+    assert!(parser.starts_with("digraph {"));
+    assert!(parser.contains("label = \"accept\" peripheries = 2"));
+    assert!(parser.contains("[ label = \"PLUS("));
+    // the state after `e PLUS e` can shift another PLUS or reduce
+    assert!(parser.contains("color = red"));
+}
+
+// compiles the files with rustc into a binary, starting at `main.rs`, and runs it
+fn compile_and_run_rust(name: &str, files: &BTreeMap<String, String>) {
+    let dir = std::env::temp_dir().join(format!("lapex-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", name, "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join(name)).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
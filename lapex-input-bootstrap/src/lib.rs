@@ -2,17 +2,19 @@ use std::ops::Range;
 
 use lapex_input::{
     Characters, EntryRule, LapexInputParser, LapexParsingError, Pattern, ProductionPattern,
-    ProductionRule, RuleSet, Spanned, TokenPattern, TokenRule,
+    ProductionRule, RuleSet, SourceSpan, Spanned, TokenPattern, TokenRule,
 };
-use nom::character::complete::{multispace0, multispace1};
+use nom::character::complete::{multispace0, multispace1, space0};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take, take_while1, take_while_m_n},
     character::complete::space1,
-    combinator::{map, opt},
+    combinator::{map, opt, recognize},
     multi::{many1, separated_list1},
+    sequence::{delimited, tuple},
     IResult,
 };
+use regex_syntax::hir::{Class, HirKind};
 
 fn parse_char_unescaped(input: &[u8]) -> IResult<&[u8], char> {
     let (input, ch) = take_while_m_n(1, 1, |c: u8| {
@@ -44,6 +46,10 @@ fn parse_char_escaped(input: &[u8]) -> IResult<&[u8], char> {
         't' => '\t',
         '\\' => '\\',
         '/' => '/',
+        '[' => '[',
+        ']' => ']',
+        '(' => '(',
+        ')' => ')',
         'u' => {
             let (input, _) = tag("{")(input)?;
             let (input, code) = take_while_m_n(4, 6, |ch: u8| {
@@ -77,6 +83,48 @@ fn parse_char(input: &[u8]) -> IResult<&[u8], char> {
     alt((parse_char_unescaped, parse_char_escaped))(input)
 }
 
+/// The ranges of characters a class escape like `\\d` or `\\p{Greek}` matches. The classes are
+/// the Unicode aware ones of the regex syntax, like in the generated parser.
+fn class_escape_ranges(escape: &[u8]) -> Option<Vec<Characters>> {
+    let hir = regex_syntax::parse(std::str::from_utf8(escape).ok()?).ok()?;
+    match hir.kind() {
+        HirKind::Class(Class::Unicode(class)) => Some(
+            class
+                .iter()
+                .map(|range| Characters::Range(range.start(), range.end()))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn parse_class_escape(input: &[u8]) -> IResult<&[u8], Vec<Characters>> {
+    let (rest, escape) = recognize(tuple((
+        tag("\\"),
+        alt((
+            take_while_m_n(1, 1, |c: u8| b"dDwWsS".contains(&c)),
+            recognize(tuple((
+                take_while_m_n(1, 1, |c: u8| c == b'p' || c == b'P'),
+                alt((
+                    delimited(
+                        tag("{"),
+                        take_while1(|c: u8| c != b'}' && c != b'/'),
+                        tag("}"),
+                    ),
+                    take_while_m_n(1, 1, |c: u8| c.is_ascii_alphabetic()),
+                )),
+            ))),
+        )),
+    )))(input)?;
+    match class_escape_ranges(escape) {
+        Some(ranges) => Ok((rest, ranges)),
+        None => Err(nom::Err::Error(nom::error::Error::new(
+            input,
+            nom::error::ErrorKind::Tag,
+        ))),
+    }
+}
+
 fn parse_char_range(input: &[u8]) -> IResult<&[u8], Range<char>> {
     let (input, c1) = parse_char(input)?;
     let (input, _) = tag("-")(input)?;
@@ -97,8 +145,12 @@ fn parse_char_set(input: &[u8]) -> IResult<&[u8], Pattern> {
     let (input, _) = tag("[")(input)?;
     let (input, negation_res) = opt(tag("^"))(input)?;
     let negated = negation_res.is_some();
-    let (input, chars) = many1(parse_char_or_range)(input)?;
+    let (input, chars) = many1(alt((
+        parse_class_escape,
+        map(parse_char_or_range, |chars| vec![chars]),
+    )))(input)?;
     let (input, _) = tag("]")(input)?;
+    let chars = chars.into_iter().flatten().collect();
     Ok((input, Pattern::CharSet { chars, negated }))
 }
 
@@ -121,6 +173,10 @@ fn parse_regex_element(input: &[u8]) -> IResult<&[u8], Pattern> {
             chars: Vec::new(),
             negated: true,
         }),
+        map(parse_class_escape, |chars| Pattern::CharSet {
+            chars,
+            negated: false,
+        }),
         map(parse_char, |ch| Pattern::Char {
             chars: Characters::Single(ch),
         }),
@@ -136,16 +192,19 @@ fn parse_regex_repetition(input: &[u8]) -> IResult<&[u8], Pattern> {
                 min: 0,
                 max: None,
                 inner: Box::new(inner),
+                greedy: true,
             },
             1 => Pattern::Repetition {
                 min: 1,
                 max: None,
                 inner: Box::new(inner),
+                greedy: true,
             },
             2 => Pattern::Repetition {
                 min: 0,
                 max: Some(1),
                 inner: Box::new(inner),
+                greedy: true,
             },
             _ => unreachable!(),
         }
@@ -195,9 +254,11 @@ fn parse_token_rule(input: &[u8]) -> IResult<&[u8], TokenRule> {
     Ok((
         input,
         TokenRule {
-            name: std::str::from_utf8(name).unwrap(),
+            name: std::str::from_utf8(name).unwrap().into(),
             precedence: None,
             pattern,
+            skip: false,
+            doc: None,
         },
     ))
 }
@@ -208,19 +269,41 @@ fn parse_rule_name(input: &[u8]) -> IResult<&[u8], ProductionPattern> {
         input,
         ProductionPattern::Rule {
             rule_name: std::str::from_utf8(name).unwrap(),
+            span: SourceSpan::zero(),
         },
     ))
 }
 
+// an alternative may be empty, as in `a | ` or `| a`, unless it is the only one
+fn parse_production_alternatives(input: &[u8]) -> IResult<&[u8], ProductionPattern<'_>> {
+    let (rest, alternatives) = separated_list1(
+        tuple((space0, tag("|"), space0)),
+        map(opt(parse_production_pattern), |pattern| {
+            pattern.unwrap_or(ProductionPattern::Epsilon)
+        }),
+    )(input)?;
+    if alternatives.len() == 1 {
+        parse_production_pattern(input)
+    } else {
+        Ok((
+            rest,
+            ProductionPattern::Alternative {
+                elements: alternatives,
+            },
+        ))
+    }
+}
+
 fn parse_production_group(input: &[u8]) -> IResult<&[u8], ProductionPattern> {
     let (input, _) = tag("(")(input)?;
-    let (input, mut seqs) = separated_list1(tag(" | "), parse_production_pattern)(input)?;
+    let (input, inner) = parse_production_alternatives(input)?;
     let (input, _) = tag(")")(input)?;
-    if seqs.len() == 1 {
-        Ok((input, seqs.remove(0)))
-    } else {
-        Ok((input, ProductionPattern::Alternative { elements: seqs }))
-    }
+    Ok((
+        input,
+        ProductionPattern::Group {
+            inner: Box::new(inner),
+        },
+    ))
 }
 
 fn parse_production_element(input: &[u8]) -> IResult<&[u8], ProductionPattern> {
@@ -269,7 +352,7 @@ fn parse_production_rule(input: &[u8]) -> IResult<&[u8], ProductionRule> {
     let (input, _) = space1(input)?;
     let (input, _) = tag("=")(input)?;
     let (input, _) = space1(input)?;
-    let (input, pattern) = parse_production_pattern(input)?;
+    let (input, pattern) = parse_production_alternatives(input)?;
     let (input, _) = tag(";")(input)?;
     Ok((
         input,
@@ -277,6 +360,7 @@ fn parse_production_rule(input: &[u8]) -> IResult<&[u8], ProductionRule> {
             name: std::str::from_utf8(name).unwrap(),
             tag: None,
             pattern,
+            doc: None,
         },
     ))
 }
@@ -298,11 +382,23 @@ fn parse_entry_rule(input: &[u8]) -> IResult<&[u8], EntryRule> {
     ))
 }
 
+fn parse_sync_rule(input: &[u8]) -> IResult<&[u8], Vec<&str>> {
+    let (input, _) = tag("sync")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, names) = separated_list1(
+        tuple((space0, tag("|"), space0)),
+        map(parse_symbol_name, |name| std::str::from_utf8(name).unwrap()),
+    )(input)?;
+    let (input, _) = tag(";")(input)?;
+    Ok((input, names))
+}
+
 #[derive(Debug)]
 pub enum Rule<'src> {
     TokenRule(TokenRule<'src>),
     ProductionRule(ProductionRule<'src>),
     EntryRule(EntryRule<'src>),
+    SyncRule(Vec<&'src str>),
 }
 
 fn parse_rule(input: &[u8]) -> IResult<&[u8], Rule> {
@@ -310,6 +406,7 @@ fn parse_rule(input: &[u8]) -> IResult<&[u8], Rule> {
         map(parse_token_rule, Rule::TokenRule),
         map(parse_production_rule, Rule::ProductionRule),
         map(parse_entry_rule, Rule::EntryRule),
+        map(parse_sync_rule, Rule::SyncRule),
     ))(input)
 }
 
@@ -339,11 +436,13 @@ fn parse_lapex_file(input: &[u8]) -> Result<RuleSet, LapexParsingError> {
     let mut token_rules = Vec::new();
     let mut prod_rules = Vec::new();
     let mut entry_rules = Vec::new();
+    let mut sync_tokens = Vec::new();
     for rule in rules {
         match rule {
             Rule::TokenRule(tr) => token_rules.push(Spanned::zero(tr)),
             Rule::ProductionRule(pr) => prod_rules.push(Spanned::zero(pr)),
             Rule::EntryRule(er) => entry_rules.push(er),
+            Rule::SyncRule(names) => sync_tokens.extend(names.into_iter().map(Spanned::zero)),
         }
     }
     if entry_rules.len() == 0 {
@@ -356,7 +455,8 @@ fn parse_lapex_file(input: &[u8]) -> Result<RuleSet, LapexParsingError> {
         Spanned::zero(entry_rules.remove(0)),
         token_rules,
         prod_rules,
-    );
+    )
+    .with_sync_tokens(sync_tokens);
     Ok(rule_set)
 }
 
@@ -1,6 +1,10 @@
+use lapex_input::{Characters, Pattern, ProductionPattern, SourceSpan};
 use nom::IResult;
 
-use crate::{parse_char_escaped, parse_char_unescaped};
+use crate::{
+    parse_char_escaped, parse_char_unescaped, parse_production_rule, parse_regex_element,
+    parse_sync_rule,
+};
 
 #[test]
 fn test_parse_char_unescaped() {
@@ -51,4 +55,84 @@ fn test_parse_char_escaped() {
     assert_eq!(ires, parse_char_escaped(b"\\r"));
     let ires: IResult<&[u8], char> = Ok((b"", '\n'));
     assert_eq!(ires, parse_char_escaped(b"\\n"));
+    let ires: IResult<&[u8], char> = Ok((b"", ']'));
+    assert_eq!(ires, parse_char_escaped(b"\\]"));
+}
+
+fn char_set_contains(pattern: &Pattern, ch: char) -> bool {
+    let Pattern::CharSet { chars, negated } = pattern else {
+        panic!("{:?} is not a character set", pattern);
+    };
+    let contained = chars.iter().any(|chars| match chars {
+        Characters::Single(c) => *c == ch,
+        Characters::Range(start, end) => (*start..=*end).contains(&ch),
+    });
+    contained != *negated
+}
+
+#[test]
+fn test_parse_class_escapes() {
+    let cases: [(&[u8], &[char], &[char]); 8] = [
+        (b"\\d", &['0', '7', '\u{0664}'], &['a', ' ']),
+        (b"\\D", &['a', ' '], &['0', '7']),
+        (b"\\w", &['a', 'Z', '_', '3', '\u{00E9}'], &[' ', '-']),
+        (b"\\s", &[' ', '\t', '\n', '\r', '\u{00A0}'], &['a']),
+        (b"\\p{L}", &['a', '\u{03B1}', '\u{4E2D}'], &['1', '_']),
+        (b"\\pL", &['a', '\u{03B1}'], &['1']),
+        (b"\\P{Greek}", &['a', '1'], &['\u{03B1}']),
+        (b"[^\\s\\d]", &['a', '_'], &[' ', '5']),
+    ];
+    for (input, matched, unmatched) in cases {
+        let (rest, pattern) = parse_regex_element(input).unwrap();
+        assert!(rest.is_empty());
+        for ch in matched {
+            assert!(char_set_contains(&pattern, *ch), "{:?} in {:?}", ch, input);
+        }
+        for ch in unmatched {
+            assert!(!char_set_contains(&pattern, *ch), "{:?} in {:?}", ch, input);
+        }
+    }
+    assert!(parse_regex_element(b"\\p{Unknown}").is_err());
+}
+
+fn rule(rule_name: &str) -> ProductionPattern<'_> {
+    ProductionPattern::Sequence {
+        elements: vec![ProductionPattern::Rule {
+            rule_name,
+            span: SourceSpan::zero(),
+        }],
+    }
+}
+
+#[test]
+fn test_parse_empty_alternatives() {
+    let (_, trailing) = parse_production_rule(b"prod x = a | ;").unwrap();
+    assert_eq!(
+        trailing.pattern,
+        ProductionPattern::Alternative {
+            elements: vec![rule("a"), ProductionPattern::Epsilon]
+        }
+    );
+    let (_, leading) = parse_production_rule(b"prod x = (| a | b);").unwrap();
+    assert_eq!(
+        leading.pattern,
+        ProductionPattern::Sequence {
+            elements: vec![ProductionPattern::Group {
+                inner: Box::new(ProductionPattern::Alternative {
+                    elements: vec![ProductionPattern::Epsilon, rule("a"), rule("b")]
+                })
+            }]
+        }
+    );
+    assert!(parse_production_rule(b"prod x = ;").is_err());
+}
+
+#[test]
+fn test_parse_sync_rule() {
+    assert_eq!(parse_sync_rule(b"sync SEMI;").unwrap().1, vec!["SEMI"]);
+    assert_eq!(
+        parse_sync_rule(b"sync SEMI | RBRACE;").unwrap().1,
+        vec!["SEMI", "RBRACE"]
+    );
+    assert!(parse_sync_rule(b"sync ;").is_err());
 }
@@ -1,17 +1,21 @@
 use std::env;
 use std::path::Path;
 
-use lapex::{generate, Language, ParsingAlgorithm};
+use lapex::{generate, GenerationOptions, InputEncoding, Language};
 
 fn main() {
     let out_dir = env::var_os("OUT_DIR").unwrap();
     let dest_path = Path::new(&out_dir).join("generated_lapex");
     std::fs::create_dir_all(&dest_path).unwrap();
+    let grammar_path = Path::new("src/lapex.lapex");
+    let grammar_source = std::fs::read_to_string(grammar_path).unwrap();
     generate(
-        true,
-        ParsingAlgorithm::LR1,
-        false,
-        Path::new("src/lapex.lapex"),
+        &GenerationOptions {
+            encoding: InputEncoding::Utf8,
+            ..Default::default()
+        },
+        grammar_path,
+        &grammar_source,
         &dest_path,
         Language::Rust,
         lapex_input_bootstrap::BootstrapLapexInputParser {},
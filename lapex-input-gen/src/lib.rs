@@ -1,11 +1,23 @@
-use std::{error::Error, fmt::Display, str::Utf8Error};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    error::Error,
+    fmt::Display,
+    str::Utf8Error,
+};
 
 use lapex_input::{
-    Characters, EntryRule, LapexInputParser, Pattern, ProductionPattern, ProductionRule, RuleSet,
-    SourcePos, SourceSpan, Spanned, TokenPattern, TokenRule,
+    expand_class_references, replace_references, Characters, ClassRule, ConflictKind, EntryRule,
+    GrammarVersion, InsertionRule, LapexInputParser, LapexParsingError, Pattern,
+    PrecedenceStrategy, ProductionPattern, ProductionRule, RuleSet, SourcePos, SourceSpan, Spanned,
+    TokenPattern, TokenRule,
+};
+use parser::{Parser, ParserError};
+use regex_syntax::{
+    ast,
+    hir::{Class, Hir, HirKind, Look},
 };
-use parser::Parser;
-use regex_syntax::hir::{Class, Hir, HirKind};
 use tokens::TokenType;
 
 mod parser {
@@ -18,17 +30,210 @@ mod tokens {
     include!(concat!(env!("OUT_DIR"), "/generated_lapex/tokens.rs"));
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 struct TokenData<'src> {
     text: &'src str,
     span: SourceSpan,
 }
 
+// the pattern is only built once all rules are read, as the regex may use classes defined later
+#[derive(Debug)]
+struct UnconvertedTokenRule<'src> {
+    name: Cow<'src, str>,
+    precedence: Option<u16>,
+    definition: &'src str,
+    definition_span: SourceSpan,
+    // the arguments of a macro call, whose name is the definition
+    arguments: Option<Vec<&'src str>>,
+    skip: bool,
+    // the definition is the id of the token, which is read by a lexer of its own
+    external: bool,
+    doc: Option<String>,
+}
+
+impl<'src> UnconvertedTokenRule<'src> {
+    fn convert(
+        self,
+        class_rules: &[Spanned<ClassRule>],
+        macro_rules: &[Spanned<MacroRule>],
+    ) -> Result<TokenRule<'src>, LapexParsingError> {
+        if self.external {
+            let id = self
+                .definition
+                .parse()
+                .map_err(|_| LapexParsingError::InvalidTokenId {
+                    span: self.definition_span,
+                })?;
+            return Ok(TokenRule {
+                name: self.name,
+                precedence: None,
+                pattern: TokenPattern::External { id },
+                skip: false,
+                doc: self.doc,
+            });
+        }
+        let regex = match &self.arguments {
+            Some(arguments) => {
+                let macro_rule = macro_rules
+                    .iter()
+                    .find(|rule| rule.inner.name == self.definition)
+                    .ok_or_else(|| LapexParsingError::UnknownMacro(self.definition.to_string()))?;
+                Some(macro_rule.inner.expand(arguments)?)
+            }
+            None if self.definition.starts_with('"') => None,
+            None => Some(self.definition.to_string()),
+        };
+        let pattern = match regex {
+            Some(regex) => TokenPattern::Pattern {
+                pattern: get_regex_pattern(&expand_class_references(&regex, class_rules)?)
+                    .map_err(|e| LapexParsingError::InvalidRegex {
+                        token: self.name.to_string(),
+                        span: self.definition_span,
+                        reason: e.to_string(),
+                        suggestion: e.suggestion(),
+                    })?,
+            },
+            None => TokenPattern::Literal {
+                characters: get_unescaped_chars(self.definition),
+            },
+        };
+        Ok(TokenRule {
+            name: self.name,
+            precedence: self.precedence,
+            pattern,
+            skip: self.skip,
+            doc: self.doc,
+        })
+    }
+}
+
+// a regex with parameters, which token rules use like `name("string", /regex/, ...)`
+#[derive(Debug)]
+struct MacroRule<'src> {
+    name: &'src str,
+    parameters: Vec<&'src str>,
+    regex: &'src str,
+}
+
+impl<'src> MacroRule<'src> {
+    fn expand(&self, arguments: &[&str]) -> Result<String, LapexParsingError> {
+        if arguments.len() != self.parameters.len() {
+            return Err(LapexParsingError::WrongMacroArguments(
+                self.name.to_string(),
+            ));
+        }
+        // a string argument matches its characters, a regex is inserted as it is written
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| match argument.strip_prefix('/') {
+                Some(regex) => regex[..regex.len() - 1].to_string(),
+                None => regex_syntax::escape(&unescape_string(argument)),
+            })
+            .collect();
+        replace_references(self.regex, |name| {
+            Ok(self
+                .parameters
+                .iter()
+                .position(|parameter| *parameter == name)
+                .map(|i| arguments[i].as_str()))
+        })
+    }
+}
+
+// how a token of the grammar syntax is named in diagnostics
+fn describe_token(token: TokenType) -> &'static str {
+    match token {
+        TokenType::EndOfFile => "the end of the file",
+        TokenType::TkKwToken => "`token`",
+        TokenType::TkKwTokens => "`tokens`",
+        TokenType::TkKwEntry => "`entry`",
+        TokenType::TkKwProd => "`prod`",
+        TokenType::TkKwInsert => "`insert`",
+        TokenType::TkKwMacro => "`macro`",
+        TokenType::TkKwClass => "`class`",
+        TokenType::TkKwPrefix => "`prefix`",
+        TokenType::TkKwPrecedence => "`precedence`",
+        TokenType::TkKwLapex => "`lapex`",
+        TokenType::TkKwSkip => "`skip`",
+        TokenType::TkKwExtern => "`extern`",
+        TokenType::TkKwSync => "`sync`",
+        TokenType::TkDocComment => "a `///` comment",
+        TokenType::TkEquals => "`=`",
+        TokenType::TkSemi => "`;`",
+        TokenType::TkLpar => "`(`",
+        TokenType::TkRpar => "`)`",
+        TokenType::TkLbrack => "`[`",
+        TokenType::TkRbrack => "`]`",
+        TokenType::TkLbrace => "`{`",
+        TokenType::TkRbrace => "`}`",
+        TokenType::TkComma => "`,`",
+        TokenType::TkDigit => "a number",
+        TokenType::TkVersion => "a version",
+        TokenType::TkQuestion => "`?`",
+        TokenType::TkNothing => "`!`",
+        TokenType::TkAsterisk => "`*`",
+        TokenType::TkPlus => "`+`",
+        TokenType::TkPipe => "`|`",
+        TokenType::TkIdent => "a name",
+        TokenType::TkString => "a string",
+        TokenType::TkRegex => "a regex",
+        TokenType::TkAllow => "an `@allow(...)` annotation",
+        TokenType::TkWhitespace | TokenType::TkNewline => "whitespace",
+    }
+}
+
+// the keywords which only start a declaration, and are names of tokens and productions otherwise
+fn is_name_keyword(token: TokenType) -> bool {
+    matches!(
+        token,
+        TokenType::TkKwClass
+            | TokenType::TkKwTokens
+            | TokenType::TkKwInsert
+            | TokenType::TkKwMacro
+            | TokenType::TkKwPrefix
+            | TokenType::TkKwPrecedence
+            | TokenType::TkKwLapex
+            | TokenType::TkKwSkip
+            | TokenType::TkKwExtern
+            | TokenType::TkKwSync
+    )
+}
+
+// the characters of a string, with its quotes removed and its escapes resolved
+fn unescape_string(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text[1..text.len() - 1].chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some(escaped) => escaped,
+                None => break,
+            },
+            _ => c,
+        });
+    }
+    unescaped
+}
+
 #[derive(Debug)]
 enum Rule<'src> {
-    TokenRule(TokenRule<'src>),
+    UnconvertedTokenRule(UnconvertedTokenRule<'src>),
     ProductionRule(ProductionRule<'src>),
     EntryRule(EntryRule<'src>),
+    InsertionRule(InsertionRule<'src>),
+    SyncTokens(Vec<&'src str>),
+    ClassRule(ClassRule<'src>),
+    MacroRule(MacroRule<'src>),
+    SymbolPrefix(&'src str),
+    PrecedenceStrategy(Spanned<&'src str>),
+    KeywordSet {
+        name: &'src str,
+        precedence: Option<u16>,
+        keywords: Vec<Spanned<&'src str>>,
+    },
 }
 
 #[derive(Debug)]
@@ -39,10 +244,43 @@ enum Ast<'src> {
     Pattern(ProductionPattern<'src>),
     Rules(Vec<Spanned<Rule<'src>>>),
     Precedence(Option<u16>),
+    Tokens(Vec<&'src str>),
+    MacroCall {
+        name: &'src str,
+        arguments: Vec<&'src str>,
+    },
+    Keywords(Vec<Spanned<&'src str>>),
 }
 
 struct LapexAstVisitor<'stack, 'src> {
     stack: &'stack mut Vec<Spanned<Ast<'src>>>,
+    // read even if the rest of the grammar fails to parse, as it may use syntax of a newer version
+    version: &'stack Cell<Option<Spanned<GrammarVersion>>>,
+    // the first name of an `@allow(...)` which is no kind of conflict, reported after parsing
+    unknown_conflict_kind: &'stack Cell<Option<Spanned<&'src str>>>,
+    // the number of tokens shifted or skipped so far, which is the index of the token an error is
+    // found at
+    consumed_tokens: &'stack Cell<usize>,
+    // the index of the token each error was recovered from at
+    error_tokens: &'stack mut Vec<usize>,
+}
+
+impl<'stack, 'src> LapexAstVisitor<'stack, 'src> {
+    fn allowed_conflicts(&self, annotation: Spanned<&'src str>) -> Vec<ConflictKind> {
+        let names = &annotation.inner["@allow(".len()..annotation.inner.len() - 1];
+        names
+            .split(',')
+            .map(str::trim)
+            .filter_map(|name| {
+                let kind = ConflictKind::from_name(name);
+                if kind.is_none() && self.unknown_conflict_kind.get().is_none() {
+                    self.unknown_conflict_kind
+                        .set(Some(Spanned::new(annotation.span, name)));
+                }
+                kind
+            })
+            .collect()
+    }
 }
 
 fn get_unescaped_chars(text: &str) -> Vec<char> {
@@ -54,8 +292,7 @@ fn get_unescaped_chars(text: &str) -> Vec<char> {
 
 #[derive(Debug)]
 enum RegexConversionError {
-    LazyRepetition,
-    Lookaround,
+    Lookaround(Look),
     EmptyRegex,
     RegexSyntax(regex_syntax::Error),
     Utf8Conversion(std::str::Utf8Error),
@@ -74,11 +311,70 @@ impl From<Utf8Error> for RegexConversionError {
     }
 }
 
+impl RegexConversionError {
+    // the closest rewrite the lexer supports
+    fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            RegexConversionError::Lookaround(Look::Start | Look::StartLF | Look::StartCRLF) => Some(
+                "remove the anchor, every token starts where the previous one ended. To only match \
+                 at the start of a line, make the line break before it a part of the token",
+            ),
+            RegexConversionError::Lookaround(Look::End | Look::EndLF | Look::EndCRLF) => Some(
+                "remove the anchor, a token always ends where the next one starts. To only match \
+                 at the end of a line, make the line break a part of the token",
+            ),
+            RegexConversionError::Lookaround(_) => Some(
+                "remove the word boundary, the lexer always takes the longest match, so a token \
+                 like /[a-z]+/ never ends in the middle of a word",
+            ),
+            RegexConversionError::RegexSyntax(regex_syntax::Error::Parse(e))
+                if *e.kind() == ast::ErrorKind::UnsupportedLookAround =>
+            {
+                Some(
+                    "make the context a part of the token, or replace a negative look-ahead like \
+                     /a(?!b)/ with a negated class like /a[^b]/",
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
 impl Error for RegexConversionError {}
 
 impl Display for RegexConversionError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{:?}", self) // TODO
+        match self {
+            RegexConversionError::Lookaround(look) => write!(
+                f,
+                "the lexer matches tokens with a DFA, which can not check the context of {}",
+                match look {
+                    Look::Start | Look::StartLF | Look::StartCRLF => "a start anchor",
+                    Look::End | Look::EndLF | Look::EndCRLF => "an end anchor",
+                    _ => "a word boundary",
+                }
+            ),
+            RegexConversionError::RegexSyntax(regex_syntax::Error::Parse(e))
+                if *e.kind() == ast::ErrorKind::UnsupportedLookAround =>
+            {
+                write!(
+                    f,
+                    "the lexer matches tokens with a DFA, which can not look around a token"
+                )
+            }
+            RegexConversionError::RegexSyntax(regex_syntax::Error::Parse(e)) => {
+                write!(f, "{}", e.kind())
+            }
+            RegexConversionError::RegexSyntax(regex_syntax::Error::Translate(e)) => {
+                write!(f, "{}", e.kind())
+            }
+            RegexConversionError::RegexSyntax(e) => write!(f, "{}", e),
+            RegexConversionError::EmptyRegex => write!(f, "the regex is empty"),
+            RegexConversionError::Utf8Conversion(e) => write!(f, "{}", e),
+            RegexConversionError::ByteClass => {
+                write!(f, "the regex matches bytes instead of characters")
+            }
+        }
     }
 }
 
@@ -108,19 +404,15 @@ fn make_pattern_from_hir(hir: &Hir) -> Result<Pattern, RegexConversionError> {
             },
             Class::Bytes(_) => return Err(RegexConversionError::ByteClass),
         },
-        HirKind::Look(_) => {
-            return Err(RegexConversionError::Lookaround);
-        }
-        HirKind::Repetition(rep) => {
-            if rep.greedy == false {
-                return Err(RegexConversionError::LazyRepetition);
-            }
-            Pattern::Repetition {
-                min: rep.min,
-                max: rep.max,
-                inner: Box::new(make_pattern_from_hir(rep.sub.as_ref())?),
-            }
+        HirKind::Look(look) => {
+            return Err(RegexConversionError::Lookaround(*look));
         }
+        HirKind::Repetition(rep) => Pattern::Repetition {
+            min: rep.min,
+            max: rep.max,
+            inner: Box::new(make_pattern_from_hir(rep.sub.as_ref())?),
+            greedy: rep.greedy,
+        },
         HirKind::Capture(capture) => make_pattern_from_hir(capture.sub.as_ref())?,
         HirKind::Concat(inner) => Pattern::Sequence {
             elements: inner
@@ -146,6 +438,19 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
     fn shift(&mut self, _token: TokenType, data: TokenData<'src>) {
         self.stack
             .push(Spanned::new(data.span, Ast::Token(data.text)));
+        self.consumed_tokens.set(self.consumed_tokens.get() + 1);
+    }
+
+    fn error(
+        &mut self,
+        _error: ParserError,
+        popped_symbols: usize,
+        skipped: Vec<(TokenType, TokenData<'src>)>,
+    ) {
+        self.stack.truncate(self.stack.len() - popped_symbols);
+        self.error_tokens.push(self.consumed_tokens.get());
+        self.consumed_tokens
+            .set(self.consumed_tokens.get() + skipped.len());
     }
 
     fn reduce_unary_1(&mut self) {
@@ -190,6 +495,7 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
                 name,
                 tag,
                 pattern: rhs,
+                doc: None,
             })),
         ));
     }
@@ -215,9 +521,14 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
     }
 
     fn reduce_item_1(&mut self) {
-        let pattern = self.stack.pop().unwrap().map(|s| {
+        let token = self.stack.pop().unwrap();
+        let span = token.span;
+        let pattern = token.map(|s| {
             if let Ast::Token(name) = s {
-                Ast::Pattern(ProductionPattern::Rule { rule_name: name })
+                Ast::Pattern(ProductionPattern::Rule {
+                    rule_name: name,
+                    span,
+                })
             } else {
                 panic!("Stack is broken")
             }
@@ -233,8 +544,13 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
             panic!("Stack is broken")
         };
         let start = self.stack.pop().unwrap().span;
-        self.stack
-            .push(Spanned::between(start, end, Ast::Pattern(pattern)))
+        self.stack.push(Spanned::between(
+            start,
+            end,
+            Ast::Pattern(ProductionPattern::Group {
+                inner: Box::new(pattern),
+            }),
+        ))
     }
 
     fn reduce_concatenation_1(&mut self) {
@@ -282,10 +598,16 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
 
     fn reduce_token_rule(&mut self) {
         let semi_span = self.stack.pop().unwrap().span;
-        let rhs = if let Some(Ast::Token(rhs)) = self.stack.pop().map(|s| s.inner) {
-            rhs
-        } else {
-            panic!("Stack is broken")
+        let (rhs, arguments, rhs_span) = match self.stack.pop() {
+            Some(Spanned {
+                inner: Ast::Token(rhs),
+                span,
+            }) => (rhs, None, span),
+            Some(Spanned {
+                inner: Ast::MacroCall { name, arguments },
+                span,
+            }) => (name, Some(arguments), span),
+            _ => panic!("Stack is broken"),
         };
         self.stack.pop();
         let precedence = if let Some(Ast::Precedence(prec)) = self.stack.pop().map(|s| s.inner) {
@@ -299,22 +621,18 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
             panic!("Stack is broken")
         };
         let token_span = self.stack.pop().unwrap().span;
-        let pattern = match rhs.chars().next() {
-            Some('"') => TokenPattern::Literal {
-                characters: get_unescaped_chars(rhs),
-            },
-            Some('/') => TokenPattern::Pattern {
-                pattern: get_regex_pattern(rhs).unwrap(),
-            },
-            _ => unreachable!(),
-        };
         self.stack.push(Spanned::between(
             token_span,
             semi_span,
-            Ast::Rule(Rule::TokenRule(TokenRule {
-                name,
+            Ast::Rule(Rule::UnconvertedTokenRule(UnconvertedTokenRule {
+                name: name.into(),
                 precedence,
-                pattern,
+                definition: rhs,
+                definition_span: rhs_span,
+                arguments,
+                skip: false,
+                external: false,
+                doc: None,
             })),
         ));
     }
@@ -354,6 +672,38 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         ));
     }
 
+    fn reduce_prefix_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let prefix = if let Some(Ast::Token(prefix)) = self.stack.pop().map(|s| s.inner) {
+            prefix
+        } else {
+            panic!("Stack is broken")
+        };
+        let prefix_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            prefix_span,
+            semi_span,
+            Ast::Rule(Rule::SymbolPrefix(prefix)),
+        ));
+    }
+
+    fn reduce_precedence_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let strategy = self.stack.pop().unwrap().map(|s| {
+            if let Ast::Token(strategy) = s {
+                strategy
+            } else {
+                panic!("Stack is broken")
+            }
+        });
+        let precedence_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            precedence_span,
+            semi_span,
+            Ast::Rule(Rule::PrecedenceStrategy(strategy)),
+        ));
+    }
+
     fn reduce_repetition_one(&mut self) {
         let plus_span = self.stack.pop().unwrap().span;
         let (pattern, span) = if let Some(Spanned {
@@ -396,7 +746,7 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         } else {
             panic!("Stack is broken")
         };
-        elements.push(pattern);
+        elements.insert(0, pattern);
         self.stack.push(Spanned::between(
             concat_span,
             alt_span,
@@ -408,6 +758,49 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         // NOOP
     }
 
+    fn reduce_alternative_3(&mut self) {
+        // a leading pipe adds an empty alternative before the others
+        let (mut elements, alt_span) = match self.stack.pop() {
+            Some(Spanned {
+                inner: Ast::Pattern(ProductionPattern::Alternative { elements }),
+                span,
+            }) => (elements, span),
+            Some(Spanned {
+                inner: Ast::Pattern(pattern),
+                span,
+            }) => (vec![pattern], span),
+            _ => panic!("Stack is broken"),
+        };
+        let pipe_span = self.stack.pop().unwrap().span;
+        elements.insert(0, ProductionPattern::Epsilon);
+        self.stack.push(Spanned::between(
+            pipe_span,
+            alt_span,
+            Ast::Pattern(ProductionPattern::Alternative { elements }),
+        ))
+    }
+
+    fn reduce_alternative_4(&mut self) {
+        // a trailing pipe adds an empty alternative after the others
+        let pipe_span = self.stack.pop().unwrap().span;
+        let (pattern, concat_span) = if let Some(Spanned {
+            inner: Ast::Pattern(pattern),
+            span,
+        }) = self.stack.pop()
+        {
+            (pattern, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.push(Spanned::between(
+            concat_span,
+            pipe_span,
+            Ast::Pattern(ProductionPattern::Alternative {
+                elements: vec![pattern, ProductionPattern::Epsilon],
+            }),
+        ))
+    }
+
     fn reduce_rule_1(&mut self) {
         // NOOP
     }
@@ -420,6 +813,381 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         // NOOP
     }
 
+    fn reduce_rule_4(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_rule_5(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_class_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let regex = if let Some(Ast::Token(regex)) = self.stack.pop().map(|s| s.inner) {
+            regex
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let name = if let Some(Ast::Token(name)) = self.stack.pop().map(|s| s.inner) {
+            name
+        } else {
+            panic!("Stack is broken")
+        };
+        let class_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            class_span,
+            semi_span,
+            Ast::Rule(Rule::ClassRule(ClassRule {
+                name,
+                characters: &regex[1..regex.len() - 1],
+            })),
+        ));
+    }
+
+    fn reduce_rule_6(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_rule_7(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_rule_8(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_rule_9(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_rule_10(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_skip_rule(&mut self) {
+        let (mut token_rule, rule_span) = if let Some(Spanned {
+            inner: Ast::Rule(Rule::UnconvertedTokenRule(token_rule)),
+            span,
+        }) = self.stack.pop()
+        {
+            (token_rule, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        let skip_span = self.stack.pop().unwrap().span;
+        token_rule.skip = true;
+        self.stack.push(Spanned::between(
+            skip_span,
+            rule_span,
+            Ast::Rule(Rule::UnconvertedTokenRule(token_rule)),
+        ));
+    }
+
+    fn reduce_rule_11(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_rule_12(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_sync_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let tokens = if let Some(Ast::Tokens(tokens)) = self.stack.pop().map(|s| s.inner) {
+            tokens
+        } else {
+            panic!("Stack is broken")
+        };
+        let sync_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            sync_span,
+            semi_span,
+            Ast::Rule(Rule::SyncTokens(tokens)),
+        ));
+    }
+
+    fn reduce_extern_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let (id, id_span) = if let Some(Spanned {
+            inner: Ast::Token(id),
+            span,
+        }) = self.stack.pop()
+        {
+            (id, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let name = if let Some(Ast::Token(name)) = self.stack.pop().map(|s| s.inner) {
+            name
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let extern_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            extern_span,
+            semi_span,
+            Ast::Rule(Rule::UnconvertedTokenRule(UnconvertedTokenRule {
+                name: name.into(),
+                precedence: None,
+                definition: id,
+                definition_span: id_span,
+                arguments: None,
+                skip: false,
+                external: true,
+                doc: None,
+            })),
+        ));
+    }
+
+    fn reduce_keyword_set_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        self.stack.pop();
+        let keywords = if let Some(Ast::Keywords(keywords)) = self.stack.pop().map(|s| s.inner) {
+            keywords
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        self.stack.pop();
+        let precedence = if let Some(Ast::Precedence(prec)) = self.stack.pop().map(|s| s.inner) {
+            prec
+        } else {
+            panic!("Stack is broken")
+        };
+        let name = if let Some(Ast::Token(name)) = self.stack.pop().map(|s| s.inner) {
+            name
+        } else {
+            panic!("Stack is broken")
+        };
+        let tokens_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            tokens_span,
+            semi_span,
+            Ast::Rule(Rule::KeywordSet {
+                name,
+                precedence,
+                keywords,
+            }),
+        ));
+    }
+
+    fn reduce_keyword_list_1(&mut self) {
+        let (mut keywords, end) = if let Some(Spanned {
+            inner: Ast::Keywords(keywords),
+            span,
+        }) = self.stack.pop()
+        {
+            (keywords, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let keyword = self.stack.pop().unwrap().map(|s| {
+            if let Ast::Token(keyword) = s {
+                keyword
+            } else {
+                panic!("Stack is broken")
+            }
+        });
+        let start = keyword.span;
+        keywords.insert(0, keyword);
+        self.stack
+            .push(Spanned::between(start, end, Ast::Keywords(keywords)));
+    }
+
+    fn reduce_keyword_list_2(&mut self) {
+        let comma_span = self.stack.pop().unwrap().span;
+        self.reduce_keyword_list_3();
+        self.stack.last_mut().unwrap().span.end = comma_span.end;
+    }
+
+    fn reduce_keyword_list_3(&mut self) {
+        let keyword = self.stack.pop().unwrap();
+        let span = keyword.span;
+        self.stack.push(keyword.map(|s| {
+            if let Ast::Token(keyword) = s {
+                Ast::Keywords(vec![Spanned::new(span, keyword)])
+            } else {
+                panic!("Stack is broken")
+            }
+        }));
+    }
+
+    fn reduce_keyword_set_rule_opt_some(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_keyword_set_rule_opt_none(&mut self) {
+        self.stack.push(Spanned::zero(Ast::Precedence(None)));
+    }
+
+    fn reduce_macro_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let regex = if let Some(Ast::Token(regex)) = self.stack.pop().map(|s| s.inner) {
+            regex
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        self.stack.pop();
+        let parameters = if let Some(Ast::Tokens(parameters)) = self.stack.pop().map(|s| s.inner) {
+            parameters
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let name = if let Some(Ast::Token(name)) = self.stack.pop().map(|s| s.inner) {
+            name
+        } else {
+            panic!("Stack is broken")
+        };
+        let macro_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            macro_span,
+            semi_span,
+            Ast::Rule(Rule::MacroRule(MacroRule {
+                name,
+                parameters,
+                regex,
+            })),
+        ));
+    }
+
+    fn reduce_macro_rule_opt_some(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_macro_rule_opt_none(&mut self) {
+        self.stack.push(Spanned::zero(Ast::Tokens(Vec::new())));
+    }
+
+    fn reduce_macro_parameters_1(&mut self) {
+        self.reduce_token_list_1();
+    }
+
+    fn reduce_macro_parameters_2(&mut self) {
+        self.reduce_token_list_2();
+    }
+
+    fn reduce_macro_call(&mut self) {
+        let end = self.stack.pop().unwrap().span;
+        let arguments = if let Some(Ast::Tokens(arguments)) = self.stack.pop().map(|s| s.inner) {
+            arguments
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let (name, start) = if let Some(Spanned {
+            inner: Ast::Token(name),
+            span,
+        }) = self.stack.pop()
+        {
+            (name, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.push(Spanned::between(
+            start,
+            end,
+            Ast::MacroCall { name, arguments },
+        ));
+    }
+
+    fn reduce_macro_call_opt_some(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_macro_call_opt_none(&mut self) {
+        self.stack.push(Spanned::zero(Ast::Tokens(Vec::new())));
+    }
+
+    fn reduce_macro_arguments_1(&mut self) {
+        self.reduce_token_list_1();
+    }
+
+    fn reduce_macro_arguments_2(&mut self) {
+        self.reduce_token_list_2();
+    }
+
+    fn reduce_macro_argument_1(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_macro_argument_2(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_insertion_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let after = if let Some(Ast::Tokens(after)) = self.stack.pop().map(|s| s.inner) {
+            after
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        self.stack.pop();
+        let trigger = if let Some(Ast::Token(trigger)) = self.stack.pop().map(|s| s.inner) {
+            trigger
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let token = if let Some(Ast::Token(token)) = self.stack.pop().map(|s| s.inner) {
+            token
+        } else {
+            panic!("Stack is broken")
+        };
+        let insert_span = self.stack.pop().unwrap().span;
+        self.stack.push(Spanned::between(
+            insert_span,
+            semi_span,
+            Ast::Rule(Rule::InsertionRule(InsertionRule {
+                token,
+                trigger,
+                after,
+            })),
+        ));
+    }
+
+    fn reduce_token_list_1(&mut self) {
+        let (mut tokens, end) = if let Some(Spanned {
+            inner: Ast::Tokens(tokens),
+            span,
+        }) = self.stack.pop()
+        {
+            (tokens, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        self.stack.pop();
+        let (token, start) = if let Some(Spanned {
+            inner: Ast::Token(token),
+            span,
+        }) = self.stack.pop()
+        {
+            (token, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        tokens.insert(0, token);
+        self.stack
+            .push(Spanned::between(start, end, Ast::Tokens(tokens)));
+    }
+
+    fn reduce_token_list_2(&mut self) {
+        let token = self.stack.pop().unwrap();
+        self.stack.push(token.map(|s| {
+            if let Ast::Token(name) = s {
+                Ast::Tokens(vec![name])
+            } else {
+                panic!("Stack is broken")
+            }
+        }));
+    }
+
     fn reduce_rules_1(&mut self) {
         let rule = if let Some(Spanned {
             inner: Ast::Rule(rule),
@@ -433,6 +1201,35 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         self.stack.push(Spanned::zero(Ast::Rules(vec![rule])))
     }
 
+    fn reduce_grammar_1(&mut self) {
+        let rules = self.stack.pop().unwrap();
+        self.stack.pop();
+        self.stack.push(rules);
+    }
+
+    fn reduce_grammar_2(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_version_rule(&mut self) {
+        let semi_span = self.stack.pop().unwrap().span;
+        let version = self.stack.pop().unwrap().map(|s| {
+            if let Ast::Token(version) = s {
+                // a number too large to read is certainly newer than this version
+                GrammarVersion::parse(version).unwrap_or(GrammarVersion {
+                    major: u32::MAX,
+                    minor: u32::MAX,
+                })
+            } else {
+                panic!("Stack is broken")
+            }
+        });
+        self.version.set(Some(version));
+        let lapex_span = self.stack.pop().unwrap().span;
+        self.stack
+            .push(Spanned::between(lapex_span, semi_span, Ast::Token("")));
+    }
+
     fn reduce_rules_2(&mut self) {
         let mut rules = if let Some(Ast::Rules(rules)) = self.stack.pop().map(|s| s.inner) {
             rules
@@ -460,6 +1257,10 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         // NOOP
     }
 
+    fn reduce_string_or_regex_3(&mut self) {
+        // NOOP
+    }
+
     fn reduce_precedence(&mut self) {
         let end = self.stack.pop().unwrap().span;
         let precedence: u16 = if let Some(Ast::Token(digit)) = self.stack.pop().map(|s| s.inner) {
@@ -475,11 +1276,11 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
         ));
     }
 
-    fn reduce_anon27_1(&mut self) {
+    fn reduce_token_rule_opt_some(&mut self) {
         // NOOP
     }
 
-    fn reduce_anon27_2(&mut self) {
+    fn reduce_token_rule_opt_none(&mut self) {
         self.stack.push(Spanned::zero(Ast::Precedence(None)));
     }
 
@@ -495,17 +1296,186 @@ impl<'stack, 'src> parser::Visitor<TokenData<'src>> for LapexAstVisitor<'stack,
             .push(Spanned::between(start_span, end_span, Ast::Tag(Some(tag))));
     }
 
-    fn reduce_anon26_1(&mut self) {
+    fn reduce_prod_rule_opt_some(&mut self) {
         // NOOP
     }
 
-    fn reduce_anon26_2(&mut self) {
+    fn reduce_prod_rule_opt_none(&mut self) {
         self.stack.push(Spanned::zero(Ast::Tag(None)));
     }
+
+    fn reduce_rule_13(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_annotated_rule(&mut self) {
+        let (mut prod_rule, rule_span) = if let Some(Spanned {
+            inner: Ast::Rule(Rule::ProductionRule(prod_rule)),
+            span,
+        }) = self.stack.pop()
+        {
+            (prod_rule, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        let annotation = self.stack.pop().unwrap().map(|s| {
+            if let Ast::Token(annotation) = s {
+                annotation
+            } else {
+                panic!("Stack is broken")
+            }
+        });
+        // the annotation applies to every alternative of the production
+        prod_rule.pattern = ProductionPattern::Annotated {
+            allow: self.allowed_conflicts(annotation),
+            inner: Box::new(prod_rule.pattern),
+        };
+        self.stack.push(Spanned::between(
+            annotation.span,
+            rule_span,
+            Ast::Rule(Rule::ProductionRule(prod_rule)),
+        ));
+    }
+
+    fn reduce_choice_1(&mut self) {
+        let (pattern, concat_span) = if let Some(Spanned {
+            inner: Ast::Pattern(pattern),
+            span,
+        }) = self.stack.pop()
+        {
+            (pattern, span)
+        } else {
+            panic!("Stack is broken")
+        };
+        let annotation = self.stack.pop().unwrap().map(|s| {
+            if let Ast::Token(annotation) = s {
+                annotation
+            } else {
+                panic!("Stack is broken")
+            }
+        });
+        self.stack.push(Spanned::between(
+            annotation.span,
+            concat_span,
+            Ast::Pattern(ProductionPattern::Annotated {
+                allow: self.allowed_conflicts(annotation),
+                inner: Box::new(pattern),
+            }),
+        ))
+    }
+
+    fn reduce_choice_2(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_1(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_2(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_3(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_4(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_5(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_6(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_7(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_8(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_9(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_10(&mut self) {
+        // NOOP
+    }
+
+    fn reduce_name_11(&mut self) {
+        // NOOP
+    }
+}
+
+// the keyword becomes part of the token name, like `if` in `KW_IF` for the set `kw`
+fn keyword_token_rule<'src>(
+    set_name: &str,
+    precedence: Option<u16>,
+    keyword: Spanned<&'src str>,
+) -> Result<UnconvertedTokenRule<'src>, LapexParsingError> {
+    let characters = get_unescaped_chars(keyword.inner);
+    if characters.is_empty()
+        || !characters
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || *c == '_')
+    {
+        return Err(LapexParsingError::InvalidKeyword {
+            span: keyword.span,
+            keyword: keyword.inner.to_string(),
+        });
+    }
+    let keyword_name: String = characters.iter().collect();
+    Ok(UnconvertedTokenRule {
+        name: Cow::Owned(format!(
+            "{}_{}",
+            set_name.to_ascii_uppercase(),
+            keyword_name.to_ascii_uppercase()
+        )),
+        precedence,
+        definition: keyword.inner,
+        definition_span: keyword.span,
+        arguments: None,
+        skip: false,
+        external: false,
+        doc: None,
+    })
+}
+
+// the text of a `///` comment, without the slashes and the space after them
+fn doc_line(comment: &str) -> &str {
+    let text = &comment[3..];
+    text.strip_prefix(' ').unwrap_or(text).trim_end()
+}
+
+fn find_redefinition<'src, T>(
+    rules: &[Spanned<T>],
+    name: impl Fn(&T) -> &'src str,
+) -> Option<&'src str> {
+    rules.iter().enumerate().find_map(|(i, rule)| {
+        let defined = name(&rule.inner);
+        rules[..i]
+            .iter()
+            .any(|other| name(&other.inner) == defined)
+            .then_some(defined)
+    })
 }
 
 pub struct GeneratedLapexInputParser;
 
+// the lexer counts columns in characters as well
+fn source_pos(location: lexer::Location) -> SourcePos {
+    SourcePos {
+        line: location.line as u16,
+        col: location.column as u16,
+    }
+}
+
 impl LapexInputParser for GeneratedLapexInputParser {
     fn parse_lapex<'src>(
         &self,
@@ -513,43 +1483,123 @@ impl LapexInputParser for GeneratedLapexInputParser {
     ) -> Result<lapex_input::RuleSet<'src>, lapex_input::LapexParsingError> {
         let mut lexer = lexer::Lexer::new(source);
         let mut stack = Vec::new();
-        let visitor = LapexAstVisitor { stack: &mut stack };
-        let mut col: u16 = 1;
-        let mut line: u16 = 1;
+        let version = Cell::new(None);
+        let unknown_conflict_kind = Cell::new(None);
+        let consumed_tokens = Cell::new(0);
+        let mut error_tokens = Vec::new();
+        let visitor = LapexAstVisitor {
+            stack: &mut stack,
+            version: &version,
+            unknown_conflict_kind: &unknown_conflict_kind,
+            consumed_tokens: &consumed_tokens,
+            error_tokens: &mut error_tokens,
+        };
+        // the parser can not be told about errors of the lexer, so it gets the end of the input
+        let invalid_character = Cell::new(None);
+        // every token passed to the parser, to find the ones errors are reported at
+        let mut tokens = Vec::new();
+        // the `///` comments above a rule, by the position the rule starts at
+        let docs = RefCell::new(BTreeMap::new());
+        let mut doc_lines: Vec<&str> = Vec::new();
         let token_fun = || {
-            let mut next_tk = lexer.next().unwrap();
-            loop {
-                match next_tk {
-                    TokenType::TkNewline => {
-                        next_tk = lexer.next().unwrap();
-                        col = 1;
-                        line += 1;
+            let (next_tk, token_data) = loop {
+                let next_tk = match lexer.next() {
+                    Ok(next_tk) => next_tk,
+                    Err(_) => {
+                        let start = source_pos(lexer.span_with_location().end);
+                        let mut end = start;
+                        let offset = lexer.span().end;
+                        let next = source[offset..].chars().next().map_or(0, char::len_utf8);
+                        end.advance_range(source, offset..offset + next);
+                        let span = SourceSpan { start, end };
+                        invalid_character.set(Some(span));
+                        break (TokenType::EndOfFile, TokenData { text: "", span });
                     }
-                    TokenType::TkWhitespace => {
-                        col += lexer.slice().len() as u16;
-                        next_tk = lexer.next().unwrap();
+                };
+                let located = lexer.span_with_location();
+                let (start, end) = (source_pos(located.start), source_pos(located.end));
+                match next_tk {
+                    TokenType::TkNewline | TokenType::TkWhitespace => (),
+                    TokenType::TkDocComment => doc_lines.push(doc_line(lexer.slice())),
+                    _ => {
+                        if !doc_lines.is_empty() {
+                            docs.borrow_mut().insert(start, doc_lines.join("\n"));
+                            doc_lines.clear();
+                        }
+                        let token_data = TokenData {
+                            text: lexer.slice(),
+                            span: SourceSpan { start, end },
+                        };
+                        break (next_tk, token_data);
                     }
-                    _ => break,
                 }
-            }
-            let start_line = line;
-            let start_col = col;
-            col += lexer.slice().len() as u16;
-
-            let token_data = TokenData {
-                text: lexer.slice(),
-                span: SourceSpan {
-                    start: SourcePos {
-                        line: start_line,
-                        col: start_col,
-                    },
-                    end: SourcePos { line, col },
-                },
             };
-            return (next_tk, token_data);
+            tokens.push((next_tk, token_data));
+            (next_tk, token_data)
         };
         let mut parser = Parser::new(token_fun, visitor);
-        parser.parse().expect("error: parsing");
+        // the parser skips to the next `;` after an error, so each broken rule is reported
+        let (parser_errors, ()) = parser.parse_all();
+        let version = version.get();
+        if let Some(version) = version.filter(|v| v.inner > GrammarVersion::current()) {
+            return Err(LapexParsingError::UnsupportedVersion {
+                span: version.span,
+                version: version.inner,
+            });
+        }
+        if !parser_errors.is_empty() && source.trim().is_empty() {
+            return Err(LapexParsingError::EmptyGrammar);
+        }
+        // the error not recovered from is found at the first token neither shifted nor skipped
+        error_tokens.push(consumed_tokens.get());
+        let mut errors: Vec<LapexParsingError> = parser_errors
+            .into_iter()
+            .zip(error_tokens)
+            .map(|(error, index)| {
+                let ParserError::UnexpectedToken { expected, .. } = error;
+                let (got, token) = tokens[index];
+                LapexParsingError::UnexpectedToken {
+                    span: token.span,
+                    found: match got {
+                        TokenType::EndOfFile => describe_token(got).to_string(),
+                        _ => format!("`{}`", token.text),
+                    },
+                    expected: expected
+                        .iter()
+                        // a keyword which can be a name is already expected as one
+                        .filter(|token| {
+                            !is_name_keyword(**token)
+                                || !expected
+                                    .iter()
+                                    .any(|other| matches!(other, TokenType::TkIdent))
+                        })
+                        .copied()
+                        .map(describe_token)
+                        .collect(),
+                }
+            })
+            .collect();
+        if let Some(span) = invalid_character.get() {
+            // the end of the input the parser got in place of the character is no error of its own
+            if errors
+                .last()
+                .is_some_and(|error| error.span() == Some(span))
+            {
+                errors.pop();
+            }
+            errors.push(LapexParsingError::InvalidCharacter { span });
+        }
+        match errors.len() {
+            0 => (),
+            1 => return Err(errors.remove(0)),
+            _ => return Err(LapexParsingError::Multiple(errors)),
+        }
+        if let Some(name) = unknown_conflict_kind.get() {
+            return Err(LapexParsingError::UnknownConflictKind {
+                span: name.span,
+                name: name.inner.to_string(),
+            });
+        }
         assert_eq!(stack.len(), 1);
         let rules = if let Ast::Rules(rules) = stack.pop().unwrap().inner {
             rules
@@ -559,18 +1609,148 @@ impl LapexInputParser for GeneratedLapexInputParser {
         let mut token_rules = Vec::new();
         let mut prod_rules = Vec::new();
         let mut entry_rules = Vec::new();
+        let mut symbol_prefixes = Vec::new();
+        let mut precedence_strategies = Vec::new();
+        let mut insertion_rules = Vec::new();
+        let mut sync_tokens = Vec::new();
+        let mut class_rules: Vec<Spanned<ClassRule>> = Vec::new();
+        let mut macro_rules: Vec<Spanned<MacroRule>> = Vec::new();
 
+        let mut docs = docs.into_inner();
         for rule in rules {
             let span = rule.span;
+            let doc = docs.remove(&span.start);
             match rule.inner {
-                Rule::TokenRule(token_rule) => token_rules.push(Spanned::new(span, token_rule)),
-                Rule::ProductionRule(prod_rule) => prod_rules.push(Spanned::new(span, prod_rule)),
+                Rule::UnconvertedTokenRule(mut token_rule) => {
+                    token_rule.doc = doc;
+                    token_rules.push(Spanned::new(span, token_rule))
+                }
+                Rule::ClassRule(class_rule) => {
+                    // the class is inserted into bracket expressions, so it has to be one itself
+                    if !class_rule.characters.starts_with('[')
+                        || !class_rule.characters.ends_with(']')
+                    {
+                        return Err(LapexParsingError::InvalidRegex {
+                            token: class_rule.name.to_string(),
+                            span,
+                            reason: String::from(
+                                "a class is a single bracket expression like /[0-9a-f]/",
+                            ),
+                            suggestion: None,
+                        });
+                    }
+                    class_rules.push(Spanned::new(span, class_rule))
+                }
+                Rule::MacroRule(macro_rule) => macro_rules.push(Spanned::new(span, macro_rule)),
+                Rule::ProductionRule(mut prod_rule) => {
+                    prod_rule.doc = doc;
+                    prod_rules.push(Spanned::new(span, prod_rule))
+                }
                 Rule::EntryRule(entry_rule) => entry_rules.push(Spanned::new(span, entry_rule)),
+                Rule::SymbolPrefix(prefix) => symbol_prefixes.push(prefix),
+                Rule::PrecedenceStrategy(strategy) => precedence_strategies.push(strategy),
+                Rule::KeywordSet {
+                    name,
+                    precedence,
+                    keywords,
+                } => {
+                    // every keyword of the set gets its documentation
+                    for keyword in keywords {
+                        let span = keyword.span;
+                        let mut token_rule = keyword_token_rule(name, precedence, keyword)?;
+                        token_rule.doc = doc.clone();
+                        token_rules.push(Spanned::new(span, token_rule));
+                    }
+                }
+                Rule::InsertionRule(insertion_rule) => {
+                    insertion_rules.push(Spanned::new(span, insertion_rule))
+                }
+                Rule::SyncTokens(tokens) => {
+                    sync_tokens.extend(tokens.into_iter().map(|token| Spanned::new(span, token)))
+                }
             }
         }
 
-        assert_eq!(entry_rules.len(), 1);
-        let entry_rule = entry_rules.pop().unwrap();
-        Ok(RuleSet::new(entry_rule, token_rules, prod_rules))
+        // the first matching insertion rule applies, so keep them in source order
+        insertion_rules.sort_by_key(|rule| rule.span);
+        sync_tokens.sort_by_key(|token| token.span);
+
+        // classes and macros are looked up by name, so each may only be defined once
+        class_rules.sort_by_key(|rule| rule.span);
+        if let Some(name) = find_redefinition(&class_rules, |rule| rule.name) {
+            return Err(LapexParsingError::DuplicateCharacterClass(name.to_string()));
+        }
+        macro_rules.sort_by_key(|rule| rule.span);
+        if let Some(name) = find_redefinition(&macro_rules, |rule| rule.name) {
+            return Err(LapexParsingError::DuplicateMacro(name.to_string()));
+        }
+        let token_rules = token_rules
+            .into_iter()
+            .map(|rule| {
+                let span = rule.span;
+                Ok(Spanned::new(
+                    span,
+                    rule.inner.convert(&class_rules, &macro_rules)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, LapexParsingError>>()?;
+
+        if symbol_prefixes.len() > 1 {
+            return Err(LapexParsingError::TooManySymbolPrefixes);
+        }
+        if precedence_strategies.len() > 1 {
+            return Err(LapexParsingError::TooManyPrecedenceStrategies);
+        }
+        let precedence_strategy = precedence_strategies
+            .pop()
+            .map(|strategy| {
+                PrecedenceStrategy::from_name(strategy.inner).ok_or_else(|| {
+                    LapexParsingError::UnknownPrecedenceStrategy {
+                        span: strategy.span,
+                        name: strategy.inner.to_string(),
+                    }
+                })
+            })
+            .transpose()?;
+
+        // a grammar of only tokens needs no entry rule, it is only used to generate a lexer
+        match entry_rules.len() {
+            0 if !prod_rules.is_empty() => return Err(LapexParsingError::NoEntryRule),
+            0 | 1 => (),
+            _ => return Err(LapexParsingError::TooManyEntryRules),
+        }
+        if token_rules.is_empty() {
+            return Err(LapexParsingError::NoTokens);
+        }
+        let external_ids: Vec<u32> = token_rules
+            .iter()
+            .filter_map(|rule| rule.inner.external_id())
+            .collect();
+        if !external_ids.is_empty() {
+            if external_ids.len() != token_rules.len() {
+                return Err(LapexParsingError::MixedExternalTokens);
+            }
+            if let Some((_, id)) = external_ids
+                .iter()
+                .enumerate()
+                .find(|(i, id)| external_ids[..*i].contains(id))
+            {
+                return Err(LapexParsingError::DuplicateTokenId(*id));
+            }
+        }
+        let rule_set = match entry_rules.pop() {
+            Some(entry_rule) => RuleSet::new(entry_rule, token_rules, prod_rules),
+            None => RuleSet::tokens_only(token_rules),
+        };
+        Ok(rule_set
+            .with_insertion_rules(insertion_rules)
+            .with_sync_tokens(sync_tokens)
+            .with_class_rules(class_rules)
+            .with_symbol_prefix(symbol_prefixes.pop())
+            .with_precedence_strategy(precedence_strategy)
+            .with_version(version))
     }
 }
+
+#[cfg(test)]
+mod tests;
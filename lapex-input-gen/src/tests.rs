@@ -0,0 +1,524 @@
+use std::collections::BTreeSet;
+
+use lapex_input::{
+    ConflictKind, DefaultPrecedenceModel, GrammarVersion, LapexInputParser, LapexParsingError,
+    PrecedenceStrategy, ProductionPattern, RuleSet,
+};
+use lapex_parser::grammar::{Grammar, GrammarError};
+
+use crate::GeneratedLapexInputParser;
+
+fn parse(source: &str) -> RuleSet<'_> {
+    GeneratedLapexInputParser {}.parse_lapex(source).unwrap()
+}
+
+fn rule_names<'a>(pattern: &'a ProductionPattern) -> Vec<&'a str> {
+    match pattern {
+        ProductionPattern::Rule { rule_name, .. } => vec![rule_name],
+        ProductionPattern::Sequence { elements } | ProductionPattern::Alternative { elements } => {
+            elements.iter().flat_map(rule_names).collect()
+        }
+        ProductionPattern::OneOrMany { inner }
+        | ProductionPattern::ZeroOrMany { inner }
+        | ProductionPattern::Optional { inner }
+        | ProductionPattern::Group { inner }
+        | ProductionPattern::Annotated { inner, .. } => rule_names(inner),
+        ProductionPattern::Epsilon => Vec::new(),
+    }
+}
+
+// the names of the tokens the lexer of the rules splits the input into
+fn scan<'rules>(rules: &'rules RuleSet<'rules>, input: &str) -> Vec<&'rules str> {
+    let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, _) = lapex_lexer::apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+    lapex_lexer::scan_tokens(&dfa, &alphabet, input)
+        .unwrap()
+        .into_iter()
+        .map(|rule| &*rule.name)
+        .collect()
+}
+
+#[test]
+fn test_name_before_group() {
+    // a name directly followed by a group is no macro call in a production
+    let rules = parse(
+        "token A = \"a\";\ntoken B = \"b\";\ntoken C = \"c\";\nentry x;\nprod x = A(B | C);\n",
+    );
+    let ProductionPattern::Sequence { elements } = &rules.production_rules[0].inner.pattern else {
+        panic!("not a sequence");
+    };
+    assert!(matches!(elements[1], ProductionPattern::Group { .. }));
+    assert_eq!(
+        rule_names(&rules.production_rules[0].inner.pattern),
+        ["A", "B", "C"]
+    );
+}
+
+#[test]
+fn test_pattern_precedence() {
+    // postfix operators bind tighter than concatenation, which binds tighter than `|`
+    let rules = parse(
+        "token a = \"a\";\ntoken b = \"b\";\ntoken c = \"c\";\nentry s;\nprod s = a b* | c;\n\
+        prod t = (a | b)+ c?;\nprod u = a | b |;\n",
+    );
+    let pattern = |name: &str| {
+        let rule = rules
+            .production_rules
+            .iter()
+            .find(|rule| rule.inner.name == name);
+        &rule.unwrap().inner.pattern
+    };
+    let ProductionPattern::Alternative { elements } = pattern("s") else {
+        panic!("not an alternative");
+    };
+    assert!(matches!(
+        &elements[..],
+        [ProductionPattern::Sequence { elements }, ProductionPattern::Rule { .. }]
+            if matches!(elements[1], ProductionPattern::ZeroOrMany { .. })
+    ));
+    let ProductionPattern::Sequence { elements } = pattern("t") else {
+        panic!("not a sequence");
+    };
+    assert!(matches!(
+        &elements[..],
+        [ProductionPattern::OneOrMany { inner }, ProductionPattern::Optional { .. }]
+            if matches!(**inner, ProductionPattern::Group { .. })
+    ));
+    // a trailing pipe adds an empty alternative
+    assert!(matches!(
+        pattern("u"),
+        ProductionPattern::Alternative { elements }
+            if matches!(elements[..], [_, _, ProductionPattern::Epsilon])
+    ));
+}
+
+#[test]
+fn test_class_as_name() {
+    let rules = parse(
+        "class digit = /[0-9]/;\ntoken class = /{digit}+/;\nentry s;\nprod s = class c;\nprod c = class;\n",
+    );
+    assert_eq!(rules.class_rules[0].inner.characters, "[0-9]");
+    assert_eq!(rules.token_rules[0].inner.name, "class");
+    let s = rules
+        .production_rules
+        .iter()
+        .find(|rule| rule.inner.name == "s")
+        .unwrap();
+    assert_eq!(rule_names(&s.inner.pattern), ["class", "c"]);
+
+    let error = GeneratedLapexInputParser {}
+        .parse_lapex("class digit = /[0-9]|a/;\ntoken A = /{digit}/;\n")
+        .unwrap_err();
+    assert!(matches!(error, LapexParsingError::InvalidRegex { .. }));
+}
+
+#[test]
+fn test_declaration_words_as_names() {
+    for word in [
+        "class",
+        "insert",
+        "macro",
+        "prefix",
+        "precedence",
+        "tokens",
+        "lapex",
+        "skip",
+        "extern",
+        "sync",
+    ] {
+        let source = format!(
+            "token A = \"a\";\ntoken {word} = \"b\";\nsync {word};\nentry s;\nprod s = {word};\nprod {word} = A {word};\n"
+        );
+        let rules = GeneratedLapexInputParser {}
+            .parse_lapex(&source)
+            .unwrap_or_else(|e| panic!("{}: {}", word, e));
+        assert!(rules.token_rules.iter().any(|rule| rule.inner.name == word));
+        assert_eq!(rules.sync_tokens[0].inner, word);
+    }
+}
+
+#[test]
+fn test_expected_names() {
+    // the declaration words a name may be are not listed separately
+    let error = GeneratedLapexInputParser {}
+        .parse_lapex("token A = \"a\";\nentry s;\nprod = A;\n")
+        .unwrap_err();
+    let LapexParsingError::UnexpectedToken { expected, .. } = error else {
+        panic!("not a syntax error");
+    };
+    assert_eq!(expected, ["a name"]);
+}
+
+#[test]
+fn test_unsupported_regex() {
+    for (regex, has_suggestion) in [
+        (r"/\bif\b/", true),
+        (r"/^#.*/", true),
+        (r"/a(?=b)/", true),
+        (r"/(a/", false),
+    ] {
+        let source = format!(
+            "token ws = /[ ]+/;\ntoken x = {};\nentry s;\nprod s = (x | ws)*;",
+            regex
+        );
+        let result = GeneratedLapexInputParser {}.parse_lapex(&source);
+        match result {
+            Err(LapexParsingError::InvalidRegex {
+                token,
+                span,
+                suggestion,
+                ..
+            }) => {
+                assert_eq!(token, "x");
+                assert_eq!(span.start.line, 2);
+                assert_eq!(suggestion.is_some(), has_suggestion, "{}", regex);
+            }
+            result => panic!("{} was not rejected: {:?}", regex, result),
+        }
+    }
+}
+
+#[test]
+fn test_grammar_syntax_errors() {
+    fn parse(source: &str) -> Result<RuleSet<'_>, LapexParsingError> {
+        GeneratedLapexInputParser {}.parse_lapex(source)
+    }
+    match parse("token a = \"a\";\nentry s;\nprod s = a $;") {
+        Err(LapexParsingError::InvalidCharacter { span }) => {
+            assert_eq!((span.start.line, span.start.col), (3, 12));
+        }
+        result => panic!("invalid character was not rejected: {:?}", result),
+    }
+    match parse("token a = \"a\"\nentry s;\nprod s = a;") {
+        Err(LapexParsingError::UnexpectedToken {
+            span,
+            found,
+            expected,
+        }) => {
+            assert_eq!(span.start.line, 2);
+            assert_eq!(found, "`entry`");
+            assert_eq!(expected, vec!["`;`"]);
+        }
+        result => panic!("missing semicolon was not rejected: {:?}", result),
+    }
+    match parse("token a = \"a\";\nentry s;\nprod s = a") {
+        Err(LapexParsingError::UnexpectedToken { found, .. }) => {
+            assert_eq!(found, "the end of the file");
+        }
+        result => panic!("missing semicolon was not rejected: {:?}", result),
+    }
+    assert!(matches!(
+        parse("token a = \"a\";\nprod s = a;"),
+        Err(LapexParsingError::NoEntryRule)
+    ));
+    // a lexer can be generated from only tokens
+    let tokens_only = parse("token a = \"a\";\ntoken b = /b+/;").unwrap();
+    assert!(tokens_only.entry_rule.is_none());
+    assert_eq!(tokens_only.token_rules.len(), 2);
+    assert!(matches!(
+        parse("token a = \"a\";\nentry s;\nentry t;\nprod s = a;\nprod t = a;"),
+        Err(LapexParsingError::TooManyEntryRules)
+    ));
+    for source in ["", " \n\t\n"] {
+        assert!(matches!(
+            parse(source),
+            Err(LapexParsingError::EmptyGrammar)
+        ));
+    }
+    assert!(matches!(
+        parse("entry s;\nprod s = !;"),
+        Err(LapexParsingError::NoTokens)
+    ));
+}
+
+#[test]
+fn test_precedence_declaration() {
+    let source =
+        "token name = /[a-z]+/;\ntoken kw_if = \"if\";\nentry s;\nprod s = (name | kw_if)*;\n";
+    let declared = format!("precedence declaration;\n{}", source);
+    assert_eq!(
+        parse(&declared).precedence_strategy,
+        Some(PrecedenceStrategy::Declaration)
+    );
+    assert!(matches!(
+        GeneratedLapexInputParser {}.parse_lapex(&format!("precedence shortest;\n{}", source)),
+        Err(LapexParsingError::UnknownPrecedenceStrategy { name, .. }) if name == "shortest"
+    ));
+    assert!(matches!(
+        GeneratedLapexInputParser {}.parse_lapex(&format!("precedence longest;\n{}", declared)),
+        Err(LapexParsingError::TooManyPrecedenceStrategies)
+    ));
+}
+
+#[test]
+fn test_conflict_annotations() {
+    let source = r#"token IF = "if";
+token ELSE = "else";
+token X = "x";
+token PLUS = "+";
+entry stmt;
+prod stmt = IF stmt | @allow(shift_reduce) IF stmt ELSE stmt | expr;
+@allow(shift_reduce, reduce_reduce) prod expr = expr PLUS expr | X;
+"#;
+    let rules = parse(source);
+    let allowed = |pattern: &ProductionPattern| match pattern {
+        ProductionPattern::Annotated { allow, .. } => allow.clone(),
+        _ => Vec::new(),
+    };
+    let production = |name| {
+        rules
+            .production_rules
+            .iter()
+            .find(|rule| rule.inner.name == name)
+            .unwrap()
+    };
+    let stmt = &production("stmt").inner.pattern;
+    let ProductionPattern::Alternative { elements } = stmt else {
+        panic!("expected alternatives, got {:?}", stmt);
+    };
+    assert_eq!(
+        elements.iter().map(allowed).collect::<Vec<_>>(),
+        [vec![], vec![ConflictKind::ShiftReduce], vec![]]
+    );
+    assert_eq!(
+        allowed(&production("expr").inner.pattern),
+        [ConflictKind::ShiftReduce, ConflictKind::ReduceReduce]
+    );
+    // the annotation of a production covers the whole line
+    assert_eq!(production("expr").span.start.col, 1);
+
+    let printed = lapex_input::to_lapex_source(&rules);
+    assert!(
+        printed.contains("prod stmt = IF stmt | @allow(shift_reduce) IF stmt ELSE stmt | expr;")
+    );
+    assert!(printed.contains("@allow(shift_reduce, reduce_reduce) prod expr = expr PLUS expr | X;"));
+    assert_eq!(lapex_input::to_lapex_source(&parse(&printed)), printed);
+
+    assert!(matches!(
+        GeneratedLapexInputParser {}.parse_lapex(
+            &source.replace("shift_reduce, reduce_reduce", "shift_reduce, shift_shift")
+        ),
+        Err(LapexParsingError::UnknownConflictKind { name, .. }) if name == "shift_shift"
+    ));
+}
+
+#[test]
+fn test_keyword_sets() {
+    let source = r#"tokens kw = { "if", "else", "while", };
+token NAME = /[a-z]+/;
+token WS = / /;
+entry s;
+prod s = (KW_IF | KW_ELSE | KW_WHILE | NAME | WS)*;
+"#;
+    let rules = parse(source);
+    let names: BTreeSet<&str> = rules.token_rules.iter().map(|r| &*r.inner.name).collect();
+    assert_eq!(
+        names,
+        BTreeSet::from(["KW_ELSE", "KW_IF", "KW_WHILE", "NAME", "WS"])
+    );
+    assert_eq!(
+        scan(&rules, "if elsewhere while"),
+        ["KW_IF", "WS", "NAME", "WS", "KW_WHILE"]
+    );
+    Grammar::from_rule_set(&rules, false).unwrap();
+
+    let shared = source.replace("tokens kw =", "tokens kw[3] =");
+    let rules = parse(&shared);
+    assert!(rules
+        .token_rules
+        .iter()
+        .filter(|r| r.inner.name.starts_with("KW_"))
+        .all(|r| r.inner.precedence == Some(3)));
+
+    let invalid = source.replace("\"else\"", "\"+=\"");
+    assert!(matches!(
+        GeneratedLapexInputParser {}.parse_lapex(&invalid),
+        Err(LapexParsingError::InvalidKeyword { keyword, .. }) if keyword == "\"+=\""
+    ));
+    let redefined = source.replace("token NAME", "token KW_IF = \"if\";\ntoken NAME");
+    let rules = parse(&redefined);
+    assert!(Grammar::from_rule_set(&rules, false).is_err());
+}
+
+#[test]
+fn test_token_classes_and_macros() {
+    let source = r#"class hexdigit = /[0-9a-fA-F]/;
+macro quoted(d) = /{d}([^{d}\\]|\\.)*{d}/;
+macro prefixed(start, digits) = /{start}{digits}+/;
+token sq_str = quoted("'");
+token dq_str = quoted("\"");
+token hex = prefixed("0x", /{hexdigit}/);
+token ws = /[ ]+/;
+entry s;
+prod s = (sq_str | dq_str | hex | ws)*;
+"#;
+    let rules = parse(source);
+    assert_eq!(
+        scan(&rules, r#"'a\'"' "b'" 0x1F"#),
+        ["sq_str", "ws", "dq_str", "ws", "hex"]
+    );
+}
+
+#[test]
+fn test_grammar_version() {
+    let template = lapex_grammars::find_template("csv").unwrap().grammar;
+    let current = GrammarVersion::current();
+    let source = format!("lapex {};\n{}", current, template);
+    let rules = parse(&source);
+    assert_eq!(rules.version.map(|v| v.inner), Some(current));
+
+    // a newer grammar is reported instead of the syntax this version does not know
+    let newer = format!(
+        "lapex {}.0;\n{}\nsyntax from the future;",
+        current.major + 1,
+        template
+    );
+    assert!(matches!(
+        GeneratedLapexInputParser {}.parse_lapex(&newer),
+        Err(LapexParsingError::UnsupportedVersion { version, .. }) if version.major == current.major + 1
+    ));
+    let misplaced = format!("{}lapex {};\n", template, current);
+    assert!(matches!(
+        GeneratedLapexInputParser {}.parse_lapex(&misplaced),
+        Err(LapexParsingError::UnexpectedToken { .. })
+    ));
+}
+
+#[test]
+fn test_print_lapex_source() {
+    let print = |source: &str| {
+        let rules = parse(source);
+        lapex_input::to_lapex_source(&rules)
+    };
+    let source = r#"lapex 0.1;
+prefix demo;
+precedence declaration;
+class digit = /[0-9]/;
+tokens kw[2] = { "if", "else" };
+token COMMENT = /\/\*.*?\*\//;
+token STRING = /"([^"\\\n]|\\.)*"/;
+token NUMBER = /-?{digit}{1,3}(\.{digit}+)?/;
+token NAME = /[a-zA-Z_][a-zA-Z0-9_]*/;
+token OPT = /x(ab|c)+y?/;
+token SEMI = ";";
+token INDENT = "  ";
+token NEWLINE = /\n/;
+insert SEMI[NEWLINE] = NAME | NUMBER;
+entry block;
+prod block[list] = | statement (SEMI statement)* | ;
+prod statement = KW_IF NAME (block | (NUMBER | STRING)+) KW_ELSE? | COMMENT;
+prod empty = !;
+"#;
+    let printed = print(source);
+    assert_eq!(print(&printed), printed);
+    assert!(printed.contains("prod block[list] = | statement (SEMI statement)* | ;"));
+    assert!(printed.contains("token KW_IF[2] = \"if\";"));
+    assert!(printed.contains("prod empty = !;"));
+    for template in lapex_grammars::TEMPLATES {
+        let printed = print(template.grammar);
+        assert_eq!(print(&printed), printed, "template {}", template.name);
+    }
+}
+
+#[test]
+fn test_skipped_tokens() {
+    let source = r#"token NAME = /[a-z]+/;
+skip token WS = /[ \n]+/;
+skip token COMMENT = /#[^\n]*/;
+entry s;
+prod s = NAME*;
+"#;
+    let rules = parse(source);
+    let skipped: BTreeSet<&str> = rules
+        .token_rules
+        .iter()
+        .filter(|r| r.inner.skip)
+        .map(|r| &*r.inner.name)
+        .collect();
+    assert_eq!(skipped, BTreeSet::from(["COMMENT", "WS"]));
+    assert!(lapex_input::to_lapex_source(&rules).contains("skip token WS ="));
+    Grammar::from_rule_set(&rules, false).unwrap();
+    let used = source.replace("prod s = NAME*;", "prod s = (NAME WS)*;");
+    let rules_using_skipped = parse(&used);
+    assert!(matches!(
+        Grammar::from_rule_set(&rules_using_skipped, false),
+        Err(GrammarError::SkippedToken(name)) if name == "WS"
+    ));
+}
+
+#[test]
+fn test_grammar_line_endings() {
+    let source = "/// A name.\ntoken NAME = /[a-z]+/;\n\nentry s;\nprod s = NAME+;\n";
+    let mixed = "/// A name.\r\ntoken NAME = /[a-z]+/;\r\rentry s;\nprod s = NAME+;\r\n";
+    for source in [
+        source.to_string(),
+        source.replace('\n', "\r\n"),
+        source.replace('\n', "\r"),
+        mixed.to_string(),
+    ] {
+        let rules = parse(&source);
+        let token = &rules.token_rules[0];
+        assert_eq!(token.inner.doc.as_deref(), Some("A name."));
+        assert_eq!((token.span.start.line, token.span.start.col), (2, 1));
+        assert_eq!((token.span.end.line, token.span.end.col), (2, 23));
+        assert_eq!(rules.entry_rule.as_ref().unwrap().span.start.line, 4);
+        let production = &rules.production_rules[0];
+        assert_eq!(production.span.start.line, 5);
+        assert_eq!(production.span.substring(&source), Some("prod s = NAME+;"));
+    }
+
+    // the error points at the start of the line after a `\r\n`
+    let source = "token A = \"a\"\r\nentry s;\r\nprod s = A;\r\n";
+    let result = GeneratedLapexInputParser {}.parse_lapex(source);
+    match result {
+        Err(LapexParsingError::UnexpectedToken { span, .. }) => {
+            assert_eq!((span.start.line, span.start.col), (2, 1));
+            assert_eq!(span.substring(source), Some("entry"));
+        }
+        result => panic!("the missing semicolon was not found: {:?}", result),
+    }
+}
+
+#[test]
+fn test_grammar_syntax_error_recovery() {
+    let parser = GeneratedLapexInputParser {};
+    let source =
+        "token a = \"a\"\ntoken b = \"b\";\nentry s;\nprod s == a;\nprod t = a;\ntoken c \"c\";";
+    let errors = match parser.parse_lapex(source) {
+        Err(LapexParsingError::Multiple(errors)) => errors,
+        result => panic!("the typos were not all reported: {:?}", result),
+    };
+    let found: Vec<(u16, u16, &str)> = errors
+        .iter()
+        .map(|error| match error {
+            LapexParsingError::UnexpectedToken { span, found, .. } => {
+                (span.start.line, span.start.col, found.as_str())
+            }
+            error => panic!("unexpected error {:?}", error),
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![(2, 1, "`token`"), (4, 9, "`=`"), (6, 9, "`\"c\"`")]
+    );
+
+    // the syntax errors before an invalid character are kept
+    match parser.parse_lapex("token a = \"a\"\nentry s;\nprod s = a $;") {
+        Err(LapexParsingError::Multiple(errors)) => {
+            assert!(matches!(
+                errors.as_slice(),
+                [
+                    LapexParsingError::UnexpectedToken { .. },
+                    LapexParsingError::InvalidCharacter { .. }
+                ]
+            ));
+        }
+        result => panic!("the typos were not all reported: {:?}", result),
+    }
+}
@@ -1,4 +1,13 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+
+use unicode_width::UnicodeWidthChar;
+
+mod printer;
+pub use printer::to_lapex_source;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub struct SourcePos {
@@ -12,33 +21,108 @@ pub struct SourceSpan {
     pub end: SourcePos,
 }
 
+/// The tab width used when no other width is configured.
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Returns the number of terminal columns `ch` occupies when printed at the (1-based) display
+/// column `display_col`. Tabs advance to the next multiple of `tab_width`.
+pub fn char_display_width(ch: char, display_col: usize, tab_width: usize) -> usize {
+    match ch {
+        '\t' if tab_width > 0 => tab_width - (display_col - 1) % tab_width,
+        '\t' => 0,
+        _ => UnicodeWidthChar::width(ch).unwrap_or(0),
+    }
+}
+
+/// Returns the number of terminal columns `text` occupies when printed starting at the
+/// (1-based) display column `display_col`.
+pub fn str_display_width(text: &str, display_col: usize, tab_width: usize) -> usize {
+    let mut col = display_col;
+    for ch in text.chars() {
+        col += char_display_width(ch, col, tab_width);
+    }
+    col - display_col
+}
+
 impl SourcePos {
+    /// Moves this position past `ch`. Columns count characters, not bytes or display cells.
+    /// `\n` and `\r` both end a line, so a `\r\n` has to be passed to `advance_str` or
+    /// `advance_range` to count as one line break.
+    pub fn advance(&mut self, ch: char) {
+        match ch {
+            '\n' | '\r' => {
+                self.line += 1;
+                self.col = 1;
+            }
+            _ => {
+                self.col += 1;
+            }
+        }
+    }
+
+    /// Moves this position past every character of `text`.
+    pub fn advance_str(&mut self, text: &str) {
+        self.advance_range(text, 0..text.len());
+    }
+
+    /// Moves this position past the characters of `text` in `range`. A `\n` at the start of the
+    /// range which follows a `\r` is part of the line break the `\r` already counted.
+    pub fn advance_range(&mut self, text: &str, range: Range<usize>) {
+        let mut after_cr = text[..range.start].ends_with('\r');
+        for ch in text[range].chars() {
+            if !(after_cr && ch == '\n') {
+                self.advance(ch);
+            }
+            after_cr = ch == '\r';
+        }
+    }
+
+    /// Returns the (1-based) column at which this position is displayed, taking tabs and wide
+    /// characters in the preceding part of the line into account.
+    pub fn display_col(&self, text: &str, tab_width: usize) -> Option<usize> {
+        let line_start = SourcePos {
+            line: self.line,
+            col: 1,
+        }
+        .offset(text)?;
+        let offset = self.offset(text)?;
+        Some(1 + str_display_width(&text[line_start..offset], 1, tab_width))
+    }
+
     fn offset(&self, text: &str) -> Option<usize> {
-        let mut line = 1;
-        let mut col = 1;
+        let mut pos = SourcePos { line: 1, col: 1 };
+        let mut after_cr = false;
         for (offset, ch) in text.char_indices() {
-            if line == self.line && col == self.col {
-                return Some(offset);
+            // the `\n` of a `\r\n` is not the start of the line after it
+            if after_cr && ch == '\n' {
+                after_cr = false;
+                continue;
             }
-            match ch {
-                '\n' => {
-                    line += 1;
-                    col = 1;
-                }
-                _ => {
-                    col += 1;
-                }
+            if pos == *self {
+                return Some(offset);
             }
+            pos.advance(ch);
+            after_cr = ch == '\r';
         }
-        (line == self.line && col == self.col).then_some(text.len())
+        (pos == *self).then_some(text.len())
     }
 }
 
 impl SourceSpan {
+    pub fn zero() -> Self {
+        SourceSpan {
+            start: SourcePos { line: 0, col: 0 },
+            end: SourcePos { line: 0, col: 0 },
+        }
+    }
+
     pub fn substring<'a>(&self, text: &'a str) -> Option<&'a str> {
-        let start = self.start.offset(text)?;
-        let end = self.end.offset(text)?;
-        Some(&text[start..end])
+        self.byte_range(text).map(|range| &text[range])
+    }
+
+    /// The bytes of `text` the span covers.
+    pub fn byte_range(&self, text: &str) -> Option<Range<usize>> {
+        Some(self.start.offset(text)?..self.end.offset(text)?)
     }
 }
 
@@ -51,10 +135,7 @@ pub struct Spanned<T> {
 impl<T> Spanned<T> {
     pub fn zero(inner: T) -> Self {
         Spanned {
-            span: SourceSpan {
-                start: SourcePos { line: 0, col: 0 },
-                end: SourcePos { line: 0, col: 0 },
-            },
+            span: SourceSpan::zero(),
             inner,
         }
     }
@@ -100,6 +181,7 @@ pub enum Pattern {
         min: u32,
         max: Option<u32>,
         inner: Box<Pattern>,
+        greedy: bool,
     },
     CharSet {
         chars: Vec<Characters>,
@@ -122,13 +204,15 @@ impl Pattern {
         }
     }
 
-    fn precedence(&self) -> usize {
+    /// The number of characters the pattern matches at least. An alternative counts as its
+    /// shortest branch, so it is 0 for a pattern which can match nothing, like `a*` or `(a|)`.
+    pub fn precedence(&self) -> usize {
         match self {
             Pattern::Sequence { elements } => elements.iter().map(|p| p.precedence()).sum(),
             Pattern::Alternative { elements } => {
                 elements.iter().map(|p| p.precedence()).min().unwrap()
             }
-            Pattern::Repetition { min, max: _, inner } => *min as usize * inner.precedence(),
+            Pattern::Repetition { min, inner, .. } => *min as usize * inner.precedence(),
             Pattern::CharSet {
                 chars: _,
                 negated: _,
@@ -136,32 +220,114 @@ impl Pattern {
             Pattern::Char { chars: _ } => 1,
         }
     }
+
+    fn has_lazy_repetition(&self) -> bool {
+        match self {
+            Pattern::Sequence { elements } | Pattern::Alternative { elements } => {
+                elements.iter().any(|p| p.has_lazy_repetition())
+            }
+            Pattern::Repetition { inner, greedy, .. } => !greedy || inner.has_lazy_repetition(),
+            Pattern::CharSet { .. } | Pattern::Char { .. } => false,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub enum TokenPattern {
-    Literal { characters: Vec<char> },
-    Pattern { pattern: Pattern },
+    Literal {
+        characters: Vec<char>,
+    },
+    Pattern {
+        pattern: Pattern,
+    },
+    /// Declared with `extern token`, the token is read by a lexer outside of lapex and has this
+    /// id in its token enum.
+    External {
+        id: u32,
+    },
+}
+
+/// Scores the tokens which have no explicit precedence, when several tokens match the same input
+/// and the one with the highest precedence is picked.
+pub trait PrecedenceModel {
+    /// The precedence of a literal token.
+    fn literal(&self, characters: &[char]) -> Option<usize>;
+    /// The precedence of a regex token. Without one, the token loses against every token which
+    /// has one, and it can not be told apart from another token without one.
+    fn pattern(&self, pattern: &Pattern) -> Option<usize>;
+}
+
+/// Scores a literal with twice its length and a regex with [`Pattern::precedence`], so a keyword
+/// wins against a name regex. A regex with an optional part scores as if it was left out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultPrecedenceModel;
+
+impl PrecedenceModel for DefaultPrecedenceModel {
+    fn literal(&self, characters: &[char]) -> Option<usize> {
+        Some(characters.len() * 2)
+    }
+
+    fn pattern(&self, pattern: &Pattern) -> Option<usize> {
+        Some(pattern.precedence())
+    }
+}
+
+/// Scores literals like [`DefaultPrecedenceModel`], but gives no precedence to regexes. Two
+/// regexes which match the same input need an explicit precedence to pick one of them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConservativePrecedenceModel;
+
+impl PrecedenceModel for ConservativePrecedenceModel {
+    fn literal(&self, characters: &[char]) -> Option<usize> {
+        DefaultPrecedenceModel.literal(characters)
+    }
+
+    fn pattern(&self, _pattern: &Pattern) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Debug)]
 pub struct TokenRule<'src> {
-    pub name: &'src str,
+    pub name: Cow<'src, str>,
     pub precedence: Option<u16>,
     pub pattern: TokenPattern,
+    /// Read by the lexer like any other token, but never returned, like whitespace or comments.
+    pub skip: bool,
+    /// The `///` comment above the rule, without the slashes. Its lines are joined by `\n`.
+    pub doc: Option<String>,
 }
 
 impl<'src> TokenRule<'src> {
-    pub fn precedence(&self) -> usize {
+    /// The explicit precedence of the token, or the one the model gives it otherwise.
+    pub fn precedence(&self, model: &dyn PrecedenceModel) -> Option<usize> {
         if let Some(prec) = self.precedence {
-            prec as usize
+            Some(prec as usize)
         } else {
             match &self.pattern {
-                TokenPattern::Literal { characters } => characters.len() * 2,
-                TokenPattern::Pattern { pattern } => pattern.precedence(),
+                TokenPattern::Literal { characters } => model.literal(characters),
+                TokenPattern::Pattern { pattern } => model.pattern(pattern),
+                TokenPattern::External { .. } => None,
             }
         }
     }
+
+    /// A token with a lazy repetition ends at the first position its pattern matches, instead of
+    /// the last one.
+    pub fn is_lazy(&self) -> bool {
+        match &self.pattern {
+            TokenPattern::Literal { .. } | TokenPattern::External { .. } => false,
+            TokenPattern::Pattern { pattern } => pattern.has_lazy_repetition(),
+        }
+    }
+
+    /// The id of a token declared with `extern token`.
+    pub fn external_id(&self) -> Option<u32> {
+        match &self.pattern {
+            TokenPattern::External { id } => Some(*id),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -169,6 +335,31 @@ pub struct ProductionRule<'src> {
     pub name: &'src str,
     pub tag: Option<&'src str>,
     pub pattern: ProductionPattern<'src>,
+    /// The `///` comment above the rule, without the slashes. Its lines are joined by `\n`.
+    pub doc: Option<String>,
+}
+
+/// Inserts `token` in place of a `trigger` token that follows one of the tokens in `after`, and
+/// at the end of the input after one of them. Trigger tokens never reach the parser.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InsertionRule<'src> {
+    pub token: &'src str,
+    pub trigger: &'src str,
+    pub after: Vec<&'src str>,
+}
+
+impl<'src> InsertionRule<'src> {
+    fn mentions(&self, name: &str) -> bool {
+        self.token == name || self.trigger == name || self.after.contains(&name)
+    }
+}
+
+/// A named set of characters, which the regexes of token rules use as `{name}`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ClassRule<'src> {
+    pub name: &'src str,
+    /// The bracket expression of the class, like `[0-9a-fA-F]`.
+    pub characters: &'src str,
 }
 
 #[derive(Debug)]
@@ -176,6 +367,9 @@ pub struct EntryRule<'src> {
     pub name: &'src str,
 }
 
+/// The pattern of a production rule. From the tightest to the loosest binding, the operators
+/// are the postfix `?`, `*` and `+`, which apply to a single name or parenthesized group, then
+/// concatenation and then `|`, so `a b* | c` is read as `(a (b)*) | c`.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ProductionPattern<'src> {
     Sequence {
@@ -193,17 +387,174 @@ pub enum ProductionPattern<'src> {
     Optional {
         inner: Box<ProductionPattern<'src>>,
     },
+    /// A parenthesized pattern, kept so it is never merged into the enclosing sequence or
+    /// alternative.
+    Group {
+        inner: Box<ProductionPattern<'src>>,
+    },
+    /// An alternative written after `@allow(...)`, or the whole pattern of a production written
+    /// after it. The parser tolerates the listed conflicts of its rules.
+    Annotated {
+        allow: Vec<ConflictKind>,
+        inner: Box<ProductionPattern<'src>>,
+    },
     Rule {
         rule_name: &'src str,
+        span: SourceSpan,
     },
     Epsilon,
 }
 
+/// A kind of LR conflict which the rules of an alternative allow with `@allow(...)`. The LR
+/// parser shifts instead of reducing and reduces the rule declared first, like yacc does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConflictKind {
+    ShiftReduce,
+    ReduceReduce,
+}
+
+impl ConflictKind {
+    pub const ALL: [ConflictKind; 2] = [ConflictKind::ShiftReduce, ConflictKind::ReduceReduce];
+
+    /// The name of the kind in `@allow(...)`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConflictKind::ShiftReduce => "shift_reduce",
+            ConflictKind::ReduceReduce => "reduce_reduce",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<ConflictKind> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+impl Display for ConflictKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl<'src> ProductionPattern<'src> {
+    fn collect_references(&self, name: &str, references: &mut Vec<SourceSpan>) {
+        match self {
+            ProductionPattern::Sequence { elements }
+            | ProductionPattern::Alternative { elements } => {
+                for element in elements {
+                    element.collect_references(name, references);
+                }
+            }
+            ProductionPattern::OneOrMany { inner }
+            | ProductionPattern::ZeroOrMany { inner }
+            | ProductionPattern::Optional { inner }
+            | ProductionPattern::Group { inner }
+            | ProductionPattern::Annotated { inner, .. } => {
+                inner.collect_references(name, references)
+            }
+            ProductionPattern::Rule { rule_name, span } => {
+                if *rule_name == name {
+                    references.push(*span);
+                }
+            }
+            ProductionPattern::Epsilon => (),
+        }
+    }
+}
+
+/// How the token is picked when several tokens match the same input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrecedenceStrategy {
+    /// The token with the highest precedence wins. Without an explicit one, the
+    /// [`PrecedenceModel`] scores it.
+    #[default]
+    Longest,
+    /// Explicit precedences are compared first, the token declared first wins otherwise.
+    Declaration,
+    /// Only explicit precedences are compared, a token has to have the highest one to win.
+    Explicit,
+}
+
+impl PrecedenceStrategy {
+    pub const ALL: [PrecedenceStrategy; 3] = [
+        PrecedenceStrategy::Longest,
+        PrecedenceStrategy::Declaration,
+        PrecedenceStrategy::Explicit,
+    ];
+
+    /// The name of the strategy in a grammar and on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrecedenceStrategy::Longest => "longest",
+            PrecedenceStrategy::Declaration => "declaration",
+            PrecedenceStrategy::Explicit => "explicit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PrecedenceStrategy> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == name)
+    }
+}
+
+impl Display for PrecedenceStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The version of lapex a grammar is written for. Like for lapex, a new minor version may change
+/// the syntax as long as the major version is 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GrammarVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl GrammarVersion {
+    /// The version of the grammars this lapex reads.
+    pub fn current() -> GrammarVersion {
+        GrammarVersion::parse(env!("CARGO_PKG_VERSION")).unwrap()
+    }
+
+    /// Reads `major.minor`, any further components are ignored.
+    pub fn parse(text: &str) -> Option<GrammarVersion> {
+        let mut components = text.split('.');
+        Some(GrammarVersion {
+            major: components.next()?.parse().ok()?,
+            minor: components.next()?.parse().ok()?,
+        })
+    }
+
+    /// Whether a grammar written for the other version has the same syntax.
+    pub fn is_compatible(&self, other: &GrammarVersion) -> bool {
+        self.major == other.major && (self.major > 0 || self.minor == other.minor)
+    }
+}
+
+impl Display for GrammarVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
 #[derive(Debug)]
 pub struct RuleSet<'src> {
-    pub entry_rule: Spanned<EntryRule<'src>>,
+    /// Missing in grammars of only tokens, which a lexer can be generated from but no parser.
+    pub entry_rule: Option<Spanned<EntryRule<'src>>>,
     pub token_rules: Vec<Spanned<TokenRule<'src>>>,
     pub production_rules: Vec<Spanned<ProductionRule<'src>>>,
+    pub insertion_rules: Vec<Spanned<InsertionRule<'src>>>,
+    /// Declared by `sync <tokens>;`, the tokens the parser skips to when recovering from an
+    /// error. Each is spanned by its declaration.
+    pub sync_tokens: Vec<Spanned<&'src str>>,
+    pub class_rules: Vec<Spanned<ClassRule<'src>>>,
+    /// Declared by `prefix <name>;`, the generated modules or namespaces are named after it.
+    pub symbol_prefix: Option<&'src str>,
+    /// Declared by `precedence <strategy>;`.
+    pub precedence_strategy: Option<PrecedenceStrategy>,
+    /// Declared by `lapex <version>;` at the top of the grammar.
+    pub version: Option<Spanned<GrammarVersion>>,
 }
 
 impl<'src> RuleSet<'src> {
@@ -213,28 +564,354 @@ impl<'src> RuleSet<'src> {
         production_rules: Vec<Spanned<ProductionRule<'src>>>,
     ) -> Self {
         RuleSet {
-            entry_rule,
+            entry_rule: Some(entry_rule),
             token_rules,
             production_rules,
+            insertion_rules: Vec::new(),
+            sync_tokens: Vec::new(),
+            class_rules: Vec::new(),
+            symbol_prefix: None,
+            precedence_strategy: None,
+            version: None,
+        }
+    }
+
+    /// A grammar of only tokens, without productions and an entry rule.
+    pub fn tokens_only(token_rules: Vec<Spanned<TokenRule<'src>>>) -> Self {
+        RuleSet {
+            entry_rule: None,
+            token_rules,
+            production_rules: Vec::new(),
+            insertion_rules: Vec::new(),
+            sync_tokens: Vec::new(),
+            class_rules: Vec::new(),
+            symbol_prefix: None,
+            precedence_strategy: None,
+            version: None,
         }
     }
+
+    pub fn with_insertion_rules(
+        mut self,
+        insertion_rules: Vec<Spanned<InsertionRule<'src>>>,
+    ) -> Self {
+        self.insertion_rules = insertion_rules;
+        self
+    }
+
+    pub fn with_sync_tokens(mut self, sync_tokens: Vec<Spanned<&'src str>>) -> Self {
+        self.sync_tokens = sync_tokens;
+        self
+    }
+
+    pub fn with_class_rules(mut self, class_rules: Vec<Spanned<ClassRule<'src>>>) -> Self {
+        self.class_rules = class_rules;
+        self
+    }
+
+    pub fn with_symbol_prefix(mut self, symbol_prefix: Option<&'src str>) -> Self {
+        self.symbol_prefix = symbol_prefix;
+        self
+    }
+
+    pub fn with_precedence_strategy(
+        mut self,
+        precedence_strategy: Option<PrecedenceStrategy>,
+    ) -> Self {
+        self.precedence_strategy = precedence_strategy;
+        self
+    }
+
+    pub fn with_version(mut self, version: Option<Spanned<GrammarVersion>>) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Whether the tokens are declared with `extern token`, so neither the tokens nor a lexer
+    /// are generated for them.
+    pub fn has_external_tokens(&self) -> bool {
+        self.token_rules
+            .iter()
+            .any(|rule| rule.inner.external_id().is_some())
+    }
+
+    pub fn defines_symbol(&self, name: &str) -> bool {
+        self.token_rules.iter().any(|r| r.inner.name == name)
+            || self.production_rules.iter().any(|r| r.inner.name == name)
+    }
+
+    /// Returns the spans of all places where the token or production `name` is used.
+    pub fn symbol_references(&self, name: &str) -> Vec<SourceSpan> {
+        let mut references = Vec::new();
+        if let Some(entry_rule) = self.entry_rule.as_ref().filter(|r| r.inner.name == name) {
+            references.push(entry_rule.span);
+        }
+        for rule in &self.production_rules {
+            rule.inner.pattern.collect_references(name, &mut references);
+        }
+        for rule in &self.insertion_rules {
+            if rule.inner.mentions(name) {
+                references.push(rule.span);
+            }
+        }
+        for token in self.sync_tokens.iter().filter(|token| token.inner == name) {
+            references.push(token.span);
+        }
+        references.sort();
+        references
+    }
 }
 
 #[derive(Debug)]
 pub enum LapexParsingError {
     IncompleteParsing(String),
+    /// The grammar contains nothing but whitespace.
+    EmptyGrammar,
     NoEntryRule,
+    /// The grammar declares no tokens, so its parser could only accept the empty input.
+    NoTokens,
     TooManyEntryRules,
+    TooManySymbolPrefixes,
+    TooManyPrecedenceStrategies,
+    /// The grammar is written for a newer lapex, which may have syntax this one does not know.
+    UnsupportedVersion {
+        span: SourceSpan,
+        version: GrammarVersion,
+    },
+    UnknownPrecedenceStrategy {
+        span: SourceSpan,
+        name: String,
+    },
+    /// An `@allow(...)` lists a name which is no kind of conflict.
+    UnknownConflictKind {
+        span: SourceSpan,
+        name: String,
+    },
+    UnknownCharacterClass(String),
+    DuplicateCharacterClass(String),
+    UnknownMacro(String),
+    DuplicateMacro(String),
+    WrongMacroArguments(String),
+    /// Some tokens are declared with `extern token` and others with a pattern, but a lexer can
+    /// only be generated for all tokens or none of them.
+    MixedExternalTokens,
+    /// Two `extern token` declarations share the id.
+    DuplicateTokenId(u32),
+    /// The id of an `extern token` does not fit into 32 bits.
+    InvalidTokenId {
+        span: SourceSpan,
+    },
+    /// A keyword of a `tokens` set which can not be part of a token name.
+    InvalidKeyword {
+        span: SourceSpan,
+        keyword: String,
+    },
+    /// No token of the grammar syntax starts with the character.
+    InvalidCharacter {
+        span: SourceSpan,
+    },
+    UnexpectedToken {
+        span: SourceSpan,
+        found: String,
+        expected: Vec<&'static str>,
+    },
+    /// The regex of a token uses a feature the lexer can not match.
+    InvalidRegex {
+        token: String,
+        span: SourceSpan,
+        reason: String,
+        suggestion: Option<&'static str>,
+    },
+    /// Several syntax errors, in the order they are found in the grammar.
+    Multiple(Vec<LapexParsingError>),
 }
 
 impl std::error::Error for LapexParsingError {}
 
+impl LapexParsingError {
+    /// Where in the grammar the error is, if it is caused by a single place.
+    pub fn span(&self) -> Option<SourceSpan> {
+        match self {
+            LapexParsingError::InvalidCharacter { span }
+            | LapexParsingError::UnexpectedToken { span, .. }
+            | LapexParsingError::UnknownPrecedenceStrategy { span, .. }
+            | LapexParsingError::UnknownConflictKind { span, .. }
+            | LapexParsingError::InvalidKeyword { span, .. }
+            | LapexParsingError::InvalidTokenId { span }
+            | LapexParsingError::UnsupportedVersion { span, .. }
+            | LapexParsingError::InvalidRegex { span, .. } => Some(*span),
+            _ => None,
+        }
+    }
+}
+
 impl Display for LapexParsingError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            LapexParsingError::IncompleteParsing(rest) => {
+                write!(f, "failed to parse the grammar from: {}", rest)
+            }
+            LapexParsingError::EmptyGrammar => write!(
+                f,
+                "the grammar is empty, it needs tokens, productions and an entry rule"
+            ),
+            LapexParsingError::NoEntryRule => write!(
+                f,
+                "the grammar has no entry rule, only a lexer can be generated from it"
+            ),
+            LapexParsingError::NoTokens => write!(f, "the grammar defines no tokens"),
+            LapexParsingError::TooManyEntryRules => {
+                write!(f, "the grammar has more than one entry rule")
+            }
+            LapexParsingError::TooManySymbolPrefixes => {
+                write!(f, "the grammar declares more than one prefix")
+            }
+            LapexParsingError::TooManyPrecedenceStrategies => {
+                write!(f, "the grammar declares more than one precedence strategy")
+            }
+            LapexParsingError::UnknownPrecedenceStrategy { name, .. } => {
+                let names: Vec<&str> = PrecedenceStrategy::ALL
+                    .iter()
+                    .map(|strategy| strategy.name())
+                    .collect();
+                write!(
+                    f,
+                    "there is no precedence strategy {}, expected {}",
+                    name,
+                    names.join(" or ")
+                )
+            }
+            LapexParsingError::UnknownConflictKind { name, .. } => {
+                let names: Vec<&str> = ConflictKind::ALL.iter().map(|kind| kind.name()).collect();
+                write!(
+                    f,
+                    "there is no kind of conflict {}, expected {}",
+                    name,
+                    names.join(" or ")
+                )
+            }
+            LapexParsingError::UnknownCharacterClass(name) => {
+                write!(f, "the class {} is not defined", name)
+            }
+            LapexParsingError::DuplicateCharacterClass(name) => {
+                write!(f, "the class {} is defined more than once", name)
+            }
+            LapexParsingError::UnknownMacro(name) => write!(f, "the macro {} is not defined", name),
+            LapexParsingError::DuplicateMacro(name) => {
+                write!(f, "the macro {} is defined more than once", name)
+            }
+            LapexParsingError::WrongMacroArguments(name) => {
+                write!(
+                    f,
+                    "the macro {} is called with the wrong number of arguments",
+                    name
+                )
+            }
+            LapexParsingError::MixedExternalTokens => write!(
+                f,
+                "the grammar declares tokens both with `extern token` and with a pattern"
+            ),
+            LapexParsingError::DuplicateTokenId(id) => {
+                write!(f, "the token id {} is declared more than once", id)
+            }
+            LapexParsingError::InvalidTokenId { .. } => {
+                write!(f, "the token id does not fit into 32 bits")
+            }
+            LapexParsingError::UnsupportedVersion { version, .. } => {
+                write!(
+                    f,
+                    "the grammar is written for lapex {}, which is newer than this lapex {}",
+                    version,
+                    GrammarVersion::current()
+                )
+            }
+            LapexParsingError::InvalidKeyword { keyword, .. } => {
+                write!(
+                    f,
+                    "the keyword {} may only contain letters, digits and underscores",
+                    keyword
+                )
+            }
+            LapexParsingError::InvalidCharacter { .. } => {
+                write!(
+                    f,
+                    "no token of the grammar syntax starts with this character"
+                )
+            }
+            LapexParsingError::UnexpectedToken {
+                found, expected, ..
+            } => write!(f, "found {}, expected {}", found, expected.join(" or ")),
+            LapexParsingError::InvalidRegex { token, reason, .. } => {
+                write!(f, "the regex of {} can not be used, {}", token, reason)
+            }
+            LapexParsingError::Multiple(errors) => {
+                let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+                write!(f, "{}", messages.join("\n"))
+            }
+        }
+    }
+}
+
+/// Replaces every `{name}` in `regex` for which `lookup` returns a replacement, the others are
+/// kept. Escaped braces, property classes like `\p{L}` and counted repetitions like `{2,3}` are
+/// left as they are.
+pub fn replace_references<'r, E>(
+    regex: &str,
+    mut lookup: impl FnMut(&str) -> Result<Option<&'r str>, E>,
+) -> Result<String, E> {
+    let mut replaced = String::with_capacity(regex.len());
+    let mut rest = regex;
+    while let Some(i) = rest.find(['\\', '{']) {
+        replaced.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if rest.starts_with('\\') {
+            let mut escaped = rest[1..].chars().next().map_or(0, char::len_utf8);
+            // the braces of a property class like `\p{Greek}` are a part of the escape
+            if rest[1..].starts_with(['p', 'P']) && rest[2..].starts_with('{') {
+                escaped = rest[2..].find('}').map_or(escaped, |end| end + 2);
+            }
+            replaced.push_str(&rest[..1 + escaped]);
+            rest = &rest[1 + escaped..];
+            continue;
+        }
+        let name = rest[1..]
+            .find('}')
+            .map(|end| &rest[1..1 + end])
+            .filter(|name| name.starts_with(|c: char| c.is_ascii_alphabetic()));
+        match name.map(&mut lookup).transpose()?.flatten() {
+            Some(replacement) => {
+                replaced.push_str(replacement);
+                rest = &rest[name.unwrap().len() + 2..];
+            }
+            None => {
+                replaced.push('{');
+                rest = &rest[1..];
+            }
+        }
     }
+    replaced.push_str(rest);
+    Ok(replaced)
+}
+
+/// Replaces every `{name}` in `regex` with the bracket expression of the class `name`. Inside of
+/// a bracket expression, the class is nested as another one, which the regex syntax unites with
+/// it.
+pub fn expand_class_references(
+    regex: &str,
+    class_rules: &[Spanned<ClassRule>],
+) -> Result<String, LapexParsingError> {
+    replace_references(regex, |name| {
+        class_rules
+            .iter()
+            .find(|class| class.inner.name == name)
+            .map(|class| Some(class.inner.characters))
+            .ok_or_else(|| LapexParsingError::UnknownCharacterClass(name.to_string()))
+    })
 }
 
 pub trait LapexInputParser {
     fn parse_lapex<'src>(&self, source: &'src str) -> Result<RuleSet<'src>, LapexParsingError>;
 }
+
+#[cfg(test)]
+mod tests;
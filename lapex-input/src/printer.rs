@@ -0,0 +1,273 @@
+use std::fmt::Write;
+
+use crate::{
+    Characters, ConflictKind, Pattern, ProductionPattern, RuleSet, Spanned, TokenPattern, TokenRule,
+};
+
+/// How tightly the surrounding pattern binds, a pattern binding looser is put in parentheses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Binding {
+    Alternative,
+    Sequence,
+    Postfix,
+}
+
+/// Writes the rules as grammar text, which parses into the same rules. Tokens declared by a
+/// macro, a class or a keyword set are written as single token rules with the expanded regex.
+pub fn to_lapex_source(rules: &RuleSet) -> String {
+    let mut source = String::new();
+    if let Some(version) = &rules.version {
+        writeln!(source, "lapex {};", version.inner).unwrap();
+    }
+    if let Some(prefix) = rules.symbol_prefix {
+        writeln!(source, "prefix {};", prefix).unwrap();
+    }
+    if let Some(strategy) = rules.precedence_strategy {
+        writeln!(source, "precedence {};", strategy).unwrap();
+    }
+    for class in &rules.class_rules {
+        writeln!(
+            source,
+            "class {} = /{}/;",
+            class.inner.name, class.inner.characters
+        )
+        .unwrap();
+    }
+    for token in in_source_order(&rules.token_rules) {
+        write_doc(&mut source, token.inner.doc.as_deref());
+        writeln!(source, "{}", token_rule_source(&token.inner)).unwrap();
+    }
+    for insertion in &rules.insertion_rules {
+        writeln!(
+            source,
+            "insert {}[{}] = {};",
+            insertion.inner.token,
+            insertion.inner.trigger,
+            insertion.inner.after.join(" | ")
+        )
+        .unwrap();
+    }
+    if !rules.sync_tokens.is_empty() {
+        let names: Vec<&str> = rules.sync_tokens.iter().map(|token| token.inner).collect();
+        writeln!(source, "sync {};", names.join(" | ")).unwrap();
+    }
+    if let Some(entry_rule) = &rules.entry_rule {
+        writeln!(source, "\nentry {};", entry_rule.inner.name).unwrap();
+    }
+    for production in in_source_order(&rules.production_rules) {
+        let production = &production.inner;
+        write_doc(&mut source, production.doc.as_deref());
+        // an annotation of the whole pattern is written in front of the production
+        let pattern = match &production.pattern {
+            ProductionPattern::Annotated { allow, inner } => {
+                write!(source, "{} ", annotation_source(allow)).unwrap();
+                inner
+            }
+            pattern => pattern,
+        };
+        write!(source, "prod {}", production.name).unwrap();
+        if let Some(tag) = production.tag {
+            write!(source, "[{}]", tag).unwrap();
+        }
+        let pattern = match pattern {
+            ProductionPattern::Epsilon => String::from("!"),
+            pattern => production_source(pattern, Binding::Alternative),
+        };
+        // a trailing empty alternative is separated from the semicolon like in `a | ;`
+        let separator = if pattern.ends_with('|') { " " } else { "" };
+        writeln!(source, " = {}{};", pattern, separator).unwrap();
+    }
+    source
+}
+
+fn write_doc(source: &mut String, doc: Option<&str>) {
+    for line in doc.into_iter().flat_map(str::lines) {
+        if line.is_empty() {
+            writeln!(source, "///").unwrap();
+        } else {
+            writeln!(source, "/// {}", line).unwrap();
+        }
+    }
+}
+
+// the parser collects the rules in reverse, rules built without a position keep their order
+fn in_source_order<T>(rules: &[Spanned<T>]) -> Vec<&Spanned<T>> {
+    let mut ordered: Vec<&Spanned<T>> = rules.iter().collect();
+    ordered.sort_by_key(|rule| rule.span);
+    ordered
+}
+
+fn token_rule_source(token: &TokenRule) -> String {
+    let keyword = if token.skip {
+        "skip token"
+    } else if token.external_id().is_some() {
+        "extern token"
+    } else {
+        "token"
+    };
+    let mut source = format!("{} {}", keyword, token.name);
+    if let Some(precedence) = token.precedence {
+        write!(source, "[{}]", precedence).unwrap();
+    }
+    match &token.pattern {
+        // the characters of a literal are kept as they are written, escapes included
+        TokenPattern::Literal { characters } => {
+            write!(source, " = \"{}\";", characters.iter().collect::<String>()).unwrap()
+        }
+        TokenPattern::Pattern { pattern } => write!(
+            source,
+            " = /{}/;",
+            regex_source(pattern, Binding::Alternative)
+        )
+        .unwrap(),
+        TokenPattern::External { id } => write!(source, " = {};", id).unwrap(),
+    }
+    source
+}
+
+fn production_source(pattern: &ProductionPattern, binding: Binding) -> String {
+    match pattern {
+        ProductionPattern::Alternative { elements } => {
+            // an empty alternative is written as nothing between the pipes
+            let mut source = String::new();
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    source.push_str(" |");
+                }
+                if *element != ProductionPattern::Epsilon {
+                    if i > 0 {
+                        source.push(' ');
+                    }
+                    source.push_str(&production_source(element, Binding::Sequence));
+                }
+            }
+            parenthesize(
+                source.trim_start().to_string(),
+                binding > Binding::Alternative,
+            )
+        }
+        ProductionPattern::Sequence { elements } => {
+            let source: Vec<String> = elements
+                .iter()
+                .filter(|element| **element != ProductionPattern::Epsilon)
+                .map(|element| production_source(element, Binding::Postfix))
+                .collect();
+            parenthesize(source.join(" "), binding > Binding::Sequence)
+        }
+        ProductionPattern::OneOrMany { inner } => {
+            format!("{}+", production_source(inner, Binding::Postfix))
+        }
+        ProductionPattern::ZeroOrMany { inner } => {
+            format!("{}*", production_source(inner, Binding::Postfix))
+        }
+        ProductionPattern::Optional { inner } => {
+            format!("{}?", production_source(inner, Binding::Postfix))
+        }
+        ProductionPattern::Group { inner } => {
+            format!("({})", production_source(inner, Binding::Alternative))
+        }
+        // the annotation starts an alternative
+        ProductionPattern::Annotated { allow, inner } => parenthesize(
+            format!(
+                "{} {}",
+                annotation_source(allow),
+                production_source(inner, Binding::Sequence)
+            ),
+            binding > Binding::Sequence,
+        ),
+        ProductionPattern::Rule { rule_name, .. } => rule_name.to_string(),
+        ProductionPattern::Epsilon => String::new(),
+    }
+}
+
+fn annotation_source(allow: &[ConflictKind]) -> String {
+    let names: Vec<&str> = allow.iter().map(|kind| kind.name()).collect();
+    format!("@allow({})", names.join(", "))
+}
+
+fn regex_source(pattern: &Pattern, binding: Binding) -> String {
+    match pattern {
+        Pattern::Alternative { elements } => {
+            let source: Vec<String> = elements
+                .iter()
+                .map(|element| regex_source(element, Binding::Alternative))
+                .collect();
+            parenthesize(source.join("|"), binding > Binding::Alternative)
+        }
+        Pattern::Sequence { elements } if elements.len() == 1 => {
+            regex_source(&elements[0], binding)
+        }
+        Pattern::Sequence { elements } => {
+            let source: String = elements
+                .iter()
+                .map(|element| regex_source(element, Binding::Sequence))
+                .collect();
+            parenthesize(source, binding == Binding::Postfix)
+        }
+        Pattern::Repetition {
+            min,
+            max,
+            inner,
+            greedy,
+        } => {
+            let mut source = regex_source(inner, Binding::Postfix);
+            match (min, max) {
+                (0, None) => source.push('*'),
+                (1, None) => source.push('+'),
+                (0, Some(1)) => source.push('?'),
+                (min, None) => write!(source, "{{{},}}", min).unwrap(),
+                (min, Some(max)) if min == max => write!(source, "{{{}}}", min).unwrap(),
+                (min, Some(max)) => write!(source, "{{{},{}}}", min, max).unwrap(),
+            }
+            if !greedy {
+                source.push('?');
+            }
+            // a repetition of a repetition would read as a lazy or possessive one
+            parenthesize(source, binding == Binding::Postfix)
+        }
+        Pattern::CharSet { chars, negated } => {
+            let mut source = String::from(if *negated { "[^" } else { "[" });
+            for characters in chars {
+                source.push_str(&characters_source(characters));
+            }
+            source.push(']');
+            source
+        }
+        Pattern::Char {
+            chars: Characters::Single(c),
+        } => escape_regex_char(*c),
+        Pattern::Char { chars } => format!("[{}]", characters_source(chars)),
+    }
+}
+
+fn characters_source(characters: &Characters) -> String {
+    match characters {
+        Characters::Single(c) => escape_regex_char(*c),
+        Characters::Range(start, end) if start == end => escape_regex_char(*start),
+        Characters::Range(start, end) => {
+            format!("{}-{}", escape_regex_char(*start), escape_regex_char(*end))
+        }
+    }
+}
+
+// the slash ends the regex and a brace would start a class reference, so both are escaped
+fn escape_regex_char(c: char) -> String {
+    match c {
+        '\n' => String::from("\\n"),
+        '\r' => String::from("\\r"),
+        '\t' => String::from("\\t"),
+        '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '|' | '[' | ']' | '{' | '}' | '^' | '$'
+        | '#' | '&' | '-' | '~' | '/' => format!("\\{}", c),
+        // without braces, which would read as a class reference
+        c if c.is_control() => format!("\\x{:02X}", c as u32),
+        c => c.to_string(),
+    }
+}
+
+fn parenthesize(source: String, parenthesized: bool) -> String {
+    if parenthesized {
+        format!("({})", source)
+    } else {
+        source
+    }
+}
@@ -0,0 +1,249 @@
+use crate::{
+    expand_class_references, str_display_width, to_lapex_source, Characters, ClassRule, EntryRule,
+    GrammarVersion, InsertionRule, LapexParsingError, Pattern, ProductionPattern, ProductionRule,
+    RuleSet, SourcePos, SourceSpan, Spanned, TokenPattern, TokenRule,
+};
+
+fn span_at(line: u16, col: u16) -> SourceSpan {
+    SourceSpan {
+        start: SourcePos { line, col },
+        end: SourcePos { line, col: col + 1 },
+    }
+}
+
+#[test]
+fn test_advance_counts_chars() {
+    let mut pos = SourcePos { line: 1, col: 1 };
+    pos.advance_str("a\täö");
+    assert_eq!(pos, SourcePos { line: 1, col: 5 });
+    pos.advance_str("\nb");
+    assert_eq!(pos, SourcePos { line: 2, col: 2 });
+}
+
+#[test]
+fn test_advance_line_endings() {
+    for text in ["a\r\nb", "a\rb", "a\nb"] {
+        let mut pos = SourcePos { line: 1, col: 1 };
+        pos.advance_str(text);
+        assert_eq!(pos, SourcePos { line: 2, col: 2 }, "{:?}", text);
+    }
+    let mut pos = SourcePos { line: 1, col: 1 };
+    pos.advance_str("a\r\n\r\rb\n\r\nc");
+    assert_eq!(pos, SourcePos { line: 6, col: 2 });
+
+    // a `\r\n` split between two ranges is still one line break
+    let text = "a\r\nb";
+    let mut pos = SourcePos { line: 1, col: 1 };
+    pos.advance_range(text, 0..2);
+    assert_eq!(pos, SourcePos { line: 2, col: 1 });
+    pos.advance_range(text, 2..4);
+    assert_eq!(pos, SourcePos { line: 2, col: 2 });
+}
+
+#[test]
+fn test_substring_line_endings() {
+    let text = "prod a = b;\r\nprod c = d;\rprod e = f;\n";
+    let line = |line| SourceSpan {
+        start: SourcePos { line, col: 1 },
+        end: SourcePos { line, col: 12 },
+    };
+    assert_eq!(line(1).substring(text), Some("prod a = b;"));
+    assert_eq!(line(2).substring(text), Some("prod c = d;"));
+    assert_eq!(line(3).substring(text), Some("prod e = f;"));
+    assert_eq!(SourcePos { line: 2, col: 6 }.display_col(text, 4), Some(6));
+}
+
+#[test]
+fn test_substring_multibyte() {
+    let text = "prod ä = b;\n\tprod c = d;";
+    let span = SourceSpan {
+        start: SourcePos { line: 1, col: 6 },
+        end: SourcePos { line: 1, col: 7 },
+    };
+    assert_eq!(span.substring(text), Some("ä"));
+    let span = SourceSpan {
+        start: SourcePos { line: 2, col: 2 },
+        end: SourcePos { line: 2, col: 6 },
+    };
+    assert_eq!(span.substring(text), Some("prod"));
+}
+
+#[test]
+fn test_display_col_tabs() {
+    let text = "a\tb\n\t\tc";
+    assert_eq!(SourcePos { line: 1, col: 3 }.display_col(text, 4), Some(5));
+    assert_eq!(SourcePos { line: 1, col: 3 }.display_col(text, 8), Some(9));
+    assert_eq!(SourcePos { line: 2, col: 3 }.display_col(text, 4), Some(9));
+    assert_eq!(SourcePos { line: 3, col: 1 }.display_col(text, 4), None);
+}
+
+#[test]
+fn test_display_width_wide_chars() {
+    assert_eq!(str_display_width("日本", 1, 4), 4);
+    assert_eq!(str_display_width("\t", 3, 4), 2);
+    assert_eq!(str_display_width("\t", 5, 4), 4);
+}
+
+#[test]
+fn test_symbol_references() {
+    let rule_set = RuleSet::new(
+        Spanned::new(span_at(1, 1), EntryRule { name: "a" }),
+        Vec::new(),
+        vec![
+            Spanned::zero(ProductionRule {
+                name: "a",
+                tag: None,
+                pattern: ProductionPattern::Sequence {
+                    elements: vec![
+                        ProductionPattern::Rule {
+                            rule_name: "b",
+                            span: span_at(2, 10),
+                        },
+                        ProductionPattern::ZeroOrMany {
+                            inner: Box::new(ProductionPattern::Rule {
+                                rule_name: "b",
+                                span: span_at(2, 13),
+                            }),
+                        },
+                    ],
+                },
+                doc: None,
+            }),
+            Spanned::zero(ProductionRule {
+                name: "b",
+                tag: None,
+                pattern: ProductionPattern::Rule {
+                    rule_name: "a",
+                    span: span_at(3, 10),
+                },
+                doc: None,
+            }),
+        ],
+    );
+    assert_eq!(
+        rule_set.symbol_references("b"),
+        vec![span_at(2, 10), span_at(2, 13)]
+    );
+    assert_eq!(
+        rule_set.symbol_references("a"),
+        vec![span_at(1, 1), span_at(3, 10)]
+    );
+    assert!(rule_set.symbol_references("c").is_empty());
+    assert!(rule_set.defines_symbol("b"));
+    assert!(!rule_set.defines_symbol("c"));
+
+    let rule_set = rule_set.with_insertion_rules(vec![Spanned::new(
+        span_at(4, 1),
+        InsertionRule {
+            token: "c",
+            trigger: "d",
+            after: vec!["b"],
+        },
+    )]);
+    assert_eq!(
+        rule_set.symbol_references("b"),
+        vec![span_at(2, 10), span_at(2, 13), span_at(4, 1)]
+    );
+}
+
+#[test]
+fn test_expand_class_references() {
+    let classes = vec![
+        Spanned::zero(ClassRule {
+            name: "hexdigit",
+            characters: "[0-9a-fA-F]",
+        }),
+        Spanned::zero(ClassRule {
+            name: "sign",
+            characters: "[+-]",
+        }),
+    ];
+    assert_eq!(
+        expand_class_references("/{sign}?0x{hexdigit}+/", &classes).unwrap(),
+        "/[+-]?0x[0-9a-fA-F]+/"
+    );
+    assert_eq!(
+        expand_class_references("/[{hexdigit}_]/", &classes).unwrap(),
+        "/[[0-9a-fA-F]_]/"
+    );
+    assert_eq!(
+        expand_class_references("/a{2,3}\\{sign}/", &classes).unwrap(),
+        "/a{2,3}\\{sign}/"
+    );
+    assert_eq!(
+        expand_class_references("/\\p{L}{hexdigit}\\P{Greek}/", &classes).unwrap(),
+        "/\\p{L}[0-9a-fA-F]\\P{Greek}/"
+    );
+    assert!(matches!(
+        expand_class_references("/{digit}/", &classes),
+        Err(LapexParsingError::UnknownCharacterClass(name)) if name == "digit"
+    ));
+}
+
+#[test]
+fn test_print_built_rules() {
+    let rule = |rule_name| ProductionPattern::Rule {
+        rule_name,
+        span: SourceSpan::zero(),
+    };
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "list" }),
+        vec![Spanned::zero(TokenRule {
+            name: "NUM".into(),
+            precedence: Some(2),
+            pattern: TokenPattern::Pattern {
+                pattern: Pattern::Repetition {
+                    min: 1,
+                    max: None,
+                    inner: Box::new(Pattern::CharSet {
+                        chars: vec![Characters::Range('0', '9'), Characters::Single('/')],
+                        negated: false,
+                    }),
+                    greedy: true,
+                },
+            },
+            skip: false,
+            doc: None,
+        })],
+        vec![
+            Spanned::zero(ProductionRule {
+                name: "list",
+                tag: None,
+                pattern: ProductionPattern::ZeroOrMany {
+                    inner: Box::new(ProductionPattern::Alternative {
+                        elements: vec![
+                            ProductionPattern::Sequence {
+                                elements: vec![rule("NUM"), rule("list")],
+                            },
+                            ProductionPattern::Epsilon,
+                        ],
+                    }),
+                },
+                doc: None,
+            }),
+            Spanned::zero(ProductionRule {
+                name: "empty",
+                tag: Some("none"),
+                pattern: ProductionPattern::Epsilon,
+                doc: None,
+            }),
+        ],
+    );
+    assert_eq!(
+        to_lapex_source(&rule_set),
+        "token NUM[2] = /[0-9\\/]+/;\n\nentry list;\nprod list = (NUM list |)*;\nprod empty[none] = !;\n"
+    );
+}
+
+#[test]
+fn test_grammar_version_compatibility() {
+    assert_eq!(
+        GrammarVersion::parse("0.1.0"),
+        Some(GrammarVersion { major: 0, minor: 1 })
+    );
+    assert!(!GrammarVersion { major: 0, minor: 1 }
+        .is_compatible(&GrammarVersion { major: 0, minor: 2 }));
+    assert!(
+        GrammarVersion { major: 1, minor: 1 }.is_compatible(&GrammarVersion { major: 1, minor: 2 })
+    );
+}
@@ -1,29 +1,94 @@
-use std::{collections::BTreeSet, ops::RangeInclusive};
+use std::{collections::BTreeSet, fmt::Display, ops::RangeInclusive};
 
 use lapex_input::{Characters, Pattern, Spanned, TokenPattern, TokenRule};
 
-#[derive(Debug)]
+/// A partition of code points into ranges, the character classes the transitions of a lexer
+/// DFA are labelled with. Every character a token distinguishes starts or ends a range, so all
+/// characters of a range are treated alike. With the `serde` feature, it is (de)serialized as the
+/// list of its ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        into = "Vec<RangeInclusive<u32>>",
+        try_from = "Vec<RangeInclusive<u32>>"
+    )
+)]
 pub struct Alphabet {
     ranges: Vec<RangeInclusive<u32>>,
 }
 
+/// The range at `index` is empty, or does not start after the previous one ends.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AlphabetError {
+    pub index: usize,
+}
+
+impl Display for AlphabetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "range {} of the alphabet is empty or overlaps the previous one",
+            self.index
+        )
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
 impl Alphabet {
+    /// Partitions all code points so that every single character and the first and last
+    /// character of every range start or end a class, like `generate_alphabet` does for the
+    /// characters of the tokens. Class 0 only holds `'\0'`.
+    pub fn from_characters(characters: &[Characters]) -> Self {
+        let mut chars = BTreeSet::new();
+        for characters in characters {
+            insert_characters(&mut chars, characters);
+        }
+        Alphabet::from_boundaries(chars)
+    }
+
+    fn from_boundaries(mut chars: BTreeSet<char>) -> Self {
+        chars.insert('\0');
+        chars.insert(char::MAX);
+
+        let mut ranges = Vec::new();
+        let mut chars_iter = chars.iter();
+        let mut prev = chars_iter.next().unwrap();
+        ranges.push(RangeInclusive::new(*prev as u32, *prev as u32));
+        for ch in chars_iter {
+            if *ch as u32 - *prev as u32 > 1 {
+                ranges.push(RangeInclusive::new(*prev as u32 + 1, *ch as u32 - 1));
+            }
+            ranges.push(RangeInclusive::new(*ch as u32, *ch as u32));
+            prev = ch;
+        }
+        Alphabet { ranges }
+    }
+
+    /// The class of the character, or `None` if no range contains it.
+    pub fn classify(&self, ch: char) -> Option<usize> {
+        self.find_range(ch as u32)
+    }
+
     pub fn find_range(&self, ch: u32) -> Option<usize> {
         let search_result = self
             .ranges
             .binary_search_by_key(&ch, |range| *range.start());
         match search_result {
             Ok(index) => Some(index),
-            Err(index) => {
-                if self.ranges[index - 1].contains(&ch) {
-                    Some(index - 1)
-                } else {
-                    None
-                }
-            }
+            Err(index) => index
+                .checked_sub(1)
+                .filter(|index| self.ranges[*index].contains(&ch)),
         }
     }
 
+    /// The ranges of code points, ordered by their start. The index of a range is its class.
+    pub fn ranges(&self) -> &[RangeInclusive<u32>] {
+        &self.ranges
+    }
+
     pub fn into_ranges(self) -> Vec<RangeInclusive<u32>> {
         self.ranges
     }
@@ -33,6 +98,40 @@ impl Alphabet {
     }
 }
 
+impl From<Alphabet> for Vec<RangeInclusive<u32>> {
+    fn from(alphabet: Alphabet) -> Self {
+        alphabet.ranges
+    }
+}
+
+impl TryFrom<Vec<RangeInclusive<u32>>> for Alphabet {
+    type Error = AlphabetError;
+
+    /// Accepts ranges which are not empty and ordered without overlapping. They do not have to
+    /// cover all code points, the others are not classified.
+    fn try_from(ranges: Vec<RangeInclusive<u32>>) -> Result<Self, Self::Error> {
+        for (index, range) in ranges.iter().enumerate() {
+            let after_previous = index == 0 || ranges[index - 1].end() < range.start();
+            if range.is_empty() || !after_previous {
+                return Err(AlphabetError { index });
+            }
+        }
+        Ok(Alphabet { ranges })
+    }
+}
+
+fn insert_characters(chars: &mut BTreeSet<char>, characters: &Characters) {
+    match characters {
+        Characters::Single(c) => {
+            chars.insert(*c);
+        }
+        Characters::Range(c1, c2) => {
+            chars.insert(*c1);
+            chars.insert(*c2);
+        }
+    }
+}
+
 fn get_chars_from_pattern(chars: &mut BTreeSet<char>, pattern: &Pattern) {
     match pattern {
         Pattern::Sequence { elements } => {
@@ -45,39 +144,20 @@ fn get_chars_from_pattern(chars: &mut BTreeSet<char>, pattern: &Pattern) {
                 get_chars_from_pattern(chars, elem)
             }
         }
-        Pattern::Repetition {
-            min: _,
-            max: _,
-            inner,
-        } => get_chars_from_pattern(chars, inner),
+        Pattern::Repetition { inner, .. } => get_chars_from_pattern(chars, inner),
         Pattern::CharSet {
             chars: ch,
             negated: _,
         } => {
             for ch in ch {
-                match &ch {
-                    Characters::Single(c) => {
-                        chars.insert(*c);
-                    }
-                    Characters::Range(c1, c2) => {
-                        chars.insert(*c1);
-                        chars.insert(*c2);
-                    }
-                }
+                insert_characters(chars, ch);
             }
         }
-        Pattern::Char { chars: ch } => match &ch {
-            Characters::Single(c) => {
-                chars.insert(*c);
-            }
-            Characters::Range(c1, c2) => {
-                chars.insert(*c1);
-                chars.insert(*c2);
-            }
-        },
+        Pattern::Char { chars: ch } => insert_characters(chars, ch),
     }
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(tokens = rules.len())))]
 pub fn generate_alphabet(rules: &[Spanned<TokenRule>]) -> Alphabet {
     let mut chars = BTreeSet::new();
     for rule in rules {
@@ -86,21 +166,11 @@ pub fn generate_alphabet(rules: &[Spanned<TokenRule>]) -> Alphabet {
                 get_chars_from_pattern(&mut chars, &Pattern::from_chars(characters))
             }
             TokenPattern::Pattern { pattern } => get_chars_from_pattern(&mut chars, pattern),
+            TokenPattern::External { .. } => (),
         }
     }
-    chars.insert('\0');
-    chars.insert(char::MAX);
-
-    let mut ranges = Vec::new();
-    let mut chars_iter = chars.iter();
-    let mut prev = chars_iter.next().unwrap();
-    ranges.push(RangeInclusive::new(*prev as u32, *prev as u32));
-    for ch in chars_iter {
-        if *ch as u32 - *prev as u32 > 1 {
-            ranges.push(RangeInclusive::new(*prev as u32 + 1, *ch as u32 - 1));
-        }
-        ranges.push(RangeInclusive::new(*ch as u32, *ch as u32));
-        prev = ch;
-    }
-    Alphabet { ranges }
+    let alphabet = Alphabet::from_boundaries(chars);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(ranges = alphabet.ranges.len(), "built the alphabet");
+    alphabet
 }
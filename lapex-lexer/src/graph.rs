@@ -0,0 +1,69 @@
+use std::ops::RangeInclusive;
+
+use lapex_automaton::{AutomatonState, Nfa};
+use lapex_input::{Spanned, TokenRule};
+
+use crate::{Alphabet, TokenDfa};
+
+/// Writes the NFA of the tokens as a Graphviz graph. The transitions are labeled with the
+/// characters of their class, the accepting states with their token.
+pub fn nfa_to_dot(nfa: &Nfa<&Spanned<TokenRule>, usize>, alphabet: &Alphabet) -> String {
+    nfa.to_dot(
+        |index, state| match state {
+            AutomatonState::Accepting(rule) => format!("{}\n{}", index.index(), rule.inner.name),
+            AutomatonState::Intermediate(_) => index.index().to_string(),
+        },
+        |classes| classes_label(classes, alphabet),
+    )
+}
+
+/// Writes the DFA the lexer is generated from as a Graphviz graph, labeled like the NFA. The
+/// states are numbered like in the generated lexer.
+pub fn dfa_to_dot(dfa: &TokenDfa, alphabet: &Alphabet) -> String {
+    dfa.to_dot(
+        |index, state| match state {
+            AutomatonState::Accepting(rule) => format!("{}\n{}", index.index(), rule.name),
+            AutomatonState::Intermediate(_) => index.index().to_string(),
+        },
+        |classes| classes_label(classes, alphabet),
+    )
+}
+
+// the classes are written like the characters and ranges of a character set in a regex, with
+// adjacent classes merged into one range
+fn classes_label(classes: &[&usize], alphabet: &Alphabet) -> String {
+    let mut ranges: Vec<&RangeInclusive<u32>> = classes
+        .iter()
+        .map(|class| &alphabet.ranges()[**class])
+        .collect();
+    ranges.sort_by_key(|range| range.start());
+    let mut merged: Vec<RangeInclusive<u32>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if *last.end() + 1 == *range.start() => {
+                *last = *last.start()..=*range.end();
+            }
+            _ => merged.push(range.clone()),
+        }
+    }
+    merged
+        .iter()
+        .map(|range| {
+            if range.start() == range.end() {
+                show_char(*range.start())
+            } else {
+                format!("{}-{}", show_char(*range.start()), show_char(*range.end()))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn show_char(code: u32) -> String {
+    match char::from_u32(code) {
+        // a space would be invisible at the end of a label
+        Some(' ') => String::from("\\u{20}"),
+        Some(ch) => ch.escape_debug().to_string(),
+        None => format!("\\u{{{:x}}}", code),
+    }
+}
@@ -1,14 +1,18 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 pub use codegen::*;
 
 mod alphabet;
 mod codegen;
+mod graph;
 mod nfa;
-pub use alphabet::generate_alphabet;
+mod scan;
+pub use alphabet::{generate_alphabet, Alphabet, AlphabetError};
+pub use graph::{dfa_to_dot, nfa_to_dot};
 use lapex_automaton::{AutomatonState, Dfa};
-use lapex_input::{Spanned, TokenRule};
+use lapex_input::{PrecedenceModel, PrecedenceStrategy, Spanned, TokenRule};
 pub use nfa::generate_nfa;
+pub use scan::{scan_token_spans, scan_tokens};
 
 #[derive(Debug)]
 pub struct PrecedenceError {
@@ -17,41 +21,123 @@ pub struct PrecedenceError {
 }
 
 fn resolve_precedence<'rules>(
-    rules: &Vec<&'rules Spanned<TokenRule<'rules>>>,
+    rules: &[&'rules Spanned<TokenRule<'rules>>],
+    strategy: PrecedenceStrategy,
+    model: &dyn PrecedenceModel,
 ) -> Result<&'rules TokenRule<'rules>, PrecedenceError> {
     assert!(!rules.is_empty());
-    let mut sorted_rules: Vec<(&Spanned<TokenRule>, usize)> =
-        rules.iter().map(|r| (*r, r.inner.precedence())).collect();
-    sorted_rules.sort_by_key(|r| std::cmp::Reverse(r.1));
-    let highest_precedence = sorted_rules[0].1;
-    let rules_with_matching_prec: Vec<&Spanned<TokenRule>> = sorted_rules
+    if rules.len() == 1 {
+        return Ok(&rules[0].inner);
+    }
+    match strategy {
+        PrecedenceStrategy::Longest => {
+            highest_unique(rules, |r| r.inner.precedence(model)).map(|r| &r.inner)
+        }
+        PrecedenceStrategy::Declaration => Ok(&rules
+            .iter()
+            .min_by_key(|r| (std::cmp::Reverse(r.inner.precedence), r.span))
+            .unwrap()
+            .inner),
+        PrecedenceStrategy::Explicit => {
+            highest_unique(rules, |r| r.inner.precedence.map(usize::from)).map(|r| &r.inner)
+        }
+    }
+}
+
+/// Picks the rule with the highest precedence, which has to be the only one with it. A rule
+/// without a precedence never wins.
+fn highest_unique<'rules>(
+    rules: &[&'rules Spanned<TokenRule<'rules>>],
+    precedence: impl Fn(&Spanned<TokenRule>) -> Option<usize>,
+) -> Result<&'rules Spanned<TokenRule<'rules>>, PrecedenceError> {
+    let highest_precedence = rules.iter().map(|r| precedence(r)).max().unwrap();
+    let rules_with_matching_prec: Vec<&Spanned<TokenRule>> = rules
         .iter()
-        .filter(|(_r, p)| *p == highest_precedence)
-        .map(|(r, _p)| *r)
+        .filter(|r| precedence(r) == highest_precedence)
+        .copied()
         .collect();
-    if rules_with_matching_prec.len() > 1 {
-        return Err(PrecedenceError {
+    match (highest_precedence, rules_with_matching_prec.as_slice()) {
+        (Some(_), [rule]) => Ok(rule),
+        _ => Err(PrecedenceError {
             rules: rules_with_matching_prec
                 .iter()
                 .map(|r| Spanned::new(r.span, r.inner.name.to_string()))
                 .collect(),
-            precedence: highest_precedence,
-        });
+            precedence: highest_precedence.unwrap_or(0),
+        }),
     }
-    Ok(&rules_with_matching_prec[0].inner)
 }
 
+/// Finds the pairs of tokens matching the same input which the strategy decides between by the
+/// order they are declared in, as their precedences are equal. Swapping the declarations of such
+/// a pair changes which token is lexed. The token declared first, which wins, comes first in each
+/// pair, and the pairs are ordered by where it is declared.
+pub fn find_order_dependent_tokens<'rules>(
+    dfa: &Dfa<Vec<&'rules Spanned<TokenRule<'rules>>>, usize>,
+    strategy: PrecedenceStrategy,
+) -> Vec<[&'rules Spanned<TokenRule<'rules>>; 2]> {
+    // the other strategies fail instead of picking between tokens of equal precedence
+    if strategy != PrecedenceStrategy::Declaration {
+        return Vec::new();
+    }
+    let mut pairs = BTreeMap::new();
+    for (_, state) in dfa.states() {
+        if let AutomatonState::Accepting(accepted) = state {
+            let precedence = accepted.iter().map(|r| r.inner.precedence).max().unwrap();
+            let mut tied: Vec<&Spanned<TokenRule>> = accepted
+                .iter()
+                .filter(|r| r.inner.precedence == precedence)
+                .copied()
+                .collect();
+            tied.sort_by_key(|r| r.span);
+            if let [winner, others @ ..] = tied.as_slice() {
+                for other in others {
+                    pairs.insert((winner.span, other.span), [*winner, *other]);
+                }
+            }
+        }
+    }
+    pairs.into_values().collect()
+}
+
+/// A DFA of the lexer which accepts a single token in each accepting state.
+pub type TokenDfa<'rules> = Dfa<&'rules TokenRule<'rules>, usize>;
+
+/// Resolves the accepting states which match more than one token to a single token, as picked by
+/// the strategy. The longest strategy scores the tokens without an explicit precedence with the
+/// model. The states accepting a lazy token have no transitions, so it is the shortest match
+/// instead of the longest. The states which became equivalent are merged afterwards. Also returns
+/// the tokens with an explicit precedence which never had to be resolved against another token, in
+/// the order they are declared.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(strategy = strategy.name()))
+)]
 pub fn apply_precedence_to_dfa<'rules>(
     dfa: Dfa<Vec<&'rules Spanned<TokenRule<'rules>>>, usize>,
-) -> Result<Dfa<&'rules TokenRule<'rules>, usize>, PrecedenceError> {
+    strategy: PrecedenceStrategy,
+    model: &dyn PrecedenceModel,
+) -> Result<(TokenDfa<'rules>, Vec<&'rules Spanned<TokenRule<'rules>>>), PrecedenceError> {
     let mut resulting_dfa = Dfa::new();
     let mut state_mapping = BTreeMap::new();
+    let mut final_states = BTreeSet::new();
+    let mut explicit_precedences = BTreeMap::new();
+    let mut consulted_precedences = BTreeSet::new();
     for (idx, state) in dfa.states() {
         match state {
             AutomatonState::Accepting(accepted) => {
-                let rule = resolve_precedence(accepted)?;
+                for rule in accepted.iter().filter(|r| r.inner.precedence.is_some()) {
+                    explicit_precedences.insert(&*rule.inner.name, *rule);
+                    if accepted.len() > 1 {
+                        consulted_precedences.insert(&*rule.inner.name);
+                    }
+                }
+                let rule = resolve_precedence(accepted, strategy, model)?;
                 let new_idx = resulting_dfa.add_accepting_state(rule);
                 state_mapping.insert(idx, new_idx);
+                if rule.is_lazy() {
+                    final_states.insert(idx);
+                }
             }
             AutomatonState::Intermediate(_) => {
                 let new_idx = resulting_dfa.add_intermediate_state();
@@ -60,6 +146,9 @@ pub fn apply_precedence_to_dfa<'rules>(
         }
     }
     for (old_idx, new_idx) in &state_mapping {
+        if final_states.contains(old_idx) {
+            continue;
+        }
         for (weight, old_target_idx) in dfa.transitions_from(*old_idx) {
             resulting_dfa.add_transition(
                 *new_idx,
@@ -68,5 +157,22 @@ pub fn apply_precedence_to_dfa<'rules>(
             );
         }
     }
-    Ok(resulting_dfa)
+    let mut unused_precedences: Vec<&Spanned<TokenRule>> = explicit_precedences
+        .into_iter()
+        .filter(|(name, _)| !consulted_precedences.contains(name))
+        .map(|(_, rule)| rule)
+        .collect();
+    unused_precedences.sort_by_key(|rule| rule.span);
+    // the tokens are compared by identity, different rules may have the same pattern
+    let resulting_dfa = resulting_dfa.minimize_by_key(|rule| *rule as *const TokenRule);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        states = resulting_dfa.states().count(),
+        unused_precedences = unused_precedences.len(),
+        "resolved the precedences of the DFA"
+    );
+    Ok((resulting_dfa, unused_precedences))
 }
+
+#[cfg(test)]
+mod tests;
@@ -73,7 +73,9 @@ fn build_nfa_from_pattern<'rules>(
                 nfa.add_epsilon_transition(inner_end, end);
             }
         }
-        Pattern::Repetition { min, max, inner } => {
+        Pattern::Repetition {
+            min, max, inner, ..
+        } => {
             let inner_start = nfa.add_intermediate_state();
             let inner_end = nfa.add_intermediate_state();
             nfa.add_epsilon_transition(start, inner_start);
@@ -177,6 +179,7 @@ fn build_nfa_from_pattern<'rules>(
     Some(())
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(tokens = rules.len())))]
 pub fn generate_nfa<'rules>(
     alphabet: &Alphabet,
     rules: &'rules [Spanned<TokenRule>],
@@ -185,6 +188,10 @@ pub fn generate_nfa<'rules>(
 
     let start = nfa.add_intermediate_state();
     for rule in rules {
+        // read by a lexer of its own, so no input leads to it
+        if rule.inner.external_id().is_some() {
+            continue;
+        }
         let rule_start = nfa.add_intermediate_state();
         let rule_end = nfa.add_accepting_state(&rule);
         nfa.add_epsilon_transition(start, rule_start);
@@ -199,8 +206,11 @@ pub fn generate_nfa<'rules>(
             TokenPattern::Pattern { pattern } => {
                 build_nfa_from_pattern(rule_start, rule_end, alphabet, &mut nfa, pattern)
             }
+            TokenPattern::External { .. } => unreachable!("external tokens are skipped"),
         };
     }
+    #[cfg(feature = "tracing")]
+    tracing::debug!(states = nfa.graph().node_count(), "built the NFA");
     (start, nfa)
 }
 
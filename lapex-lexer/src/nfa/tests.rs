@@ -5,7 +5,7 @@ use crate::{generate_alphabet, generate_nfa};
 #[test]
 fn test_repetition_option() {
     let rules = [Spanned::zero(TokenRule {
-        name: "test",
+        name: "test".into(),
         precedence: None,
         pattern: TokenPattern::Pattern {
             pattern: Pattern::Repetition {
@@ -14,8 +14,11 @@ fn test_repetition_option() {
                 inner: Box::new(Pattern::Char {
                     chars: Characters::Single('a'),
                 }),
+                greedy: true,
             },
         },
+        skip: false,
+        doc: None,
     })];
     let alphabet = generate_alphabet(&rules);
     let (_entry, nfa) = generate_nfa(&alphabet, &rules);
@@ -26,7 +29,7 @@ fn test_repetition_option() {
 #[test]
 fn test_repetition_bounded() {
     let rules = [Spanned::zero(TokenRule {
-        name: "test",
+        name: "test".into(),
         precedence: None,
         pattern: TokenPattern::Pattern {
             pattern: Pattern::Repetition {
@@ -35,8 +38,11 @@ fn test_repetition_bounded() {
                 inner: Box::new(Pattern::Char {
                     chars: Characters::Single('a'),
                 }),
+                greedy: true,
             },
         },
+        skip: false,
+        doc: None,
     })];
     let alphabet = generate_alphabet(&rules);
     let (_entry, nfa) = generate_nfa(&alphabet, &rules);
@@ -47,7 +53,7 @@ fn test_repetition_bounded() {
 #[test]
 fn test_repetition_unbounded() {
     let rules = [Spanned::zero(TokenRule {
-        name: "test",
+        name: "test".into(),
         precedence: None,
         pattern: TokenPattern::Pattern {
             pattern: Pattern::Repetition {
@@ -56,8 +62,11 @@ fn test_repetition_unbounded() {
                 inner: Box::new(Pattern::Char {
                     chars: Characters::Single('a'),
                 }),
+                greedy: true,
             },
         },
+        skip: false,
+        doc: None,
     })];
     let alphabet = generate_alphabet(&rules);
     let (_entry, nfa) = generate_nfa(&alphabet, &rules);
@@ -68,7 +77,7 @@ fn test_repetition_unbounded() {
 #[test]
 fn test_repetition_lower_bounded() {
     let rules = [Spanned::zero(TokenRule {
-        name: "test",
+        name: "test".into(),
         precedence: None,
         pattern: TokenPattern::Pattern {
             pattern: Pattern::Repetition {
@@ -77,8 +86,11 @@ fn test_repetition_lower_bounded() {
                 inner: Box::new(Pattern::Char {
                     chars: Characters::Single('a'),
                 }),
+                greedy: true,
             },
         },
+        skip: false,
+        doc: None,
     })];
     let alphabet = generate_alphabet(&rules);
     let (_entry, nfa) = generate_nfa(&alphabet, &rules);
@@ -0,0 +1,54 @@
+use std::ops::Range;
+
+use lapex_automaton::{AutomatonState, Dfa, StateId};
+use lapex_input::TokenRule;
+
+use crate::alphabet::Alphabet;
+
+/// Splits the input into tokens like the generated lexers do: the longest match wins and
+/// there is no backtracking. On failure, the byte offset of the offending character is returned.
+pub fn scan_tokens<'rules>(
+    dfa: &Dfa<&'rules TokenRule<'rules>, usize>,
+    alphabet: &Alphabet,
+    input: &str,
+) -> Result<Vec<&'rules TokenRule<'rules>>, usize> {
+    let tokens = scan_token_spans(dfa, alphabet, input)?;
+    Ok(tokens.into_iter().map(|(rule, _)| rule).collect())
+}
+
+/// Like `scan_tokens`, but also returns the byte range of the input each token was scanned from.
+pub fn scan_token_spans<'rules>(
+    dfa: &Dfa<&'rules TokenRule<'rules>, usize>,
+    alphabet: &Alphabet,
+    input: &str,
+) -> Result<Vec<(&'rules TokenRule<'rules>, Range<usize>)>, usize> {
+    let start = StateId::new(0);
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    let mut state = start;
+    let mut token_start = 0;
+    loop {
+        let (offset, ch) = chars.peek().copied().unwrap_or((input.len(), '\0'));
+        let class = alphabet.find_range(ch as u32).ok_or(offset)?;
+        // class 0 only holds the end of input marker
+        if class == 0 && state == start {
+            return Ok(tokens);
+        }
+        if state == start {
+            token_start = offset;
+        }
+        let target = dfa
+            .transitions_from(state)
+            .find(|(transition, _)| class != 0 && **transition == class)
+            .map(|(_, target)| target);
+        if let Some(target) = target {
+            chars.next();
+            state = target;
+        } else if let Some(AutomatonState::Accepting(rule)) = dfa.state(state) {
+            tokens.push((*rule, token_start..offset));
+            state = start;
+        } else {
+            return Err(offset);
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::ops::RangeInclusive;
+
+use lapex_input::{
+    Characters, ConservativePrecedenceModel, DefaultPrecedenceModel, LapexInputParser,
+    PrecedenceModel, PrecedenceStrategy, RuleSet, TokenRule,
+};
+
+use crate::{
+    apply_precedence_to_dfa, find_order_dependent_tokens, generate_alphabet, generate_nfa,
+    scan_token_spans, Alphabet, AlphabetError, TokenDfa,
+};
+
+fn parse(source: &str) -> RuleSet<'_> {
+    lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(source)
+        .unwrap()
+}
+
+// the lexer automaton with the default precedence
+fn build_dfa<'rules>(rules: &'rules RuleSet) -> (Alphabet, TokenDfa<'rules>) {
+    let alphabet = generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, _) = apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+    (alphabet, dfa)
+}
+
+#[test]
+fn test_lazy_repetition() {
+    let source = r#"token comment = /\/\*.*?\*\//;
+token line = /#[^\n]*?/;
+token name = /[a-z]+/;
+token ws = /[ \n]+/;
+entry s;
+prod s = (comment | line | name | ws)*;
+"#;
+    let rules = parse(source);
+    let is_lazy = |name: &str| {
+        let rule = rules.token_rules.iter().find(|r| r.inner.name == name);
+        rule.unwrap().inner.is_lazy()
+    };
+    assert!(is_lazy("comment") && is_lazy("line"));
+    assert!(!is_lazy("name") && !is_lazy("ws"));
+    let (alphabet, dfa) = build_dfa(&rules);
+    let input = "/* a */ b /* c */\n# d";
+    let tokens: Vec<(&str, &str)> = scan_token_spans(&dfa, &alphabet, input)
+        .unwrap()
+        .into_iter()
+        .map(|(rule, span)| (&*rule.name, &input[span]))
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            ("comment", "/* a */"),
+            ("ws", " "),
+            ("name", "b"),
+            ("ws", " "),
+            ("comment", "/* c */"),
+            ("ws", "\n"),
+            ("line", "#"),
+            ("ws", " "),
+            ("name", "d"),
+        ]
+    );
+}
+
+#[test]
+fn test_precedence_strategies() {
+    let tokenize = |source: &str, strategy: PrecedenceStrategy, model: &dyn PrecedenceModel| {
+        let rules = parse(source);
+        let alphabet = generate_alphabet(&rules.token_rules);
+        let (nfa_entrypoint, nfa) = generate_nfa(&alphabet, &rules.token_rules);
+        let strategy = rules.precedence_strategy.unwrap_or(strategy);
+        match apply_precedence_to_dfa(nfa.powerset_construction(nfa_entrypoint), strategy, model) {
+            Ok((dfa, _)) => Ok(scan_token_spans(&dfa, &alphabet, "if iff")
+                .unwrap()
+                .into_iter()
+                .map(|(rule, _)| rule.name.to_string())
+                .collect::<Vec<_>>()),
+            Err(e) => Err(e.rules.into_iter().map(|r| r.inner).collect::<Vec<_>>()),
+        }
+    };
+    let source = r#"token name = /[a-z]+/;
+token kw_if = "if";
+token ws = / /;
+entry s;
+prod s = (name | kw_if | ws)*;
+"#;
+    assert_eq!(
+        tokenize(source, PrecedenceStrategy::Longest, &DefaultPrecedenceModel).unwrap(),
+        ["kw_if", "ws", "name"]
+    );
+    assert_eq!(
+        tokenize(
+            source,
+            PrecedenceStrategy::Declaration,
+            &DefaultPrecedenceModel
+        )
+        .unwrap(),
+        ["name", "ws", "name"]
+    );
+    let mut conflicting = tokenize(
+        source,
+        PrecedenceStrategy::Explicit,
+        &DefaultPrecedenceModel,
+    )
+    .unwrap_err();
+    conflicting.sort();
+    assert_eq!(conflicting, ["kw_if", "name"]);
+
+    let annotated = source.replace("token kw_if =", "token kw_if[1] =");
+    assert_eq!(
+        tokenize(
+            &annotated,
+            PrecedenceStrategy::Explicit,
+            &DefaultPrecedenceModel
+        )
+        .unwrap(),
+        ["kw_if", "ws", "name"]
+    );
+    let declared = format!("precedence declaration;\n{}", source);
+    assert_eq!(
+        tokenize(
+            &declared,
+            PrecedenceStrategy::Longest,
+            &DefaultPrecedenceModel
+        )
+        .unwrap(),
+        ["name", "ws", "name"]
+    );
+
+    let source = source.replace("token kw_if", "token word = /[a-z][a-z]+/;\ntoken kw_if");
+    let conservative = &ConservativePrecedenceModel;
+    assert_eq!(
+        tokenize(
+            &source,
+            PrecedenceStrategy::Longest,
+            &DefaultPrecedenceModel
+        )
+        .unwrap(),
+        ["kw_if", "ws", "word"]
+    );
+    let mut conflicting = tokenize(&source, PrecedenceStrategy::Longest, conservative).unwrap_err();
+    conflicting.sort();
+    assert_eq!(conflicting, ["name", "word"]);
+    let annotated = source.replace("token word =", "token word[1] =");
+    assert_eq!(
+        tokenize(&annotated, PrecedenceStrategy::Longest, conservative).unwrap(),
+        ["kw_if", "ws", "word"]
+    );
+}
+
+#[test]
+fn test_token_order_independence() {
+    let lex = |rules: &RuleSet, input: &str| {
+        let alphabet = generate_alphabet(&rules.token_rules);
+        let (nfa_entrypoint, nfa) = generate_nfa(&alphabet, &rules.token_rules);
+        let dfa = nfa.powerset_construction(nfa_entrypoint);
+        let strategy = rules.precedence_strategy.unwrap_or_default();
+        let order_dependent: Vec<[String; 2]> = find_order_dependent_tokens(&dfa, strategy)
+            .into_iter()
+            .map(|pair| pair.map(|rule| rule.inner.name.to_string()))
+            .collect();
+        let (dfa, _) = apply_precedence_to_dfa(dfa, strategy, &DefaultPrecedenceModel).unwrap();
+        let tokens: Vec<String> = scan_token_spans(&dfa, &alphabet, input)
+            .unwrap()
+            .into_iter()
+            .map(|(rule, _)| rule.name.to_string())
+            .collect();
+        (tokens, order_dependent)
+    };
+    // the default strategy does not look at the order of the tokens
+    for template in lapex_grammars::TEMPLATES {
+        let mut rules = parse(template.grammar);
+        let (tokens, order_dependent) = lex(&rules, template.sample);
+        assert_eq!(order_dependent, Vec::<[String; 2]>::new());
+        rules.token_rules.reverse();
+        assert_eq!(lex(&rules, template.sample).0, tokens, "{}", template.name);
+    }
+
+    let source = r#"precedence declaration;
+token kw_if = "if";
+token name = /[a-z]+/;
+token kw_else[1] = "else";
+token ws = / /;
+entry s;
+prod s = (name | kw_if | kw_else | ws)*;
+"#;
+    let (tokens, order_dependent) = lex(&parse(source), "if else");
+    assert_eq!(tokens, ["kw_if", "ws", "kw_else"]);
+    assert_eq!(order_dependent, [["kw_if", "name"]]);
+    // swapping the flagged tokens changes the lexer, the one with a precedence keeps its token
+    let swapped = source.replace(
+        "token kw_if = \"if\";\ntoken name = /[a-z]+/;",
+        "token name = /[a-z]+/;\ntoken kw_if = \"if\";",
+    );
+    let (tokens, order_dependent) = lex(&parse(&swapped), "if else");
+    assert_eq!(tokens, ["name", "ws", "kw_else"]);
+    assert_eq!(order_dependent, [["name", "kw_if"]]);
+}
+
+#[test]
+fn test_dfa_minimization() {
+    let source = r#"token pair = /(ab|cb)x/;
+token num = /[0-9]+|0x[0-9]+/;
+token name = /[a-z]+/;
+entry s;
+prod s = (pair | num | name)*;
+"#;
+    let rules = parse(source);
+    let alphabet = generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = generate_nfa(&alphabet, &rules.token_rules);
+    let powerset = nfa.powerset_construction(nfa_entrypoint);
+    let (dfa, _) = apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+    assert!(dfa.states().count() < powerset.states().count());
+    // minimizing again finds nothing to merge
+    let minimized = dfa.minimize_by_key(|rule| *rule as *const TokenRule);
+    assert_eq!(minimized.states().count(), dfa.states().count());
+
+    let names: Vec<&str> = "abx cbx 0x12 42 abc"
+        .split(' ')
+        .flat_map(|word| scan_token_spans(&dfa, &alphabet, word).unwrap())
+        .map(|(rule, _)| &*rule.name)
+        .collect();
+    assert_eq!(names, ["pair", "pair", "num", "num", "name"]);
+}
+
+#[test]
+fn test_alphabet() {
+    let alphabet = Alphabet::from_characters(&[
+        Characters::Single('_'),
+        Characters::Range('0', '9'),
+        Characters::Range('a', 'z'),
+    ]);
+    assert_eq!(alphabet.classify('\0'), Some(0));
+    assert_eq!(alphabet.classify('1'), alphabet.classify('8'));
+    assert_ne!(alphabet.classify('0'), alphabet.classify('1'));
+    assert_ne!(alphabet.classify('_'), alphabet.classify('a'));
+    assert_eq!(
+        alphabet.classify(char::MAX),
+        Some(alphabet.ranges().len() - 1)
+    );
+    // the classes partition all code points
+    for (previous, range) in alphabet.ranges().iter().zip(&alphabet.ranges()[1..]) {
+        assert_eq!(previous.end() + 1, *range.start());
+    }
+
+    let ranges: Vec<RangeInclusive<u32>> = alphabet.clone().into();
+    assert_eq!(Alphabet::try_from(ranges), Ok(alphabet));
+    let sparse = Alphabet::try_from(vec![10..=20, 30..=30]).unwrap();
+    assert_eq!(sparse.find_range(5), None);
+    assert_eq!(sparse.find_range(25), None);
+    assert_eq!(sparse.find_range(30), Some(1));
+    assert_eq!(
+        Alphabet::try_from(vec![0..=5, 5..=6]),
+        Err(AlphabetError { index: 1 })
+    );
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = vec![3..=2];
+    assert_eq!(Alphabet::try_from(empty), Err(AlphabetError { index: 0 }));
+}
+
+#[test]
+fn test_unicode_class_escapes() {
+    let source = r#"class underscore = /[_]/;
+token ident = /(\p{L}|{underscore})[\w]*/;
+token number = /\d+/;
+token ws = /\s+/;
+token other = /[^\s\w]/;
+entry s;
+prod s = (ident | number | ws | other)*;
+"#;
+    let rules = parse(source);
+    let (alphabet, dfa) = build_dfa(&rules);
+    let input = "größe\u{00A0}_x1 = λ٣ + 42;";
+    let tokens: Vec<(&str, &str)> = scan_token_spans(&dfa, &alphabet, input)
+        .unwrap()
+        .into_iter()
+        .map(|(rule, span)| (&*rule.name, &input[span]))
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            ("ident", "größe"),
+            ("ws", "\u{00A0}"),
+            ("ident", "_x1"),
+            ("ws", " "),
+            ("other", "="),
+            ("ws", " "),
+            ("ident", "λ٣"),
+            ("ws", " "),
+            ("other", "+"),
+            ("ws", " "),
+            ("number", "42"),
+            ("other", ";"),
+        ]
+    );
+}
@@ -0,0 +1,193 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::grammar::{Grammar, Symbol};
+
+pub fn get_first_terminals_of_sequence(
+    sequence: &[Symbol],
+    first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+) -> BTreeSet<Symbol> {
+    let epsilon_first_set = {
+        let mut new_set = BTreeSet::new();
+        new_set.insert(Symbol::Epsilon);
+        new_set
+    };
+
+    let mut result_set = BTreeSet::new();
+    for i in 0..sequence.len() {
+        let symbol = sequence[i];
+        let is_last = i + 1 == sequence.len();
+        match symbol {
+            Symbol::End | Symbol::Terminal(_) | Symbol::Error => {
+                result_set.insert(symbol);
+                return result_set;
+            }
+            Symbol::Epsilon | Symbol::NonTerminal(_) => {
+                let first_set_for_symbol = if symbol == Symbol::Epsilon {
+                    &epsilon_first_set
+                } else {
+                    first_sets.get(&symbol).unwrap()
+                };
+                let has_epsilon = first_set_for_symbol.contains(&Symbol::Epsilon);
+                for first_symbol in first_set_for_symbol {
+                    if first_symbol != &Symbol::Epsilon {
+                        result_set.insert(*first_symbol);
+                    }
+                }
+                if !has_epsilon {
+                    break;
+                } else {
+                    if is_last {
+                        result_set.insert(Symbol::Epsilon);
+                    }
+                }
+            }
+        }
+    }
+    result_set
+}
+
+pub fn compute_first_sets(grammar: &Grammar) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+    // init empty first sets
+    let mut first_sets = BTreeMap::new();
+    for nt in grammar.non_terminals() {
+        first_sets.insert(nt, BTreeSet::new());
+    }
+    // repeat until no more changes occur
+    loop {
+        let mut inserted_any = false;
+        for rule in grammar.rules() {
+            let first_for_rhs = get_first_terminals_of_sequence(rule.rhs(), &first_sets);
+            let first_set_of_lhs = first_sets.get_mut(&rule.lhs().unwrap()).unwrap();
+            for symbol in first_for_rhs {
+                let was_inserted = first_set_of_lhs.insert(symbol);
+                inserted_any = inserted_any || was_inserted;
+            }
+        }
+        // if nothing new was added, we are done
+        if !inserted_any {
+            break;
+        }
+    }
+
+    first_sets
+}
+
+fn get_follow_symbols_of_remainder(
+    lhs: Option<Symbol>,
+    remainder: &[Symbol],
+    first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+    follow_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+) -> BTreeSet<Symbol> {
+    let mut result_set = BTreeSet::new();
+    let remainder_first_set = get_first_terminals_of_sequence(remainder, first_sets);
+    let remainder_first_has_epsilon = remainder_first_set.contains(&Symbol::Epsilon);
+    let should_add_lhs_follow_set = remainder_first_has_epsilon || remainder.is_empty();
+    if should_add_lhs_follow_set {
+        let follow_set_of_lhs = follow_sets.get(&lhs.unwrap()).unwrap().clone();
+        result_set.extend(follow_set_of_lhs);
+    }
+    for remainder_first_symbol in remainder_first_set {
+        if remainder_first_symbol != Symbol::Epsilon {
+            result_set.insert(remainder_first_symbol);
+        }
+    }
+
+    result_set
+}
+
+pub fn compute_follow_sets(
+    grammar: &Grammar,
+    first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+    // init empty first sets
+    let mut follow_sets = BTreeMap::new();
+    for nt in grammar.non_terminals() {
+        follow_sets.insert(nt, BTreeSet::new());
+    }
+    // repeat until no more changes occur
+    let terminated_entry_point_rhs = vec![*grammar.entry_point(), Symbol::End];
+    loop {
+        let grammar_rules = grammar
+            .rules()
+            .iter()
+            .map(|r| (Some(r.lhs().unwrap()), r.rhs()));
+        let all_rules = std::iter::once((None, &terminated_entry_point_rhs)).chain(grammar_rules);
+        let mut inserted_any = false;
+        for (lhs, sequence) in all_rules {
+            for i in 0..sequence.len() {
+                let symbol = &sequence[i];
+                if let Symbol::NonTerminal(_) = symbol {
+                    let remainder = &sequence[i + 1..];
+                    let follow_symbols_for_remainder =
+                        get_follow_symbols_of_remainder(lhs, remainder, first_sets, &follow_sets);
+                    let follow_set_of_nt = follow_sets.get_mut(symbol).unwrap();
+                    for follow_symbol in follow_symbols_for_remainder {
+                        let was_inserted = follow_set_of_nt.insert(follow_symbol);
+                        inserted_any = inserted_any || was_inserted;
+                    }
+                }
+            }
+        }
+        if !inserted_any {
+            break;
+        }
+    }
+
+    follow_sets
+}
+
+/// The non-terminals which can derive the empty sequence.
+pub fn compute_nullable(first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>) -> BTreeSet<Symbol> {
+    first_sets
+        .iter()
+        .filter(|(_, first_set)| first_set.contains(&Symbol::Epsilon))
+        .map(|(non_terminal, _)| *non_terminal)
+        .collect()
+}
+
+/// The non-terminals the entry rule derives, directly or through others.
+pub fn compute_reachable(grammar: &Grammar) -> BTreeSet<Symbol> {
+    let mut reachable = BTreeSet::from([*grammar.entry_point()]);
+    let mut pending = vec![*grammar.entry_point()];
+    while let Some(non_terminal) = pending.pop() {
+        for rule in grammar
+            .rules()
+            .iter()
+            .filter(|rule| rule.lhs() == Some(non_terminal))
+        {
+            for symbol in rule.rhs() {
+                if let Symbol::NonTerminal(_) = symbol {
+                    if reachable.insert(*symbol) {
+                        pending.push(*symbol);
+                    }
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// The non-terminals which derive at least one sequence of tokens, which may be empty.
+pub fn compute_productive(grammar: &Grammar) -> BTreeSet<Symbol> {
+    let mut productive = BTreeSet::new();
+    // repeat until no more changes occur
+    loop {
+        let mut inserted_any = false;
+        for rule in grammar.rules() {
+            let lhs = rule.lhs().unwrap();
+            if !productive.contains(&lhs)
+                && rule.rhs().iter().all(|symbol| match symbol {
+                    Symbol::NonTerminal(_) => productive.contains(symbol),
+                    _ => true,
+                })
+            {
+                productive.insert(lhs);
+                inserted_any = true;
+            }
+        }
+        if !inserted_any {
+            break;
+        }
+    }
+    productive
+}
@@ -1,13 +1,14 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     error::Error,
     fmt::{Debug, Display},
     num::TryFromIntError,
 };
 
-use lapex_input::{ProductionRule, RuleSet, SourceSpan, Spanned};
+use lapex_input::{ConflictKind, ProductionRule, RuleSet, SourceSpan, Spanned};
 
-use crate::grammar_builder::GrammarBuilder;
+use crate::analysis::{compute_first_sets, compute_follow_sets, compute_nullable};
+pub use crate::grammar_builder::GrammarBuilder;
 
 pub type SymbolIdx = u16;
 
@@ -15,15 +16,75 @@ pub type SymbolIdx = u16;
 pub enum GrammarError {
     TooManyRules,
     MissingSymbol(String),
-    ConflictingRules { rules: Vec<SourceSpan> },
+    ConflictingRules {
+        rules: Vec<SourceSpan>,
+    },
     RuleWithTerminalLeftHandSide,
+    UnitCycle {
+        path: Vec<Spanned<String>>,
+    },
+    NotAToken(String),
+    /// A production or insertion rule uses a token the lexer skips.
+    SkippedToken(String),
+    /// The grammar has only tokens, so there is no parser to generate from it.
+    NoEntryRule,
+    /// The grammar declares sync tokens and has error productions, but a parser recovers from
+    /// errors in only one way.
+    MixedErrorRecovery,
+    /// The entry rule names a token or a production which is not defined.
+    UndefinedEntryRule {
+        name: String,
+        span: SourceSpan,
+    },
 }
 
 impl Error for GrammarError {}
 
 impl Display for GrammarError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self) // TODO
+        match self {
+            GrammarError::TooManyRules => write!(f, "the grammar has too many rules"),
+            GrammarError::MissingSymbol(name) => {
+                write!(f, "the token or production {} is not defined", name)
+            }
+            GrammarError::ConflictingRules { .. } => {
+                write!(f, "a token or production is defined more than once")
+            }
+            GrammarError::RuleWithTerminalLeftHandSide => {
+                write!(f, "a rule produces a token")
+            }
+            GrammarError::UnitCycle { path } => {
+                let names: Vec<&str> = path.iter().map(|name| name.inner.as_str()).collect();
+                write!(
+                    f,
+                    "the productions {} derive each other",
+                    names.join(" -> ")
+                )
+            }
+            GrammarError::NotAToken(name) => write!(f, "{} is not a token", name),
+            GrammarError::SkippedToken(name) => {
+                write!(
+                    f,
+                    "the token {} is skipped and never reaches the parser",
+                    name
+                )
+            }
+            GrammarError::NoEntryRule => write!(
+                f,
+                "the grammar has no entry rule, only a lexer can be generated from it"
+            ),
+            GrammarError::MixedErrorRecovery => write!(
+                f,
+                "the grammar has both error productions and sync tokens, only one of them can be used"
+            ),
+            GrammarError::UndefinedEntryRule { name, .. } => {
+                write!(
+                    f,
+                    "the entry rule references the undefined production {}",
+                    name
+                )
+            }
+        }
     }
 }
 
@@ -39,6 +100,80 @@ pub enum Symbol {
     End,
     NonTerminal(SymbolIdx),
     Terminal(SymbolIdx),
+    /// The `error` pseudo-terminal of error productions, which stands in for input that was
+    /// skipped during error recovery.
+    Error,
+}
+
+/// The EBNF operator an anonymous non-terminal was lowered from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoweredPattern {
+    Alternative,
+    Optional,
+    ZeroOrMany,
+    OneOrMany,
+}
+
+impl LoweredPattern {
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            LoweredPattern::Alternative => "alt",
+            LoweredPattern::Optional => "opt",
+            LoweredPattern::ZeroOrMany => "star",
+            LoweredPattern::OneOrMany => "plus",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            LoweredPattern::Alternative => "alternation",
+            LoweredPattern::Optional => "option",
+            LoweredPattern::ZeroOrMany | LoweredPattern::OneOrMany => "repetition",
+        }
+    }
+}
+
+/// Records which production an anonymous non-terminal was created for, so that
+/// generated code and diagnostics can refer to it by a readable name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnonymousOrigin<'rules> {
+    production: &'rules str,
+    pattern: LoweredPattern,
+    name: String,
+}
+
+impl<'rules> AnonymousOrigin<'rules> {
+    pub fn new(production: &'rules str, pattern: LoweredPattern, name: String) -> Self {
+        AnonymousOrigin {
+            production,
+            pattern,
+            name,
+        }
+    }
+
+    pub fn production(&self) -> &'rules str {
+        self.production
+    }
+
+    pub fn pattern(&self) -> LoweredPattern {
+        self.pattern
+    }
+
+    /// A name derived from the production and operator, e.g. `args_star`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<'rules> Display for AnonymousOrigin<'rules> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} inside '{}'",
+            self.pattern.description(),
+            self.production
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -46,6 +181,8 @@ pub struct Rule<'rules> {
     lhs: Option<SymbolIdx>,
     rhs: Vec<Symbol>,
     rule: &'rules Spanned<ProductionRule<'rules>>,
+    tag: Option<&'rules str>,
+    allowed_conflicts: BTreeSet<ConflictKind>,
 }
 
 impl<'rules> Rule<'rules> {
@@ -54,12 +191,41 @@ impl<'rules> Rule<'rules> {
             lhs: None,
             rhs: vec![entry_symbol],
             rule,
+            tag: None,
+            allowed_conflicts: BTreeSet::new(),
         }
     }
 
     pub fn rule(&self) -> &'rules Spanned<ProductionRule<'rules>> {
         self.rule
     }
+
+    /// Distinguishes this rule from the other rules of its non-terminal. Rules of a
+    /// production use the production's tag, rules of anonymous non-terminals get
+    /// a role like `tail` or `none` assigned during lowering.
+    pub fn tag(&self) -> Option<&'rules str> {
+        self.tag
+    }
+
+    pub fn with_tag(mut self, tag: Option<&'rules str>) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    /// Whether a conflict of the given kind involving this rule is tolerated, because the
+    /// production or alternative it was lowered from is annotated with `@allow(...)`.
+    pub fn allows(&self, kind: ConflictKind) -> bool {
+        self.allowed_conflicts.contains(&kind)
+    }
+
+    pub fn allowed_conflicts(&self) -> &BTreeSet<ConflictKind> {
+        &self.allowed_conflicts
+    }
+
+    pub fn with_allowed_conflicts(mut self, allowed_conflicts: BTreeSet<ConflictKind>) -> Self {
+        self.allowed_conflicts = allowed_conflicts;
+        self
+    }
 }
 
 pub struct RuleDisplay<'rule, 'grammar> {
@@ -82,6 +248,8 @@ impl<'rules> Rule<'rules> {
                 lhs: Some(non_terminal_index),
                 rhs,
                 rule,
+                tag: rule.inner.tag,
+                allowed_conflicts: BTreeSet::new(),
             })
         } else {
             Err(GrammarError::RuleWithTerminalLeftHandSide)
@@ -128,14 +296,44 @@ impl<'rule, 'grammar> Display for RuleDisplay<'rule, 'grammar> {
     }
 }
 
+/// An insertion rule of the grammar, with its token names resolved to terminals.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TokenInsertion {
+    pub token: Symbol,
+    pub trigger: Symbol,
+    pub after: Vec<Symbol>,
+}
+
+/// Rules which produce the same symbols.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Duplicate {
+    /// An alternative repeats an earlier alternative of the same production, or a production
+    /// which is merged with an earlier one of the same name repeats it. A parser can never
+    /// decide which of the two to reduce.
+    Alternative {
+        production: String,
+        spans: [SourceSpan; 2],
+    },
+    /// Two productions have the same alternatives. The parser tells them apart by where they are
+    /// used, so this only means one of them could be used in both places.
+    Production {
+        names: [String; 2],
+        spans: [SourceSpan; 2],
+    },
+}
+
 #[derive(Debug)]
 pub struct Grammar<'rules> {
     rules: Vec<Rule<'rules>>,
-    anonymous_non_terminals: Vec<Symbol>,
+    anonymous_non_terminals: BTreeMap<Symbol, AnonymousOrigin<'rules>>,
     productions: BTreeMap<Symbol, &'rules str>,
     tokens: BTreeMap<Symbol, &'rules str>,
     entry_rule: Rule<'rules>,
     entry_symbol: Symbol,
+    token_insertions: Vec<TokenInsertion>,
+    sync_tokens: BTreeSet<Symbol>,
+    duplicates: Vec<Duplicate>,
+    external_token_ids: BTreeMap<Symbol, u32>,
 }
 
 impl<'rules> Grammar<'rules> {
@@ -145,7 +343,8 @@ impl<'rules> Grammar<'rules> {
         rules: Vec<Rule<'rules>>,
         tokens: BTreeMap<Symbol, &'rules str>,
         productions: BTreeMap<Symbol, &'rules str>,
-        anonymous_non_terminals: Vec<Symbol>,
+        anonymous_non_terminals: BTreeMap<Symbol, AnonymousOrigin<'rules>>,
+        token_insertions: Vec<TokenInsertion>,
     ) -> Self {
         Grammar {
             rules,
@@ -154,19 +353,41 @@ impl<'rules> Grammar<'rules> {
             tokens,
             entry_rule,
             entry_symbol,
+            token_insertions,
+            sync_tokens: BTreeSet::new(),
+            duplicates: Vec::new(),
+            external_token_ids: BTreeMap::new(),
         }
     }
+
+    pub fn with_duplicates(mut self, duplicates: Vec<Duplicate>) -> Self {
+        self.duplicates = duplicates;
+        self
+    }
+
+    pub fn with_sync_tokens(mut self, sync_tokens: BTreeSet<Symbol>) -> Self {
+        self.sync_tokens = sync_tokens;
+        self
+    }
+
+    pub fn with_external_token_ids(mut self, external_token_ids: BTreeMap<Symbol, u32>) -> Self {
+        self.external_token_ids = external_token_ids;
+        self
+    }
 }
 
 impl<'rules> Grammar<'rules> {
-    pub fn from_rule_set(rule_set: &'rules RuleSet) -> Result<Self, GrammarError> {
-        GrammarBuilder::from_rule_set(rule_set)?.build()
+    pub fn from_rule_set(
+        rule_set: &'rules RuleSet,
+        merge_duplicate_productions: bool,
+    ) -> Result<Self, GrammarError> {
+        GrammarBuilder::from_rule_set(rule_set, merge_duplicate_productions)?.build()
     }
 
     pub fn non_terminals(&'rules self) -> impl Iterator<Item = Symbol> + 'rules {
         self.productions
             .keys()
-            .chain(self.anonymous_non_terminals.iter())
+            .chain(self.anonymous_non_terminals.keys())
             .map(|s| s.clone())
     }
 
@@ -178,12 +399,75 @@ impl<'rules> Grammar<'rules> {
         self.terminals().chain(self.non_terminals())
     }
 
+    /// The terminals each non-terminal can start with, including `Symbol::Epsilon` if it can
+    /// derive the empty sequence.
+    pub fn first_sets(&self) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+        compute_first_sets(self)
+    }
+
+    /// The terminals which can follow each non-terminal, with `Symbol::End` for the end of input.
+    pub fn follow_sets(&self) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
+        compute_follow_sets(self, &compute_first_sets(self))
+    }
+
+    /// The non-terminals which can derive the empty sequence.
+    pub fn nullable(&self) -> BTreeSet<Symbol> {
+        compute_nullable(&compute_first_sets(self))
+    }
+
+    /// The insertion rules of the grammar, in the order they are tried.
+    pub fn token_insertions(&self) -> &[TokenInsertion] {
+        &self.token_insertions
+    }
+
+    /// The tokens declared with `sync`, which the parser skips to when it recovers from an error.
+    pub fn sync_tokens(&self) -> &BTreeSet<Symbol> {
+        &self.sync_tokens
+    }
+
+    /// The repeated alternatives and identical productions, in the order they were found.
+    pub fn duplicates(&self) -> &[Duplicate] {
+        &self.duplicates
+    }
+
+    /// Whether any rule uses the `error` pseudo-terminal.
+    pub fn has_error_productions(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule.rhs().contains(&Symbol::Error))
+    }
+
+    /// Whether any rule tolerates a kind of conflict, so conflicts have to be checked one by one.
+    pub fn has_allowed_conflicts(&self) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| !rule.allowed_conflicts.is_empty())
+    }
+
+    /// Whether the parser recovers from errors, with error productions or sync tokens.
+    pub fn recovers_from_errors(&self) -> bool {
+        self.has_error_productions() || !self.sync_tokens.is_empty()
+    }
+
     pub fn terminals_with_names(&self) -> impl Iterator<Item = (Symbol, &str)> {
         self.tokens
             .iter()
             .map(|(sym, token_rule)| (sym.clone(), *token_rule))
     }
 
+    /// The `///` comment of the production the rule was written as. The rules of anonymous
+    /// non-terminals have none, they are only a part of that production.
+    pub fn get_rule_doc(&self, rule: &Rule<'rules>) -> Option<&'rules str> {
+        let non_terminal = rule.lhs()?;
+        self.get_production_name(&non_terminal)?;
+        rule.rule().inner.doc.as_deref()
+    }
+
+    /// The id a token declared with `extern token` has in the token enum of its lexer.
+    pub fn get_external_token_id(&self, terminal: &Symbol) -> Option<u32> {
+        self.external_token_ids.get(terminal).copied()
+    }
+
     pub fn get_token_name(&self, index: SymbolIdx) -> &str {
         self.tokens
             .get(&Symbol::Terminal(index))
@@ -203,11 +487,23 @@ impl<'rules> Grammar<'rules> {
         }
     }
 
-    pub fn rules(&self) -> &[Rule] {
+    pub fn get_anonymous_origin(&self, non_terminal: &Symbol) -> Option<&AnonymousOrigin<'_>> {
+        self.anonymous_non_terminals.get(non_terminal)
+    }
+
+    /// Returns the production name, or the derived name for anonymous non-terminals.
+    pub fn get_non_terminal_name(&self, non_terminal: &Symbol) -> Option<&str> {
+        self.get_production_name(non_terminal).or_else(|| {
+            self.get_anonymous_origin(non_terminal)
+                .map(|origin| origin.name())
+        })
+    }
+
+    pub fn rules(&self) -> &[Rule<'_>] {
         &self.rules
     }
 
-    pub fn entry_rule(&self) -> &Rule {
+    pub fn entry_rule(&self) -> &Rule<'_> {
         &self.entry_rule
     }
 
@@ -215,6 +511,30 @@ impl<'rules> Grammar<'rules> {
         &self.entry_symbol
     }
 
+    /// Describes the symbols and rules without their names and positions. Grammars with the
+    /// same structure have the same parser tables, however their tokens and productions are named.
+    pub fn structure(&self) -> Vec<u8> {
+        let mut structure = Vec::new();
+        for symbols in [
+            self.terminals().collect::<Vec<_>>(),
+            self.non_terminals().collect(),
+            self.entry_rule.rhs.clone(),
+        ] {
+            write_symbols(&mut structure, &symbols);
+        }
+        structure.extend_from_slice(&(self.rules.len() as u32).to_le_bytes());
+        for rule in &self.rules {
+            write_symbol(&mut structure, rule.lhs().unwrap_or(Symbol::Epsilon));
+            write_symbols(&mut structure, &rule.rhs);
+            // the allowed conflicts decide how the table is resolved
+            let allowed = ConflictKind::ALL
+                .iter()
+                .fold(0u8, |bits, kind| bits << 1 | rule.allows(*kind) as u8);
+            structure.push(allowed);
+        }
+        structure
+    }
+
     pub fn get_symbol_name(&self, symbol: &Symbol) -> String {
         match symbol {
             Symbol::Terminal(terminal_index) => {
@@ -227,12 +547,15 @@ impl<'rules> Grammar<'rules> {
             Symbol::NonTerminal(non_terminal_index) => {
                 if let Some(rule) = self.productions.get(&symbol) {
                     format!("{}({})", rule, non_terminal_index)
+                } else if let Some(origin) = self.anonymous_non_terminals.get(symbol) {
+                    format!("<{}>({})", origin.name(), non_terminal_index)
                 } else {
                     format!("<anon>({})", non_terminal_index)
                 }
             }
             Symbol::Epsilon => String::from("<eps>"),
             Symbol::End => String::from("<end>"),
+            Symbol::Error => String::from("error"),
         }
     }
 }
@@ -251,3 +574,45 @@ impl<'rules> Display for Grammar<'rules> {
         Ok(())
     }
 }
+
+fn write_symbols(output: &mut Vec<u8>, symbols: &[Symbol]) {
+    output.extend_from_slice(&(symbols.len() as u32).to_le_bytes());
+    for symbol in symbols {
+        write_symbol(output, *symbol);
+    }
+}
+
+pub(crate) fn write_symbol(output: &mut Vec<u8>, symbol: Symbol) {
+    match symbol {
+        Symbol::Epsilon => output.push(0),
+        Symbol::End => output.push(1),
+        Symbol::NonTerminal(index) => {
+            output.push(2);
+            output.extend_from_slice(&index.to_le_bytes());
+        }
+        Symbol::Terminal(index) => {
+            output.push(3);
+            output.extend_from_slice(&index.to_le_bytes());
+        }
+        Symbol::Error => output.push(4),
+    }
+}
+
+/// Reads a symbol written by `write_symbol` from the start of the input and advances past it.
+pub(crate) fn read_symbol(input: &mut &[u8]) -> Option<Symbol> {
+    let (tag, rest) = input.split_first()?;
+    *input = rest;
+    let mut read_index = || {
+        let index = input.get(..2)?;
+        *input = &input[2..];
+        Some(SymbolIdx::from_le_bytes([index[0], index[1]]))
+    };
+    match tag {
+        0 => Some(Symbol::Epsilon),
+        1 => Some(Symbol::End),
+        2 => read_index().map(Symbol::NonTerminal),
+        3 => read_index().map(Symbol::Terminal),
+        4 => Some(Symbol::Error),
+        _ => None,
+    }
+}
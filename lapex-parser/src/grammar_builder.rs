@@ -1,27 +1,58 @@
-use std::collections::BTreeMap;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
 
-use lapex_input::{ProductionPattern, ProductionRule, RuleSet, Spanned, TokenRule};
+use lapex_input::{
+    ConflictKind, ProductionPattern, ProductionRule, RuleSet, SourceSpan, Spanned, TokenRule,
+};
 
-use crate::grammar::{Grammar, GrammarError, Rule, Symbol, SymbolIdx};
+use crate::grammar::{
+    AnonymousOrigin, Duplicate, Grammar, GrammarError, LoweredPattern, Rule, Symbol, SymbolIdx,
+    TokenInsertion,
+};
+
+const ERROR_SYMBOL_NAME: &str = "error";
 
 pub struct GrammarBuilder<'rules> {
     temp_count: SymbolIdx,
     symbols: BTreeMap<&'rules str, Symbol>,
     max_symbol: SymbolIdx,
-    anonymous_non_terminals: Vec<Symbol>,
+    anonymous_non_terminals: BTreeMap<Symbol, AnonymousOrigin<'rules>>,
     tokens: BTreeMap<Symbol, &'rules str>,
     productions: BTreeMap<Symbol, &'rules str>,
     rule_set: &'rules RuleSet<'rules>,
     rules: Vec<Rule<'rules>>,
+    duplicates: Vec<Duplicate>,
+    // the conflicts allowed by the `@allow(...)` annotations around the pattern being lowered
+    allowed_conflicts: Vec<ConflictKind>,
+    eliminates_left_recursion: bool,
 }
 
 impl<'rules> GrammarBuilder<'rules> {
-    pub fn from_rule_set(rule_set: &'rules RuleSet) -> Result<Self, GrammarError> {
+    pub fn from_rule_set(
+        rule_set: &'rules RuleSet,
+        merge_duplicate_productions: bool,
+    ) -> Result<Self, GrammarError> {
+        let entry_rule = rule_set
+            .entry_rule
+            .as_ref()
+            .ok_or(GrammarError::NoEntryRule)?;
+        if !rule_set
+            .production_rules
+            .iter()
+            .any(|rule| rule.inner.name == entry_rule.inner.name)
+        {
+            return Err(GrammarError::UndefinedEntryRule {
+                name: entry_rule.inner.name.to_string(),
+                span: entry_rule.span,
+            });
+        }
         let token_triples: Vec<(&str, Symbol, &Spanned<TokenRule>)> = rule_set
             .token_rules
             .iter()
             .enumerate()
-            .map(|(i, rule)| (rule.inner.name, Symbol::Terminal(i as SymbolIdx), rule))
+            .map(|(i, rule)| (&*rule.inner.name, Symbol::Terminal(i as SymbolIdx), rule))
             .collect();
         let production_triples: Vec<(&str, Symbol, &Spanned<ProductionRule>)> = rule_set
             .production_rules
@@ -40,14 +71,15 @@ impl<'rules> GrammarBuilder<'rules> {
                     rules: vec![prev_span, rule.span],
                 });
             }
-            tokens.insert(symbol, rule.inner.name);
+            tokens.insert(symbol, &*rule.inner.name);
         }
         for (prod_name, symbol, rule) in production_triples {
             if let Some((existing_symbol, existing_span)) = symbols_with_span.get(prod_name) {
-                if tokens.contains_key(existing_symbol) {
-                    return Err(GrammarError::ConflictingRules {
-                        rules: vec![existing_span.clone(), rule.span],
-                    });
+                // a repeated production name is only allowed if its alternatives get merged
+                if tokens.contains_key(existing_symbol) || !merge_duplicate_productions {
+                    let mut rules = vec![existing_span.clone(), rule.span];
+                    rules.sort();
+                    return Err(GrammarError::ConflictingRules { rules });
                 }
             } else {
                 symbols_with_span.insert(prod_name, (symbol, rule.span));
@@ -59,11 +91,14 @@ impl<'rules> GrammarBuilder<'rules> {
             temp_count: 0,
             rule_set,
             rules: Vec::new(),
+            duplicates: Vec::new(),
+            allowed_conflicts: Vec::new(),
+            eliminates_left_recursion: false,
             max_symbol: symbols_with_span
                 .values()
                 .map(|(s, _)| match s {
                     Symbol::Epsilon => 0,
-                    Symbol::End => 0,
+                    Symbol::End | Symbol::Error => 0,
                     Symbol::NonTerminal(nt_index) => *nt_index,
                     Symbol::Terminal(t_index) => *t_index,
                 })
@@ -73,42 +108,140 @@ impl<'rules> GrammarBuilder<'rules> {
                 .into_iter()
                 .map(|(name, (symbol, _span))| (name, symbol))
                 .collect(),
-            anonymous_non_terminals: Vec::new(),
+            anonymous_non_terminals: BTreeMap::new(),
             tokens,
             productions,
         })
     }
 
-    fn get_temp_symbol(&mut self) -> Result<Symbol, GrammarError> {
+    /// Rewrites the rules of productions deriving themselves before any token, so that LL parsers
+    /// can be generated for them. `a = a x | y;` becomes `a = y a_star; a_star = x a_star | ;`
+    /// with a repetition like the one of `a = y x*;`, and the rules of productions starting
+    /// with each other are substituted into each other first. The visitor of the parser sees
+    /// the rewritten rules.
+    pub fn with_left_recursion_elimination(mut self, eliminate: bool) -> Self {
+        self.eliminates_left_recursion = eliminate;
+        self
+    }
+
+    fn get_temp_symbol(
+        &mut self,
+        parent_rule: &'rules Spanned<ProductionRule<'rules>>,
+        pattern: LoweredPattern,
+    ) -> Result<Symbol, GrammarError> {
         let non_terminal = Symbol::NonTerminal(self.temp_count + self.max_symbol + 1);
-        self.anonymous_non_terminals.push(non_terminal.clone());
+        let production = parent_rule.inner.name;
+        let name = self.get_unique_anonymous_name(format!("{}_{}", production, pattern.suffix()));
+        self.anonymous_non_terminals.insert(
+            non_terminal,
+            AnonymousOrigin::new(production, pattern, name),
+        );
         self.temp_count = self.temp_count.checked_add(1).unwrap();
         Ok(non_terminal)
     }
 
+    fn get_unique_anonymous_name(&self, base: String) -> String {
+        let is_taken = |name: &str| {
+            self.symbols.contains_key(name)
+                || self
+                    .anonymous_non_terminals
+                    .values()
+                    .any(|origin| origin.name() == name)
+        };
+        if !is_taken(&base) {
+            return base;
+        }
+        (2..)
+            .map(|i| format!("{}_{}", base, i))
+            .find(|name| !is_taken(name))
+            .unwrap()
+    }
+
     fn get_symbol_by_name(&mut self, symbol_name: &str) -> Result<Symbol, GrammarError> {
+        // a symbol named "error" shadows the pseudo-terminal of error productions
+        if symbol_name == ERROR_SYMBOL_NAME && !self.symbols.contains_key(symbol_name) {
+            return Ok(Symbol::Error);
+        }
         let symbol = self
             .symbols
             .get(symbol_name)
             .map(|s| s.clone())
             .ok_or(GrammarError::MissingSymbol(symbol_name.to_string()))?;
+        if let Symbol::Terminal(index) = symbol {
+            if self.rule_set.token_rules[index as usize].inner.skip {
+                return Err(GrammarError::SkippedToken(symbol_name.to_string()));
+            }
+        }
         Ok(symbol)
     }
 
+    fn get_token_by_name(&mut self, token_name: &str) -> Result<Symbol, GrammarError> {
+        match self.get_symbol_by_name(token_name)? {
+            symbol @ Symbol::Terminal(_) => Ok(symbol),
+            _ => Err(GrammarError::NotAToken(token_name.to_string())),
+        }
+    }
+
     pub fn build(mut self) -> Result<Grammar<'rules>, GrammarError> {
         for rule in &self.rule_set.production_rules {
             self.add_production_rule(&rule)?;
         }
-        let entry_name = self.rule_set.entry_rule.inner.name;
+        let mut token_insertions = Vec::new();
+        for rule in &self.rule_set.insertion_rules {
+            token_insertions.push(TokenInsertion {
+                token: self.get_token_by_name(rule.inner.token)?,
+                trigger: self.get_token_by_name(rule.inner.trigger)?,
+                after: rule
+                    .inner
+                    .after
+                    .iter()
+                    .map(|name| self.get_token_by_name(name))
+                    .collect::<Result<_, _>>()?,
+            });
+        }
+        let sync_tokens = self
+            .rule_set
+            .sync_tokens
+            .iter()
+            .map(|token| self.get_token_by_name(token.inner))
+            .collect::<Result<BTreeSet<Symbol>, _>>()?;
+        if !sync_tokens.is_empty()
+            && self
+                .rules
+                .iter()
+                .any(|rule| rule.rhs().contains(&Symbol::Error))
+        {
+            return Err(GrammarError::MixedErrorRecovery);
+        }
+        self.check_unit_cycles()?;
+        // checked to exist before building
+        let entry_name = self.rule_set.entry_rule.as_ref().unwrap().inner.name;
         let entry_symbol = self.get_symbol_by_name(entry_name)?;
+        if self.eliminates_left_recursion {
+            self.eliminate_left_recursion(entry_symbol)?;
+        }
+        self.find_identical_productions();
+        self.duplicates.sort_by_key(|duplicate| match duplicate {
+            Duplicate::Alternative { spans, .. } | Duplicate::Production { spans, .. } => spans[0],
+        });
         let entry_production = self
             .rule_set
             .production_rules
             .iter()
             .find(|r| r.inner.name == entry_name)
-            .ok_or(GrammarError::MissingSymbol(String::from(entry_name)))?;
+            .unwrap();
         // the entry rule is a pseudo-rule that has no LHS and maps to the entry symbol.
         let entry_rule = Rule::entry(entry_symbol, &entry_production);
+        let external_token_ids = self
+            .rule_set
+            .token_rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rule)| {
+                let id = rule.inner.external_id()?;
+                Some((Symbol::Terminal(i as SymbolIdx), id))
+            })
+            .collect();
         Ok(Grammar::new(
             entry_symbol,
             entry_rule,
@@ -116,7 +249,11 @@ impl<'rules> GrammarBuilder<'rules> {
             self.tokens,
             self.productions,
             self.anonymous_non_terminals,
-        ))
+            token_insertions,
+        )
+        .with_duplicates(self.duplicates)
+        .with_sync_tokens(sync_tokens)
+        .with_external_token_ids(external_token_ids))
     }
 }
 
@@ -126,11 +263,40 @@ impl<'rules> GrammarBuilder<'rules> {
         prod_rule: &'rules Spanned<ProductionRule<'rules>>,
     ) -> Result<(), GrammarError> {
         let symbol = self.get_symbol_by_name(prod_rule.inner.name)?;
+        let allowed_len = self.allowed_conflicts.len();
+        self.allowed_conflicts
+            .extend(annotation(&prod_rule.inner.pattern));
         let produces = self.transform_pattern(&prod_rule.inner.pattern, prod_rule)?;
-        self.rules.push(Rule::new(symbol, produces, prod_rule)?);
+        // a production merged into an earlier one of the same name adds its alternatives to it
+        let repeated = self
+            .rules
+            .iter()
+            .find(|rule| rule.lhs() == Some(symbol) && self.same_symbols(rule.rhs(), &produces))
+            .map(|rule| rule.rule().span);
+        if let Some(span) = repeated {
+            let mut spans = [span, prod_rule.span];
+            spans.sort();
+            self.duplicates.push(Duplicate::Alternative {
+                production: prod_rule.inner.name.to_string(),
+                spans,
+            });
+        }
+        let rule = self.new_rule(symbol, produces, prod_rule)?;
+        self.rules.push(rule);
+        self.allowed_conflicts.truncate(allowed_len);
         Ok(())
     }
 
+    fn new_rule(
+        &self,
+        lhs: Symbol,
+        rhs: Vec<Symbol>,
+        parent_rule: &'rules Spanned<ProductionRule<'rules>>,
+    ) -> Result<Rule<'rules>, GrammarError> {
+        let allowed_conflicts = self.allowed_conflicts.iter().copied().collect();
+        Ok(Rule::new(lhs, rhs, parent_rule)?.with_allowed_conflicts(allowed_conflicts))
+    }
+
     fn transform_pattern(
         &mut self,
         pattern: &ProductionPattern,
@@ -146,45 +312,505 @@ impl<'rules> GrammarBuilder<'rules> {
                 Ok(symbols)
             }
             ProductionPattern::Alternative { elements } => {
-                let alt_symbol = self.get_temp_symbol()?;
+                let alt_symbol = self.get_temp_symbol(parent_rule, LoweredPattern::Alternative)?;
+                let mut alternatives: Vec<(Vec<Symbol>, SourceSpan)> = Vec::new();
                 for elem in elements {
+                    let allowed_len = self.allowed_conflicts.len();
+                    self.allowed_conflicts.extend(annotation(elem));
                     let inner_produces = self.transform_pattern(elem, parent_rule)?;
-                    self.rules
-                        .push(Rule::new(alt_symbol, inner_produces, parent_rule)?);
+                    // an empty alternative has no symbols to point at, so its whole production is
+                    let span = pattern_span(elem).unwrap_or(parent_rule.span);
+                    let repeated = alternatives
+                        .iter()
+                        .find(|(produces, _)| self.same_symbols(produces, &inner_produces))
+                        .map(|(_, span)| *span);
+                    if let Some(repeated) = repeated {
+                        self.duplicates.push(Duplicate::Alternative {
+                            production: parent_rule.inner.name.to_string(),
+                            spans: [repeated, span],
+                        });
+                    }
+                    alternatives.push((inner_produces.clone(), span));
+                    let rule = self.new_rule(alt_symbol, inner_produces, parent_rule)?;
+                    self.rules.push(rule.with_tag(None));
+                    self.allowed_conflicts.truncate(allowed_len);
                 }
                 Ok(vec![alt_symbol])
             }
             ProductionPattern::OneOrMany { inner } => {
-                let rep_symbol = self.get_temp_symbol()?;
+                let rep_symbol = self.get_temp_symbol(parent_rule, LoweredPattern::OneOrMany)?;
                 let mut inner_produces = self.transform_pattern(inner, parent_rule)?;
-                self.rules
-                    .push(Rule::new(rep_symbol, inner_produces.clone(), parent_rule)?);
+                self.rules.push(
+                    self.new_rule(rep_symbol, inner_produces.clone(), parent_rule)?
+                        .with_tag(Some("one")),
+                );
                 inner_produces.push(rep_symbol);
-                self.rules
-                    .push(Rule::new(rep_symbol, inner_produces, parent_rule)?);
+                self.rules.push(
+                    self.new_rule(rep_symbol, inner_produces, parent_rule)?
+                        .with_tag(Some("tail")),
+                );
                 Ok(vec![rep_symbol])
             }
             ProductionPattern::ZeroOrMany { inner } => {
-                let rep_symbol = self.get_temp_symbol()?;
+                let rep_symbol = self.get_temp_symbol(parent_rule, LoweredPattern::ZeroOrMany)?;
                 let mut inner_produces = self.transform_pattern(inner, parent_rule)?;
                 inner_produces.push(rep_symbol);
-                self.rules
-                    .push(Rule::new(rep_symbol, vec![Symbol::Epsilon], parent_rule)?);
-                self.rules
-                    .push(Rule::new(rep_symbol, inner_produces, parent_rule)?);
+                self.rules.push(
+                    self.new_rule(rep_symbol, vec![Symbol::Epsilon], parent_rule)?
+                        .with_tag(Some("empty")),
+                );
+                self.rules.push(
+                    self.new_rule(rep_symbol, inner_produces, parent_rule)?
+                        .with_tag(Some("tail")),
+                );
                 Ok(vec![rep_symbol])
             }
             ProductionPattern::Optional { inner } => {
-                let symbol = self.get_temp_symbol()?;
+                let symbol = self.get_temp_symbol(parent_rule, LoweredPattern::Optional)?;
                 let inner_produces = self.transform_pattern(inner, parent_rule)?;
-                self.rules
-                    .push(Rule::new(symbol, inner_produces, parent_rule)?);
-                self.rules
-                    .push(Rule::new(symbol, vec![Symbol::Epsilon], parent_rule)?);
+                self.rules.push(
+                    self.new_rule(symbol, inner_produces, parent_rule)?
+                        .with_tag(Some("some")),
+                );
+                self.rules.push(
+                    self.new_rule(symbol, vec![Symbol::Epsilon], parent_rule)?
+                        .with_tag(Some("none")),
+                );
                 Ok(vec![symbol])
             }
-            ProductionPattern::Rule { rule_name } => Ok(vec![self.get_symbol_by_name(rule_name)?]),
+            // a group only decides how the operators around it apply, its symbols are inlined
+            ProductionPattern::Group { inner } => self.transform_pattern(inner, parent_rule),
+            ProductionPattern::Rule { rule_name, .. } => {
+                Ok(vec![self.get_symbol_by_name(rule_name)?])
+            }
+            ProductionPattern::Annotated { allow, inner } => {
+                let allowed_len = self.allowed_conflicts.len();
+                self.allowed_conflicts.extend(allow);
+                let produces = self.transform_pattern(inner, parent_rule);
+                self.allowed_conflicts.truncate(allowed_len);
+                produces
+            }
             ProductionPattern::Epsilon => Ok(vec![Symbol::Epsilon]),
         }
     }
 }
+
+impl<'rules> GrammarBuilder<'rules> {
+    fn check_unit_cycles(&self) -> Result<(), GrammarError> {
+        let mut unit_rules: BTreeMap<Symbol, Vec<&Rule<'rules>>> = BTreeMap::new();
+        for rule in &self.rules {
+            if let (Some(lhs), [Symbol::NonTerminal(_)]) = (rule.lhs(), rule.rhs().as_slice()) {
+                unit_rules.entry(lhs).or_default().push(rule);
+            }
+        }
+        let mut finished = BTreeSet::new();
+        for symbol in unit_rules.keys() {
+            let mut path = Vec::new();
+            if let Some(cycle) =
+                Self::find_unit_cycle(*symbol, &unit_rules, &mut path, &mut finished)
+            {
+                return Err(GrammarError::UnitCycle {
+                    path: Self::cycle_path(cycle),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn find_unit_cycle<'a>(
+        symbol: Symbol,
+        unit_rules: &BTreeMap<Symbol, Vec<&'a Rule<'rules>>>,
+        path: &mut Vec<(Symbol, &'a Rule<'rules>)>,
+        finished: &mut BTreeSet<Symbol>,
+    ) -> Option<Vec<&'a Rule<'rules>>> {
+        if finished.contains(&symbol) {
+            return None;
+        }
+        if let Some(start) = path.iter().position(|(s, _)| *s == symbol) {
+            return Some(path[start..].iter().map(|(_, rule)| *rule).collect());
+        }
+        for rule in unit_rules.get(&symbol).into_iter().flatten() {
+            path.push((symbol, rule));
+            let cycle = Self::find_unit_cycle(rule.rhs()[0], unit_rules, path, finished);
+            path.pop();
+            if cycle.is_some() {
+                return cycle;
+            }
+        }
+        finished.insert(symbol);
+        None
+    }
+
+    pub(crate) fn cycle_path(cycle: Vec<&Rule<'rules>>) -> Vec<Spanned<String>> {
+        // anonymous non-terminals belong to the production they were created for,
+        // so consecutive steps within the same production are merged. The path starts
+        // at the production that comes first in the source.
+        let mut path: Vec<Spanned<String>> = Vec::new();
+        for rule in cycle {
+            let production = rule.rule();
+            if path.last().map(|s| s.span) != Some(production.span) {
+                path.push(Spanned::new(
+                    production.span,
+                    production.inner.name.to_string(),
+                ));
+            }
+        }
+        if path.len() > 1 && path.first().map(|s| s.span) == path.last().map(|s| s.span) {
+            path.pop();
+        }
+        let first = (0..path.len()).min_by_key(|i| path[*i].span).unwrap_or(0);
+        path.rotate_left(first);
+        path
+    }
+}
+
+impl<'rules> GrammarBuilder<'rules> {
+    // Paull's algorithm, limited to the non-terminals which lead back to the one being
+    // rewritten, so the rules of the others stay as they are. The recursion ends up in the
+    // production rewritten last, which is the entry production if it is part of one.
+    fn eliminate_left_recursion(&mut self, entry_symbol: Symbol) -> Result<(), GrammarError> {
+        let mut non_terminals: Vec<(Symbol, SourceSpan)> = Vec::new();
+        for rule in &self.rules {
+            let lhs = rule.lhs().unwrap();
+            if !non_terminals.iter().any(|(symbol, _)| *symbol == lhs) {
+                non_terminals.push((lhs, rule.rule().span));
+            }
+        }
+        let entry_span = self.production_span(entry_symbol);
+        non_terminals.sort_by_key(|(symbol, span)| (*span == entry_span, Reverse(*span), *symbol));
+        let non_terminals: Vec<Symbol> = non_terminals
+            .into_iter()
+            .map(|(symbol, _)| symbol)
+            .collect();
+
+        let reachable = self.reachable_non_terminals(entry_symbol);
+        for (i, non_terminal) in non_terminals.iter().enumerate() {
+            for earlier in &non_terminals[..i] {
+                if self.starts_with(*earlier, *non_terminal) {
+                    self.substitute_start(*non_terminal, *earlier)?;
+                }
+            }
+            self.eliminate_direct_left_recursion(*non_terminal)?;
+        }
+        // the productions substituted into others may not be used anymore, their rules would
+        // only add conflicts to the table
+        let still_reachable = self.reachable_non_terminals(entry_symbol);
+        self.rules.retain(|rule| {
+            let lhs = rule.lhs().unwrap();
+            still_reachable.contains(&lhs) || !reachable.contains(&lhs)
+        });
+        Ok(())
+    }
+
+    fn reachable_non_terminals(&self, entry_symbol: Symbol) -> BTreeSet<Symbol> {
+        let mut reachable = BTreeSet::from([entry_symbol]);
+        let mut queue = vec![entry_symbol];
+        while let Some(current) = queue.pop() {
+            for rule in self.rules.iter().filter(|rule| rule.lhs() == Some(current)) {
+                for symbol in rule.rhs() {
+                    if let Symbol::NonTerminal(_) = symbol {
+                        if reachable.insert(*symbol) {
+                            queue.push(*symbol);
+                        }
+                    }
+                }
+            }
+        }
+        reachable
+    }
+
+    // whether a rule of `from`, or of a non-terminal one of them starts with, starts with `to`
+    fn starts_with(&self, from: Symbol, to: Symbol) -> bool {
+        let mut visited = BTreeSet::from([from]);
+        let mut queue = vec![from];
+        while let Some(current) = queue.pop() {
+            for rule in self.rules.iter().filter(|rule| rule.lhs() == Some(current)) {
+                let Some(first @ Symbol::NonTerminal(_)) = rule.rhs().first().copied() else {
+                    continue;
+                };
+                if first == to {
+                    return true;
+                }
+                if visited.insert(first) {
+                    queue.push(first);
+                }
+            }
+        }
+        false
+    }
+
+    // replaces the rules of `non_terminal` starting with `start` with one rule for every rule
+    // of `start`
+    fn substitute_start(
+        &mut self,
+        non_terminal: Symbol,
+        start: Symbol,
+    ) -> Result<(), GrammarError> {
+        let expansions: Vec<Vec<Symbol>> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.lhs() == Some(start))
+            .map(|rule| rule.rhs().clone())
+            .collect();
+        let mut rules = Vec::with_capacity(self.rules.len());
+        for rule in std::mem::take(&mut self.rules) {
+            if rule.lhs() != Some(non_terminal) || rule.rhs().first() != Some(&start) {
+                rules.push(rule);
+                continue;
+            }
+            for expansion in &expansions {
+                let rhs = without_epsilon(expansion.iter().chain(&rule.rhs()[1..]));
+                rules.push(rewritten_rule(&rule, non_terminal, rhs)?);
+            }
+        }
+        self.rules = rules;
+        Ok(())
+    }
+
+    fn eliminate_direct_left_recursion(
+        &mut self,
+        non_terminal: Symbol,
+    ) -> Result<(), GrammarError> {
+        let Some(parent_rule) = self
+            .rules
+            .iter()
+            .find(|rule| rule.lhs() == Some(non_terminal) && rule.rhs()[0] == non_terminal)
+            .map(|rule| rule.rule())
+        else {
+            return Ok(());
+        };
+        let repetition = self.get_temp_symbol(parent_rule, LoweredPattern::ZeroOrMany)?;
+        let mut rules = Vec::with_capacity(self.rules.len() + 1);
+        let mut tails = Vec::new();
+        for rule in std::mem::take(&mut self.rules) {
+            if rule.lhs() != Some(non_terminal) {
+                rules.push(rule);
+            } else if rule.rhs()[0] == non_terminal {
+                let mut rhs = rule.rhs()[1..].to_vec();
+                rhs.push(repetition);
+                tails.push(rewritten_rule(&rule, repetition, rhs)?);
+            } else {
+                let mut rhs: Vec<Symbol> = rule
+                    .rhs()
+                    .iter()
+                    .copied()
+                    .filter(|symbol| *symbol != Symbol::Epsilon)
+                    .collect();
+                rhs.push(repetition);
+                rules.push(rewritten_rule(&rule, non_terminal, rhs)?);
+            }
+        }
+        rules.push(
+            Rule::new(repetition, vec![Symbol::Epsilon], parent_rule)?.with_tag(Some("empty")),
+        );
+        rules.extend(tails);
+        self.rules = rules;
+        Ok(())
+    }
+}
+
+// the symbols without the epsilons of empty rules, or just an epsilon if nothing is left
+fn without_epsilon<'a>(symbols: impl IntoIterator<Item = &'a Symbol>) -> Vec<Symbol> {
+    let symbols: Vec<Symbol> = symbols
+        .into_iter()
+        .copied()
+        .filter(|symbol| *symbol != Symbol::Epsilon)
+        .collect();
+    if symbols.is_empty() {
+        vec![Symbol::Epsilon]
+    } else {
+        symbols
+    }
+}
+
+// a rule with other symbols, which keeps the production, tag and allowed conflicts of `rule`
+fn rewritten_rule<'rules>(
+    rule: &Rule<'rules>,
+    lhs: Symbol,
+    rhs: Vec<Symbol>,
+) -> Result<Rule<'rules>, GrammarError> {
+    Ok(Rule::new(lhs, rhs, rule.rule())?
+        .with_tag(rule.tag())
+        .with_allowed_conflicts(rule.allowed_conflicts().clone()))
+}
+
+impl<'rules> GrammarBuilder<'rules> {
+    fn rules_by_lhs(&self) -> BTreeMap<Symbol, Vec<&[Symbol]>> {
+        let mut rules_by_lhs: BTreeMap<Symbol, Vec<&[Symbol]>> = BTreeMap::new();
+        for rule in &self.rules {
+            if let Some(lhs) = rule.lhs() {
+                rules_by_lhs.entry(lhs).or_default().push(rule.rhs());
+            }
+        }
+        rules_by_lhs
+    }
+
+    fn same_symbols(&self, first: &[Symbol], second: &[Symbol]) -> bool {
+        // only anonymous non-terminals need their rules to be compared
+        if first == second
+            || !first
+                .iter()
+                .any(|s| self.anonymous_non_terminals.contains_key(s))
+        {
+            return first == second;
+        }
+        let rules_by_lhs = self.rules_by_lhs();
+        SameRules {
+            rules_by_lhs: &rules_by_lhs,
+            anonymous_non_terminals: &self.anonymous_non_terminals,
+        }
+        .same_symbols(first, second, &mut Vec::new())
+    }
+
+    fn find_identical_productions(&mut self) {
+        let rules_by_lhs = self.rules_by_lhs();
+        let rules = SameRules {
+            rules_by_lhs: &rules_by_lhs,
+            anonymous_non_terminals: &self.anonymous_non_terminals,
+        };
+        // only productions with the same shape can be the same, so each is compared with the
+        // others of its shape. A production substituted away by the elimination of left recursion
+        // has no rules left.
+        let mut shapes: BTreeMap<Shape, Vec<(Symbol, &'rules str)>> = BTreeMap::new();
+        for (symbol, name) in &self.productions {
+            if rules_by_lhs.contains_key(symbol) {
+                shapes
+                    .entry(rules.shape(*symbol))
+                    .or_default()
+                    .push((*symbol, *name));
+            }
+        }
+        let mut duplicates = Vec::new();
+        for productions in shapes.values() {
+            for (i, (first, first_name)) in productions.iter().enumerate() {
+                for (second, second_name) in &productions[i + 1..] {
+                    if rules.same_rules(*first, *second, &mut Vec::new()) {
+                        let mut pair = [
+                            (self.production_span(*first), first_name.to_string()),
+                            (self.production_span(*second), second_name.to_string()),
+                        ];
+                        pair.sort();
+                        let [(first_span, first_name), (second_span, second_name)] = pair;
+                        duplicates.push(Duplicate::Production {
+                            names: [first_name, second_name],
+                            spans: [first_span, second_span],
+                        });
+                    }
+                }
+            }
+        }
+        self.duplicates.extend(duplicates);
+    }
+
+    fn production_span(&self, symbol: Symbol) -> SourceSpan {
+        self.rules
+            .iter()
+            .find(|rule| rule.lhs() == Some(symbol))
+            .map(|rule| rule.rule().span)
+            .unwrap()
+    }
+}
+
+// the symbols of each rule of a production, with anonymous non-terminals left out
+type Shape = Vec<Vec<Option<Symbol>>>;
+
+// compares the rules of non-terminals, looked up by their left-hand side
+struct SameRules<'a, 'rules> {
+    rules_by_lhs: &'a BTreeMap<Symbol, Vec<&'a [Symbol]>>,
+    anonymous_non_terminals: &'a BTreeMap<Symbol, AnonymousOrigin<'rules>>,
+}
+
+impl<'a, 'rules> SameRules<'a, 'rules> {
+    fn rules(&self, symbol: Symbol) -> &[&'a [Symbol]] {
+        self.rules_by_lhs.get(&symbol).map_or(&[], Vec::as_slice)
+    }
+
+    // the symbols of the rules, with the anonymous non-terminals left out, as those are compared
+    // by their own rules
+    fn shape(&self, symbol: Symbol) -> Shape {
+        self.rules(symbol)
+            .iter()
+            .map(|rhs| {
+                rhs.iter()
+                    .map(|symbol| {
+                        (!self.anonymous_non_terminals.contains_key(symbol)).then_some(*symbol)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn same_symbols(
+        &self,
+        first: &[Symbol],
+        second: &[Symbol],
+        assumed: &mut Vec<(Symbol, Symbol)>,
+    ) -> bool {
+        first.len() == second.len()
+            && first
+                .iter()
+                .zip(second)
+                .all(|(first, second)| self.same_symbol(*first, *second, assumed))
+    }
+
+    // anonymous non-terminals are the same if they are lowered from the same pattern, while
+    // named productions are only compared with each other as a whole
+    fn same_symbol(
+        &self,
+        first: Symbol,
+        second: Symbol,
+        assumed: &mut Vec<(Symbol, Symbol)>,
+    ) -> bool {
+        first == second
+            || assumed.contains(&(first, second))
+            || (self.anonymous_non_terminals.contains_key(&first)
+                && self.anonymous_non_terminals.contains_key(&second)
+                && self.same_rules(first, second, assumed))
+    }
+
+    // Non-terminals have the same rules if they produce the same symbols in the same order.
+    // The pairs which are compared already are assumed to be the same, so recursive rules
+    // terminate.
+    fn same_rules(
+        &self,
+        first: Symbol,
+        second: Symbol,
+        assumed: &mut Vec<(Symbol, Symbol)>,
+    ) -> bool {
+        assumed.push((first, second));
+        let (first_rules, second_rules) = (self.rules(first), self.rules(second));
+        first_rules.len() == second_rules.len()
+            && first_rules
+                .iter()
+                .zip(second_rules)
+                .all(|(first, second)| self.same_symbols(first, second, assumed))
+    }
+}
+
+// the span from the first to the last symbol of a pattern, if it contains any
+fn pattern_span(pattern: &ProductionPattern) -> Option<SourceSpan> {
+    match pattern {
+        ProductionPattern::Sequence { elements } | ProductionPattern::Alternative { elements } => {
+            let spans: Vec<SourceSpan> = elements.iter().filter_map(pattern_span).collect();
+            Some(SourceSpan {
+                start: spans.first()?.start,
+                end: spans.last()?.end,
+            })
+        }
+        ProductionPattern::OneOrMany { inner }
+        | ProductionPattern::ZeroOrMany { inner }
+        | ProductionPattern::Optional { inner }
+        | ProductionPattern::Group { inner }
+        | ProductionPattern::Annotated { inner, .. } => pattern_span(inner),
+        ProductionPattern::Rule { span, .. } => Some(*span),
+        ProductionPattern::Epsilon => None,
+    }
+}
+
+// the conflicts an annotated pattern allows, which also apply to the rule it becomes part of
+fn annotation<'a>(pattern: &'a ProductionPattern) -> &'a [ConflictKind] {
+    match pattern {
+        ProductionPattern::Annotated { allow, .. } => allow,
+        _ => &[],
+    }
+}
@@ -1,6 +1,9 @@
-mod util;
-
+pub mod analysis;
 pub mod grammar;
 mod grammar_builder;
 pub mod ll_parser;
 pub mod lr_parser;
+pub mod validation;
+
+#[cfg(test)]
+mod tests;
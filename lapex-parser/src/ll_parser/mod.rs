@@ -3,85 +3,33 @@ use std::error::Error;
 use std::fmt::{Display, Formatter};
 
 pub use codegen::LLParserCodeGen;
+pub use rewrite::{suggest_rewrites, Rewrite};
 
-use crate::grammar::{Grammar, GrammarError, Symbol, SymbolIdx};
-use crate::util::{compute_first_sets, get_first_terminals_of_sequence};
+use lapex_input::Spanned;
 
-mod codegen;
-
-fn get_follow_symbols_of_remainder(
-    lhs: Option<Symbol>,
-    remainder: &[Symbol],
-    first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
-    follow_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
-) -> BTreeSet<Symbol> {
-    let mut result_set = BTreeSet::new();
-    let remainder_first_set = get_first_terminals_of_sequence(remainder, first_sets);
-    let remainder_first_has_epsilon = remainder_first_set.contains(&Symbol::Epsilon);
-    let should_add_lhs_follow_set = remainder_first_has_epsilon || remainder.is_empty();
-    if should_add_lhs_follow_set {
-        let follow_set_of_lhs = follow_sets.get(&lhs.unwrap()).unwrap().clone();
-        result_set.extend(follow_set_of_lhs);
-    }
-    for remainder_first_symbol in remainder_first_set {
-        if remainder_first_symbol != Symbol::Epsilon {
-            result_set.insert(remainder_first_symbol);
-        }
-    }
+use crate::analysis::{compute_first_sets, compute_follow_sets, get_first_terminals_of_sequence};
+use crate::grammar::{Grammar, GrammarBuilder, GrammarError, Symbol, SymbolIdx};
+use rewrite::find_left_recursion;
 
-    result_set
-}
-
-fn compute_follow_sets(
-    grammar: &Grammar,
-    first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
-) -> BTreeMap<Symbol, BTreeSet<Symbol>> {
-    // init empty first sets
-    let mut follow_sets = BTreeMap::new();
-    for nt in grammar.non_terminals() {
-        follow_sets.insert(nt, BTreeSet::new());
-    }
-    // repeat until no more changes occur
-    let terminated_entry_point_rhs = vec![*grammar.entry_point(), Symbol::End];
-    loop {
-        let grammar_rules = grammar
-            .rules()
-            .iter()
-            .map(|r| (Some(r.lhs().unwrap()), r.rhs()));
-        let all_rules = std::iter::once((None, &terminated_entry_point_rhs)).chain(grammar_rules);
-        let mut inserted_any = false;
-        for (lhs, sequence) in all_rules {
-            for i in 0..sequence.len() {
-                let symbol = &sequence[i];
-                if let Symbol::NonTerminal(_) = symbol {
-                    let remainder = &sequence[i + 1..];
-                    let follow_symbols_for_remainder =
-                        get_follow_symbols_of_remainder(lhs, remainder, &first_sets, &follow_sets);
-                    let follow_set_of_nt = follow_sets.get_mut(symbol).unwrap();
-                    for follow_symbol in follow_symbols_for_remainder {
-                        let was_inserted = follow_set_of_nt.insert(follow_symbol);
-                        inserted_any = inserted_any || was_inserted;
-                    }
-                }
-            }
-        }
-        if !inserted_any {
-            break;
-        }
-    }
-
-    follow_sets
-}
+mod codegen;
+mod rewrite;
 
 #[derive(Debug, PartialEq)]
 pub enum LLParserError {
     InvalidParserTableEntry,
     ParserTableConflict {
         non_terminal: Symbol,
-        terminal: Symbol,
+        lookahead: Vec<Symbol>,
         production: Vec<Symbol>,
         existing_production: Vec<Symbol>,
     },
+    /// The productions of the path each start with the next one, leading back to the first
+    /// before any token is read, so no lookahead can tell how often to expand them. The path
+    /// starts at the production which comes first in the source.
+    LeftRecursion {
+        non_terminal: Symbol,
+        path: Vec<Spanned<String>>,
+    },
     GrammarError(GrammarError),
 }
 
@@ -99,95 +47,110 @@ impl Display for LLParserError {
     }
 }
 
+/// Maps a non-terminal and the upcoming tokens to the production to expand it with. A lookahead
+/// is shorter than the lookahead length only if it ends with the end of input.
 #[derive(Debug, PartialEq)]
 pub struct LLParserTable {
-    table: BTreeMap<(SymbolIdx, Option<SymbolIdx>), Vec<Symbol>>,
+    lookahead_length: usize,
+    table: BTreeMap<(SymbolIdx, Vec<Symbol>), Vec<Symbol>>,
 }
 
 impl LLParserTable {
-    fn new() -> Self {
+    fn new(lookahead_length: usize) -> Self {
         LLParserTable {
+            lookahead_length,
             table: BTreeMap::new(),
         }
     }
 
+    pub fn lookahead_length(&self) -> usize {
+        self.lookahead_length
+    }
+
     pub fn get_production(&self, non_terminal: Symbol, terminal: &Symbol) -> Option<&Vec<Symbol>> {
+        self.get_production_for_lookahead(non_terminal, std::slice::from_ref(terminal))
+    }
+
+    pub fn get_production_for_lookahead(
+        &self,
+        non_terminal: Symbol,
+        lookahead: &[Symbol],
+    ) -> Option<&Vec<Symbol>> {
         if let Symbol::NonTerminal(non_terminal_index) = non_terminal {
-            match terminal {
-                Symbol::Terminal(terminal_index) => {
-                    return self
-                        .table
-                        .get(&(non_terminal_index, Some(terminal_index + 1)));
-                }
-                Symbol::End => {
-                    return self.table.get(&(non_terminal_index, None));
-                }
-                _ => (),
-            }
+            return self.table.get(&(non_terminal_index, lookahead.to_vec()));
         }
         None
     }
 
-    fn check_for_conflict_and_insert(
+    /// The lookaheads of a non-terminal with their productions, ordered by lookahead.
+    pub fn productions_of(
+        &self,
+        non_terminal: Symbol,
+    ) -> impl Iterator<Item = (&[Symbol], &Vec<Symbol>)> {
+        self.table
+            .iter()
+            .filter(move |((index, _), _)| Symbol::NonTerminal(*index) == non_terminal)
+            .map(|((_, lookahead), production)| (lookahead.as_slice(), production))
+    }
+
+    fn insert(
         &mut self,
-        non_terminal_index: SymbolIdx,
-        terminal_index: Option<SymbolIdx>,
+        non_terminal: Symbol,
+        lookahead: Vec<Symbol>,
         production: Vec<Symbol>,
     ) -> Result<(), LLParserError> {
-        let table_key = (non_terminal_index, terminal_index);
+        let non_terminal_index = match non_terminal {
+            Symbol::NonTerminal(index) => index,
+            _ => return Err(LLParserError::InvalidParserTableEntry),
+        };
+        if !lookahead
+            .iter()
+            .all(|symbol| matches!(symbol, Symbol::Terminal(_) | Symbol::End))
+        {
+            return Err(LLParserError::InvalidParserTableEntry);
+        }
+        let table_key = (non_terminal_index, lookahead);
         if let Some(prev_production) = self.table.get(&table_key) {
             return Err(LLParserError::ParserTableConflict {
-                non_terminal: Symbol::NonTerminal(non_terminal_index),
-                terminal: match terminal_index {
-                    Some(terminal_index) => Symbol::Terminal(terminal_index - 1),
-                    None => Symbol::End,
-                },
-                production: production.clone(),
+                non_terminal,
+                lookahead: table_key.1,
+                production,
                 existing_production: prev_production.clone(),
             });
         }
-        let prev_entry = self.table.insert(table_key, production);
-        assert!(prev_entry.is_none());
+        self.table.insert(table_key, production);
         Ok(())
     }
+}
 
-    fn insert(
-        &mut self,
-        non_terminal: Symbol,
-        terminal: Symbol,
-        production: Vec<Symbol>,
-    ) -> Result<(), LLParserError> {
-        if let Symbol::NonTerminal(non_terminal_index) = non_terminal {
-            match terminal {
-                Symbol::Terminal(terminal_index) => {
-                    self.check_for_conflict_and_insert(
-                        non_terminal_index,
-                        Some(terminal_index + 1),
-                        production,
-                    )?;
-                    return Ok(());
-                }
-                Symbol::End => {
-                    self.check_for_conflict_and_insert(non_terminal_index, None, production)?;
-                    return Ok(());
-                }
-                _ => (),
-            }
-        }
-        Err(LLParserError::InvalidParserTableEntry)
+// left recursion always ends in a table conflict, but one which does not name the productions
+// causing it
+fn check_left_recursion(grammar: &Grammar) -> Result<(), LLParserError> {
+    let first_sets = compute_first_sets(grammar);
+    let recursion = grammar
+        .non_terminals()
+        .filter_map(|non_terminal| {
+            let rules = find_left_recursion(grammar, non_terminal, &first_sets)?;
+            Some((non_terminal, GrammarBuilder::cycle_path(rules)))
+        })
+        .min_by_key(|(_, path)| path.first().map(|production| production.span));
+    match recursion {
+        Some((non_terminal, path)) => Err(LLParserError::LeftRecursion { non_terminal, path }),
+        None => Ok(()),
     }
 }
 
 pub fn generate_table(grammar: &Grammar) -> Result<LLParserTable, LLParserError> {
+    check_left_recursion(grammar)?;
     let first_sets = compute_first_sets(&grammar);
     let follow_sets = compute_follow_sets(&grammar, &first_sets);
-    let mut parser_table = LLParserTable::new();
+    let mut parser_table = LLParserTable::new(1);
     for rule in grammar.rules() {
         let first_set_of_rhs = get_first_terminals_of_sequence(rule.rhs(), &first_sets);
         for symbol in first_set_of_rhs.iter() {
             match symbol {
                 Symbol::End | Symbol::Terminal(_) => {
-                    parser_table.insert(rule.lhs().unwrap(), *symbol, rule.rhs().clone())?;
+                    parser_table.insert(rule.lhs().unwrap(), vec![*symbol], rule.rhs().clone())?;
                 }
                 _ => (),
             }
@@ -197,7 +160,11 @@ pub fn generate_table(grammar: &Grammar) -> Result<LLParserTable, LLParserError>
             for symbol in follow_set_of_lhs.iter() {
                 match symbol {
                     Symbol::End | Symbol::Terminal(_) => {
-                        parser_table.insert(rule.lhs().unwrap(), *symbol, rule.rhs().clone())?;
+                        parser_table.insert(
+                            rule.lhs().unwrap(),
+                            vec![*symbol],
+                            rule.rhs().clone(),
+                        )?;
                     }
                     _ => (),
                 }
@@ -207,5 +174,140 @@ pub fn generate_table(grammar: &Grammar) -> Result<LLParserTable, LLParserError>
     Ok(parser_table)
 }
 
+// concatenates every string of the left set with every string of the right set, cutting the
+// results off after k symbols. strings ending with the end of input are not extended.
+fn concat_k(
+    left: &BTreeSet<Vec<Symbol>>,
+    right: &BTreeSet<Vec<Symbol>>,
+    k: usize,
+) -> BTreeSet<Vec<Symbol>> {
+    let mut result = BTreeSet::new();
+    if right.is_empty() {
+        return result;
+    }
+    for prefix in left {
+        if prefix.len() >= k || prefix.last() == Some(&Symbol::End) {
+            result.insert(prefix.clone());
+            continue;
+        }
+        for suffix in right {
+            let mut string = prefix.clone();
+            string.extend(suffix.iter().take(k - prefix.len()));
+            result.insert(string);
+        }
+    }
+    result
+}
+
+fn get_first_k_of_sequence(
+    sequence: &[Symbol],
+    first_sets: &BTreeMap<Symbol, BTreeSet<Vec<Symbol>>>,
+    k: usize,
+) -> BTreeSet<Vec<Symbol>> {
+    let mut result = BTreeSet::from([Vec::new()]);
+    for symbol in sequence {
+        let terminal_set;
+        let symbol_set = match symbol {
+            Symbol::Epsilon => continue,
+            Symbol::NonTerminal(_) => first_sets.get(symbol).unwrap(),
+            _ => {
+                terminal_set = BTreeSet::from([vec![*symbol]]);
+                &terminal_set
+            }
+        };
+        result = concat_k(&result, symbol_set, k);
+        if result.iter().all(|string| string.len() >= k) {
+            break;
+        }
+    }
+    result
+}
+
+fn compute_first_k_sets(grammar: &Grammar, k: usize) -> BTreeMap<Symbol, BTreeSet<Vec<Symbol>>> {
+    let mut first_sets: BTreeMap<Symbol, BTreeSet<Vec<Symbol>>> = grammar
+        .non_terminals()
+        .map(|nt| (nt, BTreeSet::new()))
+        .collect();
+    // repeat until no more changes occur
+    loop {
+        let mut inserted_any = false;
+        for rule in grammar.rules() {
+            let first_for_rhs = get_first_k_of_sequence(rule.rhs(), &first_sets, k);
+            let first_set_of_lhs = first_sets.get_mut(&rule.lhs().unwrap()).unwrap();
+            for string in first_for_rhs {
+                inserted_any |= first_set_of_lhs.insert(string);
+            }
+        }
+        if !inserted_any {
+            break;
+        }
+    }
+    first_sets
+}
+
+fn compute_follow_k_sets(
+    grammar: &Grammar,
+    first_sets: &BTreeMap<Symbol, BTreeSet<Vec<Symbol>>>,
+    k: usize,
+) -> BTreeMap<Symbol, BTreeSet<Vec<Symbol>>> {
+    let mut follow_sets: BTreeMap<Symbol, BTreeSet<Vec<Symbol>>> = grammar
+        .non_terminals()
+        .map(|nt| (nt, BTreeSet::new()))
+        .collect();
+    follow_sets
+        .get_mut(grammar.entry_point())
+        .unwrap()
+        .insert(vec![Symbol::End]);
+    // repeat until no more changes occur
+    loop {
+        let mut inserted_any = false;
+        for rule in grammar.rules() {
+            let lhs = rule.lhs().unwrap();
+            let sequence = rule.rhs();
+            for (i, symbol) in sequence.iter().enumerate() {
+                if let Symbol::NonTerminal(_) = symbol {
+                    let remainder_first =
+                        get_first_k_of_sequence(&sequence[i + 1..], first_sets, k);
+                    let follow_strings =
+                        concat_k(&remainder_first, follow_sets.get(&lhs).unwrap(), k);
+                    let follow_set_of_nt = follow_sets.get_mut(symbol).unwrap();
+                    for string in follow_strings {
+                        inserted_any |= follow_set_of_nt.insert(string);
+                    }
+                }
+            }
+        }
+        if !inserted_any {
+            break;
+        }
+    }
+    follow_sets
+}
+
+/// Generates a strong LL(k) table, which chooses productions by the next `lookahead_length`
+/// tokens. A lookahead length of 1 results in the same table as [`generate_table`].
+pub fn generate_table_k(
+    grammar: &Grammar,
+    lookahead_length: usize,
+) -> Result<LLParserTable, LLParserError> {
+    check_left_recursion(grammar)?;
+    let first_sets = compute_first_k_sets(grammar, lookahead_length);
+    let follow_sets = compute_follow_k_sets(grammar, &first_sets, lookahead_length);
+    let mut parser_table = LLParserTable::new(lookahead_length);
+    for rule in grammar.rules() {
+        let lhs = rule.lhs().unwrap();
+        let first_of_rhs = get_first_k_of_sequence(rule.rhs(), &first_sets, lookahead_length);
+        let lookaheads = concat_k(
+            &first_of_rhs,
+            follow_sets.get(&lhs).unwrap(),
+            lookahead_length,
+        );
+        for lookahead in lookaheads {
+            parser_table.insert(lhs, lookahead, rule.rhs().clone())?;
+        }
+    }
+    Ok(parser_table)
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::analysis::{compute_first_sets, compute_follow_sets, get_first_terminals_of_sequence};
+use crate::grammar::{Grammar, Rule, Symbol};
+
+use super::LLParserError;
+
+/// A change to the grammar which would resolve an LL(1) conflict.
+#[derive(Debug, PartialEq)]
+pub enum Rewrite<'grammar> {
+    /// The conflicting rules start with the same symbols, which can be factored out into a
+    /// single rule followed by the alternative remainders.
+    LeftFactor {
+        rules: [&'grammar Rule<'grammar>; 2],
+        prefix: Vec<Symbol>,
+    },
+    /// Each rule starts with the non-terminal of the next one, leading back to the
+    /// non-terminal of the first rule without consuming a token. The recursion can be replaced
+    /// with a repetition.
+    LeftRecursion {
+        rules: Vec<&'grammar Rule<'grammar>>,
+    },
+    /// The rule can derive nothing, while the lookahead can also follow its non-terminal.
+    EmptyAlternative {
+        rule: &'grammar Rule<'grammar>,
+        lookahead: Symbol,
+    },
+}
+
+fn find_rule<'grammar>(
+    grammar: &'grammar Grammar<'grammar>,
+    non_terminal: Symbol,
+    rhs: &[Symbol],
+) -> Option<&'grammar Rule<'grammar>> {
+    grammar
+        .rules()
+        .iter()
+        .find(|rule| rule.lhs() == Some(non_terminal) && rule.rhs().as_slice() == rhs)
+}
+
+// searches the rules which start with a non-terminal (possibly after symbols which derive
+// nothing) for a path leading back to the given non-terminal
+pub(super) fn find_left_recursion<'grammar>(
+    grammar: &'grammar Grammar<'grammar>,
+    non_terminal: Symbol,
+    first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
+) -> Option<Vec<&'grammar Rule<'grammar>>> {
+    let mut reached_by: BTreeMap<Symbol, &'grammar Rule<'grammar>> = BTreeMap::new();
+    let mut queue = VecDeque::from([non_terminal]);
+    while let Some(current) = queue.pop_front() {
+        for rule in grammar.rules() {
+            if rule.lhs() != Some(current) {
+                continue;
+            }
+            for (i, symbol) in rule.rhs().iter().enumerate() {
+                if let Symbol::NonTerminal(_) = symbol {
+                    if *symbol == non_terminal {
+                        let mut path = vec![rule];
+                        let mut lhs = current;
+                        while lhs != non_terminal {
+                            let previous = reached_by[&lhs];
+                            path.push(previous);
+                            lhs = previous.lhs().unwrap();
+                        }
+                        path.reverse();
+                        return Some(path);
+                    }
+                    if !reached_by.contains_key(symbol) {
+                        reached_by.insert(*symbol, rule);
+                        queue.push_back(*symbol);
+                    }
+                }
+                let prefix_is_nullable =
+                    get_first_terminals_of_sequence(&rule.rhs()[..=i], first_sets)
+                        .contains(&Symbol::Epsilon);
+                if !prefix_is_nullable {
+                    break;
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Looks for common causes of an LL(1) table conflict and suggests how to rewrite the grammar
+/// to avoid them. Left recursion is suggested to be rewritten as well, other errors result in
+/// no suggestions.
+pub fn suggest_rewrites<'grammar>(
+    grammar: &'grammar Grammar<'grammar>,
+    error: &LLParserError,
+) -> Vec<Rewrite<'grammar>> {
+    let (non_terminal, lookahead, production, existing_production) = match error {
+        LLParserError::ParserTableConflict {
+            non_terminal,
+            lookahead,
+            production,
+            existing_production,
+        } => (*non_terminal, lookahead, production, existing_production),
+        LLParserError::LeftRecursion { non_terminal, .. } => {
+            let first_sets = compute_first_sets(grammar);
+            return find_left_recursion(grammar, *non_terminal, &first_sets)
+                .map(|rules| Rewrite::LeftRecursion { rules })
+                .into_iter()
+                .collect();
+        }
+        _ => return Vec::new(),
+    };
+    let first_sets = compute_first_sets(grammar);
+    let mut rewrites = Vec::new();
+
+    if let Some(rules) = find_left_recursion(grammar, non_terminal, &first_sets) {
+        rewrites.push(Rewrite::LeftRecursion { rules });
+    }
+
+    let conflicting_rules = (
+        find_rule(grammar, non_terminal, existing_production),
+        find_rule(grammar, non_terminal, production),
+    );
+    if let (Some(existing_rule), Some(rule)) = conflicting_rules {
+        let prefix: Vec<Symbol> = existing_production
+            .iter()
+            .zip(production.iter())
+            .take_while(|(a, b)| a == b && **a != Symbol::Epsilon)
+            .map(|(symbol, _)| *symbol)
+            .collect();
+        if !prefix.is_empty() {
+            rewrites.push(Rewrite::LeftFactor {
+                rules: [existing_rule, rule],
+                prefix,
+            });
+        }
+
+        let follow_sets = compute_follow_sets(grammar, &first_sets);
+        let follows_non_terminal = lookahead
+            .first()
+            .is_some_and(|symbol| follow_sets[&non_terminal].contains(symbol));
+        for rule in [existing_rule, rule] {
+            let is_nullable =
+                get_first_terminals_of_sequence(rule.rhs(), &first_sets).contains(&Symbol::Epsilon);
+            if is_nullable && follows_non_terminal {
+                rewrites.push(Rewrite::EmptyAlternative {
+                    rule,
+                    lookahead: lookahead[0],
+                });
+            }
+        }
+    }
+    rewrites
+}
@@ -1 +1,349 @@
+use lapex_input::{
+    EntryRule, ProductionPattern, ProductionRule, RuleSet, SourcePos, SourceSpan, Spanned,
+    TokenPattern, TokenRule,
+};
 
+use crate::{
+    grammar::{Grammar, GrammarBuilder, Symbol},
+    ll_parser::{generate_table, generate_table_k, suggest_rewrites, LLParserError, Rewrite},
+};
+
+fn token<'src>(name: &'src str, characters: &str) -> Spanned<TokenRule<'src>> {
+    Spanned::zero(TokenRule {
+        name: name.into(),
+        precedence: None,
+        pattern: TokenPattern::Literal {
+            characters: characters.chars().collect(),
+        },
+        skip: false,
+        doc: None,
+    })
+}
+
+fn rule(rule_name: &str) -> ProductionPattern<'_> {
+    ProductionPattern::Rule {
+        rule_name,
+        span: SourceSpan::zero(),
+    }
+}
+
+fn terminal(grammar: &Grammar, name: &str) -> Symbol {
+    grammar
+        .terminals_with_names()
+        .find(|(_, token_name)| *token_name == name)
+        .map(|(symbol, _)| symbol)
+        .unwrap()
+}
+
+// s = (id dot)? id;
+fn qualified_name_rule_set() -> RuleSet<'static> {
+    RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("id", "x"), token("dot", ".")],
+        vec![Spanned::zero(ProductionRule {
+            name: "s",
+            tag: None,
+            pattern: ProductionPattern::Sequence {
+                elements: vec![
+                    ProductionPattern::Optional {
+                        inner: Box::new(ProductionPattern::Sequence {
+                            elements: vec![rule("id"), rule("dot")],
+                        }),
+                    },
+                    rule("id"),
+                ],
+            },
+            doc: None,
+        })],
+    )
+}
+
+#[test]
+fn test_ll1_conflict_on_optional_qualifier() {
+    let rule_set = qualified_name_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let id = terminal(&grammar, "id");
+    match generate_table(&grammar) {
+        Err(LLParserError::ParserTableConflict { lookahead, .. }) => {
+            assert_eq!(lookahead, vec![id])
+        }
+        result => panic!("expected a conflict, got {:?}", result),
+    }
+    assert!(matches!(
+        generate_table_k(&grammar, 1),
+        Err(LLParserError::ParserTableConflict { .. })
+    ));
+}
+
+#[test]
+fn test_ll2_resolves_optional_qualifier() {
+    let rule_set = qualified_name_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let id = terminal(&grammar, "id");
+    let dot = terminal(&grammar, "dot");
+    let table = generate_table_k(&grammar, 2).unwrap();
+    assert_eq!(table.lookahead_length(), 2);
+
+    let entry = *grammar.entry_point();
+    let lookaheads: Vec<&[Symbol]> = table
+        .productions_of(entry)
+        .map(|(lookahead, _)| lookahead)
+        .collect();
+    assert_eq!(lookaheads.len(), 2);
+    assert!(lookaheads.contains(&[id, dot].as_slice()));
+    assert!(lookaheads.contains(&[id, Symbol::End].as_slice()));
+
+    // the optional qualifier is only taken if a dot follows the identifier
+    let optional = table
+        .get_production_for_lookahead(entry, &[id, dot])
+        .unwrap()[0];
+    assert_eq!(
+        table.get_production_for_lookahead(optional, &[id, dot]),
+        Some(&vec![id, dot])
+    );
+    assert_eq!(
+        table.get_production_for_lookahead(optional, &[id, Symbol::End]),
+        Some(&vec![Symbol::Epsilon])
+    );
+}
+
+#[test]
+fn test_ll_k_with_k_1_matches_ll1() {
+    // s = a* b?;
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b")],
+        vec![Spanned::zero(ProductionRule {
+            name: "s",
+            tag: None,
+            pattern: ProductionPattern::Sequence {
+                elements: vec![
+                    ProductionPattern::ZeroOrMany {
+                        inner: Box::new(rule("a")),
+                    },
+                    ProductionPattern::Optional {
+                        inner: Box::new(rule("b")),
+                    },
+                ],
+            },
+            doc: None,
+        })],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert_eq!(
+        generate_table(&grammar).unwrap(),
+        generate_table_k(&grammar, 1).unwrap()
+    );
+}
+
+fn production<'src>(
+    name: &'src str,
+    pattern: ProductionPattern<'src>,
+) -> Spanned<ProductionRule<'src>> {
+    Spanned::zero(ProductionRule {
+        name,
+        tag: None,
+        pattern,
+        doc: None,
+    })
+}
+
+fn sequence<'src>(names: &[&'src str]) -> ProductionPattern<'src> {
+    ProductionPattern::Sequence {
+        elements: names.iter().map(|name| rule(name)).collect(),
+    }
+}
+
+#[test]
+fn test_suggest_left_factoring() {
+    // s = a b | a c;
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b"), token("c", "c")],
+        vec![production(
+            "s",
+            ProductionPattern::Alternative {
+                elements: vec![sequence(&["a", "b"]), sequence(&["a", "c"])],
+            },
+        )],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let error = generate_table(&grammar).unwrap_err();
+    match suggest_rewrites(&grammar, &error).as_slice() {
+        [Rewrite::LeftFactor { prefix, .. }] => assert_eq!(prefix, &vec![terminal(&grammar, "a")]),
+        rewrites => panic!("expected left factoring, got {:?}", rewrites),
+    }
+}
+
+#[test]
+fn test_suggest_removing_left_recursion() {
+    // s = s a | b;
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b")],
+        vec![production(
+            "s",
+            ProductionPattern::Alternative {
+                elements: vec![sequence(&["s", "a"]), rule("b")],
+            },
+        )],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let error = generate_table(&grammar).unwrap_err();
+    let rewrites = suggest_rewrites(&grammar, &error);
+    match rewrites.as_slice() {
+        [Rewrite::LeftRecursion { rules }] => {
+            let first = rules.first().unwrap();
+            let last = rules.last().unwrap();
+            assert_eq!(
+                last.rhs().first().copied(),
+                first.lhs(),
+                "the recursion leads back to its start"
+            );
+        }
+        rewrites => panic!("expected left recursion, got {:?}", rewrites),
+    }
+}
+
+#[test]
+fn test_suggest_for_empty_alternative() {
+    let rule_set = qualified_name_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let error = generate_table(&grammar).unwrap_err();
+    match suggest_rewrites(&grammar, &error).as_slice() {
+        [Rewrite::EmptyAlternative { rule, lookahead }] => {
+            assert_eq!(rule.rhs(), &vec![Symbol::Epsilon]);
+            assert_eq!(*lookahead, terminal(&grammar, "id"));
+        }
+        rewrites => panic!("expected an empty alternative, got {:?}", rewrites),
+    }
+}
+
+fn production_on_line<'src>(
+    line: u16,
+    name: &'src str,
+    pattern: ProductionPattern<'src>,
+) -> Spanned<ProductionRule<'src>> {
+    Spanned::new(
+        SourceSpan {
+            start: SourcePos { line, col: 1 },
+            end: SourcePos { line, col: 2 },
+        },
+        ProductionRule {
+            name,
+            tag: None,
+            pattern,
+            doc: None,
+        },
+    )
+}
+
+// s = t a | b;
+// t = s c | d;
+fn indirect_left_recursion_rule_set() -> RuleSet<'static> {
+    RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![
+            token("a", "a"),
+            token("b", "b"),
+            token("c", "c"),
+            token("d", "d"),
+        ],
+        vec![
+            production_on_line(
+                2,
+                "t",
+                ProductionPattern::Alternative {
+                    elements: vec![sequence(&["s", "c"]), rule("d")],
+                },
+            ),
+            production_on_line(
+                1,
+                "s",
+                ProductionPattern::Alternative {
+                    elements: vec![sequence(&["t", "a"]), rule("b")],
+                },
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_left_recursion_error() {
+    let rule_set = indirect_left_recursion_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    for result in [generate_table(&grammar), generate_table_k(&grammar, 2)] {
+        match result {
+            Err(LLParserError::LeftRecursion { path, .. }) => {
+                let names: Vec<&str> = path.iter().map(|name| name.inner.as_str()).collect();
+                assert_eq!(names, ["s", "t"]);
+                assert_eq!(path[0].span.start.line, 1);
+            }
+            result => panic!("expected left recursion, got {:?}", result),
+        }
+    }
+    let error = generate_table(&grammar).unwrap_err();
+    assert!(matches!(
+        suggest_rewrites(&grammar, &error).as_slice(),
+        [Rewrite::LeftRecursion { .. }]
+    ));
+}
+
+#[test]
+fn test_left_recursion_elimination() {
+    // expr = expr plus num | num;
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "expr" }),
+        vec![token("plus", "+"), token("num", "1")],
+        vec![production(
+            "expr",
+            ProductionPattern::Alternative {
+                elements: vec![sequence(&["expr", "plus", "num"]), rule("num")],
+            },
+        )],
+    );
+    let grammar = GrammarBuilder::from_rule_set(&rule_set, false)
+        .unwrap()
+        .with_left_recursion_elimination(true)
+        .build()
+        .unwrap();
+    let table = generate_table(&grammar).unwrap();
+    let plus = terminal(&grammar, "plus");
+    let num = terminal(&grammar, "num");
+    // the recursion became a repetition of `plus num`, which ends with the input
+    let alternative = table.get_production(*grammar.entry_point(), &num).unwrap()[0];
+    let repetition = *table
+        .get_production(alternative, &num)
+        .unwrap()
+        .last()
+        .unwrap();
+    assert_eq!(
+        table.get_production(repetition, &plus),
+        Some(&vec![plus, num, repetition])
+    );
+    assert_eq!(
+        table.get_production(repetition, &Symbol::End),
+        Some(&vec![Symbol::Epsilon])
+    );
+
+    // the rules of `t` are substituted into `s`, which leaves `t` without rules
+    let rule_set = indirect_left_recursion_rule_set();
+    let grammar = GrammarBuilder::from_rule_set(&rule_set, false)
+        .unwrap()
+        .with_left_recursion_elimination(true)
+        .build()
+        .unwrap();
+    generate_table(&grammar).unwrap();
+    let t = grammar
+        .non_terminals()
+        .find(|symbol| grammar.get_production_name(symbol) == Some("t"))
+        .unwrap();
+    assert!(grammar.rules().iter().all(|rule| rule.lhs() != Some(t)));
+
+    // without the option, the grammar stays as it is
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert!(matches!(
+        generate_table(&grammar),
+        Err(LLParserError::LeftRecursion { .. })
+    ));
+}
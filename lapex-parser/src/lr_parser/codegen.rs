@@ -11,4 +11,15 @@ pub trait LRParserCodeGen {
         parser_table: &ActionGotoTable,
         gen: &mut GeneratedCodeWriter,
     );
+
+    /// Whether the generated parser recovers from errors using the error productions or the sync
+    /// tokens of the grammar.
+    fn supports_error_recovery(&self) -> bool {
+        false
+    }
+
+    /// Whether the generated parser provides a token filter for the insertion rules of the grammar.
+    fn supports_token_insertion(&self) -> bool {
+        false
+    }
 }
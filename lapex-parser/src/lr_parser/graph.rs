@@ -0,0 +1,76 @@
+use std::{collections::BTreeMap, io::Write};
+
+use petgraph::{dot::Dot, graph::NodeIndex, prelude::DiGraph, Graph};
+
+use crate::grammar::{Grammar, Rule};
+
+use super::{ActionGotoTable, TableEntry};
+
+/// Writes the states of the table as a Graphviz graph, with an edge for every shift and goto
+/// labeled with its symbol. Each state lists the rules it reduces with their lookaheads, and the
+/// states with a conflict are drawn in red. The states are numbered like in the conflicts.
+pub fn output_graph<'grammar, 'rules>(
+    grammar: &'grammar Grammar,
+    table: &ActionGotoTable<'grammar, 'rules>,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut reductions = vec![BTreeMap::new(); table.state_count];
+    let mut conflicts = vec![false; table.state_count];
+    for ((state, symbol), entries) in &table.entries {
+        conflicts[*state] |= entries.len() > 1;
+        for entry in entries {
+            if let TableEntry::Reduce { rule } = entry {
+                reductions[*state]
+                    .entry(*rule as *const Rule)
+                    .or_insert_with(|| (*rule, Vec::new()))
+                    .1
+                    .push(grammar.get_symbol_name(symbol));
+            }
+        }
+    }
+
+    let mut graph: Graph<String, String> = DiGraph::new();
+    for (state, reductions) in reductions.iter().enumerate() {
+        let mut label = format!("state {}", state);
+        for (rule, lookaheads) in reductions.values() {
+            label.push_str(&format!(
+                "\n{} • [{}]",
+                rule.display(grammar),
+                lookaheads.join(", ")
+            ));
+        }
+        graph.add_node(label);
+    }
+    let mut accept = None;
+    for ((state, symbol), entries) in &table.entries {
+        for entry in entries {
+            let target = match entry {
+                TableEntry::Shift { target } => NodeIndex::new(*target),
+                TableEntry::Accept => {
+                    *accept.get_or_insert_with(|| graph.add_node(String::from("accept")))
+                }
+                TableEntry::Reduce { .. } | TableEntry::Error => continue,
+            };
+            graph.add_edge(
+                NodeIndex::new(*state),
+                target,
+                grammar.get_symbol_name(symbol),
+            );
+        }
+    }
+
+    let node_attributes = |_, (index, _): (NodeIndex, &String)| {
+        if Some(index) == accept {
+            String::from("peripheries = 2")
+        } else if conflicts[index.index()] {
+            String::from("color = red, shape = box")
+        } else {
+            String::from("shape = box")
+        }
+    };
+    write!(
+        output,
+        "{}",
+        Dot::with_attr_getters(&graph, &[], &|_, _| String::new(), &node_attributes)
+    )
+}
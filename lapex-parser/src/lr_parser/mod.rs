@@ -1,23 +1,33 @@
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
     fmt::Display,
     io::Write,
 };
 
+use lapex_input::ConflictKind;
 use petgraph::{graph::NodeIndex, prelude::DiGraph, visit::EdgeRef, Direction::Outgoing, Graph};
 
 use crate::{
+    analysis::{compute_first_sets, get_first_terminals_of_sequence},
     grammar::{Grammar, Rule, Symbol},
-    util::{compute_first_sets, get_first_terminals_of_sequence},
 };
 
 use self::bidimap::BidiMap;
 
 mod bidimap;
 mod codegen;
+mod graph;
 mod item;
+mod profile;
+mod serialize;
+mod simulate;
+mod verify;
 
 pub use codegen::LRParserCodeGen;
+pub use graph::output_graph;
+pub use profile::{RuleCoverage, StateProfile};
+pub use simulate::{ParseFailure, ParseStep, ParseTrace};
+pub use verify::TableDefect;
 
 use item::Item;
 
@@ -85,7 +95,7 @@ fn determine_lookaheads_to_expand<const N: usize>(
     let lookaheads = if N > 0 {
         if let Some(follow_symbol) = follow_symbol {
             match follow_symbol {
-                t @ Symbol::Terminal(_) => vec![[t; N]],
+                t @ (Symbol::Terminal(_) | Symbol::Error) => vec![[t; N]],
                 Symbol::NonTerminal(_) => {
                     let remaining_rhs: Vec<Symbol> = item
                         .symbols_following_symbol_after_dot()
@@ -110,6 +120,7 @@ struct ParserGraph<'grammar: 'rules, 'rules, const N: usize> {
     lr0_core_map: HashMap<ItemSet<'grammar, 'rules, 0>, NodeIndex>,
     graph: Graph<(), Symbol>,
     entry_state: Option<NodeIndex>,
+    items_allocated: usize,
 }
 
 impl<'grammar, 'rules, const N: usize> ParserGraph<'grammar, 'rules, N> {
@@ -119,6 +130,7 @@ impl<'grammar, 'rules, const N: usize> ParserGraph<'grammar, 'rules, N> {
             lr0_core_map: HashMap::new(),
             graph: DiGraph::new(),
             entry_state: None,
+            items_allocated: 0,
         }
     }
 
@@ -167,7 +179,8 @@ fn generate_parser_graph<'grammar: 'rules, 'rules, const N: usize>(
     grammar: &'grammar Grammar<'rules>,
     first_sets: &BTreeMap<Symbol, BTreeSet<Symbol>>,
     lalr: bool,
-) -> ParserGraph<'grammar, 'rules, N> {
+    is_cancelled: &dyn Fn() -> bool,
+) -> Option<ParserGraph<'grammar, 'rules, N>> {
     let entry_item = Item::new(grammar.entry_rule(), [Symbol::End; N]);
 
     let rules_map = build_rules_map(grammar);
@@ -180,8 +193,12 @@ fn generate_parser_graph<'grammar: 'rules, 'rules, const N: usize>(
 
     let mut unprocessed_states = Vec::new();
     unprocessed_states.push(entry_state);
+    let mut items_allocated = entry_item_set.len();
 
     while let Some(start_state) = unprocessed_states.pop() {
+        if is_cancelled() {
+            return None;
+        }
         let item_set = parser_graph.get_item_set(&start_state).unwrap();
         let mut transition_map: BTreeMap<Symbol, ItemHashSet<'grammar, 'rules, N>> =
             BTreeMap::new();
@@ -192,6 +209,7 @@ fn generate_parser_graph<'grammar: 'rules, 'rules, const N: usize>(
                     target_item.advance_dot();
                     let target_item_set =
                         expand_item(target_item, first_sets, &rules_map, &mut item_set_cache);
+                    items_allocated += target_item_set.len();
                     let transition_set = transition_map
                         .entry(transition_symbol)
                         .or_insert(ItemHashSet::new());
@@ -230,7 +248,8 @@ fn generate_parser_graph<'grammar: 'rules, 'rules, const N: usize>(
             }
         }
     }
-    parser_graph
+    parser_graph.items_allocated = items_allocated;
+    Some(parser_graph)
 }
 
 fn build_rules_map<'grammar: 'rules, 'rules>(
@@ -264,22 +283,103 @@ fn merge_into_state<'grammar: 'rules, 'rules, const N: usize>(
     })
 }
 
+/// An item of a state together with all lookaheads it appears with. The lookaheads are empty
+/// for LR(0) items.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LookaheadItem<'grammar, 'rules> {
+    pub item: Item<'grammar, 'rules, 0>,
+    pub lookaheads: BTreeSet<Symbol>,
+}
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Conflict<'grammar, 'rules> {
     ShiftReduce {
         state: usize,
         item_to_reduce: Item<'grammar, 'rules, 0>,
         shift_symbol: Symbol,
+        /// The lookaheads the item is reduced on in this state.
+        lookaheads: BTreeSet<Symbol>,
+        item_set: Vec<LookaheadItem<'grammar, 'rules>>,
     },
     ReduceReduce {
         state: usize,
         items: Vec<Item<'grammar, 'rules, 0>>,
+        /// The lookaheads on which all of the items can be reduced.
+        lookaheads: BTreeSet<Symbol>,
+        item_set: Vec<LookaheadItem<'grammar, 'rules>>,
     },
 }
 
+impl<'grammar, 'rules> Conflict<'grammar, 'rules> {
+    pub fn state(&self) -> usize {
+        match self {
+            Conflict::ShiftReduce { state, .. } | Conflict::ReduceReduce { state, .. } => *state,
+        }
+    }
+
+    /// The items of the conflicting state, in the state the conflict was found in.
+    pub fn item_set(&self) -> &[LookaheadItem<'grammar, 'rules>] {
+        match self {
+            Conflict::ShiftReduce { item_set, .. } | Conflict::ReduceReduce { item_set, .. } => {
+                item_set
+            }
+        }
+    }
+
+    /// Whether a rule involved in the conflict allows its kind with `@allow(...)`. A shift-reduce
+    /// conflict is allowed by the reduced rule or by a rule which shifts the symbol, a
+    /// reduce-reduce conflict by any of the reduced rules.
+    pub fn is_allowed(&self) -> bool {
+        match self {
+            Conflict::ShiftReduce {
+                item_to_reduce,
+                shift_symbol,
+                item_set,
+                ..
+            } => {
+                item_to_reduce.rule().allows(ConflictKind::ShiftReduce)
+                    || item_set.iter().any(|item| {
+                        item.item.symbol_after_dot() == Some(*shift_symbol)
+                            && item.item.rule().allows(ConflictKind::ShiftReduce)
+                    })
+            }
+            Conflict::ReduceReduce { items, .. } => items
+                .iter()
+                .any(|item| item.rule().allows(ConflictKind::ReduceReduce)),
+        }
+    }
+
+    fn renumber_state(&mut self, new_index: &[usize]) {
+        match self {
+            Conflict::ShiftReduce { state, .. } | Conflict::ReduceReduce { state, .. } => {
+                *state = new_index[*state]
+            }
+        }
+    }
+}
+
+fn lookahead_items<'grammar, 'rules, const N: usize>(
+    item_set: &ItemSet<'grammar, 'rules, N>,
+) -> Vec<LookaheadItem<'grammar, 'rules>> {
+    let mut lookaheads: BTreeMap<Item<'grammar, 'rules, 0>, BTreeSet<Symbol>> = BTreeMap::new();
+    for item in item_set {
+        lookaheads
+            .entry(item.to_lr0())
+            .or_default()
+            .extend(item.lookahead().iter().copied());
+    }
+    lookaheads
+        .into_iter()
+        .map(|(item, lookaheads)| LookaheadItem { item, lookaheads })
+        .collect()
+}
+
 fn find_conflicts<'grammar, 'rules, const N: usize>(
     parser_graph: &ParserGraph<'grammar, 'rules, N>,
 ) -> BTreeSet<Conflict<'grammar, 'rules>> {
+    if N > 1 {
+        panic!("LR(N) with N > 1 not supported");
+    }
     let mut conflicts = BTreeSet::new();
     for (item_set, state) in parser_graph.state_map.iter() {
         let mut reducing_items: BTreeMap<[Symbol; N], Vec<&Item<N>>> = BTreeMap::new();
@@ -291,43 +391,51 @@ fn find_conflicts<'grammar, 'rules, const N: usize>(
                     .push(item);
             }
         }
+        // conflicts between the same items are reported once with all lookaheads they occur on
+        let mut reduce_reduce: BTreeMap<Vec<Item<0>>, BTreeSet<Symbol>> = BTreeMap::new();
+        let mut shift_reduce: BTreeSet<(Item<0>, Symbol)> = BTreeSet::new();
+        let mut reduce_lookaheads: BTreeMap<Item<0>, BTreeSet<Symbol>> = BTreeMap::new();
         for (lookahead, reducing_items) in reducing_items {
             if reducing_items.len() > 1 {
-                conflicts.insert(Conflict::ReduceReduce {
-                    state: state.index(),
-                    items: reducing_items.into_iter().map(|i| i.to_lr0()).collect(),
-                });
-            } else if reducing_items.len() == 1 {
+                reduce_reduce
+                    .entry(reducing_items.into_iter().map(|i| i.to_lr0()).collect())
+                    .or_default()
+                    .extend(lookahead);
+            } else if let [item_to_reduce] = reducing_items.as_slice() {
+                let item_to_reduce = item_to_reduce.to_lr0();
+                reduce_lookaheads
+                    .entry(item_to_reduce.clone())
+                    .or_default()
+                    .extend(lookahead);
                 let outgoing_edges = parser_graph.graph.edges_directed(*state, Outgoing);
                 for edge in outgoing_edges {
-                    if N > 1 {
-                        panic!("LR(N) with N > 1 not supported");
-                    } else if N == 1 {
-                        if lookahead[0] == *edge.weight() {
-                            conflicts.insert(Conflict::ShiftReduce {
-                                state: state.index(),
-                                item_to_reduce: reducing_items
-                                    .first()
-                                    .map(|i| i.to_lr0())
-                                    .unwrap()
-                                    .clone(),
-                                shift_symbol: *edge.weight(),
-                            });
-                        }
-                    } else {
-                        conflicts.insert(Conflict::ShiftReduce {
-                            state: state.index(),
-                            item_to_reduce: reducing_items
-                                .first()
-                                .map(|i| i.to_lr0())
-                                .unwrap()
-                                .clone(),
-                            shift_symbol: *edge.weight(),
-                        });
+                    if N == 0 || lookahead[0] == *edge.weight() {
+                        shift_reduce.insert((item_to_reduce.clone(), *edge.weight()));
                     }
                 }
             }
         }
+        if reduce_reduce.is_empty() && shift_reduce.is_empty() {
+            continue;
+        }
+        let item_set = lookahead_items(item_set);
+        for (items, lookaheads) in reduce_reduce {
+            conflicts.insert(Conflict::ReduceReduce {
+                state: state.index(),
+                items,
+                lookaheads,
+                item_set: item_set.clone(),
+            });
+        }
+        for (item_to_reduce, shift_symbol) in shift_reduce {
+            conflicts.insert(Conflict::ShiftReduce {
+                state: state.index(),
+                lookaheads: reduce_lookaheads[&item_to_reduce].clone(),
+                item_to_reduce,
+                shift_symbol,
+                item_set: item_set.clone(),
+            });
+        }
     }
     conflicts
 }
@@ -351,23 +459,43 @@ impl<'grammar, 'rules> Display for TableEntry<'grammar, 'rules> {
     }
 }
 
+/// Sizes of the LR automaton a table was built from, to estimate the memory needed for a grammar.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TableStatistics {
+    pub states: usize,
+    pub transitions: usize,
+    /// Items created while computing the successors of all states, including duplicates.
+    pub items_allocated: usize,
+    pub largest_item_set: usize,
+}
+
 #[derive(Debug)]
 pub struct ActionGotoTable<'grammar, 'rules> {
     entries: BTreeMap<(usize, Symbol), Vec<TableEntry<'grammar, 'rules>>>,
     state_count: usize,
     entry_state: usize,
+    statistics: TableStatistics,
 }
 
 impl<'grammar: 'rules, 'rules> ActionGotoTable<'grammar, 'rules> {
-    fn new(state_count: usize, entry_state: usize) -> Self {
+    fn new(state_count: usize, entry_state: usize, statistics: TableStatistics) -> Self {
         ActionGotoTable {
             entries: BTreeMap::new(),
             state_count,
             entry_state,
+            statistics,
         }
     }
 
-    pub fn get_entry(&self, state: usize, symbol: Symbol) -> Option<&Vec<TableEntry>> {
+    pub fn statistics(&self) -> TableStatistics {
+        self.statistics
+    }
+
+    pub fn get_entry(
+        &self,
+        state: usize,
+        symbol: Symbol,
+    ) -> Option<&Vec<TableEntry<'grammar, 'rules>>> {
         self.entries.get(&(state, symbol))
     }
 
@@ -375,7 +503,7 @@ impl<'grammar: 'rules, 'rules> ActionGotoTable<'grammar, 'rules> {
         &self,
         state: usize,
         grammar: &'grammar Grammar,
-    ) -> impl Iterator<Item = (Symbol, Option<&Vec<TableEntry>>)> {
+    ) -> impl Iterator<Item = (Symbol, Option<&Vec<TableEntry<'_, '_>>>)> {
         grammar
             .terminals()
             .chain(std::iter::once(Symbol::End))
@@ -386,7 +514,7 @@ impl<'grammar: 'rules, 'rules> ActionGotoTable<'grammar, 'rules> {
         &self,
         state: usize,
         grammar: &'grammar Grammar,
-    ) -> impl Iterator<Item = (Symbol, Option<&Vec<TableEntry>>)> {
+    ) -> impl Iterator<Item = (Symbol, Option<&Vec<TableEntry<'_, '_>>>)> {
         grammar
             .non_terminals()
             .map(move |s| (s, self.get_entry(state, s)))
@@ -400,6 +528,122 @@ impl<'grammar: 'rules, 'rules> ActionGotoTable<'grammar, 'rules> {
         self.state_count
     }
 
+    /// Numbers the states in breadth-first order from the entry state, following the
+    /// transitions of each state in symbol order. The numbering only depends on the shape
+    /// of the automaton, not on the order in which its states were discovered.
+    pub fn canonical_state_order(&self) -> Vec<usize> {
+        let mut order = self.breadth_first_states();
+        let visited = self.reachable_states();
+        // the state reached by the entry symbol is replaced by accept and has no shift leading
+        // to it, such states go last
+        order.extend((0..self.state_count).filter(|state| !visited[*state]));
+        order
+    }
+
+    // the states reachable from the entry state, in the order they are first reached
+    fn breadth_first_states(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.state_count];
+        let mut order = Vec::with_capacity(self.state_count);
+        let mut queue = VecDeque::new();
+        visited[self.entry_state] = true;
+        queue.push_back(self.entry_state);
+        while let Some(state) = queue.pop_front() {
+            order.push(state);
+            let transitions = self
+                .entries
+                .range((state, Symbol::Epsilon)..(state + 1, Symbol::Epsilon));
+            for (_, entries) in transitions {
+                for entry in entries {
+                    if let TableEntry::Shift { target } = entry {
+                        if *target < self.state_count && !visited[*target] {
+                            visited[*target] = true;
+                            queue.push_back(*target);
+                        }
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    fn reachable_states(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.state_count];
+        for state in self.breadth_first_states() {
+            reachable[state] = true;
+        }
+        reachable
+    }
+
+    /// Renumbers the states, so that state `order[i]` becomes state `i`.
+    pub fn reorder_states(&mut self, order: &[usize]) {
+        assert_eq!(order.len(), self.state_count);
+        let mut new_index = vec![0; self.state_count];
+        for (new_state, old_state) in order.iter().enumerate() {
+            new_index[*old_state] = new_state;
+        }
+        self.entries = std::mem::take(&mut self.entries)
+            .into_iter()
+            .map(|((state, symbol), entries)| {
+                let entries = entries
+                    .into_iter()
+                    .map(|entry| match entry {
+                        TableEntry::Shift { target } => TableEntry::Shift {
+                            target: new_index[target],
+                        },
+                        entry => entry,
+                    })
+                    .collect();
+                ((new_index[state], symbol), entries)
+            })
+            .collect();
+        self.entry_state = new_index[self.entry_state];
+    }
+
+    /// Removes the states which no sequence of shifts leads to from the entry state, like the one
+    /// replaced by accept, keeping the order of the others. Returns how many were removed.
+    pub fn prune_unreachable_states(&mut self) -> usize {
+        let reachable = self.reachable_states();
+        let kept = reachable.iter().filter(|reachable| **reachable).count();
+        let pruned = self.state_count - kept;
+        if pruned == 0 {
+            return 0;
+        }
+        let mut order: Vec<usize> = (0..self.state_count)
+            .filter(|state| reachable[*state])
+            .collect();
+        order.extend((0..self.state_count).filter(|state| !reachable[*state]));
+        self.reorder_states(&order);
+        // the reachable states only shift to reachable states
+        self.entries.retain(|(state, _), _| *state < kept);
+        self.state_count = kept;
+        pruned
+    }
+
+    // keeps one entry of each conflicting cell: the shift, or the reduction of the rule which
+    // comes first in the grammar
+    fn resolve_conflicts(&mut self, grammar: &Grammar<'rules>) {
+        let rule_position = |rule: &Rule<'rules>| {
+            grammar
+                .rules()
+                .iter()
+                .position(|other| std::ptr::eq(other, rule))
+        };
+        for entries in self.entries.values_mut() {
+            if entries.len() < 2 {
+                continue;
+            }
+            let kept = entries
+                .iter()
+                .min_by_key(|entry| match entry {
+                    TableEntry::Reduce { rule } => (1, rule_position(rule)),
+                    _ => (0, None),
+                })
+                .cloned()
+                .unwrap();
+            *entries = vec![kept];
+        }
+    }
+
     fn insert_reduce(&mut self, state: NodeIndex, symbol: Symbol, rule: &'grammar Rule<'rules>) {
         self.entries
             .entry((state.index(), symbol))
@@ -459,23 +703,66 @@ pub fn generate_table<'grammar: 'rules, 'rules, const N: usize>(
     allow_conflicts: bool,
     lalr: bool,
 ) -> GenerationResult<'grammar, 'rules, N> {
+    generate_table_cancellable(grammar, allow_conflicts, lalr, &|| false).unwrap()
+}
+
+/// Like `generate_table`, but checks `is_cancelled` for every state of the LR automaton and
+/// returns `None` as soon as it returns true.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip(grammar, is_cancelled), fields(lookahead = N))
+)]
+pub fn generate_table_cancellable<'grammar: 'rules, 'rules, const N: usize>(
+    grammar: &'grammar Grammar<'rules>,
+    allow_conflicts: bool,
+    lalr: bool,
+    is_cancelled: &dyn Fn() -> bool,
+) -> Option<GenerationResult<'grammar, 'rules, N>> {
     let first_sets = if N > 0 {
         compute_first_sets(grammar)
     } else {
         BTreeMap::new()
     };
-    let parser_graph = generate_parser_graph::<N>(grammar, &first_sets, lalr);
-    let conflicts: Vec<Conflict> = find_conflicts(&parser_graph).into_iter().collect();
+    let parser_graph = generate_parser_graph::<N>(grammar, &first_sets, lalr, is_cancelled)?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        states = parser_graph.graph.node_count(),
+        transitions = parser_graph.graph.edge_count(),
+        items_allocated = parser_graph.items_allocated,
+        "built the LR automaton"
+    );
+    let mut conflicts: Vec<Conflict> = find_conflicts(&parser_graph).into_iter().collect();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        conflicts = conflicts.len(),
+        "searched the states for conflicts"
+    );
+
+    // conflicts are reported with the state numbers of the final table
+    let mut table = build_table(parser_graph, grammar);
+    let order = table.canonical_state_order();
+    table.reorder_states(&order);
+    let mut new_index = vec![0; order.len()];
+    for (new_state, old_state) in order.iter().enumerate() {
+        new_index[*old_state] = new_state;
+    }
+    for conflict in &mut conflicts {
+        conflict.renumber_state(&new_index);
+    }
+    conflicts.sort();
     if !allow_conflicts && !conflicts.is_empty() {
-        return GenerationResult::BadConflicts(conflicts);
+        if conflicts.iter().all(Conflict::is_allowed) {
+            table.resolve_conflicts(grammar);
+            return Some(GenerationResult::AllowedConflicts { table, conflicts });
+        }
+        conflicts.retain(|conflict| !conflict.is_allowed());
+        return Some(GenerationResult::BadConflicts(conflicts));
     }
 
-    let table = build_table(parser_graph, grammar);
-
     if conflicts.is_empty() {
-        GenerationResult::NoConflicts(table)
+        Some(GenerationResult::NoConflicts(table))
     } else {
-        GenerationResult::AllowedConflicts { table, conflicts }
+        Some(GenerationResult::AllowedConflicts { table, conflicts })
     }
 }
 
@@ -485,16 +772,32 @@ fn build_table<'grammar, 'rules, const N: usize>(
 ) -> ActionGotoTable<'grammar, 'rules> {
     let entry_state = parser_graph.entry_state.unwrap().index();
     let node_count = parser_graph.graph.node_indices().count();
+    let statistics = TableStatistics {
+        states: node_count,
+        transitions: parser_graph.graph.edge_count(),
+        items_allocated: parser_graph.items_allocated,
+        largest_item_set: parser_graph
+            .state_map
+            .iter()
+            .map(|(item_set, _)| item_set.len())
+            .max()
+            .unwrap_or(0),
+    };
 
     let mut table: ActionGotoTable<'grammar, 'rules> =
-        ActionGotoTable::new(node_count, entry_state);
+        ActionGotoTable::new(node_count, entry_state, statistics);
+    let error_symbol = grammar.has_error_productions().then_some(Symbol::Error);
     for (item_set, state) in parser_graph.state_map.iter() {
         for item in item_set {
             // we can continue after this since there can be at most one reducable (conflicts already checked)
             if item.symbol_after_dot().is_none() {
                 match N {
                     0 => {
-                        for symbol in grammar.symbols().chain(std::iter::once(Symbol::End)) {
+                        for symbol in grammar
+                            .symbols()
+                            .chain(std::iter::once(Symbol::End))
+                            .chain(error_symbol)
+                        {
                             table.insert_reduce(*state, symbol, item.rule())
                         }
                     }
@@ -510,6 +813,10 @@ fn build_table<'grammar, 'rules, const N: usize>(
             .edges_directed(*state, Outgoing)
             .map(|e| (*e.weight(), e.target()))
             .collect();
+        // the error symbol is only shifted during recovery, so it needs no other entries
+        if let Some(target) = reachable_states.get(&Symbol::Error) {
+            table.insert_shift(*state, Symbol::Error, *target);
+        }
         for symbol in grammar.symbols() {
             if symbol == *grammar.entry_point() && state.index() == entry_state {
                 table.insert_accept(*state, symbol);
@@ -551,7 +858,12 @@ pub fn output_table<'grammar, 'rules>(
     let state_count_digits = format!("{}", table.state_count).len();
     let mut column_sizes = Vec::new();
     write!(output, "{: >width$}", "", width = state_count_digits)?;
-    for symbol in grammar.symbols().chain(std::iter::once(Symbol::End)) {
+    let error_symbol = grammar.has_error_productions().then_some(Symbol::Error);
+    for symbol in grammar
+        .symbols()
+        .chain(std::iter::once(Symbol::End))
+        .chain(error_symbol)
+    {
         let name = grammar.get_symbol_name(&symbol);
         column_sizes.push(name.len());
         write!(output, "|{}", name)?;
@@ -562,6 +874,7 @@ pub fn output_table<'grammar, 'rules>(
         for (i, symbol) in grammar
             .symbols()
             .chain(std::iter::once(Symbol::End))
+            .chain(error_symbol)
             .enumerate()
         {
             if let Some(entries) = table.get_entry(state, symbol) {
@@ -0,0 +1,76 @@
+use crate::grammar::{Grammar, Symbol};
+
+use super::{
+    simulate::{run_table, ParseStep},
+    ActionGotoTable,
+};
+
+/// Counts how often each state of a parse table is entered while parsing sample inputs.
+#[derive(Debug)]
+pub struct StateProfile {
+    visits: Vec<u64>,
+}
+
+impl StateProfile {
+    pub fn new(table: &ActionGotoTable) -> Self {
+        StateProfile {
+            visits: vec![0; table.states()],
+        }
+    }
+
+    /// Runs the tokens through the table and returns whether they were accepted. Parsing stops at
+    /// the first error or ambiguous entry, but the states visited until then are still counted.
+    pub fn record(&mut self, table: &ActionGotoTable, tokens: &[Symbol]) -> bool {
+        run_table(table, tokens, |state| self.visits[state] += 1, |_| ()).is_ok()
+    }
+
+    /// Returns all states, the most visited first. States visited equally often keep their order.
+    pub fn hot_state_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.visits.len()).collect();
+        order.sort_by_key(|state| std::cmp::Reverse(self.visits[*state]));
+        order
+    }
+}
+
+/// Counts how often each rule of a grammar is reduced while parsing sample inputs.
+#[derive(Debug)]
+pub struct RuleCoverage {
+    reductions: Vec<u64>,
+}
+
+impl RuleCoverage {
+    pub fn new(grammar: &Grammar) -> Self {
+        RuleCoverage {
+            reductions: vec![0; grammar.rules().len()],
+        }
+    }
+
+    /// Runs the tokens through the table and returns whether they were accepted. Rules reduced
+    /// before an error are still counted.
+    pub fn record(
+        &mut self,
+        grammar: &Grammar,
+        table: &ActionGotoTable,
+        tokens: &[Symbol],
+    ) -> bool {
+        let rules = grammar.rules();
+        run_table(
+            table,
+            tokens,
+            |_| (),
+            |step| {
+                if let ParseStep::Reduce(rule) = step {
+                    if let Some(index) = rules.iter().position(|r| std::ptr::eq(r, rule)) {
+                        self.reductions[index] += 1;
+                    }
+                }
+            },
+        )
+        .is_ok()
+    }
+
+    /// How often each rule was reduced, in the order of `Grammar::rules`.
+    pub fn reductions(&self) -> &[u64] {
+        &self.reductions
+    }
+}
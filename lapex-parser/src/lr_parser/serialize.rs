@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use crate::grammar::{read_symbol, write_symbol, Grammar, Rule};
+
+use super::{ActionGotoTable, TableEntry, TableStatistics};
+
+const MAGIC: &[u8; 8] = b"LAPEXTBL";
+const FORMAT_VERSION: u32 = 1;
+// the entry rule is not one of the rules of the grammar
+const ENTRY_RULE: u32 = u32::MAX;
+
+impl<'grammar: 'rules, 'rules> ActionGotoTable<'grammar, 'rules> {
+    /// Writes the table in a binary format. The rules it reduces are written as their index in
+    /// the rules of the grammar, so the table can be read for any grammar of the same structure.
+    pub fn serialize(&self, grammar: &'grammar Grammar<'rules>) -> Vec<u8> {
+        let rule_indices: BTreeMap<*const Rule, u32> = grammar
+            .rules()
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| (rule as *const Rule, i as u32))
+            .collect();
+        let mut output = MAGIC.to_vec();
+        let statistics = &self.statistics;
+        for value in [
+            FORMAT_VERSION as usize,
+            self.state_count,
+            self.entry_state,
+            statistics.states,
+            statistics.transitions,
+            statistics.items_allocated,
+            statistics.largest_item_set,
+            self.entries.len(),
+        ] {
+            write_u32(&mut output, value as u32);
+        }
+        for ((state, symbol), entries) in &self.entries {
+            write_u32(&mut output, *state as u32);
+            write_symbol(&mut output, *symbol);
+            write_u32(&mut output, entries.len() as u32);
+            for entry in entries {
+                match entry {
+                    TableEntry::Shift { target } => {
+                        output.push(0);
+                        write_u32(&mut output, *target as u32);
+                    }
+                    TableEntry::Reduce { rule } => {
+                        output.push(1);
+                        let index = rule_indices
+                            .get(&(*rule as *const Rule))
+                            .copied()
+                            .unwrap_or(ENTRY_RULE);
+                        write_u32(&mut output, index);
+                    }
+                    TableEntry::Error => output.push(2),
+                    TableEntry::Accept => output.push(3),
+                }
+            }
+        }
+        output
+    }
+
+    /// Reads a table written by `serialize`. Returns `None` if the data is not a table of this
+    /// format, or refers to states or rules which do not exist.
+    pub fn deserialize(mut input: &[u8], grammar: &'grammar Grammar<'rules>) -> Option<Self> {
+        let input = &mut input;
+        if input.get(..MAGIC.len())? != MAGIC {
+            return None;
+        }
+        *input = &input[MAGIC.len()..];
+        if read_u32(input)? != FORMAT_VERSION {
+            return None;
+        }
+        let state_count = read_u32(input)? as usize;
+        let entry_state = read_u32(input)? as usize;
+        let statistics = TableStatistics {
+            states: read_u32(input)? as usize,
+            transitions: read_u32(input)? as usize,
+            items_allocated: read_u32(input)? as usize,
+            largest_item_set: read_u32(input)? as usize,
+        };
+        if entry_state >= state_count {
+            return None;
+        }
+        let mut table = ActionGotoTable::new(state_count, entry_state, statistics);
+        for _ in 0..read_u32(input)? {
+            let state = read_u32(input)? as usize;
+            let symbol = read_symbol(input)?;
+            if state >= state_count {
+                return None;
+            }
+            let mut entries = Vec::new();
+            for _ in 0..read_u32(input)? {
+                let (tag, rest) = input.split_first()?;
+                *input = rest;
+                entries.push(match tag {
+                    0 => match read_u32(input)? as usize {
+                        target if target < state_count => TableEntry::Shift { target },
+                        _ => return None,
+                    },
+                    1 => TableEntry::Reduce {
+                        rule: match read_u32(input)? {
+                            ENTRY_RULE => grammar.entry_rule(),
+                            index => grammar.rules().get(index as usize)?,
+                        },
+                    },
+                    2 => TableEntry::Error,
+                    3 => TableEntry::Accept,
+                    _ => return None,
+                });
+            }
+            table.entries.insert((state, symbol), entries);
+        }
+        input.is_empty().then_some(table)
+    }
+}
+
+fn write_u32(output: &mut Vec<u8>, value: u32) {
+    output.extend_from_slice(&value.to_le_bytes());
+}
+
+fn read_u32(input: &mut &[u8]) -> Option<u32> {
+    let bytes = input.get(..4)?;
+    let value = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    *input = &input[4..];
+    Some(value)
+}
@@ -0,0 +1,97 @@
+use crate::grammar::{Grammar, Rule, Symbol};
+
+use super::{ActionGotoTable, TableEntry};
+
+/// A step of the parser while parsing a sequence of tokens.
+#[derive(Debug, PartialEq)]
+pub enum ParseStep<'grammar, 'rules> {
+    Shift(Symbol),
+    Reduce(&'grammar Rule<'rules>),
+}
+
+/// Where the parser stopped on an input it does not accept.
+#[derive(Debug, PartialEq)]
+pub struct ParseFailure {
+    /// The index of the token which could not be parsed, the length of the input for its end.
+    pub position: usize,
+    /// The tokens the parser could have continued with, including `Symbol::End`.
+    pub expected: Vec<Symbol>,
+}
+
+/// The shifts and reductions of the parser for a sequence of tokens.
+#[derive(Debug)]
+pub struct ParseTrace<'grammar, 'rules> {
+    pub steps: Vec<ParseStep<'grammar, 'rules>>,
+    pub failure: Option<ParseFailure>,
+}
+
+impl<'grammar: 'rules, 'rules> ParseTrace<'grammar, 'rules> {
+    /// Runs the tokens through the table. Parsing stops at the first error or ambiguous entry.
+    pub fn record(
+        grammar: &'grammar Grammar,
+        table: &ActionGotoTable<'grammar, 'rules>,
+        tokens: &[Symbol],
+    ) -> Self {
+        let mut steps = Vec::new();
+        let result = run_table(table, tokens, |_| (), |step| steps.push(step));
+        ParseTrace {
+            steps,
+            failure: result.err().map(|(position, state)| ParseFailure {
+                position,
+                expected: table
+                    .iter_state_terminals(state, grammar)
+                    .filter(|(_, entries)| {
+                        entries.is_some_and(|entries| {
+                            entries.iter().any(|e| !matches!(e, TableEntry::Error))
+                        })
+                    })
+                    .map(|(symbol, _)| symbol)
+                    .collect(),
+            }),
+        }
+    }
+}
+
+// simulates the parser for the tokens, reporting every state entered and every step taken. On an
+// error, returns the position of the token and the state which could not handle it
+pub(super) fn run_table<'grammar: 'rules, 'rules>(
+    table: &ActionGotoTable<'grammar, 'rules>,
+    tokens: &[Symbol],
+    mut enter_state: impl FnMut(usize),
+    mut step: impl FnMut(ParseStep<'grammar, 'rules>),
+) -> Result<(), (usize, usize)> {
+    let mut stack = vec![table.entry_state()];
+    enter_state(table.entry_state());
+    let mut position = 0;
+    loop {
+        let state = *stack.last().unwrap();
+        let lookahead = tokens.get(position).copied().unwrap_or(Symbol::End);
+        match table.get_entry(state, lookahead).map(|e| e.as_slice()) {
+            Some([TableEntry::Shift { target }]) => {
+                stack.push(*target);
+                enter_state(*target);
+                step(ParseStep::Shift(lookahead));
+                position += 1;
+            }
+            Some([TableEntry::Reduce { rule }]) => {
+                let to_pop = rule.rhs().iter().filter(|s| **s != Symbol::Epsilon).count();
+                if stack.len() <= to_pop {
+                    return Err((position, state));
+                }
+                step(ParseStep::Reduce(rule));
+                stack.truncate(stack.len() - to_pop);
+                let state = *stack.last().unwrap();
+                let goto = rule.lhs().and_then(|lhs| table.get_entry(state, lhs));
+                match goto.map(|e| e.as_slice()) {
+                    Some([TableEntry::Shift { target }]) => {
+                        stack.push(*target);
+                        enter_state(*target);
+                    }
+                    Some([TableEntry::Accept]) if lookahead == Symbol::End => return Ok(()),
+                    _ => return Err((position, state)),
+                }
+            }
+            _ => return Err((position, state)),
+        }
+    }
+}
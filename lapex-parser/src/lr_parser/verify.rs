@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+
+use crate::grammar::{Grammar, Rule, Symbol};
+
+use super::{ActionGotoTable, TableEntry};
+
+/// A broken invariant of a parser table. These can only be caused by a bug in the table
+/// construction, never by the grammar.
+#[derive(Debug, PartialEq)]
+pub enum TableDefect<'grammar, 'rules> {
+    /// No sequence of shifts leads from the entry state to the state.
+    UnreachableState {
+        state: usize,
+    },
+    ShiftTargetMissing {
+        state: usize,
+        symbol: Symbol,
+        target: usize,
+    },
+    /// The entries leading into the state are for different symbols.
+    AmbiguousAccessingSymbol {
+        state: usize,
+    },
+    MissingAccept,
+    /// Accept is only valid alone, in the entry state and for the entry symbol.
+    MisplacedAccept {
+        state: usize,
+        symbol: Symbol,
+    },
+    /// The states leading into the state do not spell the right hand side of the rule, so
+    /// reducing it would pop the wrong number of states.
+    ReduceDepth {
+        state: usize,
+        rule: &'grammar Rule<'rules>,
+    },
+}
+
+impl<'grammar: 'rules, 'rules> ActionGotoTable<'grammar, 'rules> {
+    /// Checks the invariants every table built from a grammar satisfies, so a broken table
+    /// is found before a parser is generated from it.
+    pub fn verify(&self, grammar: &'grammar Grammar) -> Vec<TableDefect<'grammar, 'rules>> {
+        let mut defects = Vec::new();
+        let mut accessing_symbols = vec![BTreeSet::new(); self.state_count];
+        let mut predecessors = vec![BTreeSet::new(); self.state_count];
+        let mut has_accept = false;
+        for ((state, symbol), entries) in &self.entries {
+            for entry in entries {
+                match entry {
+                    TableEntry::Shift { target } if *target >= self.state_count => {
+                        defects.push(TableDefect::ShiftTargetMissing {
+                            state: *state,
+                            symbol: *symbol,
+                            target: *target,
+                        });
+                    }
+                    TableEntry::Shift { target } => {
+                        accessing_symbols[*target].insert(*symbol);
+                        predecessors[*target].insert(*state);
+                    }
+                    TableEntry::Accept => {
+                        has_accept = true;
+                        if *state != self.entry_state
+                            || symbol != grammar.entry_point()
+                            || entries.len() > 1
+                        {
+                            defects.push(TableDefect::MisplacedAccept {
+                                state: *state,
+                                symbol: *symbol,
+                            });
+                        }
+                    }
+                    TableEntry::Reduce { .. } | TableEntry::Error => (),
+                }
+            }
+        }
+        if !has_accept {
+            defects.push(TableDefect::MissingAccept);
+        }
+
+        let reachable = self.reachable_states();
+        for state in 0..self.state_count {
+            if accessing_symbols[state].len() > 1 {
+                defects.push(TableDefect::AmbiguousAccessingSymbol { state });
+            }
+            // the state reached by the entry symbol is replaced by accept, it only reduces the
+            // entry rule
+            let reduces_entry_rule = |entry: &TableEntry| matches!(entry, TableEntry::Reduce { rule } if rule.lhs().is_none());
+            let replaced_by_accept = self.state_entries(state).any(reduces_entry_rule)
+                && self
+                    .state_entries(state)
+                    .all(|entry| reduces_entry_rule(entry) || matches!(entry, TableEntry::Error));
+            if !reachable[state] && !replaced_by_accept {
+                defects.push(TableDefect::UnreachableState { state });
+            }
+        }
+
+        let mut checked_reductions = BTreeSet::new();
+        for state in 0..self.state_count {
+            for entry in self.state_entries(state) {
+                let rule = match entry {
+                    TableEntry::Reduce { rule } if rule.lhs().is_some() => *rule,
+                    _ => continue,
+                };
+                if !checked_reductions.insert((state, rule as *const Rule)) {
+                    continue;
+                }
+                // walks back one state for every symbol the reduction pops
+                let mut states = BTreeSet::from([state]);
+                for symbol in rule.rhs().iter().rev().filter(|s| **s != Symbol::Epsilon) {
+                    if states
+                        .iter()
+                        .any(|state| !accessing_symbols[*state].contains(symbol))
+                    {
+                        defects.push(TableDefect::ReduceDepth { state, rule });
+                        break;
+                    }
+                    states = states
+                        .iter()
+                        .flat_map(|state| predecessors[*state].iter().copied())
+                        .collect();
+                }
+            }
+        }
+        defects
+    }
+
+    fn state_entries(&self, state: usize) -> impl Iterator<Item = &TableEntry<'grammar, 'rules>> {
+        self.entries
+            .range((state, Symbol::Epsilon)..(state + 1, Symbol::Epsilon))
+            .flat_map(|(_, entries)| entries)
+    }
+}
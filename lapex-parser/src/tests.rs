@@ -0,0 +1,1264 @@
+use std::collections::BTreeSet;
+
+use lapex_input::{
+    ConflictKind, EntryRule, InsertionRule, ProductionPattern, ProductionRule, RuleSet, SourcePos,
+    SourceSpan, Spanned, TokenPattern, TokenRule,
+};
+
+use crate::{
+    grammar::{Duplicate, Grammar, GrammarError, Symbol},
+    lr_parser::{
+        generate_table, generate_table_cancellable, output_table, ActionGotoTable, Conflict,
+        GenerationResult, ParseFailure, ParseStep, ParseTrace, RuleCoverage, StateProfile,
+        TableDefect, TableEntry,
+    },
+    validation::{validate, GrammarIssue},
+};
+
+fn line_span(line: u16) -> SourceSpan {
+    SourceSpan {
+        start: SourcePos { line, col: 1 },
+        end: SourcePos { line, col: 2 },
+    }
+}
+
+fn production<'src>(
+    line: u16,
+    name: &'src str,
+    pattern: ProductionPattern<'src>,
+) -> Spanned<ProductionRule<'src>> {
+    Spanned::new(
+        line_span(line),
+        ProductionRule {
+            name,
+            tag: None,
+            pattern,
+            doc: None,
+        },
+    )
+}
+
+fn token<'src>(name: &'src str, characters: &str) -> Spanned<TokenRule<'src>> {
+    Spanned::zero(TokenRule {
+        name: name.into(),
+        precedence: None,
+        pattern: TokenPattern::Literal {
+            characters: characters.chars().collect(),
+        },
+        skip: false,
+        doc: None,
+    })
+}
+
+fn rule(rule_name: &str) -> ProductionPattern<'_> {
+    ProductionPattern::Rule {
+        rule_name,
+        span: SourceSpan::zero(),
+    }
+}
+
+/// Builds the LR(1) or LALR(1) table of the grammar, which must be free of conflicts.
+fn lr1_table<'grammar: 'rules, 'rules>(
+    grammar: &'grammar Grammar<'rules>,
+    lalr: bool,
+) -> ActionGotoTable<'grammar, 'rules> {
+    match generate_table::<1>(grammar, false, lalr) {
+        GenerationResult::NoConflicts(table) => table,
+        _ => panic!("expected no conflicts"),
+    }
+}
+
+#[test]
+fn test_unit_cycle_detected() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "a" }),
+        Vec::new(),
+        vec![
+            production(
+                1,
+                "a",
+                ProductionPattern::Alternative {
+                    elements: vec![rule("b"), ProductionPattern::Epsilon],
+                },
+            ),
+            production(2, "b", rule("c")),
+            production(3, "c", rule("a")),
+        ],
+    );
+    let err = Grammar::from_rule_set(&rule_set, false).unwrap_err();
+    assert_eq!(
+        err,
+        GrammarError::UnitCycle {
+            path: vec![
+                Spanned::new(line_span(1), String::from("a")),
+                Spanned::new(line_span(2), String::from("b")),
+                Spanned::new(line_span(3), String::from("c")),
+            ]
+        }
+    );
+}
+
+#[test]
+fn test_undefined_entry_rule() {
+    let entry = || Spanned::new(line_span(1), EntryRule { name: "s" });
+    let undefined = GrammarError::UndefinedEntryRule {
+        name: String::from("s"),
+        span: line_span(1),
+    };
+    let empty = RuleSet::new(entry(), Vec::new(), Vec::new());
+    assert_eq!(
+        Grammar::from_rule_set(&empty, false).unwrap_err(),
+        undefined
+    );
+    // a token is no production to start from
+    let token_entry = RuleSet::new(
+        entry(),
+        vec![token("s", "s")],
+        vec![production(2, "t", rule("s"))],
+    );
+    assert_eq!(
+        Grammar::from_rule_set(&token_entry, false).unwrap_err(),
+        undefined
+    );
+    assert_eq!(
+        undefined.to_string(),
+        "the entry rule references the undefined production s"
+    );
+    let tokens_only = RuleSet::tokens_only(vec![token("s", "s")]);
+    assert_eq!(
+        Grammar::from_rule_set(&tokens_only, false).unwrap_err(),
+        GrammarError::NoEntryRule
+    );
+}
+
+#[test]
+fn test_unit_chain_without_cycle() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "a" }),
+        Vec::new(),
+        vec![
+            production(1, "a", rule("b")),
+            production(2, "b", rule("c")),
+            production(
+                3,
+                "c",
+                ProductionPattern::Sequence {
+                    elements: vec![rule("a"), rule("b")],
+                },
+            ),
+        ],
+    );
+    assert!(Grammar::from_rule_set(&rule_set, false).is_ok());
+}
+
+#[test]
+fn test_duplicate_productions() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "a" }),
+        Vec::new(),
+        vec![
+            production(1, "a", rule("b")),
+            production(2, "b", ProductionPattern::Epsilon),
+            production(3, "a", ProductionPattern::Epsilon),
+        ],
+    );
+    assert_eq!(
+        Grammar::from_rule_set(&rule_set, false).unwrap_err(),
+        GrammarError::ConflictingRules {
+            rules: vec![line_span(1), line_span(3)]
+        }
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, true).unwrap();
+    let a_rules: Vec<&Vec<Symbol>> = grammar
+        .rules()
+        .iter()
+        .filter(|r| r.lhs() == Some(*grammar.entry_point()))
+        .map(|r| r.rhs())
+        .collect();
+    assert_eq!(
+        a_rules,
+        vec![&vec![Symbol::NonTerminal(1)], &vec![Symbol::Epsilon]]
+    );
+}
+
+#[test]
+fn test_duplicate_rules() {
+    let sequence = |elements| ProductionPattern::Sequence { elements };
+    let group = |elements| ProductionPattern::Group {
+        inner: Box::new(ProductionPattern::Alternative { elements }),
+    };
+    // the alternatives of x start with an `a` on its line, which their spans point at
+    let a_star_b = || {
+        sequence(vec![
+            ProductionPattern::Rule {
+                rule_name: "a",
+                span: line_span(5),
+            },
+            ProductionPattern::ZeroOrMany {
+                inner: Box::new(rule("b")),
+            },
+        ])
+    };
+    // u has the shape of y and z, but another anonymous alternative
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b")],
+        vec![
+            production(
+                4,
+                "s",
+                ProductionPattern::Alternative {
+                    elements: vec![rule("x"), rule("y"), rule("z"), rule("u")],
+                },
+            ),
+            production(
+                5,
+                "x",
+                ProductionPattern::Alternative {
+                    elements: vec![a_star_b(), rule("b"), a_star_b()],
+                },
+            ),
+            production(
+                6,
+                "y",
+                sequence(vec![group(vec![rule("a"), rule("b")]), rule("a")]),
+            ),
+            production(
+                7,
+                "z",
+                sequence(vec![group(vec![rule("a"), rule("b")]), rule("a")]),
+            ),
+            production(8, "w", sequence(vec![rule("a"), rule("b")])),
+            production(9, "w", sequence(vec![rule("a"), rule("b")])),
+            production(
+                10,
+                "u",
+                sequence(vec![
+                    group(vec![rule("a"), sequence(vec![rule("b"), rule("b")])]),
+                    rule("a"),
+                ]),
+            ),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, true).unwrap();
+    let duplicates: Vec<(String, [u16; 2])> = grammar
+        .duplicates()
+        .iter()
+        .map(|duplicate| match duplicate {
+            Duplicate::Alternative { production, spans } => {
+                (production.clone(), spans.map(|span| span.start.line))
+            }
+            Duplicate::Production { names, spans } => {
+                (names.join(" "), spans.map(|span| span.start.line))
+            }
+        })
+        .collect();
+    assert_eq!(
+        duplicates,
+        [
+            (String::from("x"), [5, 5]),
+            (String::from("y z"), [6, 7]),
+            (String::from("w"), [8, 9]),
+        ]
+    );
+}
+
+#[test]
+fn test_grammar_validation() {
+    let mut unused = token("b", "b");
+    unused.span = line_span(5);
+    let mut skipped = token("ws", " ");
+    skipped.inner.skip = true;
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), unused, skipped],
+        vec![
+            production(
+                1,
+                "s",
+                ProductionPattern::Alternative {
+                    elements: vec![rule("a"), rule("list")],
+                },
+            ),
+            production(
+                2,
+                "list",
+                ProductionPattern::Sequence {
+                    elements: vec![rule("a"), rule("list")],
+                },
+            ),
+            production(
+                3,
+                "t",
+                ProductionPattern::Sequence {
+                    elements: vec![rule("a"), rule("a")],
+                },
+            ),
+            // unreachable as well, which is reported instead
+            production(
+                4,
+                "dead",
+                ProductionPattern::Sequence {
+                    elements: vec![rule("a"), rule("dead_end")],
+                },
+            ),
+            production(
+                6,
+                "dead_end",
+                ProductionPattern::Sequence {
+                    elements: vec![rule("a"), rule("dead")],
+                },
+            ),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let issues = validate(&grammar, &rule_set);
+    assert_eq!(
+        issues,
+        vec![
+            GrammarIssue::UnproductiveProduction {
+                name: String::from("list"),
+                span: line_span(2),
+            },
+            GrammarIssue::UnreachableProduction {
+                name: String::from("t"),
+                span: line_span(3),
+            },
+            GrammarIssue::UnreachableProduction {
+                name: String::from("dead"),
+                span: line_span(4),
+            },
+            GrammarIssue::UnusedToken {
+                name: String::from("b"),
+                span: line_span(5),
+            },
+            GrammarIssue::UnreachableProduction {
+                name: String::from("dead_end"),
+                span: line_span(6),
+            },
+        ]
+    );
+    assert!(issues[0].is_error() && !issues[1].is_error());
+
+    // a token which only an insertion rule uses is not unused
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("semi", ";"), token("nl", "\n")],
+        vec![production(
+            1,
+            "s",
+            ProductionPattern::Sequence {
+                elements: vec![rule("a"), rule("semi")],
+            },
+        )],
+    )
+    .with_insertion_rules(vec![Spanned::zero(InsertionRule {
+        token: "semi",
+        trigger: "nl",
+        after: vec!["a"],
+    })]);
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert_eq!(validate(&grammar, &rule_set), Vec::new());
+}
+
+/// Lowers `s = <pattern>` over the tokens `a`, `b` and `c` into the rules of a grammar, without
+/// the indices of the symbols.
+fn lower_pattern(pattern: ProductionPattern) -> Vec<String> {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b"), token("c", "c")],
+        vec![production(1, "s", pattern)],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    grammar
+        .rules()
+        .iter()
+        .map(|rule| {
+            let rule = rule.display(&grammar).to_string();
+            let symbols: Vec<&str> = rule
+                .split(' ')
+                .map(|symbol| symbol.split('(').next().unwrap())
+                .collect();
+            symbols.join(" ")
+        })
+        .collect()
+}
+
+#[test]
+fn test_pattern_lowering() {
+    fn sequence(elements: Vec<ProductionPattern<'static>>) -> ProductionPattern<'static> {
+        ProductionPattern::Sequence { elements }
+    }
+    fn alternative(elements: Vec<ProductionPattern<'static>>) -> ProductionPattern<'static> {
+        ProductionPattern::Alternative { elements }
+    }
+    fn group(inner: ProductionPattern<'static>) -> ProductionPattern<'static> {
+        ProductionPattern::Group {
+            inner: Box::new(inner),
+        }
+    }
+    fn star(inner: ProductionPattern<'static>) -> ProductionPattern<'static> {
+        ProductionPattern::ZeroOrMany {
+            inner: Box::new(inner),
+        }
+    }
+    fn plus(inner: ProductionPattern<'static>) -> ProductionPattern<'static> {
+        ProductionPattern::OneOrMany {
+            inner: Box::new(inner),
+        }
+    }
+    fn optional(inner: ProductionPattern<'static>) -> ProductionPattern<'static> {
+        ProductionPattern::Optional {
+            inner: Box::new(inner),
+        }
+    }
+    let (a, b, c) = (|| rule("a"), || rule("b"), || rule("c"));
+    // each pattern as it is parsed from the source it is labeled with
+    let cases: Vec<(&str, ProductionPattern, &[&str])> = vec![
+        (
+            "a b* | c",
+            alternative(vec![sequence(vec![a(), star(b())]), c()]),
+            &[
+                "<s_star> -> <eps>",
+                "<s_star> -> b <s_star>",
+                "<s_alt> -> a <s_star>",
+                "<s_alt> -> c",
+                "s -> <s_alt>",
+            ],
+        ),
+        (
+            "a | b c",
+            alternative(vec![a(), sequence(vec![b(), c()])]),
+            &["<s_alt> -> a", "<s_alt> -> b c", "s -> <s_alt>"],
+        ),
+        (
+            "(a | b)+ c?",
+            sequence(vec![
+                plus(group(alternative(vec![a(), b()]))),
+                optional(c()),
+            ]),
+            &[
+                "<s_alt> -> a",
+                "<s_alt> -> b",
+                "<s_plus> -> <s_alt>",
+                "<s_plus> -> <s_alt> <s_plus>",
+                "<s_opt> -> c",
+                "<s_opt> -> <eps>",
+                "s -> <s_plus> <s_opt>",
+            ],
+        ),
+        (
+            "a (b | c)",
+            sequence(vec![a(), group(alternative(vec![b(), c()]))]),
+            &["<s_alt> -> b", "<s_alt> -> c", "s -> a <s_alt>"],
+        ),
+        (
+            "(a | b c)? a",
+            sequence(vec![
+                optional(group(alternative(vec![a(), sequence(vec![b(), c()])]))),
+                a(),
+            ]),
+            &[
+                "<s_alt> -> a",
+                "<s_alt> -> b c",
+                "<s_opt> -> <s_alt>",
+                "<s_opt> -> <eps>",
+                "s -> <s_opt> a",
+            ],
+        ),
+        (
+            "a+ b | (c)*",
+            alternative(vec![sequence(vec![plus(a()), b()]), star(group(c()))]),
+            &[
+                "<s_plus> -> a",
+                "<s_plus> -> a <s_plus>",
+                "<s_alt> -> <s_plus> b",
+                "<s_star> -> <eps>",
+                "<s_star> -> c <s_star>",
+                "<s_alt> -> <s_star>",
+                "s -> <s_alt>",
+            ],
+        ),
+        // a group of alternatives stays one alternative, on either side
+        (
+            "a | (b | c)",
+            alternative(vec![a(), group(alternative(vec![b(), c()]))]),
+            &[
+                "<s_alt> -> a",
+                "<s_alt_2> -> b",
+                "<s_alt_2> -> c",
+                "<s_alt> -> <s_alt_2>",
+                "s -> <s_alt>",
+            ],
+        ),
+        (
+            "(a | b) | c",
+            alternative(vec![group(alternative(vec![a(), b()])), c()]),
+            &[
+                "<s_alt_2> -> a",
+                "<s_alt_2> -> b",
+                "<s_alt> -> <s_alt_2>",
+                "<s_alt> -> c",
+                "s -> <s_alt>",
+            ],
+        ),
+        // an empty alternative produces nothing
+        (
+            "a | b |",
+            alternative(vec![a(), b(), ProductionPattern::Epsilon]),
+            &[
+                "<s_alt> -> a",
+                "<s_alt> -> b",
+                "<s_alt> -> <eps>",
+                "s -> <s_alt>",
+            ],
+        ),
+        (
+            "| a",
+            alternative(vec![ProductionPattern::Epsilon, a()]),
+            &["<s_alt> -> <eps>", "<s_alt> -> a", "s -> <s_alt>"],
+        ),
+        (
+            "(a | ) c",
+            sequence(vec![
+                group(alternative(vec![a(), ProductionPattern::Epsilon])),
+                c(),
+            ]),
+            &["<s_alt> -> a", "<s_alt> -> <eps>", "s -> <s_alt> c"],
+        ),
+        // groups without operators add no symbols
+        (
+            "a (b c) | c",
+            alternative(vec![
+                sequence(vec![a(), group(sequence(vec![b(), c()]))]),
+                c(),
+            ]),
+            &["<s_alt> -> a b c", "<s_alt> -> c", "s -> <s_alt>"],
+        ),
+        (
+            "(a) (b) c",
+            sequence(vec![group(a()), group(b()), c()]),
+            &["s -> a b c"],
+        ),
+        ("((a))", group(group(a())), &["s -> a"]),
+        (
+            "(a b)*",
+            star(group(sequence(vec![a(), b()]))),
+            &[
+                "<s_star> -> <eps>",
+                "<s_star> -> a b <s_star>",
+                "s -> <s_star>",
+            ],
+        ),
+    ];
+    for (source, pattern, expected) in cases {
+        assert_eq!(lower_pattern(pattern), expected, "lowering of {}", source);
+    }
+}
+
+#[test]
+fn test_anonymous_non_terminal_provenance() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "args" }),
+        Vec::new(),
+        vec![
+            production(
+                1,
+                "args",
+                ProductionPattern::Sequence {
+                    elements: vec![
+                        ProductionPattern::ZeroOrMany {
+                            inner: Box::new(rule("item")),
+                        },
+                        ProductionPattern::Optional {
+                            inner: Box::new(rule("item")),
+                        },
+                        ProductionPattern::ZeroOrMany {
+                            inner: Box::new(rule("item")),
+                        },
+                    ],
+                },
+            ),
+            production(2, "item", ProductionPattern::Epsilon),
+            production(3, "args_star", ProductionPattern::Epsilon),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let anonymous: Vec<(&str, String)> = grammar
+        .non_terminals()
+        .filter_map(|s| grammar.get_anonymous_origin(&s))
+        .map(|origin| (origin.name(), origin.to_string()))
+        .collect();
+    assert_eq!(
+        anonymous,
+        vec![
+            ("args_star_2", String::from("repetition inside 'args'")),
+            ("args_opt", String::from("option inside 'args'")),
+            ("args_star_3", String::from("repetition inside 'args'")),
+        ]
+    );
+    let args_opt_tags: Vec<Option<&str>> = grammar
+        .rules()
+        .iter()
+        .filter(|r| r.lhs().and_then(|lhs| grammar.get_non_terminal_name(&lhs)) == Some("args_opt"))
+        .map(|r| r.tag())
+        .collect();
+    assert_eq!(args_opt_tags, vec![Some("some"), Some("none")]);
+}
+
+#[test]
+fn test_profile_state_order() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("n", "1"), token("p", "+")],
+        vec![
+            production(1, "s", rule("e")),
+            production(
+                2,
+                "e",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        rule("n"),
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("e"), rule("p"), rule("n")],
+                        },
+                    ],
+                },
+            ),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let mut table = lr1_table(&grammar, false);
+    let symbol = |name| {
+        grammar
+            .terminals_with_names()
+            .find(|(_, n)| *n == name)
+            .unwrap()
+            .0
+    };
+    let statistics = table.statistics();
+    assert_eq!(statistics.states, table.states());
+    assert!(statistics.largest_item_set > 0);
+    assert!(statistics.items_allocated >= statistics.largest_item_set);
+    let (n, p) = (symbol("n"), symbol("p"));
+    let input = [n, p, n, p, n];
+
+    assert!(!StateProfile::new(&table).record(&table, &[p]));
+    let mut profile = StateProfile::new(&table);
+    assert!(profile.record(&table, &input));
+    table.reorder_states(&profile.hot_state_order());
+
+    let mut profile = StateProfile::new(&table);
+    assert!(profile.record(&table, &input));
+    assert_eq!(
+        profile.hot_state_order(),
+        (0..table.states()).collect::<Vec<_>>()
+    );
+}
+
+// s = e; e = n | e p n | e m n;
+fn expression_rule_set() -> RuleSet<'static> {
+    RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("n", "1"), token("p", "+"), token("m", "-")],
+        vec![
+            production(1, "s", rule("e")),
+            production(
+                2,
+                "e",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        rule("n"),
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("e"), rule("p"), rule("n")],
+                        },
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("e"), rule("m"), rule("n")],
+                        },
+                    ],
+                },
+            ),
+        ],
+    )
+}
+
+#[test]
+fn test_rule_coverage() {
+    let rule_set = expression_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let table = lr1_table(&grammar, false);
+    let symbol = |name| {
+        grammar
+            .terminals_with_names()
+            .find(|(_, n)| *n == name)
+            .unwrap()
+            .0
+    };
+    let (n, p, m) = (symbol("n"), symbol("p"), symbol("m"));
+
+    let mut coverage = RuleCoverage::new(&grammar);
+    assert!(coverage.record(&grammar, &table, &[n, p, n, p, n]));
+    let reductions_of = |coverage: &RuleCoverage, rhs: &[Symbol]| {
+        let index = grammar
+            .rules()
+            .iter()
+            .position(|rule| rule.rhs().ends_with(rhs))
+            .unwrap();
+        coverage.reductions()[index]
+    };
+    assert_eq!(reductions_of(&coverage, &[p, n]), 2);
+    assert_eq!(reductions_of(&coverage, &[m, n]), 0);
+
+    // rules reduced before an error still count
+    assert!(!coverage.record(&grammar, &table, &[n, m, n, m]));
+    assert_eq!(reductions_of(&coverage, &[m, n]), 1);
+}
+
+#[test]
+fn test_verify_table() {
+    let rule_set = expression_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    for (allow_conflicts, lalr) in [(false, false), (false, true), (true, true)] {
+        let table = match generate_table::<1>(&grammar, allow_conflicts, lalr) {
+            GenerationResult::NoConflicts(table) => table,
+            _ => panic!("expected no conflicts"),
+        };
+        assert_eq!(table.verify(&grammar), []);
+    }
+    let table = match generate_table::<0>(&grammar, true, false) {
+        GenerationResult::NoConflicts(table) | GenerationResult::AllowedConflicts { table, .. } => {
+            table
+        }
+        GenerationResult::BadConflicts(_) => unreachable!(),
+    };
+    assert_eq!(table.verify(&grammar), []);
+
+    // merging all states into one leaves most of them unreachable
+    let mut table = lr1_table(&grammar, false);
+    table.reorder_states(&vec![0; table.states()]);
+    assert!(table
+        .verify(&grammar)
+        .contains(&TableDefect::UnreachableState { state: 1 }));
+}
+
+#[test]
+fn test_table_serialization() {
+    let rule_set = expression_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let table = lr1_table(&grammar, false);
+    let data = table.serialize(&grammar);
+
+    // the names of the rules are not part of the structure, so the table is read for another
+    // grammar with the same rules
+    let renamed = RuleSet::new(
+        Spanned::zero(EntryRule { name: "start" }),
+        vec![token("num", "1"), token("plus", "+"), token("minus", "-")],
+        vec![
+            production(5, "start", rule("expr")),
+            production(
+                6,
+                "expr",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        rule("num"),
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("expr"), rule("plus"), rule("num")],
+                        },
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("expr"), rule("minus"), rule("num")],
+                        },
+                    ],
+                },
+            ),
+        ],
+    );
+    let renamed_grammar = Grammar::from_rule_set(&renamed, false).unwrap();
+    assert_eq!(grammar.structure(), renamed_grammar.structure());
+    let read = ActionGotoTable::deserialize(&data, &renamed_grammar).unwrap();
+    assert_eq!(read.verify(&renamed_grammar), []);
+    assert_eq!(read.serialize(&renamed_grammar), data);
+    let mut expected = Vec::new();
+    let rebuilt = lr1_table(&renamed_grammar, false);
+    output_table(&renamed_grammar, &rebuilt, &mut expected).unwrap();
+    let mut actual = Vec::new();
+    output_table(&renamed_grammar, &read, &mut actual).unwrap();
+    assert_eq!(actual, expected);
+
+    let other = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("n", "1"), token("p", "+"), token("m", "-")],
+        vec![production(1, "s", rule("n"))],
+    );
+    let other_grammar = Grammar::from_rule_set(&other, false).unwrap();
+    assert_ne!(grammar.structure(), other_grammar.structure());
+    assert!(ActionGotoTable::deserialize(&data[..data.len() - 1], &grammar).is_none());
+    assert!(ActionGotoTable::deserialize(b"LAPEXTBL", &grammar).is_none());
+}
+
+#[test]
+fn test_parse_trace() {
+    let rule_set = expression_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let table = lr1_table(&grammar, false);
+    let symbol = |name| {
+        grammar
+            .terminals_with_names()
+            .find(|(_, n)| *n == name)
+            .unwrap()
+            .0
+    };
+    let (n, p, m) = (symbol("n"), symbol("p"), symbol("m"));
+
+    let trace = ParseTrace::record(&grammar, &table, &[n, p, n]);
+    assert_eq!(trace.failure, None);
+    // the alternatives of e are reduced to e through an anonymous non-terminal
+    assert_eq!(trace.steps.len(), 8);
+    assert_eq!(trace.steps[0], ParseStep::Shift(n));
+    assert!(matches!(trace.steps[1], ParseStep::Reduce(rule) if rule.rhs() == &vec![n]));
+    assert!(
+        matches!(trace.steps[7], ParseStep::Reduce(rule) if rule.lhs() == Some(*grammar.entry_point()))
+    );
+
+    let trace = ParseTrace::record(&grammar, &table, &[n, m, p]);
+    assert_eq!(
+        trace.failure,
+        Some(ParseFailure {
+            position: 2,
+            expected: vec![n]
+        })
+    );
+    assert_eq!(
+        ParseTrace::record(&grammar, &table, &[n, p]).failure,
+        Some(ParseFailure {
+            position: 2,
+            expected: vec![n]
+        })
+    );
+}
+
+#[test]
+fn test_prune_unreachable_states() {
+    let rule_set = expression_rule_set();
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let mut table = lr1_table(&grammar, true);
+    let states = table.states();
+    assert_eq!(table.prune_unreachable_states(), 0);
+    assert_eq!(table.states(), states);
+
+    // the goto on a left recursive entry symbol is replaced by accept, which leaves the states
+    // continuing the recursion without a way into them
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "e" }),
+        vec![token("n", "1"), token("p", "+")],
+        vec![production(
+            1,
+            "e",
+            ProductionPattern::Alternative {
+                elements: vec![
+                    rule("n"),
+                    ProductionPattern::Sequence {
+                        elements: vec![rule("e"), rule("p"), rule("n")],
+                    },
+                ],
+            },
+        )],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let mut table = lr1_table(&grammar, false);
+    let unreachable = table
+        .verify(&grammar)
+        .into_iter()
+        .filter(|defect| matches!(defect, TableDefect::UnreachableState { .. }))
+        .count();
+    assert!(unreachable > 0);
+    let states = table.states();
+    assert!(table.prune_unreachable_states() >= unreachable);
+    assert!(table.states() < states);
+    assert!(!table
+        .verify(&grammar)
+        .iter()
+        .any(|defect| matches!(defect, TableDefect::UnreachableState { .. })));
+}
+
+#[test]
+fn test_cancelled_table_generation() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "a" }),
+        Vec::new(),
+        vec![production(1, "a", ProductionPattern::Epsilon)],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert!(generate_table_cancellable::<1>(&grammar, false, false, &|| true).is_none());
+    assert!(generate_table_cancellable::<1>(&grammar, false, false, &|| false).is_some());
+}
+
+#[test]
+fn test_canonical_state_numbering() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("n", "1"), token("p", "+"), token("m", "*")],
+        vec![
+            production(1, "s", rule("e")),
+            production(
+                2,
+                "e",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        rule("t"),
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("e"), rule("p"), rule("t")],
+                        },
+                    ],
+                },
+            ),
+            production(
+                3,
+                "t",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        rule("n"),
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("t"), rule("m"), rule("n")],
+                        },
+                    ],
+                },
+            ),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let mut table = lr1_table(&grammar, false);
+    assert_eq!(table.entry_state(), 0);
+    assert_eq!(
+        table.canonical_state_order(),
+        (0..table.states()).collect::<Vec<_>>()
+    );
+    let mut canonical = Vec::new();
+    output_table(&grammar, &table, &mut canonical).unwrap();
+
+    // any discovery order of the states maps back to the same numbering
+    let reversed: Vec<usize> = (0..table.states()).rev().collect();
+    table.reorder_states(&reversed);
+    assert_ne!(table.entry_state(), 0);
+    table.reorder_states(&table.canonical_state_order());
+    let mut renumbered = Vec::new();
+    output_table(&grammar, &table, &mut renumbered).unwrap();
+    assert_eq!(
+        String::from_utf8(canonical).unwrap(),
+        String::from_utf8(renumbered).unwrap()
+    );
+}
+
+#[test]
+fn test_conflict_lookahead_context() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b"), token("c", "c")],
+        vec![
+            production(
+                1,
+                "s",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("x"), rule("b")],
+                        },
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("y"), rule("b")],
+                        },
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("x"), rule("c")],
+                        },
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("y"), rule("c")],
+                        },
+                    ],
+                },
+            ),
+            production(2, "x", rule("a")),
+            production(3, "y", rule("a")),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let (table, conflicts) = match generate_table::<1>(&grammar, true, false) {
+        GenerationResult::AllowedConflicts { table, conflicts } => (table, conflicts),
+        _ => panic!("expected allowed conflicts"),
+    };
+    let symbol = |name| {
+        grammar
+            .terminals_with_names()
+            .find(|(_, n)| *n == name)
+            .unwrap()
+            .0
+    };
+    assert_eq!(conflicts.len(), 1);
+    match &conflicts[0] {
+        Conflict::ReduceReduce {
+            state,
+            items,
+            lookaheads,
+            item_set,
+        } => {
+            assert_eq!(items.len(), 2);
+            assert_eq!(
+                lookaheads.iter().copied().collect::<Vec<_>>(),
+                vec![symbol("b"), symbol("c")]
+            );
+            assert_eq!(item_set.len(), 2);
+            assert!(item_set.iter().all(|item| item.lookaheads == *lookaheads));
+            // the state id refers to the state of the returned table
+            for lookahead in lookaheads {
+                assert_eq!(table.get_entry(*state, *lookahead).unwrap().len(), 2);
+            }
+        }
+        conflict => panic!("expected a reduce-reduce conflict, got {:?}", conflict),
+    }
+}
+
+#[test]
+fn test_error_productions() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("b", "b")],
+        vec![production(
+            1,
+            "s",
+            ProductionPattern::Alternative {
+                elements: vec![
+                    ProductionPattern::Sequence {
+                        elements: vec![rule("a"), rule("b")],
+                    },
+                    ProductionPattern::Sequence {
+                        elements: vec![rule("error"), rule("b")],
+                    },
+                ],
+            },
+        )],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert!(grammar.has_error_productions());
+    let table = lr1_table(&grammar, false);
+    let entry_state = table.entry_state();
+    assert!(matches!(
+        table
+            .get_entry(entry_state, Symbol::Error)
+            .map(|e| e.as_slice()),
+        Some([TableEntry::Shift { .. }])
+    ));
+
+    // a token named "error" is an ordinary symbol
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("error", "e")],
+        vec![production(1, "s", rule("error"))],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert!(!grammar.has_error_productions());
+}
+
+#[test]
+fn test_token_insertions() {
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("nl", "\n"), token("semi", ";")],
+        vec![production(
+            1,
+            "s",
+            ProductionPattern::Sequence {
+                elements: vec![rule("a"), rule("semi")],
+            },
+        )],
+    )
+    .with_insertion_rules(vec![Spanned::zero(InsertionRule {
+        token: "semi",
+        trigger: "nl",
+        after: vec!["a"],
+    })]);
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let insertions = grammar.token_insertions();
+    assert_eq!(insertions.len(), 1);
+    let token_name = |symbol: Symbol| match symbol {
+        Symbol::Terminal(index) => grammar.get_token_name(index),
+        _ => panic!("expected a token"),
+    };
+    assert_eq!(token_name(insertions[0].token), "semi");
+    assert_eq!(token_name(insertions[0].trigger), "nl");
+    assert_eq!(
+        insertions[0]
+            .after
+            .iter()
+            .map(|symbol| token_name(*symbol))
+            .collect::<Vec<_>>(),
+        vec!["a"]
+    );
+
+    // only tokens can be inserted
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("a", "a"), token("nl", "\n")],
+        vec![production(1, "s", rule("a"))],
+    )
+    .with_insertion_rules(vec![Spanned::zero(InsertionRule {
+        token: "s",
+        trigger: "nl",
+        after: vec!["a"],
+    })]);
+    assert_eq!(
+        Grammar::from_rule_set(&rule_set, false).unwrap_err(),
+        GrammarError::NotAToken(String::from("s"))
+    );
+}
+
+#[test]
+fn test_first_follow_and_nullable_sets() {
+    // s = a? b; a = x;
+    let rule_set = RuleSet::new(
+        Spanned::zero(EntryRule { name: "s" }),
+        vec![token("x", "x"), token("b", "b")],
+        vec![
+            production(
+                1,
+                "s",
+                ProductionPattern::Sequence {
+                    elements: vec![
+                        ProductionPattern::Optional {
+                            inner: Box::new(rule("a")),
+                        },
+                        rule("b"),
+                    ],
+                },
+            ),
+            production(2, "a", rule("x")),
+        ],
+    );
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    let symbol = |name: &str| {
+        grammar
+            .symbols()
+            .find(|symbol| match symbol {
+                Symbol::Terminal(index) => grammar.get_token_name(*index) == name,
+                _ => grammar.get_non_terminal_name(symbol) == Some(name),
+            })
+            .unwrap()
+    };
+    let (s, a, x, b) = (symbol("s"), symbol("a"), symbol("x"), symbol("b"));
+    let optional = symbol("s_opt");
+
+    let first_sets = grammar.first_sets();
+    assert_eq!(first_sets[&s], BTreeSet::from([x, b]));
+    assert_eq!(first_sets[&optional], BTreeSet::from([x, Symbol::Epsilon]));
+
+    let follow_sets = grammar.follow_sets();
+    assert_eq!(follow_sets[&s], BTreeSet::from([Symbol::End]));
+    assert_eq!(follow_sets[&a], BTreeSet::from([b]));
+
+    assert_eq!(grammar.nullable(), BTreeSet::from([optional]));
+}
+
+#[test]
+fn test_allowed_conflicts() {
+    // s = i s | @allow(kind) i s e s | x;
+    let dangling_else = |allow: Vec<ConflictKind>| {
+        RuleSet::new(
+            Spanned::zero(EntryRule { name: "s" }),
+            vec![token("i", "i"), token("e", "e"), token("x", "x")],
+            vec![production(
+                1,
+                "s",
+                ProductionPattern::Alternative {
+                    elements: vec![
+                        ProductionPattern::Sequence {
+                            elements: vec![rule("i"), rule("s")],
+                        },
+                        ProductionPattern::Annotated {
+                            allow,
+                            inner: Box::new(ProductionPattern::Sequence {
+                                elements: vec![rule("i"), rule("s"), rule("e"), rule("s")],
+                            }),
+                        },
+                        rule("x"),
+                    ],
+                },
+            )],
+        )
+    };
+
+    let rule_set = dangling_else(vec![ConflictKind::ShiftReduce]);
+    let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+    assert!(grammar.has_allowed_conflicts());
+    let (table, conflicts) = match generate_table::<1>(&grammar, false, false) {
+        GenerationResult::AllowedConflicts { table, conflicts } => (table, conflicts),
+        _ => panic!("expected allowed conflicts"),
+    };
+    assert_eq!(conflicts.len(), 1);
+    let Conflict::ShiftReduce {
+        state,
+        shift_symbol,
+        ..
+    } = &conflicts[0]
+    else {
+        panic!("expected a shift-reduce conflict");
+    };
+    // the else belongs to the innermost if
+    assert!(matches!(
+        table.get_entry(*state, *shift_symbol).unwrap().as_slice(),
+        [TableEntry::Shift { .. }]
+    ));
+
+    for allow in [Vec::new(), vec![ConflictKind::ReduceReduce]] {
+        let rule_set = dangling_else(allow);
+        let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+        match generate_table::<1>(&grammar, false, false) {
+            GenerationResult::BadConflicts(conflicts) => {
+                assert!(conflicts.iter().all(|conflict| !conflict.is_allowed()))
+            }
+            _ => panic!("expected bad conflicts"),
+        }
+        // a GLR table keeps the conflict, it is up to the caller to reject it
+        match generate_table::<1>(&grammar, true, false) {
+            GenerationResult::AllowedConflicts { conflicts, .. } => {
+                assert!(!conflicts[0].is_allowed())
+            }
+            _ => panic!("expected allowed conflicts"),
+        }
+    }
+    // e = @allow(shift_reduce, reduce_reduce) e p e | x;
+    let ambiguous_sum = |allow: Vec<ConflictKind>| {
+        RuleSet::new(
+            Spanned::zero(EntryRule { name: "s" }),
+            vec![token("p", "+"), token("x", "x")],
+            vec![
+                production(1, "s", rule("e")),
+                production(
+                    2,
+                    "e",
+                    ProductionPattern::Annotated {
+                        allow,
+                        inner: Box::new(ProductionPattern::Alternative {
+                            elements: vec![
+                                ProductionPattern::Sequence {
+                                    elements: vec![rule("e"), rule("p"), rule("e")],
+                                },
+                                rule("x"),
+                            ],
+                        }),
+                    },
+                ),
+            ],
+        )
+    };
+    // the annotation of a production covers the rules of all of its alternatives
+    let both = vec![ConflictKind::ShiftReduce, ConflictKind::ReduceReduce];
+    for (allow, allowed) in [(both, true), (Vec::new(), false)] {
+        let rule_set = ambiguous_sum(allow);
+        let grammar = Grammar::from_rule_set(&rule_set, false).unwrap();
+        match generate_table::<1>(&grammar, false, false) {
+            GenerationResult::AllowedConflicts { conflicts, .. } => {
+                assert!(allowed && conflicts.iter().all(|conflict| conflict.is_allowed()))
+            }
+            GenerationResult::BadConflicts(conflicts) => {
+                assert!(!allowed && conflicts.iter().all(|conflict| !conflict.is_allowed()))
+            }
+            GenerationResult::NoConflicts(_) => panic!("expected conflicts"),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use lapex_input::{RuleSet, SourceSpan};
+
+use crate::{
+    analysis::{compute_productive, compute_reachable},
+    grammar::{Grammar, Symbol, SymbolIdx},
+};
+
+/// A part of a grammar which builds, but takes no part in the inputs the parser accepts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarIssue {
+    /// A token which is not skipped, but is used by no production, insertion rule or sync
+    /// declaration, so it can only be lexed to be rejected.
+    UnusedToken { name: String, span: SourceSpan },
+    /// A production which can not be reached from the entry rule.
+    UnreachableProduction { name: String, span: SourceSpan },
+    /// A production reachable from the entry rule which can not derive any sequence of tokens,
+    /// because each of its alternatives needs itself or another such production.
+    UnproductiveProduction { name: String, span: SourceSpan },
+}
+
+impl GrammarIssue {
+    /// An unproductive production is an error, as the parser could never finish it, the others
+    /// only leave dead rules behind.
+    pub fn is_error(&self) -> bool {
+        matches!(self, GrammarIssue::UnproductiveProduction { .. })
+    }
+
+    pub fn span(&self) -> SourceSpan {
+        match self {
+            GrammarIssue::UnusedToken { span, .. }
+            | GrammarIssue::UnreachableProduction { span, .. }
+            | GrammarIssue::UnproductiveProduction { span, .. } => *span,
+        }
+    }
+}
+
+/// Checks the grammar built from `rule_set` for unused tokens and for productions which are
+/// unreachable or unproductive. The issues are ordered by their position in the grammar.
+pub fn validate(grammar: &Grammar, rule_set: &RuleSet) -> Vec<GrammarIssue> {
+    let mut issues = Vec::new();
+
+    let used_tokens = used_tokens(grammar);
+    for (i, rule) in rule_set.token_rules.iter().enumerate() {
+        let symbol = Symbol::Terminal(i as SymbolIdx);
+        if !rule.inner.skip && !used_tokens.contains(&symbol) {
+            issues.push(GrammarIssue::UnusedToken {
+                name: rule.inner.name.to_string(),
+                span: rule.span,
+            });
+        }
+    }
+
+    let reachable = compute_reachable(grammar);
+    let productive = compute_productive(grammar);
+    // anonymous non-terminals share the fate of the production they are lowered from, so only
+    // the named ones are reported
+    for non_terminal in grammar.non_terminals() {
+        let Some(name) = grammar.get_production_name(&non_terminal) else {
+            continue;
+        };
+        // a merged production points at its first definition
+        let Some(span) = rule_set
+            .production_rules
+            .iter()
+            .find(|rule| rule.inner.name == name)
+            .map(|rule| rule.span)
+        else {
+            continue;
+        };
+        let name = name.to_string();
+        if !reachable.contains(&non_terminal) {
+            issues.push(GrammarIssue::UnreachableProduction { name, span });
+        } else if !productive.contains(&non_terminal) {
+            issues.push(GrammarIssue::UnproductiveProduction { name, span });
+        }
+    }
+    issues.sort_by_key(|issue| issue.span());
+    issues
+}
+
+fn used_tokens(grammar: &Grammar) -> BTreeSet<Symbol> {
+    let mut used: BTreeSet<Symbol> = grammar
+        .rules()
+        .iter()
+        .flat_map(|rule| rule.rhs().iter().copied())
+        .collect();
+    for insertion in grammar.token_insertions() {
+        used.insert(insertion.token);
+        used.insert(insertion.trigger);
+        used.extend(insertion.after.iter().copied());
+    }
+    used.extend(grammar.sync_tokens().iter().copied());
+    used
+}
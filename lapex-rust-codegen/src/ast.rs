@@ -0,0 +1,541 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use lapex_parser::grammar::{Grammar, LoweredPattern, Rule, Symbol};
+use quote::{__private::TokenStream, quote};
+
+use crate::{
+    convert_snake_to_upper_camel, doc_attributes, get_non_terminal_enum_name, visitor_method_name,
+};
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// The types of the typed syntax tree. Productions become a struct, or an enum with a variant
+/// per rule if they have several, options become an `Option` and repetitions a `Vec`. Only
+/// the types holding tokens are generic over the token data `T`.
+struct AstTypes<'grammar> {
+    grammar: &'grammar Grammar<'grammar>,
+    rules_by_non_terminal: BTreeMap<Symbol, Vec<&'grammar Rule<'grammar>>>,
+    generic: BTreeSet<Symbol>,
+}
+
+impl<'grammar> AstTypes<'grammar> {
+    fn new(grammar: &'grammar Grammar<'grammar>) -> Self {
+        let mut rules_by_non_terminal: BTreeMap<Symbol, Vec<&Rule>> = BTreeMap::new();
+        for rule in grammar.rules() {
+            if let Some(non_terminal) = rule.lhs() {
+                rules_by_non_terminal
+                    .entry(non_terminal)
+                    .or_default()
+                    .push(rule);
+            }
+        }
+        let mut types = AstTypes {
+            grammar,
+            rules_by_non_terminal,
+            generic: BTreeSet::new(),
+        };
+        // a type is generic if any of its fields holds a token, directly or through other types
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (non_terminal, rules) in &types.rules_by_non_terminal {
+                if !types.generic.contains(non_terminal)
+                    && rules
+                        .iter()
+                        .any(|rule| rule.rhs().iter().any(|s| types.holds_tokens(s)))
+                {
+                    types.generic.insert(*non_terminal);
+                    changed = true;
+                }
+            }
+        }
+        types
+    }
+
+    fn holds_tokens(&self, symbol: &Symbol) -> bool {
+        match symbol {
+            Symbol::Terminal(_) | Symbol::Error => true,
+            Symbol::NonTerminal(_) => self.generic.contains(symbol),
+            Symbol::Epsilon | Symbol::End => false,
+        }
+    }
+
+    fn rules(&self, non_terminal: &Symbol) -> &[&'grammar Rule<'grammar>] {
+        &self.rules_by_non_terminal[non_terminal]
+    }
+
+    fn pattern(&self, non_terminal: &Symbol) -> Option<LoweredPattern> {
+        self.grammar
+            .get_anonymous_origin(non_terminal)
+            .map(|origin| origin.pattern())
+    }
+
+    // the symbols a single occurrence of an option or repetition is made of
+    fn inner_symbols(&self, non_terminal: &Symbol) -> Vec<Symbol> {
+        let rule = self
+            .rules(non_terminal)
+            .iter()
+            .find(|rule| matches!(rule.tag(), Some("some" | "one" | "tail")))
+            .expect("options and repetitions have a rule with symbols");
+        let mut symbols = symbols_of(rule);
+        if rule.tag() == Some("tail") {
+            symbols.pop();
+        }
+        symbols
+    }
+
+    fn type_name(&self, non_terminal: &Symbol) -> TokenStream {
+        let name = self
+            .grammar
+            .get_non_terminal_name(non_terminal)
+            .expect("every non-terminal has a name");
+        let name: TokenStream = convert_snake_to_upper_camel(name).parse().unwrap();
+        if self.generic.contains(non_terminal) {
+            quote! { #name<T> }
+        } else {
+            name
+        }
+    }
+
+    /// The type of the value a symbol has in the tree.
+    fn value_type(&self, symbol: &Symbol) -> TokenStream {
+        match symbol {
+            Symbol::Terminal(_) => quote! { T },
+            Symbol::Error => quote! { Recovered<T> },
+            Symbol::NonTerminal(_) => match self.pattern(symbol) {
+                None => {
+                    let name = self.type_name(symbol);
+                    quote! { std::boxed::Box<#name> }
+                }
+                Some(LoweredPattern::Alternative) => self.type_name(symbol),
+                Some(LoweredPattern::Optional) => {
+                    let inner = self.sequence_type(&self.inner_symbols(symbol));
+                    quote! { std::option::Option<#inner> }
+                }
+                Some(LoweredPattern::ZeroOrMany | LoweredPattern::OneOrMany) => {
+                    let inner = self.sequence_type(&self.inner_symbols(symbol));
+                    quote! { std::vec::Vec<#inner> }
+                }
+            },
+            Symbol::Epsilon | Symbol::End => unreachable!("epsilon and end have no value"),
+        }
+    }
+
+    fn sequence_type(&self, symbols: &[Symbol]) -> TokenStream {
+        match symbols {
+            [symbol] => self.value_type(symbol),
+            symbols => {
+                let types = symbols.iter().map(|s| self.value_type(s));
+                quote! { (#(#types),*) }
+            }
+        }
+    }
+
+    fn field_name(&self, symbol: &Symbol) -> String {
+        match symbol {
+            Symbol::Terminal(index) => self.grammar.get_token_name(*index).to_ascii_lowercase(),
+            Symbol::Error => String::from("error"),
+            Symbol::NonTerminal(_) => {
+                // an option or repetition of a single symbol is named after that symbol
+                if let Some(
+                    LoweredPattern::Optional
+                    | LoweredPattern::ZeroOrMany
+                    | LoweredPattern::OneOrMany,
+                ) = self.pattern(symbol)
+                {
+                    if let [inner] = self.inner_symbols(symbol).as_slice() {
+                        return self.field_name(inner);
+                    }
+                }
+                self.grammar
+                    .get_non_terminal_name(symbol)
+                    .expect("every non-terminal has a name")
+                    .to_ascii_lowercase()
+            }
+            Symbol::Epsilon | Symbol::End => unreachable!("epsilon and end have no value"),
+        }
+    }
+
+    /// Names the values of the symbols of a rule, numbering the names that occur more than once.
+    fn field_names(&self, symbols: &[Symbol]) -> Vec<TokenStream> {
+        let names: Vec<String> = symbols.iter().map(|s| self.field_name(s)).collect();
+        let mut seen: BTreeMap<&str, usize> = BTreeMap::new();
+        names
+            .iter()
+            .map(|name| {
+                let name = if names.iter().filter(|n| *n == name).count() > 1 {
+                    let count = seen.entry(name).or_default();
+                    *count += 1;
+                    format!("{}_{}", name, count)
+                } else if KEYWORDS.contains(&name.as_str()) {
+                    format!("{}_", name)
+                } else {
+                    name.clone()
+                };
+                name.parse().unwrap()
+            })
+            .collect()
+    }
+
+    fn type_definitions(&self) -> Vec<TokenStream> {
+        let mut definitions = Vec::new();
+        for (non_terminal, rules) in &self.rules_by_non_terminal {
+            let name = self.type_name(non_terminal);
+            match self.pattern(non_terminal) {
+                None if rules.len() == 1 => {
+                    let doc = doc_attributes(self.grammar.get_rule_doc(rules[0]));
+                    let fields = self.named_fields(rules[0], quote! { pub });
+                    definitions.push(quote! {
+                        #doc
+                        #[derive(Debug, Clone)]
+                        pub struct #name {
+                            #(#fields),*
+                        }
+                    });
+                }
+                None => {
+                    let doc = doc_attributes(
+                        rules
+                            .iter()
+                            .find_map(|rule| self.grammar.get_rule_doc(rule)),
+                    );
+                    let variants = rules.iter().enumerate().map(|(i, rule)| {
+                        let doc = doc_attributes(self.grammar.get_rule_doc(rule));
+                        let variant = variant_name(rule, i);
+                        let fields = self.named_fields(rule, quote! {});
+                        quote! {
+                            #doc
+                            #variant {
+                                #(#fields),*
+                            }
+                        }
+                    });
+                    definitions.push(quote! {
+                        #doc
+                        #[derive(Debug, Clone)]
+                        pub enum #name {
+                            #(#variants),*
+                        }
+                    });
+                }
+                Some(LoweredPattern::Alternative) => {
+                    let variants = rules.iter().enumerate().map(|(i, rule)| {
+                        let variant = variant_name(rule, i);
+                        let symbols = symbols_of(rule);
+                        if symbols.is_empty() {
+                            return variant;
+                        }
+                        let types = symbols.iter().map(|s| self.value_type(s));
+                        quote! { #variant(#(#types),*) }
+                    });
+                    let production = self
+                        .grammar
+                        .get_anonymous_origin(non_terminal)
+                        .expect("alternations are anonymous")
+                        .production();
+                    let doc = format!(" An alternation in the `{}` production.", production);
+                    definitions.push(quote! {
+                        #[doc = #doc]
+                        #[derive(Debug, Clone)]
+                        pub enum #name {
+                            #(#variants),*
+                        }
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        definitions
+    }
+
+    fn named_fields(&self, rule: &Rule, visibility: TokenStream) -> Vec<TokenStream> {
+        let symbols = symbols_of(rule);
+        let names = self.field_names(&symbols);
+        symbols
+            .iter()
+            .zip(names)
+            .map(|(symbol, name)| {
+                let field_type = self.value_type(symbol);
+                quote! { #visibility #name: #field_type }
+            })
+            .collect()
+    }
+
+    /// The reduce method of a rule, which pops the values of its symbols off the stack and
+    /// pushes the value of its non-terminal.
+    fn reduce_method(&self, non_terminal: &Symbol, rule: &Rule, i: usize) -> TokenStream {
+        let rules = self.rules(non_terminal);
+        let non_terminal_name = self
+            .grammar
+            .get_non_terminal_name(non_terminal)
+            .expect("every non-terminal has a name");
+        let function: TokenStream = visitor_method_name(self.grammar, rule, i).parse().unwrap();
+
+        let symbols = symbols_of(rule);
+        let names = self.field_names(&symbols);
+        // repetitions are built back to front, they are reversed when another rule takes them
+        let pops = symbols.iter().zip(&names).rev().map(|(symbol, name)| {
+            let variant = stack_variant(self.grammar, symbol);
+            let is_repetition = matches!(
+                self.pattern(symbol),
+                Some(LoweredPattern::ZeroOrMany | LoweredPattern::OneOrMany)
+            );
+            if symbol == non_terminal {
+                quote! {
+                    let Some(StackValue::#variant(mut #name)) = self.stack.pop() else {
+                        unreachable!("the parser reduces the symbols on its stack")
+                    };
+                }
+            } else if is_repetition {
+                quote! {
+                    let Some(StackValue::#variant(mut #name)) = self.stack.pop() else {
+                        unreachable!("the parser reduces the symbols on its stack")
+                    };
+                    #name.reverse();
+                }
+            } else {
+                quote! {
+                    let Some(StackValue::#variant(#name)) = self.stack.pop() else {
+                        unreachable!("the parser reduces the symbols on its stack")
+                    };
+                }
+            }
+        });
+
+        let type_name: TokenStream = convert_snake_to_upper_camel(non_terminal_name)
+            .parse()
+            .unwrap();
+        let value = match self.pattern(non_terminal) {
+            None if rules.len() == 1 => quote! {
+                std::boxed::Box::new(#type_name { #(#names),* })
+            },
+            None => {
+                let variant = variant_name(rule, i);
+                quote! {
+                    std::boxed::Box::new(#type_name::#variant { #(#names),* })
+                }
+            }
+            Some(LoweredPattern::Alternative) => {
+                let variant = variant_name(rule, i);
+                if names.is_empty() {
+                    quote! { #type_name::#variant }
+                } else {
+                    quote! { #type_name::#variant(#(#names),*) }
+                }
+            }
+            Some(LoweredPattern::Optional) => match rule.tag() {
+                Some("none") => quote! { std::option::Option::None },
+                _ => {
+                    let inner = sequence_value(&names);
+                    quote! { std::option::Option::Some(#inner) }
+                }
+            },
+            Some(LoweredPattern::ZeroOrMany | LoweredPattern::OneOrMany) => match rule.tag() {
+                Some("empty") => quote! { std::vec::Vec::new() },
+                Some("one") => {
+                    let inner = sequence_value(&names);
+                    quote! { std::vec![#inner] }
+                }
+                _ => {
+                    let (items, inner) =
+                        names.split_last().expect("the tail ends in the repetition");
+                    let inner = sequence_value(inner);
+                    quote! {
+                        {
+                            #items.push(#inner);
+                            #items
+                        }
+                    }
+                }
+            },
+        };
+        let variant = stack_variant(self.grammar, non_terminal);
+        quote! {
+            fn #function(&mut self) {
+                #(#pops)*
+                self.stack.push(StackValue::#variant(#value));
+            }
+        }
+    }
+}
+
+fn symbols_of(rule: &Rule) -> Vec<Symbol> {
+    rule.rhs()
+        .iter()
+        .filter(|s| !matches!(s, Symbol::Epsilon))
+        .copied()
+        .collect()
+}
+
+fn sequence_value(names: &[TokenStream]) -> TokenStream {
+    match names {
+        [name] => name.clone(),
+        names => quote! { (#(#names),*) },
+    }
+}
+
+// the variants are named after the tags, like the reduce methods of the visitor
+fn variant_name(rule: &Rule, i: usize) -> TokenStream {
+    match rule.tag() {
+        Some(tag) => convert_snake_to_upper_camel(tag),
+        None => format!("Alternative{}", i + 1),
+    }
+    .parse()
+    .unwrap()
+}
+
+fn stack_variant(grammar: &Grammar, symbol: &Symbol) -> TokenStream {
+    match symbol {
+        Symbol::Terminal(_) => quote! { Token },
+        Symbol::Error => quote! { Error },
+        Symbol::NonTerminal(_) => get_non_terminal_enum_name(grammar, *symbol)
+            .parse()
+            .unwrap(),
+        Symbol::Epsilon | Symbol::End => unreachable!("epsilon and end have no value"),
+    }
+}
+
+/// Writes the `ast` module, with a type for every production and a visitor assembling them
+/// while the parser of `parser_module` runs. `parse_ast` returns the type of the entry
+/// production, which is passed to it, as its signature depends on the parser. Only LR
+/// parsers with error productions call `recover`, and only ones with sync tokens call `error`.
+pub(crate) fn write_ast_module(
+    grammar: &Grammar,
+    tokens_path: &str,
+    parser_module: &str,
+    parse_ast: &dyn Fn(&TokenStream) -> TokenStream,
+    error_recovery: bool,
+    sync_recovery: bool,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let types = AstTypes::new(grammar);
+    let definitions = types.type_definitions();
+
+    let stack_variants = types.rules_by_non_terminal.keys().map(|non_terminal| {
+        let variant = stack_variant(grammar, non_terminal);
+        let value_type = types.value_type(non_terminal);
+        quote! { #variant(#value_type) }
+    });
+    let reduce_methods: Vec<TokenStream> = types
+        .rules_by_non_terminal
+        .iter()
+        .flat_map(|(non_terminal, rules)| {
+            let types = &types;
+            rules
+                .iter()
+                .enumerate()
+                .map(move |(i, rule)| types.reduce_method(non_terminal, rule, i))
+        })
+        .collect();
+
+    let entry = grammar.entry_rule().rhs()[0];
+    let root_type = types.value_type(&entry);
+    let root_variant = stack_variant(grammar, &entry);
+
+    let (recovered_type, error_variant, recover_method) = if error_recovery {
+        (
+            quote! {
+                /// The input an error symbol stands for, after recovering from `error`.
+                #[derive(Debug, Clone)]
+                pub struct Recovered<T> {
+                    pub error: parser::ParserError,
+                    pub skipped: std::vec::Vec<(tokens::TokenType, T)>,
+                }
+            },
+            quote! { Error(Recovered<T>), },
+            quote! {
+                fn recover(&mut self, error: parser::ParserError, popped_symbols: usize, skipped: std::vec::Vec<(tokens::TokenType, T)>) {
+                    self.stack.truncate(self.stack.len() - popped_symbols);
+                    self.stack.push(StackValue::Error(Recovered { error, skipped }));
+                }
+            },
+        )
+    } else if sync_recovery {
+        // the values of the discarded symbols are dropped, the errors are returned by the parser
+        (
+            quote! {},
+            quote! {},
+            quote! {
+                fn error(&mut self, _error: parser::ParserError, popped_symbols: usize, _skipped: std::vec::Vec<(tokens::TokenType, T)>) {
+                    self.stack.truncate(self.stack.len() - popped_symbols);
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {}, quote! {})
+    };
+    let parse_ast = parse_ast(&root_type);
+    let tokens_path: TokenStream = tokens_path.parse().unwrap();
+    let parser_module: TokenStream = parser_module.parse().unwrap();
+
+    let tokens = quote! {
+        // the modules are imported under lowercase names, which cannot collide with the types
+        use #tokens_path as tokens;
+        use super::#parser_module as parser;
+
+        #recovered_type
+
+        #(#definitions)*
+
+        // the values of the symbols on the parse stack
+        #[derive(Debug)]
+        #[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
+        enum StackValue<T> {
+            Token(T),
+            #error_variant
+            #(#stack_variants),*
+        }
+
+        /// Assembles the typed syntax tree while parsing, when passed to the parser as
+        /// `&mut builder`.
+        #[derive(Debug)]
+        pub struct AstBuilder<T> {
+            stack: std::vec::Vec<StackValue<T>>,
+        }
+
+        impl<T> Default for AstBuilder<T> {
+            fn default() -> Self {
+                AstBuilder { stack: std::vec::Vec::new() }
+            }
+        }
+
+        impl<T> AstBuilder<T> {
+            #[allow(dead_code)]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Returns the value of the entry production once the input has been parsed.
+            pub fn take_root(&mut self) -> std::option::Option<#root_type> {
+                let root = match self.stack.pop() {
+                    std::option::Option::Some(StackValue::#root_variant(root)) => std::option::Option::Some(root),
+                    _ => std::option::Option::None,
+                };
+                self.stack.clear();
+                root
+            }
+        }
+
+        impl<T> parser::Visitor<T> for &mut AstBuilder<T> {
+            fn shift(&mut self, _token: tokens::TokenType, data: T) {
+                self.stack.push(StackValue::Token(data));
+            }
+
+            #recover_method
+
+            #(#reduce_methods)*
+        }
+
+        #parse_ast
+    };
+    write!(output, "{}", tokens)
+}
@@ -0,0 +1,233 @@
+use std::{collections::BTreeMap, io::Write};
+
+use lapex_parser::grammar::{Grammar, Rule, Symbol};
+use quote::{__private::TokenStream, quote};
+
+use crate::{doc_attributes, get_non_terminal_enum_name, visitor_method_name};
+
+/// Writes the `cst` module, a visitor building a concrete syntax tree while the parser of
+/// `parser_module` runs. `parse_tree` is the function parsing the input into a tree, as its
+/// signature depends on the parser. `TokenType` is imported from `tokens_path`. Only LR parsers with error productions call `recover`,
+/// and only ones with sync tokens call `error`.
+pub(crate) fn write_cst_module(
+    grammar: &Grammar,
+    tokens_path: &str,
+    parser_module: &str,
+    parse_tree: &TokenStream,
+    error_recovery: bool,
+    sync_recovery: bool,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut rules_by_non_terminal: BTreeMap<Symbol, Vec<&Rule>> = BTreeMap::new();
+    for rule in grammar.rules() {
+        if let Some(non_terminal) = rule.lhs() {
+            rules_by_non_terminal
+                .entry(non_terminal)
+                .or_default()
+                .push(rule);
+        }
+    }
+
+    // anonymous non-terminals like repetitions have no node type
+    let non_terminals: Vec<TokenStream> = rules_by_non_terminal
+        .iter()
+        .filter(|(non_terminal, _)| grammar.get_production_name(non_terminal).is_some())
+        .map(|(non_terminal, rules)| {
+            let name: TokenStream = get_non_terminal_enum_name(grammar, *non_terminal)
+                .parse()
+                .unwrap();
+            let doc = doc_attributes(rules.iter().find_map(|rule| grammar.get_rule_doc(rule)));
+            quote! { #doc #name }
+        })
+        .collect();
+
+    let mut reduce_methods = Vec::new();
+    for (non_terminal, rules) in &rules_by_non_terminal {
+        let node_type: Option<TokenStream> = grammar.get_production_name(non_terminal).map(|_| {
+            get_non_terminal_enum_name(grammar, *non_terminal)
+                .parse()
+                .unwrap()
+        });
+        for (i, rule) in rules.iter().enumerate() {
+            let function: TokenStream = visitor_method_name(grammar, rule, i).parse().unwrap();
+            let symbols = rule
+                .rhs()
+                .iter()
+                .filter(|s| !matches!(s, Symbol::Epsilon))
+                .count();
+            let alternative = i as u32 + 1;
+            let reduce = match &node_type {
+                Some(node_type) => quote! {
+                    self.reduce_named(NonTerminalType::#node_type, #alternative, #symbols)
+                },
+                None => quote! { self.reduce_anonymous(#symbols) },
+            };
+            reduce_methods.push(quote! {
+                fn #function(&mut self) {
+                    #reduce
+                }
+            });
+        }
+    }
+
+    // the tokens skipped before a sync token are kept with it, in the node of its symbol
+    let (pending_field, pending_init, shift_nodes, take_pending) = if sync_recovery {
+        (
+            quote! {
+                // the errors recovered from since the last shift
+                pending: Vec<Node<T>>,
+            },
+            quote! { pending: Vec::new(), },
+            quote! {
+                let mut nodes = std::mem::take(&mut self.pending);
+                nodes.push(Node::Terminal { token, data });
+            },
+            quote! { self.pending.clear(); },
+        )
+    } else {
+        (
+            quote! {},
+            quote! {},
+            quote! { let nodes = vec![Node::Terminal { token, data }]; },
+            quote! {},
+        )
+    };
+    let (error_variant, recover_method) = if sync_recovery {
+        (
+            quote! {
+                /// The tokens skipped when recovering from `error`, which come before the token
+                /// the parser continued with.
+                Error {
+                    error: ParserError,
+                    skipped: Vec<(TokenType, T)>,
+                },
+            },
+            quote! {
+                fn error(&mut self, error: ParserError, popped_symbols: usize, skipped: Vec<(TokenType, T)>) {
+                    self.stack.truncate(self.stack.len() - popped_symbols);
+                    self.pending.push(Node::Error { error, skipped });
+                }
+            },
+        )
+    } else if error_recovery {
+        (
+            quote! {
+                /// The input an error symbol stands for, after recovering from `error`.
+                Error {
+                    error: ParserError,
+                    skipped: Vec<(TokenType, T)>,
+                },
+            },
+            quote! {
+                fn recover(&mut self, error: ParserError, popped_symbols: usize, skipped: Vec<(TokenType, T)>) {
+                    self.stack.truncate(self.stack.len() - popped_symbols);
+                    self.stack.push(vec![Node::Error { error, skipped }]);
+                }
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+    let tokens_path: TokenStream = tokens_path.parse().unwrap();
+    let parser_module: TokenStream = parser_module.parse().unwrap();
+
+    let tokens = quote! {
+        use #tokens_path::TokenType;
+        use super::#parser_module::{Parser, ParserError, Visitor};
+
+        /// The productions of the grammar, which the nodes of the tree are labelled with.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[allow(clippy::enum_variant_names)]
+        pub enum NonTerminalType {
+            #(#non_terminals),*
+        }
+
+        /// A node of the concrete syntax tree, which keeps every shifted token.
+        #[derive(Debug, Clone)]
+        pub enum Node<T> {
+            Terminal {
+                token: TokenType,
+                data: T,
+            },
+            NonTerminal {
+                non_terminal: NonTerminalType,
+                /// The rule that was reduced, numbered like the reduce methods of the visitor.
+                alternative: u32,
+                children: Vec<Node<T>>,
+            },
+            #error_variant
+        }
+
+        impl<T> Node<T> {
+            /// The nodes of the symbols the rule was reduced from, empty for the other nodes.
+            #[allow(dead_code)]
+            pub fn children(&self) -> &[Node<T>] {
+                match self {
+                    Node::NonTerminal { children, .. } => children,
+                    _ => &[],
+                }
+            }
+        }
+
+        /// Builds a tree while parsing, when passed to the parser as `&mut builder`. Anonymous
+        /// non-terminals like repetitions have no node type, their children are added to the
+        /// node of the enclosing production instead.
+        #[derive(Debug)]
+        pub struct TreeBuilder<T> {
+            // the nodes produced by each symbol on the parse stack
+            stack: Vec<Vec<Node<T>>>,
+            #pending_field
+        }
+
+        impl<T> Default for TreeBuilder<T> {
+            fn default() -> Self {
+                TreeBuilder { stack: Vec::new(), #pending_init }
+            }
+        }
+
+        impl<T> TreeBuilder<T> {
+            #[allow(dead_code)]
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            fn pop_children(&mut self, symbols: usize) -> Vec<Node<T>> {
+                let first = self.stack.len() - symbols;
+                self.stack.drain(first..).flatten().collect()
+            }
+
+            fn reduce_named(&mut self, non_terminal: NonTerminalType, alternative: u32, symbols: usize) {
+                let children = self.pop_children(symbols);
+                self.stack.push(vec![Node::NonTerminal { non_terminal, alternative, children }]);
+            }
+
+            #[allow(dead_code)]
+            fn reduce_anonymous(&mut self, symbols: usize) {
+                let children = self.pop_children(symbols);
+                self.stack.push(children);
+            }
+
+            /// Returns the node of the entry production once the input has been parsed.
+            pub fn take_root(&mut self) -> Option<Node<T>> {
+                let root = self.stack.pop().and_then(|mut nodes| nodes.pop());
+                self.stack.clear();
+                #take_pending
+                root
+            }
+        }
+
+        impl<T> Visitor<T> for &mut TreeBuilder<T> {
+            fn shift(&mut self, token: TokenType, data: T) {
+                #shift_nodes
+                self.stack.push(nodes);
+            }
+
+            #recover_method
+
+            #(#reduce_methods)*
+        }
+
+        #parse_tree
+    };
+    write!(output, "{}", tokens)
+}
@@ -11,17 +11,32 @@ use lapex_parser::{
 use quote::{__private::TokenStream, quote};
 
 use crate::RustGLRParserCodeGen;
-use crate::{get_non_terminal_enum_name, get_token_enum_name};
+use crate::{
+    ast::write_ast_module, cst::write_cst_module, external_token_checks, get_module_name,
+    get_non_terminal_enum_name, get_token_enum_name, get_tokens_path, make_facade_support,
+    make_internal_state_error, make_internal_state_result, make_internal_state_variant,
+    make_metrics_support, make_progress_support, make_token_filter_support, make_token_insertion,
+    reduce_method_doc, visitor_method_name, write_module, MetricsSupport, ProgressSupport,
+};
 
 struct CodeWriter<'grammar, 'rules> {
     grammar: &'grammar Grammar<'grammar>,
     parser_table: &'grammar ActionGotoTable<'grammar, 'rules>,
     rule_index_map: BTreeMap<*const Rule<'rules>, usize>,
     rules_by_non_terminal: BTreeMap<Symbol, Vec<&'grammar Rule<'rules>>>,
+    panic_free: bool,
+    metrics: bool,
+    progress: bool,
 }
 
 impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
-    fn new(grammar: &'grammar Grammar, parser_table: &'grammar ActionGotoTable) -> Self {
+    fn new(
+        grammar: &'grammar Grammar,
+        parser_table: &'grammar ActionGotoTable,
+        panic_free: bool,
+        metrics: bool,
+        progress: bool,
+    ) -> Self {
         let mut rules_by_non_terminal = BTreeMap::new();
         for rule in grammar.rules() {
             if let Some(non_terminal) = rule.lhs() {
@@ -42,42 +57,22 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
             parser_table,
             rule_index_map,
             rules_by_non_terminal,
+            panic_free,
+            metrics,
+            progress,
         }
     }
 }
 
 impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
-    fn get_non_terminal_name(&self, non_terminal: &Symbol) -> String {
-        let non_terminal_name = self
-            .grammar
-            .get_production_name(non_terminal)
-            .map(|s| String::from(s))
-            .unwrap_or_else(|| {
-                if let Symbol::NonTerminal(index) = non_terminal {
-                    format!("anon{}", index)
-                } else {
-                    unreachable!()
-                }
-            });
-        non_terminal_name
-    }
-
     fn write_visitor(&self, output: &mut dyn Write) -> std::io::Result<()> {
         let mut reduce_functions: Vec<TokenStream> = Vec::new();
 
-        for (non_terminal, rules) in &self.rules_by_non_terminal {
-            let non_terminal_name = self.get_non_terminal_name(non_terminal);
+        for rules in self.rules_by_non_terminal.values() {
             if rules.len() != 1 {
                 for (i, rule) in rules.iter().enumerate() {
-                    let comment: TokenStream = format!("///{}", rule.display(self.grammar))
-                        .parse()
-                        .unwrap();
-                    let tag = rule.rule().inner.tag;
-                    let name = if let Some(tag) = tag {
-                        format!("reduce_{}_{}", non_terminal_name, tag)
-                    } else {
-                        format!("reduce_{}_{}", non_terminal_name, i + 1)
-                    };
+                    let comment = reduce_method_doc(self.grammar, rule);
+                    let name = visitor_method_name(self.grammar, rule, i);
                     let function: TokenStream = name.parse().unwrap();
                     reduce_functions.push(quote! {
                         #comment
@@ -85,11 +80,10 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                     });
                 }
             } else {
-                let comment: TokenStream = format!("///{}", rules[0].display(self.grammar))
+                let comment = reduce_method_doc(self.grammar, rules[0]);
+                let function: TokenStream = visitor_method_name(self.grammar, rules[0], 0)
                     .parse()
                     .unwrap();
-                let function: TokenStream =
-                    format!("reduce_{}", non_terminal_name).parse().unwrap();
                 reduce_functions.push(quote! {
                     #comment
                     fn #function (&mut self);
@@ -109,17 +103,11 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
     fn write_debug_visitor(&self, output: &mut dyn Write) -> std::io::Result<()> {
         let mut reduce_functions: Vec<TokenStream> = Vec::new();
 
-        for (non_terminal, rules) in &self.rules_by_non_terminal {
-            let non_terminal_name = self.get_non_terminal_name(non_terminal);
+        for rules in self.rules_by_non_terminal.values() {
             if rules.len() != 1 {
                 for (i, rule) in rules.iter().enumerate() {
                     let comment = format!("{}", rule.display(self.grammar));
-                    let tag = rule.rule().inner.tag;
-                    let name = if let Some(tag) = tag {
-                        format!("reduce_{}_{}", non_terminal_name, tag)
-                    } else {
-                        format!("reduce_{}_{}", non_terminal_name, i + 1)
-                    };
+                    let name = visitor_method_name(self.grammar, rule, i);
                     let function: TokenStream = name.parse().unwrap();
                     reduce_functions.push(quote! {
                         fn #function(&mut self) {
@@ -129,8 +117,9 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 }
             } else {
                 let comment = format!("{}", rules[0].display(self.grammar));
-                let function: TokenStream =
-                    format!("reduce_{}", non_terminal_name).parse().unwrap();
+                let function: TokenStream = visitor_method_name(self.grammar, rules[0], 0)
+                    .parse()
+                    .unwrap();
                 reduce_functions.push(quote! {
                     fn #function(&mut self) {
                         println!(#comment);
@@ -142,8 +131,8 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
         let tokens = quote! {
             pub struct DebugVisitor {}
 
-            impl Visitor<()> for DebugVisitor {
-                fn shift(&mut self, token: TokenType, _data: ()) {
+            impl<T> Visitor<T> for DebugVisitor {
+                fn shift(&mut self, token: TokenType, _data: T) {
                     println!("shift {:?}", token);
                 }
 
@@ -357,18 +346,12 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
     fn make_rule_visits(&self) -> Vec<TokenStream> {
         let mut rule_visits: Vec<TokenStream> = Vec::new();
 
-        for (non_terminal, rules) in &self.rules_by_non_terminal {
-            let non_terminal_name = self.get_non_terminal_name(non_terminal);
+        for rules in self.rules_by_non_terminal.values() {
             if rules.len() != 1 {
                 for (i, rule) in rules.iter().enumerate() {
                     let rule_index = self.rule_index_map.get(&(*rule as *const Rule)).unwrap();
                     let rule_name: TokenStream = format!("Rule{}", rule_index).parse().unwrap();
-                    let tag = rule.rule().inner.tag;
-                    let name = if let Some(tag) = tag {
-                        format!("reduce_{}_{}", non_terminal_name, tag)
-                    } else {
-                        format!("reduce_{}_{}", non_terminal_name, i + 1)
-                    };
+                    let name = visitor_method_name(self.grammar, rule, i);
                     let function: TokenStream = name.parse().unwrap();
                     rule_visits.push(quote! {
                         ReducedRule::#rule_name => self.visitor.#function ()
@@ -379,7 +362,7 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 let rule_index = self.rule_index_map.get(&(rule as *const Rule)).unwrap();
                 let rule_name: TokenStream = format!("Rule{}", rule_index).parse().unwrap();
                 let function: TokenStream =
-                    format!("reduce_{}", non_terminal_name).parse().unwrap();
+                    visitor_method_name(self.grammar, rule, 0).parse().unwrap();
                 rule_visits.push(quote! {
                     ReducedRule::#rule_name => self.visitor.#function ()
                 });
@@ -408,14 +391,83 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
             .collect();
         let rule_reductions: Vec<TokenStream> = self.make_rule_reductions();
         let rule_visits: Vec<TokenStream> = self.make_rule_visits();
+        let internal_error = make_internal_state_error(self.panic_free);
+        let internal_error_result = make_internal_state_result(self.panic_free);
+        let token_filter_support = make_token_filter_support();
+        let token_insertion = make_token_insertion(self.grammar);
+        let (internal_error_variant, internal_error_display) =
+            make_internal_state_variant(self.panic_free);
+        let MetricsSupport {
+            items: metrics_items,
+            fields: metrics_fields,
+            init: metrics_init,
+            carry_over: metrics_carry_over,
+            methods: metrics_methods,
+        } = make_metrics_support(
+            self.metrics,
+            quote! {
+                /// The tokens read from the token function, without `EndOfFile`.
+                pub tokens_consumed: usize,
+                /// The rules reduced on any of the stacks, including those which were dropped later.
+                pub reductions: usize,
+                /// The stacks forked off because a state had more than one action.
+                pub forks: usize,
+                /// The most stacks parsed in parallel.
+                pub max_stacks: usize,
+            },
+        );
+        // parse returns early on errors, so with metrics its loop is wrapped by a function which
+        // resets them before and reports them after it
+        let (parse_signature, parse_wrapper) = if self.metrics {
+            (
+                quote! { fn parse_measured(&mut self) -> Result<(), ParserError<T, E>> },
+                quote! {
+                    pub fn parse(&mut self) -> Result<(), ParserError<T, E>> {
+                        self.metrics = ParseMetrics::default();
+                        let result = self.parse_measured();
+                        self.report_metrics();
+                        result
+                    }
+                },
+            )
+        } else {
+            (
+                quote! { pub fn parse(&mut self) -> Result<(), ParserError<T, E>> },
+                quote! {},
+            )
+        };
+        let (count_token, count_reduction, count_forks, count_stacks) = if self.metrics {
+            (
+                quote! { self.metrics.tokens_consumed += 1; },
+                quote! { self.metrics.reductions += 1; },
+                quote! { self.metrics.forks += actions.len().saturating_sub(1); },
+                quote! { self.metrics.max_stacks = self.metrics.max_stacks.max(stacks.len()); },
+            )
+        } else {
+            (quote! {}, quote! {}, quote! {}, quote! {})
+        };
+
+        let ProgressSupport {
+            items: progress_items,
+            fields: progress_fields,
+            init: progress_init,
+            carry_over: progress_carry_over,
+            methods: progress_methods,
+            reset: progress_reset,
+            count_token: progress_token,
+            count_reduction: progress_reduction,
+        } = make_progress_support(self.progress);
 
         let tokens = quote! {
             pub struct Parser<T, E, F: FnMut() -> Result<(TokenType, T), E>, V: Visitor<T>> {
                 token_function: F,
                 visitor: V,
+                #metrics_fields
+                #progress_fields
             }
 
             #[derive(Debug, Clone, Copy)]
+            #[allow(clippy::enum_variant_names)]
             enum NonTerminalType {
                 #(#non_terminals),*
             }
@@ -426,7 +478,7 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 NonTerminal { non_terminal: NonTerminalType },
             }
 
-            #[derive(Clone, Copy)]
+            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
             enum ReducedRule {
                 #(#rules),*
             }
@@ -443,6 +495,9 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
 
             type StateId = usize;
 
+            // a stack of the parser, which shares its bottom with the stacks it was forked from
+            type Stack<T> = GraphNode<StateId, StackSymbol, RecordedVisit<T>>;
+
             #[derive(Debug)]
             pub enum ParserError<T, E: std::error::Error> {
                 UnexpectedToken {
@@ -457,6 +512,7 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                     got: Vec<(TokenType, T)>,
                     expected: Vec<Vec<TokenType>>,
                 },
+                #internal_error_variant
             }
 
             impl<T: std::fmt::Debug, E: std::error::Error> std::error::Error for ParserError<T, E> {}
@@ -491,6 +547,7 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                                 errors.join("\n")
                             )
                         }
+                        #internal_error_display
                     }
                 }
             }
@@ -502,17 +559,21 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
             }
 
             impl<T: Clone, E: std::error::Error, F: FnMut() -> Result<(TokenType, T), E>, V: Visitor<T>> Parser<T, E, F, V> {
+                /// The token function is not called again once it returned `TokenType::EndOfFile`,
+                /// and `EndOfFile` is never passed to `Visitor::shift`.
                 pub fn new(token_function: F, visitor: V) -> Self {
                     Parser {
                         token_function,
                         visitor,
+                        #metrics_init
+                        #progress_init
                     }
                 }
 
                 fn next_actions(&self, state: usize, next_token: TokenType, next_data: T) -> Result<&'static [Action], ParserError<T, E>> {
                     match (state, next_token) {
                         #(#actions)*
-                        (_, _) => unreachable!()
+                        (_, _) => #internal_error_result
                     }
                 }
 
@@ -529,13 +590,35 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                     }
                 }
 
+                /// Passes every token through `filter` before the parser sees it, lexer errors
+                /// bypass the filter.
+                #[allow(dead_code)]
+                pub fn with_filter<P: FnMut(&(TokenType, T)) -> FilterAction<T>>(
+                    self,
+                    filter: P,
+                ) -> Parser<T, E, impl FnMut() -> Result<(TokenType, T), E>, V> {
+                    Parser {
+                        token_function: filter_tokens(self.token_function, filter),
+                        visitor: self.visitor,
+                        #metrics_carry_over
+                        #progress_carry_over
+                    }
+                }
+
+                #metrics_methods
+
+                #progress_methods
+
                 fn do_visit(&mut self, rule: &ReducedRule) {
                     match rule {
                         #(#rule_visits),*
                     }
                 }
 
-                pub fn parse(&mut self) -> Result<(), ParserError<T, E>> {
+                #parse_wrapper
+
+                #parse_signature {
+                    #progress_reset
                     let mut lookahead = std::collections::VecDeque::new();
                     lookahead.push_back((self.token_function)().map_err(|e| ParserError::LexerError { inner: e })?);
 
@@ -551,10 +634,24 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
 
                         let (next_token, next_data) = lookahead.pop_front().unwrap();
                         let new_symbol = StackSymbol::Terminal { token: next_token };
-                        lookahead.push_back((self.token_function)().map_err(|e| ParserError::LexerError { inner: e })?);
+                        if matches!(next_token, TokenType::EndOfFile) {
+                            // the end of input stays the lookahead until a stack accepts or fails
+                            lookahead.push_back((next_token, next_data.clone()));
+                        } else {
+                            #count_token
+                            #progress_token
+                            lookahead.push_back((self.token_function)().map_err(|e| ParserError::LexerError { inner: e })?);
+                        }
 
                         let mut new_stacks = if reduced.iter().any(|s| s.top().is_none()) {
-                            reduced
+                            // an ambiguous input is accepted by several stacks, only the preferred
+                            // parse is visited
+                            let preferred = reduced
+                                .into_iter()
+                                .filter(|s| s.top().is_none())
+                                .min_by(|a, b| compare_parses(&a.recorded(), &b.recorded()))
+                                .unwrap();
+                            vec![preferred]
                         } else {
                             let mut new_stacks = Vec::new();
                             for stack in reduced {
@@ -568,35 +665,46 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                                         let new_node = stack.push(Some(state_id), Some(new_symbol));
                                         new_stacks.push(new_node);
                                     }
-                                    Some(Goto::Accept) => unreachable!(),
+                                    Some(Goto::Accept) => #internal_error,
                                     None => (),
                                 }
                             }
                             new_stacks
                         };
+                        debug_assert!(
+                            !new_stacks.is_empty(),
+                            "all parse stacks were dropped without an error"
+                        );
                         if new_stacks.len() == 1 {
                             let stack = new_stacks.pop().unwrap();
                             let recorded = stack.pop_recorded();
                             for record in recorded {
                                 match record {
                                     RecordedVisit::Reduce { rule } => self.do_visit(&rule),
-                                    RecordedVisit::Shift { token, data } => self.visitor.shift(token, data),
+                                    RecordedVisit::Shift { token, data } => {
+                                        debug_assert!(
+                                            !matches!(token, TokenType::EndOfFile),
+                                            "EndOfFile must not be shifted"
+                                        );
+                                        self.visitor.shift(token, data)
+                                    }
                                 }
                             }
                             stacks = vec![stack];
                         } else {
                             stacks = new_stacks;
                         }
+                        #count_stacks
                     }
                     Ok(())
                 }
 
                 fn apply_reduces(
                     &mut self,
-                    stacks: Vec<GraphNode<usize, StackSymbol, RecordedVisit<T>>>,
+                    stacks: Vec<Stack<T>>,
                     next_token: &TokenType,
                     next_data: &T
-                ) -> Result<Vec<GraphNode<usize, StackSymbol, RecordedVisit<T>>>, Vec<ParserError<T, E>>> {
+                ) -> Result<Vec<Stack<T>>, Vec<ParserError<T, E>>> {
                     let mut to_reduce = stacks;
                     let mut reduced = Vec::new();
                     while !to_reduce.is_empty() {
@@ -605,8 +713,9 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                         let mut new_to_reduce = Vec::new();
                         for stack in to_reduce {
                             let state = *stack.top().unwrap();
-                            match self.next_actions(state, next_token.clone(), next_data.clone()) {
+                            match self.next_actions(state, *next_token, next_data.clone()) {
                                 Ok(actions) => {
+                                    #count_forks
                                     for action in actions {
                                         match action {
                                             Action::Reduce { rule: reduced_rule } => {
@@ -640,16 +749,18 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 fn apply_reduce(
                     &mut self,
                     reduced_rule: &ReducedRule,
-                    stack: &GraphNode<usize, StackSymbol, RecordedVisit<T>>,
-                    accepted: &mut Vec<GraphNode<StateId, StackSymbol, RecordedVisit<T>>>,
-                    new_to_reduce: &mut Vec<GraphNode<usize, StackSymbol, RecordedVisit<T>>>,
+                    stack: &Stack<T>,
+                    accepted: &mut Vec<Stack<T>>,
+                    new_to_reduce: &mut Vec<Stack<T>>,
                 ) {
-                    let (to_pop, reduced_symbol) = self.get_rule_reduction(&reduced_rule);
+                    let (to_pop, reduced_symbol) = self.get_rule_reduction(reduced_rule);
                     let stacks_to_push = stack.unwind_stacks(to_pop);
                     for mut stack in stacks_to_push {
                         stack.record(RecordedVisit::Reduce {
-                            rule: reduced_rule.clone(),
+                            rule: *reduced_rule,
                         });
+                        #count_reduction
+                        #progress_reduction
                         // remove reduced symbols
                         for _ in 0..to_pop {
                             let (_edge, new_stack) = stack.pop();
@@ -663,8 +774,9 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                                 new_to_reduce.push(new_node);
                             }
                             Some(Goto::Accept) => {
-                                let (_edge, root) = stack.pop();
-                                accepted.push(root);
+                                // the entry state is shared with the other stacks, so it is left
+                                // as it is
+                                accepted.push(stack.accepted());
                             }
                             None => (),
                         }
@@ -672,7 +784,62 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 }
             }
 
+            #metrics_items
+
+            #progress_items
+
+            #token_filter_support
+
+            #token_insertion
+
+            /// Wraps a token function, so that every token is passed through `filter` first.
+            #[allow(dead_code)]
+            pub fn filter_tokens<T, E, F: FnMut() -> Result<(TokenType, T), E>, P: FnMut(&(TokenType, T)) -> FilterAction<T>>(
+                mut token_function: F,
+                mut filter: P,
+            ) -> impl FnMut() -> Result<(TokenType, T), E> {
+                let mut pending = std::collections::VecDeque::new();
+                move || loop {
+                    if let Some(token) = pending.pop_front() {
+                        return Ok(token);
+                    }
+                    apply_filter(&mut filter, token_function()?, &mut pending);
+                }
+            }
+
+            /// Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`
+            /// once the iterator is exhausted.
+            #[allow(dead_code)]
+            pub fn token_function_from_iter<T: Default, E, I: IntoIterator<Item = Result<(TokenType, T), E>>>(
+                tokens: I,
+            ) -> impl FnMut() -> Result<(TokenType, T), E> {
+                let mut tokens = tokens.into_iter();
+                move || {
+                    tokens
+                        .next()
+                        .unwrap_or_else(|| Ok((TokenType::EndOfFile, T::default())))
+                }
+            }
+
+            /// Orders the parses of an ambiguous input by their recorded visits, independent of the
+            /// order the stacks were forked in. At the first visit two parses differ in, the one
+            /// which reduces is preferred over the one which shifts, and of two reductions the one
+            /// of the rule which comes first in the grammar.
+            fn compare_parses<T>(a: &[RecordedVisit<T>], b: &[RecordedVisit<T>]) -> std::cmp::Ordering {
+                let key = |visit: &RecordedVisit<T>| match visit {
+                    RecordedVisit::Reduce { rule } => (0, Some(*rule)),
+                    RecordedVisit::Shift { .. } => (1, None),
+                };
+                a.iter().map(key).cmp(b.iter().map(key))
+            }
+
             fn combine_errors<T, E: std::error::Error>(mut errors: Vec<ParserError<T, E>>) -> ParserError<T, E> {
+                let other_error = errors
+                    .iter()
+                    .position(|e| !matches!(e, ParserError::UnexpectedToken { .. }));
+                if let Some(index) = other_error {
+                    return errors.swap_remove(index);
+                }
                 match errors.len() {
                     1 => errors.pop().unwrap(),
                     0 => unreachable!(),
@@ -747,15 +914,11 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                         self.inner.borrow_mut().neighbors.push((value, predecessor));
                     }
 
-                    pub fn top(&self) -> Option<Ref<N>> {
-                        let opt = Ref::filter_map(self.inner.borrow(), |i| i.node_value.as_ref());
-                        match opt {
-                            Ok(r) => Some(r),
-                            Err(_) => None,
-                        }
+                    pub fn top(&self) -> Option<Ref<'_, N>> {
+                        Ref::filter_map(self.inner.borrow(), |i| i.node_value.as_ref()).ok()
                     }
 
-                    fn neighbors(&self) -> Ref<[(Option<E>, GraphNode<N, E, R>)]> {
+                    fn neighbors(&self) -> Ref<'_, [Edge<N, E, R>]> {
                         Ref::map(self.inner.borrow(), |i| i.neighbors.as_slice())
                     }
 
@@ -774,6 +937,15 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                         }
                     }
 
+                    pub fn recorded(&self) -> Ref<'_, [R]> {
+                        Ref::map(self.recorded.borrow(), |recorded| recorded.as_slice())
+                    }
+
+                    // a root with the record of this stack, which leaves the nodes below as they are
+                    pub fn accepted(&self) -> Self {
+                        self.new_with_same_record(None)
+                    }
+
                     pub fn pop_recorded(&self) -> Vec<R> {
                         return self.recorded.borrow_mut().split_off(0);
                     }
@@ -803,21 +975,32 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                     }
                 }
 
+                // the symbol on the edge and the node below
+                type Edge<N, E, R> = (Option<E>, GraphNode<N, E, R>);
+
                 struct GraphNodeInner<N, E, R> {
                     node_value: Option<N>,
-                    neighbors: Vec<(Option<E>, GraphNode<N, E, R>)>,
+                    neighbors: Vec<Edge<N, E, R>>,
                 }
             }
         };
         write!(output, "{}", tokens)
     }
 
-    fn write_visitor_and_parser(&self, output: &mut dyn Write) -> std::io::Result<()> {
+    fn write_visitor_and_parser(
+        &self,
+        tokens_path: &str,
+        output: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        let tokens_path: TokenStream = tokens_path.parse().unwrap();
+        let external_token_checks = external_token_checks(self.grammar);
         write!(
             output,
             "{}",
             quote! {
-                use super::tokens::TokenType;
+                use #tokens_path::TokenType;
+
+                #external_token_checks
             }
         )?;
         self.write_visitor(output)?;
@@ -827,6 +1010,34 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
     }
 }
 
+// errors of the lexer reach the caller through the token function
+fn write_facade(
+    grammar: &Grammar,
+    symbol_prefix: Option<&str>,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let (lexer_module, filter) = make_facade_support(grammar, symbol_prefix);
+    let tokens = quote! {
+        use #lexer_module::{Lexer, LexerError};
+
+        /// An error of `parse_str`, where `ParserError::LexerError` comes from the lexer.
+        #[allow(dead_code)]
+        pub type ParseError<'src> = ParserError<&'src str, LexerError>;
+
+        /// Lexes and parses `src` in one call, passing the text of every token to the visitor.
+        #[allow(dead_code)]
+        pub fn parse_str<'src, V: Visitor<&'src str>>(
+            src: &'src str,
+            visitor: V,
+        ) -> Result<(), ParseError<'src>> {
+            let mut lexer = Lexer::new(src);
+            let mut parser = Parser::new(|| lexer.next().map(|token| (token, lexer.slice())), visitor)#filter;
+            parser.parse()
+        }
+    };
+    write!(output, "{}", tokens)
+}
+
 fn get_rule_from_pointer<'a, 'rules>(rule: &*const Rule<'rules>) -> &'a Rule<'rules> {
     // We created the hashmap from a known list of rules. The rule pointers are derived from the grammar rules, and the grammar outlives this struct.
     // Therefore, this operation is safe.
@@ -841,10 +1052,88 @@ impl LRParserCodeGen for RustGLRParserCodeGen {
         parser_table: &ActionGotoTable,
         gen: &mut GeneratedCodeWriter,
     ) {
-        let writer = CodeWriter::new(grammar, parser_table);
-        gen.generate_code("parser.rs", |output| {
-            writer.write_visitor_and_parser(output)
+        let writer = CodeWriter::new(
+            grammar,
+            parser_table,
+            self.panic_free,
+            self.metrics,
+            self.progress,
+        );
+        let symbol_prefix = self.symbol_prefix.as_deref();
+        let tokens_path = get_tokens_path(symbol_prefix, self.external_tokens.as_deref());
+        let parser_module = get_module_name(symbol_prefix, "parser");
+        let file_name = format!("{}.rs", parser_module);
+        gen.generate_code(&file_name, |output| {
+            write_module(output, self.allow_lints, |output| {
+                writer.write_visitor_and_parser(&tokens_path, output)?;
+                if self.facade {
+                    write_facade(grammar, symbol_prefix, output)?;
+                }
+                Ok(())
+            })
         })
         .unwrap();
+        if self.cst {
+            let parse_tree = quote! {
+                /// Parses the input of the token function into a tree.
+                #[allow(dead_code)]
+                pub fn parse_tree<T: Clone, E: std::error::Error, F: FnMut() -> Result<(TokenType, T), E>>(
+                    token_function: F,
+                ) -> Result<Node<T>, ParserError<T, E>> {
+                    let mut builder = TreeBuilder::new();
+                    Parser::new(token_function, &mut builder).parse()?;
+                    Ok(builder.take_root().expect("a parsed input has a root"))
+                }
+            };
+            let file_name = format!("{}.rs", get_module_name(symbol_prefix, "cst"));
+            gen.generate_code(&file_name, |output| {
+                write_module(output, self.allow_lints, |output| {
+                    write_cst_module(
+                        grammar,
+                        &tokens_path,
+                        &parser_module,
+                        &parse_tree,
+                        false,
+                        false,
+                        output,
+                    )
+                })
+            })
+            .unwrap();
+        }
+        if self.ast {
+            let parse_ast = |root_type: &TokenStream| {
+                quote! {
+                    /// Parses the input of the token function into a typed syntax tree.
+                    #[allow(dead_code)]
+                    pub fn parse_ast<T: Clone, E: std::error::Error, F: FnMut() -> std::result::Result<(tokens::TokenType, T), E>>(
+                        token_function: F,
+                    ) -> std::result::Result<#root_type, parser::ParserError<T, E>> {
+                        let mut builder = AstBuilder::new();
+                        parser::Parser::new(token_function, &mut builder).parse()?;
+                        Ok(builder.take_root().expect("a parsed input has a root"))
+                    }
+                }
+            };
+            let file_name = format!("{}.rs", get_module_name(symbol_prefix, "ast"));
+            gen.generate_code(&file_name, |output| {
+                write_module(output, self.allow_lints, |output| {
+                    write_ast_module(
+                        grammar,
+                        &tokens_path,
+                        &parser_module,
+                        &parse_ast,
+                        false,
+                        false,
+                        output,
+                    )
+                })
+            })
+            .unwrap();
+        }
+    }
+
+    fn supports_token_insertion(&self) -> bool {
+        true
     }
 }
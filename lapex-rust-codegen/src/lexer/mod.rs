@@ -6,7 +6,7 @@ use lapex_input::{Spanned, TokenRule};
 use lapex_lexer::LexerCodeGen;
 use quote::{__private::TokenStream, quote};
 
-use crate::{get_token_enum_name, RustLexerCodeGen};
+use crate::{doc_attributes, get_module_name, get_token_enum_name, write_module, RustLexerCodeGen};
 
 struct TokensCodeWriter<'grammar> {
     rules: &'grammar [Spanned<TokenRule<'grammar>>],
@@ -14,21 +14,21 @@ struct TokensCodeWriter<'grammar> {
 
 impl<'grammar> TokensCodeWriter<'grammar> {
     fn write_token_enum(&self, output: &mut dyn Write) -> Result<(), std::io::Error> {
-        let mut other_tokens = Vec::new();
-        for rule in self.rules {
-            writeln!(
-                &mut other_tokens,
-                "{},",
-                get_token_enum_name(rule.inner.name)
-            )?;
-        }
-        let other_tokens: TokenStream = String::from_utf8(other_tokens).unwrap().parse().unwrap();
+        let other_tokens: Vec<TokenStream> = self
+            .rules
+            .iter()
+            .map(|rule| {
+                let name: TokenStream = get_token_enum_name(&rule.inner.name).parse().unwrap();
+                let doc = doc_attributes(rule.inner.doc.as_deref());
+                quote! { #doc #name }
+            })
+            .collect();
 
         let tokens = quote! {
             #[derive(Clone, Copy, Debug)]
             pub enum TokenType {
                 EndOfFile,
-                #other_tokens
+                #(#other_tokens,)*
             }
         };
         writeln!(output, "{}", tokens)
@@ -36,8 +36,10 @@ impl<'grammar> TokensCodeWriter<'grammar> {
 }
 
 struct LexerCodeWriter<'grammar> {
+    rules: &'grammar [Spanned<TokenRule<'grammar>>],
     alphabet: &'grammar [RangeInclusive<u32>],
     dfa: &'grammar Dfa<&'grammar TokenRule<'grammar>, usize>,
+    tokens_module: String,
 }
 
 impl<'grammar> LexerCodeWriter<'grammar> {
@@ -55,45 +57,67 @@ impl<'grammar> LexerCodeWriter<'grammar> {
             }
         }
 
-        let mut automaton_cases: Vec<TokenStream> = Vec::new();
+        let mut step_cases: Vec<TokenStream> = Vec::new();
+        let mut accepting_cases: Vec<TokenStream> = Vec::new();
+        let mut run_cases: Vec<TokenStream> = Vec::new();
         for (index, node) in self.dfa.states() {
             let state_id = index.index();
-            if state_id == 0 {
-                automaton_cases
-                    .push(quote! { (#state_id, 0) => { return Ok(TokenType::EndOfFile); } });
-            }
+            let mut ascii_loop_ranges: Vec<(u8, u8)> = Vec::new();
             for (transition, target) in self.dfa.transitions_from(index) {
+                // class 0 only holds the end of input marker and is never consumed
                 if *transition != 0 {
                     let target_index = target.index();
-                    automaton_cases.push(quote! {
-                        (#state_id, #transition) => {
-                            let next_ch = self.char_iter.next().unwrap();
-                            self.position += next_ch.len_utf8();
-                            state = #target_index;
-                        }
+                    step_cases.push(quote! {
+                        (#state_id, #transition) => Some(#target_index)
                     });
+                    let range = &self.alphabet[*transition];
+                    if target == index && *range.end() < 0x80 {
+                        ascii_loop_ranges.push((*range.start() as u8, *range.end() as u8));
+                    }
                 }
             }
-            if let AutomatonState::Accepting(accept) = node {
-                let name: TokenStream = get_token_enum_name(accept.name).parse().unwrap();
-                automaton_cases.push(quote! {
-                    (#state_id, _) => {
-                        return Ok(TokenType::#name);
+            if !ascii_loop_ranges.is_empty() {
+                let ascii_loop_ranges: Vec<TokenStream> = merge_byte_ranges(ascii_loop_ranges)
+                    .into_iter()
+                    .map(|(start, end)| quote! { #start..=#end })
+                    .collect();
+                run_cases.push(quote! {
+                    #state_id => {
+                        let rest = &self.src.as_bytes()[self.position..];
+                        self.position += rest.iter().position(|b| !matches!(b, #(#ascii_loop_ranges)|*)).unwrap_or(rest.len());
                     }
                 });
-            } else {
-                automaton_cases.push(quote! {
-                    (#state_id, transition) => {
-                        return Err(LexerError::UnexpectedAlphabet {
-                            range: Lexer::get_alphabet_range(transition).unwrap()
-                        });
-                    }
+            }
+            if let AutomatonState::Accepting(accept) = node {
+                let name: TokenStream = get_token_enum_name(&accept.name).parse().unwrap();
+                accepting_cases.push(quote! {
+                    #state_id => Some(TokenType::#name)
                 });
             }
         }
 
+        let skipped_tokens: Vec<TokenStream> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.inner.skip)
+            .map(|rule| get_token_enum_name(&rule.inner.name).parse().unwrap())
+            .collect();
+        // a skipped token is dropped and the next one scanned from where it ended
+        let skip_check = if skipped_tokens.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                if matches!(token, #(TokenType::#skipped_tokens)|*) {
+                    state = START_STATE;
+                    self.begin_token();
+                    continue;
+                }
+            }
+        };
+
+        let tokens_module: TokenStream = self.tokens_module.parse().unwrap();
         let tokens = quote! {
-            use super::tokens::TokenType;
+            use super::#tokens_module::TokenType;
 
             #[derive(Debug)]
             pub enum LexerError {
@@ -102,7 +126,9 @@ impl<'grammar> LexerCodeWriter<'grammar> {
                 },
                 UnexpectedAlphabet {
                     range: std::ops::RangeInclusive<u32>
-                }
+                },
+                /// The input of a `StreamLexer` could not be read, or is no valid UTF-8.
+                Read(std::io::Error)
             }
 
             impl std::error::Error for LexerError {}
@@ -119,26 +145,90 @@ impl<'grammar> LexerCodeWriter<'grammar> {
                             f,
                             "Lexer got char in unexpected range: {:?}",
                             range
-                        )
+                        ),
+                        LexerError::Read(error) => write!(f, "Lexer could not read the input: {}", error)
+                    }
+                }
+            }
+
+            /// The state every token starts to be scanned in.
+            pub const START_STATE: usize = 0;
+
+            /// The character class of `'\0'`, which the lexer treats as the end of input.
+            pub const END_OF_INPUT_CLASS: usize = 0;
+
+            /// Maps a character to its character class, the unit of transitions in the DFA.
+            pub fn classify(ch: char) -> Option<usize> {
+                Lexer::get_alphabet_index(ch as u32)
+            }
+
+            /// Returns the state the DFA moves to from `state` on a character of `class`.
+            pub fn step(state: usize, class: usize) -> Option<usize> {
+                match (state, class) {
+                    #( #step_cases, )*
+                    (_, _) => None
+                }
+            }
+
+            /// Returns the token recognized when scanning stops in `state`.
+            pub fn accepting(state: usize) -> Option<TokenType> {
+                match state {
+                    #( #accepting_cases, )*
+                    _ => None
+                }
+            }
+
+            /// A line and a column in the input, both starting at 1. Columns count characters, not
+            /// bytes.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct Location {
+                pub line: usize,
+                pub column: usize
+            }
+
+            impl Location {
+                // `\n`, `\r` and `\r\n` end a line, the `\n` of a `\r\n` split between two
+                // texts belongs to the `\r` at the end of the previous one
+                fn advance(&mut self, text: &str, after_cr: bool) {
+                    let mut after_cr = after_cr;
+                    for ch in text.chars() {
+                        match ch {
+                            '\n' if after_cr => {}
+                            '\n' | '\r' => {
+                                self.line += 1;
+                                self.column = 1;
+                            }
+                            _ => self.column += 1
+                        }
+                        after_cr = ch == '\r';
                     }
                 }
             }
 
+            /// The bytes of a token in the input, with the location of its first character and of
+            /// the character after it.
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct LocatedSpan {
+                pub span: std::ops::Range<usize>,
+                pub start: Location,
+                pub end: Location
+            }
+
             pub struct Lexer<'src> {
                 src: &'src str,
-                char_iter: std::iter::Peekable<std::str::Chars<'src>>,
                 start: usize,
-                position: usize
+                position: usize,
+                // the location of `start`, only advanced once the next token begins
+                start_location: Location
             }
 
             impl<'src> Lexer<'src> {
                 pub fn new(src: &'src str) -> Self {
-                    let char_iter = src.chars().peekable();
                     Lexer {
                         src,
-                        char_iter,
                         start: 0,
-                        position: 0
+                        position: 0,
+                        start_location: Location { line: 1, column: 1 }
                     }
                 }
 
@@ -156,25 +246,58 @@ impl<'grammar> LexerCodeWriter<'grammar> {
                     }
                 }
 
+                #[allow(clippy::should_implement_trait)]
                 pub fn next(&mut self) -> Result<TokenType, LexerError> {
-                    let mut state: usize = 0;
-                    self.start = self.position;
+                    let mut state = START_STATE;
+                    self.begin_token();
                     loop {
-                        let next_ch = self.char_iter.peek().copied().map(|c| c as u32).unwrap_or(0);
-                        let symbol = if let Some(symbol) = Lexer::get_alphabet_index(next_ch) {
-                            symbol
+                        let next_ch = self.src[self.position..].chars().next().unwrap_or('\0');
+                        let class = if let Some(class) = classify(next_ch) {
+                            class
                         } else {
                             return Err(LexerError::InvalidChar {
-                                bad_ch: next_ch
+                                bad_ch: next_ch as u32
                             });
                         };
-                        match (state, symbol) {
-                            #( #automaton_cases, )*
-                            (_, _) => unreachable!()
+                        if state == START_STATE && class == END_OF_INPUT_CLASS {
+                            return Ok(TokenType::EndOfFile);
+                        } else if let Some(target) = step(state, class) {
+                            self.position += next_ch.len_utf8();
+                            state = target;
+                            self.skip_ascii_run(state);
+                        } else if let Some(token) = accepting(state) {
+                            #skip_check
+                            return Ok(token);
+                        } else {
+                            return Err(LexerError::UnexpectedAlphabet {
+                                range: Lexer::get_alphabet_range(class).unwrap()
+                            });
                         }
                     }
                 }
 
+                // consumes the ascii bytes the DFA loops on in this state with a single scan of the
+                // bytes, like a run of spaces or the text of a comment. The run ends before the first
+                // byte which is not one of them, so it never ends inside a character. It is called
+                // after every step, so the states without a run do nothing but the match.
+                fn skip_ascii_run(&mut self, state: usize) {
+                    match state {
+                        #( #run_cases, )*
+                        _ => {}
+                    }
+                }
+
+                fn begin_token(&mut self) {
+                    let after_cr = self.after_cr();
+                    self.start_location.advance(&self.src[self.start..self.position], after_cr);
+                    self.start = self.position;
+                }
+
+                // whether the current token comes right after a `\r`
+                fn after_cr(&self) -> bool {
+                    self.start > 0 && self.src.as_bytes()[self.start - 1] == b'\r'
+                }
+
                 pub fn span(&self) -> std::ops::Range<usize> {
                     self.start..self.position
                 }
@@ -182,28 +305,301 @@ impl<'grammar> LexerCodeWriter<'grammar> {
                 pub fn slice(&self) -> &'src str {
                     &self.src[self.span()]
                 }
+
+                /// The location the current token starts at.
+                #[allow(dead_code)]
+                pub fn position(&self) -> Location {
+                    self.start_location
+                }
+
+                /// The span of the current token, with the locations it starts and ends at.
+                #[allow(dead_code)]
+                pub fn span_with_location(&self) -> LocatedSpan {
+                    let mut end = self.start_location;
+                    end.advance(self.slice(), self.after_cr());
+                    LocatedSpan {
+                        span: self.span(),
+                        start: self.start_location,
+                        end
+                    }
+                }
+
+                /// Lets the context decide the type of every token but `EndOfFile`.
+                #[allow(dead_code)]
+                pub fn with_context<C: LexerContext>(self, context: SharedContext<C>) -> ContextLexer<'src, C> {
+                    ContextLexer {
+                        lexer: self,
+                        context
+                    }
+                }
+            }
+
+            /// Feeds the state of the parser back into the lexer, for languages in which the type of
+            /// a token depends on what was parsed before, like the names declared by a `typedef` in C.
+            #[allow(dead_code)]
+            pub trait LexerContext {
+                fn classify(&mut self, token: TokenType, text: &str) -> TokenType;
+            }
+
+            /// The visitor of the parser keeps a clone to update the context the lexer reads.
+            #[allow(dead_code)]
+            pub type SharedContext<C> = std::rc::Rc<std::cell::RefCell<C>>;
+
+            /// A lexer which passes its tokens through a `LexerContext`. The LR parser asks for the next
+            /// token right after shifting the previous one, so the context contains the updates of
+            /// the visitor up to that shift.
+            #[allow(dead_code)]
+            pub struct ContextLexer<'src, C: LexerContext> {
+                lexer: Lexer<'src>,
+                context: SharedContext<C>
+            }
+
+            #[allow(dead_code)]
+            impl<'src, C: LexerContext> ContextLexer<'src, C> {
+                #[allow(clippy::should_implement_trait)]
+                pub fn next(&mut self) -> Result<TokenType, LexerError> {
+                    match self.lexer.next()? {
+                        TokenType::EndOfFile => Ok(TokenType::EndOfFile),
+                        token => Ok(self.context.borrow_mut().classify(token, self.lexer.slice()))
+                    }
+                }
+
+                pub fn span(&self) -> std::ops::Range<usize> {
+                    self.lexer.span()
+                }
+
+                pub fn slice(&self) -> &'src str {
+                    self.lexer.slice()
+                }
+
+                pub fn position(&self) -> Location {
+                    self.lexer.position()
+                }
+
+                pub fn span_with_location(&self) -> LocatedSpan {
+                    self.lexer.span_with_location()
+                }
+
+                pub fn context(&self) -> &SharedContext<C> {
+                    &self.context
+                }
+            }
+
+            /// A lexer which reads its input character by character instead of borrowing it whole,
+            /// for large files or network streams. Only the text of the current token is kept.
+            #[allow(dead_code)]
+            pub struct StreamLexer<I: Iterator<Item = std::io::Result<char>>> {
+                input: I,
+                // the character after the current token, once it is read
+                peeked: Option<char>,
+                ended: bool,
+                text: String,
+                start: usize,
+                start_location: Location,
+                // whether the character before the current token is a `\r`
+                after_cr: bool
+            }
+
+            #[allow(dead_code)]
+            impl<I: Iterator<Item = std::io::Result<char>>> StreamLexer<I> {
+                pub fn new(input: I) -> Self {
+                    StreamLexer {
+                        input,
+                        peeked: None,
+                        ended: false,
+                        text: String::new(),
+                        start: 0,
+                        start_location: Location { line: 1, column: 1 },
+                        after_cr: false
+                    }
+                }
+
+                fn peek(&mut self) -> Result<char, LexerError> {
+                    if self.peeked.is_none() && !self.ended {
+                        match self.input.next() {
+                            Some(Ok(ch)) => self.peeked = Some(ch),
+                            Some(Err(error)) => return Err(LexerError::Read(error)),
+                            None => self.ended = true
+                        }
+                    }
+                    Ok(self.peeked.unwrap_or('\0'))
+                }
+
+                #[allow(clippy::should_implement_trait)]
+                pub fn next(&mut self) -> Result<TokenType, LexerError> {
+                    let mut state = START_STATE;
+                    self.begin_token();
+                    loop {
+                        let next_ch = self.peek()?;
+                        let class = if let Some(class) = classify(next_ch) {
+                            class
+                        } else {
+                            return Err(LexerError::InvalidChar {
+                                bad_ch: next_ch as u32
+                            });
+                        };
+                        if state == START_STATE && class == END_OF_INPUT_CLASS {
+                            return Ok(TokenType::EndOfFile);
+                        } else if let Some(target) = step(state, class) {
+                            self.text.push(self.peeked.take().unwrap());
+                            state = target;
+                        } else if let Some(token) = accepting(state) {
+                            #skip_check
+                            return Ok(token);
+                        } else {
+                            return Err(LexerError::UnexpectedAlphabet {
+                                range: Lexer::get_alphabet_range(class).unwrap()
+                            });
+                        }
+                    }
+                }
+
+                fn begin_token(&mut self) {
+                    self.start_location.advance(&self.text, self.after_cr);
+                    self.start += self.text.len();
+                    if let Some(last) = self.text.chars().last() {
+                        self.after_cr = last == '\r';
+                    }
+                    self.text.clear();
+                }
+
+                /// The bytes of the current token, counted in UTF-8 from the start of the input.
+                pub fn span(&self) -> std::ops::Range<usize> {
+                    self.start..self.start + self.text.len()
+                }
+
+                /// The text of the current token, which is only kept until the next one is read.
+                pub fn slice(&self) -> &str {
+                    &self.text
+                }
+
+                /// The location the current token starts at.
+                pub fn position(&self) -> Location {
+                    self.start_location
+                }
+
+                /// The span of the current token, with the locations it starts and ends at.
+                pub fn span_with_location(&self) -> LocatedSpan {
+                    let mut end = self.start_location;
+                    end.advance(&self.text, self.after_cr);
+                    LocatedSpan {
+                        span: self.span(),
+                        start: self.start_location,
+                        end
+                    }
+                }
+            }
+
+            #[allow(dead_code)]
+            impl<C: Iterator<Item = char>> StreamLexer<std::iter::Map<C, fn(char) -> std::io::Result<char>>> {
+                /// Lexes characters which can not fail to be read.
+                pub fn from_chars<T: IntoIterator<IntoIter = C>>(chars: T) -> Self {
+                    StreamLexer::new(chars.into_iter().map(Ok as fn(char) -> std::io::Result<char>))
+                }
+            }
+
+            #[allow(dead_code)]
+            impl<R: std::io::Read> StreamLexer<Utf8Chars<R>> {
+                /// Lexes the UTF-8 text of a reader, which is buffered internally.
+                pub fn from_reader(reader: R) -> Self {
+                    StreamLexer::new(Utf8Chars {
+                        bytes: std::io::Read::bytes(std::io::BufReader::new(reader))
+                    })
+                }
+            }
+
+            /// Decodes the characters of a UTF-8 reader. Invalid UTF-8 is reported as an error of
+            /// kind `InvalidData`.
+            #[allow(dead_code)]
+            pub struct Utf8Chars<R: std::io::Read> {
+                bytes: std::io::Bytes<std::io::BufReader<R>>
+            }
+
+            impl<R: std::io::Read> Iterator for Utf8Chars<R> {
+                type Item = std::io::Result<char>;
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    let first = match self.bytes.next()? {
+                        Ok(first) => first,
+                        Err(error) => return Some(Err(error))
+                    };
+                    let width = match first {
+                        0x00..=0x7f => 1,
+                        0xc0..=0xdf => 2,
+                        0xe0..=0xef => 3,
+                        0xf0..=0xf7 => 4,
+                        _ => 0
+                    };
+                    let mut encoded = [first, 0, 0, 0];
+                    for byte in encoded.iter_mut().take(width).skip(1) {
+                        match self.bytes.next() {
+                            Some(Ok(next)) => *byte = next,
+                            Some(Err(error)) => return Some(Err(error)),
+                            None => break
+                        }
+                    }
+                    let decoded = std::str::from_utf8(&encoded[..width])
+                        .ok()
+                        .and_then(|text| text.chars().next());
+                    Some(decoded.ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+                    }))
+                }
             }
         };
         writeln!(output, "{}", tokens)
     }
 }
 
+fn merge_byte_ranges(mut ranges: Vec<(u8, u8)>) -> Vec<(u8, u8)> {
+    ranges.sort();
+    let mut merged: Vec<(u8, u8)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some((_, prev_end)) if *prev_end as u16 + 1 >= start as u16 => {
+                *prev_end = (*prev_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
 impl LexerCodeGen for RustLexerCodeGen {
     fn generate_lexer(
         &self,
-        _rules: &[Spanned<TokenRule>],
+        rules: &[Spanned<TokenRule>],
         alphabet: &[RangeInclusive<u32>],
         dfa: &Dfa<&TokenRule, usize>,
         gen: &mut GeneratedCodeWriter,
     ) {
-        let writer = LexerCodeWriter { alphabet, dfa };
-        gen.generate_code("lexer.rs", |output| writer.write_lexer(output))
-            .unwrap();
+        let symbol_prefix = self.symbol_prefix.as_deref();
+        let writer = LexerCodeWriter {
+            rules,
+            alphabet,
+            dfa,
+            tokens_module: get_module_name(symbol_prefix, "tokens"),
+        };
+        let file_name = format!("{}.rs", get_module_name(symbol_prefix, "lexer"));
+        gen.generate_code(&file_name, |output| {
+            write_module(output, self.allow_lints, |output| {
+                writer.write_lexer(output)
+            })
+        })
+        .unwrap();
     }
 
     fn generate_tokens(&self, rules: &[Spanned<TokenRule>], gen: &mut GeneratedCodeWriter) {
         let writer = TokensCodeWriter { rules };
-        gen.generate_code("tokens.rs", |output| writer.write_token_enum(output))
-            .unwrap();
+        let file_name = format!(
+            "{}.rs",
+            get_module_name(self.symbol_prefix.as_deref(), "tokens")
+        );
+        gen.generate_code(&file_name, |output| {
+            write_module(output, self.allow_lints, |output| {
+                writer.write_token_enum(output)
+            })
+        })
+        .unwrap();
     }
 }
@@ -1,10 +1,31 @@
-use lapex_parser::grammar::{Grammar, Symbol};
+use std::{collections::BTreeSet, io::Write};
 
-pub struct RustLexerCodeGen {}
+use lapex_codegen::write_user_code_section;
+use lapex_parser::grammar::{Grammar, Rule, Symbol, TokenInsertion};
+use quote::{__private::TokenStream, quote};
+
+pub struct RustLexerCodeGen {
+    symbol_prefix: Option<String>,
+    allow_lints: bool,
+}
 
 impl RustLexerCodeGen {
     pub fn new() -> Self {
-        RustLexerCodeGen {}
+        RustLexerCodeGen {
+            symbol_prefix: None,
+            allow_lints: false,
+        }
+    }
+
+    /// Starts the generated files with `#![allow]` for the lints a crate may enable beyond the
+    /// defaults of rustc and clippy.
+    pub fn set_allow_lints(&mut self, allow_lints: bool) {
+        self.allow_lints = allow_lints;
+    }
+
+    /// Writes the `lexer` and `tokens` modules as `<prefix>_lexer` and `<prefix>_tokens`.
+    pub fn set_symbol_prefix(&mut self, symbol_prefix: Option<&str>) {
+        self.symbol_prefix = symbol_prefix.map(str::to_string);
     }
 }
 
@@ -28,11 +49,83 @@ impl Default for RustLLParserCodeGen {
     }
 }
 
-pub struct RustLRParserCodeGen {}
+pub struct RustLRParserCodeGen {
+    panic_free: bool,
+    metrics: bool,
+    progress: bool,
+    symbol_prefix: Option<String>,
+    allow_lints: bool,
+    cst: bool,
+    ast: bool,
+    external_tokens: Option<String>,
+    facade: bool,
+}
 
 impl RustLRParserCodeGen {
     pub fn new() -> Self {
-        RustLRParserCodeGen {}
+        RustLRParserCodeGen {
+            panic_free: false,
+            metrics: false,
+            progress: false,
+            symbol_prefix: None,
+            allow_lints: false,
+            cst: false,
+            ast: false,
+            external_tokens: None,
+            facade: false,
+        }
+    }
+
+    pub fn set_panic_free(&mut self, panic_free: bool) {
+        self.panic_free = panic_free;
+    }
+
+    /// Counts what the parser does during a parse and reports it through the `Metrics` trait
+    /// once the parse ends.
+    pub fn set_metrics(&mut self, metrics: bool) {
+        self.metrics = metrics;
+    }
+
+    /// Lets a hook be passed to the parser with `with_progress`, which is called every few
+    /// tokens during a parse through the `Progress` trait.
+    pub fn set_progress(&mut self, progress: bool) {
+        self.progress = progress;
+    }
+
+    /// Writes the `parser` module as `<prefix>_parser`, which uses the tokens of the lexer
+    /// generated with the same prefix.
+    pub fn set_symbol_prefix(&mut self, symbol_prefix: Option<&str>) {
+        self.symbol_prefix = symbol_prefix.map(str::to_string);
+    }
+
+    /// Starts the generated file with `#![allow]` for the lints a crate may enable beyond the
+    /// defaults of rustc and clippy.
+    pub fn set_allow_lints(&mut self, allow_lints: bool) {
+        self.allow_lints = allow_lints;
+    }
+
+    /// Writes the `cst` module next to the parser, with a visitor building a concrete syntax
+    /// tree and a `parse_tree` function returning it.
+    pub fn set_cst(&mut self, cst: bool) {
+        self.cst = cst;
+    }
+
+    /// Writes the `ast` module next to the parser, with a type for every production, a visitor
+    /// assembling them and a `parse_ast` function returning the value of the entry production.
+    pub fn set_ast(&mut self, ast: bool) {
+        self.ast = ast;
+    }
+
+    /// Takes `TokenType` from the module at this path, like `crate::lexer`, instead of the
+    /// generated tokens module. Its variants are named like the generated ones.
+    pub fn set_external_tokens(&mut self, external_tokens: Option<&str>) {
+        self.external_tokens = external_tokens.map(str::to_string);
+    }
+
+    /// Writes a `parse_str` function into the parser module, which lexes and parses a string
+    /// in one call with the lexer generated with the same prefix.
+    pub fn set_facade(&mut self, facade: bool) {
+        self.facade = facade;
     }
 }
 
@@ -42,11 +135,83 @@ impl Default for RustLRParserCodeGen {
     }
 }
 
-pub struct RustGLRParserCodeGen {}
+pub struct RustGLRParserCodeGen {
+    panic_free: bool,
+    metrics: bool,
+    progress: bool,
+    symbol_prefix: Option<String>,
+    allow_lints: bool,
+    cst: bool,
+    ast: bool,
+    external_tokens: Option<String>,
+    facade: bool,
+}
 
 impl RustGLRParserCodeGen {
     pub fn new() -> Self {
-        RustGLRParserCodeGen {}
+        RustGLRParserCodeGen {
+            panic_free: false,
+            metrics: false,
+            progress: false,
+            symbol_prefix: None,
+            allow_lints: false,
+            cst: false,
+            ast: false,
+            external_tokens: None,
+            facade: false,
+        }
+    }
+
+    pub fn set_panic_free(&mut self, panic_free: bool) {
+        self.panic_free = panic_free;
+    }
+
+    /// Counts what the parser does during a parse and reports it through the `Metrics` trait
+    /// once the parse ends.
+    pub fn set_metrics(&mut self, metrics: bool) {
+        self.metrics = metrics;
+    }
+
+    /// Lets a hook be passed to the parser with `with_progress`, which is called every few
+    /// tokens during a parse through the `Progress` trait.
+    pub fn set_progress(&mut self, progress: bool) {
+        self.progress = progress;
+    }
+
+    /// Writes the `parser` module as `<prefix>_parser`, which uses the tokens of the lexer
+    /// generated with the same prefix.
+    pub fn set_symbol_prefix(&mut self, symbol_prefix: Option<&str>) {
+        self.symbol_prefix = symbol_prefix.map(str::to_string);
+    }
+
+    /// Starts the generated file with `#![allow]` for the lints a crate may enable beyond the
+    /// defaults of rustc and clippy.
+    pub fn set_allow_lints(&mut self, allow_lints: bool) {
+        self.allow_lints = allow_lints;
+    }
+
+    /// Writes the `cst` module next to the parser, with a visitor building a concrete syntax
+    /// tree and a `parse_tree` function returning it.
+    pub fn set_cst(&mut self, cst: bool) {
+        self.cst = cst;
+    }
+
+    /// Writes the `ast` module next to the parser, with a type for every production, a visitor
+    /// assembling them and a `parse_ast` function returning the value of the entry production.
+    pub fn set_ast(&mut self, ast: bool) {
+        self.ast = ast;
+    }
+
+    /// Takes `TokenType` from the module at this path, like `crate::lexer`, instead of the
+    /// generated tokens module. Its variants are named like the generated ones.
+    pub fn set_external_tokens(&mut self, external_tokens: Option<&str>) {
+        self.external_tokens = external_tokens.map(str::to_string);
+    }
+
+    /// Writes a `parse_str` function into the parser module, which lexes and parses a string
+    /// in one call with the lexer generated with the same prefix.
+    pub fn set_facade(&mut self, facade: bool) {
+        self.facade = facade;
     }
 }
 
@@ -56,18 +221,488 @@ impl Default for RustGLRParserCodeGen {
     }
 }
 
+// generated modules are named after the prefix, so several parsers can share a parent module
+fn get_module_name(symbol_prefix: Option<&str>, module: &str) -> String {
+    match symbol_prefix {
+        Some(prefix) => format!("{}_{}", prefix, module),
+        None => module.to_string(),
+    }
+}
+
+// the path the parsers import `TokenType` from
+fn get_tokens_path(symbol_prefix: Option<&str>, external_tokens: Option<&str>) -> String {
+    match external_tokens {
+        Some(path) => path.to_string(),
+        None => format!("super::{}", get_module_name(symbol_prefix, "tokens")),
+    }
+}
+
+// an enum from outside has to give the tokens the ids declared with `extern token`, which is
+// checked when the parser is compiled
+fn external_token_checks(grammar: &Grammar) -> TokenStream {
+    let checks = grammar
+        .terminals_with_names()
+        .filter_map(|(terminal, name)| {
+            let id = grammar.get_external_token_id(&terminal)?;
+            let variant: TokenStream = get_token_enum_name(name).parse().unwrap();
+            let message = format!("the token {} has to have the id {}", name, id);
+            Some(quote! {
+                const _: () = assert!(TokenType::#variant as u32 == #id, #message);
+            })
+        });
+    quote! { #(#checks)* }
+}
+
+// the `///` comment of a token or production, as doc attributes
+fn doc_attributes(doc: Option<&str>) -> TokenStream {
+    let lines = doc.into_iter().flat_map(str::lines).map(|line| match line {
+        "" => String::new(),
+        line => format!(" {}", line),
+    });
+    quote! { #(#[doc = #lines])* }
+}
+
+// the documentation of the production comes before the rule it was lowered to
+fn reduce_method_doc(grammar: &Grammar, rule: &Rule) -> TokenStream {
+    let doc = grammar.get_rule_doc(rule);
+    let separator = doc.map(|_| quote! { #[doc = ""] });
+    let doc = doc_attributes(doc);
+    let rule = rule.display(grammar).to_string();
+    quote! {
+        #doc
+        #separator
+        #[doc = #rule]
+    }
+}
+
+// the visitor method of a rule is named after its non-terminal, alternatives are told apart by
+// their tag or else by their position among the rules of the non-terminal
+fn visitor_method_name(grammar: &Grammar, rule: &Rule, index: usize) -> String {
+    let non_terminal = rule.lhs().expect("every rule has a non-terminal");
+    let non_terminal_name = grammar
+        .get_non_terminal_name(&non_terminal)
+        .expect("every non-terminal has a name");
+    let alternatives = grammar
+        .rules()
+        .iter()
+        .filter(|other| other.lhs() == Some(non_terminal))
+        .count();
+    if alternatives == 1 {
+        format!("reduce_{}", non_terminal_name)
+    } else if let Some(tag) = rule.tag() {
+        format!("reduce_{}_{}", non_terminal_name, tag)
+    } else {
+        format!("reduce_{}_{}", non_terminal_name, index + 1)
+    }
+}
+
+// the generated code passes the default lints, these are the groups and lints a crate can add
+const ALLOWED_LINTS: &[&str] = &[
+    "missing_docs",
+    "unreachable_pub",
+    "unused_qualifications",
+    "unused_results",
+    "rust_2018_idioms",
+    "clippy::all",
+    "clippy::pedantic",
+    "clippy::nursery",
+    "clippy::restriction",
+];
+
+// surrounds the module with sections for the imports and helpers of the user
+fn write_module<F>(output: &mut dyn Write, allow_lints: bool, write_code: F) -> std::io::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> std::io::Result<()>,
+{
+    // inner attributes have to come before the imports
+    if allow_lints {
+        writeln!(output, "#![allow({})]", ALLOWED_LINTS.join(", "))?;
+        writeln!(output)?;
+    }
+    write_user_code_section(output, "imports")?;
+    writeln!(output)?;
+    write_code(output)?;
+    writeln!(output)?;
+    write_user_code_section(output, "code")
+}
+
 fn get_token_enum_name(name: &str) -> String {
     format!("Tk{}", convert_snake_to_upper_camel(name))
 }
 
 fn get_non_terminal_enum_name(grammar: &Grammar, non_terminal: Symbol) -> String {
-    if let Some(name) = grammar.get_production_name(&non_terminal) {
-        format!("Nt{}", convert_snake_to_upper_camel(name))
+    let name = grammar
+        .get_non_terminal_name(&non_terminal)
+        .expect("every non-terminal has a name");
+    format!("Nt{}", convert_snake_to_upper_camel(name))
+}
+
+// expects `state` and `next_token` to be in scope where the tokens are inserted
+fn make_internal_state_error(panic_free: bool) -> TokenStream {
+    let result = make_internal_state_result(panic_free);
+    if panic_free {
+        quote! { return #result }
     } else {
-        if let Symbol::NonTerminal(non_terminal_index) = non_terminal {
-            format!("NtAnon{}", non_terminal_index)
-        } else {
-            unreachable!()
+        result
+    }
+}
+
+// like `make_internal_state_error`, but as the value of a function returning the error
+fn make_internal_state_result(panic_free: bool) -> TokenStream {
+    if panic_free {
+        quote! {
+            Err(ParserError::InternalState { state, token: next_token })
+        }
+    } else {
+        quote! {
+            unreachable!("invalid parser state {} for token {:?}", state, next_token)
+        }
+    }
+}
+
+fn make_internal_state_variant(panic_free: bool) -> (TokenStream, TokenStream) {
+    if panic_free {
+        (
+            quote! {
+                InternalState {
+                    state: usize,
+                    token: TokenType,
+                },
+            },
+            quote! {
+                ParserError::InternalState { state, token } => write!(
+                    f,
+                    "Parser reached invalid state {} with token {:?}",
+                    state, token
+                ),
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    }
+}
+
+// the parts of token filtering that do not depend on the signature of the token function
+fn make_token_filter_support() -> TokenStream {
+    quote! {
+        /// What a token filter does with a token before the parser sees it.
+        #[allow(dead_code)]
+        pub enum FilterAction<T> {
+            /// Passes the token on.
+            Keep,
+            /// Drops the token.
+            Drop,
+            /// Passes another token on in place of this one.
+            Replace(TokenType, T),
+            /// Passes these tokens on before the token itself.
+            Inject(Vec<(TokenType, T)>),
+        }
+
+        // `EndOfFile` always reaches the parser, dropping it keeps it and a replacement is
+        // passed on before it
+        fn apply_filter<T, P: FnMut(&(TokenType, T)) -> FilterAction<T>>(
+            filter: &mut P,
+            token: (TokenType, T),
+            pending: &mut std::collections::VecDeque<(TokenType, T)>,
+        ) {
+            let is_end = matches!(token.0, TokenType::EndOfFile);
+            match filter(&token) {
+                FilterAction::Keep => pending.push_back(token),
+                FilterAction::Drop => {
+                    if is_end {
+                        pending.push_back(token);
+                    }
+                }
+                FilterAction::Replace(replacement, data) => {
+                    pending.push_back((replacement, data));
+                    if is_end {
+                        pending.push_back(token);
+                    }
+                }
+                FilterAction::Inject(tokens) => {
+                    pending.extend(tokens);
+                    pending.push_back(token);
+                }
+            }
+        }
+    }
+}
+
+// the parts of the parser which collect and report metrics, all of them empty without metrics
+struct MetricsSupport {
+    items: TokenStream,
+    fields: TokenStream,
+    init: TokenStream,
+    carry_over: TokenStream,
+    methods: TokenStream,
+}
+
+// the counters differ between the parsers, the report at the end of a parse does not
+fn make_metrics_support(metrics: bool, counters: TokenStream) -> MetricsSupport {
+    if !metrics {
+        return MetricsSupport {
+            items: quote! {},
+            fields: quote! {},
+            init: quote! {},
+            carry_over: quote! {},
+            methods: quote! {},
+        };
+    }
+    MetricsSupport {
+        items: quote! {
+            /// What the parser did during the last parse.
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+            pub struct ParseMetrics {
+                #counters
+            }
+
+            /// Receives the metrics at the end of every parse, also of one that failed.
+            pub trait Metrics {
+                fn report(&mut self, metrics: &ParseMetrics);
+            }
+
+            impl<M: FnMut(&ParseMetrics)> Metrics for M {
+                fn report(&mut self, metrics: &ParseMetrics) {
+                    self(metrics)
+                }
+            }
+        },
+        fields: quote! {
+            metrics: ParseMetrics,
+            metrics_sink: Option<Box<dyn Metrics>>,
+        },
+        init: quote! {
+            metrics: ParseMetrics::default(),
+            metrics_sink: None,
+        },
+        carry_over: quote! {
+            metrics: self.metrics,
+            metrics_sink: self.metrics_sink,
+        },
+        methods: quote! {
+            /// Reports the metrics of every parse to `sink`.
+            #[allow(dead_code)]
+            pub fn with_metrics<M: Metrics + 'static>(mut self, sink: M) -> Self {
+                self.metrics_sink = Some(Box::new(sink));
+                self
+            }
+
+            /// The metrics of the last parse, or of the current one while parsing.
+            #[allow(dead_code)]
+            pub fn metrics(&self) -> &ParseMetrics {
+                &self.metrics
+            }
+
+            fn report_metrics(&mut self) {
+                if let Some(sink) = &mut self.metrics_sink {
+                    sink.report(&self.metrics);
+                }
+            }
+        },
+    }
+}
+
+// the parts of the parser which call the progress hook, all of them empty without progress
+struct ProgressSupport {
+    items: TokenStream,
+    fields: TokenStream,
+    init: TokenStream,
+    carry_over: TokenStream,
+    methods: TokenStream,
+    reset: TokenStream,
+    count_token: TokenStream,
+    count_reduction: TokenStream,
+}
+
+fn make_progress_support(progress: bool) -> ProgressSupport {
+    if !progress {
+        return ProgressSupport {
+            items: quote! {},
+            fields: quote! {},
+            init: quote! {},
+            carry_over: quote! {},
+            methods: quote! {},
+            reset: quote! {},
+            count_token: quote! {},
+            count_reduction: quote! {},
+        };
+    }
+    ProgressSupport {
+        items: quote! {
+            /// How far the current parse got, passed to the progress hook.
+            #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+            pub struct ParseProgress {
+                /// The tokens consumed so far, without `EndOfFile`.
+                pub tokens_consumed: usize,
+                /// The rules reduced so far.
+                pub reductions: usize,
+            }
+
+            /// Receives the progress of a parse every few tokens.
+            pub trait Progress {
+                fn progress(&mut self, progress: &ParseProgress);
+            }
+
+            impl<P: FnMut(&ParseProgress)> Progress for P {
+                fn progress(&mut self, progress: &ParseProgress) {
+                    self(progress)
+                }
+            }
+        },
+        fields: quote! {
+            progress: ParseProgress,
+            progress_hook: Option<(usize, Box<dyn Progress>)>,
+            // the tokens left until the hook is called next
+            progress_countdown: usize,
+        },
+        init: quote! {
+            progress: ParseProgress::default(),
+            progress_hook: None,
+            progress_countdown: 0,
+        },
+        carry_over: quote! {
+            progress: self.progress,
+            progress_hook: self.progress_hook,
+            progress_countdown: self.progress_countdown,
+        },
+        methods: quote! {
+            /// Calls `hook` every `interval` tokens during a parse, so the progress of long inputs
+            /// can be shown.
+            #[allow(dead_code)]
+            pub fn with_progress<P: Progress + 'static>(mut self, interval: usize, hook: P) -> Self {
+                self.progress_hook = Some((interval.max(1), Box::new(hook)));
+                self
+            }
+
+            fn reset_progress(&mut self) {
+                self.progress = ParseProgress::default();
+                if let Some((interval, _)) = &self.progress_hook {
+                    self.progress_countdown = *interval;
+                }
+            }
+
+            fn advance_progress(&mut self) {
+                self.progress.tokens_consumed += 1;
+                if let Some((interval, hook)) = &mut self.progress_hook {
+                    self.progress_countdown -= 1;
+                    if self.progress_countdown == 0 {
+                        self.progress_countdown = *interval;
+                        hook.progress(&self.progress);
+                    }
+                }
+            }
+        },
+        reset: quote! { self.reset_progress(); },
+        count_token: quote! { self.advance_progress(); },
+        count_reduction: quote! { self.progress.reductions += 1; },
+    }
+}
+
+// the lexer module `parse_str` reads the tokens from, and the filter applying the insertion rules
+// of the grammar to them, if it has any
+fn make_facade_support(
+    grammar: &Grammar,
+    symbol_prefix: Option<&str>,
+) -> (TokenStream, TokenStream) {
+    let lexer_module: TokenStream = get_module_name(symbol_prefix, "lexer").parse().unwrap();
+    let filter = if grammar.token_insertions().is_empty() {
+        quote! {}
+    } else {
+        quote! { .with_filter(insert_tokens()) }
+    };
+    (quote! { super::#lexer_module }, filter)
+}
+
+// lowers the insertion rules of the grammar to a token filter, where the first matching rule wins
+fn make_token_insertion(grammar: &Grammar) -> TokenStream {
+    let insertions = grammar.token_insertions();
+    if insertions.is_empty() {
+        return quote! {};
+    }
+    let token_type = |symbol: Symbol| -> TokenStream {
+        match symbol {
+            Symbol::Terminal(index) => get_token_enum_name(grammar.get_token_name(index))
+                .parse()
+                .unwrap(),
+            _ => unreachable!("insertion rules only refer to tokens"),
+        }
+    };
+    // one arm per rule, leaving out the tokens already matched by an earlier rule
+    let make_arms = |insertions: &[&TokenInsertion],
+                     make_action: &dyn Fn(TokenStream) -> TokenStream| {
+        let mut matched = BTreeSet::new();
+        let mut arms = Vec::new();
+        for insertion in insertions {
+            let after: Vec<TokenStream> = insertion
+                .after
+                .iter()
+                .filter(|symbol| matched.insert(**symbol))
+                .map(|symbol| token_type(*symbol))
+                .collect();
+            if !after.is_empty() {
+                let action = make_action(token_type(insertion.token));
+                arms.push(quote! {
+                    Some(#(TokenType::#after)|*) => #action,
+                });
+            }
+        }
+        arms
+    };
+
+    let mut triggers: Vec<Symbol> = Vec::new();
+    for insertion in insertions {
+        if !triggers.contains(&insertion.trigger) {
+            triggers.push(insertion.trigger);
+        }
+    }
+    let trigger_arms: Vec<TokenStream> = triggers
+        .into_iter()
+        .map(|trigger| {
+            let rules: Vec<&TokenInsertion> = insertions
+                .iter()
+                .filter(|insertion| insertion.trigger == trigger)
+                .collect();
+            let arms = make_arms(&rules, &|token| {
+                quote! {
+                    {
+                        previous = Some(TokenType::#token);
+                        FilterAction::Replace(TokenType::#token, data.clone())
+                    }
+                }
+            });
+            let trigger = token_type(trigger);
+            quote! {
+                TokenType::#trigger => match previous {
+                    #(#arms)*
+                    _ => FilterAction::Drop,
+                },
+            }
+        })
+        .collect();
+    let rules: Vec<&TokenInsertion> = insertions.iter().collect();
+    let end_arms = make_arms(&rules, &|token| {
+        quote! {
+            FilterAction::Inject(vec![(TokenType::#token, data.clone())])
+        }
+    });
+
+    quote! {
+        /// Applies the insertion rules of the grammar as a token filter. Filters that drop tokens
+        /// have to come before this one, as the dropped tokens would count as the previous token.
+        #[allow(dead_code)]
+        pub fn insert_tokens<T: Clone>() -> impl FnMut(&(TokenType, T)) -> FilterAction<T> {
+            let mut previous = None;
+            move |(token, data): &(TokenType, T)| match token {
+                #(#trigger_arms)*
+                TokenType::EndOfFile => match previous {
+                    #(#end_arms)*
+                    _ => FilterAction::Keep,
+                },
+                token => {
+                    previous = Some(*token);
+                    FilterAction::Keep
+                }
+            }
         }
     }
 }
@@ -82,7 +717,15 @@ fn convert_snake_to_upper_camel(name: &str) -> String {
         .join("")
 }
 
+mod ast;
+mod cst;
 mod glr_parser;
 mod lexer;
 mod ll_parser;
 mod lr_parser;
+mod scaffold;
+
+pub use scaffold::write_visitor_scaffold;
+
+#[cfg(test)]
+mod tests;
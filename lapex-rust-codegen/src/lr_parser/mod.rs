@@ -7,17 +7,38 @@ use lapex_parser::{
 };
 use quote::{__private::TokenStream, quote};
 
-use crate::{get_non_terminal_enum_name, get_token_enum_name, RustLRParserCodeGen};
+use crate::{
+    ast::write_ast_module, cst::write_cst_module, external_token_checks, get_module_name,
+    get_non_terminal_enum_name, get_token_enum_name, get_tokens_path, make_facade_support,
+    make_internal_state_error, make_internal_state_result, make_internal_state_variant,
+    make_metrics_support, make_progress_support, make_token_filter_support, make_token_insertion,
+    reduce_method_doc, visitor_method_name, write_module, MetricsSupport, ProgressSupport,
+    RustLRParserCodeGen,
+};
+
+// the most states whose actions or gotos are matched in one generated function
+const STATES_PER_FUNCTION: usize = 256;
 
 struct CodeWriter<'grammar, 'rules> {
     grammar: &'grammar Grammar<'grammar>,
     parser_table: &'grammar ActionGotoTable<'grammar, 'rules>,
     rule_index_map: BTreeMap<*const Rule<'rules>, usize>,
     rules_by_non_terminal: BTreeMap<Symbol, Vec<&'grammar Rule<'rules>>>,
+    panic_free: bool,
+    metrics: bool,
+    progress: bool,
+    error_recovery: bool,
+    sync_recovery: bool,
 }
 
 impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
-    fn new(grammar: &'grammar Grammar, parser_table: &'grammar ActionGotoTable) -> Self {
+    fn new(
+        grammar: &'grammar Grammar,
+        parser_table: &'grammar ActionGotoTable,
+        panic_free: bool,
+        metrics: bool,
+        progress: bool,
+    ) -> Self {
         let mut rules_by_non_terminal = BTreeMap::new();
         for rule in grammar.rules() {
             if let Some(non_terminal) = rule.lhs() {
@@ -38,42 +59,24 @@ impl<'grammar: 'rules, 'rules> CodeWriter<'grammar, 'rules> {
             parser_table,
             rule_index_map,
             rules_by_non_terminal,
+            panic_free,
+            metrics,
+            progress,
+            error_recovery: grammar.has_error_productions(),
+            sync_recovery: !grammar.sync_tokens().is_empty(),
         }
     }
 }
 
 impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
-    fn get_non_terminal_name(&self, non_terminal: &Symbol) -> String {
-        let non_terminal_name = self
-            .grammar
-            .get_production_name(non_terminal)
-            .map(|s| String::from(s))
-            .unwrap_or_else(|| {
-                if let Symbol::NonTerminal(index) = non_terminal {
-                    format!("anon{}", index)
-                } else {
-                    unreachable!()
-                }
-            });
-        non_terminal_name
-    }
-
     fn write_visitor(&self, output: &mut dyn Write) -> std::io::Result<()> {
         let mut reduce_functions: Vec<TokenStream> = Vec::new();
 
-        for (non_terminal, rules) in &self.rules_by_non_terminal {
-            let non_terminal_name = self.get_non_terminal_name(non_terminal);
+        for rules in self.rules_by_non_terminal.values() {
             if rules.len() != 1 {
                 for (i, rule) in rules.iter().enumerate() {
-                    let comment: TokenStream = format!("///{}", rule.display(self.grammar))
-                        .parse()
-                        .unwrap();
-                    let tag = rule.rule().inner.tag;
-                    let name = if let Some(tag) = tag {
-                        format!("reduce_{}_{}", non_terminal_name, tag)
-                    } else {
-                        format!("reduce_{}_{}", non_terminal_name, i + 1)
-                    };
+                    let comment = reduce_method_doc(self.grammar, rule);
+                    let name = visitor_method_name(self.grammar, rule, i);
                     let function: TokenStream = name.parse().unwrap();
                     reduce_functions.push(quote! {
                         #comment
@@ -81,11 +84,10 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                     });
                 }
             } else {
-                let comment: TokenStream = format!("///{}", rules[0].display(self.grammar))
+                let comment = reduce_method_doc(self.grammar, rules[0]);
+                let function: TokenStream = visitor_method_name(self.grammar, rules[0], 0)
                     .parse()
                     .unwrap();
-                let function: TokenStream =
-                    format!("reduce_{}", non_terminal_name).parse().unwrap();
                 reduce_functions.push(quote! {
                     #comment
                     fn #function (&mut self);
@@ -93,18 +95,41 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
             }
         }
 
+        let recover_function = if self.error_recovery {
+            quote! {
+                /// Called after recovering from a syntax error with an error production. The popped
+                /// symbols were shifted or reduced before and are discarded, the skipped tokens are
+                /// the input the error symbol stands for. Like a shifted token, the error symbol
+                /// counts as one symbol of the rule it is reduced with.
+                fn recover(&mut self, error: ParserError, popped_symbols: usize, skipped: Vec<(TokenType, T)>);
+            }
+        } else if self.sync_recovery {
+            quote! {
+                /// Called after recovering from a syntax error by skipping to a sync token, or the
+                /// token after one. The popped symbols were shifted or reduced before and are
+                /// discarded, the skipped tokens are the input before the token the parser
+                /// continues with.
+                fn error(&mut self, error: ParserError, popped_symbols: usize, skipped: Vec<(TokenType, T)>);
+            }
+        } else {
+            quote! {}
+        };
+
         let tokens = quote! {
             pub trait Visitor<T> {
                 fn shift(&mut self, token: TokenType, data: T);
+                #recover_function
                 #(#reduce_functions)*
             }
         };
         write!(output, "{}", tokens)
     }
 
-    fn make_gotos(&self) -> Vec<TokenStream> {
-        let mut gotos: Vec<TokenStream> = Vec::new();
+    // the arms of every state, in the order of the states
+    fn make_gotos(&self) -> Vec<Vec<TokenStream>> {
+        let mut state_gotos = Vec::new();
         for state in 0..self.parser_table.states() {
+            let mut gotos: Vec<TokenStream> = Vec::new();
             for (symbol, entry) in self
                 .parser_table
                 .iter_state_terminals(state, self.grammar)
@@ -123,8 +148,321 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                     None => (),
                 }
             }
+            if let Some([TableEntry::Shift { target }]) = self
+                .parser_table
+                .get_entry(state, Symbol::Error)
+                .map(|v| v.as_slice())
+            {
+                gotos.push(quote! {
+                    (#state, StackSymbol::Error) => Ok(Goto::State { state_id: #target }),
+                });
+            }
+            state_gotos.push(gotos);
+        }
+        state_gotos
+    }
+
+    fn make_error_actions(&self) -> Vec<TokenStream> {
+        let mut error_actions = Vec::new();
+        for state in 0..self.parser_table.states() {
+            match self
+                .parser_table
+                .get_entry(state, Symbol::Error)
+                .map(|v| v.as_slice())
+            {
+                Some([TableEntry::Shift { target: _ }]) => {
+                    error_actions.push(quote! { #state => Some(Action::Shift), });
+                }
+                Some([TableEntry::Reduce { rule }]) => {
+                    let rule_ptr = (*rule) as *const Rule;
+                    let rule_index = self.rule_index_map.get(&rule_ptr).unwrap();
+                    let rule_name: TokenStream = format!("Rule{}", rule_index).parse().unwrap();
+                    error_actions.push(quote! {
+                        #state => Some(Action::Reduce { rule: ReducedRule::#rule_name }),
+                    });
+                }
+                _ => (),
+            }
+        }
+        error_actions
+    }
+
+    fn make_parse_entry_points(&self) -> (TokenStream, TokenStream) {
+        if !self.grammar.recovers_from_errors() {
+            let signature = quote! {
+                pub fn parse(&mut self) -> Result<(), ParserError>
+            };
+            let entry_points = quote! {
+                /// Parses the whole input and returns its errors. The grammar has no error
+                /// productions, so parsing stops at the first error.
+                #[allow(dead_code)]
+                pub fn parse_all(&mut self) -> (Vec<ParserError>, ()) {
+                    match self.parse() {
+                        Ok(()) => (Vec::new(), ()),
+                        Err(error) => (vec![error], ()),
+                    }
+                }
+            };
+            return (signature, entry_points);
+        }
+        let signature = quote! {
+            fn parse_recovering(&mut self, errors: &mut Vec<ParserError>) -> Result<(), ParserError>
+        };
+        let parse_doc = if self.error_recovery {
+            quote! {
+                /// Parses the whole input, recovering from errors with the error productions of the
+                /// grammar. Only fails if an error could not be recovered from.
+            }
+        } else {
+            quote! {
+                /// Parses the whole input, recovering from errors by skipping to the sync tokens of
+                /// the grammar. Only fails if an error could not be recovered from.
+            }
+        };
+        let entry_points = quote! {
+            #parse_doc
+            #[allow(dead_code)]
+            pub fn parse(&mut self) -> Result<(), ParserError> {
+                self.parse_recovering(&mut Vec::new())
+            }
+
+            /// Parses the whole input and returns all errors, both the ones that were recovered
+            /// from and the one that parsing stopped at, if any.
+            #[allow(dead_code)]
+            pub fn parse_all(&mut self) -> (Vec<ParserError>, ()) {
+                let mut errors = Vec::new();
+                if let Err(error) = self.parse_recovering(&mut errors) {
+                    errors.push(error);
+                }
+                (errors, ())
+            }
+        };
+        (signature, entry_points)
+    }
+
+    // a parse returns early on errors, so with metrics the parse loop is wrapped by a function
+    // which resets them before and reports them after it
+    fn make_measured_parse(&self) -> (TokenStream, TokenStream) {
+        let (parse_signature, entry_points) = self.make_parse_entry_points();
+        if !self.metrics {
+            return (parse_signature, entry_points);
+        }
+        let (signature, parse_call) = if self.grammar.recovers_from_errors() {
+            (
+                quote! {
+                    fn parse_recovering_measured(&mut self, errors: &mut Vec<ParserError>) -> Result<(), ParserError>
+                },
+                quote! { self.parse_recovering_measured(errors) },
+            )
+        } else {
+            (
+                quote! {
+                    fn parse_measured(&mut self) -> Result<(), ParserError>
+                },
+                quote! { self.parse_measured() },
+            )
+        };
+        let entry_points = quote! {
+            #entry_points
+
+            #parse_signature {
+                self.metrics = ParseMetrics::default();
+                let result = #parse_call;
+                self.report_metrics();
+                result
+            }
+        };
+        (signature, entry_points)
+    }
+
+    fn make_recovery(&self) -> (TokenStream, TokenStream, TokenStream) {
+        let (methods, recover) = if self.error_recovery {
+            (self.make_error_production_recovery(), quote! { recover })
+        } else if self.sync_recovery {
+            (self.make_sync_recovery(), quote! { synchronize })
+        } else {
+            let next_action = quote! {
+                let action = self.next_action(state, next_token)?;
+            };
+            return (quote! {}, next_action, quote! {});
+        };
+        let internal_error_arm = if self.panic_free {
+            quote! {
+                Err(error @ ParserError::InternalState { .. }) => return Err(error),
+            }
+        } else {
+            quote! {}
+        };
+        let next_action = quote! {
+            let action = match self.next_action(state, next_token) {
+                Ok(action) => action,
+                #internal_error_arm
+                Err(error) => {
+                    state = self.#recover(error, &mut stack, &mut lookahead, !shifted_since_recovery, errors)?;
+                    shifted_since_recovery = false;
+                    continue;
+                }
+            };
+        };
+        let on_shift = quote! {
+            shifted_since_recovery = true;
+        };
+        (methods, next_action, on_shift)
+    }
+
+    fn make_error_production_recovery(&self) -> TokenStream {
+        let error_actions = self.make_error_actions();
+        quote! {
+            impl<T, F: FnMut() -> (TokenType, T), V: Visitor<T>> Parser<T, F, V> {
+                /// The action of a state on the error symbol, if it has one.
+                fn error_action(&self, state: usize) -> Option<Action> {
+                    match state {
+                        #(#error_actions)*
+                        _ => None
+                    }
+                }
+
+                /// Treats the error symbol as the lookahead, reducing where the table says so and
+                /// popping the stack where it does not, until the error symbol can be shifted. Then
+                /// skips tokens until the parser can continue after it. Returns the state after the
+                /// error symbol.
+                fn recover(
+                    &mut self,
+                    error: ParserError,
+                    stack: &mut Vec<StackSymbol>,
+                    lookahead: &mut std::collections::VecDeque<(TokenType, T)>,
+                    must_skip: bool,
+                    errors: &mut Vec<ParserError>,
+                ) -> Result<usize, ParserError> {
+                    let mut popped_symbols = 0;
+                    let (next_token, _) = lookahead.front().unwrap();
+                    let next_token = *next_token;
+                    let target = loop {
+                        let state = match stack.last() {
+                            Some(StackSymbol::State { state_id }) => *state_id,
+                            _ => return Err(error),
+                        };
+                        let symbol = match self.error_action(state) {
+                            Some(Action::Shift) => StackSymbol::Error,
+                            Some(Action::Reduce { rule }) => {
+                                self.reduce_stack_and_visit(rule, stack, state, next_token)?;
+                                stack.pop().unwrap()
+                            }
+                            None => {
+                                if stack.len() < 3 {
+                                    return Err(error);
+                                }
+                                stack.truncate(stack.len() - 2);
+                                popped_symbols += 1;
+                                continue;
+                            }
+                        };
+                        let goto_state = match stack.last() {
+                            Some(StackSymbol::State { state_id }) => *state_id,
+                            _ => return Err(error),
+                        };
+                        stack.push(symbol);
+                        match self.next_goto(goto_state, symbol, next_token)? {
+                            Goto::State { state_id } => {
+                                stack.push(StackSymbol::State { state_id });
+                                if let StackSymbol::Error = symbol {
+                                    break state_id;
+                                }
+                            }
+                            Goto::Accept => return Err(error),
+                        }
+                    };
+
+                    // without a shift since the last recovery, a token has to be skipped to make progress
+                    let mut must_skip = must_skip;
+                    let mut skipped = Vec::new();
+                    loop {
+                        let (next_token, _) = lookahead.front().unwrap();
+                        let next_token = *next_token;
+                        if !must_skip && self.next_action(target, next_token).is_ok() {
+                            break;
+                        } else if matches!(next_token, TokenType::EndOfFile) {
+                            return Err(error);
+                        }
+                        skipped.push(lookahead.pop_front().unwrap());
+                        lookahead.push_back((self.token_function)());
+                        must_skip = false;
+                    }
+                    errors.push(error.clone());
+                    self.visitor.recover(error, popped_symbols, skipped);
+                    Ok(target)
+                }
+            }
+        }
+    }
+
+    fn make_sync_recovery(&self) -> TokenStream {
+        let sync_tokens: Vec<TokenStream> = self
+            .grammar
+            .sync_tokens()
+            .iter()
+            .map(|token| match token {
+                Symbol::Terminal(index) => get_token_enum_name(self.grammar.get_token_name(*index))
+                    .parse()
+                    .unwrap(),
+                _ => unreachable!("sync tokens are terminals"),
+            })
+            .collect();
+        quote! {
+            impl<T, F: FnMut() -> (TokenType, T), V: Visitor<T>> Parser<T, F, V> {
+                /// Whether the token is declared with `sync`.
+                fn is_sync_token(&self, token: TokenType) -> bool {
+                    matches!(token, #(TokenType::#sync_tokens)|*)
+                }
+
+                /// Skips tokens until a sync token, or the token after a skipped one, that a state
+                /// on the stack has an action on, then pops the stack down to the topmost such
+                /// state. Returns that state.
+                fn synchronize(
+                    &mut self,
+                    error: ParserError,
+                    stack: &mut Vec<StackSymbol>,
+                    lookahead: &mut std::collections::VecDeque<(TokenType, T)>,
+                    must_skip: bool,
+                    errors: &mut Vec<ParserError>,
+                ) -> Result<usize, ParserError> {
+                    // without a shift since the last recovery, a token has to be skipped to make progress
+                    let mut must_skip = must_skip;
+                    let mut skipped = Vec::new();
+                    let mut after_sync_token = false;
+                    loop {
+                        let (next_token, _) = lookahead.front().unwrap();
+                        let next_token = *next_token;
+                        let target = if !must_skip && (after_sync_token || self.is_sync_token(next_token)) {
+                            stack.iter().enumerate().rev().find_map(|(position, symbol)| match symbol {
+                                StackSymbol::State { state_id } if self.next_action(*state_id, next_token).is_ok() => {
+                                    Some((position, *state_id))
+                                }
+                                _ => None,
+                            })
+                        } else {
+                            None
+                        };
+                        match target {
+                            Some((position, target)) => {
+                                // every symbol on the stack is followed by a state
+                                let popped_symbols = (stack.len() - 1 - position) / 2;
+                                stack.truncate(position + 1);
+                                errors.push(error.clone());
+                                self.visitor.error(error, popped_symbols, skipped);
+                                return Ok(target);
+                            }
+                            None if matches!(next_token, TokenType::EndOfFile) => return Err(error),
+                            None => (),
+                        }
+                        skipped.push(lookahead.pop_front().unwrap());
+                        lookahead.push_back((self.token_function)());
+                        must_skip = false;
+                        after_sync_token = self.is_sync_token(next_token);
+                    }
+                }
+            }
         }
-        gotos
     }
 
     fn make_goto(
@@ -159,12 +497,12 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
             match entry {
                 TableEntry::Shift { target } => {
                     gotos.push(quote! {
-                        #condition => Goto::State { state_id: #target },
+                        #condition => Ok(Goto::State { state_id: #target }),
                     });
                 }
                 TableEntry::Accept => {
                     gotos.push(quote! {
-                        #condition => Goto::Accept,
+                        #condition => Ok(Goto::Accept),
                     });
                 }
                 _ => (),
@@ -172,9 +510,11 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
         }
     }
 
-    fn make_actions(&self) -> Vec<TokenStream> {
-        let mut actions: Vec<TokenStream> = Vec::new();
+    // the arms of every state, in the order of the states
+    fn make_actions(&self) -> Vec<Vec<TokenStream>> {
+        let mut state_actions = Vec::new();
         for state in 0..self.parser_table.states() {
+            let mut actions: Vec<TokenStream> = Vec::new();
             let mut expected_symbols = Vec::new();
             for (symbol, entry) in self.parser_table.iter_state_terminals(state, self.grammar) {
                 match entry.map(|v| v.as_slice()) {
@@ -203,8 +543,65 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
             actions.push(quote! {
                 (#state, _) => Err(ParserError::UnexpectedToken { got: next_token, expected: vec![#(TokenType::#expected),*] }),
             });
+            state_actions.push(actions);
+        }
+        state_actions
+    }
+
+    // rustc slows down a lot on a single huge match, so the arms of large tables are split into
+    // one function per range of states, which the function itself dispatches to
+    fn make_segmented_match(
+        &self,
+        name: &str,
+        parameters: TokenStream,
+        arguments: TokenStream,
+        return_type: TokenStream,
+        scrutinee: TokenStream,
+        state_arms: Vec<Vec<TokenStream>>,
+    ) -> TokenStream {
+        let function: TokenStream = name.parse().unwrap();
+        let fallback = make_internal_state_result(self.panic_free);
+        if state_arms.len() <= STATES_PER_FUNCTION {
+            let arms = state_arms.into_iter().flatten();
+            return quote! {
+                fn #function(&self, #parameters) -> #return_type {
+                    match #scrutinee {
+                        #(#arms)*
+                        (_, _) => #fallback
+                    }
+                }
+            };
+        }
+        let mut dispatch = Vec::new();
+        let mut segments = Vec::new();
+        for (index, chunk) in state_arms.chunks(STATES_PER_FUNCTION).enumerate() {
+            let first = index * STATES_PER_FUNCTION;
+            let last = first + chunk.len() - 1;
+            let segment: TokenStream = format!("{}_{}", name, index).parse().unwrap();
+            let arms = chunk.iter().flatten();
+            dispatch.push(quote! {
+                #first..=#last => self.#segment(#arguments),
+            });
+            segments.push(quote! {
+                #[inline]
+                fn #segment(&self, #parameters) -> #return_type {
+                    match #scrutinee {
+                        #(#arms)*
+                        (_, _) => #fallback
+                    }
+                }
+            });
+        }
+        quote! {
+            fn #function(&self, #parameters) -> #return_type {
+                match state {
+                    #(#dispatch)*
+                    _ => #fallback
+                }
+            }
+
+            #(#segments)*
         }
-        actions
     }
 
     fn make_action(
@@ -293,18 +690,12 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
     fn make_rule_visits(&self) -> Vec<TokenStream> {
         let mut rule_visits: Vec<TokenStream> = Vec::new();
 
-        for (non_terminal, rules) in &self.rules_by_non_terminal {
-            let non_terminal_name = self.get_non_terminal_name(non_terminal);
+        for rules in self.rules_by_non_terminal.values() {
             if rules.len() != 1 {
                 for (i, rule) in rules.iter().enumerate() {
                     let rule_index = self.rule_index_map.get(&(*rule as *const Rule)).unwrap();
                     let rule_name: TokenStream = format!("Rule{}", rule_index).parse().unwrap();
-                    let tag = rule.rule().inner.tag;
-                    let name = if let Some(tag) = tag {
-                        format!("reduce_{}_{}", non_terminal_name, tag)
-                    } else {
-                        format!("reduce_{}_{}", non_terminal_name, i + 1)
-                    };
+                    let name = visitor_method_name(self.grammar, rule, i);
                     let function: TokenStream = name.parse().unwrap();
                     rule_visits.push(quote! {
                         ReducedRule::#rule_name => self.visitor.#function ()
@@ -315,7 +706,7 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 let rule_index = self.rule_index_map.get(&(rule as *const Rule)).unwrap();
                 let rule_name: TokenStream = format!("Rule{}", rule_index).parse().unwrap();
                 let function: TokenStream =
-                    format!("reduce_{}", non_terminal_name).parse().unwrap();
+                    visitor_method_name(self.grammar, rule, 0).parse().unwrap();
                 rule_visits.push(quote! {
                     ReducedRule::#rule_name => self.visitor.#function ()
                 });
@@ -326,8 +717,22 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
 
     fn write_parser(&self, output: &mut dyn Write) -> std::io::Result<()> {
         let entry = self.parser_table.entry_state();
-        let actions = self.make_actions();
-        let gotos = self.make_gotos();
+        let action_lookup = self.make_segmented_match(
+            "next_action",
+            quote! { state: usize, next_token: TokenType },
+            quote! { state, next_token },
+            quote! { Result<Action, ParserError> },
+            quote! { (state, next_token) },
+            self.make_actions(),
+        );
+        let goto_lookup = self.make_segmented_match(
+            "next_goto",
+            quote! { state: usize, symbol: StackSymbol, next_token: TokenType },
+            quote! { state, symbol, next_token },
+            quote! { Result<Goto, ParserError> },
+            quote! { (state, symbol) },
+            self.make_gotos(),
+        );
         let rules: Vec<TokenStream> = self
             .rule_index_map
             .values()
@@ -344,14 +749,74 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
             .collect();
         let rule_reductions: Vec<TokenStream> = self.make_rule_reductions();
         let rule_visits: Vec<TokenStream> = self.make_rule_visits();
+        let internal_error = make_internal_state_error(self.panic_free);
+        let (internal_error_variant, internal_error_display) =
+            make_internal_state_variant(self.panic_free);
+        let (recovery_methods, next_action, on_shift) = self.make_recovery();
+        let token_filter_support = make_token_filter_support();
+        let token_insertion = make_token_insertion(self.grammar);
+        let error_stack_symbol = if self.error_recovery {
+            quote! { Error, }
+        } else {
+            quote! {}
+        };
+        let (parse_signature, parse_entry_points) = self.make_measured_parse();
+        let metrics = make_metrics_support(
+            self.metrics,
+            quote! {
+                /// The tokens shifted, without `EndOfFile`.
+                pub tokens_consumed: usize,
+                /// The most states on the stack at once.
+                pub max_stack_depth: usize,
+                /// The rules reduced.
+                pub reductions: usize,
+            },
+        );
+        let (count_token, count_reduction, count_depth) = if self.metrics {
+            (
+                quote! { self.metrics.tokens_consumed += 1; },
+                quote! { self.metrics.reductions += 1; },
+                quote! {
+                    self.metrics.max_stack_depth = self.metrics.max_stack_depth.max(stack.len() / 2 + 1);
+                },
+            )
+        } else {
+            (quote! {}, quote! {}, quote! {})
+        };
+        let MetricsSupport {
+            items: metrics_items,
+            fields: metrics_fields,
+            init: metrics_init,
+            carry_over: metrics_carry_over,
+            methods: metrics_methods,
+        } = metrics;
+        let recovery_state = if self.grammar.recovers_from_errors() {
+            quote! { let mut shifted_since_recovery = true; }
+        } else {
+            quote! {}
+        };
+
+        let ProgressSupport {
+            items: progress_items,
+            fields: progress_fields,
+            init: progress_init,
+            carry_over: progress_carry_over,
+            methods: progress_methods,
+            reset: progress_reset,
+            count_token: progress_token,
+            count_reduction: progress_reduction,
+        } = make_progress_support(self.progress);
 
         let tokens = quote! {
             pub struct Parser<T, F: FnMut() -> (TokenType, T), V: Visitor<T>> {
                 token_function: F,
                 visitor: V,
+                #metrics_fields
+                #progress_fields
             }
 
             #[derive(Debug, Clone, Copy)]
+            #[allow(clippy::enum_variant_names)]
             enum NonTerminalType {
                 #(#non_terminals),*
             }
@@ -361,6 +826,7 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 Terminal { token: TokenType },
                 NonTerminal { non_terminal: NonTerminalType },
                 State { state_id: usize },
+                #error_stack_symbol
             }
 
             #[derive(Clone, Copy)]
@@ -378,12 +844,13 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                 State { state_id: usize }
             }
 
-            #[derive(Debug)]
+            #[derive(Debug, Clone)]
             pub enum ParserError {
                 UnexpectedToken {
                     got: TokenType,
                     expected: Vec<TokenType>
-                }
+                },
+                #internal_error_variant
             }
 
             impl std::error::Error for ParserError {}
@@ -396,101 +863,174 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
                             "Unexpected token {:?}, expected one of: {:?}",
                             got, expected
                         ),
+                        #internal_error_display
                     }
                 }
             }
 
             impl<T, F: FnMut() -> (TokenType, T), V: Visitor<T>> Parser<T, F, V> {
+                /// The token function is not called again once it returned `TokenType::EndOfFile`,
+                /// and `EndOfFile` is never passed to `Visitor::shift`.
                 pub fn new(token_function: F, visitor: V) -> Self {
                     Parser {
                         token_function,
                         visitor,
+                        #metrics_init
+                        #progress_init
                     }
                 }
 
-                fn next_action(&self, state: usize, next_token: TokenType) -> Result<Action, ParserError> {
-                    match (state, next_token) {
-                        #(#actions)*
-                        (_, _) => unreachable!()
-                    }
-                }
+                #action_lookup
+
+                #goto_lookup
 
-                fn next_goto(&self, state: usize, symbol: StackSymbol) -> Goto {
-                    match (state, symbol) {
-                        #(#gotos)*
-                        (_, _) => unreachable!()
+                /// Passes every token through `filter` before the parser sees it.
+                #[allow(dead_code)]
+                pub fn with_filter<P: FnMut(&(TokenType, T)) -> FilterAction<T>>(
+                    self,
+                    filter: P,
+                ) -> Parser<T, impl FnMut() -> (TokenType, T), V> {
+                    Parser {
+                        token_function: filter_tokens(self.token_function, filter),
+                        visitor: self.visitor,
+                        #metrics_carry_over
+                        #progress_carry_over
                     }
                 }
 
-                fn reduce_stack_and_visit(&mut self, rule: ReducedRule, stack: &mut Vec<StackSymbol>) {
+                #metrics_methods
+
+                #progress_methods
+
+                fn reduce_stack_and_visit(&mut self, rule: ReducedRule, stack: &mut Vec<StackSymbol>, state: usize, next_token: TokenType) -> Result<(), ParserError> {
                     let (to_pop, reduced) = match rule {
                         #(#rule_reductions),*
                     };
-                    for _ in 0..to_pop {
-                        stack.pop().unwrap();
-                        stack.pop().unwrap();
+                    if stack.len() < to_pop * 2 {
+                        #internal_error
                     }
+                    stack.truncate(stack.len() - to_pop * 2);
                     stack.push(reduced);
+                    #count_reduction
+                    #progress_reduction
                     match rule {
                         #(#rule_visits),*
                     }
+                    Ok(())
                 }
 
-                pub fn parse(&mut self) -> Result<(), ParserError> {
+                #parse_entry_points
+
+                #parse_signature {
+                    #progress_reset
                     let mut lookahead = std::collections::VecDeque::new();
                     lookahead.push_back((self.token_function)());
 
                     let mut stack = Vec::new();
                     stack.push(StackSymbol::State { state_id: #entry });
 
+                    let mut state = #entry;
+                    #recovery_state
                     while !stack.is_empty() {
                         let (next_token, _) = lookahead.front().unwrap();
-                        let state = match stack.last().unwrap() {
-                            StackSymbol::State { state_id } => *state_id,
-                            _ => unreachable!()
-                        };
-                        let action = self.next_action(state, *next_token)?;
+                        let next_token = *next_token;
+                        #next_action
                         match action {
                             Action::Shift => {
                                 let (next_token, next_data) = lookahead.pop_front().unwrap();
+                                debug_assert!(
+                                    !matches!(next_token, TokenType::EndOfFile),
+                                    "EndOfFile must not be shifted"
+                                );
                                 stack.push(StackSymbol::Terminal { token: next_token });
                                 self.visitor.shift(next_token, next_data);
+                                #count_token
+                                #progress_token
+                                #on_shift
 
                                 lookahead.push_back((self.token_function)());
                             }
                             Action::Reduce { rule: reduced_rule } => {
-                                self.reduce_stack_and_visit(reduced_rule, &mut stack);
+                                self.reduce_stack_and_visit(reduced_rule, &mut stack, state, next_token)?;
                             }
                         }
-                        let current_symbol = stack.last().unwrap();
-                        let state = match &stack[stack.len() - 2] {
-                            StackSymbol::State { state_id } => *state_id,
-                            _ => unreachable!()
+                        let (goto_state, current_symbol) = match stack.as_slice() {
+                            [.., StackSymbol::State { state_id }, current_symbol] => (*state_id, *current_symbol),
+                            _ => #internal_error
                         };
-                        let goto = self.next_goto(state, *current_symbol);
+                        let goto = self.next_goto(goto_state, current_symbol, next_token)?;
                         match goto {
                             Goto::Accept => {
                                 stack.pop();
                                 stack.pop();
                             }
                             Goto::State { state_id } => {
-                                stack.push(StackSymbol::State { state_id })
+                                stack.push(StackSymbol::State { state_id });
+                                state = state_id;
+                                #count_depth
                             }
                         }
                     }
                     Ok(())
                 }
             }
+
+            #recovery_methods
+
+            #metrics_items
+
+            #progress_items
+
+            #token_filter_support
+
+            #token_insertion
+
+            /// Wraps a token function, so that every token is passed through `filter` first.
+            #[allow(dead_code)]
+            pub fn filter_tokens<T, F: FnMut() -> (TokenType, T), P: FnMut(&(TokenType, T)) -> FilterAction<T>>(
+                mut token_function: F,
+                mut filter: P,
+            ) -> impl FnMut() -> (TokenType, T) {
+                let mut pending = std::collections::VecDeque::new();
+                move || loop {
+                    if let Some(token) = pending.pop_front() {
+                        return token;
+                    }
+                    apply_filter(&mut filter, token_function(), &mut pending);
+                }
+            }
+
+            /// Turns an iterator of tokens into a token function, signalling `TokenType::EndOfFile`
+            /// once the iterator is exhausted.
+            #[allow(dead_code)]
+            pub fn token_function_from_iter<T: Default, I: IntoIterator<Item = (TokenType, T)>>(
+                tokens: I,
+            ) -> impl FnMut() -> (TokenType, T) {
+                let mut tokens = tokens.into_iter();
+                move || {
+                    tokens
+                        .next()
+                        .unwrap_or_else(|| (TokenType::EndOfFile, T::default()))
+                }
+            }
         };
         write!(output, "{}", tokens)
     }
 
-    fn write_visitor_and_parser(&self, output: &mut dyn Write) -> std::io::Result<()> {
+    fn write_visitor_and_parser(
+        &self,
+        tokens_path: &str,
+        output: &mut dyn Write,
+    ) -> std::io::Result<()> {
+        let tokens_path: TokenStream = tokens_path.parse().unwrap();
+        let external_token_checks = external_token_checks(self.grammar);
         write!(
             output,
             "{}",
             quote! {
-                use super::tokens::TokenType;
+                use #tokens_path::TokenType;
+
+                #external_token_checks
             }
         )?;
         self.write_visitor(output)?;
@@ -499,6 +1039,71 @@ impl<'grammar, 'rules> CodeWriter<'grammar, 'rules> {
     }
 }
 
+// the token function of the parser can not fail, so the first error of the lexer ends the input
+// and is returned in place of the result of the parser
+fn write_facade(
+    grammar: &Grammar,
+    symbol_prefix: Option<&str>,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let (lexer_module, filter) = make_facade_support(grammar, symbol_prefix);
+    let tokens = quote! {
+        use #lexer_module::{Lexer, LexerError, Location};
+
+        /// An error of `parse_str`, from the lexer or from the parser.
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        pub enum ParseError {
+            Lexer {
+                error: LexerError,
+                location: Location,
+            },
+            Parser(ParserError),
+        }
+
+        impl std::error::Error for ParseError {}
+
+        impl std::fmt::Display for ParseError {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                match self {
+                    ParseError::Lexer { error, location } => write!(
+                        f,
+                        "{} at line {}, column {}",
+                        error, location.line, location.column
+                    ),
+                    ParseError::Parser(error) => write!(f, "{}", error),
+                }
+            }
+        }
+
+        /// Lexes and parses `src` in one call, passing the text of every token to the visitor.
+        #[allow(dead_code)]
+        pub fn parse_str<'src, V: Visitor<&'src str>>(
+            src: &'src str,
+            visitor: V,
+        ) -> Result<(), ParseError> {
+            let mut lexer = Lexer::new(src);
+            let mut lexer_error = None;
+            let token_function = || match lexer.next() {
+                Ok(token) => (token, lexer.slice()),
+                Err(error) => {
+                    lexer_error.get_or_insert(ParseError::Lexer {
+                        error,
+                        location: lexer.position(),
+                    });
+                    (TokenType::EndOfFile, "")
+                }
+            };
+            let result = Parser::new(token_function, visitor)#filter.parse();
+            match lexer_error {
+                Some(error) => Err(error),
+                None => result.map_err(ParseError::Parser),
+            }
+        }
+    };
+    write!(output, "{}", tokens)
+}
+
 fn get_rule_from_pointer<'a, 'rules>(rule: &*const Rule<'rules>) -> &'a Rule<'rules> {
     // We created the hashmap from a known list of rules. The rule pointers are derived from the grammar rules, and the grammar outlives this struct.
     // Therefore, this operation is safe.
@@ -513,10 +1118,92 @@ impl LRParserCodeGen for RustLRParserCodeGen {
         parser_table: &ActionGotoTable,
         gen: &mut GeneratedCodeWriter,
     ) {
-        let writer = CodeWriter::new(grammar, parser_table);
-        gen.generate_code("parser.rs", |output| {
-            writer.write_visitor_and_parser(output)
+        let writer = CodeWriter::new(
+            grammar,
+            parser_table,
+            self.panic_free,
+            self.metrics,
+            self.progress,
+        );
+        let symbol_prefix = self.symbol_prefix.as_deref();
+        let tokens_path = get_tokens_path(symbol_prefix, self.external_tokens.as_deref());
+        let parser_module = get_module_name(symbol_prefix, "parser");
+        let file_name = format!("{}.rs", parser_module);
+        gen.generate_code(&file_name, |output| {
+            write_module(output, self.allow_lints, |output| {
+                writer.write_visitor_and_parser(&tokens_path, output)?;
+                if self.facade {
+                    write_facade(grammar, symbol_prefix, output)?;
+                }
+                Ok(())
+            })
         })
         .unwrap();
+        if self.cst {
+            let parse_tree = quote! {
+                /// Parses the input of the token function into a tree.
+                #[allow(dead_code)]
+                pub fn parse_tree<T, F: FnMut() -> (TokenType, T)>(
+                    token_function: F,
+                ) -> Result<Node<T>, ParserError> {
+                    let mut builder = TreeBuilder::new();
+                    Parser::new(token_function, &mut builder).parse()?;
+                    Ok(builder.take_root().expect("a parsed input has a root"))
+                }
+            };
+            let file_name = format!("{}.rs", get_module_name(symbol_prefix, "cst"));
+            gen.generate_code(&file_name, |output| {
+                write_module(output, self.allow_lints, |output| {
+                    write_cst_module(
+                        grammar,
+                        &tokens_path,
+                        &parser_module,
+                        &parse_tree,
+                        grammar.has_error_productions(),
+                        !grammar.sync_tokens().is_empty(),
+                        output,
+                    )
+                })
+            })
+            .unwrap();
+        }
+        if self.ast {
+            let parse_ast = |root_type: &TokenStream| {
+                quote! {
+                    /// Parses the input of the token function into a typed syntax tree.
+                    #[allow(dead_code)]
+                    pub fn parse_ast<T, F: FnMut() -> (tokens::TokenType, T)>(
+                        token_function: F,
+                    ) -> std::result::Result<#root_type, parser::ParserError> {
+                        let mut builder = AstBuilder::new();
+                        parser::Parser::new(token_function, &mut builder).parse()?;
+                        Ok(builder.take_root().expect("a parsed input has a root"))
+                    }
+                }
+            };
+            let file_name = format!("{}.rs", get_module_name(symbol_prefix, "ast"));
+            gen.generate_code(&file_name, |output| {
+                write_module(output, self.allow_lints, |output| {
+                    write_ast_module(
+                        grammar,
+                        &tokens_path,
+                        &parser_module,
+                        &parse_ast,
+                        grammar.has_error_productions(),
+                        !grammar.sync_tokens().is_empty(),
+                        output,
+                    )
+                })
+            })
+            .unwrap();
+        }
+    }
+
+    fn supports_error_recovery(&self) -> bool {
+        true
+    }
+
+    fn supports_token_insertion(&self) -> bool {
+        true
     }
 }
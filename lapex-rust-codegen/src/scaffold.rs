@@ -0,0 +1,72 @@
+use std::{collections::BTreeMap, io::Write};
+
+use lapex_parser::grammar::{Grammar, Rule, Symbol};
+
+use crate::{get_module_name, visitor_method_name};
+
+/// Writes an implementation of the generated `Visitor` trait with empty methods, meant to be
+/// copied into user code. The method names match those of the LR and GLR parsers, the modules
+/// are the ones generated with the same prefix.
+pub fn write_visitor_scaffold(
+    grammar: &Grammar,
+    type_name: &str,
+    symbol_prefix: Option<&str>,
+    output: &mut dyn Write,
+) -> std::io::Result<()> {
+    let mut rules_by_non_terminal: BTreeMap<Symbol, Vec<&Rule>> = BTreeMap::new();
+    for rule in grammar.rules() {
+        if let Some(non_terminal) = rule.lhs() {
+            rules_by_non_terminal
+                .entry(non_terminal)
+                .or_default()
+                .push(rule);
+        }
+    }
+
+    let parser_module = get_module_name(symbol_prefix, "parser");
+    if grammar.recovers_from_errors() {
+        writeln!(
+            output,
+            "use crate::{}::{{ParserError, Visitor}};",
+            parser_module
+        )?;
+    } else {
+        writeln!(output, "use crate::{}::Visitor;", parser_module)?;
+    }
+    writeln!(
+        output,
+        "use crate::{}::TokenType;",
+        get_module_name(symbol_prefix, "tokens")
+    )?;
+    writeln!(output)?;
+    writeln!(output, "pub struct {} {{}}", type_name)?;
+    writeln!(output)?;
+    writeln!(output, "impl<T> Visitor<T> for {} {{", type_name)?;
+    writeln!(
+        output,
+        "    fn shift(&mut self, _token: TokenType, _data: T) {{}}"
+    )?;
+    if grammar.has_error_productions() {
+        writeln!(output)?;
+        writeln!(
+            output,
+            "    fn recover(&mut self, _error: ParserError, _popped_symbols: usize, _skipped: Vec<(TokenType, T)>) {{}}"
+        )?;
+    }
+    if !grammar.sync_tokens().is_empty() {
+        writeln!(output)?;
+        writeln!(
+            output,
+            "    fn error(&mut self, _error: ParserError, _popped_symbols: usize, _skipped: Vec<(TokenType, T)>) {{}}"
+        )?;
+    }
+    for rules in rules_by_non_terminal.values() {
+        for (i, rule) in rules.iter().enumerate() {
+            let function = visitor_method_name(grammar, rule, i);
+            writeln!(output)?;
+            writeln!(output, "    /// {}", rule.display(grammar))?;
+            writeln!(output, "    fn {}(&mut self) {{}}", function)?;
+        }
+    }
+    writeln!(output, "}}")
+}
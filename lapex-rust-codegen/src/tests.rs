@@ -0,0 +1,450 @@
+use std::{cell::RefCell, collections::BTreeMap, io::Write, process::Command, rc::Rc};
+
+use lapex_codegen::{restore_user_code, strip_user_code, GeneratedCodeWriter};
+use lapex_input::{DefaultPrecedenceModel, LapexInputParser, PrecedenceStrategy};
+use lapex_lexer::LexerCodeGen;
+use lapex_parser::{
+    grammar::{Grammar, GrammarBuilder},
+    lr_parser::{generate_table, GenerationResult, LRParserCodeGen},
+};
+
+use crate::{get_token_enum_name, visitor_method_name, RustLRParserCodeGen, RustLexerCodeGen};
+
+// a file of the generated code, which stays readable after the code generator dropped its writer
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn capture_generated_files<F>(generate: F) -> BTreeMap<String, String>
+where
+    F: FnOnce(&mut GeneratedCodeWriter),
+{
+    let files: RefCell<BTreeMap<String, Rc<RefCell<Vec<u8>>>>> = RefCell::new(BTreeMap::new());
+    let mut gen = GeneratedCodeWriter::with_default(|name| {
+        let buffer = Rc::new(RefCell::new(Vec::new()));
+        files.borrow_mut().insert(name.to_string(), buffer.clone());
+        Ok(SharedBuffer(buffer))
+    });
+    generate(&mut gen);
+    drop(gen);
+    files
+        .into_inner()
+        .into_iter()
+        .map(|(name, buffer)| (name, String::from_utf8(buffer.take()).unwrap()))
+        .collect()
+}
+
+// compiles the files with rustc into a binary, starting at `main.rs`, and runs it
+fn compile_and_run_rust(name: &str, files: &BTreeMap<String, String>) {
+    let dir = std::env::temp_dir().join(format!("lapex-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in files {
+        std::fs::write(dir.join(file), contents).unwrap();
+    }
+    let compiled = Command::new("rustc")
+        .current_dir(&dir)
+        .args(["--edition", "2021", "-o", name, "main.rs"])
+        .output()
+        .unwrap();
+    let run = compiled
+        .status
+        .success()
+        .then(|| Command::new(dir.join(name)).output().unwrap());
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        compiled.status.success(),
+        "{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+    let run = run.unwrap();
+    assert!(
+        run.status.success(),
+        "{}",
+        String::from_utf8_lossy(&run.stderr)
+    );
+}
+
+// the tokens, the lexer and an LR(1) parser building a concrete syntax tree, with `main.rs`
+fn generate_lr_parser_files(source: &str, main: &str) -> BTreeMap<String, String> {
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(source)
+        .unwrap();
+    let grammar = Grammar::from_rule_set(&rules, false).unwrap();
+    let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, _) = lapex_lexer::apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+    let table = match generate_table::<1>(&grammar, false, false) {
+        GenerationResult::NoConflicts(table) => table,
+        _ => panic!("grammar has conflicts"),
+    };
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+        let mut parser = RustLRParserCodeGen::new();
+        parser.set_cst(true);
+        parser.generate_code(&grammar, &table, gen);
+    });
+    files.insert(String::from("main.rs"), main.to_string());
+    files
+}
+
+#[test]
+fn test_token_enum_names() {
+    // the tokens of a keyword set are named like `KW_IF`
+    assert_eq!(get_token_enum_name("KW_IF"), "TkKwIf");
+    assert_eq!(get_token_enum_name("name"), "TkName");
+}
+
+#[test]
+fn test_left_recursion_visitor_names() {
+    // the rules a recursion is rewritten to keep their tags, or are numbered without one
+    for (productions, expected) in [
+        (
+            "prod e[add] = e PLUS NUM;\nprod e[sub] = e MINUS NUM;\nprod e = NUM;",
+            vec![
+                "reduce_e",
+                "reduce_e_star_empty",
+                "reduce_e_star_sub",
+                "reduce_e_star_add",
+            ],
+        ),
+        (
+            "prod e = e PLUS NUM | e MINUS NUM | NUM;",
+            vec![
+                "reduce_e_alt",
+                "reduce_e",
+                "reduce_e_star_empty",
+                "reduce_e_star_2",
+                "reduce_e_star_3",
+            ],
+        ),
+    ] {
+        let source = format!(
+            "token PLUS = \"+\";\ntoken MINUS = \"-\";\ntoken NUM = /[0-9]+/;\nentry e;\n{}\n",
+            productions
+        );
+        let rules = lapex_input_gen::GeneratedLapexInputParser {}
+            .parse_lapex(&source)
+            .unwrap();
+        let grammar = GrammarBuilder::from_rule_set(&rules, true)
+            .unwrap()
+            .with_left_recursion_elimination(true)
+            .build()
+            .unwrap();
+        let mut indices = BTreeMap::new();
+        let names: Vec<String> = grammar
+            .rules()
+            .iter()
+            .map(|rule| {
+                let index = indices.entry(rule.lhs()).or_insert(0);
+                *index += 1;
+                visitor_method_name(&grammar, rule, *index - 1)
+            })
+            .collect();
+        assert_eq!(names, expected, "{}", productions);
+    }
+}
+
+#[test]
+fn test_skipped_tokens() {
+    let source = r#"token NAME = /[a-z]+/;
+skip token WS = /[ \n]+/;
+skip token COMMENT = /#[^\n]*/;
+entry s;
+prod s = NAME*;
+"#;
+    let rules = lapex_input_gen::GeneratedLapexInputParser {}
+        .parse_lapex(source)
+        .unwrap();
+    let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, _) = lapex_lexer::apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        PrecedenceStrategy::default(),
+        &DefaultPrecedenceModel,
+    )
+    .unwrap();
+
+    // the generated lexer only returns the names
+    let mut files = capture_generated_files(|gen| {
+        let lexer = RustLexerCodeGen::new();
+        lexer.generate_tokens(&rules.token_rules, gen);
+        lexer.generate_lexer(&rules.token_rules, alphabet.get_ranges(), &dfa, gen);
+    });
+    files.insert(
+        String::from("main.rs"),
+        String::from(
+            r#"mod lexer;
+mod tokens;
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("  one # comment\ntwo\n  three  ");
+    let mut names = Vec::new();
+    while let tokens::TokenType::TkName = lexer.next().unwrap() {
+        names.push(lexer.slice());
+    }
+    assert_eq!(names, ["one", "two", "three"]);
+}
+"#,
+        ),
+    );
+    compile_and_run_rust("skip", &files);
+}
+
+#[test]
+fn test_parse_all() {
+    let source = r#"token ID = /[a-z]+/;
+token NUM = /[0-9]+/;
+token EQ = "=";
+token SEMI = ";";
+skip token WS = / +/;
+sync SEMI;
+entry program;
+prod program = stmt*;
+prod stmt = ID EQ NUM SEMI;
+"#;
+    let main = r#"mod cst;
+mod lexer;
+mod parser;
+mod tokens;
+
+fn parse(input: &str) -> (usize, Result<(), parser::ParserError>) {
+    let token_function = |input| {
+        let mut lexer = lexer::Lexer::new(input);
+        move || {
+            let token = lexer.next().unwrap();
+            (token, lexer.slice().to_string())
+        }
+    };
+    let mut builder = cst::TreeBuilder::new();
+    let (errors, ()) = parser::Parser::new(token_function(input), &mut builder).parse_all();
+    let mut builder = cst::TreeBuilder::new();
+    let result = parser::Parser::new(token_function(input), &mut builder).parse();
+    (errors.len(), result)
+}
+
+fn main() {
+    // every error is returned, not only the first one
+    let (errors, result) = parse("a = ; b = 2 ; c = = 3 ; d 4 ; e = 5 ;");
+    assert_eq!(errors, 3);
+    assert!(result.is_ok());
+    let (errors, result) = parse("a = 1 ; b = 2 ;");
+    assert_eq!(errors, 0);
+    assert!(result.is_ok());
+    // the error the parser could not recover from is returned last
+    let (errors, result) = parse("a = ; b = 2");
+    assert_eq!(errors, 2);
+    assert!(result.is_err());
+}
+"#;
+    compile_and_run_rust("parse-all", &generate_lr_parser_files(source, main));
+
+    // without recovery, parsing stops at the first error
+    let source = source.replace("sync SEMI;\n", "");
+    let main = r#"mod cst;
+mod lexer;
+mod parser;
+mod tokens;
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("a = ; b = 2 ; c = = 3 ;");
+    let token_function = || {
+        let token = lexer.next().unwrap();
+        (token, lexer.slice().to_string())
+    };
+    let mut builder = cst::TreeBuilder::new();
+    let (errors, ()) = parser::Parser::new(token_function, &mut builder).parse_all();
+    assert_eq!(errors.len(), 1);
+}
+"#;
+    compile_and_run_rust("parse-all-first", &generate_lr_parser_files(&source, main));
+}
+
+#[test]
+fn test_token_filters() {
+    let source = r#"token ID = /[a-z]+/;
+token NUM = /[0-9]+/;
+token EQ = "=";
+token SEMI = ";";
+token NL = /\n/;
+token COMMENT = /#[^\n]*/;
+skip token WS = / +/;
+entry program;
+prod program = stmt*;
+prod stmt = ID EQ NUM SEMI;
+"#;
+    let main = r#"mod cst;
+mod lexer;
+mod parser;
+mod tokens;
+
+use parser::FilterAction;
+use tokens::TokenType;
+
+fn main() {
+    let mut lexer = lexer::Lexer::new("a = 1 # one\nb = 2; c = 3");
+    let token_function = || {
+        let token = lexer.next().unwrap();
+        (token, lexer.slice().to_string())
+    };
+    let mut builder = cst::TreeBuilder::new();
+    // line ends become semicolons, comments are dropped and the input ends with a semicolon
+    parser::Parser::new(token_function, &mut builder)
+        .with_filter(|(token, _)| match token {
+            TokenType::TkNl => FilterAction::Replace(TokenType::TkSemi, String::from(";")),
+            TokenType::TkComment => FilterAction::Drop,
+            TokenType::EndOfFile => FilterAction::Inject(vec![(TokenType::TkSemi, String::from(";"))]),
+            _ => FilterAction::Keep,
+        })
+        .parse()
+        .unwrap();
+    let tree = builder.take_root().unwrap();
+    let statements: Vec<Vec<&str>> = tree
+        .children()
+        .iter()
+        .map(|statement| {
+            statement
+                .children()
+                .iter()
+                .map(|child| match child {
+                    cst::Node::Terminal { data, .. } => data.as_str(),
+                    _ => unreachable!(),
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        statements,
+        [["a", "=", "1", ";"], ["b", "=", "2", ";"], ["c", "=", "3", ";"]]
+    );
+}
+"#;
+    compile_and_run_rust("filter", &generate_lr_parser_files(source, main));
+}
+
+#[test]
+fn test_user_code_sections() {
+    let source = "token NUM = /[0-9]+/;\nentry s;\nprod s = NUM*;\n";
+    let main = r#"mod cst;
+mod lexer;
+mod parser;
+mod tokens;
+
+fn main() {
+    assert_eq!(tokens::describe(tokens::TokenType::TkNum), "TkNum");
+}
+"#;
+    let generated = generate_lr_parser_files(source, main);
+    for (name, code) in generated.iter().filter(|(name, _)| *name != "main.rs") {
+        assert!(
+            code.contains("// <lapex:keep name=\"imports\">\n// </lapex:keep>\n")
+                && code.contains("// <lapex:keep name=\"code\">\n// </lapex:keep>\n"),
+            "{} has no user code sections",
+            name
+        );
+        assert_eq!(restore_user_code(code, code).unwrap(), *code);
+    }
+    // the helper of the user survives generating the tokens again, and compiles with them
+    let tokens = &generated["tokens.rs"];
+    let edited = tokens
+        .replace(
+            "// <lapex:keep name=\"imports\">\n",
+            "// <lapex:keep name=\"imports\">\nuse std::fmt::Write;\n",
+        )
+        .replace(
+            "// <lapex:keep name=\"code\">\n",
+            "// <lapex:keep name=\"code\">\npub fn describe(token: TokenType) -> String {\n    \
+            let mut text = String::new();\n    write!(text, \"{:?}\", token).unwrap();\n    \
+            text\n}\n",
+        );
+    let restored = restore_user_code(&edited, tokens).unwrap();
+    assert_eq!(restored, edited);
+    assert_eq!(strip_user_code(&restored).unwrap(), *tokens);
+    let mut files = generated.clone();
+    files.insert(String::from("tokens.rs"), restored);
+    compile_and_run_rust("user-code", &files);
+}
+
+#[test]
+fn test_segmented_tables() {
+    // every rule is a distinct sequence of six letters, so the table has more than 256 states
+    let letters: Vec<char> = ('a'..='z').collect();
+    let mut source: String = letters
+        .iter()
+        .map(|letter| format!("token t{} = \"{}\";\n", letter, letter))
+        .collect();
+    let rules: Vec<String> = (0..70)
+        .map(|i| {
+            [
+                i % 26,
+                i / 26,
+                i * 3 % 26,
+                i * 5 % 26,
+                i * 7 % 26,
+                i * 11 % 26,
+            ]
+            .iter()
+            .map(|index| letters[*index])
+            .collect()
+        })
+        .collect();
+    source.push_str("entry s;\nprod s = ");
+    let names: Vec<String> = (0..rules.len()).map(|i| format!("r{}", i)).collect();
+    source.push_str(&names.join(" | "));
+    source.push_str(";\n");
+    for (name, rule) in names.iter().zip(&rules) {
+        let symbols: Vec<String> = rule.chars().map(|letter| format!("t{}", letter)).collect();
+        source.push_str(&format!("prod {} = {};\n", name, symbols.join(" ")));
+    }
+
+    // parsing every rule passes through every state, in both halves of the table
+    let inputs: Vec<String> = rules.iter().map(|rule| format!("{:?}", rule)).collect();
+    let main = format!(
+        r#"mod cst;
+mod lexer;
+mod parser;
+mod tokens;
+
+fn parse(input: &str) -> Result<(), parser::ParserError> {{
+    let mut lexer = lexer::Lexer::new(input);
+    let mut builder = cst::TreeBuilder::new();
+    let token_function = move || {{
+        let token = lexer.next().unwrap();
+        (token, lexer.slice().to_string())
+    }};
+    parser::Parser::new(token_function, &mut builder).parse()
+}}
+
+fn main() {{
+    for input in [{}] {{
+        assert!(parse(input).is_ok(), "{{}} is rejected", input);
+        assert!(parse(&input[..5]).is_err(), "{{}} is accepted", &input[..5]);
+    }}
+}}
+"#,
+        inputs.join(", ")
+    );
+    let files = generate_lr_parser_files(&source, &main);
+    let parser_code = &files["parser.rs"];
+    assert!(
+        parser_code.contains("0usize ..= 255usize => self . next_action_0 (state , next_token)")
+    );
+    assert!(parser_code.contains("256usize ..= "));
+    assert!(parser_code.contains("=> self . next_goto_1 (state , symbol , next_token)"));
+    assert!(!parser_code.contains("next_action_2"));
+    compile_and_run_rust("segmented", &files);
+}
@@ -0,0 +1,76 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use lapex_parser::{grammar::Grammar, lr_parser::ActionGotoTable};
+
+use crate::{manifest::sha256, ParsingAlgorithm};
+
+/// LR tables kept in a directory under a hash of the structure of their grammar, the algorithm
+/// and the version of lapex. A table which can not be read or written is built instead, so a
+/// broken or shared cache never fails a generation.
+pub(crate) struct TableCache<'a> {
+    directory: &'a Path,
+    key: String,
+}
+
+impl<'a> TableCache<'a> {
+    pub(crate) fn new(
+        directory: &'a Path,
+        grammar: &Grammar,
+        algorithm: &ParsingAlgorithm,
+    ) -> Self {
+        let mut key_data =
+            format!("lapex {}\n{}\n", env!("CARGO_PKG_VERSION"), algorithm).into_bytes();
+        key_data.extend(grammar.structure());
+        TableCache {
+            directory,
+            key: sha256(&key_data),
+        }
+    }
+
+    fn path(&self) -> PathBuf {
+        self.directory.join(format!("{}.table", self.key))
+    }
+
+    pub(crate) fn load<'grammar: 'rules, 'rules>(
+        &self,
+        grammar: &'grammar Grammar<'rules>,
+    ) -> Option<ActionGotoTable<'grammar, 'rules>> {
+        let data = std::fs::read(self.path()).ok()?;
+        ActionGotoTable::deserialize(&data, grammar)
+    }
+
+    pub(crate) fn store<'grammar: 'rules, 'rules>(
+        &self,
+        grammar: &'grammar Grammar<'rules>,
+        table: &ActionGotoTable<'grammar, 'rules>,
+    ) {
+        // written under another name first, so generations running at the same time never
+        // read a partially written table
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        let temporary = self.directory.join(format!(
+            "{}.{}.{}.tmp",
+            self.key,
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let stored = std::fs::create_dir_all(self.directory)
+            .and_then(|_| std::fs::write(&temporary, table.serialize(grammar)))
+            .and_then(|_| std::fs::rename(&temporary, self.path()));
+        if stored.is_err() {
+            let _ = std::fs::remove_file(&temporary);
+        }
+    }
+}
+
+/// The directory tables are cached in by default, inside the cache directory of the user.
+pub fn default_table_cache() -> Option<PathBuf> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .filter(|directory| !directory.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(cache_home.join("lapex").join("tables"))
+}
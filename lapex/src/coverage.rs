@@ -0,0 +1,127 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use lapex_input::{LapexInputParser, SourceSpan};
+use lapex_parser::{grammar::Grammar, lr_parser::RuleCoverage};
+
+use crate::{
+    build_lr1_table, build_token_dfa, errors::rule_source_text, parser_token_symbols, read_input,
+    InputEncoding, LapexError,
+};
+
+/// A rule or token of the grammar and how often the sample inputs used it.
+#[derive(Debug)]
+pub struct CoverageEntry {
+    pub text: String,
+    pub span: SourceSpan,
+    pub count: u64,
+}
+
+/// How much of a grammar was exercised by parsing sample inputs.
+#[derive(Debug)]
+pub struct Coverage {
+    /// Every rule of the grammar, with the span of the production it belongs to.
+    pub rules: Vec<CoverageEntry>,
+    pub tokens: Vec<CoverageEntry>,
+    /// The inputs which the grammar does not accept. They count until the first error.
+    pub rejected: Vec<PathBuf>,
+}
+
+impl Coverage {
+    pub fn unused_rules(&self) -> impl Iterator<Item = &CoverageEntry> {
+        self.rules.iter().filter(|entry| entry.count == 0)
+    }
+
+    pub fn unused_tokens(&self) -> impl Iterator<Item = &CoverageEntry> {
+        self.tokens.iter().filter(|entry| entry.count == 0)
+    }
+
+    pub fn rule_percentage(&self) -> f64 {
+        used_percentage(&self.rules)
+    }
+
+    pub fn token_percentage(&self) -> f64 {
+        used_percentage(&self.tokens)
+    }
+}
+
+fn used_percentage(entries: &[CoverageEntry]) -> f64 {
+    if entries.is_empty() {
+        return 100.0;
+    }
+    let used = entries.iter().filter(|entry| entry.count > 0).count();
+    used as f64 * 100.0 / entries.len() as f64
+}
+
+/// Parses every input with an LR(1) table of the grammar and counts which rules were reduced and
+/// which tokens were scanned.
+pub fn measure_coverage<I>(
+    grammar_path: &Path,
+    inputs: &[PathBuf],
+    merge_duplicate_productions: bool,
+    encoding: InputEncoding,
+    input_parser: I,
+) -> Result<Coverage, Vec<LapexError>>
+where
+    I: LapexInputParser,
+{
+    let file_contents = read_input(grammar_path, encoding)?;
+    let rules = input_parser
+        .parse_lapex(file_contents.as_str())
+        .map_err(|e| LapexError::syntax(grammar_path, &file_contents, e))?;
+    let grammar = Grammar::from_rule_set(&rules, merge_duplicate_productions)
+        .map_err(|e| LapexError::grammar(grammar_path, file_contents.as_str(), e))?;
+
+    let (alphabet, dfa) = build_token_dfa(grammar_path, file_contents.as_str(), &rules)?;
+    let table = build_lr1_table(grammar_path, file_contents.as_str(), &grammar)?;
+
+    let token_symbols = parser_token_symbols(&grammar);
+    let mut token_counts: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut rule_coverage = RuleCoverage::new(&grammar);
+    let mut rejected = Vec::new();
+    for path in inputs {
+        let contents = read_input(path, encoding)?;
+        let tokens = lapex_lexer::scan_tokens(&dfa, &alphabet, &contents)
+            .map_err(|offset| LapexError::tokenize_input(path, &contents, offset))?;
+        for token in &tokens {
+            *token_counts.entry(&token.name).or_default() += 1;
+        }
+        let symbols: Vec<_> = tokens
+            .iter()
+            .filter_map(|token| token_symbols.get(&*token.name).copied())
+            .collect();
+        if !rule_coverage.record(&grammar, &table, &symbols) {
+            rejected.push(path.clone());
+        }
+    }
+
+    let mut rule_entries: Vec<CoverageEntry> = grammar
+        .rules()
+        .iter()
+        .zip(rule_coverage.reductions())
+        .map(|(rule, count)| CoverageEntry {
+            text: rule_source_text(rule, &grammar),
+            span: rule.rule().span,
+            count: *count,
+        })
+        .collect();
+    let mut token_entries: Vec<CoverageEntry> = rules
+        .token_rules
+        .iter()
+        .map(|token| CoverageEntry {
+            text: token.inner.name.to_string(),
+            span: token.span,
+            count: token_counts.get(&*token.inner.name).copied().unwrap_or(0),
+        })
+        .collect();
+    // list the entries in the order they appear in the grammar file
+    rule_entries.sort_by_key(|entry| entry.span);
+    token_entries.sort_by_key(|entry| entry.span);
+    Ok(Coverage {
+        rules: rule_entries,
+        tokens: token_entries,
+        rejected,
+    })
+}
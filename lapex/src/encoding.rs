@@ -0,0 +1,130 @@
+use std::{fmt::Display, path::Path};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+
+use crate::LapexError;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// The text encoding of grammar files and sample inputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum InputEncoding {
+    /// Detect the encoding from a byte order mark, falling back to UTF-8 and then Latin-1.
+    #[default]
+    Auto,
+    #[cfg_attr(feature = "cli", value(name = "utf-8"))]
+    Utf8,
+    #[cfg_attr(feature = "cli", value(name = "utf-16le"))]
+    Utf16Le,
+    #[cfg_attr(feature = "cli", value(name = "utf-16be"))]
+    Utf16Be,
+    #[cfg_attr(feature = "cli", value(name = "latin-1"))]
+    Latin1,
+}
+
+impl InputEncoding {
+    /// The encoding of the name it is displayed with.
+    pub fn from_name(name: &str) -> Option<InputEncoding> {
+        [
+            InputEncoding::Auto,
+            InputEncoding::Utf8,
+            InputEncoding::Utf16Le,
+            InputEncoding::Utf16Be,
+            InputEncoding::Latin1,
+        ]
+        .into_iter()
+        .find(|encoding| encoding.to_string() == name)
+    }
+}
+
+impl Display for InputEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                InputEncoding::Auto => "auto",
+                InputEncoding::Utf8 => "utf-8",
+                InputEncoding::Utf16Le => "utf-16le",
+                InputEncoding::Utf16Be => "utf-16be",
+                InputEncoding::Latin1 => "latin-1",
+            }
+        )
+    }
+}
+
+/// Reads a file and transcodes it to a string, removing a leading byte order mark.
+pub fn read_input(path: &Path, encoding: InputEncoding) -> Result<String, Vec<LapexError>> {
+    let bytes = std::fs::read(path).map_err(|e| LapexError::io(path.to_path_buf(), e))?;
+    let encoding = match encoding {
+        InputEncoding::Auto => detect_encoding(&bytes),
+        encoding => encoding,
+    };
+    decode(&bytes, encoding).map_err(|offset| LapexError::encoding(path, encoding, offset))
+}
+
+fn detect_encoding(bytes: &[u8]) -> InputEncoding {
+    if bytes.starts_with(UTF8_BOM) {
+        InputEncoding::Utf8
+    } else if bytes.starts_with(UTF16LE_BOM) {
+        InputEncoding::Utf16Le
+    } else if bytes.starts_with(UTF16BE_BOM) {
+        InputEncoding::Utf16Be
+    } else if let Some(encoding) = detect_utf16_without_bom(bytes) {
+        encoding
+    } else if std::str::from_utf8(bytes).is_ok() {
+        InputEncoding::Utf8
+    } else {
+        InputEncoding::Latin1
+    }
+}
+
+// grammars start with ASCII, which UTF-16 pads with a zero byte
+fn detect_utf16_without_bom(bytes: &[u8]) -> Option<InputEncoding> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    match bytes {
+        [first, 0, ..] if *first != 0 => Some(InputEncoding::Utf16Le),
+        [0, second, ..] if *second != 0 => Some(InputEncoding::Utf16Be),
+        _ => None,
+    }
+}
+
+/// On failure, returns the offset of the first byte that is invalid in the encoding.
+fn decode(bytes: &[u8], encoding: InputEncoding) -> Result<String, usize> {
+    match encoding {
+        InputEncoding::Auto => decode(bytes, detect_encoding(bytes)),
+        InputEncoding::Utf8 => {
+            let bom_len = if bytes.starts_with(UTF8_BOM) { 3 } else { 0 };
+            std::str::from_utf8(&bytes[bom_len..])
+                .map(String::from)
+                .map_err(|e| bom_len + e.valid_up_to())
+        }
+        InputEncoding::Utf16Le => decode_utf16(bytes, UTF16LE_BOM, u16::from_le_bytes),
+        InputEncoding::Utf16Be => decode_utf16(bytes, UTF16BE_BOM, u16::from_be_bytes),
+        InputEncoding::Latin1 => Ok(bytes.iter().map(|b| char::from(*b)).collect()),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], bom: &[u8], to_unit: fn([u8; 2]) -> u16) -> Result<String, usize> {
+    let bom_len = if bytes.starts_with(bom) { bom.len() } else { 0 };
+    let units = bytes[bom_len..]
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]));
+    let mut decoded = String::with_capacity(bytes.len() / 2);
+    let mut offset = bom_len;
+    for ch in char::decode_utf16(units) {
+        let ch = ch.map_err(|_| offset)?;
+        offset += ch.len_utf16() * 2;
+        decoded.push(ch);
+    }
+    if !(bytes.len() - bom_len).is_multiple_of(2) {
+        return Err(bytes.len() - 1);
+    }
+    Ok(decoded)
+}
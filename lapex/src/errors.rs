@@ -1,41 +1,163 @@
 use std::{
+    collections::BTreeMap,
     error::Error,
     fmt::Display,
     path::{Path, PathBuf},
 };
 
-use lapex_input::{SourcePos, SourceSpan};
+use lapex_codegen::UserCodeError;
+use lapex_input::{
+    char_display_width, str_display_width, ConflictKind, GrammarVersion, LapexParsingError,
+    PrecedenceStrategy, SourcePos, SourceSpan, Spanned, TokenRule, DEFAULT_TAB_WIDTH,
+};
 use lapex_lexer::PrecedenceError;
 use lapex_parser::{
-    grammar::{Grammar, Symbol},
-    lr_parser::Conflict,
+    grammar::{Duplicate, Grammar, GrammarError, Rule, Symbol},
+    ll_parser::{suggest_rewrites, LLParserError, Rewrite},
+    lr_parser::{Conflict, TableDefect},
+    validation::GrammarIssue,
 };
+#[cfg(feature = "cli")]
 use owo_colors::OwoColorize;
 
+use crate::{InputEncoding, ParsingAlgorithm};
+
+// without the colors of the cli feature, the diagnostics are always rendered as plain text
+#[cfg(not(feature = "cli"))]
+trait OwoColorize {
+    fn bold(&self) -> &Self {
+        self
+    }
+
+    fn blue(&self) -> &Self {
+        self
+    }
+
+    fn bright_red(&self) -> &Self {
+        self
+    }
+
+    fn bright_yellow(&self) -> &Self {
+        self
+    }
+}
+
+#[cfg(not(feature = "cli"))]
+impl<T: ?Sized> OwoColorize for T {}
+
 #[derive(Debug)]
 pub enum Severity {
     Error,
+    Warning,
 }
 
 impl Display for Severity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Severity::Error => write!(f, "{}", "error".bright_red().bold()),
+            Severity::Warning => write!(f, "{}", "warning".bright_yellow().bold()),
         }
     }
 }
 
+/// What caused an error, ordered from the least to the most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ErrorKind {
+    /// The grammar is ambiguous for the chosen parsing algorithm.
+    Conflict,
+    /// The grammar could not be parsed or is invalid.
+    Grammar,
+    /// A file could not be read or decoded.
+    Io,
+    /// The generation was cancelled or failed because of a bug.
+    Internal,
+}
+
+/// The stable code of a kind of diagnostic, printed in its header like `error[E0001]`. A code
+/// keeps its meaning across versions, and `lapex-cli explain` describes it with examples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorCode(u16);
+
+// the descriptions of the codes, in the order they were assigned
+const EXPLANATIONS: [&str; 32] = [
+    include_str!("explanations/E0001.md"),
+    include_str!("explanations/E0002.md"),
+    include_str!("explanations/E0003.md"),
+    include_str!("explanations/E0004.md"),
+    include_str!("explanations/E0005.md"),
+    include_str!("explanations/E0006.md"),
+    include_str!("explanations/E0007.md"),
+    include_str!("explanations/E0008.md"),
+    include_str!("explanations/E0009.md"),
+    include_str!("explanations/E0010.md"),
+    include_str!("explanations/E0011.md"),
+    include_str!("explanations/E0012.md"),
+    include_str!("explanations/E0013.md"),
+    include_str!("explanations/E0014.md"),
+    include_str!("explanations/E0015.md"),
+    include_str!("explanations/E0016.md"),
+    include_str!("explanations/E0017.md"),
+    include_str!("explanations/E0018.md"),
+    include_str!("explanations/E0019.md"),
+    include_str!("explanations/E0020.md"),
+    include_str!("explanations/E0021.md"),
+    include_str!("explanations/E0022.md"),
+    include_str!("explanations/E0023.md"),
+    include_str!("explanations/E0024.md"),
+    include_str!("explanations/E0025.md"),
+    include_str!("explanations/E0026.md"),
+    include_str!("explanations/E0027.md"),
+    include_str!("explanations/E0028.md"),
+    include_str!("explanations/E0029.md"),
+    include_str!("explanations/E0030.md"),
+    include_str!("explanations/E0031.md"),
+    include_str!("explanations/E0032.md"),
+];
+
+impl ErrorCode {
+    /// Every code, in the order they were assigned.
+    pub fn all() -> impl Iterator<Item = ErrorCode> {
+        (1..=EXPLANATIONS.len() as u16).map(ErrorCode)
+    }
+
+    /// Parses a code like `E0003`, also in lower case or without the leading zeros.
+    pub fn from_name(name: &str) -> Option<ErrorCode> {
+        let number: u16 = name.strip_prefix(['E', 'e'])?.parse().ok()?;
+        Self::all().find(|code| code.0 == number)
+    }
+
+    /// A longer description of the diagnostic than its message, with examples.
+    pub fn explanation(&self) -> &'static str {
+        EXPLANATIONS[usize::from(self.0) - 1]
+    }
+}
+
+impl Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "E{:04}", self.0)
+    }
+}
+
 #[derive(Debug)]
 pub struct Location {
     pos: SourcePos,
     file: PathBuf,
+    prefix: String,
     text: String,
 }
 impl Location {
     fn from_span(span: SourceSpan, file: &Path, contents: &str) -> Option<Location> {
+        let line_start = SourceSpan {
+            start: SourcePos {
+                line: span.start.line,
+                col: 1,
+            },
+            end: span.start,
+        };
         Some(Location {
             pos: span.start,
             file: file.to_path_buf(),
+            prefix: line_start.substring(contents)?.to_string(),
             text: span.substring(contents)?.to_string(),
         })
     }
@@ -45,6 +167,8 @@ impl Location {
 pub struct LapexError {
     severity: Severity,
     error: LapexErrorType,
+    tab_width: usize,
+    color: bool,
 }
 
 #[derive(Debug)]
@@ -54,21 +178,207 @@ enum LapexErrorType {
         symbol_name: String,
         location: Location,
         item_text: String,
+        origin: Option<String>,
     },
     Precedence {
         rules: Vec<(Location, String)>,
     },
+    UnusedPrecedence {
+        location: Location,
+        name: String,
+    },
+    UnsupportedVersion {
+        location: Location,
+        version: GrammarVersion,
+    },
+    OutdatedVersion {
+        location: Location,
+        version: GrammarVersion,
+    },
     ReduceReduce {
         state: usize,
-        items: Vec<(Location, String)>,
+        lookaheads: String,
+        items: Vec<(Location, String, Option<String>)>,
+    },
+    Prediction {
+        file: PathBuf,
+        non_terminal: String,
+        lookahead: String,
+        productions: [String; 2],
+        suggestions: Vec<(Location, String, Option<String>)>,
     },
     IO {
         file: PathBuf,
         error: std::io::Error,
     },
+    UserCode {
+        file: PathBuf,
+        error: UserCodeError,
+    },
+    UndefinedEntryRule {
+        location: Location,
+        name: String,
+    },
+    UnknownSymbol {
+        file: PathBuf,
+        name: String,
+    },
+    UnitCycle {
+        path: Vec<(Location, String)>,
+    },
+    LeftRecursion {
+        path: Vec<(Location, String)>,
+        suggestions: Vec<(Location, String, Option<String>)>,
+    },
+    ConflictingRules {
+        rules: Vec<Location>,
+    },
+    DuplicateAlternative {
+        production: String,
+        locations: [Location; 2],
+    },
+    DuplicateProduction {
+        names: [String; 2],
+        locations: [Location; 2],
+    },
+    OrderDependentTokens {
+        names: [String; 2],
+        locations: [Location; 2],
+    },
+    Grammar {
+        file: PathBuf,
+        error: GrammarError,
+    },
+    Syntax {
+        file: PathBuf,
+        error: LapexParsingError,
+    },
+    UnexpectedInput {
+        location: Location,
+        text: String,
+    },
+    InvalidRegex {
+        location: Location,
+        token: String,
+        reason: String,
+        suggestion: Option<&'static str>,
+    },
+    TokenizeInput {
+        location: Location,
+    },
+    Encoding {
+        file: PathBuf,
+        encoding: InputEncoding,
+        offset: usize,
+    },
+    UnsupportedErrorRecovery {
+        file: PathBuf,
+        algorithm: ParsingAlgorithm,
+    },
+    UnsupportedTokenInsertion {
+        file: PathBuf,
+        algorithm: ParsingAlgorithm,
+    },
+    UnsupportedTablesOnly {
+        file: PathBuf,
+        algorithm: ParsingAlgorithm,
+    },
+    ExternalTokens {
+        file: PathBuf,
+        reason: &'static str,
+    },
+    InvalidTable {
+        file: PathBuf,
+        reason: String,
+    },
+    Cancelled,
+    UnusedToken {
+        location: Location,
+        name: String,
+    },
+    UnreachableProduction {
+        location: Location,
+        name: String,
+    },
+    UnproductiveProduction {
+        location: Location,
+        name: String,
+    },
 }
 
 impl LapexError {
+    fn error(error: LapexErrorType) -> Self {
+        LapexError {
+            severity: Severity::Error,
+            error,
+            tab_width: DEFAULT_TAB_WIDTH,
+            color: true,
+        }
+    }
+
+    fn warning(error: LapexErrorType) -> Self {
+        LapexError {
+            severity: Severity::Warning,
+            error,
+            tab_width: DEFAULT_TAB_WIDTH,
+            color: true,
+        }
+    }
+
+    /// Warnings do not stop the generation.
+    pub fn is_warning(&self) -> bool {
+        matches!(self.severity, Severity::Warning)
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self.error {
+            LapexErrorType::ShiftReduce { .. }
+            | LapexErrorType::ReduceReduce { .. }
+            | LapexErrorType::Precedence { .. }
+            | LapexErrorType::Prediction { .. }
+            | LapexErrorType::LeftRecursion { .. } => ErrorKind::Conflict,
+            LapexErrorType::UnusedPrecedence { .. }
+            | LapexErrorType::UnsupportedVersion { .. }
+            | LapexErrorType::OutdatedVersion { .. }
+            | LapexErrorType::DuplicateAlternative { .. }
+            | LapexErrorType::DuplicateProduction { .. }
+            | LapexErrorType::OrderDependentTokens { .. }
+            | LapexErrorType::UnusedToken { .. }
+            | LapexErrorType::UnreachableProduction { .. }
+            | LapexErrorType::UnproductiveProduction { .. } => ErrorKind::Grammar,
+            LapexErrorType::UnknownSymbol { .. }
+            | LapexErrorType::UndefinedEntryRule { .. }
+            | LapexErrorType::UnitCycle { .. }
+            | LapexErrorType::ConflictingRules { .. }
+            | LapexErrorType::Grammar { .. }
+            | LapexErrorType::Syntax { .. }
+            | LapexErrorType::UnexpectedInput { .. }
+            | LapexErrorType::InvalidRegex { .. }
+            | LapexErrorType::UnsupportedErrorRecovery { .. }
+            | LapexErrorType::UnsupportedTokenInsertion { .. }
+            | LapexErrorType::UnsupportedTablesOnly { .. }
+            | LapexErrorType::ExternalTokens { .. } => ErrorKind::Grammar,
+            LapexErrorType::IO { .. }
+            | LapexErrorType::UserCode { .. }
+            | LapexErrorType::Encoding { .. }
+            | LapexErrorType::TokenizeInput { .. } => ErrorKind::Io,
+            LapexErrorType::InvalidTable { .. } | LapexErrorType::Cancelled => ErrorKind::Internal,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.error.code()
+    }
+
+    pub fn set_tab_width(&mut self, tab_width: usize) {
+        self.tab_width = tab_width;
+    }
+
+    /// Whether the error is rendered with ANSI colors, which is the default.
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+    }
+
     pub fn conflicts(
         file: &Path,
         contents: &str,
@@ -82,30 +392,28 @@ impl LapexError {
                     state,
                     item_to_reduce,
                     shift_symbol,
+                    ..
+                } => LapexError::error(LapexErrorType::ShiftReduce {
+                    state: *state,
+                    symbol_name: symbol_display_name(shift_symbol, grammar),
+                    location: Location::from_span(item_to_reduce.production().span, file, contents)
+                        .unwrap(),
+                    item_text: format!("{}", item_to_reduce.display(grammar)),
+                    origin: item_origin(item_to_reduce.rule(), grammar),
+                }),
+                Conflict::ReduceReduce {
+                    state,
+                    items,
+                    lookaheads,
+                    ..
                 } => {
-                    let symbol_name = match shift_symbol {
-                        Symbol::Terminal(token_id) => grammar.get_token_name(*token_id).to_string(),
-                        _ => grammar.get_symbol_name(shift_symbol),
-                    };
-                    LapexError {
-                        severity: Severity::Error,
-                        error: LapexErrorType::ShiftReduce {
-                            state: *state,
-                            symbol_name,
-                            location: Location::from_span(
-                                item_to_reduce.production().span,
-                                file,
-                                contents,
-                            )
-                            .unwrap(),
-                            item_text: format!("{}", item_to_reduce.display(grammar)),
-                        },
-                    }
-                }
-                Conflict::ReduceReduce { state, items } => LapexError {
-                    severity: Severity::Error,
-                    error: LapexErrorType::ReduceReduce {
+                    let lookaheads: Vec<String> = lookaheads
+                        .iter()
+                        .map(|symbol| symbol_display_name(symbol, grammar))
+                        .collect();
+                    LapexError::error(LapexErrorType::ReduceReduce {
                         state: *state,
+                        lookaheads: lookaheads.join(", "),
                         items: items
                             .iter()
                             .map(|item| {
@@ -113,67 +421,757 @@ impl LapexError {
                                 let location =
                                     Location::from_span(item.production().span, file, contents)
                                         .unwrap();
-                                (location, item_text)
+                                (location, item_text, item_origin(item.rule(), grammar))
                             })
                             .collect(),
-                    },
-                },
+                    })
+                }
             })
             .collect()
     }
 
     pub fn io(file: PathBuf, error: std::io::Error) -> Vec<LapexError> {
-        vec![LapexError {
-            severity: Severity::Error,
-            error: LapexErrorType::IO { error, file },
-        }]
+        vec![LapexError::error(LapexErrorType::IO { error, file })]
     }
 
-    pub fn precedence(file: &Path, contents: &str, error: PrecedenceError) -> Vec<LapexError> {
-        vec![LapexError {
-            severity: Severity::Error,
-            error: LapexErrorType::Precedence {
-                rules: error
-                    .rules
+    pub fn user_code(file: PathBuf, error: UserCodeError) -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::UserCode { file, error })]
+    }
+
+    pub fn encoding(file: &Path, encoding: InputEncoding, offset: usize) -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::Encoding {
+            file: file.to_path_buf(),
+            encoding,
+            offset,
+        })]
+    }
+
+    pub fn unsupported_error_recovery(file: &Path, algorithm: ParsingAlgorithm) -> Vec<LapexError> {
+        vec![LapexError::error(
+            LapexErrorType::UnsupportedErrorRecovery {
+                file: file.to_path_buf(),
+                algorithm,
+            },
+        )]
+    }
+
+    pub fn unsupported_tables_only(file: &Path, algorithm: ParsingAlgorithm) -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::UnsupportedTablesOnly {
+            file: file.to_path_buf(),
+            algorithm,
+        })]
+    }
+
+    pub fn external_tokens(file: &Path, reason: &'static str) -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::ExternalTokens {
+            file: file.to_path_buf(),
+            reason,
+        })]
+    }
+
+    pub fn unsupported_token_insertion(
+        file: &Path,
+        algorithm: ParsingAlgorithm,
+    ) -> Vec<LapexError> {
+        vec![LapexError::error(
+            LapexErrorType::UnsupportedTokenInsertion {
+                file: file.to_path_buf(),
+                algorithm,
+            },
+        )]
+    }
+
+    pub fn syntax(file: &Path, contents: &str, error: LapexParsingError) -> Vec<LapexError> {
+        let error = match error {
+            LapexParsingError::Multiple(errors) => {
+                return errors
                     .into_iter()
-                    .map(|r| {
+                    .flat_map(|error| LapexError::syntax(file, contents, error))
+                    .collect();
+            }
+            LapexParsingError::InvalidRegex {
+                token,
+                span,
+                reason,
+                suggestion,
+            } => LapexErrorType::InvalidRegex {
+                location: Location::from_span(span, file, contents).unwrap(),
+                token,
+                reason,
+                suggestion,
+            },
+            LapexParsingError::InvalidCharacter { span } => LapexErrorType::UnexpectedInput {
+                location: Location::from_span(span, file, contents).unwrap(),
+                text: "No token of the grammar syntax starts with this character".to_string(),
+            },
+            LapexParsingError::UnexpectedToken {
+                span,
+                found,
+                expected,
+            } => LapexErrorType::UnexpectedInput {
+                location: Location::from_span(span, file, contents).unwrap(),
+                text: format!("Found {}, expected {}", found, expected.join(" or ")),
+            },
+            LapexParsingError::UnsupportedVersion { span, version } => {
+                LapexErrorType::UnsupportedVersion {
+                    location: Location::from_span(span, file, contents).unwrap(),
+                    version,
+                }
+            }
+            LapexParsingError::InvalidKeyword { span, keyword } => {
+                LapexErrorType::UnexpectedInput {
+                    location: Location::from_span(span, file, contents).unwrap(),
+                    text: format!(
+                        "The keyword {} may only contain letters, digits and underscores",
+                        keyword
+                    ),
+                }
+            }
+            LapexParsingError::UnknownPrecedenceStrategy { span, name } => {
+                LapexErrorType::UnexpectedInput {
+                    location: Location::from_span(span, file, contents).unwrap(),
+                    text: format!(
+                        "There is no precedence strategy {}, expected {}",
+                        name,
+                        PrecedenceStrategy::ALL.map(|s| s.name()).join(" or ")
+                    ),
+                }
+            }
+            LapexParsingError::UnknownConflictKind { span, name } => {
+                LapexErrorType::UnexpectedInput {
+                    location: Location::from_span(span, file, contents).unwrap(),
+                    text: format!(
+                        "There is no kind of conflict {}, expected {}",
+                        name,
+                        ConflictKind::ALL.map(|k| k.name()).join(" or ")
+                    ),
+                }
+            }
+            // a grammar without an entry rule parses, it is the same error as the one of the
+            // grammar when the parser is generated from tokens only
+            LapexParsingError::NoEntryRule => LapexErrorType::Grammar {
+                file: file.to_path_buf(),
+                error: GrammarError::NoEntryRule,
+            },
+            error => LapexErrorType::Syntax {
+                file: file.to_path_buf(),
+                error,
+            },
+        };
+        vec![LapexError::error(error)]
+    }
+
+    pub fn invalid_table(
+        file: &Path,
+        defects: &[TableDefect],
+        grammar: &Grammar,
+    ) -> Vec<LapexError> {
+        defects
+            .iter()
+            .map(|defect| {
+                let reason = match defect {
+                    TableDefect::UnreachableState { state } => {
+                        format!("state {} cannot be reached from the entry state", state)
+                    }
+                    TableDefect::ShiftTargetMissing {
+                        state,
+                        symbol,
+                        target,
+                    } => format!(
+                        "state {} shifts {} to state {}, which does not exist",
+                        state,
+                        symbol_display_name(symbol, grammar),
+                        target
+                    ),
+                    TableDefect::AmbiguousAccessingSymbol { state } => {
+                        format!("state {} is entered on different symbols", state)
+                    }
+                    TableDefect::MissingAccept => String::from("no state accepts the input"),
+                    TableDefect::MisplacedAccept { state, symbol } => format!(
+                        "state {} accepts on {} besides the entry symbol in the entry state",
+                        state,
+                        symbol_display_name(symbol, grammar)
+                    ),
+                    TableDefect::ReduceDepth { state, rule } => format!(
+                        "state {} reduces {} without being reached through its symbols",
+                        state,
+                        rule.display(grammar)
+                    ),
+                };
+                LapexError::error(LapexErrorType::InvalidTable {
+                    file: file.to_path_buf(),
+                    reason,
+                })
+            })
+            .collect()
+    }
+
+    pub fn cancelled() -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::Cancelled)]
+    }
+
+    pub fn unknown_symbol(file: &Path, name: &str) -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::UnknownSymbol {
+            file: file.to_path_buf(),
+            name: name.to_string(),
+        })]
+    }
+
+    pub fn grammar(file: &Path, contents: &str, error: GrammarError) -> Vec<LapexError> {
+        let error = match error {
+            GrammarError::UnitCycle { path } => LapexErrorType::UnitCycle {
+                path: path
+                    .into_iter()
+                    .map(|p| {
                         (
-                            Location::from_span(r.span, file, contents).unwrap(),
-                            r.inner,
+                            Location::from_span(p.span, file, contents).unwrap(),
+                            p.inner,
                         )
                     })
                     .collect(),
             },
-        }]
+            GrammarError::UndefinedEntryRule { name, span } => LapexErrorType::UndefinedEntryRule {
+                location: Location::from_span(span, file, contents).unwrap(),
+                name,
+            },
+            GrammarError::ConflictingRules { rules } => LapexErrorType::ConflictingRules {
+                rules: rules
+                    .into_iter()
+                    .map(|span| Location::from_span(span, file, contents).unwrap())
+                    .collect(),
+            },
+            error => LapexErrorType::Grammar {
+                file: file.to_path_buf(),
+                error,
+            },
+        };
+        vec![LapexError::error(error)]
+    }
+
+    pub fn ll_table(
+        file: &Path,
+        contents: &str,
+        grammar: &Grammar,
+        error: LLParserError,
+    ) -> Vec<LapexError> {
+        let suggestions = suggest_rewrites(grammar, &error)
+            .iter()
+            .map(|rewrite| {
+                let rule = match rewrite {
+                    Rewrite::LeftFactor { rules, .. } => rules[1],
+                    Rewrite::LeftRecursion { rules } => rules[0],
+                    Rewrite::EmptyAlternative { rule, .. } => rule,
+                };
+                let lhs = rule.lhs().unwrap();
+                let origin = if source_non_terminal(lhs, grammar) == lhs {
+                    item_origin(rule, grammar)
+                } else {
+                    None
+                };
+                (
+                    Location::from_span(rule.rule().span, file, contents).unwrap(),
+                    rewrite_text(rewrite, grammar),
+                    origin,
+                )
+            })
+            .collect();
+        let error = match error {
+            LLParserError::ParserTableConflict {
+                non_terminal,
+                lookahead,
+                production,
+                existing_production,
+            } => LapexErrorType::Prediction {
+                file: file.to_path_buf(),
+                non_terminal: symbol_source_name(&non_terminal, grammar),
+                lookahead: symbols_source_text(&lookahead, grammar),
+                productions: [
+                    symbols_source_text(&existing_production, grammar),
+                    symbols_source_text(&production, grammar),
+                ],
+                suggestions,
+            },
+            LLParserError::LeftRecursion { path, .. } => LapexErrorType::LeftRecursion {
+                path: path
+                    .into_iter()
+                    .map(|p| {
+                        (
+                            Location::from_span(p.span, file, contents).unwrap(),
+                            p.inner,
+                        )
+                    })
+                    .collect(),
+                suggestions,
+            },
+            LLParserError::GrammarError(error) => LapexErrorType::Grammar {
+                file: file.to_path_buf(),
+                error,
+            },
+            LLParserError::InvalidParserTableEntry => {
+                unreachable!("the table is only filled for non-terminals and tokens")
+            }
+        };
+        vec![LapexError::error(error)]
+    }
+
+    pub fn tokenize_input(file: &Path, contents: &str, offset: usize) -> Vec<LapexError> {
+        let mut start = SourcePos { line: 1, col: 1 };
+        start.advance_str(&contents[..offset]);
+        let mut end = start;
+        if let Some(ch) = contents[offset..].chars().next() {
+            end.advance_range(contents, offset..offset + ch.len_utf8());
+        }
+        let span = SourceSpan { start, end };
+        vec![LapexError::error(LapexErrorType::TokenizeInput {
+            location: Location::from_span(span, file, contents).unwrap(),
+        })]
+    }
+
+    pub fn precedence(file: &Path, contents: &str, error: PrecedenceError) -> Vec<LapexError> {
+        vec![LapexError::error(LapexErrorType::Precedence {
+            rules: error
+                .rules
+                .into_iter()
+                .map(|r| {
+                    (
+                        Location::from_span(r.span, file, contents).unwrap(),
+                        r.inner,
+                    )
+                })
+                .collect(),
+        })]
+    }
+
+    pub fn unused_precedences(
+        file: &Path,
+        contents: &str,
+        rules: &[&Spanned<TokenRule>],
+    ) -> Vec<LapexError> {
+        rules
+            .iter()
+            .map(|rule| {
+                LapexError::warning(LapexErrorType::UnusedPrecedence {
+                    location: Location::from_span(rule.span, file, contents).unwrap(),
+                    name: rule.inner.name.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn order_dependent_tokens(
+        file: &Path,
+        contents: &str,
+        pairs: &[[&Spanned<TokenRule>; 2]],
+    ) -> Vec<LapexError> {
+        pairs
+            .iter()
+            .map(|pair| {
+                LapexError::warning(LapexErrorType::OrderDependentTokens {
+                    names: pair.map(|rule| rule.inner.name.to_string()),
+                    locations: pair
+                        .map(|rule| Location::from_span(rule.span, file, contents).unwrap()),
+                })
+            })
+            .collect()
+    }
+
+    /// Warns about a grammar written for a version whose syntax may be different.
+    pub fn outdated_version(
+        file: &Path,
+        contents: &str,
+        version: Option<Spanned<GrammarVersion>>,
+    ) -> Vec<LapexError> {
+        version
+            .filter(|version| !GrammarVersion::current().is_compatible(&version.inner))
+            .map(|version| {
+                LapexError::warning(LapexErrorType::OutdatedVersion {
+                    location: Location::from_span(version.span, file, contents).unwrap(),
+                    version: version.inner,
+                })
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Unproductive productions are errors, the other issues are warnings. Rules without a
+    /// position in the grammar, like the ones of the bootstrap parser, are not reported.
+    pub fn grammar_issues(file: &Path, contents: &str, issues: &[GrammarIssue]) -> Vec<LapexError> {
+        issues
+            .iter()
+            .filter_map(|issue| {
+                let location = Location::from_span(issue.span(), file, contents)?;
+                let error = match issue {
+                    GrammarIssue::UnusedToken { name, .. } => LapexErrorType::UnusedToken {
+                        location,
+                        name: name.clone(),
+                    },
+                    GrammarIssue::UnreachableProduction { name, .. } => {
+                        LapexErrorType::UnreachableProduction {
+                            location,
+                            name: name.clone(),
+                        }
+                    }
+                    GrammarIssue::UnproductiveProduction { name, .. } => {
+                        LapexErrorType::UnproductiveProduction {
+                            location,
+                            name: name.clone(),
+                        }
+                    }
+                };
+                Some(if issue.is_error() {
+                    LapexError::error(error)
+                } else {
+                    LapexError::warning(error)
+                })
+            })
+            .collect()
+    }
+
+    pub fn duplicates(file: &Path, contents: &str, duplicates: &[Duplicate]) -> Vec<LapexError> {
+        let locations = |spans: &[SourceSpan; 2]| {
+            spans.map(|span| Location::from_span(span, file, contents).unwrap())
+        };
+        duplicates
+            .iter()
+            .map(|duplicate| {
+                LapexError::warning(match duplicate {
+                    Duplicate::Alternative { production, spans } => {
+                        LapexErrorType::DuplicateAlternative {
+                            production: production.clone(),
+                            locations: locations(spans),
+                        }
+                    }
+                    Duplicate::Production { names, spans } => LapexErrorType::DuplicateProduction {
+                        names: names.clone(),
+                        locations: locations(spans),
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+// items of anonymous non-terminals point at their whole production, so say
+// which part of it they were lowered from.
+fn item_origin(rule: &Rule, grammar: &Grammar) -> Option<String> {
+    rule.lhs()
+        .and_then(|lhs| grammar.get_anonymous_origin(&lhs))
+        .map(|origin| origin.to_string())
+}
+
+fn symbol_display_name(symbol: &Symbol, grammar: &Grammar) -> String {
+    match symbol {
+        Symbol::Terminal(token_id) => grammar.get_token_name(*token_id).to_string(),
+        _ => grammar.get_symbol_name(symbol),
+    }
+}
+
+// anonymous non-terminals which make up a whole production, like its top-level alternation,
+// stand for the production itself
+pub(crate) fn source_non_terminal(symbol: Symbol, grammar: &Grammar) -> Symbol {
+    if grammar.get_anonymous_origin(&symbol).is_none() {
+        return symbol;
+    }
+    let mut unit_rules = grammar
+        .rules()
+        .iter()
+        .filter(|rule| rule.rhs().as_slice() == [symbol]);
+    match (unit_rules.next(), unit_rules.next()) {
+        (Some(rule), None) => {
+            let lhs = rule.lhs().unwrap();
+            let rules_of_lhs = grammar
+                .rules()
+                .iter()
+                .filter(|rule| rule.lhs() == Some(lhs))
+                .count();
+            if grammar.get_production_name(&lhs).is_some() && rules_of_lhs == 1 {
+                lhs
+            } else {
+                symbol
+            }
+        }
+        _ => symbol,
+    }
+}
+
+// names symbols the way they are written in the grammar file, anonymous non-terminals get the
+// name derived from their production
+pub(crate) fn symbol_source_name(symbol: &Symbol, grammar: &Grammar) -> String {
+    let symbol = source_non_terminal(*symbol, grammar);
+    match symbol {
+        Symbol::Terminal(token_id) => grammar.get_token_name(token_id).to_string(),
+        Symbol::NonTerminal(_) => grammar
+            .get_non_terminal_name(&symbol)
+            .map(String::from)
+            .unwrap_or_else(|| grammar.get_symbol_name(&symbol)),
+        _ => grammar.get_symbol_name(&symbol),
+    }
+}
+
+pub(crate) fn symbols_source_text(symbols: &[Symbol], grammar: &Grammar) -> String {
+    let names: Vec<String> = symbols
+        .iter()
+        .filter(|symbol| **symbol != Symbol::Epsilon)
+        .map(|symbol| symbol_source_name(symbol, grammar))
+        .collect();
+    if names.is_empty() {
+        String::from("<eps>")
+    } else {
+        names.join(" ")
+    }
+}
+
+// rules which only link a production to its alternation are not written in the grammar file
+pub(crate) fn is_alternation_link(rule: &Rule, grammar: &Grammar) -> bool {
+    rule.rhs().len() == 1 && source_non_terminal(rule.rhs()[0], grammar) == rule.lhs().unwrap()
+}
+
+// writes the rule like a production of the grammar file, `lhs = rhs`
+pub(crate) fn rule_source_text(rule: &Rule, grammar: &Grammar) -> String {
+    format!(
+        "{} = {}",
+        symbol_source_name(&rule.lhs().unwrap(), grammar),
+        symbols_source_text(rule.rhs(), grammar)
+    )
+}
+
+// the pattern of two rules with the prefix written once, followed by a group of the remainders
+pub(crate) fn left_factored_pattern(
+    rules: &[&Rule; 2],
+    prefix: &[Symbol],
+    grammar: &Grammar,
+) -> String {
+    let prefix_text = symbols_source_text(prefix, grammar);
+    let remainders: Vec<&[Symbol]> = rules
+        .iter()
+        .map(|rule| &rule.rhs()[prefix.len()..])
+        .filter(|remainder| !remainder.is_empty())
+        .collect();
+    match remainders.as_slice() {
+        [] => prefix_text,
+        [remainder] => format!(
+            "{} ({})?",
+            prefix_text,
+            symbols_source_text(remainder, grammar)
+        ),
+        _ => format!(
+            "{} ({})",
+            prefix_text,
+            remainders
+                .iter()
+                .map(|remainder| symbols_source_text(remainder, grammar))
+                .collect::<Vec<String>>()
+                .join(" | ")
+        ),
+    }
+}
+
+fn rewrite_text(rewrite: &Rewrite, grammar: &Grammar) -> String {
+    match rewrite {
+        Rewrite::LeftFactor { rules, prefix } => {
+            let name = symbol_source_name(&rules[0].lhs().unwrap(), grammar);
+            let prefix_text = symbols_source_text(prefix, grammar);
+            let factored = left_factored_pattern(rules, prefix, grammar);
+            format!(
+                "Both alternatives of {} start with\n\t{}\nFactor out the common start:\n\t{} = {};",
+                name.bold(),
+                prefix_text.bold(),
+                name,
+                factored
+            )
+        }
+        Rewrite::LeftRecursion { rules } => {
+            let non_terminal = rules[0].lhs().unwrap();
+            let name = symbol_source_name(&non_terminal, grammar);
+            let starts_with_non_terminal = |rule: &Rule| {
+                rule.rhs().first().is_some_and(|first| {
+                    source_non_terminal(*first, grammar)
+                        == source_non_terminal(non_terminal, grammar)
+                })
+            };
+            let rules: Vec<&Rule> = rules
+                .iter()
+                .copied()
+                .filter(|rule| !is_alternation_link(rule, grammar))
+                .collect();
+            let path: Vec<String> = rules
+                .iter()
+                .map(|rule| {
+                    format!(
+                        "\t{} = {};",
+                        symbol_source_name(&rule.lhs().unwrap(), grammar),
+                        symbols_source_text(rule.rhs(), grammar)
+                    )
+                })
+                .collect();
+            let bases: Vec<String> = grammar
+                .rules()
+                .iter()
+                .filter(|rule| rule.lhs() == Some(non_terminal) && !starts_with_non_terminal(rule))
+                .map(|rule| symbols_source_text(rule.rhs(), grammar))
+                .collect();
+            let repetition = match (rules.as_slice(), bases.as_slice()) {
+                ([rule], [base]) if starts_with_non_terminal(rule) => format!(
+                    "Replace the recursion with a repetition:\n\t{} = {} ({})*;",
+                    name,
+                    base,
+                    symbols_source_text(&rule.rhs()[1..], grammar)
+                ),
+                _ => String::from("Replace the recursion with a repetition using *"),
+            };
+            format!(
+                "{} derives itself before any token is read, which LL parsers cannot expand:\n{}\n{}",
+                name.bold(),
+                path.join("\n"),
+                repetition
+            )
+        }
+        Rewrite::EmptyAlternative { rule, lookahead } => {
+            let name = symbol_source_name(&rule.lhs().unwrap(), grammar);
+            format!(
+                "{} can be empty, but {} can also follow it\nMove what follows {} into its alternatives, or generate an LL(2) parser",
+                name.bold(),
+                symbol_source_name(lookahead, grammar).bold(),
+                name
+            )
+        }
+    }
+}
+
+// LR(0) items reduce regardless of the lookahead
+struct LookaheadNote<'a>(&'a str);
+
+impl<'a> Display for LookaheadNote<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " on lookahead {}", self.0.bold())
+        }
+    }
+}
+
+struct OriginNote<'a>(&'a Option<String>);
+
+impl<'a> Display for OriginNote<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(origin) = self.0 {
+            write!(f, "\nFrom the {}", origin.bold())
+        } else {
+            Ok(())
+        }
     }
 }
 
 impl LapexErrorType {
+    // new kinds of diagnostics get the next free code, and the codes of removed ones are not reused
+    fn code(&self) -> ErrorCode {
+        ErrorCode(match self {
+            LapexErrorType::ShiftReduce { .. } => 1,
+            LapexErrorType::ReduceReduce { .. } => 2,
+            LapexErrorType::Prediction { .. } => 3,
+            LapexErrorType::Precedence { .. } => 4,
+            LapexErrorType::UnusedPrecedence { .. } => 5,
+            LapexErrorType::UnsupportedVersion { .. } => 6,
+            LapexErrorType::OutdatedVersion { .. } => 7,
+            LapexErrorType::IO { .. } => 8,
+            LapexErrorType::UserCode { .. } => 9,
+            LapexErrorType::UndefinedEntryRule { .. } => 10,
+            LapexErrorType::UnknownSymbol { .. } => 11,
+            LapexErrorType::UnitCycle { .. } => 12,
+            LapexErrorType::ConflictingRules { .. } => 13,
+            LapexErrorType::DuplicateAlternative { .. } => 14,
+            LapexErrorType::DuplicateProduction { .. } => 15,
+            LapexErrorType::OrderDependentTokens { .. } => 16,
+            LapexErrorType::Grammar { .. } => 17,
+            LapexErrorType::Syntax { .. } => 18,
+            LapexErrorType::UnexpectedInput { .. } => 19,
+            LapexErrorType::InvalidRegex { .. } => 20,
+            LapexErrorType::TokenizeInput { .. } => 21,
+            LapexErrorType::Encoding { .. } => 22,
+            LapexErrorType::UnsupportedErrorRecovery { .. } => 23,
+            LapexErrorType::UnsupportedTokenInsertion { .. } => 24,
+            LapexErrorType::UnsupportedTablesOnly { .. } => 25,
+            LapexErrorType::ExternalTokens { .. } => 26,
+            LapexErrorType::InvalidTable { .. } => 27,
+            LapexErrorType::Cancelled => 28,
+            LapexErrorType::UnusedToken { .. } => 29,
+            LapexErrorType::UnreachableProduction { .. } => 30,
+            LapexErrorType::UnproductiveProduction { .. } => 31,
+            LapexErrorType::LeftRecursion { .. } => 32,
+        })
+    }
+
     fn message(&self) -> &'static str {
         match self {
             LapexErrorType::ShiftReduce { .. } => "shift-reduce conflict in grammar",
             LapexErrorType::ReduceReduce { .. } => "reduce-reduce conflict in grammar",
             LapexErrorType::Precedence { .. } => "conflicting token precedences in grammar",
-            LapexErrorType::IO { .. } => "failed to read grammar file",
+            LapexErrorType::UnusedPrecedence { .. } => "token precedence is never used",
+            LapexErrorType::UnsupportedVersion { .. } => "grammar requires a newer lapex",
+            LapexErrorType::OutdatedVersion { .. } => "grammar is written for an older lapex",
+            LapexErrorType::Prediction { .. } => "prediction conflict in grammar",
+            LapexErrorType::IO { .. } => "failed to access file",
+            LapexErrorType::UserCode { .. } => "failed to keep the user code of a generated file",
+            LapexErrorType::UnknownSymbol { .. } => "symbol is not defined in grammar",
+            LapexErrorType::UndefinedEntryRule { .. } => {
+                "entry rule references an undefined production"
+            }
+            LapexErrorType::UnitCycle { .. } => "cycle of unit productions in grammar",
+            LapexErrorType::ConflictingRules { .. } => "symbol is defined more than once",
+            LapexErrorType::DuplicateAlternative { .. } => "alternative is repeated in production",
+            LapexErrorType::DuplicateProduction { .. } => "productions are identical",
+            LapexErrorType::OrderDependentTokens { .. } => {
+                "the order of the tokens decides which one is lexed"
+            }
+            LapexErrorType::Grammar { .. } => "invalid grammar",
+            LapexErrorType::Syntax { .. } => "failed to parse grammar",
+            LapexErrorType::UnexpectedInput { .. } => "failed to parse grammar",
+            LapexErrorType::InvalidRegex { .. } => "unsupported regex in token",
+            LapexErrorType::TokenizeInput { .. } => "failed to tokenize input",
+            LapexErrorType::Encoding { .. } => "failed to decode file",
+            LapexErrorType::UnsupportedErrorRecovery { .. } => {
+                "error productions and sync tokens are not supported by this backend"
+            }
+            LapexErrorType::UnsupportedTokenInsertion { .. } => {
+                "insertion rules are not supported by this backend"
+            }
+            LapexErrorType::UnsupportedTablesOnly { .. } => {
+                "only the tables of LR parsers can be written without the parser"
+            }
+            LapexErrorType::ExternalTokens { .. } => {
+                "the grammar declares its tokens with `extern token`"
+            }
+            LapexErrorType::InvalidTable { .. } => "internal error: the parser table is invalid",
+            LapexErrorType::Cancelled => "generation was cancelled",
+            LapexErrorType::UnusedToken { .. } => "token is never used",
+            LapexErrorType::UnreachableProduction { .. } => {
+                "production is unreachable from the entry rule"
+            }
+            LapexErrorType::UnproductiveProduction { .. } => "production can not derive any input",
+            LapexErrorType::LeftRecursion { .. } => "left recursion in grammar",
         }
     }
 }
 
-impl Display for LapexErrorType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl LapexErrorType {
+    fn render(&self, tab_width: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             LapexErrorType::ShiftReduce {
                 state,
                 symbol_name,
                 location,
                 item_text,
+                origin,
             } => write_section(
                 location,
+                tab_width,
                 format_args!(
-                    "In state {}:\nCould shift token\n\t{}\nOr reduce item\n\t{}",
+                    "In state {}:\nCould shift token\n\t{}\nOr reduce item\n\t{}{}",
                     state.bold(),
                     symbol_name.bold(),
-                    item_text.bold()
+                    item_text.bold(),
+                    OriginNote(origin)
                 ),
                 f,
             ),
@@ -181,6 +1179,7 @@ impl Display for LapexErrorType {
                 for (i, (location, rule)) in rules.iter().enumerate() {
                     write_section(
                         location,
+                        tab_width,
                         format_args!("Token has identical precedence:\n\t{}", rule.bold()),
                         f,
                     )?;
@@ -190,14 +1189,60 @@ impl Display for LapexErrorType {
                 }
                 Ok(())
             }
-            LapexErrorType::ReduceReduce { state, items } => {
-                for (i, (location, item_text)) in items.iter().enumerate() {
+            LapexErrorType::UnusedPrecedence { location, name } => write_section(
+                location,
+                tab_width,
+                format_args!(
+                    "No other token matches the same input as\n\t{}\nSo its precedence can be removed",
+                    name.bold()
+                ),
+                f,
+            ),
+            LapexErrorType::UnsupportedVersion { location, version } => {
+                write_section(
+                    location,
+                    tab_width,
+                    format_args!(
+                        "The grammar is written for lapex {}, this is lapex {}",
+                        version.to_string().bold(),
+                        GrammarVersion::current()
+                    ),
+                    f,
+                )?;
+                write!(f, "\n     help: update lapex to read this grammar")
+            }
+            LapexErrorType::OutdatedVersion { location, version } => {
+                write_section(
+                    location,
+                    tab_width,
+                    format_args!(
+                        "The grammar is written for lapex {}, whose syntax may differ from lapex {}",
+                        version.to_string().bold(),
+                        GrammarVersion::current()
+                    ),
+                    f,
+                )?;
+                write!(
+                    f,
+                    "\n     help: check the grammar against the changes since and declare `lapex {};`",
+                    GrammarVersion::current()
+                )
+            }
+            LapexErrorType::ReduceReduce {
+                state,
+                lookaheads,
+                items,
+            } => {
+                for (i, (location, item_text, origin)) in items.iter().enumerate() {
                     write_section(
                         location,
+                        tab_width,
                         format_args!(
-                            "In state {}:\nCould reduce this item:\n\t{}",
+                            "In state {}{}:\nCould reduce this item:\n\t{}{}",
                             state.bold(),
-                            item_text.bold()
+                            LookaheadNote(lookaheads),
+                            item_text.bold(),
+                            OriginNote(origin)
                         ),
                         f,
                     )?;
@@ -207,9 +1252,297 @@ impl Display for LapexErrorType {
                 }
                 Ok(())
             }
+            LapexErrorType::Prediction {
+                file,
+                non_terminal,
+                lookahead,
+                productions: [first, second],
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "     file: {}\n     reason: on lookahead {}, {} could expand to\n\t{}\n     or to\n\t{}",
+                    file.display(),
+                    lookahead.bold(),
+                    non_terminal.bold(),
+                    first.bold(),
+                    second.bold()
+                )?;
+                for (location, text, origin) in suggestions {
+                    writeln!(f)?;
+                    write_section(
+                        location,
+                        tab_width,
+                        format_args!("{}{}", text, OriginNote(origin)),
+                        f,
+                    )?;
+                }
+                Ok(())
+            }
             LapexErrorType::IO { error, file } => {
                 write!(f, "     file: {}\n     reason: {}", file.display(), error)
             }
+            LapexErrorType::UnknownSymbol { file, name } => {
+                write!(f, "     file: {}\n     symbol: {}", file.display(), name)
+            }
+            LapexErrorType::UndefinedEntryRule { location, name } => write_section(
+                location,
+                tab_width,
+                format_args!("There is no production named {}", name.bold()),
+                f,
+            ),
+            LapexErrorType::UnitCycle { path } => {
+                for (i, (location, name)) in path.iter().enumerate() {
+                    let (_, next) = &path[(i + 1) % path.len()];
+                    write_section(
+                        location,
+                        tab_width,
+                        format_args!(
+                            "Production\n\t{}\nCan derive just\n\t{}",
+                            name.bold(),
+                            next.bold()
+                        ),
+                        f,
+                    )?;
+                    if i + 1 < path.len() {
+                        writeln!(f)?;
+                    }
+                }
+                Ok(())
+            }
+            LapexErrorType::LeftRecursion { path, suggestions } => {
+                for (i, (location, name)) in path.iter().enumerate() {
+                    let (_, next) = &path[(i + 1) % path.len()];
+                    write_section(
+                        location,
+                        tab_width,
+                        format_args!(
+                            "Production\n\t{}\nCan start with\n\t{}",
+                            name.bold(),
+                            next.bold()
+                        ),
+                        f,
+                    )?;
+                    writeln!(f)?;
+                }
+                for (location, text, origin) in suggestions {
+                    write_section(
+                        location,
+                        tab_width,
+                        format_args!("{}{}", text, OriginNote(origin)),
+                        f,
+                    )?;
+                    writeln!(f)?;
+                }
+                write!(
+                    f,
+                    "     help: generate with {} to rewrite it automatically",
+                    "--eliminate-left-recursion".bold()
+                )
+            }
+            LapexErrorType::ConflictingRules { rules } => {
+                for (i, location) in rules.iter().enumerate() {
+                    let text = if i == 0 {
+                        "First defined here"
+                    } else {
+                        "Defined again here"
+                    };
+                    write_section(location, tab_width, text, f)?;
+                    if i + 1 < rules.len() {
+                        writeln!(f)?;
+                    }
+                }
+                Ok(())
+            }
+            LapexErrorType::DuplicateAlternative {
+                production,
+                locations: [first, second],
+            } => {
+                write_section(
+                    first,
+                    tab_width,
+                    format_args!("An alternative of\n\t{}", production.bold()),
+                    f,
+                )?;
+                writeln!(f)?;
+                write_section(
+                    second,
+                    tab_width,
+                    "Produces the same symbols as this one\nSo the parser can not decide which to reduce",
+                    f,
+                )
+            }
+            LapexErrorType::DuplicateProduction {
+                names: [first_name, second_name],
+                locations: [first, second],
+            } => {
+                write_section(
+                    first,
+                    tab_width,
+                    format_args!("The production\n\t{}", first_name.bold()),
+                    f,
+                )?;
+                writeln!(f)?;
+                write_section(
+                    second,
+                    tab_width,
+                    format_args!(
+                        "Has the same alternatives as\n\t{}\nSo one of them could be used in both places",
+                        second_name.bold()
+                    ),
+                    f,
+                )
+            }
+            LapexErrorType::OrderDependentTokens {
+                names: [first_name, second_name],
+                locations: [first, second],
+            } => {
+                write_section(
+                    first,
+                    tab_width,
+                    format_args!("The token\n\t{}", first_name.bold()),
+                    f,
+                )?;
+                writeln!(f)?;
+                write_section(
+                    second,
+                    tab_width,
+                    format_args!(
+                        "Is lexed instead of\n\t{}\nOnly because it is declared first",
+                        second_name.bold()
+                    ),
+                    f,
+                )?;
+                write!(
+                    f,
+                    "\n     help: give one of them a higher precedence to keep this when they are reordered"
+                )
+            }
+            LapexErrorType::Grammar { file, error } => {
+                write!(f, "     file: {}\n     reason: {}", file.display(), error)
+            }
+            LapexErrorType::Syntax { file, error } => {
+                write!(f, "     file: {}\n     reason: {}", file.display(), error)
+            }
+            LapexErrorType::UserCode { file, error } => {
+                write!(f, "     file: {}\n     reason: {}", file.display(), error)
+            }
+            LapexErrorType::UnexpectedInput { location, text } => {
+                write_section(location, tab_width, text, f)
+            }
+            LapexErrorType::InvalidRegex {
+                location,
+                token,
+                reason,
+                suggestion,
+            } => {
+                write_section(
+                    location,
+                    tab_width,
+                    format_args!("The regex of\n\t{}\nCan not be used, {}", token.bold(), reason),
+                    f,
+                )?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, "\n     help: {}", suggestion)?;
+                }
+                Ok(())
+            }
+            LapexErrorType::TokenizeInput { location } => {
+                write_section(location, tab_width, "No token matches the input here", f)
+            }
+            LapexErrorType::Encoding {
+                file,
+                encoding,
+                offset,
+            } => write!(
+                f,
+                "     file: {}\n     reason: invalid {} at byte {}",
+                file.display(),
+                encoding,
+                offset
+            ),
+            LapexErrorType::UnsupportedErrorRecovery { file, algorithm } => write!(
+                f,
+                "     file: {}\n     reason: the {} parser of this language cannot recover from errors",
+                file.display(),
+                algorithm
+            ),
+            LapexErrorType::UnsupportedTokenInsertion { file, algorithm } => write!(
+                f,
+                "     file: {}\n     reason: the {} parser of this language cannot insert tokens",
+                file.display(),
+                algorithm
+            ),
+            LapexErrorType::UnsupportedTablesOnly { file, algorithm } => write!(
+                f,
+                "     file: {}\n     reason: the tables of {} parsers have no documented format",
+                file.display(),
+                algorithm
+            ),
+            LapexErrorType::ExternalTokens { file, reason } => write!(
+                f,
+                "     file: {}\n     reason: {}",
+                file.display(),
+                reason
+            ),
+            LapexErrorType::InvalidTable { file, reason } => write!(
+                f,
+                "     file: {}\n     reason: {}\n     this is a bug in lapex, no parser was generated",
+                file.display(),
+                reason
+            ),
+            LapexErrorType::Cancelled => Ok(()),
+            LapexErrorType::UnusedToken { location, name } => {
+                write_section(
+                    location,
+                    tab_width,
+                    format_args!(
+                        "No production uses the token
+	{}
+So the parser rejects it wherever it is lexed",
+                        name.bold()
+                    ),
+                    f,
+                )?;
+                write!(
+                    f,
+                    "
+     help: remove the token, or declare it with `skip` if the parser should not see it"
+                )
+            }
+            LapexErrorType::UnreachableProduction { location, name } => {
+                write_section(
+                    location,
+                    tab_width,
+                    format_args!(
+                        "The production
+	{}
+Is not used by the entry rule or any production it uses",
+                        name.bold()
+                    ),
+                    f,
+                )?;
+                write!(f, "
+     help: remove the production or use it")
+            }
+            LapexErrorType::UnproductiveProduction { location, name } => {
+                write_section(
+                    location,
+                    tab_width,
+                    format_args!(
+                        "Every alternative of the production
+	{}
+Needs itself or another production that never ends",
+                        name.bold()
+                    ),
+                    f,
+                )?;
+                write!(
+                    f,
+                    "
+     help: add an alternative which ends the recursion, like one with only tokens"
+                )
+            }
         }
     }
 }
@@ -218,6 +1551,7 @@ impl Error for LapexError {}
 
 fn write_section<D: Display>(
     location: &Location,
+    tab_width: usize,
     contents: D,
     f: &mut std::fmt::Formatter<'_>,
 ) -> std::fmt::Result {
@@ -229,10 +1563,20 @@ fn write_section<D: Display>(
         location.pos.line,
         location.pos.col
     )?;
+    let source = expand_tabs(&format!("{}{}", location.prefix, location.text), tab_width);
+    let underline_start = str_display_width(&location.prefix, 1, tab_width);
+    let underline_end = source
+        .lines()
+        .map(|l| str_display_width(l, 1, tab_width))
+        .max()
+        .unwrap_or(underline_start);
     let formatted = format!(
-        "{}\n{}\n\n{}",
-        location.text.as_str(),
-        "~".repeat(location.text.len()).bright_red().bold(),
+        "{}\n{}{}\n\n{}",
+        source,
+        " ".repeat(underline_start),
+        "~".repeat(underline_end.saturating_sub(underline_start).max(1))
+            .bright_red()
+            .bold(),
         contents
     );
     let lines_iter_padded = std::iter::once("").chain(formatted.lines().chain(std::iter::once("")));
@@ -242,9 +1586,104 @@ fn write_section<D: Display>(
     write!(f, "{}", lines.join("\n"))
 }
 
+fn expand_tabs(text: &str, tab_width: usize) -> String {
+    let mut expanded = String::new();
+    let mut col = 1;
+    for ch in text.chars() {
+        let width = char_display_width(ch, col, tab_width);
+        match ch {
+            '\n' => {
+                expanded.push(ch);
+                col = 1;
+                continue;
+            }
+            '\t' => expanded.push_str(&" ".repeat(width)),
+            _ => expanded.push(ch),
+        }
+        col += width;
+    }
+    expanded
+}
+
+// removes the escape sequences of the colors, which are already part of some of the texts stored
+// in the errors
+fn strip_colors(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // skip the parameters up to the final letter of the sequence
+            chars.find(|ch| ch.is_ascii_alphabetic());
+        } else {
+            stripped.push(ch);
+        }
+    }
+    stripped
+}
+
+struct Rendered<'a>(&'a LapexError);
+
+impl<'a> Display for Rendered<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let code = format!("[{}]", self.0.error.code());
+        match self.0.severity {
+            Severity::Error => write!(f, "{}{}", self.0.severity, code.bright_red().bold())?,
+            Severity::Warning => write!(f, "{}{}", self.0.severity, code.bright_yellow().bold())?,
+        }
+        writeln!(f, ": {}", self.0.error.message())?;
+        self.0.error.render(self.0.tab_width, f)
+    }
+}
+
+impl ErrorKind {
+    fn noun(&self) -> &'static str {
+        match self {
+            ErrorKind::Conflict => "conflict",
+            ErrorKind::Grammar => "grammar error",
+            ErrorKind::Io => "IO error",
+            ErrorKind::Internal => "internal error",
+        }
+    }
+}
+
+impl LapexError {
+    /// Counts the errors by their kind and the warnings, like
+    /// `error: 3 conflicts, 1 grammar error, 1 warning`.
+    pub fn summary(errors: &[LapexError], color: bool) -> String {
+        let mut counts: BTreeMap<ErrorKind, usize> = BTreeMap::new();
+        for error in errors.iter().filter(|error| !error.is_warning()) {
+            *counts.entry(error.kind()).or_default() += 1;
+        }
+        let warning_count = errors.iter().filter(|error| error.is_warning()).count();
+        let severity = if counts.is_empty() {
+            Severity::Warning
+        } else {
+            Severity::Error
+        };
+        let counts: Vec<String> = counts
+            .into_iter()
+            .map(|(kind, count)| (kind.noun(), count))
+            .chain(Some(("warning", warning_count)).filter(|(_, count)| *count > 0))
+            .map(|(noun, count)| {
+                let plural = if count == 1 { "" } else { "s" };
+                format!("{} {}{}", count, noun, plural)
+            })
+            .collect();
+        let summary = format!("{}: {}", severity, counts.join(", "));
+        if color {
+            summary
+        } else {
+            strip_colors(&summary)
+        }
+    }
+}
+
 impl Display for LapexError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}: {}", self.severity, self.error.message())?;
-        write!(f, "{}", self.error)
+        if self.color {
+            write!(f, "{}", Rendered(self))
+        } else {
+            write!(f, "{}", strip_colors(&Rendered(self).to_string()))
+        }
     }
 }
@@ -0,0 +1,55 @@
+use std::{io::Write, path::PathBuf};
+
+use lapex_automaton::Nfa;
+use lapex_codegen::GeneratedCodeWriter;
+use lapex_input::{Spanned, TokenRule};
+use lapex_lexer::{Alphabet, TokenDfa};
+use lapex_parser::{grammar::Grammar, lr_parser::ActionGotoTable};
+
+use crate::LapexError;
+
+/// Writes the NFA and the DFA of the lexer as Graphviz graphs into `lexer_nfa.dot` and
+/// `lexer_dfa.dot`, or `<prefix>_lexer_nfa.dot` and `<prefix>_lexer_dfa.dot`.
+pub(crate) fn write_lexer_graphs(
+    gen: &mut GeneratedCodeWriter,
+    symbol_prefix: Option<&str>,
+    nfa: &Nfa<&Spanned<TokenRule>, usize>,
+    dfa: &TokenDfa,
+    alphabet: &Alphabet,
+) -> Result<(), Vec<LapexError>> {
+    let nfa = lapex_lexer::nfa_to_dot(nfa, alphabet);
+    write_graph(gen, symbol_prefix, "lexer_nfa", |output| {
+        output.write_all(nfa.as_bytes())
+    })?;
+    let dfa = lapex_lexer::dfa_to_dot(dfa, alphabet);
+    write_graph(gen, symbol_prefix, "lexer_dfa", |output| {
+        output.write_all(dfa.as_bytes())
+    })
+}
+
+/// Writes the states of an LR table as a Graphviz graph into `parser.dot`, or
+/// `<prefix>_parser.dot`.
+pub(crate) fn write_parser_graph<'grammar, 'rules>(
+    gen: &mut GeneratedCodeWriter,
+    symbol_prefix: Option<&str>,
+    grammar: &'grammar Grammar,
+    table: &ActionGotoTable<'grammar, 'rules>,
+) -> Result<(), Vec<LapexError>> {
+    write_graph(gen, symbol_prefix, "parser", |output| {
+        lapex_parser::lr_parser::output_graph(grammar, table, output)
+    })
+}
+
+fn write_graph(
+    gen: &mut GeneratedCodeWriter,
+    symbol_prefix: Option<&str>,
+    name: &str,
+    write: impl Fn(&mut dyn Write) -> std::io::Result<()>,
+) -> Result<(), Vec<LapexError>> {
+    let file_name = match symbol_prefix {
+        Some(prefix) => format!("{}_{}.dot", prefix, name),
+        None => format!("{}.dot", name),
+    };
+    gen.generate_data(&file_name, write)
+        .map_err(|e| LapexError::io(PathBuf::from(&file_name), e))
+}
@@ -0,0 +1,233 @@
+use std::{collections::BTreeMap, path::Path};
+
+#[cfg(feature = "cli")]
+use clap::ValueEnum;
+#[cfg(feature = "cpp")]
+use lapex_cpp_codegen::{
+    CppGLRParserCodeGen, CppLLParserCodeGen, CppLRParserCodeGen, CppLexerCodeGen,
+};
+use lapex_input::LapexInputParser;
+use lapex_parser::grammar::Grammar;
+#[cfg(feature = "rust")]
+use lapex_rust_codegen::{
+    RustGLRParserCodeGen, RustLLParserCodeGen, RustLRParserCodeGen, RustLexerCodeGen,
+};
+
+use crate::{GenerationOptions, GenerationReport, LapexError};
+
+/// The languages code can be generated for, each one is enabled by the cargo feature of the
+/// same name.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
+pub enum Language {
+    #[cfg(feature = "rust")]
+    Rust,
+    #[cfg(feature = "cpp")]
+    Cpp,
+}
+
+// the symbol prefix is only known once the grammar is read, as it can be declared in there
+pub(crate) trait LanguageFactory<Lexer, LR, LL, GLR> {
+    /// The name of the language on the command line.
+    fn name(&self) -> &'static str;
+    fn lexer(&self, symbol_prefix: Option<&str>) -> Lexer;
+    fn lr_parser(&self, symbol_prefix: Option<&str>) -> LR;
+    fn glr_parser(&self, symbol_prefix: Option<&str>) -> GLR;
+    fn ll_parser(&self, symbol_prefix: Option<&str>) -> LL;
+}
+
+#[cfg(feature = "cpp")]
+struct CppLanguageFactory {
+    polymorphic_allocator: bool,
+    metrics: bool,
+    namespace: Option<String>,
+    external_tokens: Option<String>,
+    facade: bool,
+}
+
+#[cfg(feature = "cpp")]
+impl LanguageFactory<CppLexerCodeGen, CppLRParserCodeGen, CppLLParserCodeGen, CppGLRParserCodeGen>
+    for CppLanguageFactory
+{
+    fn name(&self) -> &'static str {
+        "cpp"
+    }
+
+    fn lexer(&self, symbol_prefix: Option<&str>) -> CppLexerCodeGen {
+        let mut codegen = CppLexerCodeGen::new();
+        codegen.set_symbol_prefix(symbol_prefix);
+        codegen.set_namespace(self.namespace.as_deref());
+        codegen
+    }
+
+    fn lr_parser(&self, symbol_prefix: Option<&str>) -> CppLRParserCodeGen {
+        let mut codegen = CppLRParserCodeGen::new();
+        codegen.set_polymorphic_allocator(self.polymorphic_allocator);
+        codegen.set_metrics(self.metrics);
+        codegen.set_symbol_prefix(symbol_prefix);
+        codegen.set_namespace(self.namespace.as_deref());
+        codegen.set_external_tokens(self.external_tokens.as_deref());
+        codegen.set_facade(self.facade);
+        codegen
+    }
+
+    fn glr_parser(&self, _symbol_prefix: Option<&str>) -> CppGLRParserCodeGen {
+        CppGLRParserCodeGen::new()
+    }
+
+    fn ll_parser(&self, symbol_prefix: Option<&str>) -> CppLLParserCodeGen {
+        let mut codegen = CppLLParserCodeGen::new();
+        codegen.set_polymorphic_allocator(self.polymorphic_allocator);
+        codegen.set_symbol_prefix(symbol_prefix);
+        codegen.set_namespace(self.namespace.as_deref());
+        codegen.set_external_tokens(self.external_tokens.as_deref());
+        codegen
+    }
+}
+
+#[cfg(feature = "rust")]
+struct RustLanguageFactory {
+    panic_free: bool,
+    metrics: bool,
+    progress: bool,
+    allow_lints: bool,
+    cst: bool,
+    ast: bool,
+    external_tokens: Option<String>,
+    facade: bool,
+}
+
+#[cfg(feature = "rust")]
+impl
+    LanguageFactory<
+        RustLexerCodeGen,
+        RustLRParserCodeGen,
+        RustLLParserCodeGen,
+        RustGLRParserCodeGen,
+    > for RustLanguageFactory
+{
+    fn name(&self) -> &'static str {
+        "rust"
+    }
+
+    fn lexer(&self, symbol_prefix: Option<&str>) -> RustLexerCodeGen {
+        let mut codegen = RustLexerCodeGen::new();
+        codegen.set_symbol_prefix(symbol_prefix);
+        codegen.set_allow_lints(self.allow_lints);
+        codegen
+    }
+
+    fn lr_parser(&self, symbol_prefix: Option<&str>) -> RustLRParserCodeGen {
+        let mut codegen = RustLRParserCodeGen::new();
+        codegen.set_panic_free(self.panic_free);
+        codegen.set_metrics(self.metrics);
+        codegen.set_progress(self.progress);
+        codegen.set_symbol_prefix(symbol_prefix);
+        codegen.set_allow_lints(self.allow_lints);
+        codegen.set_cst(self.cst);
+        codegen.set_ast(self.ast);
+        codegen.set_external_tokens(self.external_tokens.as_deref());
+        codegen.set_facade(self.facade);
+        codegen
+    }
+
+    fn glr_parser(&self, symbol_prefix: Option<&str>) -> RustGLRParserCodeGen {
+        let mut codegen = RustGLRParserCodeGen::new();
+        codegen.set_panic_free(self.panic_free);
+        codegen.set_metrics(self.metrics);
+        codegen.set_progress(self.progress);
+        codegen.set_symbol_prefix(symbol_prefix);
+        codegen.set_allow_lints(self.allow_lints);
+        codegen.set_cst(self.cst);
+        codegen.set_ast(self.ast);
+        codegen.set_external_tokens(self.external_tokens.as_deref());
+        codegen.set_facade(self.facade);
+        codegen
+    }
+
+    fn ll_parser(&self, _symbol_prefix: Option<&str>) -> RustLLParserCodeGen {
+        RustLLParserCodeGen::new()
+    }
+}
+
+impl Language {
+    pub(crate) fn generate_lexer_and_parser<I>(
+        self,
+        options: &GenerationOptions,
+        grammar_path: &Path,
+        grammar_source: &str,
+        target_path: Option<&Path>,
+        input_parser: I,
+        report: &mut GenerationReport,
+    ) -> Result<BTreeMap<String, String>, Vec<LapexError>>
+    where
+        I: LapexInputParser,
+    {
+        match self {
+            #[cfg(feature = "cpp")]
+            Language::Cpp => crate::generate_lexer_and_parser(
+                options,
+                grammar_path,
+                grammar_source,
+                target_path,
+                CppLanguageFactory {
+                    polymorphic_allocator: options.polymorphic_allocator,
+                    metrics: options.metrics,
+                    namespace: options.cpp_namespace.clone(),
+                    external_tokens: options.external_tokens.clone(),
+                    facade: options.writes_facade(),
+                },
+                input_parser,
+                report,
+            ),
+            #[cfg(feature = "rust")]
+            Language::Rust => crate::generate_lexer_and_parser(
+                options,
+                grammar_path,
+                grammar_source,
+                target_path,
+                RustLanguageFactory {
+                    panic_free: options.panic_free,
+                    metrics: options.metrics,
+                    progress: options.progress,
+                    allow_lints: options.allow_lints,
+                    cst: options.cst,
+                    ast: options.ast,
+                    external_tokens: options.external_tokens.clone(),
+                    facade: options.writes_facade(),
+                },
+                input_parser,
+                report,
+            ),
+        }
+    }
+
+    // only the C++ scaffold is declared in a namespace
+    #[cfg_attr(not(feature = "cpp"), allow(unused_variables))]
+    pub(crate) fn write_visitor_scaffold(
+        self,
+        grammar: &Grammar,
+        type_name: &str,
+        symbol_prefix: Option<&str>,
+        cpp_namespace: Option<&str>,
+        output: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        match self {
+            #[cfg(feature = "rust")]
+            Language::Rust => lapex_rust_codegen::write_visitor_scaffold(
+                grammar,
+                type_name,
+                symbol_prefix,
+                output,
+            ),
+            #[cfg(feature = "cpp")]
+            Language::Cpp => lapex_cpp_codegen::write_visitor_scaffold(
+                grammar,
+                type_name,
+                cpp_namespace,
+                symbol_prefix,
+                output,
+            ),
+        }
+    }
+}
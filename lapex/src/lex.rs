@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use lapex_input::{LapexInputParser, SourcePos, SourceSpan};
+
+use crate::{build_token_dfa, read_input, InputEncoding, LapexError};
+
+/// A token scanned from an input file.
+#[derive(Debug)]
+pub struct LexedToken {
+    pub name: String,
+    pub span: SourceSpan,
+    pub text: String,
+}
+
+/// Splits the input file into tokens with the lexer of the grammar, without building a parser.
+/// Tokens which no production uses are included.
+pub fn lex_file<I>(
+    grammar_path: &Path,
+    input_path: &Path,
+    encoding: InputEncoding,
+    input_parser: I,
+) -> Result<Vec<LexedToken>, Vec<LapexError>>
+where
+    I: LapexInputParser,
+{
+    let file_contents = read_input(grammar_path, encoding)?;
+    let rules = input_parser
+        .parse_lapex(file_contents.as_str())
+        .map_err(|e| LapexError::syntax(grammar_path, &file_contents, e))?;
+    let (alphabet, dfa) = build_token_dfa(grammar_path, file_contents.as_str(), &rules)?;
+
+    let contents = read_input(input_path, encoding)?;
+    let tokens = lapex_lexer::scan_token_spans(&dfa, &alphabet, &contents)
+        .map_err(|offset| LapexError::tokenize_input(input_path, &contents, offset))?;
+    let mut pos = SourcePos { line: 1, col: 1 };
+    let mut offset = 0;
+    Ok(tokens
+        .into_iter()
+        .map(|(rule, range)| {
+            pos.advance_range(&contents, offset..range.start);
+            let start = pos;
+            pos.advance_range(&contents, range.clone());
+            offset = range.end;
+            LexedToken {
+                name: rule.name.to_string(),
+                span: SourceSpan { start, end: pos },
+                text: contents[range].to_string(),
+            }
+        })
+        .collect())
+}
@@ -1,30 +1,74 @@
-use std::{fmt::Display, io::BufWriter, path::Path};
+// without a language only the analysis is used, the driver of the code generation stays unused
+#![cfg_attr(
+    not(any(feature = "cpp", feature = "rust")),
+    allow(dead_code, unused_variables)
+)]
 
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+#[cfg(feature = "cli")]
 use clap::ValueEnum;
-use errors::LapexError;
+use lapex_automaton::Dfa;
 use lapex_codegen::GeneratedCodeWriter;
-use lapex_cpp_codegen::{
-    CppGLRParserCodeGen, CppLLParserCodeGen, CppLRParserCodeGen, CppLexerCodeGen,
+use lapex_input::{
+    ConservativePrecedenceModel, DefaultPrecedenceModel, LapexInputParser, PrecedenceModel,
+    RuleSet, SourceSpan, Spanned, TokenRule,
 };
-use lapex_input::LapexInputParser;
-use lapex_lexer::LexerCodeGen;
+use lapex_lexer::{Alphabet, LexerCodeGen, TokenDfa};
 use lapex_parser::{
-    grammar::Grammar,
+    grammar::{Grammar, GrammarBuilder, Symbol},
     ll_parser::LLParserCodeGen,
-    lr_parser::{GenerationResult, LRParserCodeGen},
-};
-use lapex_rust_codegen::{
-    RustGLRParserCodeGen, RustLLParserCodeGen, RustLRParserCodeGen, RustLexerCodeGen,
+    lr_parser::{ActionGotoTable, GenerationResult, LRParserCodeGen, StateProfile},
 };
 
+mod cache;
+mod cancellation;
+mod coverage;
+mod encoding;
 mod errors;
+mod graphs;
+mod languages;
+mod lex;
+mod manifest;
+mod output;
+mod repl;
+mod report;
+mod resolve;
+mod tables;
+
+use cache::TableCache;
+use languages::LanguageFactory;
+use manifest::Manifest;
+use output::GeneratedOutput;
+
+pub use cache::default_table_cache;
+pub use cancellation::CancellationToken;
+pub use coverage::{measure_coverage, Coverage, CoverageEntry};
+pub use encoding::{read_input, InputEncoding};
+pub use errors::{ErrorCode, ErrorKind, LapexError};
+pub use languages::Language;
+pub use lapex_input::{PrecedenceStrategy, DEFAULT_TAB_WIDTH};
+pub use lex::{lex_file, LexedToken};
+pub use manifest::{verify_output, Mismatch};
+pub use output::STDOUT_TARGET;
+pub use repl::run_repl;
+pub use report::{Counter, GenerationReport, Phase};
+pub use resolve::{run_resolve, ResolveOptions};
 
-#[derive(Debug, Clone, ValueEnum, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(ValueEnum))]
 pub enum ParsingAlgorithm {
     LL1,
+    LL2,
     LR0,
     LR1,
-    LALR,
+    #[cfg_attr(feature = "cli", value(alias = "lalr"))]
+    LALR1,
     GLR,
 }
 
@@ -35,86 +79,150 @@ impl Display for ParsingAlgorithm {
             "{}",
             match self {
                 ParsingAlgorithm::LL1 => "ll1",
+                ParsingAlgorithm::LL2 => "ll2",
                 ParsingAlgorithm::LR0 => "lr0",
                 ParsingAlgorithm::LR1 => "lr1",
-                ParsingAlgorithm::LALR => "lalr",
+                ParsingAlgorithm::LALR1 => "lalr1",
                 ParsingAlgorithm::GLR => "glr",
             }
         )
     }
 }
 
-#[derive(Debug, Clone, ValueEnum)]
-pub enum Language {
-    Rust,
-    Cpp,
+#[derive(Debug, Clone)]
+pub struct GenerationOptions {
+    pub generate_lexer: bool,
+    /// Without a parser, grammars of only tokens can be used, and only the tokens and the lexer
+    /// are generated.
+    pub generate_parser: bool,
+    pub algorithm: ParsingAlgorithm,
+    pub generate_table: bool,
+    /// Also writes the NFA and the DFA of the lexer and the states of an LR parser as Graphviz
+    /// `.dot` files.
+    pub emit_graphs: bool,
+    /// Merges the rules of a production written more than once into its alternatives, which the
+    /// CLI does unless `--strict-duplicates` is given. Otherwise a repeated name is an error.
+    pub merge_duplicate_productions: bool,
+    /// Rewrites left-recursive productions into repetitions before the table of an LL parser is
+    /// generated. The grammars of the other algorithms are left as they are.
+    pub eliminate_left_recursion: bool,
+    pub panic_free: bool,
+    /// Makes the generated C++ parsers allocate from a `std::pmr::memory_resource`.
+    pub polymorphic_allocator: bool,
+    /// Makes the generated LR and GLR parsers count what they do and report it at the end of
+    /// every parse.
+    pub metrics: bool,
+    /// Lets a hook be set on the generated Rust LR and GLR parsers, which is called every few
+    /// tokens with how far the parse got.
+    pub progress: bool,
+    /// Starts the generated Rust files with `#![allow]` for the lints a crate may enable beyond
+    /// the defaults, like `missing_docs` or `clippy::pedantic`.
+    pub allow_lints: bool,
+    /// Writes a `cst` module next to the generated Rust LR and GLR parsers, which builds and
+    /// returns a concrete syntax tree instead of only calling the visitor.
+    pub cst: bool,
+    /// Writes an `ast` module next to the generated Rust LR and GLR parsers, with a type for
+    /// every production which is assembled while parsing.
+    pub ast: bool,
+    /// Writes a function lexing and parsing a string in one call into the generated Rust LR and
+    /// GLR parsers and the C++ LR parser. Only done if the lexer is generated as well.
+    pub facade: bool,
+    /// Writes the tables of the lexer and the LR parser as JSON in place of the lexer and
+    /// parser code, for a driver of their own. The tokens are generated as usual.
+    pub tables_only: bool,
+    /// Creates the target directory if it does not exist, instead of failing.
+    pub create_target: bool,
+    /// Sample inputs which are parsed to order the states of LR tables by how often they are used.
+    pub profile_corpus: Vec<PathBuf>,
+    /// Checked between and during the longer phases, a cancelled run fails with an error.
+    pub cancellation: CancellationToken,
+    /// How the profiling inputs are decoded. The grammar is passed as text, this is the encoding
+    /// it was read with, recorded in the manifest to check the grammar against it later.
+    pub encoding: InputEncoding,
+    /// Prefixes the generated Rust modules and nests the C++ namespaces into one of this name,
+    /// so several parsers can be linked into one binary. Overrides the `prefix` of the grammar.
+    pub symbol_prefix: Option<String>,
+    /// Nests the generated C++ namespaces into this one, written like `mycompany::mylang`, and
+    /// names the include guards after it.
+    pub cpp_namespace: Option<String>,
+    /// The Rust module path or C++ namespace the parsers take the token enum from, for grammars
+    /// which declare their tokens with `extern token`. Neither the tokens nor a lexer are
+    /// generated for those.
+    pub external_tokens: Option<String>,
+    /// Picks the token when several tokens match the same input. Overrides the `precedence` of
+    /// the grammar.
+    pub precedence_strategy: Option<PrecedenceStrategy>,
+    /// Gives regex tokens no implicit precedence, so two regexes matching the same input need
+    /// explicit ones instead of the longer one winning.
+    pub conservative_precedence: bool,
+    /// Warns about the tokens which are picked over another token only because they are declared
+    /// before it, so reordering them would change the lexer.
+    pub warn_token_order: bool,
+    /// Written as `SPDX-License-Identifier` into a comment at the top of every generated file.
+    pub spdx_license: Option<String>,
+    /// Written into the same comment, below the license identifier. Every line of it becomes a
+    /// line of the comment.
+    pub copyright: Option<String>,
+    /// A directory LR tables are kept in, to skip their construction for a grammar of the same
+    /// structure. Tables with conflicts are only cached for GLR parsers.
+    pub table_cache: Option<PathBuf>,
 }
 
-trait LanguageFactory<Lexer, LR, LL, GLR> {
-    fn lexer(&self) -> Lexer;
-    fn lr_parser(&self) -> LR;
-    fn glr_parser(&self) -> GLR;
-    fn ll_parser(&self) -> LL;
-}
-
-struct CppLanguageFactory;
-
-impl LanguageFactory<CppLexerCodeGen, CppLRParserCodeGen, CppLLParserCodeGen, CppGLRParserCodeGen>
-    for CppLanguageFactory
-{
-    fn lexer(&self) -> CppLexerCodeGen {
-        CppLexerCodeGen::new()
-    }
-
-    fn lr_parser(&self) -> CppLRParserCodeGen {
-        CppLRParserCodeGen::new()
-    }
-
-    fn glr_parser(&self) -> CppGLRParserCodeGen {
-        CppGLRParserCodeGen::new()
-    }
-
-    fn ll_parser(&self) -> CppLLParserCodeGen {
-        CppLLParserCodeGen::new()
+/// Generates the lexer and an LR(1) parser into a target directory which is created if needed,
+/// with duplicate productions merged like the CLI does. Everything else is left out.
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        GenerationOptions {
+            generate_lexer: true,
+            generate_parser: true,
+            algorithm: ParsingAlgorithm::LR1,
+            generate_table: false,
+            emit_graphs: false,
+            merge_duplicate_productions: true,
+            eliminate_left_recursion: false,
+            panic_free: false,
+            polymorphic_allocator: false,
+            metrics: false,
+            progress: false,
+            allow_lints: false,
+            cst: false,
+            ast: false,
+            facade: false,
+            tables_only: false,
+            create_target: true,
+            profile_corpus: Vec::new(),
+            cancellation: CancellationToken::new(),
+            encoding: InputEncoding::default(),
+            symbol_prefix: None,
+            cpp_namespace: None,
+            external_tokens: None,
+            precedence_strategy: None,
+            conservative_precedence: false,
+            warn_token_order: false,
+            spdx_license: None,
+            copyright: None,
+            table_cache: None,
+        }
     }
 }
 
-struct RustLanguageFactory;
-
-impl
-    LanguageFactory<
-        RustLexerCodeGen,
-        RustLRParserCodeGen,
-        RustLLParserCodeGen,
-        RustGLRParserCodeGen,
-    > for RustLanguageFactory
-{
-    fn lexer(&self) -> RustLexerCodeGen {
-        RustLexerCodeGen::new()
-    }
-
-    fn lr_parser(&self) -> RustLRParserCodeGen {
-        RustLRParserCodeGen::new()
-    }
-
-    fn glr_parser(&self) -> RustGLRParserCodeGen {
-        RustGLRParserCodeGen::new()
-    }
-
-    fn ll_parser(&self) -> RustLLParserCodeGen {
-        RustLLParserCodeGen::new()
+impl GenerationOptions {
+    // the facade reads the tokens of the generated lexer, so it needs the lexer and its tokens
+    pub(crate) fn writes_facade(&self) -> bool {
+        self.facade && self.generate_lexer && self.external_tokens.is_none()
     }
 }
 
-fn generate_lexer_and_parser<L, LR, LL, GLR, F, I>(
-    generate_lexer: bool,
-    algorithm: ParsingAlgorithm,
-    generate_table: bool,
+// the files are kept in memory and returned without a target path
+pub(crate) fn generate_lexer_and_parser<L, LR, LL, GLR, F, I>(
+    options: &GenerationOptions,
     grammar_path: &Path,
-    target_path: &Path,
+    grammar_source: &str,
+    target_path: Option<&Path>,
     language: F,
     input_parser: I,
-) -> Result<(), Vec<LapexError>>
+    report: &mut GenerationReport,
+) -> Result<BTreeMap<String, String>, Vec<LapexError>>
 where
     L: LexerCodeGen,
     LR: LRParserCodeGen,
@@ -123,144 +231,706 @@ where
     F: LanguageFactory<L, LR, LL, GLR>,
     I: LapexInputParser,
 {
-    let lexer_codegen = language.lexer();
-    let ll_codegen = language.ll_parser();
-    let lr_codegen = language.lr_parser();
-    let glr_codegen = language.glr_parser();
+    let is_cancelled = || options.cancellation.is_cancelled();
 
-    let file_contents = std::fs::read_to_string(grammar_path)
-        .map_err(|e| LapexError::io(grammar_path.to_path_buf(), e))?;
+    let start = Instant::now();
     let rules = input_parser
-        .parse_lapex(file_contents.as_str())
-        .expect("TODO");
-    let mut gen = GeneratedCodeWriter::with_default(|name| {
-        let file = std::fs::File::create(target_path.join(name))?;
-        Ok(BufWriter::new(file))
-    });
-    lexer_codegen.generate_tokens(&rules.token_rules, &mut gen);
-
-    if generate_lexer {
+        .parse_lapex(grammar_source)
+        .map_err(|e| LapexError::syntax(grammar_path, grammar_source, e))?;
+    report.warn(LapexError::outdated_version(
+        grammar_path,
+        grammar_source,
+        rules.version,
+    ));
+    report.record(Phase::Input, start.elapsed());
+    check_cancelled(options)?;
+
+    let symbol_prefix = options.symbol_prefix.as_deref().or(rules.symbol_prefix);
+    let precedence_strategy = options
+        .precedence_strategy
+        .or(rules.precedence_strategy)
+        .unwrap_or_default();
+    let precedence_model: &dyn PrecedenceModel = if options.conservative_precedence {
+        &ConservativePrecedenceModel
+    } else {
+        &DefaultPrecedenceModel
+    };
+    let lexer_codegen = language.lexer(symbol_prefix);
+    let ll_codegen = language.ll_parser(symbol_prefix);
+    let lr_codegen = language.lr_parser(symbol_prefix);
+    let glr_codegen = language.glr_parser(symbol_prefix);
+
+    let start = Instant::now();
+    let output = match target_path {
+        Some(target_path) => {
+            GeneratedOutput::open(target_path, options.create_target, symbol_prefix)?
+        }
+        None => GeneratedOutput::in_memory(),
+    };
+    let mut gen = GeneratedCodeWriter::with_default(|name| output.create(name));
+    gen.set_header(license_header(options));
+    // tokens declared `extern` are read by a lexer of the user, which has its own token enum
+    let external_tokens = rules.has_external_tokens();
+    if external_tokens {
+        if options.external_tokens.is_none() {
+            return Err(LapexError::external_tokens(
+                grammar_path,
+                "pass the Rust module path or C++ namespace of their enum with --external-tokens",
+            ));
+        }
+        if !options.profile_corpus.is_empty() {
+            return Err(LapexError::external_tokens(
+                grammar_path,
+                "the inputs to profile can not be split into tokens without a lexer",
+            ));
+        }
+    } else {
+        lexer_codegen.generate_tokens(&rules.token_rules, &mut gen);
+    }
+    report.record(Phase::Codegen, start.elapsed());
+
+    let automaton = if external_tokens {
+        None
+    } else if options.generate_lexer || !options.profile_corpus.is_empty() {
+        let start = Instant::now();
         let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
         let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
-        let dfa = lapex_lexer::apply_precedence_to_dfa(nfa.powerset_construction(nfa_entrypoint))
-            .map_err(|e| LapexError::precedence(grammar_path, file_contents.as_str(), e))?;
+        report.record(Phase::Nfa, start.elapsed());
+        report.count(Counter::NfaStates, nfa.graph().node_count());
+        check_cancelled(options)?;
+
+        let start = Instant::now();
+        let dfa = nfa
+            .powerset_construction_cancellable(nfa_entrypoint, &is_cancelled)
+            .ok_or_else(LapexError::cancelled)?;
+        if options.warn_token_order {
+            report.warn(LapexError::order_dependent_tokens(
+                grammar_path,
+                grammar_source,
+                &lapex_lexer::find_order_dependent_tokens(&dfa, precedence_strategy),
+            ));
+        }
+        let (dfa, unused_precedences) =
+            lapex_lexer::apply_precedence_to_dfa(dfa, precedence_strategy, precedence_model)
+                .map_err(|e| LapexError::precedence(grammar_path, grammar_source, e))?;
+        report.warn(LapexError::unused_precedences(
+            grammar_path,
+            grammar_source,
+            &unused_precedences,
+        ));
+        report.record(Phase::Dfa, start.elapsed());
+        report.count(Counter::DfaStates, dfa.states().count());
+        if options.emit_graphs && options.generate_lexer {
+            graphs::write_lexer_graphs(&mut gen, symbol_prefix, &nfa, &dfa, &alphabet)?;
+        }
+        Some((alphabet, dfa))
+    } else {
+        None
+    };
 
-        lexer_codegen.generate_lexer(&rules.token_rules, &alphabet.get_ranges(), &dfa, &mut gen);
+    if options.generate_lexer && !options.tables_only {
+        if let Some((alphabet, dfa)) = &automaton {
+            let start = Instant::now();
+            lexer_codegen.generate_lexer(&rules.token_rules, &alphabet.get_ranges(), dfa, &mut gen);
+            report.record(Phase::Codegen, start.elapsed());
+        }
     }
 
-    let grammar = Grammar::from_rule_set(&rules).expect("TODO");
-    match algorithm {
-        ParsingAlgorithm::LL1 => {
-            let parser_table = lapex_parser::ll_parser::generate_table(&grammar).expect("TODO");
-            ll_codegen.generate_code(&grammar, &parser_table, &mut gen);
+    check_cancelled(options)?;
+    if options.generate_parser {
+        let start = Instant::now();
+        let eliminate_left_recursion = options.eliminate_left_recursion
+            && matches!(
+                options.algorithm,
+                ParsingAlgorithm::LL1 | ParsingAlgorithm::LL2
+            );
+        let grammar = Grammar::from_rule_set(&rules, options.merge_duplicate_productions)
+            .map_err(|e| LapexError::grammar(grammar_path, grammar_source, e))?;
+        report.warn(LapexError::duplicates(
+            grammar_path,
+            grammar_source,
+            grammar.duplicates(),
+        ));
+        let (errors, warnings): (Vec<LapexError>, Vec<LapexError>) = LapexError::grammar_issues(
+            grammar_path,
+            grammar_source,
+            &lapex_parser::validation::validate(&grammar, &rules),
+        )
+        .into_iter()
+        .partition(|error| !error.is_warning());
+        report.warn(warnings);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        // the diagnostics above are about the rules as they are written, the rewritten ones are
+        // only used for the table
+        let grammar = if eliminate_left_recursion {
+            GrammarBuilder::from_rule_set(&rules, options.merge_duplicate_productions)
+                .and_then(|builder| builder.with_left_recursion_elimination(true).build())
+                .map_err(|e| LapexError::grammar(grammar_path, grammar_source, e))?
+        } else {
+            grammar
+        };
+        if grammar.recovers_from_errors() {
+            let supports_error_recovery = match options.algorithm {
+                ParsingAlgorithm::LL1 | ParsingAlgorithm::LL2 => {
+                    ll_codegen.supports_error_recovery()
+                }
+                ParsingAlgorithm::GLR => glr_codegen.supports_error_recovery(),
+                _ => lr_codegen.supports_error_recovery(),
+            };
+            if !supports_error_recovery {
+                return Err(LapexError::unsupported_error_recovery(
+                    grammar_path,
+                    options.algorithm.clone(),
+                ));
+            }
         }
-        ParsingAlgorithm::LR0 => {
-            let parser_table =
-                match lapex_parser::lr_parser::generate_table::<0>(&grammar, false, false) {
-                    GenerationResult::NoConflicts(val) => val,
-                    GenerationResult::BadConflicts(conflicts) => {
-                        return Err(LapexError::conflicts(
-                            grammar_path,
-                            file_contents.as_str(),
-                            &conflicts,
-                            &grammar,
-                        )
-                        .into());
-                    }
-                    _ => unreachable!(),
-                };
-            if generate_table {
-                gen.generate_code("table", |output| {
-                    lapex_parser::lr_parser::output_table(&grammar, &parser_table, output)
-                })
-                .expect("TODO");
+        if !grammar.token_insertions().is_empty() {
+            let supports_token_insertion = match options.algorithm {
+                ParsingAlgorithm::LL1 | ParsingAlgorithm::LL2 => {
+                    ll_codegen.supports_token_insertion()
+                }
+                ParsingAlgorithm::GLR => glr_codegen.supports_token_insertion(),
+                _ => lr_codegen.supports_token_insertion(),
+            };
+            if !supports_token_insertion {
+                return Err(LapexError::unsupported_token_insertion(
+                    grammar_path,
+                    options.algorithm.clone(),
+                ));
             }
-            lr_codegen.generate_code(&grammar, &parser_table, &mut gen);
         }
-        ParsingAlgorithm::LALR | ParsingAlgorithm::LR1 => {
-            let parser_table = match lapex_parser::lr_parser::generate_table::<1>(
+        let corpus = match &automaton {
+            Some((alphabet, dfa)) => read_profile_corpus(
+                &options.profile_corpus,
+                options.encoding,
                 &grammar,
-                false,
-                algorithm == ParsingAlgorithm::LALR,
-            ) {
-                GenerationResult::NoConflicts(val) => val,
-                GenerationResult::BadConflicts(conflicts) => {
-                    return Err(LapexError::conflicts(
+                alphabet,
+                dfa,
+            )?,
+            None => Vec::new(),
+        };
+        match options.algorithm {
+            ParsingAlgorithm::LL1 | ParsingAlgorithm::LL2 => {
+                if options.tables_only {
+                    return Err(LapexError::unsupported_tables_only(
                         grammar_path,
-                        file_contents.as_str(),
-                        &conflicts,
-                        &grammar,
-                    )
-                    .into());
+                        options.algorithm.clone(),
+                    ));
                 }
-                _ => unreachable!(),
-            };
-            if generate_table {
-                gen.generate_code("table", |output| {
-                    lapex_parser::lr_parser::output_table(&grammar, &parser_table, output)
-                })
-                .expect("TODO");
+                let parser_table = if options.algorithm == ParsingAlgorithm::LL1 {
+                    lapex_parser::ll_parser::generate_table(&grammar)
+                } else {
+                    lapex_parser::ll_parser::generate_table_k(&grammar, 2)
+                }
+                .map_err(|e| LapexError::ll_table(grammar_path, grammar_source, &grammar, e))?;
+                report.record(Phase::Table, start.elapsed());
+                check_cancelled(options)?;
+
+                let start = Instant::now();
+                ll_codegen.generate_code(&grammar, &parser_table, &mut gen);
+                report.record(Phase::Codegen, start.elapsed());
             }
-            lr_codegen.generate_code(&grammar, &parser_table, &mut gen);
-        }
-        ParsingAlgorithm::GLR => {
-            let parser_table =
-                match lapex_parser::lr_parser::generate_table::<1>(&grammar, true, true) {
-                    GenerationResult::NoConflicts(table) => {
-                        // TODO: info about using LR1 instead
-                        table
-                    }
-                    GenerationResult::AllowedConflicts {
-                        table,
-                        conflicts: _conflicts,
-                    } => {
-                        // TODO: info about conflicts
-                        table
-                    }
-                    _ => unreachable!(),
-                };
-            if generate_table {
-                gen.generate_code("table", |output| {
-                    lapex_parser::lr_parser::output_table(&grammar, &parser_table, output)
-                })
-                .expect("TODO");
+            ParsingAlgorithm::LR0 => {
+                let parser_table = lr_table::<0>(
+                    options,
+                    grammar_path,
+                    grammar_source,
+                    &grammar,
+                    false,
+                    false,
+                )?;
+                generate_lr_parser(
+                    options,
+                    grammar_path,
+                    symbol_prefix,
+                    &rules.token_rules,
+                    automaton.as_ref(),
+                    &grammar,
+                    parser_table,
+                    &corpus,
+                    start,
+                    report,
+                    &mut gen,
+                    |grammar, table, gen| lr_codegen.generate_code(grammar, table, gen),
+                )?;
             }
-            glr_codegen.generate_code(&grammar, &parser_table, &mut gen);
+            ParsingAlgorithm::LALR1 | ParsingAlgorithm::LR1 => {
+                let parser_table = lr_table::<1>(
+                    options,
+                    grammar_path,
+                    grammar_source,
+                    &grammar,
+                    false,
+                    options.algorithm == ParsingAlgorithm::LALR1,
+                )?;
+                generate_lr_parser(
+                    options,
+                    grammar_path,
+                    symbol_prefix,
+                    &rules.token_rules,
+                    automaton.as_ref(),
+                    &grammar,
+                    parser_table,
+                    &corpus,
+                    start,
+                    report,
+                    &mut gen,
+                    |grammar, table, gen| lr_codegen.generate_code(grammar, table, gen),
+                )?;
+            }
+            ParsingAlgorithm::GLR => {
+                let parser_table =
+                    lr_table::<1>(options, grammar_path, grammar_source, &grammar, true, true)?;
+                generate_lr_parser(
+                    options,
+                    grammar_path,
+                    symbol_prefix,
+                    &rules.token_rules,
+                    automaton.as_ref(),
+                    &grammar,
+                    parser_table,
+                    &corpus,
+                    start,
+                    report,
+                    &mut gen,
+                    |grammar, table, gen| glr_codegen.generate_code(grammar, table, gen),
+                )?;
+            }
+        };
+    }
+    drop(gen);
+    output.commit(generation_manifest(
+        options,
+        language.name(),
+        symbol_prefix,
+        precedence_strategy,
+        grammar_path,
+        grammar_source,
+    ))
+}
+
+// the LR and GLR parsers are written from their table the same way, only by a different code
+// generator. `start` is when the construction of the table began
+#[allow(clippy::too_many_arguments)]
+fn generate_lr_parser<'grammar: 'rules, 'rules>(
+    options: &GenerationOptions,
+    grammar_path: &Path,
+    symbol_prefix: Option<&str>,
+    token_rules: &[Spanned<TokenRule>],
+    automaton: Option<&(Alphabet, TokenDfa)>,
+    grammar: &'grammar Grammar<'rules>,
+    mut parser_table: ActionGotoTable<'grammar, 'rules>,
+    corpus: &[Vec<Symbol>],
+    start: Instant,
+    report: &mut GenerationReport,
+    gen: &mut GeneratedCodeWriter,
+    codegen: impl FnOnce(&Grammar, &ActionGotoTable, &mut GeneratedCodeWriter),
+) -> Result<(), Vec<LapexError>> {
+    profile_states(&mut parser_table, corpus);
+    verify_table(grammar_path, grammar, &parser_table)?;
+    let pruned = parser_table.prune_unreachable_states();
+    report.record(Phase::Table, start.elapsed());
+    count_table_statistics(report, &parser_table);
+    report.count(Counter::PrunedStates, pruned);
+    check_cancelled(options)?;
+
+    let start = Instant::now();
+    if options.generate_table {
+        gen.generate_code("table", |output| {
+            lapex_parser::lr_parser::output_table(grammar, &parser_table, output)
+        })
+        .map_err(|e| LapexError::io(PathBuf::from("table"), e))?;
+    }
+    if options.emit_graphs {
+        graphs::write_parser_graph(gen, symbol_prefix, grammar, &parser_table)?;
+    }
+    if options.tables_only {
+        tables::write_tables(
+            gen,
+            symbol_prefix,
+            &options.algorithm,
+            token_rules,
+            automaton,
+            grammar,
+            &parser_table,
+        )?;
+    } else {
+        codegen(grammar, &parser_table, gen);
+    }
+    report.record(Phase::Codegen, start.elapsed());
+    Ok(())
+}
+
+// looks the table up in the cache before building it. Only tables with allowed conflicts are
+// cached besides those without, the conflicts of the others are reported from a new construction
+fn lr_table<'grammar: 'rules, 'rules, const N: usize>(
+    options: &GenerationOptions,
+    grammar_path: &Path,
+    file_contents: &str,
+    grammar: &'grammar Grammar<'rules>,
+    allow_conflicts: bool,
+    lalr: bool,
+) -> Result<ActionGotoTable<'grammar, 'rules>, Vec<LapexError>> {
+    let cache = options
+        .table_cache
+        .as_deref()
+        .map(|directory| TableCache::new(directory, grammar, &options.algorithm));
+    if let Some(table) = cache.as_ref().and_then(|cache| cache.load(grammar)) {
+        #[cfg(feature = "tracing")]
+        tracing::info!("reused the cached table");
+        return Ok(table);
+    }
+    let is_cancelled = || options.cancellation.is_cancelled();
+    let table = match lapex_parser::lr_parser::generate_table_cancellable::<N>(
+        grammar,
+        allow_conflicts,
+        lalr,
+        &is_cancelled,
+    )
+    .ok_or_else(LapexError::cancelled)?
+    {
+        // TODO: info about the conflicts of GLR parsers, or about using LR1 without any
+        GenerationResult::NoConflicts(table) => table,
+        GenerationResult::AllowedConflicts { table, conflicts } => {
+            // once a grammar allows some conflicts, the GLR parser may only keep those
+            let (_allowed, bad): (Vec<_>, Vec<_>) = conflicts
+                .into_iter()
+                .partition(|conflict| !grammar.has_allowed_conflicts() || conflict.is_allowed());
+            if !bad.is_empty() {
+                return Err(LapexError::conflicts(
+                    grammar_path,
+                    file_contents,
+                    &bad,
+                    grammar,
+                ));
+            }
+            #[cfg(feature = "tracing")]
+            tracing::info!(
+                conflicts = _allowed.len(),
+                "kept the conflicts for the GLR parser"
+            );
+            table
+        }
+        GenerationResult::BadConflicts(conflicts) => {
+            #[cfg(feature = "tracing")]
+            tracing::info!(conflicts = conflicts.len(), "the table has conflicts");
+            return Err(LapexError::conflicts(
+                grammar_path,
+                file_contents,
+                &conflicts,
+                grammar,
+            ));
         }
     };
-    Ok(())
+    if let Some(cache) = &cache {
+        verify_table(grammar_path, grammar, &table)?;
+        cache.store(grammar, &table);
+    }
+    Ok(table)
+}
+
+fn count_table_statistics(report: &mut GenerationReport, table: &ActionGotoTable) {
+    let statistics = table.statistics();
+    report.count(Counter::ParserStates, statistics.states);
+    report.count(Counter::ParserTransitions, statistics.transitions);
+    report.count(Counter::ItemsAllocated, statistics.items_allocated);
+    report.count(Counter::LargestItemSet, statistics.largest_item_set);
+}
+
+// a table breaking the invariants of the construction would be turned into a broken parser
+fn verify_table<'grammar: 'rules, 'rules>(
+    grammar_path: &Path,
+    grammar: &'grammar Grammar<'rules>,
+    table: &ActionGotoTable<'grammar, 'rules>,
+) -> Result<(), Vec<LapexError>> {
+    let defects = table.verify(grammar);
+    if defects.is_empty() {
+        Ok(())
+    } else {
+        Err(LapexError::invalid_table(grammar_path, &defects, grammar))
+    }
+}
+
+// the license notice at the top of every generated file, empty without one
+fn license_header(options: &GenerationOptions) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(license) = &options.spdx_license {
+        lines.push(format!("SPDX-License-Identifier: {}", license));
+    }
+    if let Some(copyright) = &options.copyright {
+        lines.extend(copyright.lines().map(str::to_string));
+    }
+    lines
+}
+
+// the options are recorded by the names of their command line arguments
+fn generation_manifest(
+    options: &GenerationOptions,
+    language: &'static str,
+    symbol_prefix: Option<&str>,
+    precedence_strategy: PrecedenceStrategy,
+    grammar_path: &Path,
+    grammar_source: &str,
+) -> Manifest {
+    let mut manifest = Manifest::new(grammar_path, grammar_source, options.encoding);
+    manifest.add_option("language", language);
+    manifest.add_option("algorithm", options.algorithm.to_string());
+    manifest.add_option("lexer", options.generate_lexer);
+    manifest.add_option("parser", options.generate_parser);
+    manifest.add_option("table", options.generate_table);
+    manifest.add_option("emit_graphs", options.emit_graphs);
+    manifest.add_option("merge_duplicates", options.merge_duplicate_productions);
+    manifest.add_option("panic_free", options.panic_free);
+    manifest.add_option("pmr", options.polymorphic_allocator);
+    manifest.add_option("metrics", options.metrics);
+    manifest.add_option("progress", options.progress);
+    manifest.add_option("allow_lints", options.allow_lints);
+    manifest.add_option("cst", options.cst);
+    manifest.add_option("ast", options.ast);
+    manifest.add_option("tables_only", options.tables_only);
+    manifest.add_option("encoding", options.encoding.to_string());
+    manifest.add_option("prefix", symbol_prefix);
+    manifest.add_option("cpp_namespace", options.cpp_namespace.clone());
+    manifest.add_option("external_tokens", options.external_tokens.clone());
+    manifest.add_option("precedence", precedence_strategy.name());
+    manifest.add_option("conservative_precedence", options.conservative_precedence);
+    manifest.add_option("spdx_license", options.spdx_license.clone());
+    manifest.add_option("copyright", options.copyright.clone());
+    manifest.add_option(
+        "profile",
+        options
+            .profile_corpus
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>(),
+    );
+    manifest
+}
+
+fn check_cancelled(options: &GenerationOptions) -> Result<(), Vec<LapexError>> {
+    if options.cancellation.is_cancelled() {
+        Err(LapexError::cancelled())
+    } else {
+        Ok(())
+    }
+}
+
+// tokens which no production uses are skipped, like whitespace would be by a real parser
+fn parser_token_symbols<'grammar>(grammar: &'grammar Grammar) -> BTreeMap<&'grammar str, Symbol> {
+    let used_symbols: BTreeSet<Symbol> = grammar
+        .rules()
+        .iter()
+        .flat_map(|rule| rule.rhs().iter().copied())
+        .collect();
+    grammar
+        .terminals_with_names()
+        .filter(|(symbol, _)| used_symbols.contains(symbol))
+        .map(|(symbol, name)| (name, symbol))
+        .collect()
+}
+
+// builds the lexer automaton for the tools which tokenize input in process
+fn build_token_dfa<'rules>(
+    grammar_path: &Path,
+    file_contents: &str,
+    rules: &'rules RuleSet<'rules>,
+) -> Result<(Alphabet, TokenDfa<'rules>), Vec<LapexError>> {
+    let alphabet = lapex_lexer::generate_alphabet(&rules.token_rules);
+    let (nfa_entrypoint, nfa) = lapex_lexer::generate_nfa(&alphabet, &rules.token_rules);
+    let (dfa, _) = lapex_lexer::apply_precedence_to_dfa(
+        nfa.powerset_construction(nfa_entrypoint),
+        rules.precedence_strategy.unwrap_or_default(),
+        &DefaultPrecedenceModel,
+    )
+    .map_err(|e| LapexError::precedence(grammar_path, file_contents, e))?;
+    Ok((alphabet, dfa))
+}
+
+// builds the LR(1) table for the tools which parse input in process
+fn build_lr1_table<'grammar: 'rules, 'rules>(
+    grammar_path: &Path,
+    file_contents: &str,
+    grammar: &'grammar Grammar<'rules>,
+) -> Result<ActionGotoTable<'grammar, 'rules>, Vec<LapexError>> {
+    match lapex_parser::lr_parser::generate_table::<1>(grammar, false, false) {
+        GenerationResult::NoConflicts(table) | GenerationResult::AllowedConflicts { table, .. } => {
+            verify_table(grammar_path, grammar, &table)?;
+            Ok(table)
+        }
+        GenerationResult::BadConflicts(conflicts) => Err(LapexError::conflicts(
+            grammar_path,
+            file_contents,
+            &conflicts,
+            grammar,
+        )),
+    }
+}
+
+fn read_profile_corpus(
+    paths: &[PathBuf],
+    encoding: InputEncoding,
+    grammar: &Grammar,
+    alphabet: &Alphabet,
+    dfa: &Dfa<&TokenRule, usize>,
+) -> Result<Vec<Vec<Symbol>>, Vec<LapexError>> {
+    let token_symbols = parser_token_symbols(grammar);
+
+    let mut corpus = Vec::new();
+    for path in paths {
+        let contents = read_input(path, encoding)?;
+        let tokens = lapex_lexer::scan_tokens(dfa, alphabet, &contents)
+            .map_err(|offset| LapexError::tokenize_input(path, &contents, offset))?;
+        corpus.push(
+            tokens
+                .into_iter()
+                .filter_map(|rule| token_symbols.get(&*rule.name).copied())
+                .collect(),
+        );
+    }
+    Ok(corpus)
+}
+
+fn profile_states<'grammar: 'rules, 'rules>(
+    table: &mut ActionGotoTable<'grammar, 'rules>,
+    corpus: &[Vec<Symbol>],
+) {
+    if corpus.is_empty() {
+        return;
+    }
+    let mut profile = StateProfile::new(table);
+    for tokens in corpus {
+        profile.record(table, tokens);
+    }
+    table.reorder_states(&profile.hot_state_order());
 }
 
+/// Generates the files for the grammar `grammar_source` into `target_path`. The grammar is not
+/// read from `grammar_path`, which only names it in the diagnostics and in the manifest, so
+/// the caller reads the file, like with `read_input`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        skip_all,
+        fields(grammar = %grammar_path.display(), language = ?language, algorithm = %options.algorithm)
+    )
+)]
 pub fn generate<I>(
-    generate_lexer: bool,
-    algorithm: ParsingAlgorithm,
-    generate_table: bool,
+    options: &GenerationOptions,
     grammar_path: &Path,
+    grammar_source: &str,
     target_path: &Path,
     language: Language,
     input_parser: I,
-) -> Result<(), Vec<LapexError>>
+) -> Result<GenerationReport, Vec<LapexError>>
 where
     I: LapexInputParser,
 {
-    match language {
-        Language::Cpp => generate_lexer_and_parser(
-            generate_lexer,
-            algorithm,
-            generate_table,
-            grammar_path,
-            target_path,
-            CppLanguageFactory {},
-            input_parser,
-        ),
-        Language::Rust => generate_lexer_and_parser(
-            generate_lexer,
-            algorithm,
-            generate_table,
-            grammar_path,
-            target_path,
-            RustLanguageFactory {},
-            input_parser,
-        ),
+    let mut report = GenerationReport::default();
+    let result = language.generate_lexer_and_parser(
+        options,
+        grammar_path,
+        grammar_source,
+        Some(target_path),
+        input_parser,
+        &mut report,
+    );
+    match result {
+        Ok(_) => Ok(report),
+        Err(errors) => {
+            // the warnings found before the errors are reported with them
+            let mut warnings = report.take_warnings();
+            warnings.extend(errors);
+            Err(warnings)
+        }
+    }
+}
+
+/// Generates like `generate`, but returns the generated files by their names instead of writing
+/// them. No manifest is written, and the sections for own code are empty.
+pub fn generate_in_memory<I>(
+    options: &GenerationOptions,
+    grammar_path: &Path,
+    grammar_source: &str,
+    language: Language,
+    input_parser: I,
+) -> Result<(BTreeMap<String, String>, GenerationReport), Vec<LapexError>>
+where
+    I: LapexInputParser,
+{
+    let mut report = GenerationReport::default();
+    let result = language.generate_lexer_and_parser(
+        options,
+        grammar_path,
+        grammar_source,
+        None,
+        input_parser,
+        &mut report,
+    );
+    match result {
+        Ok(files) => Ok((files, report)),
+        Err(errors) => {
+            // the warnings found before the errors are reported with them
+            let mut warnings = report.take_warnings();
+            warnings.extend(errors);
+            Err(warnings)
+        }
     }
 }
+
+pub fn find_symbol_references<I>(
+    grammar_path: &Path,
+    grammar_source: &str,
+    symbol_name: &str,
+    input_parser: I,
+) -> Result<Vec<SourceSpan>, Vec<LapexError>>
+where
+    I: LapexInputParser,
+{
+    let rules = input_parser
+        .parse_lapex(grammar_source)
+        .map_err(|e| LapexError::syntax(grammar_path, grammar_source, e))?;
+    if !rules.defines_symbol(symbol_name) {
+        return Err(LapexError::unknown_symbol(grammar_path, symbol_name));
+    }
+    Ok(rules.symbol_references(symbol_name))
+}
+
+/// Returns an empty implementation of the visitor the generated parsers call, to start user code
+/// from. Like when generating, `symbol_prefix` overrides the prefix declared in the grammar, and
+/// `cpp_namespace` is the namespace the C++ parser was generated into.
+#[allow(clippy::too_many_arguments)]
+pub fn scaffold_visitor<I>(
+    grammar_path: &Path,
+    grammar_source: &str,
+    language: Language,
+    type_name: &str,
+    symbol_prefix: Option<&str>,
+    cpp_namespace: Option<&str>,
+    merge_duplicate_productions: bool,
+    input_parser: I,
+) -> Result<String, Vec<LapexError>>
+where
+    I: LapexInputParser,
+{
+    let rules = input_parser
+        .parse_lapex(grammar_source)
+        .map_err(|e| LapexError::syntax(grammar_path, grammar_source, e))?;
+    let grammar = Grammar::from_rule_set(&rules, merge_duplicate_productions)
+        .map_err(|e| LapexError::grammar(grammar_path, grammar_source, e))?;
+    let mut output = Vec::new();
+    language
+        .write_visitor_scaffold(
+            &grammar,
+            type_name,
+            symbol_prefix.or(rules.symbol_prefix),
+            cpp_namespace,
+            &mut output,
+        )
+        .expect("writing to a Vec cannot fail");
+    Ok(String::from_utf8(output).unwrap())
+}
+
+#[cfg(test)]
+mod tests;
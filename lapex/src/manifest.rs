@@ -0,0 +1,506 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    path::{Path, PathBuf},
+};
+
+use lapex_codegen::strip_user_code;
+
+use crate::{read_input, InputEncoding, LapexError};
+
+const MANIFEST_NAME: &str = "lapex-manifest";
+const MANIFEST_VERSION: u64 = 1;
+
+/// A generated file or grammar which no longer matches the manifest of its directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The file was changed outside of its user code sections.
+    Modified(PathBuf),
+    Missing(PathBuf),
+    /// The grammar was changed after the files were generated from it.
+    GrammarChanged(PathBuf),
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mismatch::Modified(path) => write!(f, "{}: modified", path.display()),
+            Mismatch::Missing(path) => write!(f, "{}: missing", path.display()),
+            Mismatch::GrammarChanged(path) => {
+                write!(f, "{}: changed since the generation", path.display())
+            }
+        }
+    }
+}
+
+/// What a generation wrote into its target directory, kept there as `lapex-manifest.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Manifest {
+    grammar: PathBuf,
+    // the hash is of the decoded text, so the grammar is decoded like it was when generating
+    grammar_encoding: InputEncoding,
+    grammar_hash: String,
+    options: Vec<(&'static str, Json)>,
+    // the hash of every file without the contents of its user code sections
+    files: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub(crate) fn new(grammar: &Path, grammar_source: &str, encoding: InputEncoding) -> Self {
+        Manifest {
+            grammar: grammar.to_path_buf(),
+            grammar_encoding: encoding,
+            grammar_hash: sha256(grammar_source.as_bytes()),
+            options: Vec::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn add_option<V: Into<Json>>(&mut self, name: &'static str, value: V) {
+        self.options.push((name, value.into()));
+    }
+
+    pub(crate) fn add_file(&mut self, name: &str, contents: &[u8]) {
+        self.files.insert(name.to_string(), sha256(contents));
+    }
+
+    pub(crate) fn file_names(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    // parsers with different prefixes can share a directory, so each one has its own manifest
+    pub(crate) fn file_name(symbol_prefix: Option<&str>) -> String {
+        match symbol_prefix {
+            Some(prefix) => format!("{}.{}.json", MANIFEST_NAME, prefix),
+            None => format!("{}.json", MANIFEST_NAME),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let object = |entries: Vec<(&str, Json)>| {
+            Json::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect(),
+            )
+        };
+        let json = object(vec![
+            ("manifest_version", Json::Number(MANIFEST_VERSION)),
+            ("lapex_version", env!("CARGO_PKG_VERSION").into()),
+            (
+                "grammar",
+                object(vec![
+                    ("path", self.grammar.display().to_string().into()),
+                    ("sha256", self.grammar_hash.clone().into()),
+                ]),
+            ),
+            ("options", object(self.options.clone())),
+            (
+                "files",
+                Json::Object(
+                    self.files
+                        .iter()
+                        .map(|(name, hash)| (name.clone(), hash.clone().into()))
+                        .collect(),
+                ),
+            ),
+        ]);
+        format!("{}\n", json)
+    }
+
+    pub(crate) fn from_json(text: &str) -> Option<Manifest> {
+        let json = Json::parse(text)?;
+        if json.get("manifest_version")? != &Json::Number(MANIFEST_VERSION) {
+            return None;
+        }
+        let grammar = json.get("grammar")?;
+        let grammar_encoding = match json.get("options").and_then(|o| o.get("encoding")) {
+            Some(encoding) => InputEncoding::from_name(encoding.as_str()?)?,
+            None => InputEncoding::default(),
+        };
+        let files = match json.get("files")? {
+            Json::Object(files) => files
+                .iter()
+                .map(|(name, hash)| Some((name.clone(), hash.as_str()?.to_string())))
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+        Some(Manifest {
+            grammar: PathBuf::from(grammar.get("path")?.as_str()?),
+            grammar_encoding,
+            grammar_hash: grammar.get("sha256")?.as_str()?.to_string(),
+            // the other options are only recorded for the readers of the manifest
+            options: Vec::new(),
+            files,
+        })
+    }
+
+    // the grammar is only checked if it is still found where it was generated from
+    fn verify(&self, directory: &Path) -> Vec<Mismatch> {
+        let mut mismatches = Vec::new();
+        if let Ok(grammar) = read_input(&self.grammar, self.grammar_encoding) {
+            if sha256(grammar.as_bytes()) != self.grammar_hash {
+                mismatches.push(Mismatch::GrammarChanged(self.grammar.clone()));
+            }
+        }
+        for (name, hash) in &self.files {
+            let path = directory.join(name);
+            match std::fs::read_to_string(&path) {
+                Err(_) => mismatches.push(Mismatch::Missing(path)),
+                Ok(contents) => match strip_user_code(&contents) {
+                    Ok(generated) if sha256(generated.as_bytes()) == *hash => (),
+                    _ => mismatches.push(Mismatch::Modified(path)),
+                },
+            }
+        }
+        mismatches
+    }
+}
+
+/// Compares the files in a directory with the manifests written by the generations into it.
+/// The grammars are checked as well if they are found at the paths they were generated from.
+pub fn verify_output(directory: &Path) -> Result<Vec<Mismatch>, Vec<LapexError>> {
+    let entries =
+        std::fs::read_dir(directory).map_err(|e| LapexError::io(directory.to_path_buf(), e))?;
+    let mut manifest_paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| LapexError::io(directory.to_path_buf(), e))?
+            .path();
+        let name = path.file_name().and_then(|name| name.to_str());
+        if name.is_some_and(|name| name.starts_with(MANIFEST_NAME) && name.ends_with(".json")) {
+            manifest_paths.push(path);
+        }
+    }
+    if manifest_paths.is_empty() {
+        return Err(LapexError::io(
+            directory.to_path_buf(),
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "the directory contains no lapex-manifest.json",
+            ),
+        ));
+    }
+    manifest_paths.sort();
+    let mut mismatches = Vec::new();
+    for path in manifest_paths {
+        let text = std::fs::read_to_string(&path).map_err(|e| LapexError::io(path.clone(), e))?;
+        let manifest = Manifest::from_json(&text).ok_or_else(|| {
+            LapexError::io(
+                path.clone(),
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "the manifest is not valid or of a different version",
+                ),
+            )
+        })?;
+        mismatches.extend(manifest.verify(directory));
+    }
+    Ok(mismatches)
+}
+
+/// The JSON values the manifest consists of. Numbers are only unsigned integers.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(u64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Self {
+        Json::Bool(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Self {
+        Json::String(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Json::Null)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(value: Vec<T>) -> Self {
+        Json::Array(value.into_iter().map(Into::into).collect())
+    }
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn parse(text: &str) -> Option<Json> {
+        let mut chars = text.chars().peekable();
+        let json = Self::parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        chars.peek().is_none().then_some(json)
+    }
+
+    fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Json> {
+        skip_whitespace(chars);
+        match *chars.peek()? {
+            '{' => {
+                chars.next();
+                let mut entries = Vec::new();
+                skip_whitespace(chars);
+                if chars.next_if_eq(&'}').is_some() {
+                    return Some(Json::Object(entries));
+                }
+                loop {
+                    skip_whitespace(chars);
+                    let key = Self::parse_string(chars)?;
+                    skip_whitespace(chars);
+                    chars.next_if_eq(&':')?;
+                    entries.push((key, Self::parse_value(chars)?));
+                    skip_whitespace(chars);
+                    match chars.next()? {
+                        ',' => (),
+                        '}' => return Some(Json::Object(entries)),
+                        _ => return None,
+                    }
+                }
+            }
+            '[' => {
+                chars.next();
+                let mut elements = Vec::new();
+                skip_whitespace(chars);
+                if chars.next_if_eq(&']').is_some() {
+                    return Some(Json::Array(elements));
+                }
+                loop {
+                    elements.push(Self::parse_value(chars)?);
+                    skip_whitespace(chars);
+                    match chars.next()? {
+                        ',' => (),
+                        ']' => return Some(Json::Array(elements)),
+                        _ => return None,
+                    }
+                }
+            }
+            '"' => Self::parse_string(chars).map(Json::String),
+            '0'..='9' => {
+                let mut digits = String::new();
+                while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                    digits.push(digit);
+                }
+                digits.parse().ok().map(Json::Number)
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(letter) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+                    word.push(letter);
+                }
+                match word.as_str() {
+                    "null" => Some(Json::Null),
+                    "true" => Some(Json::Bool(true)),
+                    "false" => Some(Json::Bool(false)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+        chars.next_if_eq(&'"')?;
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    'r' => value.push('\r'),
+                    't' => value.push('\t'),
+                    'b' => value.push('\u{8}'),
+                    'f' => value.push('\u{c}'),
+                    'u' => {
+                        let high = parse_hex(chars)?;
+                        // characters outside of the BMP are written as a surrogate pair
+                        let code = if (0xd800..0xdc00).contains(&high) {
+                            chars.next_if_eq(&'\\')?;
+                            chars.next_if_eq(&'u')?;
+                            let low = parse_hex(chars)?;
+                            0x10000 + ((high - 0xd800) << 10) + low.checked_sub(0xdc00)?
+                        } else {
+                            high
+                        };
+                        value.push(char::from_u32(code)?);
+                    }
+                    escaped => value.push(escaped),
+                },
+                ch => value.push(ch),
+            }
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_hex(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<u32> {
+    let digits: String = (0..4).map_while(|_| chars.next()).collect();
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+// one entry per line, so the manifest can be diffed
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write_indented(0, f)
+    }
+}
+
+impl Json {
+    fn write_indented(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_json_string(value, f),
+            Json::Array(elements) if elements.is_empty() => write!(f, "[]"),
+            // numbers are kept on one line, so tables of them stay readable
+            Json::Array(elements) if elements.iter().all(|e| matches!(e, Json::Number(_))) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    write!(f, "{}", if i == 0 { "" } else { ", " })?;
+                    element.write_indented(indent + 1, f)?;
+                }
+                write!(f, "]")
+            }
+            Json::Array(elements) => {
+                writeln!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    write!(f, "{:1$}", "", (indent + 1) * 2)?;
+                    element.write_indented(indent + 1, f)?;
+                    writeln!(f, "{}", if i + 1 < elements.len() { "," } else { "" })?;
+                }
+                write!(f, "{:1$}]", "", indent * 2)
+            }
+            Json::Object(entries) if entries.is_empty() => write!(f, "{{}}"),
+            Json::Object(entries) => {
+                writeln!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    write!(f, "{:1$}", "", (indent + 1) * 2)?;
+                    write_json_string(key, f)?;
+                    write!(f, ": ")?;
+                    value.write_indented(indent + 1, f)?;
+                    writeln!(f, "{}", if i + 1 < entries.len() { "," } else { "" })?;
+                }
+                write!(f, "{:1$}}}", "", indent * 2)
+            }
+        }
+    }
+}
+
+fn write_json_string(value: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.write_char('"')?;
+    for ch in value.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            ch if (ch as u32) < 0x20 => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => f.write_char(ch)?,
+        }
+    }
+    f.write_char('"')
+}
+
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+// the hex digest of SHA-256, written out to not depend on a crate for a single hash function
+pub(crate) fn sha256(data: &[u8]) -> String {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+    for block in message.chunks_exact(64) {
+        let mut words = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = words[i - 15].rotate_right(7)
+                ^ words[i - 15].rotate_right(18)
+                ^ (words[i - 15] >> 3);
+            let s1 = words[i - 2].rotate_right(17)
+                ^ words[i - 2].rotate_right(19)
+                ^ (words[i - 2] >> 10);
+            words[i] = words[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(words[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(SHA256_ROUND_CONSTANTS[i])
+                .wrapping_add(words[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+    state.iter().map(|value| format!("{:08x}", value)).collect()
+}
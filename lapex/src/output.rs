@@ -0,0 +1,270 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use lapex_codegen::restore_user_code;
+
+use crate::{manifest::Manifest, LapexError};
+
+// the plain list of file names written before the JSON manifest
+const LEGACY_MANIFEST_NAME: &str = ".lapex-manifest";
+
+/// The target path which streams all generated files to stdout instead of writing them to disk.
+pub const STDOUT_TARGET: &str = "-";
+
+/// The destination of the generated code. Files are written next to their final location
+/// first and only moved into place once the whole generation succeeded, so failed or interrupted
+/// runs leave the previously generated files untouched.
+pub(crate) struct GeneratedOutput<'path> {
+    // streams to stdout if there is no directory, unless the files are kept in memory
+    directory: Option<&'path Path>,
+    in_memory: bool,
+    manifest_name: String,
+    legacy_manifest_name: String,
+    written: RefCell<BTreeSet<String>>,
+    streamed: RefCell<BTreeMap<String, Vec<u8>>>,
+    // the code generators cannot report errors, so the first one is kept until the commit
+    failed: RefCell<Option<(PathBuf, std::io::Error)>>,
+    committed: bool,
+}
+
+// keeps the contents of a file in memory, until the output is committed
+struct StreamedFile<'output> {
+    name: String,
+    contents: Vec<u8>,
+    streamed: &'output RefCell<BTreeMap<String, Vec<u8>>>,
+}
+
+impl<'output> Write for StreamedFile<'output> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.contents.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'output> Drop for StreamedFile<'output> {
+    fn drop(&mut self) {
+        self.streamed.borrow_mut().insert(
+            std::mem::take(&mut self.name),
+            std::mem::take(&mut self.contents),
+        );
+    }
+}
+
+impl<'path> GeneratedOutput<'path> {
+    /// Fails if the target directory does not exist, unless it may be created.
+    pub(crate) fn open(
+        path: &'path Path,
+        create: bool,
+        symbol_prefix: Option<&str>,
+    ) -> Result<Self, Vec<LapexError>> {
+        let directory = (path != Path::new(STDOUT_TARGET)).then_some(path);
+        if let Some(directory) = directory.filter(|directory| !directory.is_dir()) {
+            if create {
+                std::fs::create_dir_all(directory)
+                    .map_err(|e| LapexError::io(directory.to_path_buf(), e))?;
+            } else {
+                return Err(LapexError::io(
+                    directory.to_path_buf(),
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        "the target directory does not exist",
+                    ),
+                ));
+            }
+        }
+        Ok(GeneratedOutput {
+            directory,
+            in_memory: false,
+            manifest_name: Manifest::file_name(symbol_prefix),
+            legacy_manifest_name: match symbol_prefix {
+                Some(prefix) => format!("{}.{}", LEGACY_MANIFEST_NAME, prefix),
+                None => LEGACY_MANIFEST_NAME.to_string(),
+            },
+            written: RefCell::new(BTreeSet::new()),
+            streamed: RefCell::new(BTreeMap::new()),
+            failed: RefCell::new(None),
+            committed: false,
+        })
+    }
+
+    /// Keeps the generated files in memory, to be returned by `commit`.
+    pub(crate) fn in_memory() -> Self {
+        GeneratedOutput {
+            directory: None,
+            in_memory: true,
+            manifest_name: String::new(),
+            legacy_manifest_name: String::new(),
+            written: RefCell::new(BTreeSet::new()),
+            streamed: RefCell::new(BTreeMap::new()),
+            failed: RefCell::new(None),
+            committed: false,
+        }
+    }
+
+    fn temporary_path(directory: &Path, name: &str) -> PathBuf {
+        directory.join(format!(".{}.tmp", name))
+    }
+
+    pub(crate) fn create(&self, name: &str) -> std::io::Result<Box<dyn Write + '_>> {
+        self.written.borrow_mut().insert(name.to_string());
+        match self.directory {
+            Some(directory) => match File::create(Self::temporary_path(directory, name)) {
+                Ok(file) => Ok(Box::new(BufWriter::new(file))),
+                Err(e) => {
+                    self.failed
+                        .borrow_mut()
+                        .get_or_insert((directory.join(name), e));
+                    Ok(Box::new(std::io::sink()))
+                }
+            },
+            None => Ok(Box::new(StreamedFile {
+                name: name.to_string(),
+                contents: Vec::new(),
+                streamed: &self.streamed,
+            })),
+        }
+    }
+
+    /// Moves the written files into place and removes the files generated by an earlier run
+    /// which were not written this time. Those are listed in a manifest in the directory, other
+    /// files are never removed. The user code sections of the replaced files are kept. Returns
+    /// the files by their names if they are kept in memory, without a manifest.
+    pub(crate) fn commit(
+        mut self,
+        mut manifest: Manifest,
+    ) -> Result<BTreeMap<String, String>, Vec<LapexError>> {
+        if let Some((path, error)) = self.failed.take() {
+            return Err(LapexError::io(path, error));
+        }
+        // nothing is moved before all sections are restored, so a failure keeps the old files
+        if let Some(directory) = self.directory {
+            for name in self.written.borrow().iter() {
+                // the files are hashed as generated, with empty user code sections
+                let temporary_path = Self::temporary_path(directory, name);
+                let generated = std::fs::read(&temporary_path)
+                    .map_err(|e| LapexError::io(temporary_path, e))?;
+                manifest.add_file(name, &generated);
+                Self::keep_user_code(directory, name)?;
+            }
+        }
+        self.committed = true;
+        match self.directory {
+            Some(directory) => self
+                .commit_directory(directory, &manifest)
+                .map(|_| BTreeMap::new()),
+            // the code generators only write strings, so the files are valid UTF-8
+            None if self.in_memory => Ok(self
+                .streamed
+                .take()
+                .into_iter()
+                .map(|(name, contents)| (name, String::from_utf8(contents).unwrap()))
+                .collect()),
+            None => Self::commit_stream(&self.streamed.take(), std::io::stdout().lock())
+                .map(|_| BTreeMap::new())
+                .map_err(|e| LapexError::io(PathBuf::from(STDOUT_TARGET), e)),
+        }
+    }
+
+    fn commit_directory(
+        &self,
+        directory: &Path,
+        manifest: &Manifest,
+    ) -> Result<(), Vec<LapexError>> {
+        for name in manifest.file_names() {
+            let path = directory.join(name);
+            std::fs::rename(Self::temporary_path(directory, name), &path)
+                .map_err(|e| LapexError::io(path, e))?;
+        }
+
+        let manifest_path = directory.join(&self.manifest_name);
+        let legacy_manifest_path = directory.join(&self.legacy_manifest_name);
+        // there is no manifest before the first run
+        let previous: Vec<String> = match std::fs::read_to_string(&manifest_path) {
+            Ok(previous) => Manifest::from_json(&previous)
+                .map(|previous| previous.file_names().map(str::to_string).collect())
+                .unwrap_or_default(),
+            Err(_) => std::fs::read_to_string(&legacy_manifest_path)
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string)
+                .collect(),
+        };
+        let written: BTreeSet<&str> = manifest.file_names().collect();
+        for stale in previous
+            .iter()
+            .filter(|name| !written.contains(name.as_str()))
+        {
+            let path = directory.join(stale);
+            match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(LapexError::io(path, e))
+                }
+                _ => (),
+            }
+        }
+
+        let temporary_manifest = Self::temporary_path(directory, &self.manifest_name);
+        File::create(&temporary_manifest)
+            .and_then(|mut file| file.write_all(manifest.to_json().as_bytes()))
+            .and_then(|_| std::fs::rename(&temporary_manifest, &manifest_path))
+            .map_err(|e| LapexError::io(manifest_path, e))?;
+        match std::fs::remove_file(&legacy_manifest_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(LapexError::io(legacy_manifest_path, e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn keep_user_code(directory: &Path, name: &str) -> Result<(), Vec<LapexError>> {
+        let path = directory.join(name);
+        let previous = match std::fs::read_to_string(&path) {
+            Ok(previous) => previous,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(LapexError::io(path, e)),
+        };
+        let temporary_path = Self::temporary_path(directory, name);
+        let generated = std::fs::read_to_string(&temporary_path)
+            .map_err(|e| LapexError::io(temporary_path.clone(), e))?;
+        let restored =
+            restore_user_code(&previous, &generated).map_err(|e| LapexError::user_code(path, e))?;
+        if restored != generated {
+            std::fs::write(&temporary_path, restored)
+                .map_err(|e| LapexError::io(temporary_path, e))?;
+        }
+        Ok(())
+    }
+
+    // every file starts with a "==> name <==" line, like the output of head with several files
+    pub(crate) fn commit_stream<W: Write>(
+        streamed: &BTreeMap<String, Vec<u8>>,
+        mut output: W,
+    ) -> std::io::Result<()> {
+        for (name, contents) in streamed {
+            writeln!(output, "==> {} <==", name)?;
+            output.write_all(contents)?;
+            if !contents.is_empty() && !contents.ends_with(b"\n") {
+                writeln!(output)?;
+            }
+        }
+        output.flush()
+    }
+}
+
+impl<'path> Drop for GeneratedOutput<'path> {
+    fn drop(&mut self) {
+        if let (Some(directory), false) = (self.directory, self.committed) {
+            for name in self.written.borrow().iter() {
+                let _ = std::fs::remove_file(Self::temporary_path(directory, name));
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use lapex_input::LapexInputParser;
+use lapex_parser::{
+    grammar::{Grammar, Symbol},
+    lr_parser::{ParseStep, ParseTrace},
+};
+
+use crate::{
+    build_lr1_table, build_token_dfa,
+    errors::{is_alternation_link, rule_source_text, symbol_source_name},
+    parser_token_symbols, read_input, InputEncoding, LapexError,
+};
+
+/// Parses each line of `input` with an LR(1) parser of the grammar and writes the tokens, the
+/// rules reduced and where parsing failed to `output`, until the input ends.
+pub fn run_repl<I, R, W>(
+    grammar_path: &Path,
+    merge_duplicate_productions: bool,
+    encoding: InputEncoding,
+    input_parser: I,
+    input: R,
+    mut output: W,
+) -> Result<(), Vec<LapexError>>
+where
+    I: LapexInputParser,
+    R: BufRead,
+    W: Write,
+{
+    let file_contents = read_input(grammar_path, encoding)?;
+    let rules = input_parser
+        .parse_lapex(file_contents.as_str())
+        .map_err(|e| LapexError::syntax(grammar_path, &file_contents, e))?;
+    let grammar = Grammar::from_rule_set(&rules, merge_duplicate_productions)
+        .map_err(|e| LapexError::grammar(grammar_path, file_contents.as_str(), e))?;
+    let (alphabet, dfa) = build_token_dfa(grammar_path, file_contents.as_str(), &rules)?;
+    let table = build_lr1_table(grammar_path, file_contents.as_str(), &grammar)?;
+    let token_symbols = parser_token_symbols(&grammar);
+
+    read_lines(input, &mut output, |output, line| {
+        let tokens = match lapex_lexer::scan_token_spans(&dfa, &alphabet, line) {
+            Ok(tokens) => tokens,
+            Err(offset) => {
+                let column = line[..offset].chars().count() + 1;
+                return writeln!(
+                    output,
+                    "error at column {}: no token matches the input",
+                    column
+                );
+            }
+        };
+        // the texts of the tokens the parser sees, skipped tokens are left out
+        let (symbols, texts): (Vec<Symbol>, Vec<&str>) = tokens
+            .iter()
+            .filter_map(|(rule, range)| {
+                let symbol = token_symbols.get(&*rule.name)?;
+                Some((*symbol, &line[range.clone()]))
+            })
+            .unzip();
+        let trace = ParseTrace::record(&grammar, &table, &symbols);
+        write_trace(output, &grammar, &trace, &symbols, &texts)
+    })
+    .map_err(|e| LapexError::io(PathBuf::from("<terminal>"), e))
+}
+
+// prompts for each line and ends the output with a newline once the input ends
+fn read_lines<R: BufRead, W: Write>(
+    input: R,
+    output: &mut W,
+    mut handle_line: impl FnMut(&mut W, &str) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        match lines.next() {
+            Some(line) => handle_line(output, &line?)?,
+            None => return writeln!(output),
+        }
+    }
+}
+
+fn write_trace<W: Write>(
+    output: &mut W,
+    grammar: &Grammar,
+    trace: &ParseTrace,
+    symbols: &[Symbol],
+    texts: &[&str],
+) -> std::io::Result<()> {
+    let tokens: Vec<String> = symbols
+        .iter()
+        .zip(texts)
+        .map(|(symbol, text)| format!(" {} {:?}", symbol_source_name(symbol, grammar), text))
+        .collect();
+    writeln!(output, "tokens:{}", tokens.join(","))?;
+    for step in &trace.steps {
+        if let ParseStep::Reduce(rule) = step {
+            if !is_alternation_link(rule, grammar) {
+                writeln!(output, "reduce {}", rule_source_text(rule, grammar))?;
+            }
+        }
+    }
+    match &trace.failure {
+        None => writeln!(output, "accepted"),
+        Some(failure) => {
+            let expected: Vec<String> = failure
+                .expected
+                .iter()
+                .map(|symbol| match symbol {
+                    Symbol::End => String::from("end of input"),
+                    _ => symbol_source_name(symbol, grammar),
+                })
+                .collect();
+            let position = match texts.get(failure.position) {
+                Some(text) => format!("{:?} (token {})", text, failure.position + 1),
+                None => String::from("end of input"),
+            };
+            writeln!(
+                output,
+                "error at {}: expected {}",
+                position,
+                expected.join(", ")
+            )
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::LapexError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Input,
+    Nfa,
+    Dfa,
+    Table,
+    Codegen,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.pad(match self {
+            Phase::Input => "input",
+            Phase::Nfa => "nfa",
+            Phase::Dfa => "dfa",
+            Phase::Table => "table",
+            Phase::Codegen => "codegen",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Counter {
+    NfaStates,
+    DfaStates,
+    ParserStates,
+    ParserTransitions,
+    ItemsAllocated,
+    LargestItemSet,
+    /// States of the table no shift leads to, which are left out of the generated code.
+    PrunedStates,
+}
+
+impl Display for Counter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.pad(match self {
+            Counter::NfaStates => "nfa_states",
+            Counter::DfaStates => "dfa_states",
+            Counter::ParserStates => "parser_states",
+            Counter::ParserTransitions => "parser_transitions",
+            Counter::ItemsAllocated => "items_allocated",
+            Counter::LargestItemSet => "largest_item_set",
+            Counter::PrunedStates => "pruned_states",
+        })
+    }
+}
+
+/// The time spent in each phase of a generator run, ordered like the phases of the pipeline, the
+/// sizes of the automata built along the way and the warnings about the grammar.
+#[derive(Debug, Default)]
+pub struct GenerationReport {
+    phases: Vec<(Phase, Duration)>,
+    counters: Vec<(Counter, usize)>,
+    warnings: Vec<LapexError>,
+}
+
+impl GenerationReport {
+    /// Adds the duration to the phase, phases can be entered multiple times.
+    pub(crate) fn record(&mut self, phase: Phase, duration: Duration) {
+        #[cfg(feature = "tracing")]
+        tracing::info!(%phase, ?duration, "finished a phase");
+        match self.phases.binary_search_by_key(&phase, |(p, _)| *p) {
+            Ok(index) => self.phases[index].1 += duration,
+            Err(index) => self.phases.insert(index, (phase, duration)),
+        }
+    }
+
+    pub(crate) fn count(&mut self, counter: Counter, value: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(%counter, value);
+        match self.counters.binary_search_by_key(&counter, |(c, _)| *c) {
+            Ok(index) => self.counters[index].1 = value,
+            Err(index) => self.counters.insert(index, (counter, value)),
+        }
+    }
+
+    pub(crate) fn warn(&mut self, warnings: Vec<LapexError>) {
+        self.warnings.extend(warnings);
+    }
+
+    pub fn take_warnings(&mut self) -> Vec<LapexError> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    pub fn phases(&self) -> &[(Phase, Duration)] {
+        &self.phases
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Only contains the counters of the phases which were run.
+    pub fn counters(&self) -> &[(Counter, usize)] {
+        &self.counters
+    }
+}
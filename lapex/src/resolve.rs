@@ -0,0 +1,580 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    io::{BufRead, Lines, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use lapex_input::{LapexInputParser, ProductionPattern, ProductionRule, Spanned};
+use lapex_parser::{
+    grammar::{Grammar, Rule, Symbol},
+    ll_parser::{suggest_rewrites, LLParserError, Rewrite},
+    lr_parser::{ActionGotoTable, Conflict, GenerationResult, TableEntry},
+};
+
+use crate::{
+    errors::{left_factored_pattern, rule_source_text, symbol_source_name, symbols_source_text},
+    read_input, InputEncoding, LapexError, ParsingAlgorithm,
+};
+
+#[derive(Debug, Clone)]
+pub struct ResolveOptions {
+    pub algorithm: ParsingAlgorithm,
+    pub merge_duplicate_productions: bool,
+    pub encoding: InputEncoding,
+    pub tab_width: usize,
+    pub color: bool,
+}
+
+// a change of the grammar file which removes the cause of a conflict
+struct Fix {
+    description: String,
+    // byte ranges of the grammar file and their replacements, which do not overlap
+    edits: Vec<(Range<usize>, String)>,
+}
+
+struct ConflictReport {
+    // the states are numbered anew after every edit, so conflicts are told apart by their rules
+    key: String,
+    errors: Vec<LapexError>,
+    // the symbols which lead from the start of the input into the conflicting state
+    reached_after: Option<String>,
+    fixes: Vec<Fix>,
+}
+
+enum Choice {
+    Apply(Fix),
+    Skip,
+    Quit,
+}
+
+/// Shows the conflicts of the parser table of the grammar one by one and offers the changes of
+/// the grammar which resolve them. A change is written to the grammar file once it is
+/// confirmed, then the conflicts are searched again. Returns the number of conflicts left.
+pub fn run_resolve<I, R, W>(
+    grammar_path: &Path,
+    options: &ResolveOptions,
+    input_parser: I,
+    input: R,
+    mut output: W,
+) -> Result<usize, Vec<LapexError>>
+where
+    I: LapexInputParser,
+    R: BufRead,
+    W: Write,
+{
+    let terminal_error = |e| LapexError::io(PathBuf::from("<terminal>"), e);
+    if options.algorithm == ParsingAlgorithm::GLR {
+        writeln!(output, "a GLR parser accepts every conflict").map_err(terminal_error)?;
+        return Ok(0);
+    }
+    let mut lines = input.lines();
+    let mut skipped = BTreeSet::new();
+    loop {
+        let file_contents = read_input(grammar_path, options.encoding)?;
+        let rules = input_parser
+            .parse_lapex(file_contents.as_str())
+            .map_err(|e| LapexError::syntax(grammar_path, &file_contents, e))?;
+        let grammar = Grammar::from_rule_set(&rules, options.merge_duplicate_productions)
+            .map_err(|e| LapexError::grammar(grammar_path, file_contents.as_str(), e))?;
+        let reports = find_conflicts(grammar_path, &file_contents, &grammar, &options.algorithm)?;
+        let conflict_count = reports.len();
+        let next = reports
+            .into_iter()
+            .find(|report| !skipped.contains(&report.key));
+        let mut report = match next {
+            Some(report) => report,
+            None => {
+                write_summary(&mut output, conflict_count, &options.algorithm)
+                    .map_err(terminal_error)?;
+                return Ok(conflict_count);
+            }
+        };
+        let key = report.key.clone();
+        // only the first LL conflict is found, the fix resolves it if another one is found
+        let is_ll = matches!(
+            options.algorithm,
+            ParsingAlgorithm::LL1 | ParsingAlgorithm::LL2
+        );
+        report.fixes.retain(|fix| {
+            let edited = apply_edits(&file_contents, &fix.edits);
+            match conflicts_after_edit(grammar_path, &edited, options, &input_parser) {
+                Some(keys) if is_ll => !keys.contains(&key),
+                Some(keys) => keys.len() < conflict_count,
+                None => false,
+            }
+        });
+        match choose_fix(&mut lines, &mut output, report, options).map_err(terminal_error)? {
+            Choice::Apply(fix) => {
+                std::fs::write(grammar_path, apply_edits(&file_contents, &fix.edits))
+                    .map_err(|e| LapexError::io(grammar_path.to_path_buf(), e))?;
+            }
+            Choice::Skip => {
+                skipped.insert(key);
+            }
+            Choice::Quit => return Ok(conflict_count),
+        }
+    }
+}
+
+fn apply_edits(file_contents: &str, edits: &[(Range<usize>, String)]) -> String {
+    let mut contents = file_contents.to_string();
+    for (range, text) in edits.iter().rev() {
+        contents.replace_range(range.clone(), text);
+    }
+    contents
+}
+
+// a fix is only offered if it resolves the conflict, which left factoring does not do for
+// every LR conflict
+fn conflicts_after_edit<I: LapexInputParser>(
+    grammar_path: &Path,
+    file_contents: &str,
+    options: &ResolveOptions,
+    input_parser: &I,
+) -> Option<Vec<String>> {
+    let rules = input_parser.parse_lapex(file_contents).ok()?;
+    let grammar = Grammar::from_rule_set(&rules, options.merge_duplicate_productions).ok()?;
+    let reports = find_conflicts(grammar_path, file_contents, &grammar, &options.algorithm).ok()?;
+    Some(reports.into_iter().map(|report| report.key).collect())
+}
+
+fn find_conflicts(
+    grammar_path: &Path,
+    file_contents: &str,
+    grammar: &Grammar,
+    algorithm: &ParsingAlgorithm,
+) -> Result<Vec<ConflictReport>, Vec<LapexError>> {
+    use lapex_parser::lr_parser::generate_table;
+
+    let reports = match algorithm {
+        ParsingAlgorithm::LR0 => lr_conflicts(
+            grammar_path,
+            file_contents,
+            grammar,
+            generate_table::<0>(grammar, true, false),
+        ),
+        ParsingAlgorithm::LR1 => lr_conflicts(
+            grammar_path,
+            file_contents,
+            grammar,
+            generate_table::<1>(grammar, true, false),
+        ),
+        ParsingAlgorithm::LALR1 => lr_conflicts(
+            grammar_path,
+            file_contents,
+            grammar,
+            generate_table::<1>(grammar, true, true),
+        ),
+        ParsingAlgorithm::LL1 | ParsingAlgorithm::LL2 | ParsingAlgorithm::GLR => {
+            ll_conflicts(grammar_path, file_contents, grammar, algorithm)?
+        }
+    };
+    Ok(reports)
+}
+
+fn lr_conflicts<'grammar, 'rules, const N: usize>(
+    grammar_path: &Path,
+    file_contents: &str,
+    grammar: &'grammar Grammar<'rules>,
+    result: GenerationResult<'grammar, 'rules, N>,
+) -> Vec<ConflictReport> {
+    let (table, conflicts) = match result {
+        GenerationResult::NoConflicts(_) => return Vec::new(),
+        GenerationResult::AllowedConflicts { table, conflicts } => (table, conflicts),
+        GenerationResult::BadConflicts(_) => unreachable!("the conflicts are allowed"),
+    };
+    // conflicts allowed with `@allow(...)` are accepted already
+    conflicts
+        .iter()
+        .filter(|conflict| !conflict.is_allowed())
+        .map(|conflict| {
+            let (kind, rules): (String, Vec<&Rule>) = match conflict {
+                Conflict::ShiftReduce {
+                    item_to_reduce,
+                    shift_symbol,
+                    item_set,
+                    ..
+                } => {
+                    let shifting_rules = item_set
+                        .iter()
+                        .filter(|item| item.item.symbol_after_dot() == Some(*shift_symbol))
+                        .map(|item| item.item.rule());
+                    (
+                        format!("shift {}", symbol_source_name(shift_symbol, grammar)),
+                        std::iter::once(item_to_reduce.rule())
+                            .chain(shifting_rules)
+                            .collect(),
+                    )
+                }
+                Conflict::ReduceReduce { items, .. } => (
+                    String::from("reduce"),
+                    items.iter().map(|item| item.rule()).collect(),
+                ),
+            };
+            let rules: Vec<&Rule> = rules
+                .into_iter()
+                .filter(|rule| rule.lhs().is_some())
+                .collect();
+            let path = symbols_leading_to(&table, grammar, conflict.state());
+            ConflictReport {
+                key: conflict_key(&kind, &rules, grammar),
+                errors: LapexError::conflicts(
+                    grammar_path,
+                    file_contents,
+                    std::slice::from_ref(conflict),
+                    grammar,
+                ),
+                reached_after: Some(if path.is_empty() {
+                    String::from("the start of the input")
+                } else {
+                    symbols_source_text(&path, grammar)
+                }),
+                fixes: left_factor_fixes(file_contents, grammar, &rules),
+            }
+        })
+        .collect()
+}
+
+// the table generation stops at the first conflict, so there is at most one
+fn ll_conflicts(
+    grammar_path: &Path,
+    file_contents: &str,
+    grammar: &Grammar,
+    algorithm: &ParsingAlgorithm,
+) -> Result<Vec<ConflictReport>, Vec<LapexError>> {
+    let result = if *algorithm == ParsingAlgorithm::LL2 {
+        lapex_parser::ll_parser::generate_table_k(grammar, 2)
+    } else {
+        lapex_parser::ll_parser::generate_table(grammar)
+    };
+    let error = match result {
+        Ok(_) => return Ok(Vec::new()),
+        Err(
+            error @ (LLParserError::ParserTableConflict { .. }
+            | LLParserError::LeftRecursion { .. }),
+        ) => error,
+        Err(error) => {
+            return Err(LapexError::ll_table(
+                grammar_path,
+                file_contents,
+                grammar,
+                error,
+            ))
+        }
+    };
+    let fixes = suggest_rewrites(grammar, &error)
+        .iter()
+        .filter_map(|rewrite| match rewrite {
+            Rewrite::LeftFactor { rules, prefix } => {
+                left_factor_fix(file_contents, grammar, rules, prefix)
+            }
+            Rewrite::LeftRecursion { .. } | Rewrite::EmptyAlternative { .. } => None,
+        })
+        .collect();
+    let key = match &error {
+        LLParserError::ParserTableConflict {
+            non_terminal,
+            lookahead,
+            ..
+        } => format!(
+            "{} on {}",
+            symbol_source_name(non_terminal, grammar),
+            symbols_source_text(lookahead, grammar)
+        ),
+        LLParserError::LeftRecursion { path, .. } => {
+            let names: Vec<&str> = path.iter().map(|name| name.inner.as_str()).collect();
+            format!("left recursion of {}", names.join(" -> "))
+        }
+        _ => unreachable!(),
+    };
+    Ok(vec![ConflictReport {
+        key,
+        errors: LapexError::ll_table(grammar_path, file_contents, grammar, error),
+        reached_after: None,
+        fixes,
+    }])
+}
+
+fn conflict_key(kind: &str, rules: &[&Rule], grammar: &Grammar) -> String {
+    let rules: BTreeSet<String> = rules
+        .iter()
+        .map(|rule| rule_source_text(rule, grammar))
+        .collect();
+    format!(
+        "{}: {}",
+        kind,
+        rules.into_iter().collect::<Vec<String>>().join("; ")
+    )
+}
+
+// the shortest sequence of symbols which the parser shifts from the entry state to the state
+fn symbols_leading_to(table: &ActionGotoTable, grammar: &Grammar, state: usize) -> Vec<Symbol> {
+    let mut reached_by: Vec<Option<(usize, Symbol)>> = vec![None; table.states()];
+    let mut queue = VecDeque::from([table.entry_state()]);
+    while let Some(current) = queue.pop_front() {
+        if current == state {
+            break;
+        }
+        let transitions = table
+            .iter_state_terminals(current, grammar)
+            .chain(table.iter_state_non_terminals(current, grammar));
+        for (symbol, entries) in transitions {
+            for entry in entries.into_iter().flatten() {
+                if let TableEntry::Shift { target } = entry {
+                    if *target != table.entry_state() && reached_by[*target].is_none() {
+                        reached_by[*target] = Some((current, symbol));
+                        queue.push_back(*target);
+                    }
+                }
+            }
+        }
+    }
+    let mut path = Vec::new();
+    let mut current = state;
+    while let Some((previous, symbol)) = reached_by[current] {
+        path.push(symbol);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+fn left_factor_fixes(file_contents: &str, grammar: &Grammar, rules: &[&Rule]) -> Vec<Fix> {
+    let mut fixes: Vec<Fix> = Vec::new();
+    for (i, first) in rules.iter().enumerate() {
+        for second in &rules[i + 1..] {
+            if first.lhs() != second.lhs() || first.rhs() == second.rhs() {
+                continue;
+            }
+            let prefix: Vec<Symbol> = first
+                .rhs()
+                .iter()
+                .zip(second.rhs())
+                .take_while(|(a, b)| a == b && **a != Symbol::Epsilon)
+                .map(|(symbol, _)| *symbol)
+                .collect();
+            if prefix.is_empty() {
+                continue;
+            }
+            if let Some(fix) = left_factor_fix(file_contents, grammar, &[first, second], &prefix) {
+                if fixes.iter().all(|other| other.edits != fix.edits) {
+                    fixes.push(fix);
+                }
+            }
+        }
+    }
+    fixes
+}
+
+// the names of a production which is only a sequence of symbols, nothing for other patterns
+fn plain_names<'src>(pattern: &ProductionPattern<'src>) -> Option<Vec<&'src str>> {
+    match pattern {
+        ProductionPattern::Rule { rule_name, .. } => Some(vec![rule_name]),
+        ProductionPattern::Sequence { elements } => elements
+            .iter()
+            .map(|element| match element {
+                ProductionPattern::Rule { rule_name, .. } => Some(*rule_name),
+                _ => None,
+            })
+            .collect(),
+        ProductionPattern::Epsilon => Some(Vec::new()),
+        _ => None,
+    }
+}
+
+// the production written with another pattern, keeping its name and tag
+fn replace_pattern(
+    file_contents: &str,
+    production: &Spanned<ProductionRule>,
+    pattern: &str,
+) -> Option<(Range<usize>, String)> {
+    let range = production.span.byte_range(file_contents)?;
+    let head = &file_contents[range.start..range.start + file_contents[range.clone()].find('=')?];
+    Some((range, format!("{}= {};", head, pattern)))
+}
+
+// The rules are either two productions of the same name, or two alternatives of the top-level
+// alternation of one production. Other patterns would have to be rewritten as a whole, so they
+// are left to the author.
+fn left_factor_fix(
+    file_contents: &str,
+    grammar: &Grammar,
+    rules: &[&Rule; 2],
+    prefix: &[Symbol],
+) -> Option<Fix> {
+    let rhs_names = |rule: &Rule| -> Vec<String> {
+        rule.rhs()
+            .iter()
+            .filter(|symbol| **symbol != Symbol::Epsilon)
+            .map(|symbol| symbol_source_name(symbol, grammar))
+            .collect()
+    };
+    let lhs = rules[0].lhs()?;
+    // the factored pattern keeps the order of the alternatives in the file
+    let (edits, factored) = if !std::ptr::eq(rules[0].rule(), rules[1].rule()) {
+        let [first, second] = if rules[0].rule().span <= rules[1].rule().span {
+            [rules[0], rules[1]]
+        } else {
+            [rules[1], rules[0]]
+        };
+        let (first_production, second_production) = (first.rule(), second.rule());
+        grammar.get_production_name(&lhs)?;
+        if first_production.inner.tag != second_production.inner.tag {
+            return None;
+        }
+        for rule in [first, second] {
+            plain_names(&rule.rule().inner.pattern).filter(|names| *names == rhs_names(rule))?;
+        }
+        let factored = left_factored_pattern(&[first, second], prefix, grammar);
+        let mut removed = second_production.span.byte_range(file_contents)?;
+        if file_contents[removed.end..].starts_with('\n') {
+            removed.end += 1;
+        }
+        let edits = vec![
+            replace_pattern(file_contents, first_production, &factored)?,
+            (removed, String::new()),
+        ];
+        (edits, factored)
+    } else {
+        let production = rules[0].rule();
+        let elements = match &production.inner.pattern {
+            ProductionPattern::Alternative { elements } => elements,
+            _ => return None,
+        };
+        let alternatives: Vec<Vec<&str>> =
+            elements.iter().map(plain_names).collect::<Option<_>>()?;
+        if alternatives.iter().any(|names| names.is_empty()) {
+            return None;
+        }
+        let position = |rule: &Rule| {
+            let names = rhs_names(rule);
+            alternatives
+                .iter()
+                .position(|alternative| *alternative == names)
+        };
+        let (mut first_position, mut second_position) = (position(rules[0])?, position(rules[1])?);
+        let mut ordered = *rules;
+        if first_position > second_position {
+            std::mem::swap(&mut first_position, &mut second_position);
+            ordered.swap(0, 1);
+        }
+        let factored = left_factored_pattern(&ordered, prefix, grammar);
+        let pattern: Vec<String> = alternatives
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != second_position)
+            .map(|(i, names)| {
+                if i == first_position {
+                    factored.clone()
+                } else {
+                    names.join(" ")
+                }
+            })
+            .collect();
+        let edits = vec![replace_pattern(
+            file_contents,
+            production,
+            &pattern.join(" | "),
+        )?];
+        (edits, factored)
+    };
+    let name = symbol_source_name(&lhs, grammar);
+    Some(Fix {
+        description: format!(
+            "factor out the common start {} of {}: {} = {};",
+            symbols_source_text(prefix, grammar),
+            name,
+            name,
+            factored
+        ),
+        edits,
+    })
+}
+
+fn prompt<R: BufRead, W: Write>(
+    lines: &mut Lines<R>,
+    output: &mut W,
+    question: &str,
+) -> std::io::Result<Option<String>> {
+    write!(output, "{} > ", question)?;
+    output.flush()?;
+    match lines.next() {
+        Some(line) => Ok(Some(line?.trim().to_string())),
+        None => {
+            writeln!(output)?;
+            Ok(None)
+        }
+    }
+}
+
+fn choose_fix<R: BufRead, W: Write>(
+    lines: &mut Lines<R>,
+    output: &mut W,
+    report: ConflictReport,
+    options: &ResolveOptions,
+) -> std::io::Result<Choice> {
+    for mut error in report.errors {
+        error.set_tab_width(options.tab_width);
+        error.set_color(options.color);
+        writeln!(output, "{}\n", error)?;
+    }
+    if let Some(symbols) = &report.reached_after {
+        writeln!(output, "reached after: {}", symbols)?;
+    }
+    if report.fixes.is_empty() {
+        writeln!(
+            output,
+            "no change of the grammar is known to resolve this conflict"
+        )?;
+    }
+    for (i, fix) in report.fixes.iter().enumerate() {
+        writeln!(output, "{}) {}", i + 1, fix.description)?;
+    }
+    let mut fixes = report.fixes;
+    loop {
+        let answer = match prompt(lines, output, "fix number, s to skip or q to quit")? {
+            Some(answer) => answer,
+            None => return Ok(Choice::Quit),
+        };
+        let index = match answer.as_str() {
+            "s" | "" => return Ok(Choice::Skip),
+            "q" => return Ok(Choice::Quit),
+            number => match number.parse::<usize>() {
+                Ok(number) if (1..=fixes.len()).contains(&number) => number - 1,
+                _ => continue,
+            },
+        };
+        for (_, text) in fixes[index]
+            .edits
+            .iter()
+            .filter(|(_, text)| !text.is_empty())
+        {
+            writeln!(output, "\t{}", text)?;
+        }
+        match prompt(lines, output, "write this to the grammar? [y/n]")?.as_deref() {
+            Some("y") => return Ok(Choice::Apply(fixes.swap_remove(index))),
+            Some(_) => continue,
+            None => return Ok(Choice::Quit),
+        }
+    }
+}
+
+fn write_summary<W: Write>(
+    output: &mut W,
+    conflict_count: usize,
+    algorithm: &ParsingAlgorithm,
+) -> std::io::Result<()> {
+    match conflict_count {
+        0 => writeln!(output, "the grammar has no {} conflicts", algorithm),
+        _ => {
+            writeln!(output, "conflicts left: {}", conflict_count)?;
+            match algorithm {
+                ParsingAlgorithm::LR0 | ParsingAlgorithm::LR1 | ParsingAlgorithm::LALR1 => {
+                    writeln!(output, "a GLR parser (--algorithm glr) accepts them")
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use lapex_automaton::AutomatonState;
+use lapex_codegen::GeneratedCodeWriter;
+use lapex_input::{Spanned, TokenRule};
+use lapex_lexer::{Alphabet, TokenDfa};
+use lapex_parser::{
+    grammar::{Grammar, Rule, Symbol},
+    lr_parser::{ActionGotoTable, TableEntry},
+};
+
+use crate::{manifest::Json, LapexError, ParsingAlgorithm};
+
+const FORMAT_VERSION: u64 = 1;
+
+/// Writes the tables of the lexer and the LR parser as JSON into `tables.json`, or
+/// `<prefix>_tables.json`, for drivers of their own. The format is described in the README and
+/// changes only together with `FORMAT_VERSION`.
+pub(crate) fn write_tables<'grammar: 'rules, 'rules>(
+    gen: &mut GeneratedCodeWriter,
+    symbol_prefix: Option<&str>,
+    algorithm: &ParsingAlgorithm,
+    token_rules: &[Spanned<TokenRule>],
+    lexer: Option<&(Alphabet, TokenDfa)>,
+    grammar: &'grammar Grammar<'rules>,
+    table: &ActionGotoTable<'grammar, 'rules>,
+) -> Result<(), Vec<LapexError>> {
+    let file_name = match symbol_prefix {
+        Some(prefix) => format!("{}_tables.json", prefix),
+        None => String::from("tables.json"),
+    };
+    let json = tables_to_json(algorithm, token_rules, lexer, grammar, table);
+    gen.generate_data(&file_name, |output| output.write_all(json.as_bytes()))
+        .map_err(|e| LapexError::io(PathBuf::from(&file_name), e))
+}
+
+fn tables_to_json<'grammar: 'rules, 'rules>(
+    algorithm: &ParsingAlgorithm,
+    token_rules: &[Spanned<TokenRule>],
+    lexer: Option<&(Alphabet, TokenDfa)>,
+    grammar: &'grammar Grammar<'rules>,
+    table: &ActionGotoTable<'grammar, 'rules>,
+) -> String {
+    let object = |entries: Vec<(&str, Json)>| {
+        Json::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    };
+    let tokens: Vec<Json> = token_rules
+        .iter()
+        .map(|rule| rule.inner.name.to_string().into())
+        .collect();
+    let mut entries = vec![
+        ("format_version", Json::Number(FORMAT_VERSION)),
+        ("lapex_version", env!("CARGO_PKG_VERSION").into()),
+        ("algorithm", algorithm.to_string().into()),
+        ("tokens", Json::Array(tokens)),
+    ];
+    if let Some((alphabet, dfa)) = lexer {
+        entries.push(("lexer", lexer_to_json(token_rules, alphabet, dfa)));
+    }
+    entries.push(("parser", parser_to_json(grammar, table)));
+    format!("{}\n", object(entries))
+}
+
+fn lexer_to_json(token_rules: &[Spanned<TokenRule>], alphabet: &Alphabet, dfa: &TokenDfa) -> Json {
+    let pair = |a: u64, b: u64| Json::Array(vec![Json::Number(a), Json::Number(b)]);
+    let classes = alphabet
+        .get_ranges()
+        .iter()
+        .map(|range| pair(*range.start() as u64, *range.end() as u64))
+        .collect();
+    let states = dfa
+        .states()
+        .map(|(index, state)| {
+            let token = match state {
+                AutomatonState::Accepting(rule) => rule.name.to_string().into(),
+                AutomatonState::Intermediate(_) => Json::Null,
+            };
+            // class 0 only holds the end of input marker and is never consumed
+            let mut transitions: Vec<(u64, u64)> = dfa
+                .transitions_from(index)
+                .filter(|(class, _)| **class != 0)
+                .map(|(class, target)| (*class as u64, target.index() as u64))
+                .collect();
+            transitions.sort();
+            let transitions = transitions
+                .into_iter()
+                .map(|(class, target)| pair(class, target))
+                .collect();
+            Json::Object(vec![
+                (String::from("token"), token),
+                (String::from("transitions"), Json::Array(transitions)),
+            ])
+        })
+        .collect();
+    let skipped_tokens = token_rules
+        .iter()
+        .filter(|rule| rule.inner.skip)
+        .map(|rule| rule.inner.name.to_string().into())
+        .collect();
+    Json::Object(vec![
+        (String::from("classes"), Json::Array(classes)),
+        (String::from("start_state"), Json::Number(0)),
+        (String::from("states"), Json::Array(states)),
+        (String::from("skipped_tokens"), Json::Array(skipped_tokens)),
+    ])
+}
+
+fn parser_to_json<'grammar: 'rules, 'rules>(
+    grammar: &'grammar Grammar<'rules>,
+    table: &ActionGotoTable<'grammar, 'rules>,
+) -> Json {
+    let rule_indices: BTreeMap<*const Rule, u64> = grammar
+        .rules()
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| (rule as *const Rule, i as u64))
+        .collect();
+    let symbol_name = |symbol: &Symbol| match symbol {
+        Symbol::Terminal(index) => grammar.get_token_name(*index).to_string(),
+        Symbol::NonTerminal(_) => grammar
+            .get_non_terminal_name(symbol)
+            .expect("every non-terminal has a name")
+            .to_string(),
+        Symbol::End => String::from("<eof>"),
+        Symbol::Error => String::from("<error>"),
+        Symbol::Epsilon => unreachable!("the table has no entries for epsilon"),
+    };
+    let non_terminals = grammar
+        .non_terminals()
+        .map(|symbol| symbol_name(&symbol).into())
+        .collect();
+    let rules = grammar
+        .rules()
+        .iter()
+        .map(|rule| {
+            let symbols = rule
+                .rhs()
+                .iter()
+                .filter(|symbol| **symbol != Symbol::Epsilon)
+                .map(|symbol| symbol_name(symbol).into())
+                .collect();
+            Json::Object(vec![
+                (
+                    String::from("non_terminal"),
+                    symbol_name(&rule.lhs().unwrap()).into(),
+                ),
+                (String::from("symbols"), Json::Array(symbols)),
+            ])
+        })
+        .collect();
+    let states = (0..table.states())
+        .map(|state| {
+            let mut actions = Vec::new();
+            for (symbol, entries) in table.iter_state_terminals(state, grammar).chain(
+                grammar
+                    .has_error_productions()
+                    .then(|| (Symbol::Error, table.get_entry(state, Symbol::Error))),
+            ) {
+                // error entries only mark the absence of an action, and the reductions of the
+                // entry rule are replaced by accepting on its goto
+                let entries: Vec<Json> = entries
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|entry| match entry {
+                        TableEntry::Shift { target } => Some(("shift", *target as u64)),
+                        TableEntry::Reduce { rule } => rule_indices
+                            .get(&(*rule as *const Rule))
+                            .map(|index| ("reduce", *index)),
+                        TableEntry::Error | TableEntry::Accept => None,
+                    })
+                    .map(|(action, value)| {
+                        Json::Object(vec![(action.to_string(), Json::Number(value))])
+                    })
+                    .collect();
+                if !entries.is_empty() {
+                    actions.push((symbol_name(&symbol), Json::Array(entries)));
+                }
+            }
+            let mut gotos = Vec::new();
+            for (symbol, entries) in table.iter_state_non_terminals(state, grammar) {
+                for entry in entries.into_iter().flatten() {
+                    if let TableEntry::Shift { target } = entry {
+                        gotos.push((symbol_name(&symbol), Json::Number(*target as u64)));
+                    }
+                }
+            }
+            Json::Object(vec![
+                (String::from("actions"), Json::Object(actions)),
+                (String::from("gotos"), Json::Object(gotos)),
+            ])
+        })
+        .collect();
+    let mut parser = vec![
+        (String::from("non_terminals"), Json::Array(non_terminals)),
+        (
+            String::from("entry_non_terminal"),
+            symbol_name(grammar.entry_point()).into(),
+        ),
+        (
+            String::from("entry_state"),
+            Json::Number(table.entry_state() as u64),
+        ),
+        (String::from("rules"), Json::Array(rules)),
+        (String::from("states"), Json::Array(states)),
+    ];
+    // only grammars recovering with sync tokens have them, the tables of others stay the same
+    if !grammar.sync_tokens().is_empty() {
+        let sync_tokens = grammar
+            .sync_tokens()
+            .iter()
+            .map(|token| symbol_name(token).into())
+            .collect();
+        parser.push((String::from("sync_tokens"), Json::Array(sync_tokens)));
+    }
+    Json::Object(parser)
+}
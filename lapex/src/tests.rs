@@ -0,0 +1,288 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use lapex_input_gen::GeneratedLapexInputParser;
+
+use crate::{
+    generate, generate_in_memory, output::GeneratedOutput, run_resolve, verify_output, ErrorCode,
+    ErrorKind, GenerationOptions, InputEncoding, Language, Mismatch, ParsingAlgorithm,
+    ResolveOptions, STDOUT_TARGET,
+};
+
+// writes the file into a new directory in the temporary directory of the system
+fn write_temp_file(dir_name: &str, file_name: &str, contents: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lapex-{}-{}", dir_name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(file_name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn resolve_options(algorithm: ParsingAlgorithm) -> ResolveOptions {
+    ResolveOptions {
+        algorithm,
+        merge_duplicate_productions: true,
+        encoding: InputEncoding::Auto,
+        tab_width: 4,
+        color: false,
+    }
+}
+
+#[test]
+fn test_resolve_symbols() {
+    // a symbol which is not defined is not resolved
+    let path = write_temp_file(
+        "resolve-undefined",
+        "grammar.lapex",
+        "token NUM = /[0-9]+/;\nentry e;\nprod e = NUM PLUS NUM;\n",
+    );
+    let mut output = Vec::new();
+    let errors = run_resolve(
+        &path,
+        &resolve_options(ParsingAlgorithm::LR1),
+        GeneratedLapexInputParser {},
+        "".as_bytes(),
+        &mut output,
+    )
+    .unwrap_err();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind(), ErrorKind::Grammar);
+    assert!(format!("{}", errors[0]).contains("PLUS"));
+    assert!(output.is_empty());
+
+    // a symbol which is both a token and a production is ambiguous
+    let path = write_temp_file(
+        "resolve-ambiguous",
+        "grammar.lapex",
+        "token NUM = /[0-9]+/;\nentry e;\nprod e = NUM;\nprod NUM = e;\n",
+    );
+    let mut output = Vec::new();
+    let errors = run_resolve(
+        &path,
+        &resolve_options(ParsingAlgorithm::LR1),
+        GeneratedLapexInputParser {},
+        "".as_bytes(),
+        &mut output,
+    )
+    .unwrap_err();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind(), ErrorKind::Grammar);
+    assert_eq!(errors[0].code(), ErrorCode::from_name("E0013").unwrap());
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_resolve_skipped_conflict() {
+    let grammar =
+        "token NUM = /[0-9]+/;\ntoken PLUS = \"+\";\nentry e;\nprod e = e PLUS e | NUM;\n";
+    let path = write_temp_file("resolve-skipped", "grammar.lapex", grammar);
+    let mut output = Vec::new();
+    let conflicts_left = run_resolve(
+        &path,
+        &resolve_options(ParsingAlgorithm::LR1),
+        GeneratedLapexInputParser {},
+        "s\n".as_bytes(),
+        &mut output,
+    )
+    .unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(conflicts_left, 1);
+    assert!(output.contains("conflicts left: 1"), "{}", output);
+    // a skipped conflict leaves the grammar unchanged
+    assert_eq!(contents, grammar);
+}
+
+fn generation_options() -> GenerationOptions {
+    GenerationOptions {
+        encoding: InputEncoding::Utf8,
+        ..Default::default()
+    }
+}
+
+const SUM_GRAMMAR: &str =
+    "token NUM = /[0-9]+/;\ntoken PLUS = \"+\";\nentry expr;\nprod expr = sum;\nprod sum = sum PLUS NUM | NUM;\n";
+
+#[test]
+fn test_verify_output() {
+    let grammar_path = write_temp_file("verify", "sum.lapex", SUM_GRAMMAR);
+    let target = grammar_path.parent().unwrap().join("generated");
+    generate(
+        &generation_options(),
+        &grammar_path,
+        SUM_GRAMMAR,
+        &target,
+        Language::Rust,
+        GeneratedLapexInputParser {},
+    )
+    .unwrap();
+    let unchanged = verify_output(&target);
+
+    // a hash of the manifest which does not match its file
+    let manifest_path = target.join("lapex-manifest.json");
+    let manifest = std::fs::read_to_string(&manifest_path).unwrap();
+    let hash_start = manifest.find("\"tokens.rs\"").unwrap() + "\"tokens.rs\"".len();
+    let hash_start = hash_start
+        + manifest[hash_start..]
+            .find(|c: char| c.is_ascii_hexdigit())
+            .unwrap();
+    let mut tampered = manifest.clone();
+    tampered.replace_range(hash_start..hash_start + 64, &"0".repeat(64));
+    std::fs::write(&manifest_path, tampered).unwrap();
+    let tampered = verify_output(&target);
+    std::fs::write(&manifest_path, &manifest).unwrap();
+
+    std::fs::remove_file(target.join("lexer.rs")).unwrap();
+    let missing = verify_output(&target);
+
+    std::fs::write(&manifest_path, "{").unwrap();
+    let invalid = verify_output(&target);
+    let _ = std::fs::remove_dir_all(grammar_path.parent().unwrap());
+
+    assert_eq!(unchanged.unwrap(), Vec::new());
+    assert_eq!(
+        tampered.unwrap(),
+        vec![Mismatch::Modified(target.join("tokens.rs"))]
+    );
+    assert_eq!(
+        missing.unwrap(),
+        vec![Mismatch::Missing(target.join("lexer.rs"))]
+    );
+    assert_eq!(invalid.unwrap_err()[0].kind(), ErrorKind::Io);
+}
+
+#[test]
+fn test_tables_only() {
+    let generated_files = |tables_only| {
+        let (files, _) = generate_in_memory(
+            &GenerationOptions {
+                tables_only,
+                ..generation_options()
+            },
+            Path::new("sum.lapex"),
+            SUM_GRAMMAR,
+            Language::Rust,
+            GeneratedLapexInputParser {},
+        )
+        .unwrap();
+        files
+    };
+    let code = generated_files(false);
+    let tables = generated_files(true);
+    assert!(code.contains_key("lexer.rs") && code.contains_key("parser.rs"));
+    assert!(!code.contains_key("tables.json"));
+    assert_eq!(
+        tables.keys().map(String::as_str).collect::<Vec<_>>(),
+        vec!["tables.json", "tokens.rs"]
+    );
+    assert_eq!(tables["tokens.rs"], code["tokens.rs"]);
+    assert!(tables["tables.json"].contains("\"lexer\""));
+    assert!(tables["tables.json"].contains("\"parser\""));
+}
+
+#[test]
+fn test_missing_entry_rule() {
+    // a grammar of tokens only and one whose productions lack an entry both report it the same way
+    let errors: Vec<String> = [
+        "token NUM = /[0-9]+/;\n",
+        "token NUM = /[0-9]+/;\nprod e = NUM;\n",
+    ]
+    .into_iter()
+    .map(|source| {
+        let errors = generate_in_memory(
+            &generation_options(),
+            Path::new("grammar.lapex"),
+            source,
+            Language::Rust,
+            GeneratedLapexInputParser {},
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code(), ErrorCode::from_name("E0017").unwrap());
+        errors[0].to_string()
+    })
+    .collect();
+    assert_eq!(errors[0], errors[1]);
+    assert!(errors[0].contains("only a lexer can be generated from it"));
+}
+
+// the names and contents of the files in the directory
+fn directory_contents(directory: &Path) -> BTreeMap<String, String> {
+    std::fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn test_atomic_output() {
+    let grammar_path = write_temp_file("atomic", "sum.lapex", SUM_GRAMMAR);
+    let target = grammar_path.parent().unwrap().join("generated");
+    let generate_grammar = |grammar_source| {
+        generate(
+            &generation_options(),
+            &grammar_path,
+            grammar_source,
+            &target,
+            Language::Rust,
+            GeneratedLapexInputParser {},
+        )
+    };
+    generate_grammar(SUM_GRAMMAR).unwrap();
+    let generated = directory_contents(&target);
+
+    // an output which is not committed removes its temporary files
+    let output = GeneratedOutput::open(&target, false, None).unwrap();
+    let mut file = output.create("parser.rs").unwrap();
+    file.write_all(b"// half written").unwrap();
+    drop(file);
+    let written = directory_contents(&target);
+    drop(output);
+    let dropped = directory_contents(&target);
+
+    // the tokens and the lexer are written before the conflicts of the parser are found
+    let failed = generate_grammar(
+        "token NUM = /[0-9]+/;\ntoken PLUS = \"+\";\nentry expr;\nprod expr = sum;\nprod sum = sum PLUS sum | NUM;\n",
+    );
+    let after_failure = directory_contents(&target);
+    let _ = std::fs::remove_dir_all(grammar_path.parent().unwrap());
+
+    assert!(generated.contains_key("parser.rs"));
+    assert!(written.contains_key(".parser.rs.tmp"));
+    assert_eq!(written["parser.rs"], generated["parser.rs"]);
+    assert_eq!(dropped, generated);
+    assert_eq!(failed.unwrap_err()[0].kind(), ErrorKind::Conflict);
+    assert_eq!(after_failure, generated);
+}
+
+#[test]
+fn test_stdout_target() {
+    // the target `-` is not a directory, so it is neither created nor written to
+    let output = GeneratedOutput::open(Path::new(STDOUT_TARGET), true, None).unwrap();
+    write!(output.create("lexer.rs").unwrap(), "struct Lexer;").unwrap();
+    drop(output);
+    assert!(!Path::new(STDOUT_TARGET).exists());
+    assert!(!Path::new(".lexer.rs.tmp").exists());
+
+    let streamed = BTreeMap::from([
+        (String::from("empty.rs"), Vec::new()),
+        (String::from("lexer.rs"), b"struct Lexer;".to_vec()),
+        (String::from("tokens.rs"), b"enum TokenType {}\n".to_vec()),
+    ]);
+    let mut stdout = Vec::new();
+    GeneratedOutput::commit_stream(&streamed, &mut stdout).unwrap();
+    assert_eq!(
+        String::from_utf8(stdout).unwrap(),
+        "==> empty.rs <==\n==> lexer.rs <==\nstruct Lexer;\n==> tokens.rs <==\nenum TokenType {}\n"
+    );
+}